@@ -0,0 +1,176 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use cidr::IpCidr;
+use netvisor::server::{
+    groups::r#impl::{
+        base::{Group, GroupBase},
+        types::GroupType,
+    },
+    hosts::r#impl::{
+        base::{Host, HostBase},
+        interfaces::{Interface, InterfaceBase},
+        ports::{Port, PortBase},
+    },
+    services::{
+        definitions::ServiceDefinitionRegistry,
+        r#impl::{
+            base::{Service, ServiceBase},
+            bindings::Binding,
+            definitions::ServiceDefinition,
+        },
+    },
+    shared::{
+        storage::traits::StorableEntity,
+        types::{attributes::EntityAttributes, entities::EntitySource},
+    },
+    subnets::r#impl::{
+        base::{Subnet, SubnetBase},
+        types::{SubnetLayer2, SubnetType},
+    },
+    topology::types::api::TopologyRequestOptions,
+};
+use uuid::Uuid;
+
+/// Interfaces per subnet; every subnet ends up with roughly this many child nodes
+const HOSTS_PER_SUBNET: usize = 25;
+/// Every nth host also gets an interface in the next subnet, creating cross-subnet edges
+const MULTI_HOMED_EVERY: usize = 10;
+/// Every nth host starts a request path group through the following subnets
+const GROUP_EVERY: usize = 20;
+const GROUP_LENGTH: usize = 3;
+
+/// In-memory network shaped like a real one, sized by the number of interface nodes
+pub struct SyntheticNetwork {
+    pub hosts: Vec<Host>,
+    pub subnets: Vec<Subnet>,
+    pub services: Vec<Service>,
+    pub groups: Vec<Group>,
+    pub options: TopologyRequestOptions,
+}
+
+impl SyntheticNetwork {
+    /// Generate a network with about `node_count` interface nodes. Generation is
+    /// deterministic apart from entity IDs.
+    pub fn generate(node_count: usize) -> Self {
+        let network_id = Uuid::new_v4();
+        let subnet_count = node_count.div_ceil(HOSTS_PER_SUBNET).max(1);
+        let host_count = node_count * MULTI_HOMED_EVERY / (MULTI_HOMED_EVERY + 1);
+
+        let subnet_types = [
+            SubnetType::Lan,
+            SubnetType::WiFi,
+            SubnetType::IoT,
+            SubnetType::Storage,
+            SubnetType::Management,
+        ];
+
+        let subnets: Vec<Subnet> = (0..subnet_count)
+            .map(|i| {
+                Subnet::new(SubnetBase {
+                    cidr: IpCidr::new(subnet_address(i, 0), 24).expect("Synthetic subnet CIDR"),
+                    network_id,
+                    name: format!("Subnet {}", i),
+                    description: None,
+                    subnet_type: subnet_types[i % subnet_types.len()],
+                    source: EntitySource::System,
+                    layer2: SubnetLayer2::default(),
+                    attributes: EntityAttributes::default(),
+                })
+            })
+            .collect();
+
+        let definitions = ServiceDefinitionRegistry::all_service_definitions();
+
+        let mut hosts = Vec::with_capacity(host_count);
+        let mut services = Vec::with_capacity(host_count);
+
+        for i in 0..host_count {
+            let subnet_index = i % subnet_count;
+            let octet = (i / subnet_count) % 253 + 2;
+
+            let mut interfaces = vec![interface(&subnets[subnet_index], subnet_index, octet)];
+            if i % MULTI_HOMED_EVERY == 0 && subnet_count > 1 {
+                let next = (subnet_index + 1) % subnet_count;
+                interfaces.push(interface(&subnets[next], next, octet));
+            }
+
+            let mut host = Host::new(HostBase {
+                name: format!("host-{}", i),
+                network_id,
+                interfaces,
+                source: EntitySource::System,
+                ..HostBase::default()
+            });
+
+            let definition = definitions[i % definitions.len()].clone();
+            let port = Port::new(PortBase::new_tcp(8000 + (i % 1000) as u16));
+            let binding = Binding::new_port(port.id, Some(host.base.interfaces[0].id));
+            host.base.ports.push(port);
+
+            let service = Service::new(ServiceBase {
+                host_id: host.id,
+                network_id,
+                name: definition.name().to_string(),
+                service_definition: definition,
+                bindings: vec![binding],
+                virtualization: None,
+                source: EntitySource::System,
+                versions: Vec::new(),
+                vulnerabilities: Vec::new(),
+                attributes: EntityAttributes::default(),
+            });
+
+            host.add_service(service.id);
+            hosts.push(host);
+            services.push(service);
+        }
+
+        let groups = (0..host_count)
+            .step_by(GROUP_EVERY)
+            .map(|start| {
+                let service_bindings = (0..GROUP_LENGTH)
+                    .filter_map(|step| services.get(start + step * (subnet_count.max(2) - 1)))
+                    .filter_map(|s| s.base.bindings.first().map(|b| b.id()))
+                    .collect();
+
+                Group::new(GroupBase {
+                    name: format!("Path {}", start),
+                    network_id,
+                    description: None,
+                    group_type: GroupType::RequestPath { service_bindings },
+                    source: EntitySource::System,
+                    color: "rose".to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            hosts,
+            subnets,
+            services,
+            groups,
+            options: TopologyRequestOptions {
+                network_ids: vec![network_id],
+                ..TopologyRequestOptions::default()
+            },
+        }
+    }
+}
+
+fn subnet_address(index: usize, host: usize) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(
+        10,
+        (index / 256) as u8,
+        (index % 256) as u8,
+        host as u8,
+    ))
+}
+
+fn interface(subnet: &Subnet, subnet_index: usize, octet: usize) -> Interface {
+    Interface::new(InterfaceBase {
+        subnet_id: subnet.id,
+        ip_address: subnet_address(subnet_index, octet),
+        mac_address: None,
+        name: None,
+    })
+}
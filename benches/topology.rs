@@ -0,0 +1,204 @@
+//! Layout engine benchmarks on synthetic networks of 50, 500 and 5000 nodes.
+//!
+//! `cargo bench --bench topology` runs the criterion benchmarks, then times a full layout per
+//! size and fails if one exceeds its regression threshold or the layout isn't the same on every
+//! run, printing the layout quality alongside. Set `TOPOLOGY_BENCH_THRESHOLD_SCALE` to loosen
+//! the thresholds on slow machines (e.g. `2.0`).
+
+mod support;
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group};
+use netvisor::server::topology::{
+    service::{
+        context::TopologyContext,
+        edge_builder::EdgeBuilder,
+        main::TopologyService,
+        optimizer::{
+            child_positioner::ChildPositioner, main::TopologyOptimizer,
+            subnet_positioner::SubnetPositioner, utils::OptimizerUtils,
+        },
+        planner::subnet_layout_planner::SubnetLayoutPlanner,
+    },
+    types::{edges::Edge, nodes::Node},
+};
+use support::SyntheticNetwork;
+
+const SIZES: [usize; 3] = [50, 500, 5000];
+
+/// Maximum median time for a full layout of each size
+const THRESHOLDS: [(usize, Duration); 3] = [
+    (50, Duration::from_millis(50)),
+    (500, Duration::from_millis(1_000)),
+    (5000, Duration::from_secs(30)),
+];
+
+const THRESHOLD_RUNS: usize = 3;
+
+/// Nodes and edges as they are before optimization, mirroring `TopologyService::layout_graph`
+fn plan(ctx: &TopologyContext) -> (Vec<Node>, Vec<Edge>) {
+    let mut edges = Vec::new();
+    edges.extend(EdgeBuilder::create_interface_edges(ctx));
+    edges.extend(EdgeBuilder::create_trunk_edges(ctx));
+    edges.extend(EdgeBuilder::create_group_edges(ctx));
+    edges.extend(EdgeBuilder::create_vm_host_edges(ctx));
+    let (container_edges, docker_bridges) = EdgeBuilder::create_containerized_service_edges(
+        ctx,
+        ctx.options.group_docker_bridges_by_host,
+    );
+    edges.extend(container_edges);
+
+    let mut planner = SubnetLayoutPlanner::new();
+    let (layouts, child_nodes) = planner.create_subnet_child_nodes(
+        ctx,
+        &mut edges,
+        ctx.options.group_docker_bridges_by_host,
+        docker_bridges,
+    );
+    let subnet_nodes = planner.create_subnet_nodes(ctx, &layouts);
+
+    (subnet_nodes.into_iter().chain(child_nodes).collect(), edges)
+}
+
+fn bench_layout(c: &mut Criterion) {
+    for size in SIZES {
+        let network = SyntheticNetwork::generate(size);
+        let ctx = TopologyContext::new(
+            &network.hosts,
+            &network.subnets,
+            &network.services,
+            &network.groups,
+            &network.options,
+        );
+        let (nodes, edges) = plan(&ctx);
+
+        let mut group = c.benchmark_group(format!("topology_{}", size));
+        if size >= 500 {
+            group.sample_size(10);
+        }
+
+        group.bench_function(BenchmarkId::new("layout_graph", size), |b| {
+            b.iter(|| {
+                TopologyService::layout_graph(
+                    black_box(&network.hosts),
+                    &network.subnets,
+                    &network.groups,
+                    &network.services,
+                    &network.options,
+                )
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("topology_optimizer", size), |b| {
+            let optimizer = TopologyOptimizer::new(&ctx);
+            b.iter_batched(
+                || nodes.clone(),
+                |mut nodes| optimizer.optimize_graph(&mut nodes, &edges),
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("subnet_positioner", size), |b| {
+            let positioner = SubnetPositioner::new(&ctx);
+            b.iter_batched(
+                || nodes.clone(),
+                |mut nodes| positioner.optimize_positions(&mut nodes, &edges),
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("child_positioner", size), |b| {
+            let positioner = ChildPositioner::new(&ctx);
+            b.iter_batched(
+                || nodes.clone(),
+                |mut nodes| positioner.optimize_positions(&mut nodes, &edges),
+                BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("edge_crossings", size), |b| {
+            let utils = OptimizerUtils::new();
+            b.iter(|| utils.count_edge_crossings(black_box(&nodes), &edges))
+        });
+
+        group.finish();
+    }
+}
+
+/// Median full layout time per size, checked against `THRESHOLDS`, and layout determinism
+fn check_thresholds() {
+    let scale: f64 = std::env::var("TOPOLOGY_BENCH_THRESHOLD_SCALE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1.0);
+
+    let mut regressions = Vec::new();
+
+    for (size, threshold) in THRESHOLDS {
+        let network = SyntheticNetwork::generate(size);
+
+        let mut quality = None;
+        let mut layouts = Vec::new();
+        let mut times: Vec<Duration> = (0..THRESHOLD_RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                let (graph, report) = black_box(TopologyService::layout_graph_with_report(
+                    &network.hosts,
+                    &network.subnets,
+                    &network.groups,
+                    &network.services,
+                    &network.options,
+                ));
+                let elapsed = start.elapsed();
+                quality = Some(report.result);
+                layouts.push(
+                    graph
+                        .node_weights()
+                        .map(|n| (n.id, n.position))
+                        .collect::<Vec<_>>(),
+                );
+                elapsed
+            })
+            .collect();
+        times.sort();
+
+        let median = times[times.len() / 2];
+        let limit = threshold.mul_f64(scale);
+
+        println!(
+            "topology_{}: median {:?} (threshold {:?})",
+            size, median, limit
+        );
+        if let Some(quality) = quality {
+            println!(
+                "topology_{}: {} crossings, edge length {:.0}",
+                size, quality.edge_crossings, quality.total_edge_length
+            );
+        }
+        if median > limit {
+            regressions.push(format!("topology_{}: {:?} > {:?}", size, median, limit));
+        }
+        if layouts.windows(2).any(|pair| pair[0] != pair[1]) {
+            regressions.push(format!("topology_{}: layout differs between runs", size));
+        }
+    }
+
+    if !regressions.is_empty() {
+        panic!(
+            "Layout regression thresholds exceeded:\n{}",
+            regressions.join("\n")
+        );
+    }
+}
+
+criterion_group!(benches, bench_layout);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    check_thresholds();
+}
@@ -0,0 +1,15 @@
+CREATE TABLE webhooks (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    template TEXT,
+    content_type TEXT,
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_delivery TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_webhooks_network ON webhooks(network_id);
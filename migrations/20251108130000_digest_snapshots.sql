@@ -0,0 +1,7 @@
+CREATE TABLE digest_snapshots (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL UNIQUE REFERENCES networks(id) ON DELETE CASCADE,
+    snapshot JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
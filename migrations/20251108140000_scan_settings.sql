@@ -0,0 +1 @@
+ALTER TABLE daemons ADD COLUMN scan_settings JSONB NOT NULL DEFAULT '{}';
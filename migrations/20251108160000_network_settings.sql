@@ -0,0 +1,12 @@
+CREATE TABLE network_settings (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL UNIQUE REFERENCES networks(id) ON DELETE CASCADE,
+    host_naming_fallback JSONB NOT NULL,
+    discovery_schedule TEXT NOT NULL,
+    scan_settings JSONB NOT NULL DEFAULT '{}',
+    discovery_retention_days INTEGER,
+    approval_mode JSONB NOT NULL,
+    topology JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
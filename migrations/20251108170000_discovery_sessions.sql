@@ -0,0 +1,9 @@
+-- In-flight discovery sessions and daemon queues, saved on shutdown and restored on startup
+CREATE TABLE discovery_sessions (
+    id UUID PRIMARY KEY,
+    daemon_id UUID NOT NULL REFERENCES daemons(id) ON DELETE CASCADE,
+    queue_position INTEGER NOT NULL,
+    session JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+CREATE TABLE custom_service_definitions (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    category TEXT NOT NULL,
+    logo_url TEXT,
+    discovery_pattern TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+ALTER TABLE network_settings ADD COLUMN min_service_confidence JSONB NOT NULL DEFAULT '"Low"';
+ALTER TABLE hosts ADD COLUMN service_overrides JSONB NOT NULL DEFAULT '{}';
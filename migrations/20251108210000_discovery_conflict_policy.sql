@@ -0,0 +1 @@
+ALTER TABLE network_settings ADD COLUMN discovery_conflict_policy JSONB NOT NULL DEFAULT '"KeepExisting"';
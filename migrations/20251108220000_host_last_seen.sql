@@ -0,0 +1,7 @@
+ALTER TABLE hosts ADD COLUMN last_seen_at TIMESTAMPTZ;
+ALTER TABLE hosts ADD COLUMN stale BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE hosts SET last_seen_at = updated_at WHERE source->>'type' = 'Discovery';
+
+ALTER TABLE network_settings ADD COLUMN stale_after_scans INTEGER;
+ALTER TABLE network_settings ADD COLUMN stale_host_action JSONB NOT NULL DEFAULT '"Flag"';
@@ -0,0 +1,32 @@
+CREATE TABLE health_checks (
+    id UUID PRIMARY KEY,
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    kind JSONB NOT NULL,
+    port INTEGER,
+    interval_seconds INTEGER NOT NULL DEFAULT 60,
+    timeout_ms INTEGER NOT NULL DEFAULT 5000,
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_health_checks_network ON health_checks(network_id);
+CREATE INDEX idx_health_checks_service ON health_checks(service_id);
+
+CREATE TABLE health_check_results (
+    id UUID PRIMARY KEY,
+    check_id UUID NOT NULL REFERENCES health_checks(id) ON DELETE CASCADE,
+    service_id UUID NOT NULL REFERENCES services(id) ON DELETE CASCADE,
+    daemon_id UUID NOT NULL,
+    checked_at TIMESTAMPTZ NOT NULL,
+    up BOOLEAN NOT NULL,
+    latency_ms INTEGER,
+    status_code INTEGER,
+    message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_health_check_results_service_time ON health_check_results(service_id, checked_at);
+CREATE INDEX idx_health_check_results_checked_at ON health_check_results(checked_at);
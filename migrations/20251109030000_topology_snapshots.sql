@@ -0,0 +1,12 @@
+CREATE TABLE topology_snapshots (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    discovery_session_id UUID,
+    data JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (network_id, version)
+);
+
+CREATE INDEX idx_topology_snapshots_network ON topology_snapshots(network_id);
@@ -0,0 +1,10 @@
+CREATE TABLE topology_node_pins (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    node_id UUID NOT NULL UNIQUE,
+    position JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_topology_node_pins_network ON topology_node_pins(network_id);
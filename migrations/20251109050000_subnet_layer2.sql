@@ -0,0 +1 @@
+ALTER TABLE subnets ADD COLUMN layer2 JSONB NOT NULL DEFAULT '{}';
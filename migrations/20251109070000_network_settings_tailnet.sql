@@ -0,0 +1 @@
+ALTER TABLE network_settings ADD COLUMN tailnet JSONB;
@@ -0,0 +1,14 @@
+CREATE TABLE proxmox_credentials (
+    id UUID PRIMARY KEY,
+    service_id UUID NOT NULL UNIQUE REFERENCES services(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    url TEXT,
+    token_id TEXT NOT NULL,
+    token_secret TEXT NOT NULL,
+    verify_tls BOOLEAN NOT NULL DEFAULT FALSE,
+    last_synced_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_proxmox_credentials_network ON proxmox_credentials(network_id);
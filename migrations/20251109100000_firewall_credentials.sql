@@ -0,0 +1,14 @@
+CREATE TABLE firewall_credentials (
+    id UUID PRIMARY KEY,
+    service_id UUID NOT NULL UNIQUE REFERENCES services(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    url TEXT,
+    api_key TEXT NOT NULL,
+    api_secret TEXT,
+    verify_tls BOOLEAN NOT NULL DEFAULT FALSE,
+    last_synced_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_firewall_credentials_network ON firewall_credentials(network_id);
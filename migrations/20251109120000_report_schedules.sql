@@ -0,0 +1,14 @@
+CREATE TABLE report_schedules (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    cron_schedule TEXT NOT NULL,
+    recipients JSONB NOT NULL DEFAULT '[]',
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    snapshot JSONB,
+    last_sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_report_schedules_network ON report_schedules(network_id);
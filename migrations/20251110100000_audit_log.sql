@@ -0,0 +1,16 @@
+CREATE TABLE audit_log (
+    id UUID PRIMARY KEY,
+    user_id UUID,
+    api_key_id UUID,
+    source_ip TEXT,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    status INTEGER NOT NULL,
+    changes JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX idx_audit_log_user ON audit_log(user_id);
+CREATE INDEX idx_audit_log_changes ON audit_log USING GIN (changes jsonb_path_ops);
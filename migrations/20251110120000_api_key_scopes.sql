@@ -0,0 +1,5 @@
+-- Existing keys keep full access
+ALTER TABLE api_keys
+    ADD COLUMN scopes JSONB NOT NULL DEFAULT '["*"]',
+    ADD COLUMN revoked_at TIMESTAMPTZ,
+    ADD COLUMN revoked_by UUID REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,16 @@
+-- Invitations to sign up and join a network
+CREATE TABLE invites (
+    id UUID PRIMARY KEY,
+    email TEXT NOT NULL,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    invited_by UUID NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    accepted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_invites_network ON invites(network_id);
@@ -0,0 +1,8 @@
+-- Tags and custom fields users attach to hosts, services and subnets
+ALTER TABLE hosts ADD COLUMN attributes JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE services ADD COLUMN attributes JSONB NOT NULL DEFAULT '{}';
+ALTER TABLE subnets ADD COLUMN attributes JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX idx_hosts_tags ON hosts USING GIN ((attributes -> 'tags'));
+CREATE INDEX idx_services_tags ON services USING GIN ((attributes -> 'tags'));
+CREATE INDEX idx_subnets_tags ON subnets USING GIN ((attributes -> 'tags'));
@@ -0,0 +1 @@
+ALTER TABLE hosts ADD COLUMN dhcp_lease JSONB;
@@ -0,0 +1,14 @@
+CREATE TABLE dns_zone_settings (
+    id UUID PRIMARY KEY,
+    service_id UUID NOT NULL UNIQUE REFERENCES services(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    server TEXT,
+    zones JSONB NOT NULL DEFAULT '[]',
+    reverse_zones BOOLEAN NOT NULL DEFAULT TRUE,
+    names JSONB NOT NULL DEFAULT '{}',
+    last_synced_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_dns_zone_settings_network ON dns_zone_settings(network_id);
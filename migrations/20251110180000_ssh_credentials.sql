@@ -0,0 +1,19 @@
+CREATE TABLE ssh_credentials (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    host_id UUID REFERENCES hosts(id) ON DELETE CASCADE,
+    subnet_id UUID REFERENCES subnets(id) ON DELETE CASCADE,
+    username TEXT NOT NULL,
+    password TEXT,
+    private_key TEXT,
+    passphrase TEXT,
+    port INTEGER NOT NULL DEFAULT 22,
+    host_key_fingerprint TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((host_id IS NULL) <> (subnet_id IS NULL))
+);
+
+CREATE INDEX idx_ssh_credentials_network ON ssh_credentials(network_id);
+
+ALTER TABLE hosts ADD COLUMN ssh JSONB;
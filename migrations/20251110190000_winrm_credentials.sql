@@ -0,0 +1,18 @@
+CREATE TABLE winrm_credentials (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    host_id UUID REFERENCES hosts(id) ON DELETE CASCADE,
+    subnet_id UUID REFERENCES subnets(id) ON DELETE CASCADE,
+    username TEXT NOT NULL,
+    password TEXT NOT NULL,
+    port INTEGER NOT NULL DEFAULT 5986,
+    https BOOLEAN NOT NULL DEFAULT TRUE,
+    verify_tls BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((host_id IS NULL) <> (subnet_id IS NULL))
+);
+
+CREATE INDEX idx_winrm_credentials_network ON winrm_credentials(network_id);
+
+ALTER TABLE hosts ADD COLUMN windows JSONB;
@@ -0,0 +1,12 @@
+ALTER TABLE services ADD COLUMN versions JSONB NOT NULL DEFAULT '[]';
+ALTER TABLE services ADD COLUMN vulnerabilities JSONB NOT NULL DEFAULT '[]';
+
+CREATE INDEX idx_services_vulnerable ON services((jsonb_array_length(vulnerabilities) > 0));
+
+CREATE TABLE vulnerability_feed (
+    id UUID PRIMARY KEY,
+    cpe TEXT NOT NULL UNIQUE,
+    vulnerabilities JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,29 @@
+CREATE TABLE policies (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    condition JSONB NOT NULL,
+    severity TEXT NOT NULL DEFAULT 'Medium',
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_policies_network ON policies(network_id);
+
+CREATE TABLE policy_violations (
+    id UUID PRIMARY KEY,
+    policy_id UUID NOT NULL REFERENCES policies(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    host_id UUID NOT NULL REFERENCES hosts(id) ON DELETE CASCADE,
+    service_id UUID REFERENCES services(id) ON DELETE CASCADE,
+    port JSONB,
+    message TEXT NOT NULL,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_policy_violations_network ON policy_violations(network_id);
+CREATE INDEX idx_policy_violations_open ON policy_violations(policy_id) WHERE resolved_at IS NULL;
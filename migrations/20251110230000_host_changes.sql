@@ -0,0 +1,13 @@
+CREATE TABLE host_changes (
+    id UUID PRIMARY KEY,
+    host_id UUID NOT NULL REFERENCES hosts(id) ON DELETE CASCADE,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    opened_ports JSONB NOT NULL DEFAULT '[]',
+    closed_ports JSONB NOT NULL DEFAULT '[]',
+    added_services JSONB NOT NULL DEFAULT '[]',
+    removed_services JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_host_changes_host ON host_changes(host_id, created_at);
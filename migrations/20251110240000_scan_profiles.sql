@@ -0,0 +1,13 @@
+CREATE TABLE scan_profiles (
+    id UUID PRIMARY KEY,
+    network_id UUID NOT NULL REFERENCES networks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    ports JSONB NOT NULL DEFAULT '{"type": "Standard"}',
+    settings JSONB NOT NULL DEFAULT '{}',
+    limits JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_scan_profiles_network ON scan_profiles(network_id);
@@ -0,0 +1 @@
+ALTER TABLE network_settings ADD COLUMN scan_limits JSONB NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+ALTER TABLE daemons ADD COLUMN status TEXT NOT NULL DEFAULT 'Online';
+ALTER TABLE daemons ADD COLUMN health JSONB;
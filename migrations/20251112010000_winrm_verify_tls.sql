@@ -0,0 +1 @@
+ALTER TABLE winrm_credentials ALTER COLUMN verify_tls SET DEFAULT TRUE;
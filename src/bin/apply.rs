@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use netvisor::{client::api::ApiClient, server::apply::r#impl::plan::ChangeAction};
+
+#[derive(Parser)]
+#[command(name = "netvisor-apply")]
+#[command(about = "Reconcile NetVisor server state with a YAML config spec")]
+struct Cli {
+    /// Config spec file
+    file: PathBuf,
+
+    /// Show the planned changes without applying them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Server URL
+    #[arg(
+        long,
+        env = "NETVISOR_CLI_SERVER_URL",
+        default_value = "http://localhost:60072"
+    )]
+    server_url: String,
+
+    /// User email
+    #[arg(long, env = "NETVISOR_CLI_EMAIL")]
+    email: String,
+
+    /// User password
+    #[arg(long, env = "NETVISOR_CLI_PASSWORD", hide_env_values = true)]
+    password: String,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let spec = tokio::fs::read_to_string(&cli.file).await?;
+
+    let client = ApiClient::new(&cli.server_url)?;
+    client.login(&cli.email, &cli.password).await?;
+
+    let plan = client.apply_config(spec, cli.dry_run).await?;
+
+    if plan.changes.is_empty() {
+        println!("No changes; server state matches {}", cli.file.display());
+        return Ok(());
+    }
+
+    for change in &plan.changes {
+        let symbol = match change.action {
+            ChangeAction::Create => "+",
+            ChangeAction::Update => "~",
+            ChangeAction::Delete => "-",
+        };
+
+        let fields = if change.changed_fields.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", change.changed_fields.join(", "))
+        };
+
+        println!(
+            "{} {} {}/{}{}",
+            symbol, change.entity, change.network, change.name, fields
+        );
+    }
+
+    if plan.applied {
+        println!("Applied {} changes", plan.changes.len());
+    } else {
+        println!(
+            "{} changes planned; run without --dry-run to apply",
+            plan.changes.len()
+        );
+    }
+
+    Ok(())
+}
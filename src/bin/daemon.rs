@@ -53,6 +53,10 @@ struct Cli {
     #[arg(long)]
     heartbeat_interval: Option<u64>,
 
+    /// Ping monitor interval in seconds, 0 to disable
+    #[arg(long)]
+    ping_interval: Option<u64>,
+
     /// Daemon bind address
     #[arg(long)]
     bind_address: Option<String>,
@@ -68,6 +72,18 @@ struct Cli {
     /// Docker socket proxy
     #[arg(long)]
     docker_proxy: Option<String>,
+
+    /// HTTP or SOCKS5 proxy for connections to the server
+    #[arg(long)]
+    proxy: Option<String>,
+
+    /// Hosts that bypass the proxy, comma separated
+    #[arg(long)]
+    no_proxy: Option<String>,
+
+    /// PEM bundle of extra trusted CA certificates
+    #[arg(long)]
+    ca_cert_path: Option<String>,
 }
 
 impl From<Cli> for CliArgs {
@@ -81,9 +97,13 @@ impl From<Cli> for CliArgs {
             network_id: cli.network_id.and_then(|s| Uuid::parse_str(&s).ok()),
             log_level: cli.log_level,
             heartbeat_interval: cli.heartbeat_interval,
+            ping_interval: cli.ping_interval,
             concurrent_scans: cli.concurrent_scans,
             daemon_api_key: cli.daemon_api_key,
             docker_proxy: cli.docker_proxy,
+            proxy: cli.proxy,
+            no_proxy: cli.no_proxy,
+            ca_cert_path: cli.ca_cert_path,
         }
     }
 }
@@ -155,6 +175,9 @@ async fn main() -> anyhow::Result<()> {
             runtime_service
                 .initialize_services(*network_id, api_key.clone())
                 .await?;
+            if let Err(e) = runtime_service.report_interrupted_discovery().await {
+                tracing::warn!("Failed to report interrupted discovery: {}", e);
+            }
         } else {
             tracing::warn!(
                 "Daemon is missing an API key. Go to discovery tab in UI to generate an API key."
@@ -164,6 +187,22 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("Missing network ID - waiting for server to hit /api/initialize...");
     }
 
+    // Spawn ping monitor task in background
+    let monitor_service = runtime_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = monitor_service.monitor_reachability().await {
+            tracing::warn!("Ping monitor stopped: {}", e);
+        }
+    });
+
+    // Spawn service health check task in background
+    let health_service = runtime_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = health_service.monitor_health().await {
+            tracing::warn!("Health checks stopped: {}", e);
+        }
+    });
+
     // Spawn heartbeat task in background
     tokio::spawn(async move {
         if let Err(e) = runtime_service.heartbeat().await {
@@ -1,28 +1,43 @@
-use std::time::Duration;
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Error;
-use axum::{Router, http::Method};
-use clap::Parser;
+use axum::{Router, http::Method, middleware::from_fn_with_state};
+use clap::{Parser, Subcommand};
 use netvisor::{
-    daemon::runtime::types::InitializeDaemonRequest,
+    daemon::{discovery::types::base::DiscoveryPhase, runtime::types::InitializeDaemonRequest},
     server::{
-        api_keys::r#impl::base::{ApiKey, ApiKeyBase},
-        config::{AppState, CliArgs, ServerConfig},
+        api_keys::r#impl::{
+            base::{ApiKey, ApiKeyBase},
+            scopes::DAEMON_SCOPES,
+        },
+        audit::middleware::record_audit_trail,
+        auth::rate_limit::limit_requests,
+        backups::service::restore,
+        config::{AppState, CliArgs, ServerConfig, log_directives},
+        hosts::r#impl::api::HostEvent,
+        integrations::{
+            home_assistant::service::HomeAssistantService,
+            mqtt::events::{MqttEvent, MqttEventPublisher},
+        },
+        notifications::r#impl::api::Alert,
         shared::{
             handlers::factory::create_router,
             services::traits::CrudService,
             storage::{filter::EntityFilter, traits::StorableEntity},
         },
         users::r#impl::base::{User, UserBase},
+        webhooks::r#impl::api::WebhookEvent,
     },
 };
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
 use tower::ServiceBuilder;
 use tower_http::{
     cors::{Any, CorsLayer},
     services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, layer::SubscriberExt, reload, util::SubscriberInitExt};
 use uuid::Uuid;
 
 #[derive(Parser)]
@@ -53,10 +68,14 @@ struct Cli {
     #[arg(long)]
     use_secure_session_cookies: Option<bool>,
 
-    /// Enable or disable registration flow
+    /// Only let invited users register
     #[arg(long)]
     disable_registration: bool,
 
+    /// Populate the first network with demo data instead of scanning
+    #[arg(long)]
+    demo: bool,
+
     /// OIDC client ID
     #[arg(long)]
     oidc_client_id: Option<String>,
@@ -76,6 +95,22 @@ struct Cli {
     /// OIDC redirect url
     #[arg(long)]
     oidc_redirect_url: Option<String>,
+
+    /// JSON config file, re-read when the configuration is reloaded
+    #[arg(long)]
+    config_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Replace the database's contents with a backup and exit. Stop the server first.
+    Restore {
+        /// Backup file written by the scheduled backups
+        file: PathBuf,
+    },
 }
 
 impl From<Cli> for CliArgs {
@@ -88,11 +123,13 @@ impl From<Cli> for CliArgs {
             integrated_daemon_url: cli.integrated_daemon_url,
             use_secure_session_cookies: cli.use_secure_session_cookies,
             disable_registration: cli.disable_registration,
+            demo: cli.demo,
             oidc_client_id: cli.oidc_client_id,
             oidc_client_secret: cli.oidc_client_secret,
             oidc_issuer_url: cli.oidc_issuer_url,
             oidc_provider_name: cli.oidc_provider_name,
             oidc_redirect_url: cli.oidc_redirect_url,
+            config_file: cli.config_file,
         }
     }
 }
@@ -101,11 +138,12 @@ impl From<Cli> for CliArgs {
 async fn main() -> anyhow::Result<()> {
     let _ = dotenv::dotenv();
 
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let command = cli.command.take();
     let cli_args = CliArgs::from(cli);
 
     // Load configuration using figment
-    let config = ServerConfig::load(cli_args)?;
+    let config = ServerConfig::load(cli_args.clone())?;
     let listen_addr = format!("0.0.0.0:{}", &config.server_port);
     let web_external_path = config.web_external_path.clone();
     let integrated_daemon_url = config
@@ -113,20 +151,37 @@ async fn main() -> anyhow::Result<()> {
         .clone()
         .unwrap_or("http://daemon:60073".to_string());
 
-    // Initialize tracing
+    // Initialize tracing, keeping a handle so the log level can be reloaded
+    let (filter_layer, log_filter) =
+        reload::Layer::new(EnvFilter::new(log_directives(&config.log_level)));
     tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(format!(
-            "netvisor={},server={}",
-            config.log_level, config.log_level
-        )))
+        .with(filter_layer)
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    if let Some(Command::Restore { file }) = command {
+        restore(&config.database_url(), &file).await?;
+        tracing::info!("Restored database from {}", file.display());
+        return Ok(());
+    }
+
     // Create app state
-    let state = AppState::new(config).await?;
+    let state = AppState::new(config, cli_args).await?;
+    let _ = state.log_filter.set(log_filter);
     let user_service = state.services.user_service.clone();
     let api_key_service = state.services.api_key_service.clone();
     let discovery_service = state.services.discovery_service.clone();
+    let report_service = state.services.report_service.clone();
+    let backup_service = state.backup_service.clone();
+    let demo_service = state.services.demo_service.clone();
+    let demo = state.config.demo;
+
+    // Restore discovery sessions saved at the last shutdown, before daemons can post updates
+    match discovery_service.restore_sessions().await {
+        Ok(restored) if restored > 0 => tracing::info!("Restored {} discovery sessions", restored),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to restore discovery sessions: {}", e),
+    }
 
     // Create discovery cleanup task
     let discovery_cleanup_state = state.clone();
@@ -158,16 +213,533 @@ async fn main() -> anyhow::Result<()> {
                 .auth_service
                 .cleanup_old_login_attempts()
                 .await;
+            auth_cleanup_state.rate_limits.cleanup();
+        }
+    });
+
+    // Create daemon offline monitor task
+    let daemon_monitor_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            if let Err(e) = daemon_monitor_state
+                .services
+                .daemon_service
+                .mark_offline_daemons()
+                .await
+            {
+                tracing::warn!("Daemon offline monitor failed: {}", e);
+            }
+        }
+    });
+
+    // Create orphaned record maintenance task
+    let orphan_cleanup_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            let dry_run = !orphan_cleanup_state.config.auto_remove_orphans;
+
+            match orphan_cleanup_state
+                .services
+                .maintenance_service
+                .cleanup_orphans(&[], dry_run)
+                .await
+            {
+                Ok(report) if report.total() > 0 && dry_run => tracing::warn!(
+                    "Found {} orphaned records, see /api/maintenance/orphans",
+                    report.total()
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Orphaned record cleanup failed: {}", e),
+            }
+        }
+    });
+
+    // Create discovery history retention task
+    let retention_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            match retention_state
+                .services
+                .discovery_service
+                .prune_expired_history()
+                .await
+            {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!("Deleted {} expired discovery runs", deleted)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Discovery history retention task failed: {}", e),
+            }
+        }
+    });
+
+    // Create trash purge task
+    let trash_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            match trash_state.services.maintenance_service.purge_trash().await {
+                Ok(purged) if purged > 0 => {
+                    tracing::info!("Permanently deleted {} entities from the trash", purged)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Trash purge task failed: {}", e),
+            }
+        }
+    });
+
+    // Create stale host expiry task
+    let stale_host_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            match stale_host_state
+                .services
+                .host_service
+                .expire_stale_hosts()
+                .await
+            {
+                Ok(expired) if expired > 0 => tracing::info!("Marked {} hosts stale", expired),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Stale host expiry task failed: {}", e),
+            }
+        }
+    });
+
+    // Create vulnerability feed sync task
+    if state.config.vulnerability_feed {
+        let vulnerability_state = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+            loop {
+                interval.tick().await;
+                match vulnerability_state
+                    .services
+                    .vulnerability_service
+                    .sync(vulnerability_state.config.nvd_api_key.clone())
+                    .await
+                {
+                    Ok(changed) if changed > 0 => {
+                        tracing::info!("Updated vulnerabilities of {} services", changed)
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Vulnerability feed sync failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Create health check result retention task
+    let health_retention_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            match health_retention_state
+                .services
+                .monitoring_service
+                .prune_results()
+                .await
+            {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!("Deleted {} expired health check results", deleted)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Health check retention task failed: {}", e),
+            }
+        }
+    });
+
+    // Create alert dispatch task
+    let alert_state = state.clone();
+    let mut host_events = state.services.host_service.subscribe();
+    let mut discovery_updates = state.services.discovery_service.subscribe();
+    let mut policy_violations = state.services.policy_service.subscribe();
+    let mut daemon_events = state.services.daemon_service.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let alert = tokio::select! {
+                event = host_events.recv() => match event {
+                    Ok(event) => Some(Alert::from_host_event(&event)),
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Alerts skipped {} host events", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                update = discovery_updates.recv() => match update {
+                    Ok(update) => Alert::from_discovery_update(&update),
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Alerts skipped {} discovery updates", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                event = policy_violations.recv() => match event {
+                    Ok(event) => Some(Alert::from_policy_violation(&event)),
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Alerts skipped {} policy violations", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                event = daemon_events.recv() => match event {
+                    Ok(event) => Some(Alert::from_daemon_event(&event)),
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Alerts skipped {} daemon events", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            let Some(alert) = alert else {
+                continue;
+            };
+
+            // Sent in the background so slow channels don't make the receivers lag
+            let alert_service = alert_state.services.alert_service.clone();
+            let email_client = alert_state.reloadable().email_client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = alert_service
+                    .dispatch(&alert, email_client.as_deref())
+                    .await
+                {
+                    tracing::warn!("Failed to dispatch alert: {}", e);
+                }
+            });
+        }
+    });
+
+    // Create webhook dispatch task
+    let webhook_state = state.clone();
+    let mut entity_events = state.storage.events.subscribe();
+    let mut webhook_discovery_updates = state.services.discovery_service.subscribe();
+    tokio::spawn(async move {
+        // Last event sent per discovery session, so progress updates within a phase are skipped
+        let mut session_phases: HashMap<Uuid, String> = HashMap::new();
+        loop {
+            let event = tokio::select! {
+                event = entity_events.recv() => match event {
+                    Ok(event) => WebhookEvent::from_entity_event(&event),
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Webhooks skipped {} entity events", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                update = webhook_discovery_updates.recv() => match update {
+                    Ok(update) => {
+                        let event = WebhookEvent::from_discovery_update(&update);
+                        if matches!(
+                            update.phase,
+                            DiscoveryPhase::Complete
+                                | DiscoveryPhase::Failed
+                                | DiscoveryPhase::Cancelled
+                        ) {
+                            session_phases.remove(&update.session_id);
+                            Some(event)
+                        } else if session_phases.get(&update.session_id) != Some(&event.event) {
+                            session_phases.insert(update.session_id, event.event.clone());
+                            Some(event)
+                        } else {
+                            None
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!("Webhooks skipped {} discovery updates", n);
+                        None
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+
+            let Some(event) = event else {
+                continue;
+            };
+
+            let webhook_service = webhook_state.services.webhook_service.clone();
+            tokio::spawn(async move {
+                if let Err(e) = webhook_service.dispatch(&event).await {
+                    tracing::warn!("Failed to dispatch webhook event {}: {}", event.event, e);
+                }
+            });
+        }
+    });
+
+    // Create webhook retry task
+    let webhook_retry_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            match webhook_retry_state
+                .services
+                .webhook_service
+                .retry_due()
+                .await
+            {
+                Ok(retried) if retried > 0 => {
+                    tracing::info!("Retried {} webhook deliveries", retried)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Webhook retry task failed: {}", e),
+            }
+        }
+    });
+
+    // Create webhook delivery retention task
+    let webhook_retention_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+        loop {
+            interval.tick().await;
+            match webhook_retention_state
+                .services
+                .webhook_service
+                .prune_deliveries()
+                .await
+            {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!("Deleted {} expired webhook deliveries", deleted)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Webhook delivery retention task failed: {}", e),
+            }
+        }
+    });
+
+    // Create topology snapshot task, taking a snapshot after every completed discovery run
+    let snapshot_state = state.clone();
+    let mut snapshot_discovery_updates = state.services.discovery_service.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let update = match snapshot_discovery_updates.recv().await {
+                Ok(update) if matches!(update.phase, DiscoveryPhase::Complete) => update,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(n)) => {
+                    tracing::warn!("Topology snapshots skipped {} discovery updates", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let service = snapshot_state.services.topology_snapshot_service.clone();
+            tokio::spawn(async move {
+                if let Err(e) = service
+                    .capture(update.network_id, Some(update.session_id))
+                    .await
+                {
+                    tracing::warn!(
+                        "Failed to snapshot topology of network {}: {}",
+                        update.network_id,
+                        e
+                    );
+                }
+            });
+        }
+    });
+
+    // Create policy evaluation task, checking a network's policies after every completed
+    // discovery run
+    let policy_state = state.clone();
+    let mut policy_discovery_updates = state.services.discovery_service.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let update = match policy_discovery_updates.recv().await {
+                Ok(update) if matches!(update.phase, DiscoveryPhase::Complete) => update,
+                Ok(_) => continue,
+                Err(RecvError::Lagged(n)) => {
+                    tracing::warn!("Policy evaluation skipped {} discovery updates", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let service = policy_state.services.policy_service.clone();
+            tokio::spawn(async move {
+                match service.evaluate(update.network_id).await {
+                    Ok(opened) if opened > 0 => tracing::info!(
+                        "Found {} new policy violations in network {}",
+                        opened,
+                        update.network_id
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!(
+                        "Failed to evaluate policies of network {}: {}",
+                        update.network_id,
+                        e
+                    ),
+                }
+            });
+        }
+    });
+
+    // Create Home Assistant presence publishing task
+    let home_assistant_service = match state.mqtt_client.clone() {
+        Some(mqtt_client) if state.config.home_assistant_presence => {
+            Some(Arc::new(HomeAssistantService::new(
+                state.services.host_service.clone(),
+                mqtt_client,
+                state.config.home_assistant_discovery_prefix.clone(),
+                state.config.home_assistant_offline_after_secs,
+            )))
+        }
+        _ => None,
+    };
+
+    if let Some(home_assistant_service) = home_assistant_service.clone() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                if let Err(e) = home_assistant_service.publish_presence().await {
+                    tracing::warn!("Failed to publish Home Assistant presence: {}", e);
+                }
+            }
+        });
+    }
+
+    // Create MQTT event publishing task. New hosts are announced to Home Assistant right away
+    // rather than on the next presence tick.
+    if let Some(mqtt_client) = state.mqtt_client.clone()
+        && state.config.mqtt_events
+    {
+        let publisher = MqttEventPublisher::new(mqtt_client);
+        let mut mqtt_host_events = state.services.host_service.subscribe();
+        let mut mqtt_discovery_updates = state.services.discovery_service.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    event = mqtt_host_events.recv() => match event {
+                        Ok(event) => {
+                            if let (HostEvent::Discovered(_), Some(home_assistant_service)) =
+                                (&event, &home_assistant_service)
+                                && let Err(e) = home_assistant_service.publish_presence().await
+                            {
+                                tracing::warn!("Failed to publish Home Assistant presence: {}", e);
+                            }
+                            MqttEvent::from_host_event(&event).map(Some)
+                        }
+                        Err(RecvError::Lagged(n)) => {
+                            tracing::warn!("MQTT skipped {} host events", n);
+                            Ok(None)
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    update = mqtt_discovery_updates.recv() => match update {
+                        Ok(update) => MqttEvent::from_discovery_update(&update),
+                        Err(RecvError::Lagged(n)) => {
+                            tracing::warn!("MQTT skipped {} discovery updates", n);
+                            Ok(None)
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+
+                let result = match event {
+                    Ok(Some(event)) => publisher.publish(&event).await,
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Failed to publish MQTT event: {}", e);
+                }
+            }
+        });
+    }
+
+    // Reload configuration on SIGHUP
+    #[cfg(unix)]
+    {
+        let reload_state = state.clone();
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                if let Err(e) = reload_state.reload_config() {
+                    tracing::warn!("Failed to reload configuration: {}", e);
+                }
+            }
+        });
+    }
+
+    // Create change digest email task
+    if let Some(frequency) = state.config.digest_frequency {
+        let digest_state = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60 * 60)); // 1 hour
+            loop {
+                interval.tick().await;
+
+                // SMTP settings can be reloaded, so pick up the current client each time
+                let Some(email_client) = digest_state.reloadable().email_client.clone() else {
+                    continue;
+                };
+
+                match digest_state
+                    .services
+                    .digest_service
+                    .send_due_digests(&email_client, frequency)
+                    .await
+                {
+                    Ok(sent) if sent > 0 => tracing::info!("Sent {} change digests", sent),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Change digest task failed: {}", e),
+                }
+            }
+        });
+    }
+
+    // Create scheduled report email task
+    let report_state = state.clone();
+    let mut due_reports = state.services.report_service.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let schedule_id = match due_reports.recv().await {
+                Ok(id) => id,
+                Err(RecvError::Lagged(n)) => {
+                    tracing::warn!("Report task skipped {} due reports", n);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let Some(email_client) = report_state.reloadable().email_client.clone() else {
+                tracing::warn!("Report {} is due but SMTP is not configured", schedule_id);
+                continue;
+            };
+
+            if let Err(e) = report_state
+                .services
+                .report_service
+                .send_report(&schedule_id, &email_client)
+                .await
+            {
+                tracing::warn!("Failed to send report {}: {}", schedule_id, e);
+            }
         }
     });
 
     let session_store = state.storage.sessions.clone();
 
-    let api_router = if let Some(static_path) = &web_external_path {
-        // First create the API router
-        let router = create_router().layer(session_store).with_state(state);
+    // The session layer goes outside the rate limit and audit layers, so they can see who's
+    // signed in. Requests turned away for their rate aren't audited.
+    let router = create_router()
+        .layer(from_fn_with_state(state.clone(), record_audit_trail))
+        .layer(from_fn_with_state(state.clone(), limit_requests))
+        .layer(session_store)
+        .with_state(state);
 
-        // Then add static file serving with SPA fallback
+    let api_router = if let Some(static_path) = &web_external_path {
+        // Add static file serving with SPA fallback
         router.fallback_service(
             ServeDir::new(static_path)
                 .append_index_html_on_directories(true)
@@ -178,7 +750,7 @@ async fn main() -> anyhow::Result<()> {
         )
     } else {
         tracing::info!("Server is not serving web assets due to no web_external_path");
-        create_router().layer(session_store).with_state(state)
+        router
     };
 
     // Create main app
@@ -203,12 +775,28 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("🔧 API: http://<your-ip>:{}/api", actual_port);
 
     // Spawn server in background
-    tokio::spawn(async move {
-        axum::serve(listener, app).await.unwrap();
+    let shutdown = CancellationToken::new();
+    let server_shutdown = shutdown.clone();
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(server_shutdown.cancelled_owned())
+        .await
     });
 
     // Start cron for discovery scheduler
     discovery_service.start_scheduler().await?;
+    report_service.start_scheduler().await?;
+    if let Some(backup_service) = &backup_service {
+        backup_service.start_scheduler().await?;
+    }
+
+    // Check restored sessions against what daemons are actually running
+    if let Err(e) = discovery_service.resync_sessions().await {
+        tracing::warn!("Failed to re-sync discovery sessions with daemons: {}", e);
+    }
 
     let all_users = user_service.get_all(EntityFilter::unfiltered()).await?;
 
@@ -219,22 +807,64 @@ async fn main() -> anyhow::Result<()> {
             .create_user(User::new(UserBase::new_seed()))
             .await?;
 
-        let api_key = api_key_service
-            .create(ApiKey::new(ApiKeyBase {
-                key: "".to_string(),
-                name: "Integrated Daemon API Key".to_string(),
-                last_used: None,
-                expires_at: None,
-                network_id: network.id,
-                is_enabled: true,
-            }))
-            .await?;
-
-        initialize_local_daemon(integrated_daemon_url, network.id, api_key.base.key).await?;
+        // Demo data stands in for scan results, so no daemon is needed
+        if demo {
+            let summary = demo_service.populate(network.id).await?;
+            tracing::info!(
+                "Populated demo network with {} hosts and {} services",
+                summary.hosts,
+                summary.services
+            );
+        } else {
+            let api_key = api_key_service
+                .create(ApiKey::new(ApiKeyBase {
+                    key: "".to_string(),
+                    name: "Integrated Daemon API Key".to_string(),
+                    last_used: None,
+                    expires_at: None,
+                    network_id: network.id,
+                    is_enabled: true,
+                    scopes: DAEMON_SCOPES.iter().map(|s| s.to_string()).collect(),
+                    revoked_at: None,
+                    revoked_by: None,
+                }))
+                .await?;
+
+            initialize_local_daemon(integrated_daemon_url, network.id, api_key.base.key).await?;
+        }
     } else {
         tracing::debug!("Server already has data, skipping seed data");
     }
 
+    shutdown_signal().await?;
+    tracing::info!("Shutting down...");
+
+    // Stop accepting requests, then save sessions so daemon updates aren't lost mid-save
+    shutdown.cancel();
+    server.await??;
+
+    match discovery_service.persist_sessions().await {
+        Ok(saved) if saved > 0 => tracing::info!("Saved {} discovery sessions", saved),
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to save discovery sessions: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM where supported
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
     tokio::signal::ctrl_c().await?;
 
     Ok(())
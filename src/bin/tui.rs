@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use clap::Parser;
+use crossterm::{
+    event::{self, Event, KeyEventKind},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use netvisor::{
+    client::api::ApiClient,
+    tui::{app::App, ui},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+
+#[derive(Parser)]
+#[command(name = "netvisor-tui")]
+#[command(about = "NetVisor terminal dashboard")]
+struct Cli {
+    /// Server URL
+    #[arg(
+        long,
+        env = "NETVISOR_CLI_SERVER_URL",
+        default_value = "http://localhost:60072"
+    )]
+    server_url: String,
+
+    /// User email
+    #[arg(long, env = "NETVISOR_CLI_EMAIL")]
+    email: String,
+
+    /// User password
+    #[arg(long, env = "NETVISOR_CLI_PASSWORD", hide_env_values = true)]
+    password: String,
+
+    /// Refresh interval in seconds
+    #[arg(long, default_value_t = 2)]
+    refresh_interval: u64,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let client = ApiClient::new(&cli.server_url)?;
+    client.login(&cli.email, &cli.password).await?;
+
+    enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = run(
+        &mut terminal,
+        &client,
+        Duration::from_secs(cli.refresh_interval),
+    )
+    .await;
+
+    // Restore the terminal even if the dashboard failed
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+async fn run(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    client: &ApiClient,
+    refresh_interval: Duration,
+) -> anyhow::Result<()> {
+    let mut app = App::new();
+    let mut last_refresh = tokio::time::Instant::now();
+    app.refresh(client).await;
+
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, &mut app))?;
+
+        if event::poll(Duration::from_millis(250))?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+
+        if last_refresh.elapsed() >= refresh_interval {
+            app.refresh(client).await;
+            last_refresh = tokio::time::Instant::now();
+        }
+    }
+
+    Ok(())
+}
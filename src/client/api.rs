@@ -0,0 +1,106 @@
+use crate::server::{
+    apply::r#impl::plan::ApplyPlan,
+    daemons::r#impl::{api::DiscoveryUpdatePayload, base::Daemon},
+    hosts::r#impl::base::Host,
+    shared::types::api::ApiResponse,
+};
+use anyhow::{Result, anyhow};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/// Session-authenticated client for the server API
+pub struct ApiClient {
+    client: reqwest::Client,
+    server_url: String,
+}
+
+impl ApiClient {
+    pub fn new(server_url: &str) -> Result<Self> {
+        let client = reqwest::Client::builder().cookie_store(true).build()?;
+
+        Ok(Self {
+            client,
+            server_url: server_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Log in with user credentials; the session cookie is kept for later requests
+    pub async fn login(&self, email: &str, password: &str) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/api/auth/login", self.server_url))
+            .json(&json!({ "email": email, "password": password }))
+            .send()
+            .await?;
+
+        let api_response: ApiResponse<serde_json::Value> = response.json().await?;
+
+        if !api_response.success {
+            return Err(anyhow!(
+                "Login failed: {}",
+                api_response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_hosts(&self) -> Result<Vec<Host>> {
+        self.get("/api/hosts").await
+    }
+
+    pub async fn get_daemons(&self) -> Result<Vec<Daemon>> {
+        self.get("/api/daemons").await
+    }
+
+    pub async fn get_active_sessions(&self) -> Result<Vec<DiscoveryUpdatePayload>> {
+        self.get("/api/discovery/active-sessions").await
+    }
+
+    /// Send a config spec to be reconciled, or only planned when `dry_run` is set
+    pub async fn apply_config(&self, spec: String, dry_run: bool) -> Result<ApplyPlan> {
+        let response = self
+            .client
+            .post(format!("{}/api/apply", self.server_url))
+            .query(&[("dry_run", dry_run)])
+            .header("Content-Type", "application/yaml")
+            .body(spec)
+            .send()
+            .await?;
+
+        Self::into_data(response, "POST /api/apply").await
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.server_url, path))
+            .send()
+            .await?;
+
+        Self::into_data(response, &format!("GET {}", path)).await
+    }
+
+    async fn into_data<T: DeserializeOwned>(
+        response: reqwest::Response,
+        request: &str,
+    ) -> Result<T> {
+        let api_response: ApiResponse<T> = response.json().await?;
+
+        if !api_response.success {
+            return Err(anyhow!(
+                "{} failed: {}",
+                request,
+                api_response
+                    .error
+                    .unwrap_or_else(|| "Unknown error".to_string())
+            ));
+        }
+
+        api_response
+            .data
+            .ok_or_else(|| anyhow!("{} returned no data", request))
+    }
+}
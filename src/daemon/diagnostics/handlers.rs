@@ -0,0 +1,31 @@
+use crate::{
+    daemon::runtime::types::DaemonAppState,
+    server::{
+        diagnostics::r#impl::base::DaemonDiagnosticRequest,
+        shared::types::api::{ApiResponse, ApiResult},
+    },
+};
+use axum::{Router, extract::State, response::Json, routing::post};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<DaemonAppState>> {
+    Router::new().route("/run", post(handle_diagnostic_request))
+}
+
+/// Start a diagnostic in the background; results are reported to the server as they come
+async fn handle_diagnostic_request(
+    State(state): State<Arc<DaemonAppState>>,
+    Json(request): Json<DaemonDiagnosticRequest>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    tracing::info!(
+        "Received {} diagnostic request for {}, run ID {}",
+        request.diagnostic,
+        request.diagnostic.target(),
+        request.run_id
+    );
+
+    let service = state.services.diagnostic_service.clone();
+    tokio::spawn(async move { service.run(request).await });
+
+    Ok(Json(ApiResponse::success(())))
+}
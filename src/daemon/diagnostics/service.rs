@@ -0,0 +1,218 @@
+use crate::{
+    daemon::{
+        shared::storage::ConfigStore,
+        utils::{http::HttpClients, ping::Pinger, traceroute},
+    },
+    server::diagnostics::r#impl::base::{
+        DaemonDiagnosticRequest, Diagnostic, DiagnosticEvent, DiagnosticUpdate,
+    },
+};
+use anyhow::{Result, anyhow, bail};
+use std::{
+    net::IpAddr,
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+    net::{TcpStream, lookup_host},
+    process::Command,
+    time::timeout,
+};
+use uuid::Uuid;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+const PING_INTERVAL: Duration = Duration::from_secs(1);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Upper bound for a whole traceroute, in case the system's tool hangs
+const TRACEROUTE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Runs ping, traceroute and port checks the server asks for, reporting each result as soon
+/// as it's known
+pub struct DaemonDiagnosticService {
+    config_store: Arc<ConfigStore>,
+    client: reqwest::Client,
+}
+
+impl DaemonDiagnosticService {
+    pub fn new(config_store: Arc<ConfigStore>, http: &HttpClients) -> Self {
+        Self {
+            config_store,
+            client: http.server.clone(),
+        }
+    }
+
+    /// Run the diagnostic to the end; the last event reported is always `Finished`
+    pub async fn run(&self, request: DaemonDiagnosticRequest) {
+        let run_id = request.run_id;
+        let error = self.execute(request).await.err().map(|e| e.to_string());
+
+        if let Some(error) = &error {
+            tracing::warn!("Diagnostic {} failed: {}", run_id, error);
+        }
+        if let Err(e) = self
+            .report(run_id, DiagnosticEvent::Finished { error })
+            .await
+        {
+            tracing::warn!("Failed to report end of diagnostic {}: {}", run_id, e);
+        }
+    }
+
+    async fn execute(&self, request: DaemonDiagnosticRequest) -> Result<()> {
+        let run_id = request.run_id;
+        let ip = resolve(request.diagnostic.target()).await?;
+        self.report(run_id, DiagnosticEvent::Resolved { ip })
+            .await?;
+
+        match request.diagnostic {
+            Diagnostic::Ping { count, .. } => self.ping(run_id, ip, count).await,
+            Diagnostic::Traceroute { max_hops, .. } => {
+                timeout(TRACEROUTE_TIMEOUT, self.traceroute(run_id, ip, max_hops))
+                    .await
+                    .map_err(|_| anyhow!("Traceroute timed out"))?
+            }
+            Diagnostic::TcpConnect { port, .. } => self.tcp_connect(run_id, ip, port).await,
+        }
+    }
+
+    async fn ping(&self, run_id: Uuid, ip: IpAddr, count: u16) -> Result<()> {
+        let pinger = Pinger::new()?;
+
+        for sequence in 0..count {
+            let started = Instant::now();
+            let rtt = pinger.ping(ip, PING_TIMEOUT, 1).await;
+            self.report(
+                run_id,
+                DiagnosticEvent::Reply {
+                    sequence,
+                    rtt_ms: rtt.map(millis),
+                },
+            )
+            .await?;
+
+            if sequence + 1 < count {
+                tokio::time::sleep(PING_INTERVAL.saturating_sub(started.elapsed())).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn traceroute(&self, run_id: Uuid, ip: IpAddr, max_hops: u8) -> Result<()> {
+        let (program, args) = traceroute::command(ip, max_hops);
+        let mut child = Command::new(program)
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to run {}: {}", program, e))?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("No output from {}", program))?;
+        let mut lines = BufReader::new(stdout).lines();
+        let mut hops = 0;
+
+        while let Some(line) = lines.next_line().await? {
+            let Some(hop) = traceroute::parse_hop(&line) else {
+                continue;
+            };
+            hops += 1;
+            self.report(
+                run_id,
+                DiagnosticEvent::Hop {
+                    hop: hop.hop,
+                    ip: hop.ip,
+                    rtt_ms: hop.rtt_ms,
+                },
+            )
+            .await?;
+        }
+
+        let status = child.wait().await?;
+        if !status.success() && hops == 0 {
+            let mut stderr = String::new();
+            if let Some(mut pipe) = child.stderr.take() {
+                pipe.read_to_string(&mut stderr).await?;
+            }
+            bail!("{} exited with {}: {}", program, status, stderr.trim());
+        }
+
+        Ok(())
+    }
+
+    async fn tcp_connect(&self, run_id: Uuid, ip: IpAddr, port: u16) -> Result<()> {
+        let started = Instant::now();
+        let event = match timeout(CONNECT_TIMEOUT, TcpStream::connect((ip, port))).await {
+            Ok(Ok(_)) => DiagnosticEvent::Connect {
+                open: true,
+                rtt_ms: Some(millis(started.elapsed())),
+                error: None,
+            },
+            Ok(Err(e)) => DiagnosticEvent::Connect {
+                open: false,
+                rtt_ms: Some(millis(started.elapsed())),
+                error: Some(e.to_string()),
+            },
+            Err(_) => DiagnosticEvent::Connect {
+                open: false,
+                rtt_ms: None,
+                error: Some(format!("No answer within {}s", CONNECT_TIMEOUT.as_secs())),
+            },
+        };
+
+        self.report(run_id, event).await
+    }
+
+    async fn report(&self, run_id: Uuid, event: DiagnosticEvent) -> Result<()> {
+        let server_target = self.config_store.get_server_endpoint().await?;
+        let daemon_id = self.config_store.get_id().await?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow!("API key not set"))?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/api/daemons/{}/diagnostics",
+                server_target, daemon_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&DiagnosticUpdate::new(run_id, event))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            bail!(
+                "Failed to report diagnostic result: HTTP {}",
+                response.status()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Address of the target as this daemon resolves it, which may differ from the server
+async fn resolve(target: &str) -> Result<IpAddr> {
+    let target = target.trim();
+    if let Ok(ip) = target.parse::<IpAddr>() {
+        return Ok(ip);
+    }
+
+    lookup_host((target, 0))
+        .await
+        .map_err(|e| anyhow!("Could not resolve '{}': {}", target, e))?
+        .next()
+        .map(|addr| addr.ip())
+        .ok_or_else(|| anyhow!("'{}' has no addresses", target))
+}
+
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
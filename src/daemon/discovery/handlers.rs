@@ -1,15 +1,30 @@
 use crate::daemon::discovery::manager::DaemonDiscoverySessionManager;
+use crate::daemon::discovery::service::arp::ArpSweepDiscovery;
 use crate::daemon::discovery::service::base::{DiscoveryRunner, RunsDiscovery};
+use crate::daemon::discovery::service::dhcp::DhcpLeaseDiscovery;
 use crate::daemon::discovery::service::docker::DockerScanDiscovery;
 use crate::daemon::discovery::service::network::NetworkScanDiscovery;
 use crate::daemon::discovery::service::self_report::SelfReportDiscovery;
+use crate::daemon::discovery::service::ssdp::SsdpDiscovery;
+use crate::daemon::discovery::service::tailnet::TailnetDiscovery;
+use crate::daemon::discovery::service::vpn::VpnTunnelDiscovery;
 use crate::daemon::runtime::types::DaemonAppState;
 use crate::server::discovery::r#impl::types::DiscoveryType;
+use crate::server::service_definitions::r#impl::{
+    plugins::WasmPlugin, runtime::RuntimeServiceDefinition,
+};
+use crate::server::services::definitions::ServiceDefinitionRegistry;
 use crate::server::{
     daemons::r#impl::api::{DaemonDiscoveryRequest, DaemonDiscoveryResponse},
     shared::types::api::{ApiError, ApiResponse, ApiResult},
 };
-use axum::{Router, extract::State, response::Json, routing::post};
+use anyhow::Context;
+use axum::{
+    Router,
+    extract::State,
+    response::Json,
+    routing::{get, post},
+};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
@@ -18,6 +33,8 @@ pub fn create_router() -> Router<Arc<DaemonAppState>> {
     Router::new()
         .route("/initiate", post(handle_discovery_request))
         .route("/cancel", post(handle_cancel_request))
+        .route("/pause", post(handle_pause_request))
+        .route("/session", get(get_current_session))
 }
 
 async fn handle_discovery_request(
@@ -31,8 +48,39 @@ async fn handle_discovery_request(
         request.session_id
     );
 
+    if request.discovery_type.runs_on_server() {
+        return Err(ApiError::bad_request(&format!(
+            "{} discovery runs on the server, not on daemons",
+            request.discovery_type
+        )));
+    }
+
+    if matches!(request.discovery_type, DiscoveryType::Tailnet) && request.tailnet.is_none() {
+        return Err(ApiError::bad_request(
+            "Tailnet discovery requires the network's Tailscale or Headscale API settings",
+        ));
+    }
+
+    // Match the server's custom definitions and plugins too. Ones this daemon can't load, e.g.
+    // using pattern syntax it doesn't know yet, are skipped rather than failing the whole scan.
+    let custom_definitions = request
+        .service_definitions
+        .iter()
+        .map(|definition| {
+            RuntimeServiceDefinition::new(definition)
+                .with_context(|| format!("Skipping service definition '{}'", definition.name))
+        })
+        .chain(request.plugins.iter().map(|module| {
+            WasmPlugin::new(module.clone())
+                .and_then(|plugin| RuntimeServiceDefinition::from_plugin(Arc::new(plugin)))
+                .with_context(|| format!("Skipping plugin {}", module.name))
+        }))
+        .filter_map(|definition| definition.inspect_err(|e| tracing::warn!("{:#}", e)).ok())
+        .collect();
+    ServiceDefinitionRegistry::replace_runtime_definitions(custom_definitions);
+
     let manager = state.services.discovery_manager.clone();
-    let cancel_token = manager.start_new_session().await;
+    let cancel_token = manager.start_new_session(session_id).await;
 
     let handle = match &request.discovery_type {
         DiscoveryType::SelfReport { host_id } => spawn_discovery(
@@ -61,16 +109,100 @@ async fn handle_discovery_request(
         DiscoveryType::Network {
             subnet_ids,
             host_naming_fallback,
+            shard,
+            ..
+        } => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                NetworkScanDiscovery::new(subnet_ids.clone(), *host_naming_fallback, *shard),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::Ssdp {
+            host_naming_fallback,
         } => spawn_discovery(
             DiscoveryRunner::new(
                 state.services.discovery_service.clone(),
                 state.services.discovery_manager.clone(),
-                NetworkScanDiscovery::new(subnet_ids.clone(), *host_naming_fallback),
+                SsdpDiscovery::new(*host_naming_fallback),
             ),
             request.clone(),
             cancel_token,
             manager.clone(),
         ),
+        DiscoveryType::VpnTunnels { host_id } => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                VpnTunnelDiscovery::new(*host_id),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::ArpSweep {
+            subnet_ids,
+            host_naming_fallback,
+        } => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                ArpSweepDiscovery::new(subnet_ids.clone(), *host_naming_fallback),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::HostRescan {
+            host_id,
+            subnet_id,
+            ip,
+            host_naming_fallback,
+        } => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                NetworkScanDiscovery::rescan(*host_id, *subnet_id, *ip, *host_naming_fallback),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::DhcpLeases { format, path } => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                DhcpLeaseDiscovery::new(*format, path.clone()),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::Tailnet => spawn_discovery(
+            DiscoveryRunner::new(
+                state.services.discovery_service.clone(),
+                state.services.discovery_manager.clone(),
+                TailnetDiscovery::new(
+                    request
+                        .tailnet
+                        .clone()
+                        .expect("Tailnet settings are checked above"),
+                ),
+            ),
+            request.clone(),
+            cancel_token,
+            manager.clone(),
+        ),
+        DiscoveryType::NmapImport { .. }
+        | DiscoveryType::Proxmox { .. }
+        | DiscoveryType::Vmware { .. }
+        | DiscoveryType::Firewall { .. }
+        | DiscoveryType::IpamImport => {
+            unreachable!("Server-side discoveries are rejected above")
+        }
     };
 
     manager.set_current_task(handle).await;
@@ -133,3 +265,40 @@ async fn handle_cancel_request(
         ))
     }
 }
+
+/// Stop a network scan with its progress saved, so a later discovery request for the same
+/// session carries on from there
+async fn handle_pause_request(
+    State(state): State<Arc<DaemonAppState>>,
+    Json(session_id): Json<Uuid>,
+) -> ApiResult<Json<ApiResponse<Uuid>>> {
+    tracing::info!(
+        "Received discovery pause request for session {}",
+        session_id
+    );
+
+    let manager = state.services.discovery_manager.clone();
+
+    if manager.current_session().await != Some(session_id) {
+        return Err(ApiError::conflict(
+            "Discovery session not currently running",
+        ));
+    }
+
+    if manager.pause_current_session().await {
+        Ok(Json(ApiResponse::success(session_id)))
+    } else {
+        Err(ApiError::internal_error(
+            "Failed to pause discovery session",
+        ))
+    }
+}
+
+/// Session currently running on this daemon, so the server can re-sync after a restart
+async fn get_current_session(
+    State(state): State<Arc<DaemonAppState>>,
+) -> ApiResult<Json<ApiResponse<Option<Uuid>>>> {
+    let session_id = state.services.discovery_manager.current_session().await;
+
+    Ok(Json(ApiResponse::success(session_id)))
+}
@@ -2,10 +2,14 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
 
 pub struct DaemonDiscoverySessionManager {
     current_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
     cancellation_token: Arc<RwLock<CancellationToken>>,
+    session_id: Arc<RwLock<Option<Uuid>>>,
+    /// Set when the current session was cancelled to pause it rather than end it
+    pause_requested: Arc<RwLock<bool>>,
 }
 
 impl DaemonDiscoverySessionManager {
@@ -13,6 +17,8 @@ impl DaemonDiscoverySessionManager {
         Self {
             current_task: Arc::new(RwLock::new(None)),
             cancellation_token: Arc::new(RwLock::new(CancellationToken::new())),
+            session_id: Arc::new(RwLock::new(None)),
+            pause_requested: Arc::new(RwLock::new(false)),
         }
     }
 
@@ -35,10 +41,21 @@ impl DaemonDiscoverySessionManager {
         }
     }
 
+    /// ID of the session currently being run, if any
+    pub async fn current_session(&self) -> Option<Uuid> {
+        if self.is_discovery_running().await {
+            *self.session_id.read().await
+        } else {
+            None
+        }
+    }
+
     /// Set the current discovery task for cancellation
-    pub async fn start_new_session(&self) -> CancellationToken {
+    pub async fn start_new_session(&self, session_id: Uuid) -> CancellationToken {
         *self.cancellation_token.write().await = CancellationToken::new();
         *self.current_task.write().await = None;
+        *self.session_id.write().await = Some(session_id);
+        *self.pause_requested.write().await = false;
 
         self.cancellation_token.read().await.clone()
     }
@@ -64,6 +81,25 @@ impl DaemonDiscoverySessionManager {
         true
     }
 
+    /// Stop the current session so it can be resumed later. Scans save their progress when they
+    /// see the cancellation.
+    pub async fn pause_current_session(&self) -> bool {
+        if !self.is_discovery_running().await {
+            return false;
+        }
+
+        tracing::info!("Pausing discovery session...");
+
+        *self.pause_requested.write().await = true;
+        self.cancellation_token.write().await.cancel();
+        true
+    }
+
+    /// Whether the current session is being stopped to be resumed later
+    pub async fn is_pause_requested(&self) -> bool {
+        *self.pause_requested.read().await
+    }
+
     pub async fn token(&self) -> CancellationToken {
         self.cancellation_token.read().await.clone()
     }
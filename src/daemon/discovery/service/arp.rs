@@ -0,0 +1,355 @@
+use crate::daemon::discovery::service::base::{
+    CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
+};
+use crate::daemon::discovery::types::base::DiscoverySessionUpdate;
+use crate::daemon::utils::scanner::{arp_sweep, arp_table_sweep};
+use crate::server::discovery::r#impl::types::{DiscoveryType, HostNamingFallback};
+use crate::server::hosts::r#impl::{
+    interfaces::{Interface, InterfaceBase},
+    liveness::{HostLiveness, LivenessEvidence},
+};
+use crate::server::services::r#impl::base::ServiceMatchBaselineParams;
+use crate::server::subnets::r#impl::types::SubnetTypeDiscriminants;
+use crate::{
+    daemon::utils::base::DaemonUtils,
+    server::{
+        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
+        subnets::r#impl::base::Subnet,
+    },
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use cidr::{IpCidr, Ipv4Cidr};
+use futures::future::try_join_all;
+use mac_address::MacAddress;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::Ordering;
+use strum::IntoDiscriminant;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Subnets larger than this are not swept
+const MIN_SWEEP_PREFIX: u8 = 16;
+
+/// Reading the OS ARP table is one lookup per address, so the fallback is limited further
+const MIN_TABLE_SWEEP_PREFIX: u8 = 22;
+
+/// Finds hosts by ARP reply alone, so devices with every port closed still show up with a MAC
+/// address
+#[derive(Default)]
+pub struct ArpSweepDiscovery {
+    subnet_ids: Option<Vec<Uuid>>,
+    host_naming_fallback: HostNamingFallback,
+}
+
+impl ArpSweepDiscovery {
+    pub fn new(subnet_ids: Option<Vec<Uuid>>, host_naming_fallback: HostNamingFallback) -> Self {
+        Self {
+            subnet_ids,
+            host_naming_fallback,
+        }
+    }
+}
+
+/// Daemon interface a subnet is swept from
+struct SweepSource {
+    subnet: Subnet,
+    cidr: Ipv4Cidr,
+    interface_name: String,
+    ip: Ipv4Addr,
+    mac: Option<MacAddress>,
+}
+
+impl CreatesDiscoveredEntities for DiscoveryRunner<ArpSweepDiscovery> {}
+
+#[async_trait]
+impl RunsDiscovery for DiscoveryRunner<ArpSweepDiscovery> {
+    fn discovery_type(&self) -> DiscoveryType {
+        DiscoveryType::ArpSweep {
+            subnet_ids: None,
+            host_naming_fallback: self.domain.host_naming_fallback,
+        }
+    }
+
+    async fn discover(
+        &self,
+        request: DaemonDiscoveryRequest,
+        cancel: CancellationToken,
+    ) -> Result<(), Error> {
+        let subnets = self.discover_create_subnets().await?;
+        let sources = self.sweep_sources(subnets).await?;
+
+        let total_ips: usize = sources
+            .iter()
+            .map(|s| s.cidr.iter().count().saturating_sub(1))
+            .sum();
+
+        self.start_discovery(total_ips, request).await?;
+
+        let discovery_result = self
+            .sweep_and_process_hosts(sources, cancel.clone())
+            .await
+            .map(|_| ());
+
+        self.finish_discovery(discovery_result, cancel.clone())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DiscoversNetworkedEntities for DiscoveryRunner<ArpSweepDiscovery> {
+    async fn get_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        self.as_ref()
+            .utils
+            .get_own_routing_table_gateway_ips()
+            .await
+    }
+
+    /// ARP only reaches directly attached subnets, so targeted subnets are narrowed down from
+    /// the interfaced ones
+    async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let (_, subnets) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        let subnets: Vec<Subnet> = subnets
+            .into_iter()
+            .filter(|s| {
+                let IpCidr::V4(cidr) = s.base.cidr else {
+                    return false;
+                };
+
+                if cidr.network_length() < MIN_SWEEP_PREFIX {
+                    tracing::warn!(
+                        "Skipping {} with CIDR {}, sweeping would take too long",
+                        s.base.name,
+                        s.base.cidr
+                    );
+                    return false;
+                }
+
+                !matches!(
+                    s.base.subnet_type.discriminant(),
+                    SubnetTypeDiscriminants::DockerBridge | SubnetTypeDiscriminants::VpnTunnel
+                )
+            })
+            .collect();
+
+        let subnet_futures = subnets.iter().map(|subnet| self.create_subnet(subnet));
+        let created = try_join_all(subnet_futures).await?;
+
+        Ok(match &self.domain.subnet_ids {
+            Some(subnet_ids) => created
+                .into_iter()
+                .filter(|s| subnet_ids.contains(&s.id))
+                .collect(),
+            None => created,
+        })
+    }
+}
+
+impl DiscoveryRunner<ArpSweepDiscovery> {
+    async fn sweep_sources(&self, subnets: Vec<Subnet>) -> Result<Vec<SweepSource>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let (interfaces, _) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        Ok(subnets
+            .into_iter()
+            .filter_map(|subnet| {
+                let IpCidr::V4(cidr) = subnet.base.cidr else {
+                    return None;
+                };
+
+                interfaces
+                    .iter()
+                    .find_map(|i| match (i.base.ip_address, &i.base.name) {
+                        (IpAddr::V4(ip), Some(name)) if cidr.contains(&ip) => Some(SweepSource {
+                            subnet: subnet.clone(),
+                            cidr,
+                            interface_name: name.clone(),
+                            ip,
+                            mac: i.base.mac_address,
+                        }),
+                        _ => None,
+                    })
+            })
+            .collect())
+    }
+
+    async fn sweep_and_process_hosts(
+        &self,
+        sources: Vec<SweepSource>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Host>, Error> {
+        let session = self.as_ref().get_session().await?;
+
+        self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
+            .await?;
+
+        let mut last_reported_processed_count: usize = 0;
+        let mut created_hosts = Vec::new();
+
+        for source in sources {
+            let replies = self.sweep(&source, cancel.clone()).await?;
+
+            tracing::info!(
+                "🔍 {} hosts answered ARP on {}",
+                replies.len(),
+                source.subnet.base.cidr
+            );
+
+            session.processed_count.fetch_add(
+                source.cidr.iter().count().saturating_sub(1),
+                Ordering::Relaxed,
+            );
+            last_reported_processed_count = self
+                .periodic_scan_update(last_reported_processed_count)
+                .await?;
+
+            for (ip, mac) in replies {
+                if cancel.is_cancelled() {
+                    return Err(Error::msg("Discovery session was cancelled"));
+                }
+
+                match self
+                    .process_reply(IpAddr::V4(ip), mac, &source.subnet)
+                    .await
+                {
+                    Ok(Some(host)) => created_hosts.push(host),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("Host {} - failed to process ARP reply: {}", ip, e),
+                }
+            }
+        }
+
+        tracing::info!("📊 ARP sweep created {} hosts", created_hosts.len());
+
+        Ok(created_hosts)
+    }
+
+    /// Raw ARP sweep, falling back to the OS ARP table when raw sockets aren't permitted
+    async fn sweep(
+        &self,
+        source: &SweepSource,
+        cancel: CancellationToken,
+    ) -> Result<Vec<(Ipv4Addr, MacAddress)>, Error> {
+        let raw_sweep = match source.mac {
+            Some(mac) => {
+                arp_sweep(
+                    &source.interface_name,
+                    source.ip,
+                    mac,
+                    source.cidr,
+                    cancel.clone(),
+                )
+                .await
+            }
+            None => Err(anyhow::anyhow!(
+                "Interface {} has no MAC address",
+                source.interface_name
+            )),
+        };
+
+        let replies = match raw_sweep {
+            Ok(replies) => replies,
+            Err(_) if cancel.is_cancelled() => {
+                return Err(Error::msg("Discovery session was cancelled"));
+            }
+            Err(e) if source.cidr.network_length() >= MIN_TABLE_SWEEP_PREFIX => {
+                tracing::warn!(
+                    "Raw ARP sweep on {} failed ({}), reading the ARP table instead",
+                    source.cidr,
+                    e
+                );
+                arp_table_sweep(&self.as_ref().utils, source.ip, source.cidr, cancel).await?
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Raw ARP sweep on {} failed ({}), and it is too large to sweep through the ARP table",
+                    source.cidr,
+                    e
+                );
+                Default::default()
+            }
+        };
+
+        Ok(replies.into_iter().collect())
+    }
+
+    async fn process_reply(
+        &self,
+        ip: IpAddr,
+        mac: MacAddress,
+        subnet: &Subnet,
+    ) -> Result<Option<Host>, Error> {
+        let exclusions = self.as_ref().get_session().await?.scan_settings.exclusions;
+        if exclusions.excludes_ip(ip) || exclusions.excludes_mac(&mac) {
+            tracing::debug!("Host {} - skipped, excluded from discovery", ip);
+            return Ok(None);
+        }
+
+        let hostname = self.get_hostname_for_ip(ip).await?;
+
+        let interface = Interface::new(InterfaceBase {
+            name: None,
+            subnet_id: subnet.id,
+            ip_address: ip,
+            mac_address: Some(mac),
+        });
+
+        // No ports are scanned; services can still match on MAC vendor or subnet
+        let Some((mut host, services)) = self
+            .process_host(
+                ServiceMatchBaselineParams {
+                    subnet,
+                    interface: &interface,
+                    all_ports: &Vec::new(),
+                    endpoint_responses: &Vec::new(),
+                    banners: &Vec::new(),
+                    virtualization: &None,
+                    upnp_device: &None,
+                },
+                hostname.clone(),
+                self.domain.host_naming_fallback,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        host.base.liveness = Some(HostLiveness::from_evidence(&LivenessEvidence {
+            arp_reply: true,
+            has_mac: true,
+            has_hostname: hostname.is_some(),
+            ..LivenessEvidence::default()
+        }));
+
+        let (created_host, _) = self.reconcile_host(host, services, false).await?;
+        tracing::info!("✓ Host {} - created from ARP reply", ip);
+
+        Ok(Some(created_host))
+    }
+}
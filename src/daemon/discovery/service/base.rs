@@ -1,6 +1,8 @@
 use std::{
+    collections::HashMap,
     net::IpAddr,
-    sync::{Arc, atomic::AtomicUsize},
+    sync::{Arc, Mutex, MutexGuard, atomic::AtomicUsize},
+    time::Duration,
 };
 
 use crate::{
@@ -8,49 +10,49 @@ use crate::{
         manager::DaemonDiscoverySessionManager, types::base::DiscoveryCriticalError,
     },
     server::{
-        discovery::r#impl::types::{DiscoveryType, HostNamingFallback},
+        discovery::r#impl::{
+            results::DiscoveryResults,
+            types::{DiscoveryType, HostNamingFallback},
+        },
         groups::r#impl::base::Group,
         services::r#impl::{
-            base::{
-                DiscoverySessionServiceMatchParams, ServiceMatchBaselineParams,
-                ServiceMatchServiceParams,
-            },
-            patterns::MatchConfidence,
+            base::ServiceMatchBaselineParams,
+            matching::{DiscoveryContext, discover_host},
         },
-        shared::types::entities::{DiscoveryMetadata, EntitySource},
+        ssh_credentials::r#impl::base::SshLogin,
+        winrm_credentials::r#impl::base::WinrmLogin,
     },
 };
 use anyhow::{Error, anyhow};
 use async_trait::async_trait;
 use chrono::Utc;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time::timeout};
 use tokio_util::sync::CancellationToken;
 
 use uuid::Uuid;
 
 use crate::{
     daemon::{
-        discovery::types::base::{DiscoveryPhase, DiscoverySessionInfo, DiscoverySessionUpdate},
+        discovery::types::base::{
+            DiscoveryCheckpoint, DiscoveryPhase, DiscoverySessionInfo, DiscoverySessionUpdate,
+        },
         shared::storage::ConfigStore,
-        utils::base::{PlatformDaemonUtils, create_system_utils},
+        utils::{
+            base::{PlatformDaemonUtils, create_system_utils},
+            http::HttpClients,
+            scanner::build_scan_client,
+            udp_probes::{UdpProbeConfig, UdpProbeRegistry},
+        },
     },
     server::{
-        daemons::r#impl::api::{DaemonDiscoveryRequest, DiscoveryUpdatePayload},
+        daemons::r#impl::api::{DaemonDiscoveryRequest, DiscoveryUpdatePayload, LastScan},
+        discovery::r#impl::scan_settings::ScanSettings,
         hosts::r#impl::{
-            api::HostWithServicesRequest,
-            base::{Host, HostBase},
-            ports::{Port, PortBase},
-            targets::HostTarget,
-        },
-        services::{
-            definitions::{ServiceDefinitionRegistry, gateway::Gateway},
-            r#impl::{
-                base::Service,
-                bindings::Binding,
-                definitions::{ServiceDefinition, ServiceDefinitionExt},
-            },
+            api::{HostReconcileRequest, HostWithServicesRequest},
+            base::Host,
         },
-        shared::types::{api::ApiResponse, metadata::HasId},
+        services::r#impl::base::Service,
+        shared::types::api::ApiResponse,
         subnets::r#impl::base::Subnet,
     },
 };
@@ -60,15 +62,52 @@ pub struct DiscoverySession {
     pub info: DiscoverySessionInfo,
     pub gateway_ips: Vec<IpAddr>,
     pub processed_count: Arc<AtomicUsize>,
+    /// Hosts created, updated and skipped and errors so far, sent with every update
+    pub results: Arc<Mutex<DiscoveryResults>>,
+    /// HTTP client shared by all endpoint probes in the session, so connections and TLS
+    /// sessions are reused across hosts
+    pub scan_client: reqwest::Client,
+    /// Proxy and TLS config, for clients built per host such as WinRM's
+    pub http: Arc<HttpClients>,
+    /// Ports, timeouts, retries and limits delivered by the server for this session
+    pub scan_settings: ScanSettings,
+    pub udp_probes: Arc<UdpProbeRegistry>,
+    /// Names from the network's synced DNS zones
+    pub dns_names: Arc<HashMap<IpAddr, String>>,
+    /// Logins for reading hosts with SSH open
+    pub ssh_logins: Arc<Vec<SshLogin>>,
+    /// Logins for querying Windows hosts with WinRM open
+    pub winrm_logins: Arc<Vec<WinrmLogin>>,
 }
 
 impl DiscoverySession {
-    pub fn new(info: DiscoverySessionInfo, gateway_ips: Vec<IpAddr>) -> Self {
-        Self {
+    pub fn new(
+        http: Arc<HttpClients>,
+        info: DiscoverySessionInfo,
+        gateway_ips: Vec<IpAddr>,
+        scan_settings: ScanSettings,
+        udp_probes: &[UdpProbeConfig],
+        dns_names: HashMap<IpAddr, String>,
+        ssh_logins: Vec<SshLogin>,
+        winrm_logins: Vec<WinrmLogin>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
             info,
             gateway_ips,
             processed_count: Arc::new(AtomicUsize::new(0)),
-        }
+            results: Arc::new(Mutex::new(DiscoveryResults::default())),
+            scan_client: build_scan_client(&http, &scan_settings)?,
+            http,
+            scan_settings,
+            udp_probes: Arc::new(UdpProbeRegistry::new(udp_probes)),
+            dns_names: Arc::new(dns_names),
+            ssh_logins: Arc::new(ssh_logins),
+            winrm_logins: Arc::new(winrm_logins),
+        })
+    }
+
+    pub fn results(&self) -> MutexGuard<'_, DiscoveryResults> {
+        self.results.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -101,15 +140,17 @@ impl<T> AsRef<DaemonDiscoveryService> for DiscoveryRunner<T> {
 pub struct DaemonDiscoveryService {
     pub config_store: Arc<ConfigStore>,
     pub client: reqwest::Client,
+    pub http: Arc<HttpClients>,
     pub utils: PlatformDaemonUtils,
     pub current_session: Arc<RwLock<Option<DiscoverySession>>>,
 }
 
 impl DaemonDiscoveryService {
-    pub fn new(config_store: Arc<ConfigStore>) -> Self {
+    pub fn new(config_store: Arc<ConfigStore>, http: Arc<HttpClients>) -> Self {
         Self {
             config_store,
-            client: reqwest::Client::new(),
+            client: http.server.clone(),
+            http,
             utils: create_system_utils(),
             current_session: Arc::new(RwLock::new(None)),
         }
@@ -123,6 +164,27 @@ impl DaemonDiscoveryService {
             .cloned()
             .ok_or_else(|| anyhow!("No active discovery session"))
     }
+
+    /// Count a host the server accepted towards the current session's results
+    pub async fn record_reported_host(&self, host: &Host) {
+        if let Some(session) = self.current_session.read().await.as_ref() {
+            session
+                .results()
+                .record_host(host.id, host.created_at, session.info.started_at);
+        }
+    }
+
+    pub async fn record_skipped_host(&self) {
+        if let Some(session) = self.current_session.read().await.as_ref() {
+            session.results().record_skipped();
+        }
+    }
+
+    pub async fn record_host_error(&self, ip: IpAddr, error: impl ToString) {
+        if let Some(session) = self.current_session.read().await.as_ref() {
+            session.results().record_error(ip, error);
+        }
+    }
 }
 
 #[async_trait]
@@ -148,10 +210,12 @@ pub trait RunsDiscovery: AsRef<DaemonDiscoveryService> + Send + Sync {
             .await?
             .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
 
+        let results = session.results().clone();
         let payload = DiscoveryUpdatePayload::from_state_and_update(
             discovery_type,
             session.info.clone(),
             update,
+            results,
         );
 
         let response = self
@@ -189,6 +253,25 @@ pub trait DiscoversNetworkedEntities:
 
     async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error>;
 
+    /// Name from the network's DNS zones, else reverse DNS name for the IP if one resolves
+    /// quickly
+    async fn get_hostname_for_ip(&self, ip: IpAddr) -> Result<Option<String>, Error> {
+        if let Ok(session) = self.as_ref().get_session().await
+            && let Some(name) = session.dns_names.get(&ip)
+        {
+            return Ok(Some(name.clone()));
+        }
+
+        match timeout(Duration::from_millis(800), async {
+            tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip)).await?
+        })
+        .await
+        {
+            Ok(Ok(hostname)) => Ok(Some(hostname)),
+            _ => Ok(None),
+        }
+    }
+
     async fn initialize_discovery_session(
         &self,
         total_to_process: usize,
@@ -216,7 +299,18 @@ pub trait DiscoversNetworkedEntities:
             started_at: Some(Utc::now()),
         };
 
-        let session = DiscoverySession::new(session_info, gateway_ips);
+        let udp_probes = self.as_ref().config_store.get_udp_probes().await?;
+
+        let session = DiscoverySession::new(
+            self.as_ref().http.clone(),
+            session_info,
+            gateway_ips,
+            request.scan_settings,
+            &udp_probes,
+            request.dns_names,
+            request.ssh_credentials,
+            request.winrm_credentials,
+        )?;
 
         let mut current_session = self.as_ref().current_session.write().await;
         *current_session = Some(session);
@@ -251,6 +345,63 @@ pub trait DiscoversNetworkedEntities:
         Ok(())
     }
 
+    /// Start a session again from where it was paused or interrupted, keeping its progress and
+    /// start time
+    async fn resume_discovery(
+        &self,
+        checkpoint: &DiscoveryCheckpoint,
+        request: DaemonDiscoveryRequest,
+    ) -> Result<(), Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+
+        tracing::info!(
+            "Resuming {} discovery session {}, {} addresses left",
+            request.discovery_type,
+            request.session_id,
+            checkpoint.remaining.len()
+        );
+
+        self.initialize_discovery_session(checkpoint.total_to_process, request, daemon_id)
+            .await?;
+
+        if let Some(session) = self.as_ref().current_session.write().await.as_mut() {
+            session.info.started_at = checkpoint.started_at;
+            session
+                .processed_count
+                .store(checkpoint.processed, std::sync::atomic::Ordering::Relaxed);
+            *session.results() = checkpoint.results.clone();
+        }
+
+        self.report_discovery_update(DiscoverySessionUpdate {
+            phase: DiscoveryPhase::Started,
+            processed: checkpoint.processed,
+            error: None,
+            finished_at: None,
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Report the session paused and let go of it. Its checkpoint must already be saved.
+    async fn pause_discovery(&self, processed: usize) -> Result<(), Error> {
+        let session = self.as_ref().get_session().await?;
+        tracing::info!("Discovery session {} paused", session.info.session_id);
+
+        self.report_discovery_update(DiscoverySessionUpdate {
+            phase: DiscoveryPhase::Paused,
+            processed,
+            error: None,
+            finished_at: None,
+        })
+        .await?;
+
+        let mut current_session = self.as_ref().current_session.write().await;
+        *current_session = None;
+
+        Ok(())
+    }
+
     async fn finish_discovery(
         &self,
         discovery_result: Result<(), Error>,
@@ -263,6 +414,25 @@ pub trait DiscoversNetworkedEntities:
             .processed_count
             .load(std::sync::atomic::Ordering::Relaxed);
 
+        // Kept even if the server can't be told below, for the next heartbeat
+        let phase = match &discovery_result {
+            Ok(_) => DiscoveryPhase::Complete,
+            Err(_) if cancel.is_cancelled() => DiscoveryPhase::Cancelled,
+            Err(_) => DiscoveryPhase::Failed,
+        };
+        if let Err(e) = self
+            .as_ref()
+            .config_store
+            .set_last_scan(LastScan {
+                session_id,
+                phase,
+                finished_at: Utc::now(),
+            })
+            .await
+        {
+            tracing::warn!("Failed to save last scan: {}", e);
+        }
+
         match &discovery_result {
             Ok(_) => {
                 tracing::info!("Discovery session {} completed successfully", session_id);
@@ -304,6 +474,14 @@ pub trait DiscoversNetworkedEntities:
 
         let mut current_session = self.as_ref().current_session.write().await;
         *current_session = None;
+        drop(current_session);
+
+        // Nothing left to resume, including any checkpoint of a session that was cancelled
+        // while paused
+        self.as_ref()
+            .config_store
+            .clear_discovery_checkpoint()
+            .await?;
 
         if cancel.is_cancelled() {
             tracing::info!("Discovery session {} was cancelled", session_id);
@@ -320,8 +498,6 @@ pub trait DiscoversNetworkedEntities:
         hostname: Option<String>,
         host_naming_fallback: HostNamingFallback,
     ) -> Result<Option<(Host, Vec<Service>)>, Error> {
-        let ServiceMatchBaselineParams::<'a> { interface, .. } = params;
-
         let daemon_id = self.as_ref().config_store.get_id().await?;
         let network_id = self
             .as_ref()
@@ -331,169 +507,24 @@ pub trait DiscoversNetworkedEntities:
             .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
 
         let session = self.as_ref().get_session().await?;
-        let gateway_ips = session.gateway_ips.clone();
         let discovery_type = self.discovery_type();
 
-        // Create host
-        let mut host = Host::new(HostBase {
-            name: "Unknown Device".to_string(),
-            hostname: hostname.clone(),
-            target: HostTarget::None,
-            network_id,
-            description: None,
-            interfaces: vec![interface.clone()],
-            services: Vec::new(),
-            ports: Vec::new(),
-            source: EntitySource::Discovery {
-                metadata: vec![DiscoveryMetadata::new(discovery_type.clone(), daemon_id)],
-            },
-            virtualization: None,
-            hidden: false,
-        });
-
-        let services = self.discover_services(
-            &mut host,
+        let (host, services) = discover_host(
             &params,
-            &gateway_ips,
-            &daemon_id,
-            &network_id,
-            &discovery_type,
-        )?;
-
-        // Determine host's name
-        let best_service_name = services
-            .iter()
-            .find(|s| !ServiceDefinitionExt::is_generic(&s.base.service_definition))
-            .map(|s| s.base.service_definition.name().to_string());
-
-        if let Some(hostname) = hostname {
-            host.base.name = hostname;
-            if host.base.target == HostTarget::None {
-                host.base.target = HostTarget::Hostname
-            }
-        } else if host_naming_fallback == HostNamingFallback::BestService
-            && let Some(best_service_name) = best_service_name
-        {
-            host.base.name = best_service_name
-        } else if host_naming_fallback == HostNamingFallback::Ip {
-            host.base.name = interface.base.ip_address.to_string()
-        } else if let Some(best_service_name) = best_service_name {
-            host.base.name = best_service_name
-        } else {
-            host.base.name = interface.base.ip_address.to_string()
-        }
+            hostname,
+            host_naming_fallback,
+            &DiscoveryContext {
+                daemon_id: &daemon_id,
+                network_id: &network_id,
+                gateway_ips: &session.gateway_ips,
+                discovery_type: &discovery_type,
+            },
+        );
 
-        tracing::info!("Processed host for ip {}", interface.base.ip_address);
+        tracing::info!("Processed host for ip {}", params.interface.base.ip_address);
         Ok(Some((host, services)))
     }
 
-    fn discover_services(
-        &self,
-        host: &mut Host,
-        baseline_params: &ServiceMatchBaselineParams,
-        gateway_ips: &[IpAddr],
-        daemon_id: &Uuid,
-        network_id: &Uuid,
-        discovery_type: &DiscoveryType,
-    ) -> Result<Vec<Service>, Error> {
-        let ServiceMatchBaselineParams { all_ports, .. } = baseline_params;
-
-        let mut services = Vec::new();
-
-        // Need to track which ports are bound vs open for services to bind to
-        let mut l4_unbound_ports = all_ports.to_vec();
-
-        let mut sorted_service_definitions: Vec<Box<dyn ServiceDefinition>> =
-            ServiceDefinitionRegistry::all_service_definitions()
-                .into_iter()
-                .collect();
-
-        sorted_service_definitions.sort_by_key(|s| {
-            if !ServiceDefinitionExt::is_generic(s) {
-                0 // Highest priority - non-generic services
-            } else if ServiceDefinitionExt::is_generic(s) && s.id() != Gateway.id() {
-                1 // Generic services that aren't Gateway
-            } else {
-                2 // Generic gateways need to go last, as other services may be classified as gateway first
-            }
-        });
-
-        // Add services from detected ports
-        for service_definition in sorted_service_definitions {
-            let service_params = ServiceMatchServiceParams {
-                service_definition,
-                matched_services: &services,
-                unbound_ports: &l4_unbound_ports,
-            };
-
-            let params: DiscoverySessionServiceMatchParams<'_> =
-                DiscoverySessionServiceMatchParams {
-                    service_params,
-                    baseline_params,
-                    daemon_id,
-                    discovery_type,
-                    network_id,
-                    gateway_ips,
-                    host_id: &host.id,
-                };
-
-            if let Some((service, mut result)) = Service::from_discovery(params) {
-                // If there's a endpoint match + host target is hostname or none, use a binding as the host target
-                if let (Some(binding), true) = (
-                    service.base.bindings.iter().find(|b| {
-                        match b {
-                            Binding::Interface { .. } => false,
-                            Binding::Port { port_id, .. } => {
-                                if let Some(port) = host.get_port(port_id) {
-                                    return result
-                                        .endpoint
-                                        .iter()
-                                        .any(|e| e.port_base == port.base);
-                                }
-                                false
-                            }
-                        };
-                        false
-                    }),
-                    matches!(host.base.target, HostTarget::Hostname | HostTarget::None),
-                ) {
-                    host.base.target = HostTarget::ServiceBinding(binding.id())
-                }
-
-                // Add any bound ports to host ports array, remove from open ports
-                let bound_port_bases: Vec<PortBase> = result.ports.iter().map(|p| p.base).collect();
-
-                host.base.ports.append(&mut result.ports);
-
-                // Add new service
-                l4_unbound_ports.retain(|p| !bound_port_bases.contains(p));
-                services.push(service);
-            }
-        }
-
-        services.sort_by_key(|a| {
-            -(match &a.base.source {
-                EntitySource::DiscoveryWithMatch { details, .. } => {
-                    (details.confidence as i32)
-                        + if a.base.service_definition.has_logo() {
-                            1
-                        } else {
-                            0
-                        }
-                }
-                _ => MatchConfidence::NotApplicable as i32,
-            })
-        });
-
-        services.iter().for_each(|s| host.add_service(s.id));
-
-        host.base
-            .ports
-            .extend(l4_unbound_ports.into_iter().map(Port::new));
-
-        Ok(services)
-    }
-
     /// Report discovery progress update periodically
     /// Returns the current processed count for tracking
     async fn periodic_scan_update(
@@ -592,10 +623,67 @@ pub trait CreatesDiscoveredEntities:
             .ok_or_else(|| anyhow::anyhow!("No host data in successful response"))?;
 
         let services = services.unwrap_or(vec![]);
+        self.as_ref().record_reported_host(&host).await;
 
         Ok((host, services))
     }
 
+    /// Like `create_host`, but the server merges the result into the host it describes, found by
+    /// MAC, IP or hostname. `complete_port_scan` tells it ports missing from `host` are closed.
+    async fn reconcile_host(
+        &self,
+        host: Host,
+        services: Vec<Service>,
+        complete_port_scan: bool,
+    ) -> Result<(Host, Vec<Service>), Error> {
+        let server_target = self.as_ref().config_store.get_server_endpoint().await?;
+
+        tracing::info!("Reconciling host {}", host.base.name);
+
+        let api_key = self
+            .as_ref()
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+
+        let response = self
+            .as_ref()
+            .client
+            .post(format!("{}/api/hosts/reconcile", server_target))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&HostReconcileRequest {
+                host,
+                services,
+                complete_port_scan,
+            })
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to reconcile discovered host: HTTP {}",
+                response.status()
+            );
+        }
+
+        let api_response: ApiResponse<HostWithServicesRequest> = response.json().await?;
+
+        if !api_response.success {
+            let error_msg = api_response
+                .error
+                .unwrap_or_else(|| "Unknown error".to_string());
+            anyhow::bail!("Failed to reconcile host: {}", error_msg);
+        }
+
+        let HostWithServicesRequest { host, services } = api_response
+            .data
+            .ok_or_else(|| anyhow::anyhow!("No host data in successful response"))?;
+        self.as_ref().record_reported_host(&host).await;
+
+        Ok((host, services.unwrap_or_default()))
+    }
+
     async fn create_subnet(&self, subnet: &Subnet) -> Result<Subnet, Error> {
         let server_target = self.as_ref().config_store.get_server_endpoint().await?;
 
@@ -0,0 +1,214 @@
+use crate::daemon::discovery::service::base::{
+    CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
+};
+use crate::daemon::discovery::types::base::DiscoverySessionUpdate;
+use crate::server::discovery::r#impl::{
+    dhcp::{self, DhcpLease},
+    types::DiscoveryType,
+};
+use crate::server::hosts::r#impl::{
+    base::HostBase,
+    dhcp::{HostDhcpLease, LeaseFormat},
+    interfaces::{Interface, InterfaceBase},
+};
+use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
+use crate::server::subnets::r#impl::types::SubnetTypeDiscriminants;
+use crate::{
+    daemon::utils::base::DaemonUtils,
+    server::{
+        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
+        subnets::r#impl::base::Subnet,
+    },
+};
+use anyhow::{Error, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future::try_join_all;
+use std::net::IpAddr;
+use std::sync::atomic::Ordering;
+use strum::IntoDiscriminant;
+use tokio_util::sync::CancellationToken;
+
+/// Reads the lease database of a DHCP server running on the daemon host and adds a host for
+/// each active lease in one of the daemon's subnets
+pub struct DhcpLeaseDiscovery {
+    format: LeaseFormat,
+    path: Option<String>,
+}
+
+impl DhcpLeaseDiscovery {
+    pub fn new(format: LeaseFormat, path: Option<String>) -> Self {
+        Self { format, path }
+    }
+}
+
+impl CreatesDiscoveredEntities for DiscoveryRunner<DhcpLeaseDiscovery> {}
+
+#[async_trait]
+impl RunsDiscovery for DiscoveryRunner<DhcpLeaseDiscovery> {
+    fn discovery_type(&self) -> DiscoveryType {
+        DiscoveryType::DhcpLeases {
+            format: self.domain.format,
+            path: self.domain.path.clone(),
+        }
+    }
+
+    async fn discover(
+        &self,
+        request: DaemonDiscoveryRequest,
+        cancel: CancellationToken,
+    ) -> Result<(), Error> {
+        let leases = self.read_leases().await?;
+        let subnets = self.discover_create_subnets().await?;
+
+        self.start_discovery(leases.len(), request).await?;
+
+        let discovery_result = self
+            .process_leases(leases, &subnets, cancel.clone())
+            .await
+            .map(|_| ());
+
+        self.finish_discovery(discovery_result, cancel.clone())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DiscoversNetworkedEntities for DiscoveryRunner<DhcpLeaseDiscovery> {
+    async fn get_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        self.as_ref()
+            .utils
+            .get_own_routing_table_gateway_ips()
+            .await
+    }
+
+    async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow!("Network ID not set"))?;
+
+        let (_, subnets) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        let subnet_futures = subnets
+            .iter()
+            .filter(|s| {
+                !matches!(
+                    s.base.subnet_type.discriminant(),
+                    SubnetTypeDiscriminants::DockerBridge | SubnetTypeDiscriminants::VpnTunnel
+                )
+            })
+            .map(|subnet| self.create_subnet(subnet));
+        try_join_all(subnet_futures).await
+    }
+}
+
+impl DiscoveryRunner<DhcpLeaseDiscovery> {
+    /// Active leases from the configured path, or where the server keeps them by default
+    async fn read_leases(&self) -> Result<Vec<DhcpLease>, Error> {
+        let path = self
+            .domain
+            .path
+            .as_deref()
+            .or(self.domain.format.default_path())
+            .ok_or_else(|| anyhow!("A path is required to read {} leases", self.domain.format))?;
+
+        let text = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| anyhow!("Failed to read {}: {}", path, e))?;
+
+        let now = Utc::now();
+        Ok(dhcp::parse(self.domain.format, &text)?
+            .into_iter()
+            .filter(|lease| lease.is_active(now))
+            .collect())
+    }
+
+    async fn process_leases(
+        &self,
+        leases: Vec<DhcpLease>,
+        subnets: &[Subnet],
+        cancel: CancellationToken,
+    ) -> Result<Vec<Host>, Error> {
+        let session = self.as_ref().get_session().await?;
+
+        self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
+            .await?;
+
+        let mut last_reported_processed_count: usize = 0;
+        let mut created_hosts = Vec::new();
+
+        for lease in &leases {
+            if cancel.is_cancelled() {
+                return Err(Error::msg("Discovery session was cancelled"));
+            }
+
+            match subnets.iter().find(|s| s.base.cidr.contains(&lease.ip)) {
+                Some(subnet) => match self.process_lease(lease, subnet).await {
+                    Ok(host) => created_hosts.push(host),
+                    Err(e) => tracing::warn!("DHCP lease {} - {}", lease.ip, e),
+                },
+                None => tracing::debug!("DHCP lease {} is outside the daemon's subnets", lease.ip),
+            }
+
+            session.processed_count.fetch_add(1, Ordering::Relaxed);
+            last_reported_processed_count = self
+                .periodic_scan_update(last_reported_processed_count)
+                .await?;
+        }
+
+        tracing::info!(
+            "📇 DHCP lease discovery created {} hosts",
+            created_hosts.len()
+        );
+
+        Ok(created_hosts)
+    }
+
+    async fn process_lease(&self, lease: &DhcpLease, subnet: &Subnet) -> Result<Host, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+
+        let host = Host::new(HostBase {
+            name: lease
+                .hostname
+                .clone()
+                .unwrap_or_else(|| lease.ip.to_string()),
+            hostname: lease.hostname.clone(),
+            network_id: subnet.base.network_id,
+            interfaces: vec![Interface::new(InterfaceBase {
+                name: None,
+                subnet_id: subnet.id,
+                ip_address: lease.ip,
+                mac_address: lease.mac,
+            })],
+            source: EntitySource::Discovery {
+                metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
+            },
+            dhcp_lease: Some(HostDhcpLease {
+                format: self.domain.format,
+                client_hostname: lease.hostname.clone(),
+                expires_at: lease.expires_at,
+                collected_at: Utc::now(),
+            }),
+            ..HostBase::default()
+        });
+
+        let (created_host, _) = self.reconcile_host(host, Vec::new(), false).await?;
+        tracing::info!(
+            "✓ DHCP lease {} - created as {}",
+            lease.ip,
+            created_host.base.name
+        );
+
+        Ok(created_host)
+    }
+}
@@ -31,9 +31,10 @@ use crate::server::services::r#impl::virtualization::{
     DockerVirtualization, ServiceVirtualization,
 };
 use crate::server::shared::storage::traits::StorableEntity;
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
 use crate::server::subnets::r#impl::base::{Subnet, SubnetBase};
-use crate::server::subnets::r#impl::types::{SubnetType, SubnetTypeDiscriminants};
+use crate::server::subnets::r#impl::types::{SubnetLayer2, SubnetType, SubnetTypeDiscriminants};
 use crate::{
     daemon::discovery::service::base::{
         CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner,
@@ -267,6 +268,9 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                 )],
                 details: MatchDetails::new_certain("Docker daemon self-report"),
             },
+            versions: Vec::new(),
+            vulnerabilities: Vec::new(),
+            attributes: EntityAttributes::default(),
         });
 
         let mut temp_docker_daemon_host = Host::new(HostBase::default());
@@ -415,8 +419,11 @@ impl DiscoveryRunner<DockerScanDiscovery> {
 
             let port_scan_batch_size = self.as_ref().utils.get_optimal_port_batch_size().await?;
 
+            let scan_client = self.as_ref().get_session().await?.scan_client;
+
             // Scan ports and any endpoints that match open ports
             let endpoint_responses = tokio::spawn(scan_endpoints(
+                scan_client,
                 host_ip,
                 cancel.clone(),
                 Some(open_ports.clone()),
@@ -438,6 +445,7 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                     interface,
                     all_ports: &open_ports,
                     endpoint_responses: &endpoint_responses,
+                    banners: &Vec::new(),
                     virtualization: &Some(ServiceVirtualization::Docker(DockerVirtualization {
                         container_name: container
                             .name
@@ -446,6 +454,7 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                         container_id: container.id.clone(),
                         service_id: **docker_service_id,
                     })),
+                    upnp_device: &None,
                 };
 
                 if let Ok(Some((mut host, services))) = self
@@ -537,6 +546,7 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                         interface,
                         all_ports: container_ports_on_interface,
                         endpoint_responses: &endpoint_responses,
+                        banners: &Vec::new(),
                         virtualization: &Some(ServiceVirtualization::Docker(
                             DockerVirtualization {
                                 container_name: container
@@ -547,6 +557,7 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                                 service_id: **docker_service_id,
                             },
                         )),
+                        upnp_device: &None,
                     },
                     None,
                     self.domain.host_naming_fallback,
@@ -763,6 +774,8 @@ impl DiscoveryRunner<DockerScanDiscovery> {
                                         daemon_id,
                                     )],
                                 },
+                                layer2: SubnetLayer2::default(),
+                                attributes: EntityAttributes::default(),
                             }));
                         }
                         None
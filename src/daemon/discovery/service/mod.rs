@@ -1,4 +1,9 @@
+pub mod arp;
 pub mod base;
+pub mod dhcp;
 pub mod docker;
 pub mod network;
 pub mod self_report;
+pub mod ssdp;
+pub mod tailnet;
+pub mod vpn;
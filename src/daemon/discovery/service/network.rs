@@ -1,21 +1,35 @@
 use crate::daemon::discovery::service::base::{
     CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
 };
-use crate::daemon::discovery::types::base::{DiscoveryCriticalError, DiscoverySessionUpdate};
-use crate::daemon::utils::scanner::scan_ports_and_endpoints;
-use crate::server::discovery::r#impl::types::{DiscoveryType, HostNamingFallback};
+use crate::daemon::discovery::types::base::{
+    DiscoveryCheckpoint, DiscoveryCriticalError, DiscoverySessionUpdate,
+};
+use crate::daemon::utils::ping::Pinger;
+use crate::daemon::utils::rate_limit::{ProbeGate, ScanLimiter};
+use crate::daemon::utils::scanner::{resolve_mac, scan_ports_and_endpoints};
+use crate::daemon::utils::{snmp, ssh, winrm};
+use crate::server::discovery::r#impl::{
+    parallel::ScanShard,
+    types::{DiscoveryType, HostNamingFallback},
+};
 use crate::server::hosts::r#impl::{
     interfaces::{Interface, InterfaceBase},
-    ports::PortBase,
+    liveness::{HostLiveness, LivenessEvidence},
+    os::{HostOs, OsEvidence},
+    ports::{PortBase, TransportProtocol},
 };
 use crate::server::services::r#impl::base::ServiceMatchBaselineParams;
 use crate::server::shared::types::api::ApiResponse;
+use crate::server::ssh_credentials::r#impl::base::SshLogin;
 use crate::server::subnets::r#impl::types::{SubnetType, SubnetTypeDiscriminants};
+use crate::server::winrm_credentials::r#impl::base::WinrmLogin;
 use crate::{
     daemon::utils::base::DaemonUtils,
     server::{
-        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
-        services::r#impl::endpoints::EndpointResponse, subnets::r#impl::base::Subnet,
+        daemons::r#impl::api::DaemonDiscoveryRequest,
+        hosts::r#impl::base::Host,
+        services::r#impl::{banners::PortBanner, endpoints::EndpointResponse},
+        subnets::r#impl::base::Subnet,
     },
 };
 use anyhow::Error;
@@ -25,25 +39,67 @@ use futures::{
     future::try_join_all,
     stream::{self, StreamExt},
 };
+use mac_oui::Oui;
 use std::result::Result::Ok;
-use std::time::Duration;
-use std::{net::IpAddr, sync::Arc};
+use std::{collections::HashSet, net::IpAddr, sync::Arc, time::Duration};
 use strum::IntoDiscriminant;
-use tokio::time::timeout;
 use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// How long to wait for the echo reply whose TTL goes into OS fingerprinting
+const TTL_PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often a running scan saves the addresses it has left, bounding what a daemon restart
+/// makes it scan again
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Default)]
 pub struct NetworkScanDiscovery {
     subnet_ids: Option<Vec<Uuid>>,
     host_naming_fallback: HostNamingFallback,
+    /// The range of each subnet to scan when the scan is split with other daemons
+    shard: Option<ScanShard>,
+    /// Set when rescanning one known host instead of whole subnets
+    target: Option<RescanTarget>,
+}
+
+struct RescanTarget {
+    host_id: Uuid,
+    subnet_id: Uuid,
+    ip: IpAddr,
 }
 
 impl NetworkScanDiscovery {
-    pub fn new(subnet_ids: Option<Vec<Uuid>>, host_naming_fallback: HostNamingFallback) -> Self {
+    pub fn new(
+        subnet_ids: Option<Vec<Uuid>>,
+        host_naming_fallback: HostNamingFallback,
+        shard: Option<ScanShard>,
+    ) -> Self {
         Self {
             subnet_ids,
             host_naming_fallback,
+            shard,
+            target: None,
+        }
+    }
+
+    /// Scan only `ip` and report the result as host `host_id`, so the server updates that host
+    /// rather than matching a new one to it
+    pub fn rescan(
+        host_id: Uuid,
+        subnet_id: Uuid,
+        ip: IpAddr,
+        host_naming_fallback: HostNamingFallback,
+    ) -> Self {
+        Self {
+            subnet_ids: Some(vec![subnet_id]),
+            host_naming_fallback,
+            shard: None,
+            target: Some(RescanTarget {
+                host_id,
+                subnet_id,
+                ip,
+            }),
         }
     }
 }
@@ -53,9 +109,20 @@ impl CreatesDiscoveredEntities for DiscoveryRunner<NetworkScanDiscovery> {}
 #[async_trait]
 impl RunsDiscovery for DiscoveryRunner<NetworkScanDiscovery> {
     fn discovery_type(&self) -> DiscoveryType {
-        DiscoveryType::Network {
-            subnet_ids: None,
-            host_naming_fallback: HostNamingFallback::BestService,
+        match &self.domain.target {
+            Some(target) => DiscoveryType::HostRescan {
+                host_id: target.host_id,
+                subnet_id: target.subnet_id,
+                ip: target.ip,
+                host_naming_fallback: self.domain.host_naming_fallback,
+            },
+            None => DiscoveryType::Network {
+                subnet_ids: None,
+                host_naming_fallback: HostNamingFallback::BestService,
+                scan_profile_id: None,
+                parallel_daemon_ids: Vec::new(),
+                shard: None,
+            },
         }
     }
 
@@ -67,19 +134,58 @@ impl RunsDiscovery for DiscoveryRunner<NetworkScanDiscovery> {
         // Ignore docker bridge subnets, they are discovered through Docker Discovery
         let subnets: Vec<Subnet> = self.discover_create_subnets().await?;
 
-        let total_ips_across_subnets: usize = subnets
-            .iter()
-            .map(|subnet| subnet.base.cidr.iter().count())
-            .sum();
+        let total_ips_across_subnets: usize = match &self.domain.target {
+            Some(_) => 1,
+            None => subnets
+                .iter()
+                .map(|subnet| match &self.domain.shard {
+                    Some(shard) => shard.range(subnet.base.cidr.iter().count()).len(),
+                    None => subnet.base.cidr.iter().count(),
+                })
+                .sum(),
+        };
 
-        self.start_discovery(total_ips_across_subnets, request)
-            .await?;
+        let session_id = request.session_id;
+
+        // A paused or interrupted run of this session carries on with what it hadn't scanned
+        let checkpoint = self
+            .as_ref()
+            .config_store
+            .get_discovery_checkpoint()
+            .await?
+            .filter(|c| c.session_id == session_id && self.domain.target.is_none());
+
+        let remaining = match &checkpoint {
+            Some(checkpoint) => {
+                self.resume_discovery(checkpoint, request).await?;
+                Some(checkpoint.remaining.iter().copied().collect())
+            }
+            None => {
+                self.start_discovery(total_ips_across_subnets, request)
+                    .await?;
+                None
+            }
+        };
 
         let discovery_result = self
-            .scan_and_process_hosts(subnets, cancel.clone())
+            .scan_and_process_hosts(subnets, remaining, cancel.clone())
             .await
             .map(|_| ());
 
+        // Paused scans saved what they had left when they saw the cancellation
+        if discovery_result.is_err()
+            && self.manager.is_pause_requested().await
+            && let Some(checkpoint) = self
+                .as_ref()
+                .config_store
+                .get_discovery_checkpoint()
+                .await?
+                .filter(|c| c.paused && c.session_id == session_id)
+        {
+            self.pause_discovery(checkpoint.processed).await?;
+            return Ok(());
+        }
+
         self.finish_discovery(discovery_result, cancel.clone())
             .await?;
 
@@ -153,46 +259,119 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
     async fn scan_and_process_hosts(
         &self,
         subnets: Vec<Subnet>,
+        remaining: Option<HashSet<IpAddr>>,
         cancel: CancellationToken,
     ) -> Result<Vec<Host>, Error> {
-        let configured_concurrent_scans = self.as_ref().config_store.get_concurrent_scans().await?;
-        let concurrent_scans = self
-            .as_ref()
-            .utils
-            .get_optimal_concurrent_scans(configured_concurrent_scans)
-            .await?;
+        let session = self.as_ref().get_session().await?;
+
+        // Configured concurrency wins over the daemon's own
+        let concurrent_scans = match session.scan_settings.limits.concurrent_scans {
+            Some(concurrent_scans) => concurrent_scans as usize,
+            None => {
+                let configured_concurrent_scans =
+                    self.as_ref().config_store.get_concurrent_scans().await?;
+                self.as_ref()
+                    .utils
+                    .get_optimal_concurrent_scans(configured_concurrent_scans)
+                    .await?
+            }
+        };
 
         tracing::info!(
             "🔍 Starting scan with concurrent_scans={}",
             concurrent_scans
         );
 
-        let session = self.as_ref().get_session().await?;
+        // Hosts and probes are paced by the network's and scan profile's limits, backing off
+        // while the network drops probes
+        let limiter = ScanLimiter::new(&session.scan_settings.limits, concurrent_scans);
+
         let scanned_count = session.processed_count.clone();
+        let ssh_logins = session.ssh_logins.clone();
+        let winrm_logins = session.winrm_logins.clone();
+        let http = session.http.clone();
+
+        // For OS fingerprinting; without them the estimate relies on ports and banners alone
+        let pinger = Pinger::new()
+            .inspect_err(|e| tracing::debug!("ICMP unavailable, skipping TTL probes: {}", e))
+            .ok()
+            .map(Arc::new);
+        let oui = Oui::default().ok().map(Arc::new);
 
         self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
             .await?;
 
-        let all_ips_with_subnets: Vec<(IpAddr, Subnet)> = subnets
-            .iter()
-            .flat_map(|subnet| {
-                self.determine_scan_order(&subnet.base.cidr)
-                    .map(move |ip| (ip, subnet.clone()))
-            })
+        let all_ips_with_subnets: Vec<(IpAddr, Subnet)> = match &self.domain.target {
+            Some(target) => {
+                let subnet = subnets
+                    .iter()
+                    .find(|s| s.id == target.subnet_id && s.base.cidr.contains(&target.ip))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "{} is not in subnet {} on this daemon",
+                            target.ip,
+                            target.subnet_id
+                        )
+                    })?;
+                vec![(target.ip, subnet.clone())]
+            }
+            None => subnets
+                .iter()
+                .flat_map(|subnet| {
+                    self.determine_scan_order(&subnet.base.cidr)
+                        .map(move |ip| (ip, subnet.clone()))
+                })
+                .collect(),
+        };
+
+        let exclusions = &session.scan_settings.exclusions;
+        let total_before_exclusions = all_ips_with_subnets.len();
+        let all_ips_with_subnets: Vec<(IpAddr, Subnet)> = all_ips_with_subnets
+            .into_iter()
+            .filter(|(ip, _)| !exclusions.excludes_ip(*ip))
+            .filter(|(ip, _)| remaining.as_ref().is_none_or(|r| r.contains(ip)))
             .collect();
 
         let total_ips = all_ips_with_subnets.len();
-        tracing::info!("📋 Total IPs to scan: {}", total_ips);
+        tracing::info!(
+            "📋 Total IPs to scan: {} ({} excluded)",
+            total_ips,
+            total_before_exclusions - total_ips
+        );
+
+        // Addresses still to scan, saved as the session's checkpoint
+        let mut pending: HashSet<IpAddr> = all_ips_with_subnets.iter().map(|(ip, _)| *ip).collect();
 
         let results = stream::iter(all_ips_with_subnets)
             .map(|(ip, subnet)| {
                 let cancel = cancel.clone();
                 let subnet = subnet.clone();
                 let scanned_count = scanned_count.clone();
+                let ssh_logins = ssh_logins.clone();
+                let winrm_logins = winrm_logins.clone();
+                let http = http.clone();
+                let pinger = pinger.clone();
+                let oui = oui.clone();
+                let gate = limiter.for_subnet(&subnet.id);
+
+                let scan = async move {
+                    let _permits = gate.host().await;
+
+                    // Devices excluded by MAC, like printers that crash under port scans, are
+                    // only found out by their ARP reply
+                    if exclusions.has_mac_rules()
+                        && !matches!(subnet.base.subnet_type, SubnetType::VpnTunnel)
+                        && let Some(mac) = resolve_mac(&self.as_ref().utils, ip).await?
+                        && exclusions.excludes_mac(&mac)
+                    {
+                        tracing::debug!("Host {} - skipped, MAC {} is excluded", ip, mac);
+                        self.as_ref().record_skipped_host().await;
+                        scanned_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return Ok(None);
+                    }
 
-                async move {
                     match self
-                        .scan_host(ip, scanned_count, cancel, subnet.base.cidr)
+                        .scan_host(ip, scanned_count, cancel, subnet.base.cidr, gate)
                         .await
                     {
                         Ok(None) => {
@@ -203,7 +382,7 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                             tracing::debug!("Host {} - scan error: {}", ip, e);
                             Err(e)
                         }
-                        Ok(Some((all_ports, endpoint_responses))) => {
+                        Ok(Some((mut all_ports, endpoint_responses, banners, mut evidence))) => {
                             tracing::debug!(
                                 "Host {} - found {} ports, {} endpoints",
                                 ip,
@@ -211,12 +390,72 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                                 endpoint_responses.len()
                             );
 
-                            let hostname = self.get_hostname_for_ip(ip).await?;
+                            let ssh = match SshLogin::for_ip(&ssh_logins, ip) {
+                                Some(login)
+                                    if all_ports.contains(&PortBase::new_tcp(login.port)) =>
+                                {
+                                    match ssh::collect(ip, login).await {
+                                        Ok(ssh) => Some(ssh),
+                                        Err(e) => {
+                                            tracing::debug!(
+                                                "Host {} - SSH inventory failed: {}",
+                                                ip,
+                                                e
+                                            );
+                                            None
+                                        }
+                                    }
+                                }
+                                _ => None,
+                            };
+
+                            // TCP listeners a firewall hides from the scan still identify
+                            // services; UDP sockets are too often clients to match on
+                            if let Some(ssh) = &ssh {
+                                for socket in ssh.listening.iter().filter(|s| {
+                                    s.is_exposed() && s.protocol == TransportProtocol::Tcp
+                                }) {
+                                    let port = PortBase::new_tcp(socket.port);
+                                    if !all_ports.contains(&port) {
+                                        all_ports.push(port);
+                                    }
+                                }
+                            }
+
+                            let windows = match WinrmLogin::for_ip(&winrm_logins, ip) {
+                                Some(login)
+                                    if all_ports.contains(&PortBase::new_tcp(login.port)) =>
+                                {
+                                    match winrm::collect(ip, login, &http).await {
+                                        Ok(windows) => Some(windows),
+                                        Err(e) => {
+                                            tracing::debug!(
+                                                "Host {} - WinRM inventory failed: {}",
+                                                ip,
+                                                e
+                                            );
+                                            None
+                                        }
+                                    }
+                                }
+                                _ => None,
+                            };
+
+                            let hostname = match self.get_hostname_for_ip(ip).await? {
+                                Some(hostname) => Some(hostname),
+                                None => ssh
+                                    .as_ref()
+                                    .and_then(|ssh| ssh.hostname.clone())
+                                    .or_else(|| windows.as_ref().and_then(|w| w.hostname.clone())),
+                            };
                             let mac = match subnet.base.subnet_type {
                                 SubnetType::VpnTunnel => None,
                                 _ => self.as_ref().utils.get_mac_address_for_ip(ip).await?,
                             };
 
+                            evidence.has_hostname = hostname.is_some();
+                            evidence.has_mac = mac.is_some();
+
                             let interface = Interface::new(InterfaceBase {
                                 name: None,
                                 subnet_id: subnet.id,
@@ -224,33 +463,90 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                                 mac_address: mac,
                             });
 
-                            if let Ok(Some((host, services))) = self
+                            if let Ok(Some((mut host, services))) = self
                                 .process_host(
                                     ServiceMatchBaselineParams {
                                         subnet: &subnet,
                                         interface: &interface,
                                         all_ports: &all_ports,
                                         endpoint_responses: &endpoint_responses,
+                                        banners: &banners,
                                         virtualization: &None,
+                                        upnp_device: &None,
                                     },
                                     hostname,
                                     self.domain.host_naming_fallback,
                                 )
                                 .await
                             {
+                                let liveness = HostLiveness::from_evidence(&evidence);
                                 tracing::info!(
-                                    "✓ Host {} - processed, {} services matched",
+                                    "✓ Host {} - processed, {} services matched, liveness {}",
                                     ip,
-                                    services.len()
+                                    services.len(),
+                                    liveness.confidence
                                 );
+                                host.base.liveness = Some(liveness);
+
+                                let ttl = match &pinger {
+                                    Some(pinger) => pinger.ttl(ip, TTL_PROBE_TIMEOUT).await,
+                                    None => None,
+                                };
+                                let mac_vendor = match (&oui, mac) {
+                                    (Some(oui), Some(mac)) => {
+                                        Oui::lookup_by_mac(oui, &mac.to_string())
+                                            .ok()
+                                            .flatten()
+                                            .map(|entry| entry.company_name.clone())
+                                    }
+                                    _ => None,
+                                };
+                                host.base.os = HostOs::fingerprint(&OsEvidence {
+                                    ttl,
+                                    ports: &all_ports,
+                                    banners: &banners,
+                                    mac_vendor: mac_vendor.as_deref(),
+                                    ssh: ssh.as_ref(),
+                                    windows: windows.as_ref(),
+                                });
+                                if let Some(os) = &host.base.os {
+                                    tracing::debug!(
+                                        "Host {} - OS {} ({}% confidence)",
+                                        ip,
+                                        os.family,
+                                        os.confidence
+                                    );
+                                }
 
-                                if let Ok((created_host, _)) =
-                                    self.create_host(host, services).await
-                                {
-                                    tracing::info!("✓ Host {} - created successfully", ip);
-                                    return Ok::<Option<Host>, Error>(Some(created_host));
-                                } else {
-                                    tracing::warn!("✗ Host {} - failed to create in database", ip);
+                                host.base.ssh = ssh;
+                                host.base.windows = windows;
+
+                                if all_ports.contains(&PortBase::new_udp(161)) {
+                                    match snmp::walk(ip).await {
+                                        Ok(snmp) => host.base.snmp = Some(snmp),
+                                        Err(e) => {
+                                            tracing::debug!("Host {} - SNMP walk failed: {}", ip, e)
+                                        }
+                                    }
+                                }
+
+                                if let Some(target) = &self.domain.target {
+                                    host.id = target.host_id;
+                                }
+
+                                match self.reconcile_host(host, services, true).await {
+                                    Ok((created_host, _)) => {
+                                        tracing::info!("✓ Host {} - created successfully", ip);
+                                        return Ok::<Option<Host>, Error>(Some(created_host));
+                                    }
+                                    Err(e) => {
+                                        tracing::warn!(
+                                            "✗ Host {} - failed to create in database: {}",
+                                            ip,
+                                            e
+                                        );
+                                        self.as_ref().record_host_error(ip, &e).await;
+                                    }
                                 }
                             } else {
                                 tracing::debug!("Host {} - process_host returned None", ip);
@@ -258,7 +554,9 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                             Ok(None)
                         }
                     }
-                }
+                };
+
+                async move { (ip, scan.await) }
             })
             .buffer_unordered(concurrent_scans);
 
@@ -266,15 +564,23 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
         let mut last_reported_processed_count: usize = 0;
         let mut successful_discoveries = Vec::new();
         let mut scanned = 0;
+        let mut last_checkpoint = std::time::Instant::now();
 
-        while let Some(result) = stream_pin.next().await {
+        while let Some((ip, result)) = stream_pin.next().await {
             scanned += 1;
 
             if cancel.is_cancelled() {
+                // Hosts cut short by the cancellation are still pending, so they're scanned again
+                // on resume
+                if self.manager.is_pause_requested().await {
+                    self.save_checkpoint(&pending, true).await?;
+                }
                 tracing::warn!("Discovery session was cancelled");
                 return Err(Error::msg("Discovery session was cancelled"));
             }
 
+            pending.remove(&ip);
+
             match result {
                 Ok(Some(host)) => successful_discoveries.push(host),
                 Ok(None) => {}
@@ -283,6 +589,7 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                         return Err(e);
                     } else {
                         tracing::warn!("Error during scanning/processing: {}", e);
+                        self.as_ref().record_host_error(ip, &e).await;
                     }
                 }
             }
@@ -290,6 +597,11 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
             last_reported_processed_count = self
                 .periodic_scan_update(last_reported_processed_count)
                 .await?;
+
+            if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                self.save_checkpoint(&pending, false).await?;
+                last_checkpoint = std::time::Instant::now();
+            }
         }
 
         tracing::info!("📊 Scan complete:");
@@ -303,19 +615,61 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
         Ok(successful_discoveries)
     }
 
+    /// Save the addresses left to scan, so the session can resume after a pause or a restart.
+    /// Rescans of a single host aren't worth resuming.
+    async fn save_checkpoint(&self, pending: &HashSet<IpAddr>, paused: bool) -> Result<(), Error> {
+        if self.domain.target.is_some() {
+            return Ok(());
+        }
+
+        let session = self.as_ref().get_session().await?;
+        let total_to_process = session.info.total_to_process;
+        let results = session.results().clone();
+
+        self.as_ref()
+            .config_store
+            .set_discovery_checkpoint(DiscoveryCheckpoint {
+                session_id: session.info.session_id,
+                discovery_type: self.discovery_type(),
+                total_to_process,
+                processed: total_to_process.saturating_sub(pending.len()),
+                started_at: session.info.started_at,
+                remaining: pending.iter().copied().collect(),
+                paused,
+                results,
+            })
+            .await
+    }
+
     pub async fn scan_host(
         &self,
         ip: IpAddr,
         scanned_count: Arc<std::sync::atomic::AtomicUsize>,
         cancel: CancellationToken,
         cidr: IpCidr,
-    ) -> Result<Option<(Vec<PortBase>, Vec<EndpointResponse>)>, Error> {
+        gate: ProbeGate,
+    ) -> Result<
+        Option<(
+            Vec<PortBase>,
+            Vec<EndpointResponse>,
+            Vec<PortBanner>,
+            LivenessEvidence,
+        )>,
+        Error,
+    > {
         // Check cancellation at the start
         if cancel.is_cancelled() {
             return Err(Error::msg("Discovery was cancelled"));
         }
 
-        let port_scan_batch_size = self.as_ref().utils.get_optimal_port_batch_size().await?;
+        let session = self.as_ref().get_session().await?;
+
+        // Kept within the file descriptor limit even when a scan profile asks for more
+        let optimal_batch_size = self.as_ref().utils.get_optimal_port_batch_size().await?;
+        let port_scan_batch_size = match session.scan_settings.limits.port_batch_size {
+            Some(batch_size) => optimal_batch_size.min(batch_size as usize),
+            None => optimal_batch_size,
+        };
 
         let gateway_ips = self
             .as_ref()
@@ -324,10 +678,19 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
             .await?;
 
         // Scan ports and endpoints
-        let scan_result =
-            scan_ports_and_endpoints(ip, cancel.clone(), port_scan_batch_size, cidr, gateway_ips)
-                .await
-                .map_err(|e| anyhow::anyhow!("Scan task panicked: {}", e));
+        let scan_result = scan_ports_and_endpoints(
+            session.scan_client,
+            session.scan_settings,
+            gate,
+            session.udp_probes,
+            ip,
+            cancel.clone(),
+            port_scan_batch_size,
+            cidr,
+            gateway_ips,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Scan task panicked: {}", e));
 
         // Check cancellation after network operation
         if cancel.is_cancelled() {
@@ -336,7 +699,7 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
         }
 
         match scan_result {
-            Ok((open_ports, endpoint_responses)) => {
+            Ok((open_ports, endpoint_responses, banners, evidence)) => {
                 if !open_ports.is_empty() || !endpoint_responses.is_empty() {
                     tracing::info!(
                         "Processing host {} with {} open ports and {} endpoint responses",
@@ -351,7 +714,7 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
                         return Err(Error::msg("Discovery was cancelled"));
                     }
 
-                    Ok(Some((open_ports, endpoint_responses)))
+                    Ok(Some((open_ports, endpoint_responses, banners, evidence)))
                 } else {
                     tracing::debug!("No open ports found on {}", ip);
                     scanned_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -371,21 +734,17 @@ impl DiscoveryRunner<NetworkScanDiscovery> {
         }
     }
 
-    async fn get_hostname_for_ip(&self, ip: IpAddr) -> Result<Option<String>, Error> {
-        match timeout(Duration::from_millis(800), async {
-            tokio::task::spawn_blocking(move || dns_lookup::lookup_addr(&ip)).await?
-        })
-        .await
-        {
-            Ok(Ok(hostname)) => Ok(Some(hostname)),
-            _ => Ok(None),
-        }
-    }
-
     /// Figure out what order to scan IPs in given allocation patterns
     fn determine_scan_order(&self, subnet: &IpCidr) -> impl Iterator<Item = IpAddr> {
         let mut ips: Vec<IpAddr> = subnet.iter().map(|ip| ip.address()).collect();
 
+        // Other daemons scan the rest of the subnet
+        if let Some(shard) = &self.domain.shard {
+            let range = shard.range(ips.len());
+            ips.truncate(range.end);
+            ips.drain(..range.start);
+        }
+
         // Sort by likelihood of being active hosts - highest probability first
         ips.sort_by_key(|ip| {
             let last_octet = match ip {
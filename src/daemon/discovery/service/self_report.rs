@@ -1,3 +1,4 @@
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::{
     daemon::discovery::{
         service::base::{
@@ -11,6 +12,7 @@ use crate::{
         hosts::r#impl::{
             interfaces::{ALL_INTERFACES_IP, Interface},
             ports::{Port, PortBase},
+            service_overrides::HostServiceOverrides,
         },
         services::{
             definitions::netvisor_daemon::NetvisorDaemon,
@@ -90,7 +92,16 @@ impl RunsDiscovery for DiscoveryRunner<SelfReportDiscovery> {
             started_at: Some(Utc::now()),
         };
 
-        let session = DiscoverySession::new(session_info, Vec::new());
+        let session = DiscoverySession::new(
+            self.as_ref().http.clone(),
+            session_info,
+            Vec::new(),
+            request.scan_settings,
+            &[],
+            request.dns_names,
+            Vec::new(),
+            Vec::new(),
+        )?;
         let mut current_session = self.as_ref().current_session.write().await;
         *current_session = Some(session);
         drop(current_session);
@@ -182,6 +193,19 @@ impl RunsDiscovery for DiscoveryRunner<SelfReportDiscovery> {
             },
             hidden: false,
             virtualization: None,
+            liveness: None,
+            snmp: None,
+            ssh: None,
+            windows: None,
+            os: None,
+            service_overrides: HostServiceOverrides::default(),
+            last_seen_at: None,
+            stale: false,
+            reachability: None,
+            vpn_peer: None,
+            device: None,
+            dhcp_lease: None,
+            attributes: EntityAttributes::default(),
         };
 
         let mut host = Host::new(host_base);
@@ -210,6 +234,9 @@ impl RunsDiscovery for DiscoveryRunner<SelfReportDiscovery> {
                 metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
                 details: MatchDetails::new_certain("NetVisor Daemon self-report"),
             },
+            versions: Vec::new(),
+            vulnerabilities: Vec::new(),
+            attributes: EntityAttributes::default(),
         });
 
         services.push(daemon_service);
@@ -245,6 +272,7 @@ impl DiscoveryRunner<SelfReportDiscovery> {
         let capabilities = DaemonCapabilities {
             has_docker_socket,
             interfaced_subnet_ids,
+            features: Some(self.as_ref().utils.get_own_features()),
         };
 
         let api_key = self
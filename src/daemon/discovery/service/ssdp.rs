@@ -0,0 +1,298 @@
+use crate::daemon::discovery::service::base::{
+    CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
+};
+use crate::daemon::discovery::types::base::DiscoverySessionUpdate;
+use crate::daemon::utils::ssdp::{fetch_description, search};
+use crate::server::discovery::r#impl::types::{DiscoveryType, HostNamingFallback};
+use crate::server::hosts::r#impl::{
+    interfaces::{Interface, InterfaceBase},
+    liveness::{HostLiveness, LivenessEvidence},
+    ports::PortBase,
+};
+use crate::server::services::r#impl::{
+    base::ServiceMatchBaselineParams,
+    endpoints::{ApplicationProtocol, Endpoint, EndpointResponse},
+    upnp::UpnpDevice,
+};
+use crate::server::subnets::r#impl::types::SubnetTypeDiscriminants;
+use crate::{
+    daemon::utils::base::DaemonUtils,
+    server::{
+        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
+        subnets::r#impl::base::Subnet,
+    },
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::future::{join_all, try_join_all};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::Ordering;
+use strum::IntoDiscriminant;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// Finds UPnP devices by multicasting an SSDP search on every interfaced subnet and reading
+/// the device description each one announces
+#[derive(Default)]
+pub struct SsdpDiscovery {
+    host_naming_fallback: HostNamingFallback,
+}
+
+impl SsdpDiscovery {
+    pub fn new(host_naming_fallback: HostNamingFallback) -> Self {
+        Self {
+            host_naming_fallback,
+        }
+    }
+}
+
+impl CreatesDiscoveredEntities for DiscoveryRunner<SsdpDiscovery> {}
+
+#[async_trait]
+impl RunsDiscovery for DiscoveryRunner<SsdpDiscovery> {
+    fn discovery_type(&self) -> DiscoveryType {
+        DiscoveryType::Ssdp {
+            host_naming_fallback: self.domain.host_naming_fallback,
+        }
+    }
+
+    async fn discover(
+        &self,
+        request: DaemonDiscoveryRequest,
+        cancel: CancellationToken,
+    ) -> Result<(), Error> {
+        let subnets = self.discover_create_subnets().await?;
+
+        // Devices only answer within a few seconds, so search before the session starts
+        // to know how many there are to process
+        let responders = self.search_subnets(&subnets).await?;
+
+        self.start_discovery(responders.len(), request).await?;
+
+        let discovery_result = self
+            .process_responders(responders, cancel.clone())
+            .await
+            .map(|_| ());
+
+        self.finish_discovery(discovery_result, cancel.clone())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DiscoversNetworkedEntities for DiscoveryRunner<SsdpDiscovery> {
+    async fn get_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        self.as_ref()
+            .utils
+            .get_own_routing_table_gateway_ips()
+            .await
+    }
+
+    async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let (_, subnets) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        // Multicast doesn't cross into docker bridges or VPN tunnels
+        let subnets: Vec<Subnet> = subnets
+            .into_iter()
+            .filter(|s| {
+                !matches!(
+                    s.base.subnet_type.discriminant(),
+                    SubnetTypeDiscriminants::DockerBridge | SubnetTypeDiscriminants::VpnTunnel
+                )
+            })
+            .collect();
+
+        let subnet_futures = subnets.iter().map(|subnet| self.create_subnet(subnet));
+        try_join_all(subnet_futures).await
+    }
+}
+
+impl DiscoveryRunner<SsdpDiscovery> {
+    /// Search from the daemon's own address in each subnet. Returns the description URL and
+    /// subnet of each responding host.
+    async fn search_subnets(
+        &self,
+        subnets: &[Subnet],
+    ) -> Result<HashMap<IpAddr, (String, Subnet)>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let (interfaces, _) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        let searches = subnets.iter().filter_map(|subnet| {
+            let local_ip = interfaces.iter().find_map(|i| match i.base.ip_address {
+                IpAddr::V4(ip) if subnet.base.cidr.contains(&i.base.ip_address) => Some(ip),
+                _ => None,
+            })?;
+
+            Some(async move {
+                match search(local_ip).await {
+                    Ok(locations) => locations
+                        .into_iter()
+                        .filter(|(ip, _)| subnet.base.cidr.contains(ip))
+                        .map(|(ip, location)| (ip, (location, subnet.clone())))
+                        .collect(),
+                    Err(e) => {
+                        tracing::warn!("SSDP search on {} failed: {}", subnet.base.cidr, e);
+                        Vec::new()
+                    }
+                }
+            })
+        });
+
+        let responders: HashMap<IpAddr, (String, Subnet)> =
+            join_all(searches).await.into_iter().flatten().collect();
+
+        tracing::info!("📡 {} hosts answered the SSDP search", responders.len());
+
+        Ok(responders)
+    }
+
+    async fn process_responders(
+        &self,
+        responders: HashMap<IpAddr, (String, Subnet)>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Host>, Error> {
+        let session = self.as_ref().get_session().await?;
+
+        self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
+            .await?;
+
+        let mut last_reported_processed_count: usize = 0;
+        let mut created_hosts = Vec::new();
+
+        for (ip, (location, subnet)) in responders {
+            if cancel.is_cancelled() {
+                return Err(Error::msg("Discovery session was cancelled"));
+            }
+
+            match self
+                .process_device(&session.scan_client, ip, &location, &subnet)
+                .await
+            {
+                Ok(Some(host)) => created_hosts.push(host),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("UPnP device at {} - {}", ip, e),
+            }
+
+            session.processed_count.fetch_add(1, Ordering::Relaxed);
+            last_reported_processed_count = self
+                .periodic_scan_update(last_reported_processed_count)
+                .await?;
+        }
+
+        tracing::info!("📊 SSDP discovery created {} hosts", created_hosts.len());
+
+        Ok(created_hosts)
+    }
+
+    async fn process_device(
+        &self,
+        scan_client: &reqwest::Client,
+        ip: IpAddr,
+        location: &str,
+        subnet: &Subnet,
+    ) -> Result<Option<Host>, Error> {
+        let url = Url::parse(location)?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| anyhow::anyhow!("No port in description URL {}", location))?;
+
+        // Fetched with the scan client so it never goes through the server's proxy
+        let xml = fetch_description(scan_client, location).await?;
+        let device = Some(UpnpDevice::from_description(location, &xml));
+
+        let port_base = PortBase::new_tcp(port);
+        let all_ports = vec![port_base];
+        let endpoint_responses = vec![EndpointResponse {
+            endpoint: Endpoint {
+                protocol: match url.scheme() {
+                    "https" => ApplicationProtocol::Https,
+                    _ => ApplicationProtocol::Http,
+                },
+                ip: Some(ip),
+                port_base,
+                path: url.path().to_string(),
+            },
+            response: xml,
+        }];
+
+        let mac = self.as_ref().utils.get_mac_address_for_ip(ip).await?;
+
+        let has_mac = mac.is_some();
+
+        let interface = Interface::new(InterfaceBase {
+            name: None,
+            subnet_id: subnet.id,
+            ip_address: ip,
+            mac_address: mac,
+        });
+
+        let Some((mut host, services)) = self
+            .process_host(
+                ServiceMatchBaselineParams {
+                    subnet,
+                    interface: &interface,
+                    all_ports: &all_ports,
+                    endpoint_responses: &endpoint_responses,
+                    banners: &Vec::new(),
+                    virtualization: &None,
+                    upnp_device: &device,
+                },
+                None,
+                self.domain.host_naming_fallback,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Devices name themselves better than any fallback
+        if let Some(device) = &device {
+            if let Some(friendly_name) = &device.friendly_name {
+                host.base.name = friendly_name.clone();
+            }
+            host.base.description = device.model();
+        }
+
+        host.base.liveness = Some(HostLiveness::from_evidence(&LivenessEvidence {
+            endpoint_responses: 1,
+            has_mac,
+            ..LivenessEvidence::default()
+        }));
+
+        let (created_host, _) = self.reconcile_host(host, services, false).await?;
+        tracing::info!(
+            "✓ UPnP device {} at {} - created as {}",
+            location,
+            ip,
+            created_host.base.name
+        );
+
+        Ok(Some(created_host))
+    }
+}
@@ -0,0 +1,251 @@
+use crate::daemon::discovery::service::base::{
+    CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
+};
+use crate::daemon::discovery::types::base::DiscoverySessionUpdate;
+use crate::daemon::utils::tailnet::{TailnetDevice, fetch_devices, tailnet_cidr};
+use crate::server::discovery::r#impl::types::DiscoveryType;
+use crate::server::hosts::r#impl::{
+    base::HostBase,
+    interfaces::{Interface, InterfaceBase},
+    vpn::{HostVpnPeer, VpnKind},
+};
+use crate::server::network_settings::r#impl::base::TailnetSettings;
+use crate::server::shared::storage::traits::StorableEntity;
+use crate::server::shared::types::attributes::EntityAttributes;
+use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
+use crate::server::subnets::r#impl::{
+    base::SubnetBase,
+    types::{SubnetLayer2, SubnetType, SubnetTypeDiscriminants},
+};
+use crate::{
+    daemon::utils::base::DaemonUtils,
+    server::{
+        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
+        subnets::r#impl::base::Subnet,
+    },
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use cidr::IpCidr;
+use futures::future::try_join_all;
+use std::net::IpAddr;
+use std::sync::atomic::Ordering;
+use tokio_util::sync::CancellationToken;
+
+/// Lists the devices in a tailnet from the Tailscale or Headscale API and adds them to a
+/// `VpnTunnel` subnet spanning the tailnet's address range
+pub struct TailnetDiscovery {
+    settings: TailnetSettings,
+}
+
+impl TailnetDiscovery {
+    pub fn new(settings: TailnetSettings) -> Self {
+        Self { settings }
+    }
+}
+
+impl CreatesDiscoveredEntities for DiscoveryRunner<TailnetDiscovery> {}
+
+#[async_trait]
+impl RunsDiscovery for DiscoveryRunner<TailnetDiscovery> {
+    fn discovery_type(&self) -> DiscoveryType {
+        DiscoveryType::Tailnet
+    }
+
+    async fn discover(
+        &self,
+        request: DaemonDiscoveryRequest,
+        cancel: CancellationToken,
+    ) -> Result<(), Error> {
+        let devices = fetch_devices(&self.as_ref().client, &self.domain.settings).await?;
+        let subnets = self.discover_create_subnets().await?;
+
+        self.start_discovery(devices.len(), request).await?;
+
+        let discovery_result = self
+            .process_devices(devices, &subnets, cancel.clone())
+            .await
+            .map(|_| ());
+
+        self.finish_discovery(discovery_result, cancel.clone())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DiscoversNetworkedEntities for DiscoveryRunner<TailnetDiscovery> {
+    async fn get_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        self.as_ref()
+            .utils
+            .get_own_routing_table_gateway_ips()
+            .await
+    }
+
+    /// The tailnet subnet, followed by the daemon's own LAN subnets that devices may also
+    /// have an endpoint in
+    async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let tailnet = Subnet::new(SubnetBase {
+            cidr: tailnet_cidr(),
+            network_id,
+            name: "Tailnet".to_string(),
+            description: Some(format!("{:?} tailnet", self.domain.settings.provider)),
+            subnet_type: SubnetType::VpnTunnel,
+            source: EntitySource::Discovery {
+                metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
+            },
+            layer2: SubnetLayer2::default(),
+            attributes: EntityAttributes::default(),
+        });
+
+        let (_, own_subnets) = self
+            .as_ref()
+            .utils
+            .get_own_interfaces(self.discovery_type(), daemon_id, network_id)
+            .await?;
+
+        let subnets: Vec<Subnet> = std::iter::once(tailnet)
+            .chain(own_subnets.into_iter().filter(|s| {
+                !matches!(
+                    s.base.subnet_type.discriminant(),
+                    SubnetTypeDiscriminants::DockerBridge | SubnetTypeDiscriminants::VpnTunnel
+                )
+            }))
+            .collect();
+
+        let subnet_futures = subnets.iter().map(|subnet| self.create_subnet(subnet));
+        try_join_all(subnet_futures).await
+    }
+}
+
+impl DiscoveryRunner<TailnetDiscovery> {
+    async fn process_devices(
+        &self,
+        devices: Vec<TailnetDevice>,
+        subnets: &[Subnet],
+        cancel: CancellationToken,
+    ) -> Result<Vec<Host>, Error> {
+        let session = self.as_ref().get_session().await?;
+
+        self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
+            .await?;
+
+        let mut last_reported_processed_count: usize = 0;
+        let mut created_hosts = Vec::new();
+
+        for device in &devices {
+            if cancel.is_cancelled() {
+                return Err(Error::msg("Discovery session was cancelled"));
+            }
+
+            match self.process_device(device, subnets).await {
+                Ok(Some(host)) => created_hosts.push(host),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Tailnet device {} - {}", device.hostname, e),
+            }
+
+            session.processed_count.fetch_add(1, Ordering::Relaxed);
+            last_reported_processed_count = self
+                .periodic_scan_update(last_reported_processed_count)
+                .await?;
+        }
+
+        tracing::info!("🔒 Tailnet discovery created {} hosts", created_hosts.len());
+
+        Ok(created_hosts)
+    }
+
+    /// Neither API exposes MAC addresses, so a device is tied to its physical host through its
+    /// hostname and, on Tailscale, any endpoint it reports on one of the daemon's LANs. The
+    /// server merges it into an existing host on either.
+    async fn process_device(
+        &self,
+        device: &TailnetDevice,
+        subnets: &[Subnet],
+    ) -> Result<Option<Host>, Error> {
+        let Some((tailnet, lans)) = subnets.split_first() else {
+            return Ok(None);
+        };
+        let Some(ip) = device
+            .addresses
+            .iter()
+            .find(|ip| tailnet.base.cidr.contains(ip))
+        else {
+            return Ok(None);
+        };
+
+        let mut interfaces = vec![Interface::new(InterfaceBase {
+            name: Some("tailscale0".to_string()),
+            subnet_id: tailnet.id,
+            ip_address: *ip,
+            mac_address: None,
+        })];
+        for endpoint in &device.endpoints {
+            if let Some(lan) = lans.iter().find(|s| s.base.cidr.contains(&endpoint.ip()))
+                && !interfaces
+                    .iter()
+                    .any(|i| i.base.ip_address == endpoint.ip())
+            {
+                interfaces.push(Interface::new(InterfaceBase {
+                    name: None,
+                    subnet_id: lan.id,
+                    ip_address: endpoint.ip(),
+                    mac_address: None,
+                }));
+            }
+        }
+
+        let public_endpoint = device
+            .endpoints
+            .iter()
+            .find(|endpoint| !lans.iter().any(|s| s.base.cidr.contains(&endpoint.ip())))
+            .map(|endpoint| endpoint.to_string());
+
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+
+        let host = Host::new(HostBase {
+            name: device.hostname.clone(),
+            hostname: Some(device.hostname.clone()),
+            network_id: tailnet.base.network_id,
+            description: Some("Tailnet device".to_string()),
+            interfaces,
+            source: EntitySource::Discovery {
+                metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
+            },
+            last_seen_at: device.last_seen,
+            vpn_peer: Some(HostVpnPeer {
+                kind: VpnKind::Tailscale,
+                tunnel_interface: "tailscale0".to_string(),
+                public_key: device.node_key.clone(),
+                endpoint: public_endpoint,
+                allowed_ips: device
+                    .addresses
+                    .iter()
+                    .map(|ip| IpCidr::new_host(*ip))
+                    .collect(),
+                latest_handshake: device.last_seen,
+                collected_at: Utc::now(),
+            }),
+            ..HostBase::default()
+        });
+
+        let (created_host, _) = self.reconcile_host(host, Vec::new(), false).await?;
+        tracing::info!(
+            "✓ Tailnet device {} - created as {}",
+            ip,
+            created_host.base.name
+        );
+
+        Ok(Some(created_host))
+    }
+}
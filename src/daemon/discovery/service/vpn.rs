@@ -0,0 +1,309 @@
+use crate::daemon::discovery::service::base::{
+    CreatesDiscoveredEntities, DiscoversNetworkedEntities, DiscoveryRunner, RunsDiscovery,
+};
+use crate::daemon::discovery::types::base::DiscoverySessionUpdate;
+use crate::daemon::utils::tailnet::tailnet_cidr;
+use crate::daemon::utils::vpn::{VpnPeer, VpnTunnel, read_tunnels};
+use crate::server::discovery::r#impl::types::DiscoveryType;
+use crate::server::hosts::r#impl::{
+    base::HostBase,
+    interfaces::{Interface, InterfaceBase},
+    vpn::{HostVpnPeer, VpnKind},
+};
+use crate::server::shared::storage::traits::StorableEntity;
+use crate::server::shared::types::attributes::EntityAttributes;
+use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
+use crate::server::subnets::r#impl::{
+    base::SubnetBase,
+    types::{SubnetLayer2, SubnetType},
+};
+use crate::{
+    daemon::utils::base::DaemonUtils,
+    server::{
+        daemons::r#impl::api::DaemonDiscoveryRequest, hosts::r#impl::base::Host,
+        subnets::r#impl::base::Subnet,
+    },
+};
+use anyhow::Error;
+use async_trait::async_trait;
+use chrono::Utc;
+use cidr::{IpCidr, Ipv4Cidr};
+use pnet::ipnetwork::IpNetwork;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::atomic::Ordering;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Reads the WireGuard, Tailscale and OpenVPN tunnels on the daemon host and creates a
+/// `VpnTunnel` subnet for each, with a host per peer
+pub struct VpnTunnelDiscovery {
+    host_id: Uuid,
+}
+
+impl VpnTunnelDiscovery {
+    pub fn new(host_id: Uuid) -> Self {
+        Self { host_id }
+    }
+}
+
+impl CreatesDiscoveredEntities for DiscoveryRunner<VpnTunnelDiscovery> {}
+
+#[async_trait]
+impl RunsDiscovery for DiscoveryRunner<VpnTunnelDiscovery> {
+    fn discovery_type(&self) -> DiscoveryType {
+        DiscoveryType::VpnTunnels {
+            host_id: self.domain.host_id,
+        }
+    }
+
+    async fn discover(
+        &self,
+        request: DaemonDiscoveryRequest,
+        cancel: CancellationToken,
+    ) -> Result<(), Error> {
+        let tunnels = self.tunnel_subnets().await?;
+        let total_peers = tunnels.iter().map(|(t, _)| t.peers.len()).sum();
+
+        self.start_discovery(total_peers, request).await?;
+
+        let discovery_result = self
+            .process_tunnels(tunnels, cancel.clone())
+            .await
+            .map(|_| ());
+
+        self.finish_discovery(discovery_result, cancel.clone())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DiscoversNetworkedEntities for DiscoveryRunner<VpnTunnelDiscovery> {
+    async fn get_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
+        self.as_ref()
+            .utils
+            .get_own_routing_table_gateway_ips()
+            .await
+    }
+
+    async fn discover_create_subnets(&self) -> Result<Vec<Subnet>, Error> {
+        Ok(self
+            .tunnel_subnets()
+            .await?
+            .into_iter()
+            .map(|(_, subnet)| subnet)
+            .collect())
+    }
+}
+
+impl DiscoveryRunner<VpnTunnelDiscovery> {
+    /// Each tunnel with at least one peer, with its interface resolved and its subnet created
+    async fn tunnel_subnets(&self) -> Result<Vec<(VpnTunnel, Subnet)>, Error> {
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = self
+            .as_ref()
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+
+        let local_networks: Vec<(String, IpNetwork)> = pnet::datalink::interfaces()
+            .into_iter()
+            .flat_map(|i| i.ips.into_iter().map(move |ip| (i.name.clone(), ip)))
+            .collect();
+
+        let mut tunnel_subnets = Vec::new();
+        for mut tunnel in read_tunnels().await {
+            if tunnel.peers.is_empty() {
+                continue;
+            }
+
+            let local = local_network(&tunnel, &local_networks);
+            if tunnel.interface.is_none() {
+                tunnel.interface = local.map(|(name, _)| name.clone());
+            }
+
+            let Some(cidr) = tunnel_cidr(&tunnel, local.map(|(_, network)| network)) else {
+                tracing::debug!("No IPv4 address found for {} tunnel", tunnel.kind);
+                continue;
+            };
+
+            let interface_name = tunnel
+                .interface
+                .clone()
+                .unwrap_or_else(|| tunnel.kind.to_string());
+
+            let subnet = Subnet::new(SubnetBase {
+                cidr,
+                network_id,
+                name: format!("{} {}", tunnel.kind, interface_name),
+                description: Some(format!("{} tunnel on the daemon host", tunnel.kind)),
+                subnet_type: SubnetType::VpnTunnel,
+                source: EntitySource::Discovery {
+                    metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
+                },
+                layer2: SubnetLayer2::default(),
+                attributes: EntityAttributes::default(),
+            });
+
+            let created = self.create_subnet(&subnet).await?;
+            tunnel_subnets.push((tunnel, created));
+        }
+
+        Ok(tunnel_subnets)
+    }
+
+    async fn process_tunnels(
+        &self,
+        tunnels: Vec<(VpnTunnel, Subnet)>,
+        cancel: CancellationToken,
+    ) -> Result<Vec<Host>, Error> {
+        let session = self.as_ref().get_session().await?;
+
+        self.report_discovery_update(DiscoverySessionUpdate::scanning(0))
+            .await?;
+
+        let mut last_reported_processed_count: usize = 0;
+        let mut created_hosts = Vec::new();
+
+        for (tunnel, subnet) in &tunnels {
+            for peer in &tunnel.peers {
+                if cancel.is_cancelled() {
+                    return Err(Error::msg("Discovery session was cancelled"));
+                }
+
+                match self.process_peer(tunnel, peer, subnet).await {
+                    Ok(Some(host)) => created_hosts.push(host),
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!("{} peer {:?} - {}", tunnel.kind, peer.name, e),
+                }
+
+                session.processed_count.fetch_add(1, Ordering::Relaxed);
+                last_reported_processed_count = self
+                    .periodic_scan_update(last_reported_processed_count)
+                    .await?;
+            }
+        }
+
+        tracing::info!("🔒 VPN discovery created {} hosts", created_hosts.len());
+
+        Ok(created_hosts)
+    }
+
+    async fn process_peer(
+        &self,
+        tunnel: &VpnTunnel,
+        peer: &VpnPeer,
+        subnet: &Subnet,
+    ) -> Result<Option<Host>, Error> {
+        // Site-to-site peers may only route whole networks; there's no address to place them at
+        let Some(ip) = peer
+            .tunnel_ips
+            .iter()
+            .find(|ip| subnet.base.cidr.contains(ip))
+        else {
+            return Ok(None);
+        };
+
+        let daemon_id = self.as_ref().config_store.get_id().await?;
+        let network_id = subnet.base.network_id;
+
+        let host = Host::new(HostBase {
+            name: peer.name.clone().unwrap_or_else(|| ip.to_string()),
+            // OpenVPN common names and WireGuard comments aren't hostnames
+            hostname: match tunnel.kind {
+                VpnKind::Tailscale => peer.name.clone(),
+                _ => None,
+            },
+            network_id,
+            description: Some(format!("{} peer", tunnel.kind)),
+            interfaces: vec![Interface::new(InterfaceBase {
+                name: None,
+                subnet_id: subnet.id,
+                ip_address: *ip,
+                mac_address: None,
+            })],
+            source: EntitySource::Discovery {
+                metadata: vec![DiscoveryMetadata::new(self.discovery_type(), daemon_id)],
+            },
+            last_seen_at: peer.latest_handshake,
+            vpn_peer: Some(HostVpnPeer {
+                kind: tunnel.kind,
+                tunnel_interface: tunnel
+                    .interface
+                    .clone()
+                    .unwrap_or_else(|| tunnel.kind.to_string()),
+                public_key: peer.public_key.clone(),
+                endpoint: peer.endpoint.clone(),
+                allowed_ips: peer.allowed_ips.clone(),
+                latest_handshake: peer.latest_handshake,
+                collected_at: Utc::now(),
+            }),
+            ..HostBase::default()
+        });
+
+        let (created_host, _) = self.reconcile_host(host, Vec::new(), false).await?;
+        tracing::info!(
+            "✓ {} peer {} - created as {}",
+            tunnel.kind,
+            ip,
+            created_host.base.name
+        );
+
+        Ok(Some(created_host))
+    }
+}
+
+/// The daemon's own address on the tunnel, by interface name or, for OpenVPN, the interface
+/// whose network holds the peers
+fn local_network<'a>(
+    tunnel: &VpnTunnel,
+    local_networks: &'a [(String, IpNetwork)],
+) -> Option<&'a (String, IpNetwork)> {
+    local_networks
+        .iter()
+        .filter(|(_, network)| network.is_ipv4())
+        .find(|(name, network)| match &tunnel.interface {
+            Some(interface) => name == interface,
+            None => tunnel
+                .peers
+                .iter()
+                .flat_map(|p| &p.tunnel_ips)
+                .any(|ip| network.prefix() < 32 && network.contains(*ip)),
+        })
+}
+
+/// Tailnets span the CGNAT range. Other tunnels use the daemon's network on them, widened to
+/// a /24 around a lone address like `Subnet::from_discovery` does for VPN tunnels.
+fn tunnel_cidr(tunnel: &VpnTunnel, local: Option<&IpNetwork>) -> Option<IpCidr> {
+    if tunnel.kind == VpnKind::Tailscale {
+        return Some(tailnet_cidr());
+    }
+
+    let (ip, prefix) = match local {
+        Some(IpNetwork::V4(network)) if network.prefix() < 32 => {
+            (network.network(), network.prefix())
+        }
+        Some(IpNetwork::V4(network)) => (network.ip(), 24),
+        _ => {
+            let ip = tunnel
+                .peers
+                .iter()
+                .flat_map(|p| &p.tunnel_ips)
+                .find_map(|ip| match ip {
+                    IpAddr::V4(ip) => Some(*ip),
+                    IpAddr::V6(_) => None,
+                })?;
+            (ip, 24)
+        }
+    };
+
+    let network = if prefix == 24 {
+        let [a, b, c, _] = ip.octets();
+        Ipv4Addr::new(a, b, c, 0)
+    } else {
+        ip
+    };
+    Some(IpCidr::V4(Ipv4Cidr::new(network, prefix).ok()?))
+}
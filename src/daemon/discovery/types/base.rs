@@ -1,15 +1,19 @@
-use std::fmt::Display;
+use std::{fmt::Display, net::IpAddr};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::server::discovery::r#impl::{results::DiscoveryResults, types::DiscoveryType};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Copy)]
 pub enum DiscoveryPhase {
     Pending, // Initial state, set by server; all subsequent states until Finished are set by Daemon
     Starting,
     Started,
     Scanning,
+    /// Stopped part way with its progress saved on the daemon; resuming scans what's left
+    Paused,
     Complete,
     Failed,
     Cancelled,
@@ -32,6 +36,23 @@ pub struct DiscoverySessionUpdate {
     pub finished_at: Option<DateTime<Utc>>,
 }
 
+/// Where a network scan left off, saved by the daemon so it can pick up from there after being
+/// paused or after the daemon restarts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryCheckpoint {
+    pub session_id: Uuid,
+    pub discovery_type: DiscoveryType,
+    pub total_to_process: usize,
+    pub processed: usize,
+    pub started_at: Option<DateTime<Utc>>,
+    /// Addresses not scanned yet
+    pub remaining: Vec<IpAddr>,
+    /// Paused on request, rather than cut short by the daemon stopping
+    pub paused: bool,
+    #[serde(default)]
+    pub results: DiscoveryResults,
+}
+
 impl DiscoverySessionUpdate {
     pub fn scanning(processed: usize) -> Self {
         Self {
@@ -43,6 +64,16 @@ impl DiscoverySessionUpdate {
     }
 }
 
+impl DiscoveryPhase {
+    /// Complete, failed or cancelled; no further updates follow
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DiscoveryPhase::Complete | DiscoveryPhase::Failed | DiscoveryPhase::Cancelled
+        )
+    }
+}
+
 impl std::fmt::Display for DiscoveryPhase {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -52,6 +83,7 @@ impl std::fmt::Display for DiscoveryPhase {
             DiscoveryPhase::Starting => write!(f, "Sending session to daemon"),
             DiscoveryPhase::Started => write!(f, "Session started in daemon"),
             DiscoveryPhase::Scanning => write!(f, "Scanning for active hosts"),
+            DiscoveryPhase::Paused => write!(f, "Discovery paused"),
             DiscoveryPhase::Complete => write!(f, "Discovery complete"),
             DiscoveryPhase::Cancelled => write!(f, "Discovery cancelled"),
             DiscoveryPhase::Failed => write!(f, "Discovery failed"),
@@ -1,3 +1,4 @@
+pub mod diagnostics;
 pub mod discovery;
 pub mod runtime;
 pub mod shared;
@@ -1,6 +1,14 @@
+use crate::daemon::discovery::types::base::DiscoveryPhase;
 use crate::daemon::utils::base::DaemonUtils;
 use crate::daemon::utils::base::{PlatformDaemonUtils, create_system_utils};
-use crate::server::daemons::r#impl::api::DaemonCapabilities;
+use crate::daemon::utils::health::{health_check_client, run_check};
+use crate::daemon::utils::http::HttpClients;
+use crate::daemon::utils::ping::Pinger;
+use crate::server::daemons::r#impl::api::{
+    DaemonCapabilities, DaemonHeartbeat, DiscoveryUpdatePayload,
+};
+use crate::server::daemons::r#impl::api::{MonitorTarget, ReachabilityChange};
+use crate::server::monitoring::r#impl::api::{HealthCheckReport, HealthCheckTarget};
 use crate::{
     daemon::shared::storage::ConfigStore,
     server::{
@@ -9,22 +17,51 @@ use crate::{
     },
 };
 use anyhow::Result;
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
 use std::net::IpAddr;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use uuid::Uuid;
 
+/// How long to wait for each echo reply
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Echo requests sent before a host counts as down, so one lost packet doesn't flap it
+const PING_ATTEMPTS: u16 = 2;
+
+const PING_CONCURRENCY: usize = 64;
+
+/// How often due health checks are looked for
+const HEALTH_CHECK_TICK: Duration = Duration::from_secs(5);
+
+/// How often the list of health checks is fetched from the server
+const HEALTH_CHECK_REFRESH: Duration = Duration::from_secs(60);
+
+const HEALTH_CHECK_CONCURRENCY: usize = 32;
+
+/// Results kept while the server can't be reached; the oldest are dropped beyond this
+const MAX_PENDING_HEALTH_REPORTS: usize = 1000;
+
 pub struct DaemonRuntimeService {
     pub config_store: Arc<ConfigStore>,
     pub client: reqwest::Client,
     pub utils: PlatformDaemonUtils,
+    http: Arc<HttpClients>,
+    started_at: Instant,
 }
 
 impl DaemonRuntimeService {
-    pub fn new(config_store: Arc<ConfigStore>) -> Self {
+    pub fn new(config_store: Arc<ConfigStore>, http: Arc<HttpClients>) -> Self {
         Self {
             config_store,
-            client: reqwest::Client::new(),
+            client: http.server.clone(),
             utils: create_system_utils(),
+            http,
+            started_at: Instant::now(),
         }
     }
 
@@ -46,6 +83,13 @@ impl DaemonRuntimeService {
             interval_timer.tick().await;
 
             if self.config_store.get_network_id().await?.is_some() {
+                let heartbeat = DaemonHeartbeat {
+                    version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                    uptime_secs: self.started_at.elapsed().as_secs(),
+                    last_scan: self.config_store.get_last_scan().await?,
+                    resources: self.utils.get_resource_usage(),
+                };
+
                 let response = self
                     .client
                     .post(format!(
@@ -53,6 +97,7 @@ impl DaemonRuntimeService {
                         server_target, daemon_id
                     ))
                     .header("Authorization", format!("Bearer {}", api_key))
+                    .json(&heartbeat)
                     .send()
                     .await?;
 
@@ -78,6 +123,326 @@ impl DaemonRuntimeService {
         }
     }
 
+    /// Ping known hosts on the daemon's subnets every `ping_interval` seconds and report the ones
+    /// that went up or down since the last round. Runs alongside discovery, not as part of it.
+    pub async fn monitor_reachability(&self) -> Result<()> {
+        let interval = self.config_store.get_ping_interval().await?;
+        if interval == 0 {
+            tracing::info!("Ping monitor disabled");
+            return Ok(());
+        }
+
+        let pinger = Arc::new(Pinger::new()?);
+        let mut interval_timer = tokio::time::interval(Duration::from_secs(interval));
+        interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        // Reachability the server last accepted, so only changes are sent
+        let mut last_reported: HashMap<MonitorTarget, bool> = HashMap::new();
+
+        loop {
+            interval_timer.tick().await;
+
+            if self.config_store.get_network_id().await?.is_none()
+                || self.config_store.get_api_key().await?.is_none()
+            {
+                continue;
+            }
+
+            let targets = match self.get_monitor_targets().await {
+                Ok(targets) => targets,
+                Err(e) => {
+                    tracing::warn!("Failed to get ping monitor targets: {}", e);
+                    continue;
+                }
+            };
+
+            let results: Vec<(MonitorTarget, Option<Duration>)> = stream::iter(targets)
+                .map(|target| {
+                    let pinger = pinger.clone();
+                    async move {
+                        let rtt = pinger.ping(target.ip, PING_TIMEOUT, PING_ATTEMPTS).await;
+                        (target, rtt)
+                    }
+                })
+                .buffer_unordered(PING_CONCURRENCY)
+                .collect()
+                .await;
+
+            let checked_at = Utc::now();
+            let changes: Vec<ReachabilityChange> = results
+                .iter()
+                .filter(|(target, rtt)| last_reported.get(target) != Some(&rtt.is_some()))
+                .map(|(target, rtt)| ReachabilityChange {
+                    host_id: target.host_id,
+                    ip: target.ip,
+                    reachable: rtt.is_some(),
+                    latency_ms: rtt.map(|rtt| rtt.as_millis() as u32),
+                    checked_at,
+                })
+                .collect();
+
+            if !changes.is_empty() {
+                if let Err(e) = self.report_reachability(&changes).await {
+                    // Sent again next round, since last_reported is unchanged
+                    tracing::warn!("Failed to report reachability changes: {}", e);
+                    continue;
+                }
+                tracing::debug!("Reported {} reachability changes", changes.len());
+            }
+
+            last_reported = results
+                .into_iter()
+                .map(|(target, rtt)| (target, rtt.is_some()))
+                .collect();
+        }
+    }
+
+    /// Run the service health checks assigned to this daemon, each at its own interval, and
+    /// report every result
+    pub async fn monitor_health(&self) -> Result<()> {
+        let client = health_check_client(&self.http)?;
+        let mut interval_timer = tokio::time::interval(HEALTH_CHECK_TICK);
+        interval_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        let mut targets: Vec<HealthCheckTarget> = Vec::new();
+        let mut refreshed_at: Option<Instant> = None;
+        let mut next_run: HashMap<Uuid, Instant> = HashMap::new();
+        let mut pending: Vec<HealthCheckReport> = Vec::new();
+
+        loop {
+            interval_timer.tick().await;
+
+            if self.config_store.get_network_id().await?.is_none()
+                || self.config_store.get_api_key().await?.is_none()
+            {
+                continue;
+            }
+
+            if refreshed_at.is_none_or(|at| at.elapsed() >= HEALTH_CHECK_REFRESH) {
+                match self.get_health_checks().await {
+                    Ok(fetched) => {
+                        targets = fetched;
+                        refreshed_at = Some(Instant::now());
+                        next_run.retain(|id, _| targets.iter().any(|t| &t.check_id == id));
+                    }
+                    Err(e) => tracing::warn!("Failed to get health checks: {}", e),
+                }
+            }
+
+            let now = Instant::now();
+            let due: Vec<&HealthCheckTarget> = targets
+                .iter()
+                .filter(|t| next_run.get(&t.check_id).is_none_or(|at| *at <= now))
+                .collect();
+            for target in &due {
+                let interval = Duration::from_secs(target.interval_seconds.into());
+                next_run.insert(target.check_id, now + interval);
+            }
+
+            let reports: Vec<HealthCheckReport> = stream::iter(due)
+                .map(|target| run_check(&client, target))
+                .buffer_unordered(HEALTH_CHECK_CONCURRENCY)
+                .collect()
+                .await;
+            pending.extend(reports);
+
+            if pending.is_empty() {
+                continue;
+            }
+
+            if pending.len() > MAX_PENDING_HEALTH_REPORTS {
+                let overflow = pending.len() - MAX_PENDING_HEALTH_REPORTS;
+                pending.drain(..overflow);
+            }
+
+            match self.report_health_results(&pending).await {
+                Ok(()) => pending.clear(),
+                // Sent again with the next batch
+                Err(e) => tracing::warn!("Failed to report health check results: {}", e),
+            }
+        }
+    }
+
+    async fn get_health_checks(&self) -> Result<Vec<HealthCheckTarget>> {
+        let daemon_id = self.config_store.get_id().await?;
+        let server_target = self.config_store.get_server_endpoint().await?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+
+        let response = self
+            .client
+            .get(format!(
+                "{}/api/daemons/{}/health-checks",
+                server_target, daemon_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status());
+        }
+
+        let api_response: ApiResponse<Vec<HealthCheckTarget>> = response.json().await?;
+
+        api_response
+            .data
+            .ok_or_else(|| anyhow::anyhow!(api_response.error.unwrap_or_default()))
+    }
+
+    async fn report_health_results(&self, reports: &[HealthCheckReport]) -> Result<()> {
+        let daemon_id = self.config_store.get_id().await?;
+        let server_target = self.config_store.get_server_endpoint().await?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/api/daemons/{}/health-results",
+                server_target, daemon_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(reports)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status());
+        }
+
+        Ok(())
+    }
+
+    async fn get_monitor_targets(&self) -> Result<Vec<MonitorTarget>> {
+        let daemon_id = self.config_store.get_id().await?;
+        let server_target = self.config_store.get_server_endpoint().await?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+
+        let response = self
+            .client
+            .get(format!(
+                "{}/api/daemons/{}/monitor-targets",
+                server_target, daemon_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status());
+        }
+
+        let api_response: ApiResponse<Vec<MonitorTarget>> = response.json().await?;
+
+        api_response
+            .data
+            .ok_or_else(|| anyhow::anyhow!(api_response.error.unwrap_or_default()))
+    }
+
+    async fn report_reachability(&self, changes: &[ReachabilityChange]) -> Result<()> {
+        let daemon_id = self.config_store.get_id().await?;
+        let server_target = self.config_store.get_server_endpoint().await?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/api/daemons/{}/reachability",
+                server_target, daemon_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(changes)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("HTTP {}", response.status());
+        }
+
+        Ok(())
+    }
+
+    /// Report a network scan the daemon was running when it last stopped as paused, so it can be
+    /// resumed from its checkpoint instead of left running forever on the server
+    pub async fn report_interrupted_discovery(&self) -> Result<()> {
+        let Some(mut checkpoint) = self.config_store.get_discovery_checkpoint().await? else {
+            return Ok(());
+        };
+        if checkpoint.paused {
+            return Ok(());
+        }
+
+        let network_id = self
+            .config_store
+            .get_network_id()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Network ID not set"))?;
+        let api_key = self
+            .config_store
+            .get_api_key()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("API key not set"))?;
+        let server_target = self.config_store.get_server_endpoint().await?;
+
+        let payload = DiscoveryUpdatePayload {
+            session_id: checkpoint.session_id,
+            daemon_id: self.config_store.get_id().await?,
+            network_id,
+            phase: DiscoveryPhase::Paused,
+            discovery_type: checkpoint.discovery_type.clone(),
+            processed: checkpoint.processed,
+            total_to_process: checkpoint.total_to_process,
+            error: Some("Interrupted by a daemon restart".to_string()),
+            started_at: checkpoint.started_at,
+            finished_at: None,
+            results: checkpoint.results.clone(),
+            group_id: None,
+        };
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/api/discovery/{}/update",
+                server_target, checkpoint.session_id
+            ))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&payload)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to report interrupted discovery session {}: HTTP {}",
+                checkpoint.session_id,
+                response.status()
+            );
+        }
+
+        tracing::info!(
+            "Discovery session {} was interrupted with {} addresses left, reported it paused",
+            checkpoint.session_id,
+            checkpoint.remaining.len()
+        );
+
+        checkpoint.paused = true;
+        self.config_store.set_discovery_checkpoint(checkpoint).await
+    }
+
     /// Initialize daemon services (called immediately or via /initialize endpoint)
     pub async fn initialize_services(&self, network_id: Uuid, api_key: String) -> Result<()> {
         // Ensure network_id is stored
@@ -133,6 +498,7 @@ impl DaemonRuntimeService {
                 capabilities: DaemonCapabilities {
                     has_docker_socket,
                     interfaced_subnet_ids: Vec::new(),
+                    features: Some(self.utils.get_own_features()),
                 },
             };
 
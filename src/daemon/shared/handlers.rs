@@ -1,5 +1,6 @@
 use crate::{
     daemon::{
+        diagnostics::handlers as diagnostic_handlers,
         discovery::handlers as discovery_handlers,
         runtime::types::{DaemonAppState, InitializeDaemonRequest},
     },
@@ -15,6 +16,7 @@ use std::sync::Arc;
 pub fn create_router() -> Router<Arc<DaemonAppState>> {
     Router::new()
         .nest("/api/discovery", discovery_handlers::create_router())
+        .nest("/api/diagnostics", diagnostic_handlers::create_router())
         .route("/api/health", get(get_health))
         .route("/api/initialize", post(initialize))
 }
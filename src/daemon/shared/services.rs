@@ -1,7 +1,9 @@
 use crate::daemon::{
+    diagnostics::service::DaemonDiagnosticService,
     discovery::{manager::DaemonDiscoverySessionManager, service::base::DaemonDiscoveryService},
     runtime::service::DaemonRuntimeService,
     shared::storage::ConfigStore,
+    utils::http::HttpClients,
 };
 use anyhow::Result;
 use std::sync::Arc;
@@ -10,20 +12,24 @@ pub struct DaemonServiceFactory {
     pub discovery_service: Arc<DaemonDiscoveryService>,
     pub discovery_manager: Arc<DaemonDiscoverySessionManager>,
     pub runtime_service: Arc<DaemonRuntimeService>,
+    pub diagnostic_service: Arc<DaemonDiagnosticService>,
 }
 
 impl DaemonServiceFactory {
     pub async fn new(config: Arc<ConfigStore>) -> Result<Self> {
         // Initialize services with proper dependencies
+        let http = Arc::new(HttpClients::from_config(&config.get_config().await)?);
 
-        let discovery_service = Arc::new(DaemonDiscoveryService::new(config.clone()));
+        let discovery_service = Arc::new(DaemonDiscoveryService::new(config.clone(), http.clone()));
         let discovery_manager = Arc::new(DaemonDiscoverySessionManager::new());
-        let runtime_service = Arc::new(DaemonRuntimeService::new(config.clone()));
+        let runtime_service = Arc::new(DaemonRuntimeService::new(config.clone(), http.clone()));
+        let diagnostic_service = Arc::new(DaemonDiagnosticService::new(config.clone(), &http));
 
         Ok(Self {
             discovery_service,
             discovery_manager,
             runtime_service,
+            diagnostic_service,
         })
     }
 }
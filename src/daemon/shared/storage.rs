@@ -1,3 +1,6 @@
+use crate::daemon::discovery::types::base::DiscoveryCheckpoint;
+use crate::daemon::utils::udp_probes::UdpProbeConfig;
+use crate::server::daemons::r#impl::api::LastScan;
 use anyhow::{Context, Error, Result};
 use async_fs;
 use directories_next::ProjectDirs;
@@ -21,9 +24,13 @@ pub struct CliArgs {
     pub bind_address: Option<String>,
     pub log_level: Option<String>,
     pub heartbeat_interval: Option<u64>,
+    pub ping_interval: Option<u64>,
     pub concurrent_scans: Option<usize>,
     pub daemon_api_key: Option<String>,
     pub docker_proxy: Option<String>,
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    pub ca_cert_path: Option<String>,
 }
 
 /// Unified configuration struct that handles both startup and runtime config
@@ -39,8 +46,32 @@ pub struct AppConfig {
     pub name: String,
     pub log_level: String,
     pub heartbeat_interval: u64,
+    /// Seconds between ping monitor rounds; 0 disables the monitor
+    #[serde(default = "default_ping_interval")]
+    pub ping_interval: u64,
     pub bind_address: String,
     pub concurrent_scans: usize,
+    /// Extra UDP probes, overriding built-in probes on the same port
+    #[serde(default)]
+    pub udp_probes: Vec<UdpProbeConfig>,
+
+    // Outbound HTTP
+    /// Proxy for connections to the server and the Tailscale API: http://, https://, socks5://
+    /// or socks5h://. Scanned hosts are always reached directly.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// Hosts that bypass the proxy, comma separated as in `NO_PROXY`
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    /// PEM bundle of CAs trusted in addition to the built-in roots, for an internal CA that
+    /// signed the server's or scanned services' certificates
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    #[serde(default = "default_true")]
+    pub verify_server_tls: bool,
+    /// Off by default since most services on a local network are self-signed
+    #[serde(default)]
+    pub verify_scan_tls: bool,
 
     // Runtime state
     pub id: Uuid,
@@ -48,6 +79,12 @@ pub struct AppConfig {
     pub host_id: Option<Uuid>,
     pub daemon_api_key: Option<String>,
     pub docker_proxy: Option<String>,
+    /// Progress of a paused or interrupted network scan
+    #[serde(default)]
+    pub discovery_checkpoint: Option<DiscoveryCheckpoint>,
+    /// The discovery session finished most recently, reported with heartbeats
+    #[serde(default)]
+    pub last_scan: Option<LastScan>,
 }
 
 impl Default for AppConfig {
@@ -61,16 +98,33 @@ impl Default for AppConfig {
             name: "netvisor-daemon".to_string(),
             log_level: "info".to_string(),
             heartbeat_interval: 30,
+            ping_interval: default_ping_interval(),
             id: Uuid::new_v4(),
             last_heartbeat: None,
             host_id: None,
             daemon_api_key: None,
             concurrent_scans: 15,
+            udp_probes: Vec::new(),
             docker_proxy: None,
+            proxy: None,
+            no_proxy: None,
+            ca_cert_path: None,
+            verify_server_tls: true,
+            verify_scan_tls: false,
+            discovery_checkpoint: None,
+            last_scan: None,
         }
     }
 }
 
+fn default_ping_interval() -> u64 {
+    30
+}
+
+fn default_true() -> bool {
+    true
+}
+
 impl AppConfig {
     pub fn get_config_path() -> Result<(bool, PathBuf)> {
         let proj_dirs = ProjectDirs::from("com", "netvisor", "daemon")
@@ -115,6 +169,9 @@ impl AppConfig {
         if let Some(heartbeat_interval) = cli_args.heartbeat_interval {
             figment = figment.merge(("heartbeat_interval", heartbeat_interval));
         }
+        if let Some(ping_interval) = cli_args.ping_interval {
+            figment = figment.merge(("ping_interval", ping_interval));
+        }
         if let Some(bind_address) = cli_args.bind_address {
             figment = figment.merge(("bind_address", bind_address));
         }
@@ -127,6 +184,15 @@ impl AppConfig {
         if let Some(docker_proxy) = cli_args.docker_proxy {
             figment = figment.merge(("docker_proxy", docker_proxy));
         }
+        if let Some(proxy) = cli_args.proxy {
+            figment = figment.merge(("proxy", proxy));
+        }
+        if let Some(no_proxy) = cli_args.no_proxy {
+            figment = figment.merge(("no_proxy", no_proxy));
+        }
+        if let Some(ca_cert_path) = cli_args.ca_cert_path {
+            figment = figment.merge(("ca_cert_path", ca_cert_path));
+        }
 
         let config: AppConfig = figment
             .extract()
@@ -179,6 +245,8 @@ impl ConfigStore {
         let mut config = self.config.write().await;
         config.id = loaded_config.id;
         config.last_heartbeat = loaded_config.last_heartbeat;
+        config.discovery_checkpoint = loaded_config.discovery_checkpoint;
+        config.last_scan = loaded_config.last_scan;
 
         tracing::info!("Loaded daemon runtime state from {}", self.path.display());
         Ok(())
@@ -282,17 +350,57 @@ impl ConfigStore {
         Ok(config.docker_proxy.clone())
     }
 
+    pub async fn get_udp_probes(&self) -> Result<Vec<UdpProbeConfig>> {
+        let config = self.config.read().await;
+        Ok(config.udp_probes.clone())
+    }
+
     pub async fn get_heartbeat_interval(&self) -> Result<u64> {
         let config = self.config.read().await;
         Ok(config.heartbeat_interval)
     }
 
+    pub async fn get_ping_interval(&self) -> Result<u64> {
+        let config = self.config.read().await;
+        Ok(config.ping_interval)
+    }
+
     pub async fn update_heartbeat(&self) -> Result<()> {
         let mut config = self.config.write().await;
         config.last_heartbeat = Some(chrono::Utc::now());
         self.save(&config.clone()).await
     }
 
+    pub async fn get_discovery_checkpoint(&self) -> Result<Option<DiscoveryCheckpoint>> {
+        let config = self.config.read().await;
+        Ok(config.discovery_checkpoint.clone())
+    }
+
+    pub async fn set_discovery_checkpoint(&self, checkpoint: DiscoveryCheckpoint) -> Result<()> {
+        let mut config = self.config.write().await;
+        config.discovery_checkpoint = Some(checkpoint);
+        self.save(&config.clone()).await
+    }
+
+    pub async fn clear_discovery_checkpoint(&self) -> Result<()> {
+        let mut config = self.config.write().await;
+        if config.discovery_checkpoint.take().is_some() {
+            self.save(&config.clone()).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_last_scan(&self) -> Result<Option<LastScan>> {
+        let config = self.config.read().await;
+        Ok(config.last_scan.clone())
+    }
+
+    pub async fn set_last_scan(&self, last_scan: LastScan) -> Result<()> {
+        let mut config = self.config.write().await;
+        config.last_scan = Some(last_scan);
+        self.save(&config.clone()).await
+    }
+
     pub async fn get_config(&self) -> AppConfig {
         let config = self.config.read().await;
         config.clone()
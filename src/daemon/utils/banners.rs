@@ -0,0 +1,157 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::r#impl::banners::PortBanner;
+use futures::future::join_all;
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+
+/// Banners longer than this are truncated; the first line is what identifies a service
+const MAX_BANNER_LEN: usize = 512;
+
+/// What to do after connecting, for protocols where the client speaks first or the greeting
+/// needs decoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BannerProbe {
+    /// Wait for the server to speak first, as SSH, FTP, POP3 and IMAP servers do
+    Read,
+    /// Read the greeting, then `EHLO` for the extensions list
+    Smtp,
+    /// `PING`, answered with `+PONG` or an auth error
+    Redis,
+    /// Decode the server version from the initial handshake packet
+    Mysql,
+    /// `version`, answered with `VERSION x.y.z`
+    Memcached,
+}
+
+impl BannerProbe {
+    fn for_port(port: u16) -> Self {
+        match port {
+            25 | 587 | 2525 => Self::Smtp,
+            6379 => Self::Redis,
+            3306 => Self::Mysql,
+            11211 => Self::Memcached,
+            _ => Self::Read,
+        }
+    }
+}
+
+/// Grab banners from open TCP ports concurrently. Ports that timed out without sending
+/// anything are left out.
+pub async fn grab_banners(
+    ip: IpAddr,
+    ports: &[PortBase],
+    read_timeout: Duration,
+) -> Vec<PortBanner> {
+    let grabs = ports.iter().map(|port_base| async move {
+        let banner = grab_banner(ip, port_base.number(), read_timeout).await?;
+        tracing::debug!("Banner from {}:{} - {:?}", ip, port_base.number(), banner);
+        Some(PortBanner {
+            port_base: *port_base,
+            banner,
+        })
+    });
+
+    join_all(grabs).await.into_iter().flatten().collect()
+}
+
+async fn grab_banner(ip: IpAddr, port: u16, read_timeout: Duration) -> Option<String> {
+    let mut stream = timeout(read_timeout, TcpStream::connect(SocketAddr::new(ip, port)))
+        .await
+        .ok()?
+        .ok()?;
+
+    let raw = match BannerProbe::for_port(port) {
+        BannerProbe::Read => read_some(&mut stream, read_timeout).await?,
+        BannerProbe::Smtp => {
+            let mut greeting = read_some(&mut stream, read_timeout).await?;
+            if stream.write_all(b"EHLO netvisor\r\n").await.is_ok()
+                && let Some(extensions) = read_some(&mut stream, read_timeout).await
+            {
+                greeting.extend_from_slice(&extensions);
+            }
+            greeting
+        }
+        BannerProbe::Redis => {
+            stream.write_all(b"PING\r\n").await.ok()?;
+            read_some(&mut stream, read_timeout).await?
+        }
+        BannerProbe::Mysql => {
+            let handshake = read_some(&mut stream, read_timeout).await?;
+            return mysql_version(&handshake).map(|v| format!("mysql {}", v));
+        }
+        BannerProbe::Memcached => {
+            stream.write_all(b"version\r\n").await.ok()?;
+            read_some(&mut stream, read_timeout).await?
+        }
+    };
+
+    sanitize(&raw)
+}
+
+async fn read_some(stream: &mut TcpStream, read_timeout: Duration) -> Option<Vec<u8>> {
+    let mut buf = vec![0u8; MAX_BANNER_LEN];
+    match timeout(read_timeout, stream.read(&mut buf)).await {
+        Ok(Ok(len)) if len > 0 => {
+            buf.truncate(len);
+            Some(buf)
+        }
+        _ => None,
+    }
+}
+
+/// Server version from a MySQL/MariaDB initial handshake: a 4-byte packet header, protocol
+/// version 10, then the NUL-terminated version string
+fn mysql_version(handshake: &[u8]) -> Option<String> {
+    let payload = handshake.get(4..)?;
+    let (&protocol_version, rest) = payload.split_first()?;
+    if protocol_version != 10 {
+        return None;
+    }
+
+    let end = rest.iter().position(|b| *b == 0)?;
+    String::from_utf8(rest[..end].to_vec())
+        .ok()
+        .filter(|v| !v.is_empty())
+}
+
+/// Printable text of a banner, keeping line breaks so multi-line greetings stay readable
+fn sanitize(raw: &[u8]) -> Option<String> {
+    let text: String = String::from_utf8_lossy(raw)
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n')
+        .take(MAX_BANNER_LEN)
+        .collect();
+
+    Some(text.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_mysql_handshake_and_sanitizes_banners() {
+        let mut handshake = vec![0x4a, 0x00, 0x00, 0x00, 0x0a];
+        handshake.extend_from_slice(b"8.0.36-0ubuntu0.22.04.1\0");
+        handshake.extend_from_slice(&[0x08, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            mysql_version(&handshake).as_deref(),
+            Some("8.0.36-0ubuntu0.22.04.1")
+        );
+        assert_eq!(mysql_version(b"\x05\x00\x00\x00\xff"), None);
+
+        assert_eq!(
+            sanitize(b"SSH-2.0-OpenSSH_9.6\r\n\x00").as_deref(),
+            Some("SSH-2.0-OpenSSH_9.6")
+        );
+        assert_eq!(sanitize(b"\x00\x01"), None);
+    }
+}
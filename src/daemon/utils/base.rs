@@ -1,6 +1,9 @@
+use crate::server::daemons::r#impl::api::{DaemonFeatures, DaemonResourceUsage};
 use crate::server::discovery::r#impl::types::DiscoveryType;
 use crate::server::hosts::r#impl::interfaces::{Interface, InterfaceBase};
+use crate::server::shared::types::metadata::HasId;
 use crate::server::subnets::r#impl::base::Subnet;
+use crate::server::subnets::r#impl::types::SubnetLayer2;
 use anyhow::Error;
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -10,13 +13,13 @@ use local_ip_address::local_ip;
 use mac_address::MacAddress;
 use net_route::Handle;
 use pnet::ipnetwork::IpNetwork;
+use pnet::packet::ip::IpNextHeaderProtocols;
+use pnet::transport::{TransportChannelType, TransportProtocol, transport_channel};
 use std::collections::HashMap;
 use std::net::IpAddr;
-use std::time::Duration;
+use strum::IntoEnumIterator;
 use uuid::Uuid;
 
-pub const SCAN_TIMEOUT: Duration = Duration::from_millis(800);
-
 /// Cross-platform system utilities trait
 #[async_trait]
 pub trait DaemonUtils {
@@ -41,6 +44,11 @@ pub trait DaemonUtils {
             .map(|os_str| os_str.to_string_lossy().into_owned())
     }
 
+    /// VLAN and bridge configuration of a local interface, on platforms that expose it
+    fn get_interface_layer2(&self, _interface_name: &str) -> SubnetLayer2 {
+        SubnetLayer2::default()
+    }
+
     async fn get_own_interfaces(
         &self,
         discovery_type: DiscoveryType,
@@ -72,13 +80,14 @@ pub trait DaemonUtils {
         let mut subnet_map: HashMap<IpCidr, Subnet> = HashMap::new();
 
         for (interface_name, ip_network) in potential_subnets {
-            if let Some(subnet) = Subnet::from_discovery(
-                interface_name,
+            if let Some(mut subnet) = Subnet::from_discovery(
+                interface_name.clone(),
                 &ip_network,
                 daemon_id,
                 &discovery_type,
                 network_id,
             ) {
+                subnet.base.layer2 = self.get_interface_layer2(&interface_name);
                 subnet_map.entry(subnet.base.cidr).or_insert(subnet);
             }
         }
@@ -117,6 +126,52 @@ pub trait DaemonUtils {
         }
     }
 
+    /// Probe what this build and host can do, for the server to offer only discoveries that
+    /// work here
+    fn get_own_features(&self) -> DaemonFeatures {
+        let interfaces = pnet::datalink::interfaces();
+
+        let raw_sockets = transport_channel(
+            4096,
+            TransportChannelType::Layer4(TransportProtocol::Ipv4(IpNextHeaderProtocols::Icmp)),
+        )
+        .is_ok();
+
+        let pcap = interfaces
+            .iter()
+            .find(|i| i.is_up() && !i.is_loopback() && i.mac.is_some())
+            .is_some_and(|i| pnet::datalink::channel(i, Default::default()).is_ok());
+
+        let ipv6 = interfaces
+            .iter()
+            .filter(|i| !i.is_loopback())
+            .flat_map(|i| i.ips.iter())
+            .any(|ip| match ip.ip() {
+                IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) != 0xfe80,
+                IpAddr::V4(_) => false,
+            });
+
+        DaemonFeatures {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            raw_sockets,
+            pcap,
+            ipv6,
+            snmp: true,
+            discovery_types: DiscoveryType::iter()
+                .filter(|t| !t.runs_on_server())
+                .map(|t| t.id().to_string())
+                .collect(),
+        }
+    }
+
+    /// Memory, CPU time and open files of the daemon process, for its heartbeat. Only Linux
+    /// reports them so far.
+    fn get_resource_usage(&self) -> DaemonResourceUsage {
+        DaemonResourceUsage::default()
+    }
+
     async fn get_own_routing_table_gateway_ips(&self) -> Result<Vec<IpAddr>, Error> {
         let routing_handle = Handle::new()?;
         let routes = routing_handle.list().await?;
@@ -0,0 +1,111 @@
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tokio::net::TcpStream;
+
+use crate::daemon::utils::http::HttpClients;
+use crate::server::monitoring::r#impl::{
+    api::{HealthCheckReport, HealthCheckTarget},
+    base::HealthCheckKind,
+};
+
+/// Client for HTTP health checks. Certificates are verified only if scan TLS verification is
+/// on, since most services on a home network are self-signed; timeouts are set per check.
+pub fn health_check_client(http: &HttpClients) -> Result<reqwest::Client> {
+    http.scan_builder()
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .build()
+        .map_err(|e| anyhow!("Could not build client {}", e))
+}
+
+/// Run a health check once. Latency covers the whole check, including reading the body when
+/// one is expected.
+pub async fn run_check(client: &reqwest::Client, target: &HealthCheckTarget) -> HealthCheckReport {
+    let addr = SocketAddr::new(target.ip, target.port);
+    let timeout = Duration::from_millis(target.timeout_ms.into());
+    let checked_at = Utc::now();
+    let started = Instant::now();
+
+    let (status_code, outcome) = match &target.kind {
+        HealthCheckKind::Tcp => (None, check_tcp(addr, timeout).await),
+        HealthCheckKind::Http {
+            path,
+            https,
+            expected_status,
+            expected_body,
+        } => {
+            let scheme = if *https { "https" } else { "http" };
+            let url = format!("{}://{}{}", scheme, addr, path);
+            check_http(
+                client,
+                &url,
+                timeout,
+                *expected_status,
+                expected_body.as_deref(),
+            )
+            .await
+        }
+    };
+
+    let latency_ms = started.elapsed().as_millis().try_into().ok();
+
+    HealthCheckReport {
+        check_id: target.check_id,
+        checked_at,
+        up: outcome.is_ok(),
+        latency_ms: outcome.is_ok().then_some(latency_ms).flatten(),
+        status_code,
+        message: outcome.err(),
+    }
+}
+
+async fn check_tcp(addr: SocketAddr, timeout: Duration) -> Result<(), String> {
+    match tokio::time::timeout(timeout, TcpStream::connect(addr)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("No connection within {}ms", timeout.as_millis())),
+    }
+}
+
+async fn check_http(
+    client: &reqwest::Client,
+    url: &str,
+    timeout: Duration,
+    expected_status: Option<u16>,
+    expected_body: Option<&str>,
+) -> (Option<u16>, Result<(), String>) {
+    let response = match client.get(url).timeout(timeout).send().await {
+        Ok(response) => response,
+        Err(e) => return (None, Err(e.to_string())),
+    };
+
+    let status = response.status();
+    let status_ok = match expected_status {
+        Some(expected) => status.as_u16() == expected,
+        None => status.is_success(),
+    };
+    if !status_ok {
+        return (
+            Some(status.as_u16()),
+            Err(format!("Unexpected HTTP {}", status)),
+        );
+    }
+
+    if let Some(expected) = expected_body {
+        match response.text().await {
+            Ok(body) if body.contains(expected) => {}
+            Ok(_) => {
+                return (
+                    Some(status.as_u16()),
+                    Err(format!("Response doesn't contain '{}'", expected)),
+                );
+            }
+            Err(e) => return (Some(status.as_u16()), Err(e.to_string())),
+        }
+    }
+
+    (Some(status.as_u16()), Ok(()))
+}
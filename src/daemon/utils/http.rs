@@ -0,0 +1,64 @@
+use anyhow::{Context, Error, Result};
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+
+use crate::daemon::shared::storage::AppConfig;
+
+/// Outbound HTTP for the daemon, set up from its proxy and TLS config. Requests to the server
+/// and to APIs off the local network go through `server`; scanned hosts are reached directly
+/// through clients from `scan_builder`.
+#[derive(Clone)]
+pub struct HttpClients {
+    pub server: reqwest::Client,
+    ca_certs: Vec<Certificate>,
+    verify_scan_tls: bool,
+}
+
+impl HttpClients {
+    pub fn from_config(config: &AppConfig) -> Result<Self, Error> {
+        let ca_certs = match &config.ca_cert_path {
+            Some(path) => {
+                let pem = std::fs::read(path)
+                    .with_context(|| format!("Failed to read CA bundle {}", path))?;
+                Certificate::from_pem_bundle(&pem)
+                    .with_context(|| format!("Failed to parse CA bundle {}", path))?
+            }
+            None => Vec::new(),
+        };
+
+        let mut builder = trust(reqwest::Client::builder(), &ca_certs)
+            .danger_accept_invalid_certs(!config.verify_server_tls);
+
+        if let Some(proxy_url) = &config.proxy {
+            // http://, https://, socks5:// or socks5h:// to resolve names through the proxy
+            let proxy = Proxy::all(proxy_url)
+                .with_context(|| format!("Invalid proxy URL {}", proxy_url))?
+                .no_proxy(config.no_proxy.as_deref().and_then(NoProxy::from_string));
+            builder = builder.proxy(proxy);
+        }
+
+        if !config.verify_server_tls {
+            tracing::warn!("Server certificate verification is disabled");
+        }
+
+        Ok(Self {
+            server: builder.build().context("Failed to build server client")?,
+            ca_certs,
+            verify_scan_tls: config.verify_scan_tls,
+        })
+    }
+
+    /// Builder for a client that reaches scanned hosts: never proxied, trusting the extra CAs,
+    /// and verifying certificates only if configured to since most are self-signed
+    pub fn scan_builder(&self) -> ClientBuilder {
+        trust(reqwest::Client::builder(), &self.ca_certs)
+            .no_proxy()
+            .danger_accept_invalid_certs(!self.verify_scan_tls)
+    }
+}
+
+fn trust(builder: ClientBuilder, ca_certs: &[Certificate]) -> ClientBuilder {
+    ca_certs
+        .iter()
+        .cloned()
+        .fold(builder, |builder, cert| builder.add_root_certificate(cert))
+}
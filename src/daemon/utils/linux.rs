@@ -4,6 +4,10 @@ use crate::daemon::utils::base::DaemonUtils;
 #[cfg(target_os = "linux")]
 pub struct LinuxDaemonUtils;
 
+#[cfg(target_os = "linux")]
+use crate::server::daemons::r#impl::api::DaemonResourceUsage;
+#[cfg(target_os = "linux")]
+use crate::server::subnets::r#impl::types::SubnetLayer2;
 #[cfg(target_os = "linux")]
 use anyhow::{Error, Result, anyhow};
 #[cfg(target_os = "linux")]
@@ -13,6 +17,8 @@ use mac_address::MacAddress;
 #[cfg(target_os = "linux")]
 use std::net::IpAddr;
 #[cfg(target_os = "linux")]
+use std::path::Path;
+#[cfg(target_os = "linux")]
 #[async_trait]
 impl DaemonUtils for LinuxDaemonUtils {
     fn new() -> Self {
@@ -36,6 +42,66 @@ impl DaemonUtils for LinuxDaemonUtils {
         }
     }
 
+    fn get_interface_layer2(&self, interface_name: &str) -> SubnetLayer2 {
+        let (vlan_id, parent_interface) = std::fs::read_to_string("/proc/net/vlan/config")
+            .ok()
+            .and_then(|config| parse_vlan_config(&config, interface_name))
+            .unzip();
+
+        let sys = Path::new("/sys/class/net").join(interface_name);
+        let (bridge, bridge_members) = if sys.join("bridge").is_dir() {
+            let mut members: Vec<String> = std::fs::read_dir(sys.join("brif"))
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.file_name().to_string_lossy().into_owned())
+                        .collect()
+                })
+                .unwrap_or_default();
+            members.sort();
+            (Some(interface_name.to_string()), members)
+        } else {
+            // A port's master can also be a bond, which isn't a bridge
+            let master = std::fs::read_link(sys.join("master"))
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .filter(|m| Path::new("/sys/class/net").join(m).join("bridge").is_dir());
+            (master, Vec::new())
+        };
+
+        SubnetLayer2 {
+            vlan_id,
+            parent_interface,
+            bridge,
+            bridge_members,
+        }
+    }
+
+    fn get_resource_usage(&self) -> DaemonResourceUsage {
+        use procfs::process::Process;
+
+        let Ok(process) = Process::myself() else {
+            return DaemonResourceUsage::default();
+        };
+
+        let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        let cpu_secs = process
+            .stat()
+            .ok()
+            .filter(|_| ticks_per_sec > 0)
+            .map(|stat| (stat.utime + stat.stime) as f64 / ticks_per_sec as f64);
+
+        DaemonResourceUsage {
+            memory_bytes: process
+                .status()
+                .ok()
+                .and_then(|status| status.vmrss)
+                .map(|kb| kb * 1024),
+            cpu_secs,
+            open_files: process.fd_count().ok(),
+        }
+    }
+
     async fn get_mac_address_for_ip(&self, ip: IpAddr) -> Result<Option<MacAddress>, Error> {
         use procfs::net;
 
@@ -59,3 +125,40 @@ impl DaemonUtils for LinuxDaemonUtils {
         Ok(None)
     }
 }
+
+/// Find an interface's VLAN id and parent device in `/proc/net/vlan/config`, whose entries look
+/// like `eth0.20 | 20 | eth0`
+#[cfg(target_os = "linux")]
+fn parse_vlan_config(config: &str, interface_name: &str) -> Option<(u16, String)> {
+    config.lines().find_map(|line| {
+        let mut fields = line.split('|').map(str::trim);
+        let (name, id, parent) = (fields.next()?, fields.next()?, fields.next()?);
+        if name != interface_name {
+            return None;
+        }
+        Some((id.parse().ok()?, parent.to_string()))
+    })
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vlan_config_entries() {
+        let config = "VLAN Dev name    | VLAN ID\n\
+            Name-Type: VLAN_NAME_TYPE_RAW_PLUS_VID_NO_PAD\n\
+            eth0.20        | 20  | eth0\n\
+            iot            | 30  | enp3s0\n";
+
+        assert_eq!(
+            parse_vlan_config(config, "eth0.20"),
+            Some((20, "eth0".to_string()))
+        );
+        assert_eq!(
+            parse_vlan_config(config, "iot"),
+            Some((30, "enp3s0".to_string()))
+        );
+        assert_eq!(parse_vlan_config(config, "eth0"), None);
+    }
+}
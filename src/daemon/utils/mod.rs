@@ -1,5 +1,18 @@
+pub mod banners;
 pub mod base;
+pub mod health;
+pub mod http;
 pub mod linux;
 pub mod macos;
+pub mod ping;
+pub mod rate_limit;
 pub mod scanner;
+pub mod snmp;
+pub mod ssdp;
+pub mod ssh;
+pub mod tailnet;
+pub mod traceroute;
+pub mod udp_probes;
+pub mod vpn;
 pub mod windows;
+pub mod winrm;
@@ -0,0 +1,60 @@
+use anyhow::Error;
+use std::{net::IpAddr, time::Duration};
+use surge_ping::{Client, Config, ICMP, IcmpPacket, PingIdentifier, PingSequence};
+
+const PAYLOAD: [u8; 16] = [0; 16];
+
+/// ICMP echo clients for both address families. On Linux these use unprivileged datagram
+/// sockets, which need the daemon's group in `net.ipv4.ping_group_range`; otherwise the daemon
+/// needs CAP_NET_RAW.
+pub struct Pinger {
+    v4: Client,
+    v6: Option<Client>,
+}
+
+impl Pinger {
+    pub fn new() -> Result<Self, Error> {
+        let v4 = Client::new(&Config::default())?;
+        // Hosts without IPv6 can still ping IPv4 addresses
+        let v6 = Client::new(&Config::builder().kind(ICMP::V6).build()).ok();
+
+        Ok(Self { v4, v6 })
+    }
+
+    /// Round trip of the first of `attempts` echo requests answered within `timeout`, or None if
+    /// the host never answered
+    pub async fn ping(&self, ip: IpAddr, timeout: Duration, attempts: u16) -> Option<Duration> {
+        let client = match ip {
+            IpAddr::V4(_) => &self.v4,
+            IpAddr::V6(_) => self.v6.as_ref()?,
+        };
+
+        let mut pinger = client.pinger(ip, PingIdentifier(rand::random())).await;
+        pinger.timeout(timeout);
+
+        for sequence in 0..attempts {
+            match pinger.ping(PingSequence(sequence), &PAYLOAD).await {
+                Ok((_, rtt)) => return Some(rtt),
+                Err(e) => tracing::trace!("Ping {} attempt {} failed: {}", ip, sequence + 1, e),
+            }
+        }
+
+        None
+    }
+
+    /// TTL of an echo reply within `timeout`, or None if the host didn't answer. Only IPv4
+    /// replies carry the IP header it's read from.
+    pub async fn ttl(&self, ip: IpAddr, timeout: Duration) -> Option<u8> {
+        if ip.is_ipv6() {
+            return None;
+        }
+
+        let mut pinger = self.v4.pinger(ip, PingIdentifier(rand::random())).await;
+        pinger.timeout(timeout);
+
+        match pinger.ping(PingSequence(0), &PAYLOAD).await {
+            Ok((IcmpPacket::V4(packet), _)) => packet.get_ttl(),
+            _ => None,
+        }
+    }
+}
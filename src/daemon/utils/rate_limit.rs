@@ -0,0 +1,367 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::{sync::Notify, time::Instant};
+use uuid::Uuid;
+
+use crate::server::discovery::r#impl::scan_settings::ScanLimits;
+
+/// How often an adaptive limiter looks back at the probes it has seen
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Probes a window needs before it says anything about congestion
+const MIN_WINDOW_PROBES: u32 = 20;
+
+/// Share of answered probes that needed a retry above which probes are being lost
+const MAX_RETRIED_SHARE: f64 = 0.05;
+
+/// How far the share of unanswered probes may rise above its recent average before it's a spike.
+/// Subnets full of absent hosts leave most probes unanswered, so only the change matters.
+const UNANSWERED_SPIKE: f64 = 0.3;
+
+/// Weight of the latest window in the average share of unanswered probes
+const BASELINE_WEIGHT: f64 = 0.3;
+
+/// Rate and host concurrency are multiplied by these when backing off and recovering
+const BACKOFF: f64 = 0.5;
+const RECOVERY: f64 = 1.25;
+
+/// Slowest pace, in probes per second, that backing off goes down to
+const MIN_RATE: f64 = 10.0;
+
+/// What became of one probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// Open, or refused
+    Answered,
+    /// Answered only after a retry, so an earlier attempt was lost
+    Retried,
+    /// No answer on any attempt
+    TimedOut,
+    /// Host or network unreachable
+    Unreachable,
+}
+
+#[derive(Default)]
+struct Window {
+    sent: u32,
+    answered: u32,
+    retried: u32,
+    unanswered: u32,
+}
+
+struct LimiterState {
+    /// Probes per second allowed now; unlimited if unset
+    rate: Option<f64>,
+    next_slot: Instant,
+    hosts_allowed: usize,
+    hosts_in_flight: usize,
+    window_start: Instant,
+    window: Window,
+    unanswered_baseline: Option<f64>,
+}
+
+/// Paces probes and caps how many hosts are scanned at once, for a whole scan or one subnet.
+/// An adaptive limiter halves both when probes start getting lost or going unanswered, and
+/// works its way back up to the configured limits while they aren't.
+pub struct RateLimiter {
+    max_rate: Option<f64>,
+    max_hosts: usize,
+    adaptive: bool,
+    state: Mutex<LimiterState>,
+    host_freed: Notify,
+}
+
+impl RateLimiter {
+    /// `max_rate` must be positive
+    pub fn new(max_rate: Option<f64>, max_hosts: usize, adaptive: bool) -> Self {
+        let now = Instant::now();
+        let max_hosts = max_hosts.max(1);
+
+        Self {
+            max_rate,
+            max_hosts,
+            adaptive,
+            state: Mutex::new(LimiterState {
+                rate: max_rate,
+                next_slot: now,
+                hosts_allowed: max_hosts,
+                hosts_in_flight: 0,
+                window_start: now,
+                window: Window::default(),
+                unanswered_baseline: None,
+            }),
+            host_freed: Notify::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Wait for the next free slot to send a probe
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut state = self.lock();
+            let Some(rate) = state.rate else {
+                return;
+            };
+            let slot = state.next_slot.max(Instant::now());
+            state.next_slot = slot + Duration::from_secs_f64(1.0 / rate);
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Wait until another host may be scanned. The host counts until the permit is dropped.
+    pub async fn host(self: &Arc<Self>) -> HostPermit {
+        loop {
+            {
+                let mut state = self.lock();
+                if state.hosts_in_flight < state.hosts_allowed {
+                    state.hosts_in_flight += 1;
+                    return HostPermit(self.clone());
+                }
+            }
+            self.host_freed.notified().await;
+        }
+    }
+
+    pub fn record(&self, outcome: ProbeOutcome) {
+        self.record_at(outcome, Instant::now());
+    }
+
+    fn record_at(&self, outcome: ProbeOutcome, now: Instant) {
+        if !self.adaptive {
+            return;
+        }
+
+        let mut state = self.lock();
+        let window = &mut state.window;
+        window.sent += 1;
+        match outcome {
+            ProbeOutcome::Answered => window.answered += 1,
+            ProbeOutcome::Retried => {
+                window.answered += 1;
+                window.retried += 1;
+            }
+            ProbeOutcome::TimedOut | ProbeOutcome::Unreachable => window.unanswered += 1,
+        }
+
+        let elapsed = now - state.window_start;
+        if elapsed < WINDOW || state.window.sent < MIN_WINDOW_PROBES {
+            return;
+        }
+        let window = std::mem::take(&mut state.window);
+        state.window_start = now;
+
+        let retried_share = if window.answered >= MIN_WINDOW_PROBES {
+            f64::from(window.retried) / f64::from(window.answered)
+        } else {
+            0.0
+        };
+        let unanswered_share = f64::from(window.unanswered) / f64::from(window.sent);
+        let spike = state
+            .unanswered_baseline
+            .is_some_and(|baseline| unanswered_share > baseline + UNANSWERED_SPIKE);
+        state.unanswered_baseline = Some(match state.unanswered_baseline {
+            Some(baseline) => {
+                baseline * (1.0 - BASELINE_WEIGHT) + unanswered_share * BASELINE_WEIGHT
+            }
+            None => unanswered_share,
+        });
+
+        let observed_rate = f64::from(window.sent) / elapsed.as_secs_f64();
+
+        if retried_share > MAX_RETRIED_SHARE || spike {
+            let current = state
+                .rate
+                .map_or(observed_rate, |rate| rate.min(observed_rate));
+            state.rate = Some((current * BACKOFF).max(MIN_RATE));
+            state.hosts_allowed = ((state.hosts_allowed as f64 * BACKOFF) as usize).max(1);
+
+            tracing::debug!(
+                "Probes are being lost ({:.0}% retried, {:.0}% unanswered), slowing to {:.0}/s and {} hosts",
+                retried_share * 100.0,
+                unanswered_share * 100.0,
+                state.rate.unwrap_or_default(),
+                state.hosts_allowed
+            );
+            return;
+        }
+
+        state.rate = match (state.rate, self.max_rate) {
+            (Some(rate), Some(max_rate)) => Some((rate * RECOVERY).min(max_rate)),
+            // Uncapped again once the pace is well beyond what the scan actually sends
+            (Some(rate), None) if rate * RECOVERY > observed_rate * 2.0 => None,
+            (Some(rate), None) => Some(rate * RECOVERY),
+            (None, _) => None,
+        };
+
+        if state.hosts_allowed < self.max_hosts {
+            let recovered = ((state.hosts_allowed as f64 * RECOVERY).ceil() as usize)
+                .clamp(state.hosts_allowed + 1, self.max_hosts);
+            let added = recovered - state.hosts_allowed;
+            state.hosts_allowed = recovered;
+            drop(state);
+
+            for _ in 0..added {
+                self.host_freed.notify_one();
+            }
+        }
+    }
+}
+
+/// A host being scanned under a limiter
+pub struct HostPermit(Arc<RateLimiter>);
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        self.0.lock().hosts_in_flight -= 1;
+        self.0.host_freed.notify_one();
+    }
+}
+
+/// The limiters of one scan: one for the whole scan, and one for each subnet with limits of its
+/// own
+#[derive(Clone)]
+pub struct ScanLimiter {
+    global: Arc<RateLimiter>,
+    subnets: Arc<HashMap<Uuid, Arc<RateLimiter>>>,
+}
+
+impl ScanLimiter {
+    /// `concurrent_hosts` caps the whole scan, and subnets without a host limit of their own
+    pub fn new(limits: &ScanLimits, concurrent_hosts: usize) -> Self {
+        let adaptive = limits.is_adaptive();
+
+        let subnets = limits
+            .subnets
+            .iter()
+            .map(|(subnet_id, subnet)| {
+                let max_rate = subnet
+                    .max_packets_per_second
+                    .filter(|rate| *rate > 0)
+                    .map(f64::from);
+                let max_hosts = subnet
+                    .concurrent_hosts
+                    .map_or(concurrent_hosts, |hosts| hosts as usize);
+                (
+                    *subnet_id,
+                    Arc::new(RateLimiter::new(max_rate, max_hosts, adaptive)),
+                )
+            })
+            .collect();
+
+        Self {
+            global: Arc::new(RateLimiter::new(
+                limits.max_probes_per_second(),
+                concurrent_hosts,
+                adaptive,
+            )),
+            subnets: Arc::new(subnets),
+        }
+    }
+
+    /// The limiters probes to a host in the subnet go through
+    pub fn for_subnet(&self, subnet_id: &Uuid) -> ProbeGate {
+        ProbeGate {
+            global: self.global.clone(),
+            subnet: self.subnets.get(subnet_id).cloned(),
+        }
+    }
+}
+
+/// Limits probes to hosts in one subnet by the scan's limiter and the subnet's own
+#[derive(Clone)]
+pub struct ProbeGate {
+    global: Arc<RateLimiter>,
+    subnet: Option<Arc<RateLimiter>>,
+}
+
+impl ProbeGate {
+    /// Wait until a probe may be sent
+    pub async fn acquire(&self) {
+        if let Some(subnet) = &self.subnet {
+            subnet.acquire().await;
+        }
+        self.global.acquire().await;
+    }
+
+    pub fn record(&self, outcome: ProbeOutcome) {
+        if let Some(subnet) = &self.subnet {
+            subnet.record(outcome);
+        }
+        self.global.record(outcome);
+    }
+
+    /// Wait until another host may be scanned. The subnet's permit is taken first, so a busy
+    /// subnet doesn't hold on to hosts other subnets could use.
+    pub async fn host(&self) -> (Option<HostPermit>, HostPermit) {
+        let subnet = match &self.subnet {
+            Some(subnet) => Some(subnet.host().await),
+            None => None,
+        };
+        (subnet, self.global.host().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Record a window's worth of outcomes, the last one as the window ends
+    fn window(limiter: &RateLimiter, outcomes: &[(ProbeOutcome, u32)]) {
+        let start = limiter.lock().window_start;
+        let mut outcomes: Vec<ProbeOutcome> = outcomes
+            .iter()
+            .flat_map(|(outcome, count)| std::iter::repeat_n(*outcome, *count as usize))
+            .collect();
+        let last = outcomes.pop().unwrap();
+
+        for outcome in outcomes {
+            limiter.record_at(outcome, start);
+        }
+        limiter.record_at(last, start + WINDOW);
+    }
+
+    #[test]
+    fn backs_off_when_probes_need_retries_and_recovers() {
+        let limiter = RateLimiter::new(Some(1000.0), 8, true);
+
+        // 100 probes over a second, a fifth answered only after a retry
+        window(
+            &limiter,
+            &[(ProbeOutcome::Answered, 80), (ProbeOutcome::Retried, 20)],
+        );
+        {
+            let state = limiter.lock();
+            assert_eq!(state.rate, Some(50.0));
+            assert_eq!(state.hosts_allowed, 4);
+        }
+
+        window(&limiter, &[(ProbeOutcome::Answered, 100)]);
+        let state = limiter.lock();
+        assert_eq!(state.rate, Some(62.5));
+        assert_eq!(state.hosts_allowed, 5);
+    }
+
+    #[test]
+    fn steady_unanswered_probes_are_not_a_spike() {
+        let limiter = RateLimiter::new(None, 8, true);
+
+        for _ in 0..3 {
+            window(
+                &limiter,
+                &[(ProbeOutcome::Answered, 10), (ProbeOutcome::TimedOut, 90)],
+            );
+        }
+
+        let state = limiter.lock();
+        assert_eq!(state.rate, None);
+        assert_eq!(state.hosts_allowed, 8);
+    }
+}
@@ -1,27 +1,86 @@
 use crate::daemon::discovery::types::base::DiscoveryCriticalError;
+use crate::daemon::utils::banners::grab_banners;
+use crate::daemon::utils::base::{DaemonUtils, PlatformDaemonUtils};
+use crate::daemon::utils::http::HttpClients;
+use crate::daemon::utils::rate_limit::{ProbeGate, ProbeOutcome};
+use crate::daemon::utils::udp_probes::{UdpProbeContext, UdpProbeRegistry, run_probe};
+use crate::server::discovery::r#impl::scan_settings::ScanSettings;
+use crate::server::hosts::r#impl::liveness::LivenessEvidence;
+use crate::server::services::r#impl::banners::PortBanner;
 use crate::server::services::r#impl::base::Service;
 use crate::server::services::r#impl::endpoints::{Endpoint, EndpointResponse};
 use anyhow::anyhow;
-use anyhow::{Error, Result};
-use cidr::IpCidr;
-use dhcproto::Encodable;
-use dhcproto::v4::{self, Decodable, Encoder, Message, MessageType};
+use anyhow::{Error, Result, bail};
+use cidr::{IpCidr, Ipv4Cidr};
 use futures::stream::FuturesUnordered;
 use futures::stream::StreamExt;
-use rand::{Rng, SeedableRng};
-use rsntp::AsyncSntpClient;
-use snmp2::{AsyncSession, Oid};
-use std::net::{IpAddr, SocketAddr};
+use mac_address::MacAddress;
+use pnet::datalink::{self, Channel};
+use pnet::packet::{
+    MutablePacket, Packet,
+    arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket},
+    ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket},
+};
+use pnet::util::MacAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
-use tokio::net::UdpSocket;
-use tokio::{net::TcpStream, time::timeout};
+use tokio::{
+    net::{TcpStream, UdpSocket},
+    time::timeout,
+};
 use tokio_util::sync::CancellationToken;
-use trust_dns_resolver::TokioAsyncResolver;
-use trust_dns_resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts};
 
 use crate::server::hosts::r#impl::ports::{PortBase, TransportProtocol};
 
-pub const SCAN_TIMEOUT: Duration = Duration::from_millis(800);
+/// Redirects followed per endpoint probe; only same-host redirects are followed
+const MAX_SCAN_REDIRECTS: usize = 5;
+
+/// How long to keep listening for ARP replies after the last request is sent
+const ARP_REPLY_WAIT: Duration = Duration::from_secs(2);
+
+/// Pause between ARP requests so a large subnet doesn't flood the segment
+const ARP_REQUEST_INTERVAL: Duration = Duration::from_micros(500);
+
+/// Port that UDP datagrams are sent to when the OS is left to resolve addresses (discard)
+const ARP_TRIGGER_PORT: u16 = 9;
+
+/// How long the OS gets to resolve one address before its ARP table is read
+const ARP_RESOLVE_WAIT: Duration = Duration::from_millis(300);
+
+/// Client for endpoint probes, built once per discovery session. Redirects to other hosts are
+/// not followed so a probe never leaves the host being scanned, and HTTP/1 is used since
+/// embedded devices commonly mishandle HTTP/2 negotiation.
+pub fn build_scan_client(
+    http: &HttpClients,
+    settings: &ScanSettings,
+) -> Result<reqwest::Client, Error> {
+    let redirect_policy = reqwest::redirect::Policy::custom(|attempt| {
+        let same_host = attempt
+            .previous()
+            .first()
+            .is_some_and(|original| original.host_str() == attempt.url().host_str());
+
+        if attempt.previous().len() >= MAX_SCAN_REDIRECTS || !same_host {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    });
+
+    http.scan_builder()
+        .timeout(settings.endpoint_timeout())
+        .connect_timeout(settings.endpoint_timeout())
+        .redirect(redirect_policy)
+        .http1_only()
+        .tcp_nodelay(true)
+        .pool_max_idle_per_host(4)
+        .pool_idle_timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| anyhow!("Could not build client {}", e))
+}
 
 /// Generic batch scanner that maintains constant parallelism
 /// This is the core RustScan pattern extracted into a reusable function
@@ -87,13 +146,26 @@ where
     results
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn scan_ports_and_endpoints(
+    client: reqwest::Client,
+    settings: ScanSettings,
+    gate: ProbeGate,
+    udp_probes: Arc<UdpProbeRegistry>,
     ip: IpAddr,
     cancel: CancellationToken,
     port_scan_batch_size: usize,
     cidr: IpCidr,
     gateway_ips: Vec<IpAddr>,
-) -> Result<(Vec<PortBase>, Vec<EndpointResponse>), Error> {
+) -> Result<
+    (
+        Vec<PortBase>,
+        Vec<EndpointResponse>,
+        Vec<PortBanner>,
+        LivenessEvidence,
+    ),
+    Error,
+> {
     if cancel.is_cancelled() {
         return Err(anyhow!("Operation cancelled"));
     }
@@ -102,7 +174,15 @@ pub async fn scan_ports_and_endpoints(
     let mut endpoint_responses = Vec::new();
 
     // Scan TCP ports with batching
-    let tcp_ports = scan_tcp_ports(ip, cancel.clone(), port_scan_batch_size).await?;
+    let tcp_results = scan_tcp_ports(
+        &settings,
+        gate.clone(),
+        ip,
+        cancel.clone(),
+        port_scan_batch_size,
+    )
+    .await?;
+    let tcp_ports: Vec<PortBase> = tcp_results.iter().map(|(port, _)| *port).collect();
     open_ports.extend(tcp_ports.clone());
 
     if cancel.is_cancelled() {
@@ -110,9 +190,18 @@ pub async fn scan_ports_and_endpoints(
     }
 
     // Scan UDP ports with batching
-    let udp_ports =
-        scan_udp_ports(ip, cancel.clone(), port_scan_batch_size, cidr, gateway_ips).await?;
-    open_ports.extend(udp_ports);
+    let udp_ports = scan_udp_ports(
+        &settings,
+        gate,
+        &udp_probes,
+        ip,
+        cancel.clone(),
+        port_scan_batch_size,
+        cidr,
+        gateway_ips,
+    )
+    .await?;
+    open_ports.extend(udp_ports.clone());
 
     if cancel.is_cancelled() {
         return Err(anyhow!("Operation cancelled"));
@@ -121,13 +210,19 @@ pub async fn scan_ports_and_endpoints(
     // Scan endpoints - check on ALL open TCP ports, not just filtered ones
     let mut ports_to_check = tcp_ports.clone();
 
-    // Also add endpoint-only ports that we didn't scan during port scanning
-    let endpoint_only_ports = Service::endpoint_only_ports();
+    // Also add endpoint-only ports that we didn't scan during port scanning, unless the scan
+    // is limited to its listed ports
+    let scanned_ports = settings.ports.resolve();
+    let endpoint_only_ports = Service::endpoint_only_ports()
+        .into_iter()
+        .filter(|p| settings.ports.includes_probe_ports() || scanned_ports.contains(&p.config()));
     ports_to_check.extend(endpoint_only_ports);
+    ports_to_check.retain(|p| !settings.exclusions.excludes_port(p.config()));
     ports_to_check.sort_by_key(|p| (p.number(), p.protocol()));
     ports_to_check.dedup();
 
     let endpoints = scan_endpoints(
+        client,
         ip,
         cancel.clone(),
         Some(ports_to_check),
@@ -136,11 +231,41 @@ pub async fn scan_ports_and_endpoints(
     .await?;
     endpoint_responses.extend(endpoints);
 
+    // Web servers already identified themselves through endpoint responses
+    let banners = if settings.grab_banners && !cancel.is_cancelled() {
+        let banner_ports: Vec<PortBase> = tcp_ports
+            .iter()
+            .filter(|p| {
+                !endpoint_responses
+                    .iter()
+                    .any(|r| r.endpoint.port_base.number() == p.number())
+            })
+            .copied()
+            .collect();
+        grab_banners(ip, &banner_ports, settings.banner_timeout()).await
+    } else {
+        Vec::new()
+    };
+
+    let mut evidence = LivenessEvidence {
+        tcp_ports: tcp_results.len() as u32,
+        tcp_retried: tcp_results
+            .iter()
+            .filter(|(_, attempts)| *attempts > 1)
+            .count() as u32,
+        udp_responses: udp_ports.len() as u32,
+        endpoint_responses: endpoint_responses.len() as u32,
+        ..Default::default()
+    };
+
     // IMPORTANT: Add any ports that had endpoint responses but weren't in open_ports
     // This handles cases where we got HTTP response but port scan didn't detect it
     for endpoint_response in &endpoint_responses {
         let port = endpoint_response.endpoint.port_base;
         if !open_ports.contains(&port) {
+            if scanned_ports.contains(&port.config()) {
+                evidence.endpoint_only_ports += 1;
+            }
             tracing::debug!(
                 "Adding port {} to open ports based on successful endpoint response",
                 port
@@ -154,25 +279,33 @@ pub async fn scan_ports_and_endpoints(
     open_ports.dedup();
 
     tracing::debug!(
-        "Scan results for {}: found {} open ports, {} endpoint responses",
+        "Scan results for {}: found {} open ports, {} endpoint responses, {} banners",
         ip,
         open_ports.len(),
-        endpoint_responses.len()
+        endpoint_responses.len(),
+        banners.len()
     );
 
-    Ok((open_ports, endpoint_responses))
+    Ok((open_ports, endpoint_responses, banners, evidence))
 }
 
+/// Open TCP ports, with the number of connect attempts each needed
 pub async fn scan_tcp_ports(
+    settings: &ScanSettings,
+    gate: ProbeGate,
     ip: IpAddr,
     cancel: CancellationToken,
     batch_size: usize,
-) -> Result<Vec<PortBase>, Error> {
-    let discovery_ports = Service::all_discovery_ports();
-    let ports: Vec<u16> = discovery_ports
-        .iter()
-        .filter(|p| p.protocol() == TransportProtocol::Tcp)
-        .map(|p| p.number())
+) -> Result<Vec<(PortBase, u32)>, Error> {
+    let ports: Vec<u16> = settings
+        .ports
+        .numbers(TransportProtocol::Tcp)
+        .into_iter()
+        .filter(|n| {
+            !settings
+                .exclusions
+                .excludes_port(PortBase::new_tcp(*n).config())
+        })
         .collect();
 
     let total_ports = ports.len();
@@ -184,61 +317,91 @@ pub async fn scan_tcp_ports(
         batch_size
     );
 
-    let open_ports = batch_scan(ports, batch_size, cancel, move |port| async move {
-        let socket = SocketAddr::new(ip, port);
+    let connect_timeout = settings.tcp_timeout();
+    let max_attempts = settings.tcp_max_attempts();
+    let retry_delay = settings.retry_delay();
 
-        // Try connection with timeout, retry once on timeout for slow hosts
-        let mut attempts = 0;
-        let max_attempts = 2;
+    let open_ports = batch_scan(ports, batch_size, cancel, move |port| {
+        let gate = gate.clone();
+        async move {
+            let socket = SocketAddr::new(ip, port);
 
-        loop {
-            attempts += 1;
-            let start = std::time::Instant::now();
+            // Try connection with timeout, retrying on timeout for slow hosts
+            let mut attempts = 0;
 
-            match timeout(SCAN_TIMEOUT, TcpStream::connect(socket)).await {
-                Ok(Ok(stream)) => {
-                    let connect_time = start.elapsed();
+            loop {
+                attempts += 1;
+                gate.acquire().await;
+                let start = std::time::Instant::now();
 
-                    // Try to peek at the connection to detect immediate disconnects
-                    let mut buf = [0u8; 1];
-                    let _peek_result =
-                        timeout(Duration::from_millis(50), stream.peek(&mut buf)).await;
+                match timeout(connect_timeout, TcpStream::connect(socket)).await {
+                    Ok(Ok(stream)) => {
+                        let connect_time = start.elapsed();
 
-                    tracing::debug!(
-                        "Found open TCP port {}:{} (took {:?})",
-                        ip,
-                        port,
-                        connect_time
-                    );
+                        // Try to peek at the connection to detect immediate disconnects
+                        let mut buf = [0u8; 1];
+                        let _peek_result =
+                            timeout(Duration::from_millis(50), stream.peek(&mut buf)).await;
 
-                    drop(stream);
-                    return Some(PortBase::new_tcp(port));
-                }
-                Ok(Err(e)) => {
-                    if DiscoveryCriticalError::is_critical_error(e.to_string()) {
-                        tracing::error!("Critical error scanning {}:{}: {}", socket.ip(), port, e);
-                    }
-                    return None;
-                }
-                Err(_) => {
-                    let elapsed = start.elapsed();
-
-                    if attempts < max_attempts {
-                        tracing::trace!(
-                            "Port {}:{} timeout attempt {}/{} (took {:?}), retrying...",
+                        tracing::debug!(
+                            "Found open TCP port {}:{} (took {:?})",
                             ip,
                             port,
-                            attempts,
-                            max_attempts,
-                            elapsed
+                            connect_time
                         );
-                        // Small delay before retry
-                        tokio::time::sleep(Duration::from_millis(100)).await;
-                        continue;
-                    } else {
-                        tracing::trace!("Port {}:{} timeout after {} attempts", ip, port, attempts);
+
+                        drop(stream);
+                        gate.record(if attempts > 1 {
+                            ProbeOutcome::Retried
+                        } else {
+                            ProbeOutcome::Answered
+                        });
+                        return Some((PortBase::new_tcp(port), attempts));
+                    }
+                    Ok(Err(e)) => {
+                        gate.record(match e.kind() {
+                            std::io::ErrorKind::HostUnreachable
+                            | std::io::ErrorKind::NetworkUnreachable => ProbeOutcome::Unreachable,
+                            // Refused, so the host is there and keeping up
+                            _ if attempts > 1 => ProbeOutcome::Retried,
+                            _ => ProbeOutcome::Answered,
+                        });
+                        if DiscoveryCriticalError::is_critical_error(e.to_string()) {
+                            tracing::error!(
+                                "Critical error scanning {}:{}: {}",
+                                socket.ip(),
+                                port,
+                                e
+                            );
+                        }
                         return None;
                     }
+                    Err(_) => {
+                        let elapsed = start.elapsed();
+
+                        if attempts < max_attempts {
+                            tracing::trace!(
+                                "Port {}:{} timeout attempt {}/{} (took {:?}), retrying...",
+                                ip,
+                                port,
+                                attempts,
+                                max_attempts,
+                                elapsed
+                            );
+                            // Small delay before retry
+                            tokio::time::sleep(retry_delay).await;
+                            continue;
+                        } else {
+                            tracing::trace!(
+                                "Port {}:{} timeout after {} attempts",
+                                ip,
+                                port,
+                                attempts
+                            );
+                            gate.record(ProbeOutcome::TimedOut);
+                            return None;
+                        }
+                    }
                 }
             }
         }
@@ -256,50 +419,64 @@ pub async fn scan_tcp_ports(
 }
 
 pub async fn scan_udp_ports(
+    settings: &ScanSettings,
+    gate: ProbeGate,
+    udp_probes: &UdpProbeRegistry,
     ip: IpAddr,
     cancel: CancellationToken,
     batch_size: usize,
     cidr: IpCidr,
     gateway_ips: Vec<IpAddr>,
 ) -> Result<Vec<PortBase>, Error> {
-    let discovery_ports = Service::all_discovery_ports();
-    let ports: Vec<u16> = discovery_ports
-        .iter()
-        .filter(|p| p.protocol() == TransportProtocol::Udp)
-        .map(|p| p.number())
+    let mut ports = settings.ports.numbers(TransportProtocol::Udp);
+    if settings.ports.includes_probe_ports() {
+        ports.extend(udp_probes.ports());
+    }
+    ports.retain(|n| {
+        !settings
+            .exclusions
+            .excludes_port(PortBase::new_udp(*n).config())
+    });
+    ports.sort();
+    ports.dedup();
+
+    // Ports are only scanned if a probe knows how to talk to them
+    let probes: Vec<_> = ports
+        .into_iter()
+        .filter_map(|port| udp_probes.get(port))
         .collect();
 
     // UDP is slower and less reliable, cap at 10 concurrent
     let udp_batch_size = std::cmp::min(batch_size, 10);
 
-    let is_gateway = gateway_ips.contains(&ip);
-
-    let open_ports = batch_scan(ports, udp_batch_size, cancel, |port| async move {
-        let result = match port {
-            53 => test_dns_service(ip).await,
-            123 => test_ntp_service(ip).await,
-            161 => test_snmp_service(ip).await,
-            67 => {
-                if is_gateway {
-                    test_dhcp_service(ip, &cidr).await
-                } else {
-                    Ok(None)
-                }
-            }
-            _ => Ok(None),
-        };
+    let ctx = UdpProbeContext {
+        ip,
+        cidr,
+        is_gateway: gateway_ips.contains(&ip),
+    };
 
-        match result {
-            Ok(Some(detected_port)) => {
-                tracing::debug!("Found open UDP port {}:{}", ip, detected_port);
-                Some(PortBase::new_udp(detected_port))
-            }
-            Ok(None) => None,
-            Err(e) => {
-                if DiscoveryCriticalError::is_critical_error(e.to_string()) {
-                    tracing::error!("Critical error scanning UDP {}:{}: {}", ip, port, e);
+    let open_ports = batch_scan(probes, udp_batch_size, cancel, |probe| {
+        let probe_timeout = settings.udp_timeout(probe.port());
+        let gate = gate.clone();
+        async move {
+            gate.acquire().await;
+            match run_probe(probe.as_ref(), &ctx, probe_timeout).await {
+                Ok(Some(detected_port)) => {
+                    tracing::debug!("Found open UDP port {}:{}", ip, detected_port);
+                    Some(PortBase::new_udp(detected_port))
+                }
+                Ok(None) => None,
+                Err(e) => {
+                    if DiscoveryCriticalError::is_critical_error(e.to_string()) {
+                        tracing::error!(
+                            "Critical error scanning UDP {}:{}: {}",
+                            ip,
+                            probe.port(),
+                            e
+                        );
+                    }
+                    None
                 }
-                None
             }
         }
     })
@@ -309,6 +486,7 @@ pub async fn scan_udp_ports(
 }
 
 pub async fn scan_endpoints(
+    client: reqwest::Client,
     ip: IpAddr,
     cancel: CancellationToken,
     filter_ports: Option<Vec<PortBase>>,
@@ -316,12 +494,6 @@ pub async fn scan_endpoints(
 ) -> Result<Vec<EndpointResponse>, Error> {
     use std::collections::HashMap;
 
-    let client = reqwest::Client::builder()
-        .timeout(SCAN_TIMEOUT)
-        .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| anyhow!("Could not build client {}", e))?;
-
     let all_endpoints: Vec<Endpoint> = Service::all_discovery_endpoints()
         .into_iter()
         .filter_map(|e| {
@@ -459,340 +631,234 @@ pub async fn scan_endpoints(
     Ok(responses)
 }
 
-pub async fn test_dns_service(ip: IpAddr) -> Result<Option<u16>, Error> {
-    // Use the simpler approach - create resolver with custom config directly
-    let mut config = ResolverConfig::new();
-    let name_server = NameServerConfig::new(SocketAddr::new(ip, 53), Protocol::Udp);
-    config.add_name_server(name_server);
-
-    let test_resolver = TokioAsyncResolver::tokio(config, ResolverOpts::default());
+/// Layer-2 sweep: broadcast an ARP request for every address in `cidr` from the daemon's
+/// interface and collect the replies, so hosts with every port closed are still found. Needs a
+/// raw socket (root or CAP_NET_RAW); see `arp_table_sweep` for the fallback.
+pub async fn arp_sweep(
+    interface_name: &str,
+    source_ip: Ipv4Addr,
+    source_mac: MacAddress,
+    cidr: Ipv4Cidr,
+    cancel: CancellationToken,
+) -> Result<HashMap<Ipv4Addr, MacAddress>, Error> {
+    let interface = datalink::interfaces()
+        .into_iter()
+        .find(|i| i.name == interface_name)
+        .ok_or_else(|| anyhow!("Interface {} not found", interface_name))?;
 
-    match timeout(
-        Duration::from_millis(2000),
-        test_resolver.lookup_ip("google.com"),
-    )
-    .await
-    {
-        Ok(Ok(_)) => {
-            tracing::trace!("DNS server responding at {}:53", ip);
-            Ok(Some(53))
-        }
-        _ => {
-            tracing::trace!("DNS server not responding at {}:53", ip);
-            Ok(None)
-        }
-    }
-}
+    let config = datalink::Config {
+        read_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let (mut tx, mut rx) = match datalink::channel(&interface, config)? {
+        Channel::Ethernet(tx, rx) => (tx, rx),
+        _ => bail!("Unsupported datalink channel on {}", interface_name),
+    };
 
-pub async fn test_ntp_service(ip: IpAddr) -> Result<Option<u16>, Error> {
-    let client = AsyncSntpClient::new();
-    let server_addr = format!("{}:123", ip);
+    let done = Arc::new(AtomicBool::new(false));
 
-    match timeout(
-        Duration::from_millis(2000),
-        client.synchronize(&server_addr),
-    )
-    .await
-    {
-        Ok(Ok(result)) => {
-            // Validate that we got a meaningful time response
-            if let Ok(datetime) = result.datetime().unix_timestamp() {
-                if datetime > Duration::from_secs(0) {
-                    // Sanity check for valid timestamp
-                    tracing::trace!(
-                        "NTP server responding at {}:123 with time {}",
-                        ip,
-                        datetime.as_millis()
-                    );
-                    Ok(Some(123))
-                } else {
-                    tracing::trace!("Invalid NTP response from {}:123", ip);
-                    Ok(None)
+    let receiver_done = done.clone();
+    let receiver = tokio::task::spawn_blocking(move || {
+        let mut replies = HashMap::new();
+        while !receiver_done.load(Ordering::Relaxed) {
+            match rx.next() {
+                Ok(frame) => {
+                    if let Some((ip, mac)) = parse_arp_reply(frame, source_ip) {
+                        replies.insert(ip, mac);
+                    }
                 }
-            } else {
-                tracing::trace!("Invalid NTP response from {}:123", ip);
-                Ok(None)
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+                    ) => {}
+                Err(e) => return Err(Error::from(e)),
             }
         }
-        Ok(Err(e)) => {
-            tracing::trace!("NTP error from {}:123 - {}", ip, e);
-            Ok(None)
-        }
-        Err(_) => {
-            tracing::trace!("NTP timeout from {}:123", ip);
-            Ok(None)
-        }
-    }
-}
+        Ok(replies)
+    });
 
-pub async fn test_snmp_service(ip: IpAddr) -> Result<Option<u16>, Error> {
-    let target = format!("{}:161", ip);
-    let community = b"public";
-
-    match AsyncSession::new_v2c(&target, community, 0).await {
-        Ok(mut session) => {
-            let sys_descr_oid = Oid::from(&[1, 3, 6, 1, 2, 1, 1, 1, 0])
-                .map_err(|e| anyhow!("Invalid Oid: {:?}", e))?;
-
-            match timeout(Duration::from_millis(2000), session.get(&sys_descr_oid)).await {
-                Ok(Ok(mut response)) => {
-                    if let Some(_varbind) = response.varbinds.next() {
-                        tracing::trace!("SNMP server responding at {}:161", ip);
-                        Ok(Some(161))
-                    } else {
-                        tracing::trace!("Empty SNMP response from {}:161", ip);
-                        Ok(None)
-                    }
-                }
-                Ok(Err(e)) => {
-                    tracing::trace!("SNMP error from {}:161 - {}", ip, e);
-                    Ok(None)
-                }
-                Err(_) => {
-                    tracing::trace!("SNMP timeout from {}:161", ip);
-                    Ok(None)
-                }
+    let sender_cancel = cancel.clone();
+    let sender = tokio::task::spawn_blocking(move || {
+        let source_mac = MacAddr::from(source_mac.bytes());
+
+        for target in sweep_targets(cidr, source_ip) {
+            if sender_cancel.is_cancelled() {
+                break;
             }
-        }
-        Err(e) => {
-            tracing::trace!("SNMP session creation failed for {}:161 - {}", ip, e);
-            Ok(None)
-        }
-    }
-}
 
-/// Test if a host is running a DHCP server on port 67
-pub async fn test_dhcp_service(ip: IpAddr, subnet_cidr: &IpCidr) -> Result<Option<u16>, Error> {
-    let socket = match UdpSocket::bind("0.0.0.0:68").await {
-        Ok(s) => s,
-        Err(_) => {
-            // If port 68 is busy (another DHCP client), try random port
-            match UdpSocket::bind("0.0.0.0:0").await {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::debug!("Failed to bind UDP socket for DHCP test: {}", e);
-                    return Ok(None);
-                }
+            if let Some(Err(e)) = tx.send_to(&arp_request(source_mac, source_ip, target), None) {
+                return Err(Error::from(e));
             }
+            std::thread::sleep(ARP_REQUEST_INTERVAL);
         }
-    };
+        Ok(())
+    });
 
-    if let Err(e) = socket.set_broadcast(true) {
-        tracing::debug!("Failed to enable broadcast for DHCP test: {}", e);
-        return Ok(None);
+    let sent = sender.await?;
+    if sent.is_ok() && !cancel.is_cancelled() {
+        tokio::time::sleep(ARP_REPLY_WAIT).await;
     }
+    done.store(true, Ordering::Relaxed);
 
-    // Calculate broadcast address for this subnet
-    let broadcast_addr = match subnet_cidr {
-        IpCidr::V4(cidr) => {
-            let broadcast_ip = cidr.last_address();
-            SocketAddr::new(IpAddr::V4(broadcast_ip), 67)
-        }
-        IpCidr::V6(_) => {
-            tracing::trace!("Skipping DHCP test for IPv6 address");
-            return Ok(None);
-        }
-    };
+    let replies = receiver.await??;
+    sent?;
 
-    // Create a more complete DHCP DISCOVER message
-    let mut rng = rand::rngs::StdRng::from_os_rng();
-    let mac_addr: [u8; 6] = rng.random();
-    let transaction_id = rng.random::<u32>();
-
-    let mut msg = Message::default();
-    msg.set_opcode(v4::Opcode::BootRequest)
-        .set_htype(v4::HType::Eth)
-        .set_xid(transaction_id)
-        .set_flags(v4::Flags::default().set_broadcast())
-        .set_chaddr(&mac_addr);
-
-    // Add required and common DHCP options
-    msg.opts_mut()
-        .insert(v4::DhcpOption::MessageType(MessageType::Discover));
-
-    // Add parameter request list (commonly requested by clients)
-    msg.opts_mut()
-        .insert(v4::DhcpOption::ParameterRequestList(vec![
-            v4::OptionCode::SubnetMask,
-            v4::OptionCode::Router,
-            v4::OptionCode::DomainNameServer,
-            v4::OptionCode::DomainName,
-        ]));
-
-    // Encode DHCP DISCOVER packet
-    let mut buf = Vec::new();
-    let mut encoder = Encoder::new(&mut buf);
-    msg.encode(&mut encoder)?;
-
-    // Try broadcast first
-    tracing::trace!(
-        "Sending DHCP DISCOVER broadcast to {} for testing {} (xid: {:#x}, {} bytes)",
-        broadcast_addr,
-        ip,
-        transaction_id,
-        buf.len()
-    );
+    if cancel.is_cancelled() {
+        return Err(anyhow!("Operation cancelled"));
+    }
 
-    match socket.send_to(&buf, broadcast_addr).await {
-        Ok(sent) => {
-            tracing::trace!("Sent {} bytes via broadcast", sent);
-            // Try to receive multiple responses - might get responses from multiple servers
-            if let Some(port) =
-                wait_for_dhcp_responses(&socket, ip, transaction_id, "broadcast", 3).await?
-            {
-                return Ok(Some(port));
-            }
-        }
-        Err(e) => {
-            tracing::trace!("Broadcast DHCP DISCOVER failed: {}", e);
+    tracing::debug!("{} hosts answered ARP on {}", replies.len(), cidr);
+    Ok(replies)
+}
+
+/// Sweep without a raw socket: send a UDP datagram to every address so the OS resolves them,
+/// then read the results from its ARP table
+pub async fn arp_table_sweep(
+    utils: &PlatformDaemonUtils,
+    source_ip: Ipv4Addr,
+    cidr: Ipv4Cidr,
+    cancel: CancellationToken,
+) -> Result<HashMap<Ipv4Addr, MacAddress>, Error> {
+    let socket = UdpSocket::bind((source_ip, 0)).await?;
+    let targets: Vec<Ipv4Addr> = sweep_targets(cidr, source_ip).collect();
+
+    for target in &targets {
+        if cancel.is_cancelled() {
+            return Err(anyhow!("Operation cancelled"));
         }
+        // Unreachable addresses fail once ARP gives up, which is expected
+        let _ = socket.send_to(&[], (*target, ARP_TRIGGER_PORT)).await;
+        tokio::time::sleep(ARP_REQUEST_INTERVAL).await;
     }
 
-    // Fall back to unicast
-    let unicast_addr = SocketAddr::new(ip, 67);
-    tracing::trace!(
-        "Trying unicast DHCP DISCOVER to {} (xid: {:#x})",
-        unicast_addr,
-        transaction_id
-    );
+    tokio::time::sleep(ARP_REPLY_WAIT).await;
 
-    match socket.send_to(&buf, unicast_addr).await {
-        Ok(sent) => {
-            tracing::trace!("Sent {} bytes via unicast", sent);
-            if let Some(port) =
-                wait_for_dhcp_responses(&socket, ip, transaction_id, "unicast", 3).await?
-            {
-                return Ok(Some(port));
-            }
-        }
-        Err(e) => {
-            tracing::trace!("Unicast DHCP DISCOVER failed: {}", e);
+    let mut replies = HashMap::new();
+    for target in targets {
+        if let Some(mac) = utils.get_mac_address_for_ip(IpAddr::V4(target)).await? {
+            replies.insert(target, mac);
         }
     }
 
-    tracing::trace!(
-        "No DHCP response from {} after broadcast and unicast attempts",
-        ip
-    );
-    Ok(None)
+    Ok(replies)
 }
 
-/// Helper function to wait for and validate DHCP responses (checks multiple times)
-async fn wait_for_dhcp_responses(
-    socket: &UdpSocket,
-    expected_ip: IpAddr,
-    expected_xid: u32,
-    method: &str,
-    max_attempts: usize,
-) -> Result<Option<u16>, Error> {
-    let mut response_buf = [0u8; 1500];
-
-    for attempt in 1..=max_attempts {
-        match timeout(
-            Duration::from_millis(2000), // Longer timeout per attempt
-            socket.recv_from(&mut response_buf),
-        )
-        .await
-        {
-            Ok(Ok((len, from))) => {
-                if len == 0 {
-                    continue;
-                }
+/// MAC address of one host, sending a datagram to its discard port first if the OS hasn't
+/// resolved it yet. No other port is touched, so exclusions can be checked before a port scan.
+pub async fn resolve_mac(
+    utils: &PlatformDaemonUtils,
+    ip: IpAddr,
+) -> Result<Option<MacAddress>, Error> {
+    if let Some(mac) = utils.get_mac_address_for_ip(ip).await? {
+        return Ok(Some(mac));
+    }
 
-                let response_ip = from.ip();
-
-                tracing::trace!(
-                    "Received {} byte DHCP {} response (attempt {}/{}) from {}",
-                    len,
-                    method,
-                    attempt,
-                    max_attempts,
-                    response_ip
-                );
-
-                // Check if response came from the IP we're testing
-                if response_ip != expected_ip {
-                    tracing::trace!(
-                        "Response from {} (testing {}), checking next response...",
-                        response_ip,
-                        expected_ip
-                    );
-                    continue; // Keep trying - might get another response
-                }
+    let unspecified: IpAddr = match ip {
+        IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    };
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    let _ = socket.send_to(&[], (ip, ARP_TRIGGER_PORT)).await;
+    tokio::time::sleep(ARP_RESOLVE_WAIT).await;
 
-                // Parse and validate DHCP message
-                match Message::decode(&mut dhcproto::Decoder::new(&response_buf[..len])) {
-                    Ok(response_msg) => {
-                        tracing::trace!(
-                            "Parsed DHCP response - xid: {:#x} (expected {:#x}), op: {:?}",
-                            response_msg.xid(),
-                            expected_xid,
-                            response_msg.opcode()
-                        );
+    utils.get_mac_address_for_ip(ip).await
+}
 
-                        // Verify transaction ID matches
-                        if response_msg.xid() != expected_xid {
-                            tracing::trace!("XID mismatch, checking next response...");
-                            continue;
-                        }
+/// Every host address in `cidr` other than the daemon's own
+fn sweep_targets(cidr: Ipv4Cidr, source_ip: Ipv4Addr) -> impl Iterator<Item = Ipv4Addr> {
+    let has_broadcast = cidr.network_length() < 31;
+    let (network, broadcast) = (cidr.first_address(), cidr.last_address());
 
-                        // Check for valid DHCP response type
-                        let message_type = response_msg.opts().iter().find_map(|(_, opt)| {
-                            if let v4::DhcpOption::MessageType(msg_type) = opt {
-                                Some(msg_type)
-                            } else {
-                                None
-                            }
-                        });
+    cidr.iter().map(|inet| inet.address()).filter(move |ip| {
+        *ip != source_ip && !(has_broadcast && (*ip == network || *ip == broadcast))
+    })
+}
 
-                        tracing::trace!("DHCP message type: {:?}", message_type);
+fn arp_request(source_mac: MacAddr, source_ip: Ipv4Addr, target_ip: Ipv4Addr) -> [u8; 42] {
+    let mut frame = [0u8; 42];
+    {
+        let mut ethernet =
+            MutableEthernetPacket::new(&mut frame).expect("Buffer fits an ethernet header");
+        ethernet.set_destination(MacAddr::broadcast());
+        ethernet.set_source(source_mac);
+        ethernet.set_ethertype(EtherTypes::Arp);
+
+        let mut arp =
+            MutableArpPacket::new(ethernet.payload_mut()).expect("Buffer fits an ARP packet");
+        arp.set_hardware_type(ArpHardwareTypes::Ethernet);
+        arp.set_protocol_type(EtherTypes::Ipv4);
+        arp.set_hw_addr_len(6);
+        arp.set_proto_addr_len(4);
+        arp.set_operation(ArpOperations::Request);
+        arp.set_sender_hw_addr(source_mac);
+        arp.set_sender_proto_addr(source_ip);
+        arp.set_target_hw_addr(MacAddr::zero());
+        arp.set_target_proto_addr(target_ip);
+    }
+    frame
+}
 
-                        let is_valid = matches!(
-                            message_type,
-                            Some(&MessageType::Offer) | Some(&MessageType::Ack)
-                        );
+/// Sender of an ARP reply addressed to `source_ip`
+fn parse_arp_reply(frame: &[u8], source_ip: Ipv4Addr) -> Option<(Ipv4Addr, MacAddress)> {
+    let ethernet = EthernetPacket::new(frame)?;
+    if ethernet.get_ethertype() != EtherTypes::Arp {
+        return None;
+    }
 
-                        if is_valid {
-                            tracing::info!(
-                                "✓ DHCP server detected at {}:67 via {} method (response type: {:?})",
-                                expected_ip,
-                                method,
-                                message_type
-                            );
-                            return Ok(Some(67));
-                        } else {
-                            tracing::trace!(
-                                "Invalid DHCP response type, checking next response..."
-                            );
-                            continue;
-                        }
-                    }
-                    Err(e) => {
-                        tracing::trace!("Failed to parse DHCP response: {}, checking next...", e);
-                        continue;
-                    }
-                }
-            }
-            Ok(Err(e)) => {
-                tracing::trace!(
-                    "Error receiving DHCP {} response (attempt {}): {}",
-                    method,
-                    attempt,
-                    e
-                );
-                break; // Socket error, no point continuing
-            }
-            Err(_) => {
-                tracing::trace!(
-                    "DHCP {} timeout on attempt {}/{}",
-                    method,
-                    attempt,
-                    max_attempts
-                );
-                // Timeout - continue to next attempt
-            }
+    let arp = ArpPacket::new(ethernet.payload())?;
+    (arp.get_operation() == ArpOperations::Reply && arp.get_target_proto_addr() == source_ip).then(
+        || {
+            (
+                arp.get_sender_proto_addr(),
+                MacAddress::new(arp.get_sender_hw_addr().octets()),
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_reply_to_own_request() {
+        let daemon_mac = MacAddr::new(0x02, 0, 0, 0, 0, 1);
+        let device_mac = MacAddr::new(0x02, 0, 0, 0, 0, 2);
+        let daemon_ip = Ipv4Addr::new(192, 168, 1, 10);
+        let device_ip = Ipv4Addr::new(192, 168, 1, 20);
+
+        let mut frame = arp_request(daemon_mac, daemon_ip, device_ip);
+        assert_eq!(parse_arp_reply(&frame, daemon_ip), None);
+
+        // Turn the request into the device's reply
+        {
+            let mut ethernet = MutableEthernetPacket::new(&mut frame).unwrap();
+            let mut arp = MutableArpPacket::new(ethernet.payload_mut()).unwrap();
+            arp.set_operation(ArpOperations::Reply);
+            arp.set_sender_hw_addr(device_mac);
+            arp.set_sender_proto_addr(device_ip);
+            arp.set_target_hw_addr(daemon_mac);
+            arp.set_target_proto_addr(daemon_ip);
         }
+
+        assert_eq!(
+            parse_arp_reply(&frame, daemon_ip),
+            Some((device_ip, MacAddress::new(device_mac.octets())))
+        );
+        assert_eq!(
+            parse_arp_reply(&frame, Ipv4Addr::new(192, 168, 1, 11)),
+            None
+        );
     }
 
-    Ok(None)
+    #[test]
+    fn sweep_skips_own_network_and_broadcast_addresses() {
+        let cidr: Ipv4Cidr = "10.0.0.0/29".parse().unwrap();
+        let targets: Vec<Ipv4Addr> = sweep_targets(cidr, Ipv4Addr::new(10, 0, 0, 1)).collect();
+
+        assert_eq!(targets.len(), 5);
+        assert_eq!(targets.first(), Some(&Ipv4Addr::new(10, 0, 0, 2)));
+        assert_eq!(targets.last(), Some(&Ipv4Addr::new(10, 0, 0, 6)));
+    }
 }
@@ -0,0 +1,312 @@
+use crate::daemon::utils::udp_probes::snmp::COMMUNITY;
+use crate::server::hosts::r#impl::snmp::{HostSnmp, LldpNeighbor, SnmpInterface, SnmpPortMapping};
+use anyhow::{Error, anyhow};
+use mac_address::MacAddress;
+use snmp2::{AsyncSession, Oid, Value};
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::time::timeout;
+
+const SNMP_PORT: u16 = 161;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REPETITIONS: u32 = 25;
+
+/// Stop walking a table after this many cells, so a core switch can't stall a scan
+const MAX_CELLS: usize = 8192;
+
+const SYS_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 1, 0];
+const SYS_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 5, 0];
+const IF_ENTRY: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1];
+const IP_NET_TO_MEDIA_ENTRY: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 22, 1];
+const LLDP_LOC_PORT_ENTRY: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 3, 7, 1];
+const LLDP_REM_ENTRY: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1];
+
+/// Owned copy of a varbind value, since replies borrow the session's buffer
+#[derive(Debug, Clone, PartialEq)]
+enum SnmpValue {
+    Integer(i64),
+    Bytes(Vec<u8>),
+    Unsigned(u64),
+    Other,
+}
+
+impl From<&Value<'_>> for SnmpValue {
+    fn from(value: &Value<'_>) -> Self {
+        match value {
+            Value::Integer(i) => Self::Integer(*i),
+            Value::OctetString(bytes) | Value::Opaque(bytes) => Self::Bytes(bytes.to_vec()),
+            Value::IpAddress(octets) => Self::Bytes(octets.to_vec()),
+            Value::Counter32(n) | Value::Unsigned32(n) | Value::Timeticks(n) => {
+                Self::Unsigned(*n as u64)
+            }
+            Value::Counter64(n) => Self::Unsigned(*n),
+            _ => Self::Other,
+        }
+    }
+}
+
+impl SnmpValue {
+    fn as_string(&self) -> Option<String> {
+        match self {
+            Self::Bytes(bytes) => {
+                Some(String::from_utf8_lossy(bytes).trim().to_string()).filter(|s| !s.is_empty())
+            }
+            _ => None,
+        }
+    }
+
+    fn as_mac(&self) -> Option<MacAddress> {
+        match self {
+            Self::Bytes(bytes) => <[u8; 6]>::try_from(bytes.as_slice())
+                .ok()
+                .filter(|b| b.iter().any(|octet| *octet != 0))
+                .map(MacAddress::new),
+            _ => None,
+        }
+    }
+}
+
+/// Table cell from a walk: column number, row index and value
+type Cell = (u64, Vec<u64>, SnmpValue);
+
+/// Walk the system group, ifTable, ipNetToMediaTable and LLDP-MIB of the agent at `ip`. Only
+/// the system group is required; agents without the other MIBs yield empty lists.
+pub async fn walk(ip: IpAddr) -> Result<HostSnmp, Error> {
+    let mut session = AsyncSession::new_v2c(SocketAddr::new(ip, SNMP_PORT), COMMUNITY, 0).await?;
+
+    let sys_descr = get_string(&mut session, SYS_DESCR).await?;
+    let sys_name = get_string(&mut session, SYS_NAME).await.unwrap_or_default();
+
+    let if_cells = walk_table(&mut session, IF_ENTRY).await?;
+    let ip_net_to_media_cells = optional_table(&mut session, ip, IP_NET_TO_MEDIA_ENTRY).await;
+    let lldp_loc_cells = optional_table(&mut session, ip, LLDP_LOC_PORT_ENTRY).await;
+    let lldp_rem_cells = optional_table(&mut session, ip, LLDP_REM_ENTRY).await;
+
+    Ok(HostSnmp {
+        sys_name,
+        sys_descr,
+        interfaces: interfaces(&if_cells),
+        port_map: port_map(&ip_net_to_media_cells),
+        lldp_neighbors: lldp_neighbors(&lldp_loc_cells, &lldp_rem_cells),
+        collected_at: chrono::Utc::now(),
+    })
+}
+
+fn oid(arcs: &[u64]) -> Result<Oid<'static>, Error> {
+    Oid::from(arcs).map_err(|e| anyhow!("Invalid OID {:?}: {:?}", arcs, e))
+}
+
+async fn get_string(session: &mut AsyncSession, arcs: &[u64]) -> Result<Option<String>, Error> {
+    let name = oid(arcs)?;
+    let pdu = timeout(REQUEST_TIMEOUT, session.get(&name))
+        .await
+        .map_err(|_| anyhow!("SNMP get timed out"))?
+        .map_err(|e| anyhow!("SNMP get failed: {:?}", e))?;
+
+    Ok(pdu
+        .varbinds
+        .into_iter()
+        .find_map(|(_, value)| SnmpValue::from(&value).as_string()))
+}
+
+async fn optional_table(session: &mut AsyncSession, ip: IpAddr, entry: &[u64]) -> Vec<Cell> {
+    walk_table(session, entry).await.unwrap_or_else(|e| {
+        tracing::debug!("SNMP walk of {:?} on {} failed: {}", entry, ip, e);
+        Vec::new()
+    })
+}
+
+/// GETBULK through every column of the table whose entry OID is `entry`
+async fn walk_table(session: &mut AsyncSession, entry: &[u64]) -> Result<Vec<Cell>, Error> {
+    let mut cells = Vec::new();
+    let mut next = entry.to_vec();
+
+    while cells.len() < MAX_CELLS {
+        let name = oid(&next)?;
+        let pdu = timeout(
+            REQUEST_TIMEOUT,
+            session.getbulk(&[&name], 0, MAX_REPETITIONS),
+        )
+        .await
+        .map_err(|_| anyhow!("SNMP getbulk timed out"))?
+        .map_err(|e| anyhow!("SNMP getbulk failed: {:?}", e))?;
+
+        let mut advanced = false;
+        for (name, value) in pdu.varbinds {
+            let Some(arcs) = name.iter().map(|arcs| arcs.collect::<Vec<u64>>()) else {
+                return Ok(cells);
+            };
+
+            // Past the end of the table, or an agent returning OIDs out of order
+            if !arcs.starts_with(entry)
+                || arcs.len() < entry.len() + 2
+                || arcs <= next
+                || matches!(value, Value::EndOfMibView)
+            {
+                return Ok(cells);
+            }
+
+            cells.push((
+                arcs[entry.len()],
+                arcs[entry.len() + 1..].to_vec(),
+                SnmpValue::from(&value),
+            ));
+            next = arcs;
+            advanced = true;
+        }
+
+        if !advanced {
+            break;
+        }
+    }
+
+    Ok(cells)
+}
+
+/// ifTable rows, indexed by ifIndex
+fn interfaces(cells: &[Cell]) -> Vec<SnmpInterface> {
+    let mut interfaces: BTreeMap<u32, SnmpInterface> = BTreeMap::new();
+
+    for (column, index, value) in cells {
+        let [if_index] = index.as_slice() else {
+            continue;
+        };
+        let interface = interfaces
+            .entry(*if_index as u32)
+            .or_insert_with(|| SnmpInterface {
+                index: *if_index as u32,
+                description: None,
+                mac_address: None,
+                speed: None,
+                oper_up: false,
+            });
+
+        match (column, value) {
+            (2, value) => interface.description = value.as_string(),
+            (5, SnmpValue::Unsigned(speed)) => interface.speed = Some(*speed),
+            (6, value) => interface.mac_address = value.as_mac(),
+            (8, SnmpValue::Integer(status)) => interface.oper_up = *status == 1,
+            _ => {}
+        }
+    }
+
+    interfaces.into_values().collect()
+}
+
+/// ipNetToMediaPhysAddress cells, indexed by ifIndex and the IPv4 address
+fn port_map(cells: &[Cell]) -> Vec<SnmpPortMapping> {
+    cells
+        .iter()
+        .filter(|(column, _, _)| *column == 2)
+        .filter_map(|(_, index, value)| {
+            let [if_index, a, b, c, d] = index.as_slice() else {
+                return None;
+            };
+            let octets = [*a, *b, *c, *d].map(|o| u8::try_from(o).ok());
+            let [Some(a), Some(b), Some(c), Some(d)] = octets else {
+                return None;
+            };
+
+            Some(SnmpPortMapping {
+                if_index: *if_index as u32,
+                ip_address: Ipv4Addr::new(a, b, c, d),
+                mac_address: value.as_mac()?,
+            })
+        })
+        .collect()
+}
+
+/// lldpRemTable rows, indexed by time mark, local port and remote index, with the local port
+/// descriptions from lldpLocPortTable
+fn lldp_neighbors(loc_cells: &[Cell], rem_cells: &[Cell]) -> Vec<LldpNeighbor> {
+    let local_ports: BTreeMap<u64, String> = loc_cells
+        .iter()
+        .filter(|(column, _, _)| *column == 4)
+        .filter_map(|(_, index, value)| Some((*index.first()?, value.as_string()?)))
+        .collect();
+
+    let mut neighbors: BTreeMap<(u64, u64), LldpNeighbor> = BTreeMap::new();
+
+    for (column, index, value) in rem_cells {
+        let [_time_mark, local_port, rem_index] = index.as_slice() else {
+            continue;
+        };
+        let neighbor = neighbors
+            .entry((*local_port, *rem_index))
+            .or_insert_with(|| LldpNeighbor {
+                local_port: *local_port as u32,
+                local_port_description: local_ports.get(local_port).cloned(),
+                chassis_id: String::new(),
+                port_id: String::new(),
+                port_description: None,
+                system_name: None,
+            });
+
+        match (column, value) {
+            (5, SnmpValue::Bytes(bytes)) => neighbor.chassis_id = format_id(bytes),
+            (7, SnmpValue::Bytes(bytes)) => neighbor.port_id = format_id(bytes),
+            (8, value) => neighbor.port_description = value.as_string(),
+            (9, value) => neighbor.system_name = value.as_string(),
+            _ => {}
+        }
+    }
+
+    neighbors.into_values().collect()
+}
+
+/// LLDP chassis and port IDs are usually MAC addresses or interface names
+fn format_id(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) if !s.is_empty() && s.chars().all(|c| c.is_ascii_graphic() || c == ' ') => {
+            s.to_string()
+        }
+        _ => match <[u8; 6]>::try_from(bytes) {
+            Ok(mac) => MacAddress::new(mac).to_string(),
+            Err(_) => hex::encode(bytes),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_port_map_and_lldp_neighbors_from_table_cells() {
+        let mac = vec![0x00, 0x11, 0x32, 0xaa, 0xbb, 0xcc];
+
+        let port_map = port_map(&[
+            (2, vec![3, 192, 168, 1, 20], SnmpValue::Bytes(mac.clone())),
+            // ipNetToMediaType, not an address
+            (4, vec![3, 192, 168, 1, 20], SnmpValue::Integer(3)),
+        ]);
+        assert_eq!(
+            port_map,
+            vec![SnmpPortMapping {
+                if_index: 3,
+                ip_address: Ipv4Addr::new(192, 168, 1, 20),
+                mac_address: MacAddress::new([0x00, 0x11, 0x32, 0xaa, 0xbb, 0xcc]),
+            }]
+        );
+
+        let neighbors = lldp_neighbors(
+            &[(4, vec![7], SnmpValue::Bytes(b"Port 7".to_vec()))],
+            &[
+                (5, vec![0, 7, 1], SnmpValue::Bytes(mac)),
+                (7, vec![0, 7, 1], SnmpValue::Bytes(b"eth0".to_vec())),
+                (9, vec![0, 7, 1], SnmpValue::Bytes(b"nas".to_vec())),
+            ],
+        );
+        assert_eq!(neighbors.len(), 1);
+        assert_eq!(
+            neighbors[0].local_port_description.as_deref(),
+            Some("Port 7")
+        );
+        assert_eq!(neighbors[0].chassis_id, "00:11:32:AA:BB:CC");
+        assert_eq!(neighbors[0].port_id, "eth0");
+        assert_eq!(neighbors[0].system_name.as_deref(), Some("nas"));
+    }
+}
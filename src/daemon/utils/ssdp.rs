@@ -0,0 +1,104 @@
+use anyhow::Error;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::{
+    net::UdpSocket,
+    time::{Instant, timeout},
+};
+
+const SSDP_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// Seconds devices may wait before answering an M-SEARCH
+const MX_SECS: u64 = 2;
+
+/// Probes sent per search, since a single multicast datagram is easily dropped
+const SEARCH_ATTEMPTS: usize = 2;
+
+const DESCRIPTION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Multicast an M-SEARCH for all devices from `local_ip` and collect replies. Returns the
+/// device description URL (`LOCATION`) announced by each responding host.
+pub async fn search(local_ip: Ipv4Addr) -> Result<HashMap<IpAddr, String>, Error> {
+    let socket = UdpSocket::bind((local_ip, 0)).await?;
+    socket.set_multicast_ttl_v4(2)?;
+
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: ssdp:all\r\n\r\n",
+        SSDP_ADDR, MX_SECS
+    );
+    for _ in 0..SEARCH_ATTEMPTS {
+        socket.send_to(request.as_bytes(), SSDP_ADDR).await?;
+    }
+
+    let mut locations = HashMap::new();
+    let deadline = Instant::now() + Duration::from_secs(MX_SECS + 1);
+    let mut buf = [0u8; 2048];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        match timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(Ok((len, from))) => {
+                // Devices answer once per service they offer; the root description is enough
+                if let Some(location) = location_header(&String::from_utf8_lossy(&buf[..len])) {
+                    locations.entry(from.ip()).or_insert(location);
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::trace!("Error receiving SSDP reply on {}: {}", local_ip, e);
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+
+    Ok(locations)
+}
+
+/// Fetch the device description XML a device announced
+pub async fn fetch_description(client: &reqwest::Client, location: &str) -> Result<String, Error> {
+    let response = client
+        .get(location)
+        .timeout(DESCRIPTION_TIMEOUT)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch device description from {}: HTTP {}",
+            location,
+            response.status()
+        );
+    }
+
+    Ok(response.text().await?)
+}
+
+fn location_header(reply: &str) -> Option<String> {
+    reply.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim()
+            .eq_ignore_ascii_case("location")
+            .then(|| value.trim().to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_location_from_search_reply() {
+        let reply = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nLocation: http://192.168.1.1:5000/rootDesc.xml\r\nST: upnp:rootdevice\r\n\r\n";
+
+        assert_eq!(
+            location_header(reply).as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+        assert_eq!(location_header("HTTP/1.1 200 OK\r\n\r\n"), None);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::server::{
+    hosts::r#impl::{
+        ports::TransportProtocol,
+        ssh::{HostSsh, ListeningSocket, SshContainer, SshInterface},
+    },
+    ssh_credentials::r#impl::base::SshLogin,
+};
+use anyhow::{Error, anyhow, bail};
+use async_trait::async_trait;
+use chrono::Utc;
+use mac_address::MacAddress;
+use russh::{ChannelMsg, Disconnect, client};
+use russh_keys::key::PublicKey;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::timeout;
+
+/// Login, commands and reading their output together, so a stuck host can't stall a scan
+const SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_OUTPUT: usize = 1 << 20;
+
+/// One command per section, each announced by a marker line. Every command may be missing
+/// or fail, e.g. docker on hosts without it or for users outside the docker group.
+const INVENTORY_SCRIPT: &str = "export LC_ALL=C PATH=\"$PATH:/usr/sbin:/sbin\"
+echo '@@hostname'; hostname 2>/dev/null
+echo '@@os-release'; cat /etc/os-release 2>/dev/null
+echo '@@uname'; uname -sr 2>/dev/null
+echo '@@ss'; ss -lntupH 2>/dev/null
+echo '@@docker'; docker ps --format '{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Ports}}' 2>/dev/null
+echo '@@addr'; ip -o addr show 2>/dev/null
+echo '@@link'; ip -o link show 2>/dev/null
+true";
+
+struct Client {
+    expected_fingerprint: Option<String>,
+    fingerprint: Arc<Mutex<Option<String>>>,
+}
+
+#[async_trait]
+impl client::Handler for Client {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKey,
+    ) -> Result<bool, Self::Error> {
+        let fingerprint = format!("SHA256:{}", server_public_key.fingerprint());
+        let accepted = self
+            .expected_fingerprint
+            .as_deref()
+            .is_none_or(|expected| same_fingerprint(expected, &fingerprint));
+
+        if let Ok(mut presented) = self.fingerprint.lock() {
+            *presented = Some(fingerprint);
+        }
+        Ok(accepted)
+    }
+}
+
+fn same_fingerprint(expected: &str, presented: &str) -> bool {
+    let strip = |fingerprint: &str| {
+        let fingerprint = fingerprint.trim();
+        fingerprint
+            .strip_prefix("SHA256:")
+            .unwrap_or(fingerprint)
+            .trim_end_matches('=')
+            .to_string()
+    };
+    strip(expected) == strip(presented)
+}
+
+/// Log into the host and read its inventory
+pub async fn collect(ip: IpAddr, login: &SshLogin) -> Result<HostSsh, Error> {
+    let fingerprint = Arc::new(Mutex::new(None));
+    let expected = login.expected_host_key(ip);
+    let client = Client {
+        expected_fingerprint: expected.map(str::to_string),
+        fingerprint: fingerprint.clone(),
+    };
+
+    let output = timeout(SESSION_TIMEOUT, run_inventory(ip, login, client))
+        .await
+        .map_err(|_| anyhow!("SSH session timed out"))?
+        .map_err(|e| {
+            let presented = fingerprint.lock().ok().and_then(|f| f.clone());
+            match (expected, presented) {
+                (Some(expected), Some(presented)) if !same_fingerprint(expected, &presented) => {
+                    anyhow!("Host key {} doesn't match {}", presented, expected)
+                }
+                _ => e,
+            }
+        })?;
+
+    let host_key_fingerprint = fingerprint
+        .lock()
+        .ok()
+        .and_then(|f| f.clone())
+        .unwrap_or_default();
+
+    Ok(parse_inventory(&output, host_key_fingerprint))
+}
+
+async fn run_inventory(ip: IpAddr, login: &SshLogin, client: Client) -> Result<String, Error> {
+    let config = Arc::new(client::Config::default());
+    let mut session = client::connect(config, (ip, login.port), client).await?;
+
+    let authenticated = match &login.private_key {
+        Some(key) => {
+            let key = russh_keys::decode_secret_key(key, login.passphrase.as_deref())?;
+            session
+                .authenticate_publickey(&login.username, Arc::new(key))
+                .await?
+        }
+        None => {
+            session
+                .authenticate_password(
+                    &login.username,
+                    login.password.as_deref().unwrap_or_default(),
+                )
+                .await?
+        }
+    };
+    if !authenticated {
+        bail!("Login as {} was refused", login.username);
+    }
+
+    let mut channel = session.channel_open_session().await?;
+    channel.exec(true, INVENTORY_SCRIPT).await?;
+
+    let mut output = Vec::new();
+    while let Some(message) = channel.wait().await {
+        if let ChannelMsg::Data { ref data } = message {
+            output.extend_from_slice(data);
+            if output.len() > MAX_OUTPUT {
+                bail!("Inventory output is larger than {} bytes", MAX_OUTPUT);
+            }
+        }
+    }
+
+    let _ = session
+        .disconnect(Disconnect::ByApplication, "", "en")
+        .await;
+
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// Split the script's output into its sections and parse each
+pub fn parse_inventory(output: &str, host_key_fingerprint: String) -> HostSsh {
+    let mut sections: HashMap<&str, String> = HashMap::new();
+    let mut current = None;
+
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("@@") {
+            current = Some(name.trim());
+            continue;
+        }
+        if let Some(name) = current {
+            let section = sections.entry(name).or_default();
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+
+    let section = |name: &str| sections.get(name).map(String::as_str).unwrap_or_default();
+    let first_line = |name: &str| {
+        section(name)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+
+    HostSsh {
+        hostname: first_line("hostname"),
+        os: parse_os_release(section("os-release")),
+        kernel: first_line("uname"),
+        listening: parse_ss(section("ss")),
+        containers: parse_docker_ps(section("docker")),
+        interfaces: parse_ip(section("addr"), section("link")),
+        host_key_fingerprint,
+        collected_at: Utc::now(),
+    }
+}
+
+/// PRETTY_NAME of /etc/os-release, or NAME and VERSION when it has none
+pub fn parse_os_release(text: &str) -> Option<String> {
+    let values: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches(['"', '\''])))
+        .collect();
+
+    if let Some(pretty) = values.get("PRETTY_NAME").filter(|v| !v.is_empty()) {
+        return Some(pretty.to_string());
+    }
+
+    let name = values.get("NAME").filter(|v| !v.is_empty())?;
+    Some(match values.get("VERSION").filter(|v| !v.is_empty()) {
+        Some(version) => format!("{} {}", name, version),
+        None => name.to_string(),
+    })
+}
+
+/// Lines of `ss -lntupH`, e.g.
+/// `tcp LISTEN 0 4096 0.0.0.0:22 0.0.0.0:* users:(("sshd",pid=812,fd=3))`
+pub fn parse_ss(text: &str) -> Vec<ListeningSocket> {
+    let mut sockets: Vec<ListeningSocket> = Vec::new();
+
+    for line in text.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (Some(netid), Some(local)) = (tokens.first(), tokens.get(4)) else {
+            continue;
+        };
+        let protocol = match *netid {
+            "tcp" => TransportProtocol::Tcp,
+            "udp" => TransportProtocol::Udp,
+            _ => continue,
+        };
+
+        let Some((address, port)) = local.rsplit_once(':') else {
+            continue;
+        };
+        let Ok(port) = port.parse::<u16>() else {
+            continue;
+        };
+        // Sockets bound to an interface read e.g. 127.0.0.53%lo
+        let address = address.split('%').next().unwrap_or_default();
+        let address = match address.trim_matches(['[', ']']) {
+            "*" => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            address => match address.parse::<IpAddr>() {
+                Ok(address) => address.to_canonical(),
+                Err(_) => continue,
+            },
+        };
+
+        let process = tokens[5..]
+            .iter()
+            .find_map(|token| token.strip_prefix("users:((\""))
+            .and_then(|users| users.split('"').next())
+            .map(str::to_string);
+
+        let socket = ListeningSocket {
+            protocol,
+            address,
+            port,
+            process,
+        };
+        if !sockets.contains(&socket) {
+            sockets.push(socket);
+        }
+    }
+
+    sockets
+}
+
+/// Lines of `docker ps` formatted as id, name, image and ports separated by tabs
+pub fn parse_docker_ps(text: &str) -> Vec<SshContainer> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let id = fields.next()?.trim();
+            let name = fields.next()?.trim();
+            let image = fields.next()?.trim();
+            if id.is_empty() {
+                return None;
+            }
+
+            Some(SshContainer {
+                id: id.to_string(),
+                name: name.to_string(),
+                image: image.to_string(),
+                ports: fields
+                    .next()
+                    .unwrap_or_default()
+                    .split(", ")
+                    .map(str::trim)
+                    .filter(|port| !port.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Interfaces from `ip -o link show`, with their addresses from `ip -o addr show`. The
+/// loopback interface is left out.
+pub fn parse_ip(addr: &str, link: &str) -> Vec<SshInterface> {
+    let interface_name = |token: &str| {
+        // Interfaces in another namespace read e.g. veth1a2b@if5
+        let name = token.trim_end_matches(':');
+        name.split('@').next().unwrap_or(name).to_string()
+    };
+
+    let mut interfaces: Vec<SshInterface> = link
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let name = interface_name(tokens.get(1)?);
+            let mac_address = tokens
+                .iter()
+                .position(|token| *token == "link/ether")
+                .and_then(|index| tokens.get(index + 1))
+                .and_then(|mac| mac.parse::<MacAddress>().ok());
+
+            Some(SshInterface {
+                name,
+                mac_address,
+                addresses: Vec::new(),
+            })
+        })
+        .filter(|interface| interface.name != "lo")
+        .collect();
+
+    for line in addr.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (Some(name), Some(family), Some(address)) =
+            (tokens.get(1), tokens.get(2), tokens.get(3))
+        else {
+            continue;
+        };
+        if !matches!(*family, "inet" | "inet6") {
+            continue;
+        }
+
+        let name = interface_name(name);
+        if let Some(interface) = interfaces.iter_mut().find(|i| i.name == name) {
+            interface.addresses.push(address.to_string());
+        }
+    }
+
+    interfaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_os_release() {
+        let debian = "PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nNAME=\"Debian GNU/Linux\"\nVERSION_ID=\"12\"\n";
+        assert_eq!(
+            parse_os_release(debian).as_deref(),
+            Some("Debian GNU/Linux 12 (bookworm)")
+        );
+        assert_eq!(
+            parse_os_release("NAME=Alpine\nVERSION=3.19\n").as_deref(),
+            Some("Alpine 3.19")
+        );
+        assert_eq!(parse_os_release(""), None);
+    }
+
+    #[test]
+    fn parses_listening_sockets() {
+        let ss = "\
+tcp   LISTEN 0      4096         0.0.0.0:22        0.0.0.0:*    users:((\"sshd\",pid=812,fd=3))
+tcp   LISTEN 0      4096            [::]:22           [::]:*    users:((\"sshd\",pid=812,fd=4))
+tcp   LISTEN 0      511        127.0.0.1:6379      0.0.0.0:*
+udp   UNCONN 0      0      127.0.0.53%lo:53        0.0.0.0:*    users:((\"systemd-resolve\",pid=501,fd=13))
+tcp   LISTEN 0      128                *:8080            *:*    users:((\"java\",pid=2001,fd=40))
+";
+        let sockets = parse_ss(ss);
+
+        assert_eq!(sockets.len(), 5);
+        assert_eq!(
+            sockets[0],
+            ListeningSocket {
+                protocol: TransportProtocol::Tcp,
+                address: "0.0.0.0".parse().unwrap(),
+                port: 22,
+                process: Some("sshd".to_string()),
+            }
+        );
+        assert_eq!(sockets[1].address, "::".parse::<IpAddr>().unwrap());
+        assert_eq!(sockets[2].process, None);
+        assert!(!sockets[2].is_exposed());
+        assert_eq!(sockets[3].protocol, TransportProtocol::Udp);
+        assert_eq!(sockets[3].address, "127.0.0.53".parse::<IpAddr>().unwrap());
+        assert!(sockets[4].is_exposed());
+    }
+
+    #[test]
+    fn parses_docker_ps() {
+        let docker = "3f2a1b\tgrafana\tgrafana/grafana:10.4.0\t0.0.0.0:3000->3000/tcp, :::3000->3000/tcp\n9c8d7e\tworker\tacme/worker\t\n";
+        let containers = parse_docker_ps(docker);
+
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[0].name, "grafana");
+        assert_eq!(
+            containers[0].ports,
+            vec!["0.0.0.0:3000->3000/tcp", ":::3000->3000/tcp"]
+        );
+        assert!(containers[1].ports.is_empty());
+    }
+
+    #[test]
+    fn parses_interfaces() {
+        let link = "\
+1: lo: <LOOPBACK,UP,LOWER_UP> mtu 65536 qdisc noqueue state UNKNOWN mode DEFAULT group default qlen 1000\\    link/loopback 00:00:00:00:00:00 brd 00:00:00:00:00:00
+2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UP mode DEFAULT group default qlen 1000\\    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff
+5: veth1a2b@if4: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc noqueue master docker0 state UP\\    link/ether 6a:1f:00:aa:bb:cc brd ff:ff:ff:ff:ff:ff link-netnsid 0
+";
+        let addr = "\
+1: lo    inet 127.0.0.1/8 scope host lo\\       valid_lft forever preferred_lft forever
+2: eth0    inet 192.168.1.10/24 brd 192.168.1.255 scope global dynamic eth0\\       valid_lft 86000sec preferred_lft 86000sec
+2: eth0    inet6 fe80::5054:ff:fe12:3456/64 scope link \\       valid_lft forever preferred_lft forever
+";
+        let interfaces = parse_ip(addr, link);
+
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].name, "eth0");
+        assert_eq!(
+            interfaces[0].mac_address,
+            Some("52:54:00:12:34:56".parse().unwrap())
+        );
+        assert_eq!(
+            interfaces[0].addresses,
+            vec!["192.168.1.10/24", "fe80::5054:ff:fe12:3456/64"]
+        );
+        assert_eq!(interfaces[1].name, "veth1a2b");
+        assert!(interfaces[1].addresses.is_empty());
+    }
+
+    #[test]
+    fn splits_inventory_sections() {
+        let output = "@@hostname\nweb-01\n@@os-release\nPRETTY_NAME=\"Ubuntu 24.04 LTS\"\n@@uname\nLinux 6.8.0-31-generic\n@@ss\n@@docker\n@@addr\n@@link\n";
+        let inventory = parse_inventory(output, "SHA256:abc".to_string());
+
+        assert_eq!(inventory.hostname.as_deref(), Some("web-01"));
+        assert_eq!(inventory.os.as_deref(), Some("Ubuntu 24.04 LTS"));
+        assert_eq!(inventory.kernel.as_deref(), Some("Linux 6.8.0-31-generic"));
+        assert!(inventory.listening.is_empty());
+        assert!(same_fingerprint("abc=", &inventory.host_key_fingerprint));
+    }
+}
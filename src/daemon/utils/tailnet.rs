@@ -0,0 +1,155 @@
+use anyhow::{Error, bail};
+use chrono::{DateTime, Utc};
+use cidr::{IpCidr, Ipv4Cidr};
+use serde::Deserialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::server::network_settings::r#impl::base::{TailnetProvider, TailnetSettings};
+
+/// Addresses Tailscale and Headscale hand out to tailnet devices (the CGNAT range)
+pub fn tailnet_cidr() -> IpCidr {
+    IpCidr::V4(Ipv4Cidr::new(Ipv4Addr::new(100, 64, 0, 0), 10).expect("Tailnet CIDR"))
+}
+
+/// A device as listed by the control server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TailnetDevice {
+    pub hostname: String,
+    pub addresses: Vec<IpAddr>,
+    pub node_key: Option<String>,
+    /// Addresses the device's client has reported it can be reached at, public and LAN
+    pub endpoints: Vec<SocketAddr>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct TailscaleDevices {
+    devices: Vec<TailscaleDevice>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TailscaleDevice {
+    hostname: String,
+    #[serde(default)]
+    addresses: Vec<IpAddr>,
+    #[serde(default)]
+    node_key: Option<String>,
+    #[serde(default)]
+    last_seen: Option<DateTime<Utc>>,
+    #[serde(default)]
+    client_connectivity: Option<TailscaleConnectivity>,
+}
+
+#[derive(Deserialize)]
+struct TailscaleConnectivity {
+    #[serde(default)]
+    endpoints: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct HeadscaleNodes {
+    nodes: Vec<HeadscaleNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeadscaleNode {
+    name: String,
+    #[serde(default)]
+    ip_addresses: Vec<IpAddr>,
+    #[serde(default)]
+    node_key: Option<String>,
+    #[serde(default)]
+    last_seen: Option<DateTime<Utc>>,
+}
+
+/// List the tailnet's devices from the Tailscale or Headscale API
+pub async fn fetch_devices(
+    client: &reqwest::Client,
+    settings: &TailnetSettings,
+) -> Result<Vec<TailnetDevice>, Error> {
+    let url = match settings.provider {
+        TailnetProvider::Tailscale => format!(
+            "{}/api/v2/tailnet/{}/devices?fields=all",
+            settings.base_url(),
+            settings.tailnet
+        ),
+        TailnetProvider::Headscale => format!("{}/api/v1/node", settings.base_url()),
+    };
+
+    let response = client
+        .get(&url)
+        .bearer_auth(&settings.api_key)
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        bail!(
+            "Failed to list {:?} devices: HTTP {}",
+            settings.provider,
+            response.status()
+        );
+    }
+
+    parse_devices(settings.provider, &response.text().await?)
+}
+
+pub fn parse_devices(provider: TailnetProvider, body: &str) -> Result<Vec<TailnetDevice>, Error> {
+    let devices = match provider {
+        TailnetProvider::Tailscale => serde_json::from_str::<TailscaleDevices>(body)?
+            .devices
+            .into_iter()
+            .map(|device| TailnetDevice {
+                hostname: device.hostname,
+                addresses: device.addresses,
+                node_key: device.node_key,
+                endpoints: device
+                    .client_connectivity
+                    .map(|c| c.endpoints)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|endpoint| endpoint.parse().ok())
+                    .collect(),
+                last_seen: device.last_seen,
+            })
+            .collect(),
+        // Headscale doesn't expose client endpoints
+        TailnetProvider::Headscale => serde_json::from_str::<HeadscaleNodes>(body)?
+            .nodes
+            .into_iter()
+            .map(|node| TailnetDevice {
+                hostname: node.name,
+                addresses: node.ip_addresses,
+                node_key: node.node_key,
+                endpoints: Vec::new(),
+                last_seen: node.last_seen,
+            })
+            .collect(),
+    };
+
+    Ok(devices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tailscale_devices() {
+        let body = r#"{"devices": [{
+            "hostname": "nas",
+            "addresses": ["100.101.102.103", "fd7a:115c:a1e0::1"],
+            "nodeKey": "nodekey:abc",
+            "lastSeen": "2024-05-01T10:00:00Z",
+            "clientConnectivity": {"endpoints": ["203.0.113.9:41641", "192.168.1.20:41641"]}
+        }]}"#;
+
+        let devices = parse_devices(TailnetProvider::Tailscale, body).unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].hostname, "nas");
+        assert_eq!(devices[0].addresses.len(), 2);
+        assert_eq!(devices[0].endpoints.len(), 2);
+        assert!(devices[0].last_seen.is_some());
+    }
+}
@@ -0,0 +1,124 @@
+use std::net::IpAddr;
+
+/// A line of `traceroute` or `tracert` output for one hop
+#[derive(Debug, Clone, PartialEq)]
+pub struct TracerouteHop {
+    pub hop: u8,
+    /// None when the hop didn't answer in time
+    pub ip: Option<IpAddr>,
+    pub rtt_ms: Option<f64>,
+}
+
+/// The system's traceroute with one probe per hop and numeric output, so each hop can be
+/// streamed as soon as its line is printed
+pub fn command(ip: IpAddr, max_hops: u8) -> (&'static str, Vec<String>) {
+    let max_hops = max_hops.to_string();
+
+    if cfg!(target_os = "windows") {
+        let family = if ip.is_ipv6() { "-6" } else { "-4" };
+        (
+            "tracert",
+            vec![
+                "-d".into(),
+                family.into(),
+                "-h".into(),
+                max_hops,
+                "-w".into(),
+                "1000".into(),
+                ip.to_string(),
+            ],
+        )
+    } else {
+        let program = if ip.is_ipv6() && cfg!(target_os = "macos") {
+            "traceroute6"
+        } else {
+            "traceroute"
+        };
+        let mut args = vec![
+            "-n".into(),
+            "-q".into(),
+            "1".into(),
+            "-w".into(),
+            "1".into(),
+            "-m".into(),
+            max_hops,
+        ];
+        if ip.is_ipv6() && program == "traceroute" {
+            args.push("-6".into());
+        }
+        args.push(ip.to_string());
+        (program, args)
+    }
+}
+
+/// Parse a hop line of Linux and macOS `traceroute` (` 3  10.0.0.1  5.123 ms`) or Windows
+/// `tracert` (`  3    12 ms    11 ms    <1 ms  10.0.0.1`). Headers and blank lines are None.
+pub fn parse_hop(line: &str) -> Option<TracerouteHop> {
+    let mut tokens = line.split_whitespace();
+    let hop: u8 = tokens.next()?.parse().ok()?;
+    let tokens: Vec<&str> = tokens.collect();
+
+    let ip = tokens
+        .iter()
+        .find_map(|token| token.trim_matches(['(', ')']).parse::<IpAddr>().ok());
+
+    let rtt_ms = tokens.iter().enumerate().find_map(|(index, token)| {
+        let value = match token.strip_suffix("ms") {
+            Some(value) if !value.is_empty() => value,
+            Some(_) if index > 0 => tokens[index - 1],
+            _ => return None,
+        };
+        value.trim_start_matches('<').parse::<f64>().ok()
+    });
+
+    Some(TracerouteHop {
+        hop,
+        ip,
+        rtt_ms: ip.and(rtt_ms),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_traceroute_and_tracert_hops() {
+        assert_eq!(
+            parse_hop("traceroute to 1.1.1.1 (1.1.1.1), 30 hops max"),
+            None
+        );
+        assert_eq!(
+            parse_hop(" 1  192.168.1.1  0.512 ms"),
+            Some(TracerouteHop {
+                hop: 1,
+                ip: Some("192.168.1.1".parse().unwrap()),
+                rtt_ms: Some(0.512),
+            })
+        );
+        assert_eq!(
+            parse_hop(" 2  *"),
+            Some(TracerouteHop {
+                hop: 2,
+                ip: None,
+                rtt_ms: None,
+            })
+        );
+        assert_eq!(
+            parse_hop("  3    <1 ms    <1 ms    <1 ms  10.0.0.1"),
+            Some(TracerouteHop {
+                hop: 3,
+                ip: Some("10.0.0.1".parse().unwrap()),
+                rtt_ms: Some(1.0),
+            })
+        );
+        assert_eq!(
+            parse_hop("  4     *        *        *     Request timed out."),
+            Some(TracerouteHop {
+                hop: 4,
+                ip: None,
+                rtt_ms: None,
+            })
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::daemon::utils::udp_probes::{UdpProbe, UdpProbeContext, UdpProbeFactory, create_probe};
+use anyhow::Error;
+use cidr::IpCidr;
+use dhcproto::{
+    Decoder, Encodable,
+    v4::{self, Decodable, DhcpOption, Encoder, Message, MessageType},
+};
+use rand::Rng;
+
+/// DHCP DISCOVER from a random client MAC, broadcast on the subnet and then sent unicast. Only
+/// gateways are probed, since a broadcast reaches every DHCP server on the subnet.
+#[derive(Default)]
+pub struct DhcpProbe;
+
+impl UdpProbe for DhcpProbe {
+    fn name(&self) -> &str {
+        "DHCP"
+    }
+
+    fn port(&self) -> u16 {
+        67
+    }
+
+    fn should_probe(&self, ctx: &UdpProbeContext) -> bool {
+        ctx.is_gateway && matches!(ctx.cidr, IpCidr::V4(_))
+    }
+
+    fn source_port(&self) -> Option<u16> {
+        Some(68)
+    }
+
+    fn broadcast(&self) -> bool {
+        true
+    }
+
+    fn build_payload(&self, _ctx: &UdpProbeContext) -> Result<Vec<u8>, Error> {
+        let mut rng = rand::rng();
+        let mac_addr: [u8; 6] = rng.random();
+
+        let mut msg = Message::default();
+        msg.set_opcode(v4::Opcode::BootRequest)
+            .set_htype(v4::HType::Eth)
+            .set_xid(rng.random())
+            .set_flags(v4::Flags::default().set_broadcast())
+            .set_chaddr(&mac_addr);
+
+        msg.opts_mut()
+            .insert(DhcpOption::MessageType(MessageType::Discover));
+        msg.opts_mut().insert(DhcpOption::ParameterRequestList(vec![
+            v4::OptionCode::SubnetMask,
+            v4::OptionCode::Router,
+            v4::OptionCode::DomainNameServer,
+            v4::OptionCode::DomainName,
+        ]));
+
+        let mut buf = Vec::new();
+        msg.encode(&mut Encoder::new(&mut buf))?;
+
+        Ok(buf)
+    }
+
+    fn validate(&self, request: &[u8], response: &[u8]) -> bool {
+        let (Ok(request), Ok(response)) = (
+            Message::decode(&mut Decoder::new(request)),
+            Message::decode(&mut Decoder::new(response)),
+        ) else {
+            return false;
+        };
+
+        response.xid() == request.xid()
+            && matches!(
+                response.opts().msg_type(),
+                Some(MessageType::Offer) | Some(MessageType::Ack)
+            )
+    }
+}
+
+inventory::submit!(UdpProbeFactory::new(create_probe::<DhcpProbe>));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// OFFER of 192.168.1.100 from 192.168.1.1: the fixed header up to the client hardware
+    /// address, then the empty server name and boot file, then the options
+    const OFFER_HEADER: &str = "02010600 a1b2c3d4 0000 8000";
+    const OFFER_ADDRESSES: &str =
+        "00000000 c0a80164 c0a80101 00000000 a1b2c3d4e5f600000000000000000000";
+    const OFFER_OPTIONS: &str =
+        "63825363 350102 3604c0a80101 330400015180 0104ffffff00 0304c0a80101 0604c0a80101 ff";
+
+    fn decode(spaced: &str) -> Vec<u8> {
+        hex::decode(spaced.replace(' ', "")).unwrap()
+    }
+
+    fn offer() -> Vec<u8> {
+        [
+            decode(OFFER_HEADER),
+            decode(OFFER_ADDRESSES),
+            vec![0; 64 + 128],
+            decode(OFFER_OPTIONS),
+        ]
+        .concat()
+    }
+
+    fn request() -> Vec<u8> {
+        DhcpProbe
+            .build_payload(&UdpProbeContext {
+                ip: "192.168.1.1".parse().unwrap(),
+                cidr: "192.168.1.0/24".parse().unwrap(),
+                is_gateway: true,
+            })
+            .unwrap()
+    }
+
+    /// The captured offer, answering `request`
+    fn reply_to(request: &[u8]) -> Vec<u8> {
+        let mut reply = offer();
+        reply[4..8].copy_from_slice(&request[4..8]);
+        reply
+    }
+
+    #[test]
+    fn accepts_offer_for_our_transaction() {
+        let request = request();
+        assert!(DhcpProbe.validate(&request, &reply_to(&request)));
+    }
+
+    #[test]
+    fn rejects_other_replies() {
+        let request = request();
+
+        let mut other_xid = reply_to(&request);
+        other_xid[4] ^= 0xff;
+        assert!(!DhcpProbe.validate(&request, &other_xid));
+
+        // Our own DISCOVER reflected back
+        assert!(!DhcpProbe.validate(&request, &request));
+
+        // Cut off before the options
+        assert!(!DhcpProbe.validate(&request, &reply_to(&request)[..100]));
+        assert!(!DhcpProbe.validate(&request, &[]));
+    }
+}
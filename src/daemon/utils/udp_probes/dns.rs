@@ -0,0 +1,103 @@
+use crate::daemon::utils::udp_probes::{UdpProbe, UdpProbeContext, UdpProbeFactory, create_probe};
+use anyhow::Error;
+use rand::Rng;
+use trust_dns_resolver::proto::{
+    op::{Message, MessageType, OpCode, Query},
+    rr::{Name, RecordType},
+    serialize::binary::BinEncodable,
+};
+
+/// Recursive A query; any reply with a matching ID means a DNS server is listening, even if it
+/// refuses to resolve for us
+#[derive(Default)]
+pub struct DnsProbe;
+
+impl UdpProbe for DnsProbe {
+    fn name(&self) -> &str {
+        "DNS"
+    }
+
+    fn port(&self) -> u16 {
+        53
+    }
+
+    fn build_payload(&self, _ctx: &UdpProbeContext) -> Result<Vec<u8>, Error> {
+        let mut message = Message::new();
+        message
+            .set_id(rand::rng().random())
+            .set_message_type(MessageType::Query)
+            .set_op_code(OpCode::Query)
+            .set_recursion_desired(true)
+            .add_query(Query::query(
+                Name::from_ascii("google.com.")?,
+                RecordType::A,
+            ));
+
+        Ok(message.to_bytes()?)
+    }
+
+    fn validate(&self, request: &[u8], response: &[u8]) -> bool {
+        Message::from_vec(response).is_ok_and(|message| {
+            message.message_type() == MessageType::Response
+                && request.get(..2) == Some(&message.id().to_be_bytes()[..])
+        })
+    }
+}
+
+inventory::submit!(UdpProbeFactory::new(create_probe::<DnsProbe>));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> UdpProbeContext {
+        UdpProbeContext {
+            ip: "192.168.1.1".parse().unwrap(),
+            cidr: "192.168.1.0/24".parse().unwrap(),
+            is_gateway: true,
+        }
+    }
+
+    /// Answer for google.com A with ID 0xabcd
+    const REPLY: &str =
+        "abcd8180000100010000000006676f6f676c6503636f6d0000010001c00c000100010000012c00048efab8ce";
+
+    /// The captured reply, answering `request`
+    fn reply_to(request: &[u8]) -> Vec<u8> {
+        let mut reply = hex::decode(REPLY).unwrap();
+        reply[..2].copy_from_slice(&request[..2]);
+        reply
+    }
+
+    #[test]
+    fn accepts_answers_and_refusals_with_our_id() {
+        let request = DnsProbe.build_payload(&context()).unwrap();
+        assert!(DnsProbe.validate(&request, &reply_to(&request)));
+
+        // REFUSED, without the question
+        let mut refused = reply_to(&request)[..12].to_vec();
+        refused[2..].copy_from_slice(&[0x81, 0x05, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(DnsProbe.validate(&request, &refused));
+    }
+
+    #[test]
+    fn rejects_other_replies() {
+        let request = DnsProbe.build_payload(&context()).unwrap();
+
+        let mut other_id = reply_to(&request);
+        other_id[0] ^= 0xff;
+        assert!(!DnsProbe.validate(&request, &other_id));
+
+        // Our own query reflected back
+        assert!(!DnsProbe.validate(&request, &request));
+
+        let reply = reply_to(&request);
+        for len in 0..reply.len() {
+            assert!(
+                !DnsProbe.validate(&request, &reply[..len]),
+                "truncated to {}",
+                len
+            );
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use anyhow::{Error, Result};
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    net::UdpSocket,
+    time::{Instant, timeout},
+};
+
+pub mod dhcp;
+pub mod dns;
+pub mod ntp;
+pub mod snmp;
+
+/// What a probe knows about the host it is sent to
+#[derive(Debug, Clone, Copy)]
+pub struct UdpProbeContext {
+    pub ip: IpAddr,
+    pub cidr: IpCidr,
+    pub is_gateway: bool,
+}
+
+/// Detects a UDP service by sending a payload to its port and validating the reply. Probes are
+/// registered with `inventory::submit!` like service definitions, or declared in the daemon
+/// config as `udp_probes`.
+pub trait UdpProbe: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn port(&self) -> u16;
+
+    /// Request sent to the host; called once per probed host
+    fn build_payload(&self, ctx: &UdpProbeContext) -> Result<Vec<u8>, Error>;
+
+    /// Whether `response` is a valid reply to `request`
+    fn validate(&self, request: &[u8], response: &[u8]) -> bool;
+
+    /// Whether the host should be probed at all
+    fn should_probe(&self, _ctx: &UdpProbeContext) -> bool {
+        true
+    }
+
+    /// Local port to send from, if the protocol requires one. Falls back to an ephemeral port
+    /// when it is taken.
+    fn source_port(&self) -> Option<u16> {
+        None
+    }
+
+    /// Send to the subnet broadcast address before falling back to unicast
+    fn broadcast(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UdpProbeFactory(pub fn() -> Box<dyn UdpProbe>);
+
+impl UdpProbeFactory {
+    pub const fn new(factory: fn() -> Box<dyn UdpProbe>) -> Self {
+        Self(factory)
+    }
+
+    pub fn create(&self) -> Box<dyn UdpProbe> {
+        (self.0)()
+    }
+}
+
+pub fn create_probe<T>() -> Box<dyn UdpProbe>
+where
+    T: UdpProbe + Default + 'static,
+{
+    Box::new(T::default())
+}
+
+inventory::collect!(UdpProbeFactory);
+
+/// Probe declared in the daemon config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UdpProbeConfig {
+    pub name: String,
+    pub port: u16,
+    /// Hex-encoded request payload
+    pub payload: String,
+    /// Hex-encoded prefix a reply must start with; any reply is accepted if unset
+    #[serde(default)]
+    pub response_prefix: Option<String>,
+}
+
+struct ConfiguredUdpProbe {
+    name: String,
+    port: u16,
+    payload: Vec<u8>,
+    response_prefix: Option<Vec<u8>>,
+}
+
+impl TryFrom<&UdpProbeConfig> for ConfiguredUdpProbe {
+    type Error = Error;
+
+    fn try_from(config: &UdpProbeConfig) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: config.name.clone(),
+            port: config.port,
+            payload: hex::decode(&config.payload)?,
+            response_prefix: config
+                .response_prefix
+                .as_deref()
+                .map(hex::decode)
+                .transpose()?,
+        })
+    }
+}
+
+impl UdpProbe for ConfiguredUdpProbe {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn build_payload(&self, _ctx: &UdpProbeContext) -> Result<Vec<u8>, Error> {
+        Ok(self.payload.clone())
+    }
+
+    fn validate(&self, _request: &[u8], response: &[u8]) -> bool {
+        match &self.response_prefix {
+            Some(prefix) => response.starts_with(prefix),
+            None => !response.is_empty(),
+        }
+    }
+}
+
+/// Probes by port: registered probes, overridden by those from the daemon config
+#[derive(Clone, Default)]
+pub struct UdpProbeRegistry {
+    probes: BTreeMap<u16, Arc<dyn UdpProbe>>,
+}
+
+impl UdpProbeRegistry {
+    pub fn new(configured: &[UdpProbeConfig]) -> Self {
+        let mut probes: BTreeMap<u16, Arc<dyn UdpProbe>> = inventory::iter::<UdpProbeFactory>()
+            .map(|factory| {
+                let probe: Arc<dyn UdpProbe> = factory.create().into();
+                (probe.port(), probe)
+            })
+            .collect();
+
+        for config in configured {
+            match ConfiguredUdpProbe::try_from(config) {
+                Ok(probe) => {
+                    probes.insert(probe.port, Arc::new(probe));
+                }
+                Err(e) => tracing::warn!("Ignoring UDP probe '{}': {}", config.name, e),
+            }
+        }
+
+        Self { probes }
+    }
+
+    pub fn get(&self, port: u16) -> Option<Arc<dyn UdpProbe>> {
+        self.probes.get(&port).cloned()
+    }
+
+    pub fn ports(&self) -> impl Iterator<Item = u16> + '_ {
+        self.probes.keys().copied()
+    }
+}
+
+/// Send a probe to the host and wait up to `probe_timeout` per destination for a valid reply
+/// from it. Returns the probed port if the service was detected.
+pub async fn run_probe(
+    probe: &dyn UdpProbe,
+    ctx: &UdpProbeContext,
+    probe_timeout: Duration,
+) -> Result<Option<u16>, Error> {
+    if !probe.should_probe(ctx) {
+        return Ok(None);
+    }
+
+    let payload = probe.build_payload(ctx)?;
+    let socket = bind_socket(probe, ctx).await?;
+
+    let mut destinations = Vec::new();
+    if probe.broadcast()
+        && let IpCidr::V4(cidr) = ctx.cidr
+    {
+        socket.set_broadcast(true)?;
+        destinations.push(SocketAddr::new(
+            IpAddr::V4(cidr.last_address()),
+            probe.port(),
+        ));
+    }
+    destinations.push(SocketAddr::new(ctx.ip, probe.port()));
+
+    for destination in destinations {
+        if let Err(e) = socket.send_to(&payload, destination).await {
+            tracing::trace!("{} probe to {} failed: {}", probe.name(), destination, e);
+            continue;
+        }
+
+        if wait_for_reply(&socket, probe, ctx, &payload, probe_timeout).await {
+            tracing::trace!("{} responding at {}:{}", probe.name(), ctx.ip, probe.port());
+            return Ok(Some(probe.port()));
+        }
+    }
+
+    tracing::trace!(
+        "{} not responding at {}:{}",
+        probe.name(),
+        ctx.ip,
+        probe.port()
+    );
+    Ok(None)
+}
+
+async fn bind_socket(probe: &dyn UdpProbe, ctx: &UdpProbeContext) -> Result<UdpSocket, Error> {
+    let unspecified = match ctx.ip {
+        IpAddr::V4(_) => "0.0.0.0",
+        IpAddr::V6(_) => "::",
+    };
+
+    if let Some(port) = probe.source_port()
+        && let Ok(socket) = UdpSocket::bind((unspecified, port)).await
+    {
+        return Ok(socket);
+    }
+
+    Ok(UdpSocket::bind((unspecified, 0)).await?)
+}
+
+/// Replies from other hosts (e.g. other DHCP servers answering a broadcast) are skipped
+async fn wait_for_reply(
+    socket: &UdpSocket,
+    probe: &dyn UdpProbe,
+    ctx: &UdpProbeContext,
+    request: &[u8],
+    probe_timeout: Duration,
+) -> bool {
+    let deadline = Instant::now() + probe_timeout;
+    let mut buf = [0u8; 1500];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        match timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(Ok((len, from))) => {
+                if from.ip() == ctx.ip && probe.validate(request, &buf[..len]) {
+                    return true;
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::trace!("Error receiving {} reply: {}", probe.name(), e);
+                return false;
+            }
+            Err(_) => return false,
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::daemon::utils::udp_probes::{UdpProbe, UdpProbeContext, UdpProbeFactory, create_probe};
+use anyhow::Error;
+use rand::Rng;
+
+const NTP_PACKET_LEN: usize = 48;
+
+/// NTPv4 client request; the server must echo our transmit timestamp as the originate
+/// timestamp and send a non-zero transmit timestamp of its own
+#[derive(Default)]
+pub struct NtpProbe;
+
+impl UdpProbe for NtpProbe {
+    fn name(&self) -> &str {
+        "NTP"
+    }
+
+    fn port(&self) -> u16 {
+        123
+    }
+
+    fn build_payload(&self, _ctx: &UdpProbeContext) -> Result<Vec<u8>, Error> {
+        let mut packet = vec![0u8; NTP_PACKET_LEN];
+        // LI = 0, VN = 4, Mode = 3 (client)
+        packet[0] = 0b00_100_011;
+        // A random transmit timestamp works as a nonce
+        rand::rng().fill(&mut packet[40..48]);
+
+        Ok(packet)
+    }
+
+    fn validate(&self, request: &[u8], response: &[u8]) -> bool {
+        response.len() >= NTP_PACKET_LEN
+            // Mode 4 (server)
+            && response[0] & 0b111 == 4
+            && response[24..32] == request[40..48]
+            && response[40..48].iter().any(|b| *b != 0)
+    }
+}
+
+inventory::submit!(UdpProbeFactory::new(create_probe::<NtpProbe>));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> UdpProbeContext {
+        UdpProbeContext {
+            ip: "192.168.1.1".parse().unwrap(),
+            cidr: "192.168.1.0/24".parse().unwrap(),
+            is_gateway: true,
+        }
+    }
+
+    /// Reply from a stratum 2 server
+    const REPLY: &str = "240203e800000a1c00000c52c0a80101ea7b0d1a2c8b43950000000000000000ea7b0d2b1d2f1a00ea7b0d2b1d31c8e1";
+
+    /// The captured reply, answering `request`
+    fn reply_to(request: &[u8]) -> Vec<u8> {
+        let mut reply = hex::decode(REPLY).unwrap();
+        reply[24..32].copy_from_slice(&request[40..48]);
+        reply
+    }
+
+    #[test]
+    fn accepts_reply_echoing_our_timestamp() {
+        let request = NtpProbe.build_payload(&context()).unwrap();
+        assert!(NtpProbe.validate(&request, &reply_to(&request)));
+    }
+
+    #[test]
+    fn rejects_other_replies() {
+        let request = NtpProbe.build_payload(&context()).unwrap();
+
+        assert!(!NtpProbe.validate(&request, &hex::decode(REPLY).unwrap()));
+
+        // Our own request reflected back is in client mode
+        assert!(!NtpProbe.validate(&request, &request));
+
+        let mut no_transmit = reply_to(&request);
+        no_transmit[40..48].fill(0);
+        assert!(!NtpProbe.validate(&request, &no_transmit));
+
+        let reply = reply_to(&request);
+        for len in 0..reply.len() {
+            assert!(
+                !NtpProbe.validate(&request, &reply[..len]),
+                "truncated to {}",
+                len
+            );
+        }
+    }
+}
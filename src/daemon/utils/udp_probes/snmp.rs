@@ -0,0 +1,208 @@
+use crate::daemon::utils::udp_probes::{UdpProbe, UdpProbeContext, UdpProbeFactory, create_probe};
+use anyhow::Error;
+use rand::Rng;
+
+pub const COMMUNITY: &[u8] = b"public";
+/// 1.3.6.1.2.1.1.1.0 (sysDescr.0)
+const SYS_DESCR_OID: [u8; 8] = [0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00];
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_GET_REQUEST: u8 = 0xa0;
+const TAG_GET_RESPONSE: u8 = 0xa2;
+
+/// SNMPv2c GetRequest for sysDescr.0 with the "public" community; the reply must be a
+/// GetResponse carrying our request ID
+#[derive(Default)]
+pub struct SnmpProbe;
+
+impl UdpProbe for SnmpProbe {
+    fn name(&self) -> &str {
+        "SNMP"
+    }
+
+    fn port(&self) -> u16 {
+        161
+    }
+
+    fn build_payload(&self, _ctx: &UdpProbeContext) -> Result<Vec<u8>, Error> {
+        // Positive 31-bit request ID, so its encoding is always 4 bytes
+        let request_id = rand::rng().random_range(0x0100_0000..=i32::MAX);
+
+        let varbind = tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_OID, &SYS_DESCR_OID), tlv(TAG_NULL, &[])].concat(),
+        );
+        let pdu = tlv(
+            TAG_GET_REQUEST,
+            &[
+                tlv(TAG_INTEGER, &request_id.to_be_bytes()),
+                tlv(TAG_INTEGER, &[0]),
+                tlv(TAG_INTEGER, &[0]),
+                tlv(TAG_SEQUENCE, &varbind),
+            ]
+            .concat(),
+        );
+
+        Ok(tlv(
+            TAG_SEQUENCE,
+            &[
+                // Version 2c
+                tlv(TAG_INTEGER, &[1]),
+                tlv(TAG_OCTET_STRING, COMMUNITY),
+                pdu,
+            ]
+            .concat(),
+        ))
+    }
+
+    fn validate(&self, request: &[u8], response: &[u8]) -> bool {
+        request_id(request, TAG_GET_REQUEST).is_some_and(|expected| {
+            request_id(response, TAG_GET_RESPONSE).is_some_and(|id| id == expected)
+        })
+    }
+}
+
+inventory::submit!(UdpProbeFactory::new(create_probe::<SnmpProbe>));
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    // Payloads here are far below 64KiB, so at most two length bytes are needed
+    match content.len() {
+        len @ 0..=0x7f => encoded.push(len as u8),
+        len @ 0x80..=0xff => encoded.extend([0x81, len as u8]),
+        len => encoded.extend([0x82, (len >> 8) as u8, len as u8]),
+    }
+    encoded.extend_from_slice(content);
+    encoded
+}
+
+/// Read one TLV at the start of `data`, returning its tag, content and the remaining bytes
+fn read_tlv(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count]
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        (len, &rest[count..])
+    };
+
+    if rest.len() < len {
+        return None;
+    }
+
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+/// Request ID of an SNMP message whose PDU has the given tag
+fn request_id(message: &[u8], pdu_tag: u8) -> Option<&[u8]> {
+    let (TAG_SEQUENCE, content, _) = read_tlv(message)? else {
+        return None;
+    };
+
+    let (TAG_INTEGER, _version, rest) = read_tlv(content)? else {
+        return None;
+    };
+    let (TAG_OCTET_STRING, _community, rest) = read_tlv(rest)? else {
+        return None;
+    };
+    let (tag, pdu, _) = read_tlv(rest)?;
+    if tag != pdu_tag {
+        return None;
+    }
+
+    match read_tlv(pdu)? {
+        (TAG_INTEGER, id, _) => Some(id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// GetResponse from a router for sysDescr.0, request ID 0x12345678
+    const REPLY: &str = "303c02010104067075626c6963a22f0204123456780201000201003021301f06082b0601020101010004134c696e757820726f7574657220352e31302e30";
+
+    fn request() -> Vec<u8> {
+        SnmpProbe
+            .build_payload(&UdpProbeContext {
+                ip: "192.168.1.1".parse().unwrap(),
+                cidr: "192.168.1.0/24".parse().unwrap(),
+                is_gateway: false,
+            })
+            .unwrap()
+    }
+
+    /// The captured reply, answering `request`
+    fn reply_to(request: &[u8]) -> Vec<u8> {
+        let mut reply = hex::decode(REPLY).unwrap();
+        let id = request_id(request, TAG_GET_REQUEST).unwrap();
+        reply[17..21].copy_from_slice(id);
+        reply
+    }
+
+    #[test]
+    fn reads_short_and_long_form_lengths() {
+        assert_eq!(
+            read_tlv(&[0x04, 0x02, 0xab, 0xcd, 0xff]),
+            Some((0x04, &[0xab, 0xcd][..], &[0xff][..]))
+        );
+
+        let long = [&[0x04, 0x81, 0x80][..], &[0x61; 0x80]].concat();
+        let (tag, content, rest) = read_tlv(&long).unwrap();
+        assert_eq!((tag, content.len(), rest.len()), (0x04, 0x80, 0));
+    }
+
+    #[test]
+    fn rejects_malformed_lengths() {
+        assert_eq!(read_tlv(&[]), None);
+        assert_eq!(read_tlv(&[0x04]), None);
+        // Content shorter than its length
+        assert_eq!(read_tlv(&[0x04, 0x03, 0x61]), None);
+        // Indefinite length, too many length bytes, length bytes cut off
+        assert_eq!(read_tlv(&[0x30, 0x80, 0x00, 0x00]), None);
+        assert_eq!(read_tlv(&[0x04, 0x85, 0, 0, 0, 0, 1, 0x61]), None);
+        assert_eq!(read_tlv(&[0x04, 0x82, 0x01]), None);
+        // A length past the end of any buffer
+        assert_eq!(read_tlv(&[0x04, 0x84, 0xff, 0xff, 0xff, 0xff]), None);
+    }
+
+    #[test]
+    fn accepts_reply_with_our_request_id() {
+        let request = request();
+        assert!(SnmpProbe.validate(&request, &reply_to(&request)));
+    }
+
+    #[test]
+    fn rejects_other_replies() {
+        let request = request();
+
+        let mut other_id = reply_to(&request);
+        other_id[20] ^= 0xff;
+        assert!(!SnmpProbe.validate(&request, &other_id));
+
+        // Our own request reflected back
+        assert!(!SnmpProbe.validate(&request, &request));
+
+        let reply = reply_to(&request);
+        for len in 0..reply.len() {
+            assert!(
+                !SnmpProbe.validate(&request, &reply[..len]),
+                "truncated to {}",
+                len
+            );
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use anyhow::{Error, bail};
+use chrono::{DateTime, Utc};
+use cidr::IpCidr;
+use serde::Deserialize;
+use std::{collections::HashMap, net::IpAddr, path::Path};
+use tokio::process::Command;
+
+use crate::server::hosts::r#impl::vpn::VpnKind;
+
+/// Where OpenVPN servers are commonly told to write their `--status` file
+const OPENVPN_STATUS_DIRS: &[&str] = &[
+    "/run/openvpn-server",
+    "/run/openvpn",
+    "/etc/openvpn",
+    "/etc/openvpn/server",
+    "/var/log/openvpn",
+];
+
+/// A VPN tunnel on the daemon host and the peers it knows about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VpnTunnel {
+    pub kind: VpnKind,
+    /// Unknown for OpenVPN, whose status file doesn't name the tun device
+    pub interface: Option<String>,
+    pub peers: Vec<VpnPeer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VpnPeer {
+    pub name: Option<String>,
+    pub public_key: Option<String>,
+    pub endpoint: Option<String>,
+    /// Addresses the peer has inside the tunnel
+    pub tunnel_ips: Vec<IpAddr>,
+    pub allowed_ips: Vec<IpCidr>,
+    pub latest_handshake: Option<DateTime<Utc>>,
+}
+
+/// Read every WireGuard, Tailscale and OpenVPN tunnel on this host. Tools that aren't
+/// installed or running are skipped.
+pub async fn read_tunnels() -> Vec<VpnTunnel> {
+    let mut tunnels = Vec::new();
+
+    match run("wg", &["show", "all", "dump"]).await {
+        Ok(dump) => tunnels.extend(parse_wg_dump(&dump)),
+        Err(e) => tracing::debug!("No WireGuard state: {}", e),
+    }
+
+    match run("tailscale", &["status", "--json"]).await {
+        Ok(status) => match parse_tailscale_status(&status) {
+            Ok(tunnel) => tunnels.push(tunnel),
+            Err(e) => tracing::warn!("Failed to parse tailscale status: {}", e),
+        },
+        Err(e) => tracing::debug!("No Tailscale state: {}", e),
+    }
+
+    for dir in OPENVPN_STATUS_DIRS {
+        let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if !is_openvpn_status_file(&path) {
+                continue;
+            }
+            if let Ok(status) = tokio::fs::read_to_string(&path).await
+                && let Some(tunnel) = parse_openvpn_status(&status)
+            {
+                tunnels.push(tunnel);
+            }
+        }
+    }
+
+    tunnels
+}
+
+async fn run(program: &str, args: &[&str]) -> Result<String, Error> {
+    let output = Command::new(program).args(args).output().await?;
+    if !output.status.success() {
+        bail!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn is_openvpn_status_file(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().contains("status"))
+}
+
+/// Parse `wg show all dump`: a tab separated line per interface (5 fields) followed by one
+/// per peer (9 fields)
+pub fn parse_wg_dump(dump: &str) -> Vec<VpnTunnel> {
+    let mut tunnels: Vec<VpnTunnel> = Vec::new();
+
+    for line in dump.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.as_slice() {
+            [interface, _, _, _, _] => tunnels.push(VpnTunnel {
+                kind: VpnKind::WireGuard,
+                interface: Some(interface.to_string()),
+                peers: Vec::new(),
+            }),
+            [
+                interface,
+                public_key,
+                _,
+                endpoint,
+                allowed_ips,
+                handshake,
+                ..,
+            ] => {
+                let Some(tunnel) = tunnels
+                    .iter_mut()
+                    .find(|t| t.interface.as_deref() == Some(*interface))
+                else {
+                    continue;
+                };
+
+                let allowed_ips: Vec<IpCidr> = allowed_ips
+                    .split(',')
+                    .filter_map(|cidr| cidr.trim().parse().ok())
+                    .collect();
+
+                tunnel.peers.push(VpnPeer {
+                    name: None,
+                    public_key: Some(public_key.to_string()),
+                    endpoint: none_if_unset(endpoint),
+                    tunnel_ips: allowed_ips
+                        .iter()
+                        .filter(|cidr| cidr.is_host_address())
+                        .map(|cidr| cidr.first_address())
+                        .collect(),
+                    allowed_ips,
+                    latest_handshake: handshake
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    tunnels
+}
+
+fn none_if_unset(value: &str) -> Option<String> {
+    match value {
+        "" | "(none)" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TailscaleStatus {
+    #[serde(default)]
+    peer: HashMap<String, TailscalePeer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TailscalePeer {
+    host_name: String,
+    #[serde(rename = "DNSName", default)]
+    dns_name: String,
+    #[serde(rename = "TailscaleIPs", default)]
+    tailscale_ips: Vec<IpAddr>,
+    #[serde(rename = "AllowedIPs", default)]
+    allowed_ips: Vec<IpCidr>,
+    #[serde(default)]
+    cur_addr: String,
+    #[serde(default)]
+    public_key: String,
+    last_handshake: Option<DateTime<Utc>>,
+}
+
+/// Parse `tailscale status --json`
+pub fn parse_tailscale_status(status: &str) -> Result<VpnTunnel, Error> {
+    let status: TailscaleStatus = serde_json::from_str(status)?;
+
+    let mut peers: Vec<VpnPeer> = status
+        .peer
+        .into_values()
+        .map(|peer| VpnPeer {
+            name: Some(
+                peer.dns_name
+                    .split('.')
+                    .next()
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(peer.host_name.as_str())
+                    .to_string(),
+            ),
+            public_key: none_if_unset(&peer.public_key),
+            endpoint: none_if_unset(&peer.cur_addr),
+            tunnel_ips: peer.tailscale_ips,
+            allowed_ips: peer.allowed_ips,
+            // Never-connected peers report the zero time
+            latest_handshake: peer.last_handshake.filter(|t| t.timestamp() > 0),
+        })
+        .collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(VpnTunnel {
+        kind: VpnKind::Tailscale,
+        interface: Some("tailscale0".to_string()),
+        peers,
+    })
+}
+
+/// Parse an OpenVPN `--status` file. Versions 2 and 3 list clients on `CLIENT_LIST` lines;
+/// version 1 only has them in its routing table.
+pub fn parse_openvpn_status(status: &str) -> Option<VpnTunnel> {
+    let mut peers: Vec<VpnPeer> = Vec::new();
+    let mut in_routing_table = false;
+
+    for line in status.lines() {
+        let separator = if line.contains('\t') { '\t' } else { ',' };
+        let fields: Vec<&str> = line.split(separator).collect();
+
+        match fields.as_slice() {
+            // CLIENT_LIST,name,real address,virtual address,virtual v6,rx,tx,since,since time_t,...
+            [
+                "CLIENT_LIST",
+                name,
+                real,
+                virtual_v4,
+                virtual_v6,
+                _,
+                _,
+                _,
+                since,
+                ..,
+            ] => {
+                peers.push(VpnPeer {
+                    name: Some(name.to_string()),
+                    public_key: None,
+                    endpoint: none_if_unset(real),
+                    tunnel_ips: [virtual_v4, virtual_v6]
+                        .iter()
+                        .filter_map(|ip| ip.parse().ok())
+                        .collect(),
+                    allowed_ips: Vec::new(),
+                    latest_handshake: since
+                        .parse::<i64>()
+                        .ok()
+                        .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+                });
+            }
+            ["ROUTING TABLE"] => in_routing_table = true,
+            ["GLOBAL STATS"] => in_routing_table = false,
+            // Virtual Address,Common Name,Real Address,Last Ref
+            [virtual_address, name, real, _] if in_routing_table => {
+                let Ok(ip) = virtual_address.parse::<IpAddr>() else {
+                    continue;
+                };
+                peers.push(VpnPeer {
+                    name: Some(name.to_string()),
+                    public_key: None,
+                    endpoint: none_if_unset(real),
+                    tunnel_ips: vec![ip],
+                    allowed_ips: Vec::new(),
+                    latest_handshake: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    (!peers.is_empty()).then_some(VpnTunnel {
+        kind: VpnKind::OpenVpn,
+        interface: None,
+        peers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_wg_dump() {
+        let dump = "wg0\tprivkey=\tpubkey=\t51820\toff\n\
+            wg0\tpeerA=\t(none)\t203.0.113.7:51820\t10.6.0.2/32,192.168.50.0/24\t1700000000\t100\t200\t25\n\
+            wg0\tpeerB=\t(none)\t(none)\t10.6.0.3/32\t0\t0\t0\toff\n";
+
+        let tunnels = parse_wg_dump(dump);
+        assert_eq!(tunnels.len(), 1);
+
+        let peers = &tunnels[0].peers;
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[0].endpoint.as_deref(), Some("203.0.113.7:51820"));
+        assert_eq!(
+            peers[0].tunnel_ips,
+            vec!["10.6.0.2".parse::<IpAddr>().unwrap()]
+        );
+        assert_eq!(peers[0].allowed_ips.len(), 2);
+        assert!(peers[0].latest_handshake.is_some());
+        assert_eq!(peers[1].endpoint, None);
+        assert_eq!(peers[1].latest_handshake, None);
+    }
+
+    #[test]
+    fn parses_openvpn_status_v2() {
+        let status = "TITLE,OpenVPN 2.6.8\n\
+            HEADER,CLIENT_LIST,Common Name,Real Address,Virtual Address,Virtual IPv6 Address,Bytes Received,Bytes Sent,Connected Since,Connected Since (time_t),Username,Client ID,Peer ID,Data Channel Cipher\n\
+            CLIENT_LIST,laptop,198.51.100.4:50123,10.8.0.6,,1000,2000,2024-01-01 10:00:00,1704103200,UNDEF,0,0,AES-256-GCM\n\
+            END\n";
+
+        let tunnel = parse_openvpn_status(status).unwrap();
+        assert_eq!(tunnel.peers.len(), 1);
+        assert_eq!(tunnel.peers[0].name.as_deref(), Some("laptop"));
+        assert_eq!(
+            tunnel.peers[0].tunnel_ips,
+            vec!["10.8.0.6".parse::<IpAddr>().unwrap()]
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use crate::daemon::utils::http::HttpClients;
+use crate::server::{
+    hosts::r#impl::windows::{HostWindows, WindowsService, WindowsShare},
+    services::r#impl::upnp::unescape,
+    winrm_credentials::r#impl::base::WinrmLogin,
+};
+use anyhow::{Error, anyhow, bail};
+use base64ct::{Base64, Encoding};
+use chrono::Utc;
+use regex::Regex;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::{net::IpAddr, time::Duration};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+/// Opening the shell, running the script and reading its output together, so a stuck host
+/// can't stall a scan
+const SESSION_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_OUTPUT: usize = 1 << 20;
+
+const SHELL_URI: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/cmd";
+const ACTION_CREATE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Create";
+const ACTION_DELETE: &str = "http://schemas.xmlsoap.org/ws/2004/09/transfer/Delete";
+const ACTION_COMMAND: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Command";
+const ACTION_RECEIVE: &str = "http://schemas.microsoft.com/wbem/wsman/1/windows/shell/Receive";
+
+/// Reads everything in one PowerShell run through CIM, printed as a single JSON object.
+/// Win32_ServerFeature only exists on server editions; elsewhere the roles stay empty.
+const INVENTORY_SCRIPT: &str = r#"$ErrorActionPreference = 'SilentlyContinue'
+$os = Get-CimInstance Win32_OperatingSystem
+$cs = Get-CimInstance Win32_ComputerSystem
+[pscustomobject]@{
+    hostname = [System.Net.Dns]::GetHostName()
+    domain = $cs.Domain
+    os = $os.Caption
+    os_version = $os.Version
+    shares = @(Get-CimInstance Win32_Share | ForEach-Object {
+        [pscustomobject]@{ name = $_.Name; path = $_.Path; description = $_.Description }
+    })
+    services = @(Get-CimInstance Win32_Service -Filter "State='Running'" | ForEach-Object {
+        [pscustomobject]@{ name = $_.Name; display_name = $_.DisplayName; start_mode = $_.StartMode }
+    })
+    roles = @(Get-CimInstance Win32_ServerFeature | Where-Object { $_.ParentID -eq 0 } | ForEach-Object { $_.Name })
+} | ConvertTo-Json -Depth 3 -Compress"#;
+
+/// What the inventory script prints
+#[derive(Debug, Deserialize)]
+struct Inventory {
+    hostname: Option<String>,
+    domain: Option<String>,
+    os: Option<String>,
+    os_version: Option<String>,
+    #[serde(default)]
+    shares: Vec<WindowsShare>,
+    #[serde(default)]
+    services: Vec<WindowsService>,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Query the host over WinRM for its inventory
+pub async fn collect(
+    ip: IpAddr,
+    login: &WinrmLogin,
+    http: &HttpClients,
+) -> Result<HostWindows, Error> {
+    let client = http
+        .scan_builder()
+        .danger_accept_invalid_certs(!login.verify_tls)
+        .build()?;
+    let scheme = if login.https { "https" } else { "http" };
+    let url = match ip {
+        IpAddr::V4(ip) => format!("{}://{}:{}/wsman", scheme, ip, login.port),
+        IpAddr::V6(ip) => format!("{}://[{}]:{}/wsman", scheme, ip, login.port),
+    };
+    let session = WinrmSession { client, url, login };
+
+    let output = timeout(SESSION_TIMEOUT, session.run(INVENTORY_SCRIPT))
+        .await
+        .map_err(|_| anyhow!("WinRM session timed out"))??;
+
+    parse_inventory(&output)
+}
+
+struct WinrmSession<'a> {
+    client: reqwest::Client,
+    url: String,
+    login: &'a WinrmLogin,
+}
+
+impl WinrmSession<'_> {
+    /// Run a PowerShell script in a new shell and return what it printed
+    async fn run(&self, script: &str) -> Result<String, Error> {
+        let reply = self
+            .send(
+                ACTION_CREATE,
+                None,
+                "<w:OptionSet><w:Option Name=\"WINRS_NOPROFILE\">TRUE</w:Option>\
+                 <w:Option Name=\"WINRS_CODEPAGE\">65001</w:Option></w:OptionSet>",
+                "<rsp:Shell><rsp:InputStreams>stdin</rsp:InputStreams>\
+                 <rsp:OutputStreams>stdout stderr</rsp:OutputStreams></rsp:Shell>",
+            )
+            .await?;
+        let shell_id = element(&reply, "ShellId")
+            .or_else(|| selector(&reply, "ShellId"))
+            .ok_or_else(|| anyhow!("WinRM didn't return a shell"))?;
+
+        let output = self.run_in_shell(&shell_id, script).await;
+
+        if let Err(e) = self.send(ACTION_DELETE, Some(&shell_id), "", "").await {
+            tracing::debug!("Failed to close WinRM shell {}: {}", shell_id, e);
+        }
+
+        output
+    }
+
+    async fn run_in_shell(&self, shell_id: &str, script: &str) -> Result<String, Error> {
+        let reply = self
+            .send(
+                ACTION_COMMAND,
+                Some(shell_id),
+                "<w:OptionSet><w:Option Name=\"WINRS_CONSOLEMODE_STDIN\">TRUE</w:Option>\
+                 <w:Option Name=\"WINRS_SKIP_CMD_SHELL\">FALSE</w:Option></w:OptionSet>",
+                &format!(
+                    "<rsp:CommandLine><rsp:Command>powershell.exe</rsp:Command>\
+                     <rsp:Arguments>-NoProfile -NonInteractive -EncodedCommand {}</rsp:Arguments>\
+                     </rsp:CommandLine>",
+                    encode_command(script)
+                ),
+            )
+            .await?;
+        let command_id = element(&reply, "CommandId")
+            .ok_or_else(|| anyhow!("WinRM didn't start the command"))?;
+
+        let mut output = Vec::new();
+        loop {
+            let reply = self
+                .send(
+                    ACTION_RECEIVE,
+                    Some(shell_id),
+                    "",
+                    &format!(
+                        "<rsp:Receive><rsp:DesiredStream CommandId=\"{}\">stdout stderr\
+                         </rsp:DesiredStream></rsp:Receive>",
+                        command_id
+                    ),
+                )
+                .await?;
+
+            let (stdout, done) = read_streams(&reply)?;
+            output.extend(stdout);
+            if output.len() > MAX_OUTPUT {
+                bail!("Inventory output is larger than {} bytes", MAX_OUTPUT);
+            }
+            if done {
+                break;
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    async fn send(
+        &self,
+        action: &str,
+        shell_id: Option<&str>,
+        options: &str,
+        body: &str,
+    ) -> Result<String, Error> {
+        let response = self
+            .client
+            .post(&self.url)
+            .basic_auth(&self.login.username, Some(&self.login.password))
+            .header("Content-Type", "application/soap+xml;charset=UTF-8")
+            .body(envelope(&self.url, action, shell_id, options, body))
+            .send()
+            .await?;
+
+        let status = response.status();
+        let text = response.text().await?;
+        match status {
+            StatusCode::OK => Ok(text),
+            StatusCode::UNAUTHORIZED => bail!(
+                "Login as {} was refused; WinRM must allow Basic authentication",
+                self.login.username
+            ),
+            status => bail!(
+                "WinRM answered HTTP {}: {}",
+                status,
+                element(&text, "Message")
+                    .or_else(|| element(&text, "Text"))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// SOAP envelope of a WS-Management request to the cmd shell resource
+fn envelope(url: &str, action: &str, shell_id: Option<&str>, options: &str, body: &str) -> String {
+    let selector = shell_id
+        .map(|id| {
+            format!(
+                "<w:SelectorSet><w:Selector Name=\"ShellId\">{}</w:Selector></w:SelectorSet>",
+                id
+            )
+        })
+        .unwrap_or_default();
+
+    format!(
+        r#"<s:Envelope xmlns:s="http://www.w3.org/2003/05/soap-envelope" xmlns:a="http://schemas.xmlsoap.org/ws/2004/08/addressing" xmlns:w="http://schemas.dmtf.org/wbem/wsman/1/wsman.xsd" xmlns:rsp="http://schemas.microsoft.com/wbem/wsman/1/windows/shell"><s:Header><a:To>{url}</a:To><a:ReplyTo><a:Address s:mustUnderstand="true">http://schemas.xmlsoap.org/ws/2004/08/addressing/role/anonymous</a:Address></a:ReplyTo><w:MaxEnvelopeSize s:mustUnderstand="true">512000</w:MaxEnvelopeSize><a:MessageID>uuid:{message_id}</a:MessageID><w:Locale xml:lang="en-US" s:mustUnderstand="false"/><w:OperationTimeout>PT20S</w:OperationTimeout><w:ResourceURI s:mustUnderstand="true">{SHELL_URI}</w:ResourceURI><a:Action s:mustUnderstand="true">{action}</a:Action>{selector}{options}</s:Header><s:Body>{body}</s:Body></s:Envelope>"#,
+        message_id = Uuid::new_v4().to_string().to_uppercase(),
+    )
+}
+
+/// `-EncodedCommand` takes the script as base64 of UTF-16LE, which sidesteps quoting
+fn encode_command(script: &str) -> String {
+    let bytes: Vec<u8> = script.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    Base64::encode_string(&bytes)
+}
+
+/// stdout carried by a Receive reply, and whether the command has finished
+fn read_streams(reply: &str) -> Result<(Vec<u8>, bool), Error> {
+    let stream = Regex::new(r"<(?:\w+:)?Stream\s([^>]*)>([^<]*)</(?:\w+:)?Stream>")?;
+
+    let mut stdout = Vec::new();
+    for captures in stream.captures_iter(reply) {
+        if captures[1].contains("Name=\"stdout\"") {
+            stdout.extend(
+                Base64::decode_vec(captures[2].trim())
+                    .map_err(|_| anyhow!("WinRM returned output that isn't base64"))?,
+            );
+        }
+    }
+
+    Ok((stdout, reply.contains("CommandState/Done")))
+}
+
+fn parse_inventory(output: &str) -> Result<HostWindows, Error> {
+    let json = output
+        .find('{')
+        .map(|start| &output[start..])
+        .ok_or_else(|| anyhow!("The inventory script printed nothing"))?;
+    let inventory: Inventory = serde_json::from_str(json.trim())?;
+
+    Ok(HostWindows {
+        hostname: inventory.hostname.filter(|h| !h.is_empty()),
+        domain: inventory.domain.filter(|d| !d.is_empty()),
+        os: inventory.os.map(|os| os.trim().to_string()),
+        os_version: inventory.os_version,
+        shares: inventory.shares,
+        services: inventory.services,
+        roles: inventory.roles,
+        collected_at: Utc::now(),
+    })
+}
+
+fn element(xml: &str, name: &str) -> Option<String> {
+    Regex::new(&format!(
+        r"(?s)<(?:\w+:)?{name}(?:\s[^>]*)?>(.*?)</(?:\w+:)?{name}>"
+    ))
+    .ok()?
+    .captures(xml)
+    .map(|c| unescape(c[1].trim()))
+    .filter(|s| !s.is_empty())
+}
+
+fn selector(xml: &str, name: &str) -> Option<String> {
+    Regex::new(&format!(
+        r#"<(?:\w+:)?Selector\s+Name="{name}"\s*>([^<]*)<"#
+    ))
+    .ok()?
+    .captures(xml)
+    .map(|c| c[1].trim().to_string())
+    .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_commands_as_utf16_base64() {
+        assert_eq!(encode_command("dir"), "ZABpAHIA");
+    }
+
+    #[test]
+    fn reads_ids_and_streams_from_replies() {
+        let created = r#"<s:Envelope><s:Body><rsp:Shell><rsp:ShellId>11D9B3C4-6C0C-4C5E-9D0E-3A4B1F2E7A90</rsp:ShellId></rsp:Shell></s:Body></s:Envelope>"#;
+        assert_eq!(
+            element(created, "ShellId").as_deref(),
+            Some("11D9B3C4-6C0C-4C5E-9D0E-3A4B1F2E7A90")
+        );
+
+        let received = r#"<rsp:ReceiveResponse><rsp:Stream Name="stdout" CommandId="A1">eyJh</rsp:Stream><rsp:Stream Name="stderr" CommandId="A1">ZXJy</rsp:Stream><rsp:Stream Name="stdout" CommandId="A1" End="true"></rsp:Stream><rsp:CommandState CommandId="A1" State="http://schemas.microsoft.com/wbem/wsman/1/windows/shell/CommandState/Done"><rsp:ExitCode>0</rsp:ExitCode></rsp:CommandState></rsp:ReceiveResponse>"#;
+        let (stdout, done) = read_streams(received).unwrap();
+        assert_eq!(stdout, b"{\"a");
+        assert!(done);
+    }
+
+    #[test]
+    fn parses_inventory() {
+        let output = r#"{"hostname":"DC01","domain":"corp.example.com","os":"Microsoft Windows Server 2022 Standard ","os_version":"10.0.20348","shares":[{"name":"SYSVOL","path":"C:\\Windows\\SYSVOL\\sysvol","description":"Logon server share "}],"services":[{"name":"DNS","display_name":"DNS Server","start_mode":"Auto"}],"roles":["AD-Domain-Services","DNS"]}"#;
+        let windows = parse_inventory(output).unwrap();
+
+        assert_eq!(windows.hostname.as_deref(), Some("DC01"));
+        assert_eq!(
+            windows.os.as_deref(),
+            Some("Microsoft Windows Server 2022 Standard")
+        );
+        assert_eq!(windows.shares[0].name, "SYSVOL");
+        assert_eq!(
+            windows.services[0].display_name.as_deref(),
+            Some("DNS Server")
+        );
+        assert_eq!(windows.roles, vec!["AD-Domain-Services", "DNS"]);
+
+        assert!(parse_inventory("").is_err());
+    }
+}
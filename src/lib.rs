@@ -1,2 +1,4 @@
+pub mod client;
 pub mod daemon;
 pub mod server;
+pub mod tui;
@@ -3,7 +3,9 @@ use crate::server::{
     auth::middleware::AuthenticatedUser,
     config::AppState,
     shared::{
-        handlers::traits::{CrudHandlers, delete_handler, get_all_handler, get_by_id_handler},
+        handlers::traits::{
+            CrudHandlers, check_access, delete_handler, get_all_handler, get_by_id_handler,
+        },
         services::traits::CrudService,
         types::api::{ApiError, ApiResponse, ApiResult},
     },
@@ -21,16 +23,34 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .route("/", get(get_all_handler::<ApiKey>))
         .route("/", post(create_handler))
         .route("/{id}/rotate", post(rotate_key_handler))
+        .route("/{id}/revoke", post(revoke_handler))
         .route("/{id}", put(update_handler))
         .route("/{id}", delete(delete_handler::<ApiKey>))
         .route("/{id}", get(get_by_id_handler::<ApiKey>))
 }
 
+async fn get_existing(state: &AppState, user_id: &Uuid, id: &Uuid) -> ApiResult<ApiKey> {
+    let existing = ApiKey::get_service(state)
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Api Key '{}' not found", id)))?;
+
+    check_access(state, user_id, &existing).await?;
+
+    Ok(existing)
+}
+
 pub async fn create_handler(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Json(api_key): Json<ApiKey>,
 ) -> ApiResult<Json<ApiResponse<ApiKeyResponse>>> {
+    api_key
+        .validate()
+        .map_err(|e| ApiError::bad_request(&format!("Api Key validation failed: {}", e)))?;
+    check_access(&state, &user.0, &api_key).await?;
+
     let service = ApiKey::get_service(&state);
     let api_key = service.create(api_key).await?;
 
@@ -42,32 +62,57 @@ pub async fn create_handler(
 
 pub async fn rotate_key_handler(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(api_key_id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<String>>> {
+    let existing = get_existing(&state, &user.0, &api_key_id).await?;
+    if existing.base.revoked_at.is_some() {
+        return Err(ApiError::bad_request("A revoked API key can't be rotated"));
+    }
+
     let service = ApiKey::get_service(&state);
     let key = service.rotate_key(api_key_id).await?;
 
     Ok(Json(ApiResponse::success(key)))
 }
 
+pub async fn revoke_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(api_key_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<ApiKey>>> {
+    get_existing(&state, &user.0, &api_key_id).await?;
+
+    let revoked = ApiKey::get_service(&state)
+        .revoke(&api_key_id, &user.0)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Api Key '{}' not found", api_key_id)))?;
+
+    Ok(Json(ApiResponse::success(revoked)))
+}
+
 pub async fn update_handler(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(id): Path<Uuid>,
     Json(mut request): Json<ApiKey>,
 ) -> ApiResult<Json<ApiResponse<ApiKey>>> {
-    let service = ApiKey::get_service(&state);
+    request
+        .validate()
+        .map_err(|e| ApiError::bad_request(&format!("Api Key validation failed: {}", e)))?;
 
-    // Verify entity exists
-    let existing = service
-        .get_by_id(&id)
-        .await
-        .map_err(|e| ApiError::internal_error(&e.to_string()))?
-        .ok_or_else(|| ApiError::not_found(format!("Api Key '{}' not found", id)))?;
+    let existing = get_existing(&state, &user.0, &id).await?;
+    check_access(&state, &user.0, &request).await?;
 
     request.base.key = existing.base.key;
+    request.base.revoked_at = existing.base.revoked_at;
+    request.base.revoked_by = existing.base.revoked_by;
+    if request.base.revoked_at.is_some() {
+        request.base.is_enabled = false;
+    }
 
+    let service = ApiKey::get_service(&state);
     let updated = service
         .update(&mut request)
         .await
@@ -4,6 +4,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize, Serializer};
 use uuid::Uuid;
 
+use crate::server::api_keys::r#impl::scopes::FULL_ACCESS;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiKeyBase {
     #[serde(serialize_with = "serialize_api_key_status")]
@@ -13,6 +15,18 @@ pub struct ApiKeyBase {
     pub expires_at: Option<DateTime<Utc>>,
     pub network_id: Uuid,
     pub is_enabled: bool,
+    /// Resources the key may read or write, e.g. `hosts:read`, or `*` for everything
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// Set once the key is revoked; a revoked key can't be enabled again
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub revoked_by: Option<Uuid>,
+}
+
+fn default_scopes() -> Vec<String> {
+    vec![FULL_ACCESS.to_string()]
 }
 
 fn serialize_api_key_status<S>(_key: &String, serializer: S) -> Result<S::Ok, S::Error>
@@ -1,6 +1,7 @@
-use crate::server::api_keys::r#impl::base::ApiKey;
+use crate::server::api_keys::r#impl::{base::ApiKey, scopes};
 use crate::server::api_keys::service::ApiKeyService;
 use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
 
 impl CrudHandlers for ApiKey {
     type Service = ApiKeyService;
@@ -8,4 +9,18 @@ impl CrudHandlers for ApiKey {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.api_key_service
     }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.scopes.is_empty() {
+            return Err("API key needs at least one scope".to_string());
+        }
+        self.base
+            .scopes
+            .iter()
+            .try_for_each(|scope| scopes::validate(scope))
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
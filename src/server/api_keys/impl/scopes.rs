@@ -0,0 +1,148 @@
+use axum::http::Method;
+
+/// Grants everything. Keys created before scopes existed have it.
+pub const FULL_ACCESS: &str = "*";
+
+/// What a key can be scoped to: the path segment right after `/api/`
+pub const RESOURCES: &[&str] = &[
+    "daemons",
+    "diagnostics",
+    "discovery",
+    "export",
+    "grafana",
+    "graphql",
+    "groups",
+    "health-checks",
+    "hosts",
+    "integrations",
+    "notes",
+    "policies",
+    "scan-profiles",
+    "services",
+    "ssh-credentials",
+    "subnets",
+    "topology",
+    "winrm-credentials",
+];
+
+/// Everything a daemon calls while registering, heartbeating and reporting scan results
+pub const DAEMON_SCOPES: &[&str] = &[
+    "daemons:write",
+    "discovery:write",
+    "groups:write",
+    "hosts:write",
+    "services:write",
+    "subnets:write",
+];
+
+/// Scope a request needs, e.g. `hosts:read` for `GET /api/hosts/{id}`. Reads are requests
+/// that can't change anything; everything else is a write.
+pub fn required_scope(method: &Method, path: &str) -> Option<String> {
+    let resource = path.strip_prefix("/api/")?.split('/').next()?;
+    if resource.is_empty() {
+        return None;
+    }
+
+    // GraphQL queries are POSTed, but the schema has no mutations
+    let access = if resource == "graphql"
+        || matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+    {
+        "read"
+    } else {
+        "write"
+    };
+
+    Some(format!("{}:{}", resource, access))
+}
+
+/// Whether `scopes` cover `required`. Write access to a resource includes reading it.
+pub fn grants(scopes: &[String], required: &str) -> bool {
+    let write = required
+        .strip_suffix(":read")
+        .map(|resource| format!("{}:write", resource));
+
+    scopes.iter().any(|scope| {
+        scope == FULL_ACCESS || scope == required || write.as_deref() == Some(scope.as_str())
+    })
+}
+
+pub fn validate(scope: &str) -> Result<(), String> {
+    if scope == FULL_ACCESS {
+        return Ok(());
+    }
+
+    let (resource, access) = scope
+        .split_once(':')
+        .ok_or_else(|| format!("Scope '{}' must look like 'resource:read'", scope))?;
+
+    if !RESOURCES.contains(&resource) {
+        return Err(format!(
+            "Unknown resource '{}' in scope '{}', expected one of: {}",
+            resource,
+            scope,
+            RESOURCES.join(", ")
+        ));
+    }
+
+    if access != "read" && access != "write" {
+        return Err(format!("Scope '{}' must end in ':read' or ':write'", scope));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> Vec<String> {
+        scopes.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn maps_requests_to_scopes() {
+        assert_eq!(
+            required_scope(&Method::GET, "/api/hosts/1").as_deref(),
+            Some("hosts:read")
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/daemons/1/heartbeat").as_deref(),
+            Some("daemons:write")
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/graphql").as_deref(),
+            Some("graphql:read")
+        );
+        assert_eq!(required_scope(&Method::GET, "/health"), None);
+    }
+
+    #[test]
+    fn prometheus_targets_can_be_scoped() {
+        let scope = required_scope(&Method::GET, "/api/integrations/prometheus/targets").unwrap();
+
+        assert_eq!(scope, "integrations:read");
+        assert!(validate(&scope).is_ok());
+        assert!(grants(&scopes(&["integrations:read"]), &scope));
+    }
+
+    #[test]
+    fn write_implies_read() {
+        let granted = scopes(&["hosts:write", "subnets:read"]);
+
+        assert!(grants(&granted, "hosts:read"));
+        assert!(grants(&granted, "hosts:write"));
+        assert!(grants(&granted, "subnets:read"));
+        assert!(!grants(&granted, "subnets:write"));
+        assert!(!grants(&granted, "daemons:read"));
+        assert!(grants(&scopes(&[FULL_ACCESS]), "daemons:write"));
+    }
+
+    #[test]
+    fn rejects_unknown_scopes() {
+        assert!(validate("hosts:read").is_ok());
+        assert!(validate(FULL_ACCESS).is_ok());
+        assert!(validate("hosts").is_err());
+        assert!(validate("users:read").is_err());
+        assert!(validate("hosts:admin").is_err());
+    }
+}
@@ -59,6 +59,9 @@ impl StorableEntity for ApiKey {
                     expires_at,
                     network_id,
                     is_enabled,
+                    scopes,
+                    revoked_at,
+                    revoked_by,
                 },
         } = self.clone();
 
@@ -73,6 +76,9 @@ impl StorableEntity for ApiKey {
                 "name",
                 "is_enabled",
                 "key",
+                "scopes",
+                "revoked_at",
+                "revoked_by",
             ],
             vec![
                 SqlValue::Uuid(id),
@@ -84,6 +90,9 @@ impl StorableEntity for ApiKey {
                 SqlValue::String(name),
                 SqlValue::Bool(is_enabled),
                 SqlValue::String(key),
+                SqlValue::Json(serde_json::to_value(scopes)?),
+                SqlValue::OptionTimestamp(revoked_at),
+                SqlValue::OptionalUuid(revoked_by),
             ],
         ))
     }
@@ -100,6 +109,9 @@ impl StorableEntity for ApiKey {
                 key: row.get("key"),
                 is_enabled: row.get("is_enabled"),
                 network_id: row.get("network_id"),
+                scopes: serde_json::from_value(row.get::<serde_json::Value, _>("scopes"))?,
+                revoked_at: row.get("revoked_at"),
+                revoked_by: row.get("revoked_by"),
             },
         })
     }
@@ -1,5 +1,6 @@
 use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use chrono::Utc;
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -44,11 +45,30 @@ impl ApiKeyService {
             expires_at: api_key.base.expires_at,
             network_id: api_key.base.network_id,
             is_enabled: true,
+            scopes: api_key.base.scopes,
+            revoked_at: None,
+            revoked_by: None,
         });
 
         self.storage.create(&api_key).await
     }
 
+    /// Disable the key for good, recording when and by whom
+    pub async fn revoke(&self, api_key_id: &Uuid, revoked_by: &Uuid) -> Result<Option<ApiKey>> {
+        let Some(mut api_key) = self.get_by_id(api_key_id).await? else {
+            return Ok(None);
+        };
+
+        if api_key.base.revoked_at.is_none() {
+            api_key.base.is_enabled = false;
+            api_key.base.revoked_at = Some(Utc::now());
+            api_key.base.revoked_by = Some(*revoked_by);
+            api_key = self.update(&mut api_key).await?;
+        }
+
+        Ok(Some(api_key))
+    }
+
     pub async fn rotate_key(&self, api_key_id: Uuid) -> Result<String> {
         if let Some(mut api_key) = self.get_by_id(&api_key_id).await? {
             let new_key = self.generate_api_key();
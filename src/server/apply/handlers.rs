@@ -0,0 +1,46 @@
+use crate::server::{
+    apply::r#impl::{
+        plan::{ApplyPlan, ApplyQuery},
+        spec::ConfigSpec,
+    },
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+    routing::post,
+};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/", post(apply_config))
+}
+
+/// Reconcile the user's networks with a YAML (or JSON) config spec
+async fn apply_config(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<ApplyQuery>,
+    body: String,
+) -> ApiResult<Json<ApiResponse<ApplyPlan>>> {
+    let spec: ConfigSpec = serde_yaml::from_str(&body)
+        .map_err(|e| ApiError::bad_request(&format!("Invalid config spec: {}", e)))?;
+
+    let plan = state
+        .services
+        .apply_service
+        .apply(user.0, &spec, query.dry_run)
+        .await?;
+
+    if !plan.errors.is_empty() {
+        return Err(ApiError::bad_request(&format!(
+            "Config spec has errors: {}",
+            plan.errors.join("; ")
+        )));
+    }
+
+    Ok(Json(ApiResponse::success(plan)))
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use uuid::Uuid;
+
+/// Changes needed to bring the server in line with a spec
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ApplyPlan {
+    pub changes: Vec<PlannedChange>,
+    /// Problems in the spec; a plan with errors is never applied
+    pub errors: Vec<String>,
+    /// Whether the changes were made, or only previewed
+    pub applied: bool,
+}
+
+impl ApplyPlan {
+    pub fn record(
+        &mut self,
+        action: ChangeAction,
+        entity: ApplyEntity,
+        network: &str,
+        name: &str,
+        id: Option<Uuid>,
+    ) {
+        self.changes.push(PlannedChange {
+            action,
+            entity,
+            network: network.to_string(),
+            name: name.to_string(),
+            id,
+            changed_fields: Vec::new(),
+        });
+    }
+
+    pub fn record_update(
+        &mut self,
+        entity: ApplyEntity,
+        network: &str,
+        name: &str,
+        id: Uuid,
+        changed_fields: Vec<String>,
+    ) {
+        self.changes.push(PlannedChange {
+            action: ChangeAction::Update,
+            entity,
+            network: network.to_string(),
+            name: name.to_string(),
+            id: Some(id),
+            changed_fields,
+        });
+    }
+
+    pub fn error(&mut self, network: &str, message: String) {
+        self.errors.push(format!("{}: {}", network, message));
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedChange {
+    pub action: ChangeAction,
+    pub entity: ApplyEntity,
+    pub network: String,
+    pub name: String,
+    /// Existing entity, or None for entities that would be created
+    pub id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display)]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Display)]
+pub enum ApplyEntity {
+    Network,
+    Subnet,
+    Daemon,
+    Discovery,
+    Group,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ApplyQuery {
+    /// Return the plan without changing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
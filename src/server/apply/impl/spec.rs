@@ -0,0 +1,105 @@
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{
+    discovery::r#impl::types::HostNamingFallback, groups::r#impl::types::GroupTypeDiscriminants,
+    hosts::r#impl::dhcp::LeaseFormat, subnets::r#impl::types::SubnetType,
+};
+
+/// Desired server state, usually written as YAML and kept in version control.
+///
+/// Entities are matched to existing ones by name within their network. For every collection,
+/// leaving the key out means "don't manage", while listing it (even empty) means entities not
+/// listed are deleted. Networks not listed are never touched, and subnets or groups created by
+/// discovery are never deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigSpec {
+    #[serde(default)]
+    pub networks: Vec<NetworkSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSpec {
+    pub name: String,
+    pub subnets: Option<Vec<SubnetSpec>>,
+    /// IDs of daemons allowed in this network; daemons register themselves, so they can only
+    /// be kept or removed
+    pub daemons: Option<Vec<Uuid>>,
+    pub discoveries: Option<Vec<DiscoverySpec>>,
+    pub groups: Option<Vec<GroupSpec>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubnetSpec {
+    pub name: String,
+    pub cidr: IpCidr,
+    #[serde(default)]
+    pub subnet_type: SubnetType,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverySpec {
+    pub name: String,
+    pub daemon_id: Uuid,
+    #[serde(flatten)]
+    pub kind: DiscoveryKindSpec,
+    /// Cron expression; discoveries without a schedule only run on demand
+    pub schedule: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DiscoveryKindSpec {
+    /// Scans the named subnets, or every subnet the daemon has an interface on
+    Network {
+        subnets: Option<Vec<String>>,
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    Docker {
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    Ssdp {
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    /// ARP sweep of the named subnets, or every subnet the daemon has an interface on
+    ArpSweep {
+        subnets: Option<Vec<String>>,
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    SelfReport,
+    VpnTunnels,
+    /// Uses the network's Tailscale or Headscale API settings
+    Tailnet,
+    /// Reads the path, or where the DHCP server keeps its leases by default
+    DhcpLeases {
+        format: LeaseFormat,
+        #[serde(default)]
+        path: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupSpec {
+    pub name: String,
+    pub group_type: GroupTypeDiscriminants,
+    #[serde(default)]
+    pub service_bindings: Vec<Uuid>,
+    pub description: Option<String>,
+    pub color: Option<String>,
+}
@@ -0,0 +1,680 @@
+use crate::server::{
+    apply::r#impl::{
+        plan::{ApplyEntity, ApplyPlan, ChangeAction},
+        spec::{ConfigSpec, DiscoveryKindSpec, DiscoverySpec, GroupSpec, NetworkSpec, SubnetSpec},
+    },
+    daemons::{r#impl::base::Daemon, service::DaemonService},
+    discovery::{
+        r#impl::{
+            base::{Discovery, DiscoveryBase},
+            types::{DiscoveryType, RunType},
+        },
+        service::DiscoveryService,
+    },
+    groups::{
+        r#impl::{
+            base::{Group, GroupBase},
+            types::{GroupType, GroupTypeDiscriminants},
+        },
+        service::GroupService,
+    },
+    networks::{
+        r#impl::{Network, NetworkBase},
+        service::NetworkService,
+    },
+    shared::{
+        entities::Entity,
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, traits::StorableEntity},
+        types::{entities::EntitySource, metadata::EntityMetadataProvider},
+    },
+    subnets::{
+        r#impl::base::{Subnet, SubnetBase},
+        service::SubnetService,
+    },
+};
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+pub struct ApplyService {
+    network_service: Arc<NetworkService>,
+    subnet_service: Arc<SubnetService>,
+    daemon_service: Arc<DaemonService>,
+    discovery_service: Arc<DiscoveryService>,
+    group_service: Arc<GroupService>,
+}
+
+impl ApplyService {
+    pub fn new(
+        network_service: Arc<NetworkService>,
+        subnet_service: Arc<SubnetService>,
+        daemon_service: Arc<DaemonService>,
+        discovery_service: Arc<DiscoveryService>,
+        group_service: Arc<GroupService>,
+    ) -> Self {
+        Self {
+            network_service,
+            subnet_service,
+            daemon_service,
+            discovery_service,
+            group_service,
+        }
+    }
+
+    /// Reconcile the user's networks with the spec. The spec is always planned in full first, so
+    /// nothing is changed if any part of it is invalid.
+    pub async fn apply(
+        &self,
+        user_id: Uuid,
+        spec: &ConfigSpec,
+        dry_run: bool,
+    ) -> Result<ApplyPlan> {
+        let plan = self.reconcile(user_id, spec, true).await?;
+
+        if dry_run || !plan.errors.is_empty() || plan.changes.is_empty() {
+            return Ok(plan);
+        }
+
+        let mut plan = self.reconcile(user_id, spec, false).await?;
+        plan.applied = true;
+
+        tracing::info!(
+            "Applied {} configuration changes for user {}",
+            plan.changes.len(),
+            user_id
+        );
+
+        Ok(plan)
+    }
+
+    async fn reconcile(
+        &self,
+        user_id: Uuid,
+        spec: &ConfigSpec,
+        dry_run: bool,
+    ) -> Result<ApplyPlan> {
+        let mut plan = ApplyPlan::default();
+
+        let networks = self
+            .network_service
+            .get_all(EntityFilter::unfiltered().network_member(&user_id))
+            .await?;
+
+        let mut seen = HashSet::new();
+        for network_spec in &spec.networks {
+            if !seen.insert(&network_spec.name) {
+                plan.error(
+                    &network_spec.name,
+                    "network is declared more than once".to_string(),
+                );
+                continue;
+            }
+
+            let network_id = match networks.iter().find(|n| n.base.name == network_spec.name) {
+                Some(network) => Some(network.id),
+                None => {
+                    plan.record(
+                        ChangeAction::Create,
+                        ApplyEntity::Network,
+                        &network_spec.name,
+                        &network_spec.name,
+                        None,
+                    );
+
+                    if dry_run {
+                        None
+                    } else {
+                        let network = self
+                            .network_service
+                            .create(Network::new(NetworkBase {
+                                name: network_spec.name.clone(),
+                                ..NetworkBase::new(user_id)
+                            }))
+                            .await?;
+                        Some(network.id)
+                    }
+                }
+            };
+
+            self.reconcile_network(network_spec, network_id, dry_run, &mut plan)
+                .await?;
+        }
+
+        Ok(plan)
+    }
+
+    /// `network_id` is None only while planning a network that doesn't exist yet
+    async fn reconcile_network(
+        &self,
+        spec: &NetworkSpec,
+        network_id: Option<Uuid>,
+        dry_run: bool,
+        plan: &mut ApplyPlan,
+    ) -> Result<()> {
+        let (subnets, daemons, discoveries, groups) = match network_id {
+            Some(network_id) => {
+                let filter = EntityFilter::unfiltered().network_ids(&[network_id]);
+                (
+                    self.subnet_service.get_all(filter.clone()).await?,
+                    self.daemon_service.get_all(filter.clone()).await?,
+                    self.discovery_service.get_all(filter.clone()).await?,
+                    self.group_service.get_all(filter).await?,
+                )
+            }
+            None => (Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+        };
+        let network_id = network_id.unwrap_or_default();
+
+        let subnet_ids = self
+            .reconcile_subnets(spec, network_id, subnets, dry_run, plan)
+            .await?;
+
+        let daemons_to_remove: Vec<Daemon> = match &spec.daemons {
+            Some(daemon_ids) => {
+                for daemon_id in daemon_ids {
+                    if !daemons.iter().any(|d| &d.id == daemon_id) {
+                        plan.error(
+                            &spec.name,
+                            format!("daemon {} is not registered in this network", daemon_id),
+                        );
+                    }
+                }
+                daemons
+                    .iter()
+                    .filter(|d| !daemon_ids.contains(&d.id))
+                    .cloned()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        let available_daemons: HashMap<Uuid, &Daemon> = daemons
+            .iter()
+            .filter(|d| !daemons_to_remove.iter().any(|r| r.id == d.id))
+            .map(|d| (d.id, d))
+            .collect();
+
+        if let Some(discovery_specs) = &spec.discoveries {
+            self.reconcile_discoveries(
+                spec,
+                discovery_specs,
+                network_id,
+                discoveries,
+                &available_daemons,
+                &subnet_ids,
+                dry_run,
+                plan,
+            )
+            .await?;
+        }
+
+        if let Some(group_specs) = &spec.groups {
+            self.reconcile_groups(spec, group_specs, network_id, groups, dry_run, plan)
+                .await?;
+        }
+
+        // Removed last so discoveries are no longer pointing at them
+        for daemon in daemons_to_remove {
+            plan.record(
+                ChangeAction::Delete,
+                ApplyEntity::Daemon,
+                &spec.name,
+                &daemon.to_string(),
+                Some(daemon.id),
+            );
+
+            if !dry_run {
+                self.daemon_service.delete(&daemon.id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns subnet IDs by name, for resolving discovery targets
+    async fn reconcile_subnets(
+        &self,
+        spec: &NetworkSpec,
+        network_id: Uuid,
+        subnets: Vec<Subnet>,
+        dry_run: bool,
+        plan: &mut ApplyPlan,
+    ) -> Result<HashMap<String, Uuid>> {
+        let mut subnet_ids: HashMap<String, Uuid> = subnets
+            .iter()
+            .map(|s| (s.base.name.clone(), s.id))
+            .collect();
+
+        let Some(subnet_specs) = &spec.subnets else {
+            return Ok(subnet_ids);
+        };
+
+        let mut claimed = HashSet::new();
+        for subnet_spec in subnet_specs {
+            if has_duplicate_name(subnet_specs, subnet_spec, |s: &SubnetSpec| &s.name) {
+                plan.error(
+                    &spec.name,
+                    format!("subnet '{}' is declared more than once", subnet_spec.name),
+                );
+                continue;
+            }
+
+            // Fall back to CIDR so subnets found by discovery can be adopted and renamed
+            let existing = subnets
+                .iter()
+                .find(|s| s.base.name == subnet_spec.name)
+                .or_else(|| {
+                    subnets.iter().find(|s| {
+                        s.base.cidr == subnet_spec.cidr
+                            && !claimed.contains(&s.id)
+                            && !subnet_specs.iter().any(|other| other.name == s.base.name)
+                    })
+                });
+
+            let desired = SubnetBase {
+                cidr: subnet_spec.cidr,
+                network_id,
+                name: subnet_spec.name.clone(),
+                description: subnet_spec.description.clone(),
+                subnet_type: subnet_spec.subnet_type,
+                source: existing
+                    .map(|s| s.base.source.clone())
+                    .unwrap_or(EntitySource::Manual),
+                layer2: existing.map(|s| s.base.layer2.clone()).unwrap_or_default(),
+                attributes: existing
+                    .map(|s| s.base.attributes.clone())
+                    .unwrap_or_default(),
+            };
+
+            match existing {
+                Some(subnet) => {
+                    claimed.insert(subnet.id);
+                    subnet_ids.insert(subnet_spec.name.clone(), subnet.id);
+
+                    let changed = changed_fields(&subnet.base, &desired)?;
+                    if changed.is_empty() {
+                        continue;
+                    }
+
+                    plan.record_update(
+                        ApplyEntity::Subnet,
+                        &spec.name,
+                        &subnet_spec.name,
+                        subnet.id,
+                        changed,
+                    );
+
+                    if !dry_run {
+                        let mut updated = subnet.clone();
+                        updated.base = desired;
+                        self.subnet_service.update(&mut updated).await?;
+                    }
+                }
+                None => {
+                    plan.record(
+                        ChangeAction::Create,
+                        ApplyEntity::Subnet,
+                        &spec.name,
+                        &subnet_spec.name,
+                        None,
+                    );
+
+                    let id = if dry_run {
+                        Uuid::nil()
+                    } else {
+                        self.subnet_service.create(Subnet::new(desired)).await?.id
+                    };
+                    subnet_ids.insert(subnet_spec.name.clone(), id);
+                }
+            }
+        }
+
+        for subnet in &subnets {
+            if claimed.contains(&subnet.id) || !matches!(subnet.base.source, EntitySource::Manual) {
+                continue;
+            }
+
+            plan.record(
+                ChangeAction::Delete,
+                ApplyEntity::Subnet,
+                &spec.name,
+                &subnet.base.name,
+                Some(subnet.id),
+            );
+            subnet_ids.remove(&subnet.base.name);
+
+            if !dry_run {
+                self.subnet_service.delete(&subnet.id).await?;
+            }
+        }
+
+        Ok(subnet_ids)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn reconcile_discoveries(
+        &self,
+        spec: &NetworkSpec,
+        discovery_specs: &[DiscoverySpec],
+        network_id: Uuid,
+        discoveries: Vec<Discovery>,
+        daemons: &HashMap<Uuid, &Daemon>,
+        subnet_ids: &HashMap<String, Uuid>,
+        dry_run: bool,
+        plan: &mut ApplyPlan,
+    ) -> Result<()> {
+        // Historical discoveries are past run results, not configuration
+        let discoveries: Vec<Discovery> = discoveries
+            .into_iter()
+            .filter(|d| !matches!(d.base.run_type, RunType::Historical { .. }))
+            .collect();
+
+        let mut claimed = HashSet::new();
+        for discovery_spec in discovery_specs {
+            if has_duplicate_name(discovery_specs, discovery_spec, |d: &DiscoverySpec| &d.name) {
+                plan.error(
+                    &spec.name,
+                    format!(
+                        "discovery '{}' is declared more than once",
+                        discovery_spec.name
+                    ),
+                );
+                continue;
+            }
+
+            let Some(daemon) = daemons.get(&discovery_spec.daemon_id) else {
+                plan.error(
+                    &spec.name,
+                    format!(
+                        "discovery '{}' uses daemon {}, which is not available in this network",
+                        discovery_spec.name, discovery_spec.daemon_id
+                    ),
+                );
+                continue;
+            };
+
+            let discovery_type = match &discovery_spec.kind {
+                DiscoveryKindSpec::Network {
+                    subnets,
+                    host_naming_fallback,
+                }
+                | DiscoveryKindSpec::ArpSweep {
+                    subnets,
+                    host_naming_fallback,
+                } => {
+                    let target_ids = match subnets {
+                        Some(names) => {
+                            let mut ids = Vec::new();
+                            for name in names {
+                                match subnet_ids.get(name) {
+                                    Some(id) => ids.push(*id),
+                                    None => plan.error(
+                                        &spec.name,
+                                        format!(
+                                            "discovery '{}' targets unknown subnet '{}'",
+                                            discovery_spec.name, name
+                                        ),
+                                    ),
+                                }
+                            }
+                            Some(ids)
+                        }
+                        None => None,
+                    };
+
+                    if matches!(discovery_spec.kind, DiscoveryKindSpec::ArpSweep { .. }) {
+                        DiscoveryType::ArpSweep {
+                            subnet_ids: target_ids,
+                            host_naming_fallback: *host_naming_fallback,
+                        }
+                    } else {
+                        DiscoveryType::Network {
+                            subnet_ids: target_ids,
+                            host_naming_fallback: *host_naming_fallback,
+                            scan_profile_id: None,
+                            parallel_daemon_ids: Vec::new(),
+                            shard: None,
+                        }
+                    }
+                }
+                DiscoveryKindSpec::Docker {
+                    host_naming_fallback,
+                } => DiscoveryType::Docker {
+                    host_id: daemon.base.host_id,
+                    host_naming_fallback: *host_naming_fallback,
+                },
+                DiscoveryKindSpec::Ssdp {
+                    host_naming_fallback,
+                } => DiscoveryType::Ssdp {
+                    host_naming_fallback: *host_naming_fallback,
+                },
+                DiscoveryKindSpec::SelfReport => DiscoveryType::SelfReport {
+                    host_id: daemon.base.host_id,
+                },
+                DiscoveryKindSpec::VpnTunnels => DiscoveryType::VpnTunnels {
+                    host_id: daemon.base.host_id,
+                },
+                DiscoveryKindSpec::Tailnet => DiscoveryType::Tailnet,
+                DiscoveryKindSpec::DhcpLeases { format, path } => DiscoveryType::DhcpLeases {
+                    format: *format,
+                    path: path.clone(),
+                },
+            };
+
+            let existing = discoveries
+                .iter()
+                .find(|d| d.base.name == discovery_spec.name);
+
+            let last_run = existing.and_then(|d| match &d.base.run_type {
+                RunType::Scheduled { last_run, .. } | RunType::AdHoc { last_run } => *last_run,
+                RunType::Historical { .. } => None,
+            });
+
+            let run_type = match &discovery_spec.schedule {
+                Some(cron_schedule) => RunType::Scheduled {
+                    cron_schedule: cron_schedule.clone(),
+                    last_run,
+                    enabled: discovery_spec.enabled,
+                },
+                None => RunType::AdHoc { last_run },
+            };
+
+            let desired = DiscoveryBase {
+                discovery_type,
+                run_type,
+                name: discovery_spec.name.clone(),
+                daemon_id: daemon.id,
+                network_id,
+            };
+
+            match existing {
+                Some(discovery) => {
+                    claimed.insert(discovery.id);
+
+                    let changed = changed_fields(&discovery.base, &desired)?;
+                    if changed.is_empty() {
+                        continue;
+                    }
+
+                    plan.record_update(
+                        ApplyEntity::Discovery,
+                        &spec.name,
+                        &discovery_spec.name,
+                        discovery.id,
+                        changed,
+                    );
+
+                    if !dry_run {
+                        let mut updated = discovery.clone();
+                        updated.base = desired;
+                        self.discovery_service.update_discovery(updated).await?;
+                    }
+                }
+                None => {
+                    plan.record(
+                        ChangeAction::Create,
+                        ApplyEntity::Discovery,
+                        &spec.name,
+                        &discovery_spec.name,
+                        None,
+                    );
+
+                    if !dry_run {
+                        self.discovery_service
+                            .create_discovery(Discovery::new(desired))
+                            .await?;
+                    }
+                }
+            }
+        }
+
+        for discovery in discoveries.iter().filter(|d| !claimed.contains(&d.id)) {
+            plan.record(
+                ChangeAction::Delete,
+                ApplyEntity::Discovery,
+                &spec.name,
+                &discovery.base.name,
+                Some(discovery.id),
+            );
+
+            if !dry_run {
+                self.discovery_service
+                    .delete_discovery(&discovery.id)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reconcile_groups(
+        &self,
+        spec: &NetworkSpec,
+        group_specs: &[GroupSpec],
+        network_id: Uuid,
+        groups: Vec<Group>,
+        dry_run: bool,
+        plan: &mut ApplyPlan,
+    ) -> Result<()> {
+        let mut claimed = HashSet::new();
+        for group_spec in group_specs {
+            if has_duplicate_name(group_specs, group_spec, |g: &GroupSpec| &g.name) {
+                plan.error(
+                    &spec.name,
+                    format!("group '{}' is declared more than once", group_spec.name),
+                );
+                continue;
+            }
+
+            let existing = groups.iter().find(|g| g.base.name == group_spec.name);
+
+            let service_bindings = group_spec.service_bindings.clone();
+            let group_type = match group_spec.group_type {
+                GroupTypeDiscriminants::RequestPath => GroupType::RequestPath { service_bindings },
+                GroupTypeDiscriminants::HubAndSpoke => GroupType::HubAndSpoke { service_bindings },
+            };
+
+            let desired = GroupBase {
+                name: group_spec.name.clone(),
+                network_id,
+                description: group_spec.description.clone(),
+                group_type,
+                source: existing
+                    .map(|g| g.base.source.clone())
+                    .unwrap_or(EntitySource::Manual),
+                color: group_spec
+                    .color
+                    .clone()
+                    .or_else(|| existing.map(|g| g.base.color.clone()))
+                    .unwrap_or_else(|| Entity::Group.color().to_string()),
+            };
+
+            match existing {
+                Some(group) => {
+                    claimed.insert(group.id);
+
+                    let changed = changed_fields(&group.base, &desired)?;
+                    if changed.is_empty() {
+                        continue;
+                    }
+
+                    plan.record_update(
+                        ApplyEntity::Group,
+                        &spec.name,
+                        &group_spec.name,
+                        group.id,
+                        changed,
+                    );
+
+                    if !dry_run {
+                        let mut updated = group.clone();
+                        updated.base = desired;
+                        self.group_service.update(&mut updated).await?;
+                    }
+                }
+                None => {
+                    plan.record(
+                        ChangeAction::Create,
+                        ApplyEntity::Group,
+                        &spec.name,
+                        &group_spec.name,
+                        None,
+                    );
+
+                    if !dry_run {
+                        self.group_service.create(Group::new(desired)).await?;
+                    }
+                }
+            }
+        }
+
+        for group in groups
+            .iter()
+            .filter(|g| !claimed.contains(&g.id) && matches!(g.base.source, EntitySource::Manual))
+        {
+            plan.record(
+                ChangeAction::Delete,
+                ApplyEntity::Group,
+                &spec.name,
+                &group.base.name,
+                Some(group.id),
+            );
+
+            if !dry_run {
+                self.group_service.delete(&group.id).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn has_duplicate_name<T>(items: &[T], item: &T, name: impl Fn(&T) -> &String) -> bool {
+    items
+        .iter()
+        .filter(|other| name(other) == name(item))
+        .count()
+        > 1
+}
+
+/// Top-level fields whose serialized values differ between current and desired state
+fn changed_fields<T: Serialize>(current: &T, desired: &T) -> Result<Vec<String>> {
+    let (Value::Object(current), Value::Object(desired)) = (
+        serde_json::to_value(current)?,
+        serde_json::to_value(desired)?,
+    ) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(desired
+        .iter()
+        .filter(|(key, value)| current.get(*key) != Some(value))
+        .map(|(key, _)| key.clone())
+        .collect())
+}
@@ -0,0 +1,65 @@
+use crate::server::{
+    archives::r#impl::{
+        api::{ArchiveImportQuery, ArchiveImportReport},
+        archive::NetworkArchive,
+    },
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    networks::r#impl::Network,
+    shared::{
+        handlers::traits::get_accessible,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Archives of large networks are well over axum's default 2MB body limit
+pub fn import_body_limit() -> DefaultBodyLimit {
+    DefaultBodyLimit::max(64 * 1024 * 1024)
+}
+
+/// Download a network as a JSON archive
+pub async fn export_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<impl IntoResponse> {
+    let network: Network = get_accessible(&state, &user.0, &id).await?;
+    let archive = state.services.archive_service.export(network).await?;
+
+    let filename = format!(
+        "attachment; filename=\"netvisor-{}-{}.json\"",
+        id,
+        archive.exported_at.format("%Y%m%d%H%M%S")
+    );
+
+    Ok(([(header::CONTENT_DISPOSITION, filename)], Json(archive)))
+}
+
+/// Restore a network archive into this network, under new ids
+pub async fn import_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ArchiveImportQuery>,
+    Json(archive): Json<NetworkArchive>,
+) -> ApiResult<Json<ApiResponse<ArchiveImportReport>>> {
+    network_role(&state, &user.0, &id).await?;
+
+    let report = state
+        .services
+        .archive_service
+        .import(id, archive, query.daemon_id)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ArchiveImportQuery {
+    /// Daemon in the target network to run the imported discoveries; they're skipped without one
+    pub daemon_id: Option<Uuid>,
+}
+
+/// Number of entities of each kind restored from an archive
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ArchiveImportReport {
+    pub subnets: usize,
+    pub hosts: usize,
+    pub services: usize,
+    pub groups: usize,
+    pub node_pins: usize,
+    pub discoveries: usize,
+    pub skipped_discoveries: usize,
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::server::{
+    discovery::r#impl::base::Discovery, groups::r#impl::base::Group, hosts::r#impl::base::Host,
+    networks::r#impl::Network, node_pins::r#impl::base::NodePin, services::r#impl::base::Service,
+    subnets::r#impl::base::Subnet,
+};
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Everything needed to recreate a network elsewhere. Interfaces and ports are part of their
+/// hosts; discovery history and daemons are left out, as they only make sense where they ran.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkArchive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub network: Network,
+    pub subnets: Vec<Subnet>,
+    pub hosts: Vec<Host>,
+    pub services: Vec<Service>,
+    pub groups: Vec<Group>,
+    /// Topology node positions placed by hand
+    pub node_pins: Vec<NodePin>,
+    /// Scheduled and ad hoc discovery configs
+    pub discoveries: Vec<Discovery>,
+}
+
+impl NetworkArchive {
+    /// Give every entity in the archive a new id and move it into `network_id`, so an archive
+    /// can be imported next to the network it was exported from. Discoveries are moved to
+    /// `daemon_id`.
+    ///
+    /// Every object's `id` is an id the archive owns, and every reference to one is a UUID
+    /// string elsewhere in the tree, so remapping works on the JSON form and doesn't need to
+    /// know each entity's reference fields.
+    pub fn remap(self, network_id: Uuid, daemon_id: Option<Uuid>) -> Result<Self> {
+        if self.version > ARCHIVE_VERSION {
+            bail!(
+                "Archive version {} is newer than the supported version {}",
+                self.version,
+                ARCHIVE_VERSION
+            );
+        }
+
+        let old_network_id = self.network.id;
+        let daemon_ids: Vec<Uuid> = self.discoveries.iter().map(|d| d.base.daemon_id).collect();
+
+        let mut value = serde_json::to_value(self)?;
+
+        let mut ids = HashMap::new();
+        collect_ids(&value, &mut ids);
+        ids.insert(old_network_id.to_string(), network_id.to_string());
+        if let Some(daemon_id) = daemon_id {
+            for old in daemon_ids {
+                ids.insert(old.to_string(), daemon_id.to_string());
+            }
+        }
+
+        replace_ids(&mut value, &ids);
+
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+fn collect_ids(value: &Value, ids: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(id)) = map.get("id")
+                && Uuid::parse_str(id).is_ok()
+            {
+                ids.entry(id.clone())
+                    .or_insert_with(|| Uuid::new_v4().to_string());
+            }
+            map.values().for_each(|v| collect_ids(v, ids));
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_ids(v, ids)),
+        _ => {}
+    }
+}
+
+/// Replace remapped ids in string values and in map keys, as some references are keyed by id
+fn replace_ids(value: &mut Value, ids: &HashMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            if let Some(new) = ids.get(s.as_str()) {
+                *s = new.clone();
+            }
+        }
+        Value::Object(map) => {
+            let entries = std::mem::take(map);
+            for (key, mut v) in entries {
+                replace_ids(&mut v, ids);
+                map.insert(ids.get(&key).cloned().unwrap_or(key), v);
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|v| replace_ids(v, ids)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::HostBase,
+            interfaces::{Interface, InterfaceBase},
+        },
+        networks::r#impl::NetworkBase,
+        node_pins::r#impl::base::NodePinBase,
+        shared::storage::traits::StorableEntity,
+        subnets::r#impl::base::SubnetBase,
+        topology::types::base::Ixy,
+    };
+
+    #[test]
+    fn remap_keeps_references_consistent() {
+        let network = Network::new(NetworkBase::new(Uuid::new_v4()));
+        let subnet = Subnet::new(SubnetBase {
+            network_id: network.id,
+            ..SubnetBase::default()
+        });
+        let host = Host::new(HostBase {
+            network_id: network.id,
+            interfaces: vec![Interface::new(InterfaceBase {
+                subnet_id: subnet.id,
+                ip_address: "192.168.1.10".parse().unwrap(),
+                mac_address: None,
+                name: None,
+            })],
+            ..HostBase::default()
+        });
+        let pin = NodePin::new(NodePinBase {
+            network_id: network.id,
+            node_id: subnet.id,
+            position: Ixy { x: 10, y: 20 },
+        });
+
+        let archive = NetworkArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            network,
+            subnets: vec![subnet.clone()],
+            hosts: vec![host.clone()],
+            services: Vec::new(),
+            groups: Vec::new(),
+            node_pins: vec![pin],
+            discoveries: Vec::new(),
+        };
+
+        let target = Uuid::new_v4();
+        let remapped = archive.remap(target, None).unwrap();
+
+        let new_subnet = &remapped.subnets[0];
+        let new_host = &remapped.hosts[0];
+        assert_ne!(new_subnet.id, subnet.id);
+        assert_ne!(new_host.id, host.id);
+        assert_ne!(new_host.base.interfaces[0].id, host.base.interfaces[0].id);
+        assert_eq!(new_host.base.interfaces[0].base.subnet_id, new_subnet.id);
+        assert_eq!(remapped.node_pins[0].base.node_id, new_subnet.id);
+        assert_eq!(new_subnet.base.network_id, target);
+        assert_eq!(new_host.base.network_id, target);
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    archives::r#impl::{
+        api::ArchiveImportReport,
+        archive::{ARCHIVE_VERSION, NetworkArchive},
+    },
+    daemons::service::DaemonService,
+    discovery::{r#impl::types::RunType, service::DiscoveryService},
+    groups::service::GroupService,
+    hosts::service::HostService,
+    networks::r#impl::Network,
+    node_pins::service::NodePinService,
+    services::service::ServiceService,
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+    subnets::service::SubnetService,
+};
+
+/// Backup and restore of whole networks
+pub struct ArchiveService {
+    subnet_service: Arc<SubnetService>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    group_service: Arc<GroupService>,
+    node_pin_service: Arc<NodePinService>,
+    discovery_service: Arc<DiscoveryService>,
+    daemon_service: Arc<DaemonService>,
+}
+
+impl ArchiveService {
+    pub fn new(
+        subnet_service: Arc<SubnetService>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        group_service: Arc<GroupService>,
+        node_pin_service: Arc<NodePinService>,
+        discovery_service: Arc<DiscoveryService>,
+        daemon_service: Arc<DaemonService>,
+    ) -> Self {
+        Self {
+            subnet_service,
+            host_service,
+            service_service,
+            group_service,
+            node_pin_service,
+            discovery_service,
+            daemon_service,
+        }
+    }
+
+    pub async fn export(&self, network: Network) -> Result<NetworkArchive> {
+        let filter = EntityFilter::unfiltered().network_ids(&[network.id]);
+
+        let discoveries = self
+            .discovery_service
+            .get_all(filter.clone())
+            .await?
+            .into_iter()
+            .filter(|d| !matches!(d.base.run_type, RunType::Historical { .. }))
+            .collect();
+
+        Ok(NetworkArchive {
+            version: ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            subnets: self.subnet_service.get_all(filter.clone()).await?,
+            hosts: self.host_service.get_all(filter.clone()).await?,
+            services: self.service_service.get_all(filter.clone()).await?,
+            groups: self.group_service.get_all(filter.clone()).await?,
+            node_pins: self.node_pin_service.get_all(filter).await?,
+            discoveries,
+            network,
+        })
+    }
+
+    /// Restore an archive into an existing network under new ids. Entities are stored as they
+    /// are in the archive rather than merged with what the network already has.
+    pub async fn import(
+        &self,
+        network_id: Uuid,
+        archive: NetworkArchive,
+        daemon_id: Option<Uuid>,
+    ) -> Result<ArchiveImportReport> {
+        if let Some(daemon_id) = daemon_id {
+            let daemon = self
+                .daemon_service
+                .get_by_id(&daemon_id)
+                .await?
+                .ok_or_else(|| anyhow!("Could not find daemon {}", daemon_id))?;
+            if daemon.base.network_id != network_id {
+                bail!("Daemon {} is not in network {}", daemon_id, network_id);
+            }
+        }
+
+        let mut archive = archive.remap(network_id, daemon_id)?;
+        let mut report = ArchiveImportReport::default();
+
+        // Discoveries need a daemon to run on
+        if daemon_id.is_none() {
+            report.skipped_discoveries = archive.discoveries.len();
+            archive.discoveries.clear();
+        }
+
+        // All or nothing, so a failed import can simply be retried
+        let subnets = self.subnet_service.storage();
+        let hosts = self.host_service.storage();
+        let services = self.service_service.storage();
+        let groups = self.group_service.storage();
+        let node_pins = self.node_pin_service.storage();
+        let discoveries = self.discovery_service.storage();
+
+        let mut tx = subnets.pool().begin().await?;
+        for subnet in &archive.subnets {
+            subnets.create_in(&mut tx, subnet).await?;
+        }
+        for host in &archive.hosts {
+            hosts.create_in(&mut tx, host).await?;
+        }
+        for service in &archive.services {
+            services.create_in(&mut tx, service).await?;
+        }
+        for group in &archive.groups {
+            groups.create_in(&mut tx, group).await?;
+        }
+        for pin in &archive.node_pins {
+            node_pins.create_in(&mut tx, pin).await?;
+        }
+        for discovery in &archive.discoveries {
+            discoveries.create_in(&mut tx, discovery).await?;
+        }
+        tx.commit().await?;
+
+        archive.subnets.iter().for_each(|e| subnets.created(e));
+        archive.hosts.iter().for_each(|e| hosts.created(e));
+        archive.services.iter().for_each(|e| services.created(e));
+        archive.groups.iter().for_each(|e| groups.created(e));
+        archive.node_pins.iter().for_each(|e| node_pins.created(e));
+        archive
+            .discoveries
+            .iter()
+            .for_each(|e| discoveries.created(e));
+
+        report.subnets = archive.subnets.len();
+        report.hosts = archive.hosts.len();
+        report.services = archive.services.len();
+        report.groups = archive.groups.len();
+        report.node_pins = archive.node_pins.len();
+        report.discoveries = archive.discoveries.len();
+
+        for discovery in archive.discoveries.drain(..) {
+            self.discovery_service.activate_discovery(discovery).await?;
+        }
+
+        tracing::info!(
+            "Imported archive of network {} into {}: {} subnets, {} hosts, {} services",
+            archive.network.base.name,
+            network_id,
+            report.subnets,
+            report.hosts,
+            report.services
+        );
+
+        Ok(report)
+    }
+}
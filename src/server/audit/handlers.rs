@@ -0,0 +1,47 @@
+use crate::server::{
+    audit::r#impl::{api::AuditQuery, base::AuditEntry},
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+    routing::get,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(get_audit_log))
+}
+
+/// Mutating API requests, filtered by entity, user and timeframe
+async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<AuditQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<AuditEntry>>>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    let entries = state
+        .services
+        .audit_service
+        .query(&query, &user.0, &network_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::success(entries)))
+}
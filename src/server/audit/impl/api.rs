@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditQuery {
+    /// Only requests that changed this kind of entity, e.g. `host`
+    pub entity: Option<String>,
+    /// Only requests that changed this entity
+    pub entity_id: Option<Uuid>,
+    /// Only requests made by this user
+    pub user_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Most recent entries to return; defaults to 100
+    pub limit: Option<usize>,
+}
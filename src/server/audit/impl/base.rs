@@ -0,0 +1,58 @@
+use std::{fmt::Display, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::shared::events::EntityOperation;
+
+/// One mutating API request: who made it, from where, and what it changed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntryBase {
+    /// Set when the request was made with a session
+    pub user_id: Option<Uuid>,
+    /// Set when the request was made with an API key
+    pub api_key_id: Option<Uuid>,
+    pub source_ip: Option<IpAddr>,
+    pub method: String,
+    pub path: String,
+    /// HTTP status of the response
+    pub status: u16,
+    pub changes: Vec<AuditChange>,
+}
+
+/// A single entity written while handling the request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditChange {
+    /// e.g. `host`
+    pub entity: String,
+    pub entity_id: Uuid,
+    pub network_id: Option<Uuid>,
+    pub operation: EntityOperation,
+    /// None for creates
+    pub old: Option<serde_json::Value>,
+    /// None for deletes
+    pub new: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: AuditEntryBase,
+}
+
+impl Display for AuditEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Audit entry {} {} ({} changes): {}",
+            self.base.method,
+            self.base.path,
+            self.base.changes.len(),
+            self.id
+        )
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::server::{audit::r#impl::base::AuditChange, shared::events::EntityOperation};
+
+/// Fields that hold credentials some entities serialize in full, such as notification channel
+/// tokens. Their values never reach the audit log.
+const REDACTED_FIELDS: &[&str] = &["password", "token", "bot_token", "webhook_url", "secret"];
+
+tokio::task_local! {
+    static CHANGES: Arc<Mutex<Vec<AuditChange>>>;
+}
+
+/// Run `future`, collecting the changes storage records while it runs. Work the future hands
+/// off to spawned tasks isn't collected.
+pub async fn collect<F: Future>(future: F) -> (F::Output, Vec<AuditChange>) {
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let output = CHANGES.scope(changes.clone(), future).await;
+
+    let changes = std::mem::take(&mut *changes.lock().unwrap_or_else(|e| e.into_inner()));
+    (output, changes)
+}
+
+/// Whether the current task's changes are being collected
+pub fn is_collecting() -> bool {
+    CHANGES.try_with(|_| ()).is_ok()
+}
+
+/// Record a change made by the current task. Does nothing outside `collect`.
+pub fn record(
+    entity: &'static str,
+    entity_id: Uuid,
+    operation: EntityOperation,
+    old: Option<Value>,
+    new: Option<Value>,
+) {
+    let _ = CHANGES.try_with(|changes| {
+        let network_id = new
+            .as_ref()
+            .or(old.as_ref())
+            .and_then(|data| data.get("network_id"))
+            .and_then(|id| serde_json::from_value(id.clone()).ok());
+
+        changes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(AuditChange {
+                entity: entity.to_string(),
+                entity_id,
+                network_id,
+                operation,
+                old: old.map(redact),
+                new: new.map(redact),
+            });
+    });
+}
+
+fn redact(mut value: Value) -> Value {
+    redact_in_place(&mut value);
+    value
+}
+
+fn redact_in_place(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if REDACTED_FIELDS.contains(&key.as_str()) && !value.is_null() {
+                    *value = Value::String("***REDACTED***".to_string());
+                } else {
+                    redact_in_place(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_in_place),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn collects_redacted_changes_only_inside_scope() {
+        let id = Uuid::new_v4();
+        let network_id = Uuid::new_v4();
+        let channel = json!({
+            "network_id": network_id,
+            "name": "Ops",
+            "config": { "type": "Telegram", "bot_token": "123:abc", "chat_id": "42" },
+        });
+
+        record(
+            "channel",
+            id,
+            EntityOperation::Created,
+            None,
+            Some(channel.clone()),
+        );
+        assert!(!is_collecting());
+
+        let (_, changes) = collect(async {
+            assert!(is_collecting());
+            record("channel", id, EntityOperation::Created, None, Some(channel));
+        })
+        .await;
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].network_id, Some(network_id));
+        assert_eq!(
+            changes[0].new,
+            Some(json!({
+                "network_id": network_id,
+                "name": "Ops",
+                "config": { "type": "Telegram", "bot_token": "***REDACTED***", "chat_id": "42" },
+            }))
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    audit::r#impl::base::{AuditEntry, AuditEntryBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for AuditEntry {
+    type BaseData = AuditEntryBase;
+
+    fn table_name() -> &'static str {
+        "audit_log"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    user_id,
+                    api_key_id,
+                    source_ip,
+                    method,
+                    path,
+                    status,
+                    changes,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "user_id",
+                "api_key_id",
+                "source_ip",
+                "method",
+                "path",
+                "status",
+                "changes",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::OptionalUuid(user_id),
+                SqlValue::OptionalUuid(api_key_id),
+                SqlValue::OptionalString(source_ip.map(|ip| ip.to_string())),
+                SqlValue::String(method),
+                SqlValue::String(path),
+                SqlValue::U16(status),
+                SqlValue::Json(serde_json::to_value(&changes)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let source_ip: Option<String> = row.get("source_ip");
+        let status: i32 = row.get("status");
+        let changes: serde_json::Value = row.get("changes");
+
+        Ok(AuditEntry {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: AuditEntryBase {
+                user_id: row.get("user_id"),
+                api_key_id: row.get("api_key_id"),
+                source_ip: source_ip.and_then(|ip| ip.parse().ok()),
+                method: row.get("method"),
+                path: row.get("path"),
+                status: status.try_into()?,
+                changes: serde_json::from_value(changes)?,
+            },
+        })
+    }
+}
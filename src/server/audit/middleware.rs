@@ -0,0 +1,119 @@
+use crate::server::{
+    audit::r#impl::{base::AuditEntryBase, changes},
+    config::AppState,
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method, header::AUTHORIZATION},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Record every mutating API request, along with the entities it changed, in the audit log.
+/// Requests that changed nothing are only recorded for users, so failed logins and daemon
+/// check-ins don't flood the log.
+pub async fn record_audit_trail(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // GraphQL is POSTed but only queries
+    if matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    ) || request.uri().path() == "/api/graphql"
+    {
+        return next.run(request).await;
+    }
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let source_ip = source_ip(&request, state.config.trust_forwarded_for);
+
+    // Mirrors AuthenticatedEntity, which prefers an API key over the session
+    let token = bearer_token(request.headers());
+    let (user_id, api_key_id) = match token {
+        Some(key) => (None, api_key_id(&state, key).await),
+        None => {
+            let session = request.extensions().get::<Session>().cloned();
+            (session_user_id(session).await, None)
+        }
+    };
+
+    let (response, changes) = changes::collect(next.run(request)).await;
+
+    if changes.is_empty() && user_id.is_none() {
+        return response;
+    }
+
+    let entry = AuditEntryBase {
+        user_id,
+        api_key_id,
+        source_ip,
+        method,
+        path,
+        status: response.status().as_u16(),
+        changes,
+    };
+
+    let audit_service = state.services.audit_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = audit_service.record(entry).await {
+            tracing::warn!("Failed to write audit entry: {}", e);
+        }
+    });
+
+    response
+}
+
+/// The client's address, or the first `X-Forwarded-For` address when the server is configured
+/// to trust it
+pub fn source_ip(request: &Request, trust_forwarded_for: bool) -> Option<IpAddr> {
+    if trust_forwarded_for
+        && let Some(ip) = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .and_then(|ip| ip.trim().parse().ok())
+    {
+        return Some(ip);
+    }
+
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+pub fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|key| key.to_owned())
+}
+
+async fn api_key_id(state: &AppState, key: String) -> Option<Uuid> {
+    let filter = EntityFilter::unfiltered().api_key(key);
+
+    state
+        .services
+        .api_key_service
+        .get_one(filter)
+        .await
+        .ok()
+        .flatten()
+        .map(|api_key| api_key.id)
+}
+
+async fn session_user_id(session: Option<Session>) -> Option<Uuid> {
+    session?.get::<Uuid>("user_id").await.ok().flatten()
+}
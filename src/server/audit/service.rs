@@ -0,0 +1,88 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    audit::r#impl::{
+        api::AuditQuery,
+        base::{AuditEntry, AuditEntryBase},
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+};
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+pub struct AuditService {
+    storage: Arc<GenericPostgresStorage<AuditEntry>>,
+}
+
+#[async_trait]
+impl CrudService<AuditEntry> for AuditService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<AuditEntry>> {
+        &self.storage
+    }
+}
+
+impl AuditService {
+    pub fn new(storage: Arc<GenericPostgresStorage<AuditEntry>>) -> Self {
+        Self { storage }
+    }
+
+    pub async fn record(&self, entry: AuditEntryBase) -> Result<AuditEntry> {
+        self.storage.create(&AuditEntry::new(entry)).await
+    }
+
+    /// Entries matching the query, newest first. A user sees the requests they made and the
+    /// requests that changed something in one of `network_ids`.
+    pub async fn query(
+        &self,
+        query: &AuditQuery,
+        user_id: &Uuid,
+        network_ids: &[Uuid],
+    ) -> Result<Vec<AuditEntry>> {
+        let mut filter = EntityFilter::unfiltered();
+        if let Some(entity) = &query.entity {
+            filter = filter.changed_entity(entity);
+        }
+        if let Some(entity_id) = &query.entity_id {
+            filter = filter.changed_entity_id(entity_id);
+        }
+        if let Some(user_id) = &query.user_id {
+            filter = filter.user_id(user_id);
+        }
+        if let Some(from) = query.from {
+            filter = filter.created_after(from);
+        }
+        if let Some(to) = query.to {
+            filter = filter.created_before(to);
+        }
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+        Ok(self
+            .storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .rev()
+            .filter(|entry| {
+                entry.base.user_id.as_ref() == Some(user_id)
+                    || entry.base.changes.iter().any(|change| {
+                        change
+                            .network_id
+                            .is_some_and(|id| network_ids.contains(&id))
+                    })
+            })
+            .take(limit)
+            .collect())
+    }
+}
@@ -6,9 +6,10 @@ use crate::server::{
             UpdateEmailPasswordRequest,
         },
         oidc::OidcPendingAuth,
-        service::hash_password,
+        service::{LoginLockedOut, hash_password},
     },
     config::AppState,
+    invites::handlers::get_invite,
     shared::{
         services::traits::CrudService,
         storage::filter::EntityFilter,
@@ -31,6 +32,7 @@ use uuid::Uuid;
 pub fn create_router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/register", post(register))
+        .route("/invites/{token}", get(get_invite))
         .route("/login", post(login))
         .route("/logout", post(logout))
         .route("/me", post(get_current_user))
@@ -46,12 +48,42 @@ async fn register(
     session: Session,
     Json(request): Json<RegisterRequest>,
 ) -> ApiResult<Json<ApiResponse<User>>> {
-    if state.config.disable_registration {
-        return Err(ApiError::forbidden("User registration is disabled"));
+    let invite = match &request.invite_token {
+        Some(token) => Some(
+            state
+                .services
+                .invite_service
+                .find_pending(token)
+                .await?
+                .ok_or_else(|| ApiError::bad_request("Invite link is invalid or has expired"))?,
+        ),
+        None => None,
+    };
+
+    match &invite {
+        Some(invite) if invite.base.email != request.email => {
+            return Err(ApiError::bad_request(
+                "This invite is for a different email address",
+            ));
+        }
+        None if state.reloadable().config.disable_registration => {
+            return Err(ApiError::forbidden(
+                "Registration is by invite only on this server",
+            ));
+        }
+        _ => {}
     }
 
     let user = state.services.auth_service.register(request).await?;
 
+    if let Some(invite) = invite {
+        state
+            .services
+            .invite_service
+            .accept(invite, &user.id)
+            .await?;
+    }
+
     // Store user_id in session
     session
         .insert("user_id", user.id)
@@ -66,7 +98,15 @@ async fn login(
     session: Session,
     Json(request): Json<LoginRequest>,
 ) -> ApiResult<Json<ApiResponse<User>>> {
-    let user = state.services.auth_service.login(request).await?;
+    let user = state
+        .services
+        .auth_service
+        .login(request)
+        .await
+        .map_err(|e| match e.downcast_ref::<LoginLockedOut>() {
+            Some(locked) => ApiError::too_many_requests(&locked.to_string()),
+            None => e.into(),
+        })?;
 
     // Store user_id in session
     session
@@ -146,8 +186,9 @@ async fn oidc_authorize(
     Query(params): Query<OidcAuthorizeParams>,
 ) -> ApiResult<Redirect> {
     let oidc_client = state
+        .reloadable()
         .oidc_client
-        .as_ref()
+        .clone()
         .ok_or_else(|| ApiError::internal_error("OIDC not configured"))?;
 
     let (auth_url, pending_auth) = oidc_client
@@ -200,7 +241,8 @@ async fn oidc_callback(
         }
     };
 
-    let oidc_client = match state.oidc_client.as_ref() {
+    let reloadable = state.reloadable();
+    let oidc_client = match reloadable.oidc_client.as_ref() {
         Some(client) => client,
         None => {
             return Err(Redirect::to(&format!(
@@ -303,7 +345,7 @@ async fn oidc_callback(
                 .link_oidc(
                     &user_id,
                     user_info.subject,
-                    state.config.oidc_provider_name.clone(),
+                    reloadable.config.oidc_provider_name.clone(),
                 )
                 .await
             {
@@ -406,7 +448,7 @@ async fn oidc_callback(
 
                 seed_user.base.email = email;
                 seed_user.base.oidc_subject = Some(user_info.subject.clone());
-                seed_user.base.oidc_provider = state.config.oidc_provider_name.clone();
+                seed_user.base.oidc_provider = reloadable.config.oidc_provider_name.clone();
                 seed_user.base.oidc_linked_at = Some(chrono::Utc::now());
 
                 match state.services.user_service.update(&mut seed_user).await {
@@ -430,7 +472,7 @@ async fn oidc_callback(
                     .create_user_with_oidc(
                         email,
                         user_info.subject.clone(),
-                        state.config.oidc_provider_name.clone(),
+                        reloadable.config.oidc_provider_name.clone(),
                     )
                     .await
                 {
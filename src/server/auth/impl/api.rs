@@ -22,6 +22,10 @@ pub struct RegisterRequest {
     #[validate(length(min = 12, message = "Password must be at least 12 characters"))]
     #[validate(custom(function = "validate_password_complexity"))]
     pub password: String,
+
+    /// Token from an invite link; required when open registration is disabled
+    #[serde(default)]
+    pub invite_token: Option<String>,
 }
 
 /// Validate password complexity requirements
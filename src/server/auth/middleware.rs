@@ -1,9 +1,10 @@
 use crate::server::{
+    api_keys::r#impl::scopes,
     config::AppState,
     shared::{services::traits::CrudService, storage::filter::EntityFilter, types::api::ApiError},
 };
 use axum::{
-    extract::FromRequestParts,
+    extract::{FromRequestParts, OriginalUri},
     http::request::Parts,
     response::{IntoResponse, Response},
 };
@@ -94,12 +95,33 @@ where
                     )));
                 }
 
+                if api_key.base.revoked_at.is_some() {
+                    return Err(AuthError(ApiError::unauthorized(
+                        "API key has been revoked".to_string(),
+                    )));
+                }
+
                 if !api_key.base.is_enabled {
                     return Err(AuthError(ApiError::unauthorized(
                         "API key is not enabled".to_string(),
                     )));
                 }
 
+                // Nested routers see a stripped path, the scope is named after the full one
+                let path = parts
+                    .extensions
+                    .get::<OriginalUri>()
+                    .map(|uri| uri.0.path())
+                    .unwrap_or_else(|| parts.uri.path());
+                if let Some(required) = scopes::required_scope(&parts.method, path)
+                    && !scopes::grants(&api_key.base.scopes, &required)
+                {
+                    return Err(AuthError(ApiError::forbidden(&format!(
+                        "API key is missing the '{}' scope",
+                        required
+                    ))));
+                }
+
                 // Update last used asynchronously (don't block auth)
                 api_key.base.last_used = Some(Utc::now());
                 tokio::spawn(async move {
@@ -150,6 +172,48 @@ where
     }
 }
 
+/// Extractor that only accepts users listed as server operators in `operator_emails`
+pub struct AuthenticatedOperator(pub Uuid);
+
+impl<S> FromRequestParts<S> for AuthenticatedOperator
+where
+    S: Send + Sync + AsRef<AppState>,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthenticatedUser(user_id) =
+            AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        if !is_operator(state.as_ref(), &user_id)
+            .await
+            .map_err(AuthError)?
+        {
+            return Err(AuthError(ApiError::forbidden(
+                "Only server operators can do this",
+            )));
+        }
+
+        Ok(AuthenticatedOperator(user_id))
+    }
+}
+
+/// Whether the user is listed in `operator_emails`
+pub async fn is_operator(state: &AppState, user_id: &Uuid) -> Result<bool, ApiError> {
+    let user = state
+        .services
+        .user_service
+        .get_by_id(user_id)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?
+        .ok_or_else(|| ApiError::unauthorized("Not authenticated".to_string()))?;
+
+    Ok(state
+        .reloadable()
+        .config
+        .is_operator(&user.base.email.to_string()))
+}
+
 /// Extractor that only accepts authenticated daemons (rejects users)
 pub struct AuthenticatedDaemon(pub Uuid);
 
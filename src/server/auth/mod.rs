@@ -2,4 +2,5 @@ pub mod handlers;
 pub mod r#impl;
 pub mod middleware;
 pub mod oidc;
+pub mod rate_limit;
 pub mod service;
@@ -0,0 +1,182 @@
+use crate::server::{
+    audit::middleware::{bearer_token, source_ip},
+    config::{AppState, ServerConfig},
+    shared::types::api::ApiError,
+};
+use axum::{
+    extract::{Request, State},
+    http::{HeaderValue, header::RETRY_AFTER},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tower_sessions::Session;
+use uuid::Uuid;
+
+/// Who a request is counted against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateLimitKey {
+    Ip(IpAddr),
+    User(Uuid),
+    ApiKey(String),
+}
+
+/// Allows `limit` requests per key in each fixed window; a limit of 0 allows everything
+pub struct RateLimiter<K> {
+    limit: u32,
+    window: Duration,
+    hits: Mutex<HashMap<K, (u32, Instant)>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request, or return how long until the key may make another one
+    pub fn check(&self, key: K) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        let (count, started) = hits.entry(key).or_insert((0, Instant::now()));
+
+        if started.elapsed() >= self.window {
+            *count = 0;
+            *started = Instant::now();
+        }
+
+        *count += 1;
+        if *count > self.limit {
+            return Err(self.window.saturating_sub(started.elapsed()));
+        }
+
+        Ok(())
+    }
+
+    /// Forget keys whose window has passed (called periodically from background task)
+    pub fn cleanup(&self) {
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        hits.retain(|_, (_, started)| started.elapsed() < self.window);
+    }
+}
+
+pub struct RateLimits {
+    /// Every API request, per client IP and per signed-in user or API key
+    pub api: RateLimiter<RateLimitKey>,
+    /// Logins, registrations and invite lookups, per client IP
+    pub auth: RateLimiter<IpAddr>,
+}
+
+impl RateLimits {
+    pub fn new(config: &ServerConfig) -> Self {
+        let minute = Duration::from_secs(60);
+
+        Self {
+            api: RateLimiter::new(config.rate_limit_per_minute, minute),
+            auth: RateLimiter::new(config.auth_rate_limit_per_minute, minute),
+        }
+    }
+
+    pub fn cleanup(&self) {
+        self.api.cleanup();
+        self.auth.cleanup();
+    }
+}
+
+/// Where credentials can be guessed, so the stricter per-IP limit applies
+fn is_auth_path(path: &str) -> bool {
+    matches!(path, "/api/auth/login" | "/api/auth/register")
+        || path.starts_with("/api/auth/invites/")
+}
+
+pub fn too_many_requests(message: &str, retry_after: Duration) -> Response {
+    let mut response = ApiError::too_many_requests(message).into_response();
+    // Round up, so clients waiting the advertised time aren't turned away again
+    let secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(secs.max(1)));
+    response
+}
+
+/// Reject requests over the configured limits with 429 Too Many Requests
+pub async fn limit_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limits = &state.rate_limits;
+    let path = request.uri().path();
+
+    let mut keys = Vec::new();
+    if let Some(ip) = source_ip(&request, state.config.trust_forwarded_for) {
+        if is_auth_path(path)
+            && let Err(retry_after) = limits.auth.check(ip)
+        {
+            return too_many_requests("Too many attempts, please try again later", retry_after);
+        }
+        keys.push(RateLimitKey::Ip(ip));
+    }
+
+    match bearer_token(request.headers()) {
+        Some(token) => keys.push(RateLimitKey::ApiKey(token)),
+        None => {
+            let session = request.extensions().get::<Session>().cloned();
+            if let Some(session) = session
+                && let Ok(Some(user_id)) = session.get::<Uuid>("user_id").await
+            {
+                keys.push(RateLimitKey::User(user_id));
+            }
+        }
+    }
+
+    for key in keys {
+        if let Err(retry_after) = limits.api.check(key) {
+            return too_many_requests("Too many requests, please slow down", retry_after);
+        }
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_each_key_separately() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_err());
+        assert!(limiter.check("b").is_ok());
+    }
+
+    #[test]
+    fn zero_limit_allows_everything() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+
+        assert!((0..100).all(|_| limiter.check("a").is_ok()));
+    }
+
+    #[test]
+    fn window_resets() {
+        let limiter = RateLimiter::new(1, Duration::ZERO);
+
+        assert!(limiter.check("a").is_ok());
+        assert!(limiter.check("a").is_ok());
+    }
+}
@@ -15,10 +15,32 @@ use argon2::{
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
 };
 use email_address::EmailAddress;
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::RwLock;
 use validator::Validate;
 
+/// Login refused because the account is locked out after too many failed attempts
+#[derive(Debug)]
+pub struct LoginLockedOut {
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for LoginLockedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Too many failed login attempts. Try again in {} minutes.",
+            self.retry_after.as_secs() / 60 + 1
+        )
+    }
+}
+
+impl std::error::Error for LoginLockedOut {}
+
 pub struct AuthService {
     user_service: Arc<UserService>,
     login_attempts: Arc<RwLock<HashMap<EmailAddress, (u32, Instant)>>>,
@@ -27,6 +49,7 @@ pub struct AuthService {
 impl AuthService {
     const MAX_LOGIN_ATTEMPTS: u32 = 5;
     const LOCKOUT_DURATION_SECS: u64 = 15 * 60; // 15 minutes
+    const MAX_LOCKOUT_DURATION_SECS: u64 = 24 * 60 * 60;
 
     pub fn new(user_service: Arc<UserService>) -> Self {
         Self {
@@ -117,19 +140,30 @@ impl AuthService {
         }
     }
 
+    /// How long an account stays locked after `failures` failed attempts in a row. Every
+    /// failure past the limit doubles it, up to a day.
+    fn lockout_duration(failures: u32) -> Duration {
+        if failures < Self::MAX_LOGIN_ATTEMPTS {
+            return Duration::ZERO;
+        }
+
+        let doublings = (failures - Self::MAX_LOGIN_ATTEMPTS).min(16);
+        Duration::from_secs(
+            (Self::LOCKOUT_DURATION_SECS << doublings).min(Self::MAX_LOCKOUT_DURATION_SECS),
+        )
+    }
+
     /// Check if user is locked out due to too many login attempts
     async fn check_login_lockout(&self, email: &EmailAddress) -> Result<()> {
         let attempts = self.login_attempts.read().await;
-        if let Some((count, last_attempt)) = attempts.get(email)
-            && *count >= Self::MAX_LOGIN_ATTEMPTS
-        {
-            let elapsed = last_attempt.elapsed().as_secs();
-            if elapsed < Self::LOCKOUT_DURATION_SECS {
-                let remaining = (Self::LOCKOUT_DURATION_SECS - elapsed) / 60;
-                return Err(anyhow!(
-                    "Too many failed login attempts. Try again in {} minutes.",
-                    remaining + 1
-                ));
+        if let Some((count, last_attempt)) = attempts.get(email) {
+            let lockout = Self::lockout_duration(*count);
+            let elapsed = last_attempt.elapsed();
+            if elapsed < lockout {
+                return Err(LoginLockedOut {
+                    retry_after: lockout - elapsed,
+                }
+                .into());
             }
         }
         Ok(())
@@ -173,8 +207,11 @@ impl AuthService {
     pub async fn cleanup_old_login_attempts(&self) {
         let mut attempts = self.login_attempts.write().await;
 
-        attempts.retain(|_, (_, last_attempt)| {
-            last_attempt.elapsed().as_secs() < Self::LOCKOUT_DURATION_SECS
+        // Failures are forgotten after a quiet spell once any lockout is over, so the backoff
+        // keeps growing for accounts that are attacked again right after a lockout
+        attempts.retain(|_, (count, last_attempt)| {
+            last_attempt.elapsed()
+                < Self::lockout_duration(*count) + Duration::from_secs(Self::LOCKOUT_DURATION_SECS)
         });
 
         tracing::debug!("Cleaned up old login attempts");
@@ -0,0 +1,44 @@
+use crate::server::{
+    auth::middleware::AuthenticatedOperator,
+    backups::r#impl::api::{BackupRun, BackupStatus},
+    config::AppState,
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+};
+use axum::{
+    Router,
+    extract::State,
+    response::Json,
+    routing::{get, post},
+};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_status))
+        .route("/", post(run_backup))
+}
+
+/// Backup schedule, the last run and the backups currently kept
+async fn get_status(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+) -> ApiResult<Json<ApiResponse<BackupStatus>>> {
+    let status = match &state.backup_service {
+        Some(service) => service.status().await?,
+        None => BackupStatus::default(),
+    };
+
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// Back up now instead of waiting for the schedule
+async fn run_backup(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+) -> ApiResult<Json<ApiResponse<BackupRun>>> {
+    let Some(service) = &state.backup_service else {
+        return Err(ApiError::bad_request("Backups are not configured"));
+    };
+
+    Ok(Json(ApiResponse::success(service.run_backup().await)))
+}
@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupFile {
+    pub name: String,
+    pub size: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of a backup run
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupRun {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub file: Option<String>,
+    pub uploaded: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BackupStatus {
+    pub enabled: bool,
+    pub cron_schedule: Option<String>,
+    pub keep: usize,
+    pub s3_enabled: bool,
+    pub last_run: Option<BackupRun>,
+    /// Local backups, newest first
+    pub backups: Vec<BackupFile>,
+}
@@ -0,0 +1,274 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{process::Command, sync::RwLock};
+use tokio_cron_scheduler::{Job, JobScheduler};
+use url::Url;
+
+use crate::server::backups::r#impl::{
+    api::{BackupFile, BackupRun, BackupStatus},
+    s3::{S3Client, S3Config},
+};
+
+const BACKUP_PREFIX: &str = "netvisor-";
+const BACKUP_EXTENSION: &str = ".dump";
+
+/// Scheduled `pg_dump` backups in Postgres' custom format, kept locally and optionally copied
+/// to S3-compatible storage. Requires `pg_dump` on the server's PATH.
+pub struct BackupService {
+    database_url: String,
+    dir: PathBuf,
+    cron_schedule: String,
+    keep: usize,
+    s3: Option<S3Client>,
+    last_run: RwLock<Option<BackupRun>>,
+    scheduler: Arc<RwLock<JobScheduler>>,
+}
+
+impl BackupService {
+    pub async fn new(
+        database_url: String,
+        dir: PathBuf,
+        cron_schedule: String,
+        keep: usize,
+        s3: Option<S3Config>,
+    ) -> Result<Arc<Self>> {
+        let scheduler = JobScheduler::new().await?;
+
+        Ok(Arc::new(Self {
+            database_url,
+            dir,
+            cron_schedule,
+            keep: keep.max(1),
+            s3: s3.map(S3Client::new),
+            last_run: RwLock::new(None),
+            scheduler: Arc::new(RwLock::new(scheduler)),
+        }))
+    }
+
+    pub async fn start_scheduler(self: &Arc<Self>) -> Result<()> {
+        let service = Arc::clone(self);
+
+        let job = Job::new_async(self.cron_schedule.as_str(), move |_uuid, _lock| {
+            let service = service.clone();
+
+            Box::pin(async move {
+                service.run_backup().await;
+            })
+        })?;
+
+        self.scheduler.write().await.add(job).await?;
+        self.scheduler.write().await.start().await?;
+
+        tracing::info!(
+            "Database backups scheduled with cron: {}",
+            self.cron_schedule
+        );
+        Ok(())
+    }
+
+    /// Dump the database, copy it to S3 if configured and remove backups past the ones to keep.
+    /// A failed upload is reported, but the local backup is kept.
+    pub async fn run_backup(&self) -> BackupRun {
+        let started_at = Utc::now();
+        let mut run = BackupRun {
+            started_at,
+            finished_at: started_at,
+            file: None,
+            uploaded: false,
+            error: None,
+        };
+
+        match self.dump(&started_at).await {
+            Ok(name) => {
+                if let Some(s3) = &self.s3 {
+                    match self.upload(s3, &name).await {
+                        Ok(()) => run.uploaded = true,
+                        Err(e) => run.error = Some(format!("Upload failed: {}", e)),
+                    }
+                }
+                if let Err(e) = self.rotate().await {
+                    tracing::warn!("Failed to rotate database backups: {}", e);
+                }
+                run.file = Some(name);
+            }
+            Err(e) => run.error = Some(e.to_string()),
+        }
+
+        run.finished_at = Utc::now();
+        match &run.error {
+            Some(error) => tracing::error!("Database backup failed: {}", error),
+            None => tracing::info!("Database backup {:?} written", run.file),
+        }
+
+        *self.last_run.write().await = Some(run.clone());
+        run
+    }
+
+    async fn dump(&self, started_at: &DateTime<Utc>) -> Result<String> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+
+        let name = format!(
+            "{}{}{}",
+            BACKUP_PREFIX,
+            started_at.format("%Y%m%dT%H%M%SZ"),
+            BACKUP_EXTENSION
+        );
+        let path = self.dir.join(&name);
+
+        let (database_url, password) = split_password(&self.database_url)?;
+
+        let mut command = Command::new("pg_dump");
+        command
+            .arg("--format=custom")
+            .arg("--file")
+            .arg(&path)
+            .arg("--dbname")
+            .arg(&database_url);
+        if let Some(password) = password {
+            command.env("PGPASSWORD", password);
+        }
+        let output = command.output().await?;
+
+        if !output.status.success() {
+            let _ = tokio::fs::remove_file(&path).await;
+            bail!(
+                "pg_dump failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(name)
+    }
+
+    async fn upload(&self, s3: &S3Client, name: &str) -> Result<()> {
+        let body = tokio::fs::read(self.dir.join(name)).await?;
+        s3.put(name, body).await
+    }
+
+    /// Remove the oldest backups past the number to keep, locally and from S3
+    async fn rotate(&self) -> Result<()> {
+        for backup in self.list_backups().await?.into_iter().skip(self.keep) {
+            tokio::fs::remove_file(self.dir.join(&backup.name)).await?;
+
+            if let Some(s3) = &self.s3
+                && let Err(e) = s3.delete(&backup.name).await
+            {
+                tracing::warn!("Failed to delete backup {} from S3: {}", backup.name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Local backups, newest first
+    pub async fn list_backups(&self) -> Result<Vec<BackupFile>> {
+        let mut backups = Vec::new();
+
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(backups),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(BACKUP_PREFIX) || !name.ends_with(BACKUP_EXTENSION) {
+                continue;
+            }
+
+            let metadata = entry.metadata().await?;
+            backups.push(BackupFile {
+                name,
+                size: metadata.len(),
+                created_at: metadata.modified()?.into(),
+            });
+        }
+
+        // Names are timestamped, so they sort by age
+        backups.sort_by(|a, b| b.name.cmp(&a.name));
+        Ok(backups)
+    }
+
+    pub async fn status(&self) -> Result<BackupStatus> {
+        Ok(BackupStatus {
+            enabled: true,
+            cron_schedule: Some(self.cron_schedule.clone()),
+            keep: self.keep,
+            s3_enabled: self.s3.is_some(),
+            last_run: self.last_run.read().await.clone(),
+            backups: self.list_backups().await?,
+        })
+    }
+}
+
+/// Replace the database's contents with a backup made by `BackupService`. Requires
+/// `pg_restore` on the PATH.
+pub async fn restore(database_url: &str, file: &Path) -> Result<()> {
+    if !file.is_file() {
+        bail!("Backup file {} does not exist", file.display());
+    }
+
+    let (database_url, password) = split_password(database_url)?;
+
+    let mut command = Command::new("pg_restore");
+    command
+        .arg("--clean")
+        .arg("--if-exists")
+        .arg("--no-owner")
+        .arg("--single-transaction")
+        .arg("--dbname")
+        .arg(&database_url)
+        .arg(file);
+    if let Some(password) = password {
+        command.env("PGPASSWORD", password);
+    }
+    let output = command.output().await?;
+
+    if !output.status.success() {
+        bail!(
+            "pg_restore failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// The database URL without its password, and the password, which pg_dump and pg_restore are
+/// given in `PGPASSWORD` so it doesn't show up in the process list
+fn split_password(database_url: &str) -> Result<(String, Option<String>)> {
+    let mut url = Url::parse(database_url)?;
+    let password = url
+        .password()
+        .map(|password| urlencoding::decode(password).map(|p| p.into_owned()))
+        .transpose()?;
+    if url.set_password(None).is_err() {
+        bail!("Database URL has no host");
+    }
+
+    Ok((url.to_string(), password))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn password_is_taken_out_of_the_database_url() {
+        assert_eq!(
+            split_password("postgresql://postgres:p%40ss@db:5432/netvisor").unwrap(),
+            (
+                "postgresql://postgres@db:5432/netvisor".to_string(),
+                Some("p@ss".to_string())
+            )
+        );
+        assert_eq!(
+            split_password("postgresql://postgres@db/netvisor").unwrap(),
+            ("postgresql://postgres@db/netvisor".to_string(), None)
+        );
+    }
+}
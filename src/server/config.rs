@@ -1,16 +1,32 @@
-use crate::server::{auth::oidc::OidcClient, shared::services::factory::ServiceFactory};
+use crate::server::{
+    auth::{oidc::OidcClient, rate_limit::RateLimits},
+    backups::{r#impl::s3::S3Config, service::BackupService},
+    digests::r#impl::base::DigestFrequency,
+    integrations::mqtt::client::{MqttClient, MqttConnection},
+    service_definitions::r#impl::{
+        files::load_dir, plugins::WasmPlugin, runtime::RuntimeServiceDefinition,
+    },
+    services::definitions::ServiceDefinitionRegistry,
+    shared::{
+        email::EmailClient, services::factory::ServiceFactory, types::locale::LocaleRegistry,
+    },
+};
 use anyhow::{Error, Result};
 use figment::{
     Figment,
-    providers::{Env, Serialized},
+    providers::{Env, Format, Json, Serialized},
 };
 use serde::{Deserialize, Serialize};
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock, RwLock},
+};
+use tracing_subscriber::{EnvFilter, Registry, reload};
 
 use crate::server::shared::storage::factory::StorageFactory;
 
 /// CLI arguments structure (for figment integration)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CliArgs {
     pub server_port: Option<u16>,
     pub log_level: Option<String>,
@@ -19,11 +35,13 @@ pub struct CliArgs {
     pub integrated_daemon_url: Option<String>,
     pub use_secure_session_cookies: Option<bool>,
     pub disable_registration: bool,
+    pub demo: bool,
     pub oidc_issuer_url: Option<String>,
     pub oidc_client_id: Option<String>,
     pub oidc_client_secret: Option<String>,
     pub oidc_redirect_url: Option<String>,
     pub oidc_provider_name: Option<String>,
+    pub config_file: Option<PathBuf>,
 }
 
 /// Flattened server configuration struct
@@ -51,9 +69,16 @@ pub struct ServerConfig {
     /// Use secure with issued session cookies
     pub use_secure_session_cookies: bool,
 
-    /// Disable user registration endpoint
+    /// Only let invited users register
     pub disable_registration: bool,
 
+    /// Comma-separated emails of the users who run the server, who can reload its configuration
+    /// and take backups. Nobody can when unset.
+    pub operator_emails: String,
+
+    /// URL the web UI is served at, e.g. `https://netvisor.example.com`, for links in emails
+    pub public_url: Option<String>,
+
     /// OIDC issuer URL
     pub oidc_issuer_url: Option<String>,
 
@@ -68,6 +93,119 @@ pub struct ServerConfig {
 
     /// OIDC redirect url
     pub oidc_provider_name: Option<String>,
+
+    /// Remove orphaned records during the periodic maintenance task instead of only logging them
+    pub auto_remove_orphans: bool,
+
+    /// MQTT broker host; MQTT integrations are disabled when unset
+    pub mqtt_host: Option<String>,
+
+    /// MQTT broker port
+    pub mqtt_port: u16,
+
+    /// MQTT username
+    pub mqtt_username: Option<String>,
+
+    /// MQTT password
+    pub mqtt_password: Option<String>,
+
+    /// Connect to the MQTT broker over TLS
+    pub mqtt_tls: bool,
+
+    /// PEM CA certificate to verify the MQTT broker with, instead of the system roots
+    pub mqtt_ca_cert_path: Option<PathBuf>,
+
+    /// Prefix of the topics NetVisor publishes to, e.g. `netvisor/events/host_offline`
+    pub mqtt_topic_prefix: String,
+
+    /// Publish host and discovery events to MQTT
+    pub mqtt_events: bool,
+
+    /// Publish host presence to Home Assistant via MQTT discovery
+    pub home_assistant_presence: bool,
+
+    /// Home Assistant MQTT discovery topic prefix
+    pub home_assistant_discovery_prefix: String,
+
+    /// Seconds since a host was last seen by discovery before it is reported as offline, for
+    /// hosts no daemon pings
+    pub home_assistant_offline_after_secs: i64,
+
+    /// SMTP server host; emails are disabled when unset
+    pub smtp_host: Option<String>,
+
+    /// SMTP server port (STARTTLS)
+    pub smtp_port: u16,
+
+    /// SMTP username
+    pub smtp_username: Option<String>,
+
+    /// SMTP password
+    pub smtp_password: Option<String>,
+
+    /// Sender address for emails
+    pub smtp_from: String,
+
+    /// Email each network owner a digest of inventory changes daily or weekly
+    pub digest_frequency: Option<DigestFrequency>,
+
+    /// Directory of additional `<locale>.json` metadata translation catalogs
+    pub locales_path: Option<PathBuf>,
+
+    /// Directory of `.yaml`/`.toml` files with custom service definitions to match during discovery
+    pub service_definitions_path: Option<PathBuf>,
+
+    /// Directory of `.wasm` service definition plugins, validated and loaded at startup
+    pub plugins_path: Option<PathBuf>,
+
+    /// Fill the first user's network with generated demo data instead of running real scans
+    pub demo: bool,
+
+    /// Directory to write scheduled database backups to; backups are disabled when unset
+    pub backup_path: Option<PathBuf>,
+
+    /// Cron expression with seconds for when to back up the database
+    pub backup_cron_schedule: String,
+
+    /// Number of most recent backups to keep
+    pub backup_keep: usize,
+
+    /// S3-compatible endpoint to copy backups to, e.g. `https://s3.eu-west-1.amazonaws.com`
+    pub backup_s3_endpoint: Option<String>,
+
+    /// Bucket to copy backups to
+    pub backup_s3_bucket: Option<String>,
+
+    /// Region to sign S3 requests for
+    pub backup_s3_region: String,
+
+    /// S3 access key ID
+    pub backup_s3_access_key_id: Option<String>,
+
+    /// S3 secret access key
+    pub backup_s3_secret_access_key: Option<String>,
+
+    /// Prefix of backup object keys, e.g. `netvisor/`
+    pub backup_s3_prefix: String,
+
+    /// Take client addresses for the audit log from `X-Forwarded-For`; only enable behind a
+    /// reverse proxy that sets it
+    pub trust_forwarded_for: bool,
+
+    /// Requests per minute allowed from each client IP, and for each signed-in user or API key;
+    /// 0 disables the limit
+    pub rate_limit_per_minute: u32,
+
+    /// Login, registration and invite lookup attempts per minute allowed from each client IP;
+    /// 0 disables the limit
+    pub auth_rate_limit_per_minute: u32,
+
+    /// Look up the software versions services announce in the NVD CVE feed and annotate the
+    /// services with their known vulnerabilities
+    pub vulnerability_feed: bool,
+
+    /// NVD API key, which raises the feed's rate limit tenfold
+    pub nvd_api_key: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -89,20 +227,67 @@ impl Default for ServerConfig {
             use_secure_session_cookies: false,
             integrated_daemon_url: None,
             disable_registration: false,
+            operator_emails: String::new(),
+            public_url: None,
             oidc_client_id: None,
             oidc_client_secret: None,
             oidc_issuer_url: None,
             oidc_redirect_url: None,
             oidc_provider_name: None,
+            auto_remove_orphans: false,
+            mqtt_host: None,
+            mqtt_port: 1883,
+            mqtt_username: None,
+            mqtt_password: None,
+            mqtt_tls: false,
+            mqtt_ca_cert_path: None,
+            mqtt_topic_prefix: "netvisor".to_string(),
+            mqtt_events: true,
+            home_assistant_presence: true,
+            home_assistant_discovery_prefix: "homeassistant".to_string(),
+            home_assistant_offline_after_secs: 60 * 60,
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_from: "NetVisor <netvisor@localhost>".to_string(),
+            digest_frequency: None,
+            locales_path: None,
+            service_definitions_path: None,
+            plugins_path: None,
+            demo: false,
+            backup_path: None,
+            backup_cron_schedule: "0 0 3 * * *".to_string(),
+            backup_keep: 7,
+            backup_s3_endpoint: None,
+            backup_s3_bucket: None,
+            backup_s3_region: "us-east-1".to_string(),
+            backup_s3_access_key_id: None,
+            backup_s3_secret_access_key: None,
+            backup_s3_prefix: String::new(),
+            trust_forwarded_for: false,
+            rate_limit_per_minute: 1200,
+            auth_rate_limit_per_minute: 10,
+            vulnerability_feed: true,
+            nvd_api_key: None,
         }
     }
 }
 
 impl ServerConfig {
     pub fn load(cli_args: CliArgs) -> anyhow::Result<Self> {
-        // Standard configuration layering: Defaults → Env → CLI (highest priority)
+        // Standard configuration layering: Defaults → File → Env → CLI (highest priority)
         let mut figment = Figment::from(Serialized::defaults(ServerConfig::default()));
 
+        // Optional JSON config file, re-read on every reload
+        let config_file = cli_args
+            .config_file
+            .clone()
+            .or_else(|| std::env::var_os("NETVISOR_CONFIG_FILE").map(PathBuf::from));
+        if let Some(config_file) = config_file {
+            figment = figment.merge(Json::file(config_file));
+        }
+
         // Add environment variables with NETVISOR_ prefix
         figment = figment.merge(Env::prefixed("NETVISOR_"));
 
@@ -143,6 +328,10 @@ impl ServerConfig {
 
         figment = figment.merge(("disable_registration", cli_args.disable_registration));
 
+        if cli_args.demo {
+            figment = figment.merge(("demo", true));
+        }
+
         let config: ServerConfig = figment
             .extract()
             .map_err(|e| Error::msg(format!("Configuration error: {}", e)))?;
@@ -150,24 +339,50 @@ impl ServerConfig {
         Ok(config)
     }
 
+    /// Whether the user with this email is one of the server's operators
+    pub fn is_operator(&self, email: &str) -> bool {
+        self.operator_emails
+            .split(',')
+            .any(|operator| operator.trim().eq_ignore_ascii_case(email))
+    }
+
     pub fn database_url(&self) -> String {
         self.database_url.to_string()
     }
+
+    /// S3 settings for backups, when an endpoint, bucket and credentials are all set
+    pub fn backup_s3(&self) -> Option<S3Config> {
+        Some(S3Config {
+            endpoint: self.backup_s3_endpoint.clone()?,
+            bucket: self.backup_s3_bucket.clone()?,
+            region: self.backup_s3_region.clone(),
+            access_key_id: self.backup_s3_access_key_id.clone()?,
+            secret_access_key: self.backup_s3_secret_access_key.clone()?,
+            prefix: self.backup_s3_prefix.clone(),
+        })
+    }
 }
 
-pub struct AppState {
+/// Handle for swapping the server's log filter at runtime
+pub type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Tracing filter directives for a log level
+pub fn log_directives(log_level: &str) -> String {
+    format!("netvisor={},server={}", log_level, log_level)
+}
+
+/// Settings that can change without a restart, by sending SIGHUP or an operator calling
+/// `POST /api/config/reload`: log level, registration toggle, operators, OIDC and SMTP.
+/// Notification channels are stored per network and apply as soon as they're saved.
+pub struct ReloadableConfig {
+    /// The configuration these settings were last loaded from
     pub config: ServerConfig,
-    pub storage: StorageFactory,
-    pub services: ServiceFactory,
     pub oidc_client: Option<Arc<OidcClient>>,
+    pub email_client: Option<Arc<EmailClient>>,
 }
 
-impl AppState {
-    pub async fn new(config: ServerConfig) -> Result<Arc<Self>, Error> {
-        let storage =
-            StorageFactory::new(&config.database_url(), config.use_secure_session_cookies).await?;
-        let services = ServiceFactory::new(&storage).await?;
-
+impl ReloadableConfig {
+    pub fn from_config(config: ServerConfig) -> Result<Self, Error> {
         let oidc_client =
             if let (Some(issuer_url), Some(redirect_url), Some(client_id), Some(client_secret)) = (
                 &config.oidc_issuer_url,
@@ -185,11 +400,176 @@ impl AppState {
                 None
             };
 
+        let email_client = match &config.smtp_host {
+            Some(host) => Some(Arc::new(EmailClient::new(
+                host,
+                config.smtp_port,
+                config.smtp_username.as_deref(),
+                config.smtp_password.as_deref(),
+                &config.smtp_from,
+            )?)),
+            None => None,
+        };
+
+        Ok(Self {
+            config,
+            oidc_client,
+            email_client,
+        })
+    }
+}
+
+pub struct AppState {
+    /// Configuration as loaded at startup; see `reloadable` for settings that can change
+    pub config: ServerConfig,
+    pub storage: StorageFactory,
+    pub services: ServiceFactory,
+    pub mqtt_client: Option<Arc<MqttClient>>,
+    /// Scheduled database backups, when a backup path is configured
+    pub backup_service: Option<Arc<BackupService>>,
+    pub locales: LocaleRegistry,
+    pub rate_limits: RateLimits,
+    pub log_filter: OnceLock<LogFilterHandle>,
+    cli_args: CliArgs,
+    reloadable: RwLock<Arc<ReloadableConfig>>,
+}
+
+impl AppState {
+    pub async fn new(config: ServerConfig, cli_args: CliArgs) -> Result<Arc<Self>, Error> {
+        let storage =
+            StorageFactory::new(&config.database_url(), config.use_secure_session_cookies).await?;
+        let services = ServiceFactory::new(&storage).await?;
+
+        // Files first, so definitions added through the API replace ones with the same name
+        if let Some(dir) = &config.service_definitions_path {
+            for definition in load_dir(dir)? {
+                ServiceDefinitionRegistry::register(RuntimeServiceDefinition::new(&definition)?)?;
+            }
+        }
+        if let Some(dir) = &config.plugins_path {
+            for plugin in WasmPlugin::load_dir(dir)? {
+                tracing::info!("Loaded service definition plugin {}", plugin.name());
+                ServiceDefinitionRegistry::register(RuntimeServiceDefinition::from_plugin(
+                    plugin,
+                )?)?;
+            }
+        }
+        services
+            .custom_service_definition_service
+            .register_stored()
+            .await?;
+
+        let mqtt_client = match &config.mqtt_host {
+            Some(host) => Some(Arc::new(MqttClient::connect(&MqttConnection {
+                host: host.clone(),
+                port: config.mqtt_port,
+                username: config.mqtt_username.clone(),
+                password: config.mqtt_password.clone(),
+                tls: config.mqtt_tls,
+                ca_cert_path: config.mqtt_ca_cert_path.clone(),
+                topic_prefix: config.mqtt_topic_prefix.clone(),
+            })?)),
+            None => None,
+        };
+
+        let backup_service = match &config.backup_path {
+            Some(dir) => Some(
+                BackupService::new(
+                    config.database_url(),
+                    dir.clone(),
+                    config.backup_cron_schedule.clone(),
+                    config.backup_keep,
+                    config.backup_s3(),
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let reloadable = ReloadableConfig::from_config(config.clone())?;
+
+        let locales = LocaleRegistry::load(config.locales_path.as_deref())?;
+        let rate_limits = RateLimits::new(&config);
+
         Ok(Arc::new(Self {
             config,
             storage,
             services,
-            oidc_client,
+            mqtt_client,
+            backup_service,
+            locales,
+            rate_limits,
+            log_filter: OnceLock::new(),
+            cli_args,
+            reloadable: RwLock::new(Arc::new(reloadable)),
         }))
     }
+
+    /// Current values of the settings that can be reloaded
+    pub fn reloadable(&self) -> Arc<ReloadableConfig> {
+        self.reloadable
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Re-read configuration from the environment (CLI flags still take precedence) and apply
+    /// the reloadable settings. Returns the names of the settings that changed.
+    pub fn reload_config(&self) -> Result<Vec<&'static str>, Error> {
+        let reloaded = ReloadableConfig::from_config(ServerConfig::load(self.cli_args.clone())?)?;
+        let current = self.reloadable();
+        let (new, old) = (&reloaded.config, &current.config);
+
+        let mut changed = Vec::new();
+        if new.log_level != old.log_level {
+            if let Some(handle) = self.log_filter.get() {
+                handle.reload(EnvFilter::new(log_directives(&new.log_level)))?;
+            }
+            changed.push("log_level");
+        }
+        if new.disable_registration != old.disable_registration {
+            changed.push("disable_registration");
+        }
+        if new.operator_emails != old.operator_emails {
+            changed.push("operator_emails");
+        }
+        if new.public_url != old.public_url {
+            changed.push("public_url");
+        }
+        if (
+            &new.oidc_issuer_url,
+            &new.oidc_client_id,
+            &new.oidc_client_secret,
+            &new.oidc_redirect_url,
+            &new.oidc_provider_name,
+        ) != (
+            &old.oidc_issuer_url,
+            &old.oidc_client_id,
+            &old.oidc_client_secret,
+            &old.oidc_redirect_url,
+            &old.oidc_provider_name,
+        ) {
+            changed.push("oidc");
+        }
+        if (
+            &new.smtp_host,
+            new.smtp_port,
+            &new.smtp_username,
+            &new.smtp_password,
+            &new.smtp_from,
+        ) != (
+            &old.smtp_host,
+            old.smtp_port,
+            &old.smtp_username,
+            &old.smtp_password,
+            &old.smtp_from,
+        ) {
+            changed.push("smtp");
+        }
+
+        *self.reloadable.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(reloaded);
+
+        tracing::info!("Reloaded configuration, changed: {:?}", changed);
+        Ok(changed)
+    }
 }
@@ -1,22 +1,32 @@
 use crate::server::{
-    auth::middleware::AuthenticatedDaemon,
+    auth::middleware::{AuthenticatedDaemon, AuthenticatedUser},
     config::AppState,
     daemons::r#impl::{
-        api::{DaemonCapabilities, DaemonRegistrationRequest, DaemonRegistrationResponse},
-        base::{Daemon, DaemonBase},
+        api::{
+            DaemonCapabilities, DaemonHeartbeat, DaemonRegistrationRequest,
+            DaemonRegistrationResponse, MonitorTarget, ReachabilityChange,
+        },
+        base::{Daemon, DaemonBase, DaemonStatus},
     },
+    diagnostics::r#impl::base::DiagnosticUpdate,
     discovery::r#impl::{
         base::{Discovery, DiscoveryBase},
-        types::{DiscoveryType, HostNamingFallback, RunType},
+        scan_settings::ScanSettingsOverrides,
+        types::{DiscoveryType, RunType},
     },
     hosts::r#impl::base::{Host, HostBase},
+    monitoring::r#impl::api::{HealthCheckReport, HealthCheckTarget},
     shared::{
         handlers::traits::{
-            create_handler, delete_handler, get_all_handler, get_by_id_handler, update_handler,
+            check_access, create_handler, delete_handler, get_all_handler, get_by_id_handler,
+            update_handler,
         },
         services::traits::CrudService,
         storage::traits::StorableEntity,
-        types::api::{ApiError, ApiResponse, ApiResult},
+        types::{
+            api::{ApiError, ApiResponse, ApiResult},
+            metadata::HasId,
+        },
     },
 };
 use axum::{
@@ -28,6 +38,7 @@ use axum::{
 use axum_macros::debug_handler;
 use chrono::Utc;
 use std::sync::Arc;
+use strum::IntoEnumIterator;
 use uuid::Uuid;
 
 pub fn create_router() -> Router<Arc<AppState>> {
@@ -40,10 +51,14 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .route("/register", post(register_daemon))
         .route("/{id}/heartbeat", post(receive_heartbeat))
         .route("/{id}/update-capabilities", post(update_capabilities))
+        .route("/{id}/discovery-types", get(get_discovery_types))
+        .route("/{id}/monitor-targets", get(get_monitor_targets))
+        .route("/{id}/reachability", post(receive_reachability))
+        .route("/{id}/health-checks", get(get_health_checks))
+        .route("/{id}/health-results", post(receive_health_results))
+        .route("/{id}/diagnostics", post(receive_diagnostic_update))
 }
 
-const DAILY_MIDNIGHT_CRON: &str = "0 0 0 * * *";
-
 /// Register a new daemon
 #[debug_handler]
 async fn register_daemon(
@@ -71,6 +86,9 @@ async fn register_daemon(
         port: request.daemon_port,
         capabilities: request.capabilities.clone(),
         last_seen: Utc::now(),
+        scan_settings: ScanSettingsOverrides::default(),
+        status: DaemonStatus::Online,
+        health: None,
     });
 
     daemon.id = request.daemon_id;
@@ -80,66 +98,78 @@ async fn register_daemon(
         .await
         .map_err(|e| ApiError::internal_error(&format!("Failed to register daemon: {}", e)))?;
 
+    let settings = state
+        .services
+        .network_settings_service
+        .for_network(&request.network_id)
+        .await?;
+
     let discovery_service = state.services.discovery_service.clone();
 
-    let self_report_discovery = discovery_service
-        .create_discovery(Discovery::new(DiscoveryBase {
-            run_type: RunType::Scheduled {
-                cron_schedule: DAILY_MIDNIGHT_CRON.to_string(),
-                last_run: None,
-                enabled: true,
+    let discovery_types = [
+        (
+            "Self Report",
+            DiscoveryType::SelfReport { host_id: host.id },
+        ),
+        (
+            "Docker",
+            DiscoveryType::Docker {
+                host_id: host.id,
+                host_naming_fallback: settings.host_naming_fallback,
             },
-            discovery_type: DiscoveryType::SelfReport { host_id: host.id },
-            name: format!("Self Report @ {}", request.daemon_ip),
-            daemon_id: request.daemon_id,
-            network_id: request.network_id,
-        }))
-        .await?;
+        ),
+        (
+            "Network Scan",
+            DiscoveryType::Network {
+                subnet_ids: None,
+                host_naming_fallback: settings.host_naming_fallback,
+                scan_profile_id: None,
+                parallel_daemon_ids: Vec::new(),
+                shard: None,
+            },
+        ),
+        (
+            "SSDP",
+            DiscoveryType::Ssdp {
+                host_naming_fallback: settings.host_naming_fallback,
+            },
+        ),
+        // Finishes with nothing found on hosts without a VPN
+        (
+            "VPN Tunnels",
+            DiscoveryType::VpnTunnels { host_id: host.id },
+        ),
+    ];
 
-    discovery_service
-        .start_session(self_report_discovery)
-        .await?;
+    for (name, discovery_type) in discovery_types {
+        // Docker without a socket, or types the daemon's version doesn't know yet
+        if let Some(reason) = request.capabilities.incompatibility(&discovery_type) {
+            tracing::debug!(
+                "Not scheduling {} on daemon {}: {}",
+                name,
+                request.daemon_id,
+                reason
+            );
+            continue;
+        }
 
-    if request.capabilities.has_docker_socket {
-        let docker_discovery = discovery_service
+        let discovery = discovery_service
             .create_discovery(Discovery::new(DiscoveryBase {
                 run_type: RunType::Scheduled {
-                    cron_schedule: DAILY_MIDNIGHT_CRON.to_string(),
+                    cron_schedule: settings.discovery_schedule.clone(),
                     last_run: None,
                     enabled: true,
                 },
-                discovery_type: DiscoveryType::Docker {
-                    host_id: host.id,
-                    host_naming_fallback: HostNamingFallback::BestService,
-                },
-                name: format!("Docker @ {}", request.daemon_ip),
+                discovery_type,
+                name: format!("{} @ {}", name, request.daemon_ip),
                 daemon_id: request.daemon_id,
                 network_id: request.network_id,
             }))
             .await?;
 
-        discovery_service.start_session(docker_discovery).await?;
+        discovery_service.start_session(discovery).await?;
     }
 
-    let network_discovery = discovery_service
-        .create_discovery(Discovery::new(DiscoveryBase {
-            run_type: RunType::Scheduled {
-                cron_schedule: DAILY_MIDNIGHT_CRON.to_string(),
-                last_run: None,
-                enabled: true,
-            },
-            discovery_type: DiscoveryType::Network {
-                subnet_ids: None,
-                host_naming_fallback: HostNamingFallback::BestService,
-            },
-            name: format!("Network Scan @ {}", request.daemon_ip),
-            daemon_id: request.daemon_id,
-            network_id: request.network_id,
-        }))
-        .await?;
-
-    discovery_service.start_session(network_discovery).await?;
-
     Ok(Json(ApiResponse::success(DaemonRegistrationResponse {
         daemon: registered_daemon,
         host_id: host.id,
@@ -172,26 +202,151 @@ async fn update_capabilities(
     Ok(Json(ApiResponse::success(())))
 }
 
-/// Receive heartbeat from daemon
+/// Discovery types the daemon can run, by name, going by the capabilities it reported
+async fn get_discovery_types(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<&'static str>>>> {
+    let daemon = state
+        .services
+        .daemon_service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Daemon '{}' not found", &id)))?;
+    check_access(&state, &user.0, &daemon).await?;
+
+    let discovery_types = DiscoveryType::iter()
+        .filter(|t| daemon.base.capabilities.incompatibility(t).is_none())
+        .map(|t| t.id())
+        .collect();
+
+    Ok(Json(ApiResponse::success(discovery_types)))
+}
+
+/// Receive heartbeat from daemon, with its health if it's recent enough to send it
 async fn receive_heartbeat(
     State(state): State<Arc<AppState>>,
     _daemon: AuthenticatedDaemon,
     Path(id): Path<Uuid>,
+    heartbeat: Option<Json<DaemonHeartbeat>>,
 ) -> ApiResult<Json<ApiResponse<()>>> {
     let service = &state.services.daemon_service;
 
-    let mut daemon = service
+    let daemon = service
         .get_by_id(&id)
         .await
         .map_err(|e| ApiError::internal_error(&format!("Failed to get daemon: {}", e)))?
         .ok_or_else(|| ApiError::not_found(format!("Daemon '{}' not found", &id)))?;
 
-    daemon.base.last_seen = Utc::now();
-
     service
-        .update(&mut daemon)
+        .record_heartbeat(daemon, heartbeat.map(|Json(h)| h))
         .await
         .map_err(|e| ApiError::internal_error(&format!("Failed to update heartbeat: {}", e)))?;
 
     Ok(Json(ApiResponse::success(())))
 }
+
+/// The daemon with the id, if it's in the network the calling daemon authenticated for
+async fn authenticated_daemon(
+    state: &AppState,
+    authenticated: &AuthenticatedDaemon,
+    id: &Uuid,
+) -> ApiResult<Daemon> {
+    state
+        .services
+        .daemon_service
+        .get_by_id(id)
+        .await?
+        .filter(|daemon| daemon.base.network_id == authenticated.0)
+        .ok_or_else(|| ApiError::not_found(format!("Daemon '{}' not found", id)))
+}
+
+/// Host addresses the daemon's ping monitor should watch
+async fn get_monitor_targets(
+    State(state): State<Arc<AppState>>,
+    authenticated: AuthenticatedDaemon,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<MonitorTarget>>>> {
+    let daemon = authenticated_daemon(&state, &authenticated, &id).await?;
+
+    let targets = state.services.host_service.monitor_targets(&daemon).await?;
+
+    Ok(Json(ApiResponse::success(targets)))
+}
+
+/// Receive up/down changes from the daemon's ping monitor
+async fn receive_reachability(
+    State(state): State<Arc<AppState>>,
+    authenticated: AuthenticatedDaemon,
+    Path(id): Path<Uuid>,
+    Json(changes): Json<Vec<ReachabilityChange>>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let daemon = authenticated_daemon(&state, &authenticated, &id).await?;
+
+    state
+        .services
+        .host_service
+        .record_reachability(&daemon, changes)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// A result from a diagnostic the daemon is running
+async fn receive_diagnostic_update(
+    State(state): State<Arc<AppState>>,
+    daemon: AuthenticatedDaemon,
+    Path(id): Path<Uuid>,
+    Json(update): Json<DiagnosticUpdate>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = &state.services.diagnostic_service;
+    let not_found = || ApiError::not_found(format!("Diagnostic run '{}' not found", update.run_id));
+
+    let run = service
+        .get_run(&update.run_id)
+        .await
+        .ok_or_else(not_found)?;
+    if run.daemon_id != id || run.network_id != daemon.0 {
+        return Err(not_found());
+    }
+
+    service.record(update).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Service health checks the daemon should run
+async fn get_health_checks(
+    State(state): State<Arc<AppState>>,
+    authenticated: AuthenticatedDaemon,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<HealthCheckTarget>>>> {
+    let daemon = authenticated_daemon(&state, &authenticated, &id).await?;
+
+    let targets = state
+        .services
+        .monitoring_service
+        .targets_for_daemon(&daemon)
+        .await?;
+
+    Ok(Json(ApiResponse::success(targets)))
+}
+
+/// Receive health check results from the daemon
+async fn receive_health_results(
+    State(state): State<Arc<AppState>>,
+    authenticated: AuthenticatedDaemon,
+    Path(id): Path<Uuid>,
+    Json(reports): Json<Vec<HealthCheckReport>>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let daemon = authenticated_daemon(&state, &authenticated, &id).await?;
+
+    state
+        .services
+        .monitoring_service
+        .record_results(&daemon, reports)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
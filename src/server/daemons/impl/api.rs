@@ -1,12 +1,23 @@
-use std::net::IpAddr;
+use std::{collections::HashMap, net::IpAddr};
 
 use crate::{
     daemon::discovery::types::base::{
         DiscoveryPhase, DiscoverySessionInfo, DiscoverySessionUpdate,
     },
-    server::{daemons::r#impl::base::Daemon, discovery::r#impl::types::DiscoveryType},
+    server::{
+        daemons::r#impl::base::Daemon,
+        discovery::r#impl::{
+            results::DiscoveryResults, scan_settings::ScanSettings, types::DiscoveryType,
+        },
+        network_settings::r#impl::base::TailnetSettings,
+        service_definitions::r#impl::{base::CustomServiceDefinitionBase, plugins::PluginModule},
+        shared::types::metadata::HasId,
+        ssh_credentials::r#impl::base::SshLogin,
+        winrm_credentials::r#impl::base::WinrmLogin,
+    },
 };
 use chrono::{DateTime, Utc};
+use cidr::IpCidr;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -17,6 +28,70 @@ pub struct DaemonCapabilities {
     pub has_docker_socket: bool,
     #[serde(default)]
     pub interfaced_subnet_ids: Vec<Uuid>,
+    /// What the daemon's build and host support. Unset for daemons from before they reported
+    /// it, which are offered every discovery type.
+    #[serde(default)]
+    pub features: Option<DaemonFeatures>,
+}
+
+/// Reported by a daemon when it registers and each time it reports itself
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct DaemonFeatures {
+    pub version: String,
+    /// Operating system and CPU architecture, as in `std::env::consts`
+    pub os: String,
+    pub arch: String,
+    /// Raw IP sockets can be opened, as root or with CAP_NET_RAW
+    pub raw_sockets: bool,
+    /// Frames can be sent and captured on its interfaces, needed by ARP sweeps rather than
+    /// reading the ARP table
+    pub pcap: bool,
+    /// Has an IPv6 address beyond link-local, so IPv6 subnets can be scanned
+    pub ipv6: bool,
+    /// Walks SNMP agents found by network scans
+    pub snmp: bool,
+    /// Discovery types the daemon runs, by name
+    pub discovery_types: Vec<String>,
+}
+
+impl DaemonCapabilities {
+    /// Why the daemon can't run a discovery of this type, if it can't
+    pub fn incompatibility(&self, discovery_type: &DiscoveryType) -> Option<String> {
+        if discovery_type.runs_on_server() {
+            return Some(format!(
+                "{} discovery runs on the server, not on daemons",
+                discovery_type
+            ));
+        }
+
+        if matches!(discovery_type, DiscoveryType::Docker { .. }) && !self.has_docker_socket {
+            return Some("The daemon has no access to a Docker socket".to_string());
+        }
+
+        let features = self.features.as_ref()?;
+
+        // Rescans of a host are network scans of a single address
+        let name = match discovery_type {
+            DiscoveryType::HostRescan { .. } => "Network",
+            other => other.id(),
+        };
+        if !features.discovery_types.iter().any(|t| t == name) {
+            return Some(format!(
+                "Daemon version {} doesn't support {} discovery",
+                features.version, name
+            ));
+        }
+
+        None
+    }
+
+    /// Whether the daemon can scan the subnet's addresses
+    pub fn supports_cidr(&self, cidr: &IpCidr) -> bool {
+        match (cidr, &self.features) {
+            (IpCidr::V6(_), Some(features)) => features.ipv6,
+            _ => true,
+        }
+    }
 }
 
 /// Daemon registration request from daemon to server
@@ -41,6 +116,80 @@ pub struct DaemonRegistrationResponse {
 pub struct DaemonDiscoveryRequest {
     pub session_id: Uuid,
     pub discovery_type: DiscoveryType,
+    #[serde(default)]
+    pub scan_settings: ScanSettings,
+    /// Custom service definitions registered on the server, to match alongside built-in ones
+    #[serde(default)]
+    pub service_definitions: Vec<CustomServiceDefinitionBase>,
+    /// Service definition plugins loaded on the server
+    #[serde(default)]
+    pub plugins: Vec<PluginModule>,
+    /// The network's tailnet API access, sent only with `Tailnet` discoveries
+    #[serde(default)]
+    pub tailnet: Option<TailnetSettings>,
+    /// Names from the network's synced DNS zones, preferred over reverse lookups
+    #[serde(default)]
+    pub dns_names: HashMap<IpAddr, String>,
+    /// Logins for hosts with SSH open, to read their inventory
+    #[serde(default)]
+    pub ssh_credentials: Vec<SshLogin>,
+    /// Logins for Windows hosts with WinRM open
+    #[serde(default)]
+    pub winrm_credentials: Vec<WinrmLogin>,
+}
+
+/// Sent by the daemon with each heartbeat. Daemons from before it existed send no body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonHeartbeat {
+    pub version: Option<String>,
+    /// Seconds since the daemon process started
+    pub uptime_secs: u64,
+    /// The discovery session the daemon finished most recently
+    pub last_scan: Option<LastScan>,
+    pub resources: DaemonResourceUsage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastScan {
+    pub session_id: Uuid,
+    pub phase: DiscoveryPhase,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Resource use of the daemon process, where the platform reports it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonResourceUsage {
+    /// Resident memory
+    pub memory_bytes: Option<u64>,
+    /// CPU time used since the daemon started, user and system
+    pub cpu_secs: Option<f64>,
+    pub open_files: Option<usize>,
+}
+
+/// A daemon that stopped sending heartbeats, or started again
+#[derive(Debug, Clone)]
+pub enum DaemonEvent {
+    WentOffline(Daemon),
+    CameOnline(Daemon),
+}
+
+/// A known host address for a daemon's ping monitor
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MonitorTarget {
+    pub host_id: Uuid,
+    pub ip: IpAddr,
+}
+
+/// A host that started or stopped answering a daemon's ping monitor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReachabilityChange {
+    pub host_id: Uuid,
+    pub ip: IpAddr,
+    pub reachable: bool,
+    pub latency_ms: Option<u32>,
+    pub checked_at: DateTime<Utc>,
 }
 
 /// Daemon discovery response (for immediate acknowledgment)
@@ -62,6 +211,12 @@ pub struct DiscoveryUpdatePayload {
     pub error: Option<String>,
     pub started_at: Option<DateTime<Utc>>,
     pub finished_at: Option<DateTime<Utc>>,
+    /// Hosts created, updated and skipped so far, and errors per address
+    #[serde(default)]
+    pub results: DiscoveryResults,
+    /// Shared by the sessions of a network scan split across daemons; set by the server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<Uuid>,
 }
 
 impl DiscoveryUpdatePayload {
@@ -82,6 +237,8 @@ impl DiscoveryUpdatePayload {
             error: None,
             started_at: None,
             finished_at: None,
+            results: DiscoveryResults::default(),
+            group_id: None,
         }
     }
 
@@ -89,6 +246,7 @@ impl DiscoveryUpdatePayload {
         discovery_type: DiscoveryType,
         info: DiscoverySessionInfo,
         update: DiscoverySessionUpdate,
+        results: DiscoveryResults,
     ) -> Self {
         Self {
             session_id: info.session_id,
@@ -101,6 +259,8 @@ impl DiscoveryUpdatePayload {
             error: update.error,
             started_at: info.started_at,
             finished_at: update.finished_at,
+            results,
+            group_id: None,
         }
     }
 }
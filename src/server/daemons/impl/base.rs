@@ -1,10 +1,14 @@
 use std::{fmt::Display, net::IpAddr};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
 use uuid::Uuid;
 
-use crate::server::daemons::r#impl::api::DaemonCapabilities;
+use crate::server::{
+    daemons::r#impl::api::{DaemonCapabilities, DaemonHeartbeat},
+    discovery::r#impl::scan_settings::ScanSettingsOverrides,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DaemonBase {
@@ -15,6 +19,33 @@ pub struct DaemonBase {
     pub port: u16,
     #[serde(default)]
     pub capabilities: DaemonCapabilities,
+    /// Scan timeouts and retries for this daemon, applied on top of its network's
+    #[serde(default)]
+    pub scan_settings: ScanSettingsOverrides,
+    /// Set by the server's monitor when heartbeats stop, and cleared by the next one
+    #[serde(default)]
+    pub status: DaemonStatus,
+    /// What the daemon sent with its last heartbeat
+    #[serde(default)]
+    pub health: Option<DaemonHeartbeat>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    strum_macros::Display,
+    EnumString,
+)]
+pub enum DaemonStatus {
+    #[default]
+    Online,
+    Offline,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +57,15 @@ pub struct Daemon {
     pub base: DaemonBase,
 }
 
+/// Daemons that have not sent a heartbeat within this window are considered offline
+pub const DAEMON_OFFLINE_AFTER_SECS: i64 = 90;
+
+impl Daemon {
+    pub fn is_online(&self) -> bool {
+        Utc::now() - self.base.last_seen < Duration::seconds(DAEMON_OFFLINE_AFTER_SECS)
+    }
+}
+
 impl Display for Daemon {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}: {}", self.base.ip, self.id)
@@ -1,6 +1,7 @@
 use crate::server::daemons::r#impl::base::Daemon;
 use crate::server::daemons::service::DaemonService;
 use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
 
 impl CrudHandlers for Daemon {
     type Service = DaemonService;
@@ -8,4 +9,12 @@ impl CrudHandlers for Daemon {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.daemon_service
     }
+
+    fn validate(&self) -> Result<(), String> {
+        self.base.scan_settings.validate()
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
@@ -8,9 +8,10 @@ use uuid::Uuid;
 
 use crate::server::{
     daemons::r#impl::{
-        api::DaemonCapabilities,
+        api::{DaemonCapabilities, DaemonHeartbeat},
         base::{Daemon, DaemonBase},
     },
+    discovery::r#impl::scan_settings::ScanSettingsOverrides,
     shared::storage::traits::{SqlValue, StorableEntity},
 };
 
@@ -65,6 +66,9 @@ impl StorableEntity for Daemon {
                     port,
                     capabilities,
                     last_seen,
+                    scan_settings,
+                    status,
+                    health,
                 },
         } = self.clone();
 
@@ -79,6 +83,9 @@ impl StorableEntity for Daemon {
                 "capabilities",
                 "port",
                 "ip",
+                "scan_settings",
+                "status",
+                "health",
             ],
             vec![
                 SqlValue::Uuid(id),
@@ -90,6 +97,9 @@ impl StorableEntity for Daemon {
                 SqlValue::DaemonCapabilities(capabilities),
                 SqlValue::U16(port),
                 SqlValue::IpAddr(ip),
+                SqlValue::Json(serde_json::to_value(&scan_settings)?),
+                SqlValue::String(status.to_string()),
+                SqlValue::Json(serde_json::to_value(&health)?),
             ],
         ))
     }
@@ -102,6 +112,16 @@ impl StorableEntity for Daemon {
             serde_json::from_value(row.get::<serde_json::Value, _>("capabilities"))
                 .or(Err(Error::msg("Failed to deserialize capabilities")))?;
 
+        let scan_settings: ScanSettingsOverrides =
+            serde_json::from_value(row.get::<serde_json::Value, _>("scan_settings"))
+                .or(Err(Error::msg("Failed to deserialize scan settings")))?;
+
+        let health: Option<DaemonHeartbeat> = serde_json::from_value(
+            row.get::<Option<serde_json::Value>, _>("health")
+                .unwrap_or_default(),
+        )
+        .or(Err(Error::msg("Failed to deserialize daemon health")))?;
+
         Ok(Daemon {
             id: row.get("id"),
             created_at: row.get("created_at"),
@@ -113,6 +133,9 @@ impl StorableEntity for Daemon {
                 host_id: row.get("host_id"),
                 network_id: row.get("network_id"),
                 capabilities,
+                scan_settings,
+                status: row.get::<String, _>("status").parse()?,
+                health,
             },
         })
     }
@@ -1,23 +1,43 @@
 use crate::server::{
     daemons::r#impl::{
-        api::{DaemonDiscoveryRequest, DaemonDiscoveryResponse},
-        base::Daemon,
+        api::{DaemonDiscoveryRequest, DaemonDiscoveryResponse, DaemonEvent, DaemonHeartbeat},
+        base::{DAEMON_OFFLINE_AFTER_SECS, Daemon, DaemonStatus},
     },
+    diagnostics::r#impl::base::DaemonDiagnosticRequest,
+    discovery::r#impl::{scan_settings::ScanSettings, types::DiscoveryType},
     hosts::r#impl::ports::PortBase,
-    services::r#impl::endpoints::{ApplicationProtocol, Endpoint},
+    integrations::dns::r#impl::base::DnsZoneSettings,
+    network_settings::service::NetworkSettingsService,
+    scan_profiles::r#impl::base::ScanProfile,
+    service_definitions::r#impl::base::CustomServiceDefinitionBase,
+    services::{
+        definitions::ServiceDefinitionRegistry,
+        r#impl::endpoints::{ApplicationProtocol, Endpoint},
+    },
     shared::{
-        services::traits::CrudService, storage::generic::GenericPostgresStorage,
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, generic::GenericPostgresStorage, traits::Storage},
         types::api::ApiResponse,
     },
+    ssh_credentials::service::SshCredentialService,
+    winrm_credentials::service::WinrmCredentialService,
 };
 use anyhow::{Error, Result};
 use async_trait::async_trait;
-use std::sync::Arc;
+use chrono::{Duration, Utc};
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 pub struct DaemonService {
     daemon_storage: Arc<GenericPostgresStorage<Daemon>>,
+    network_settings_service: Arc<NetworkSettingsService>,
+    dns_zone_storage: Arc<GenericPostgresStorage<DnsZoneSettings>>,
+    scan_profile_storage: Arc<GenericPostgresStorage<ScanProfile>>,
+    ssh_credential_service: Arc<SshCredentialService>,
+    winrm_credential_service: Arc<WinrmCredentialService>,
     client: reqwest::Client,
+    event_tx: broadcast::Sender<DaemonEvent>,
 }
 
 #[async_trait]
@@ -28,24 +48,211 @@ impl CrudService<Daemon> for DaemonService {
 }
 
 impl DaemonService {
-    pub fn new(daemon_storage: Arc<GenericPostgresStorage<Daemon>>) -> Self {
+    pub fn new(
+        daemon_storage: Arc<GenericPostgresStorage<Daemon>>,
+        network_settings_service: Arc<NetworkSettingsService>,
+        dns_zone_storage: Arc<GenericPostgresStorage<DnsZoneSettings>>,
+        scan_profile_storage: Arc<GenericPostgresStorage<ScanProfile>>,
+        ssh_credential_service: Arc<SshCredentialService>,
+        winrm_credential_service: Arc<WinrmCredentialService>,
+    ) -> Self {
         Self {
             daemon_storage,
+            network_settings_service,
+            dns_zone_storage,
+            scan_profile_storage,
+            ssh_credential_service,
+            winrm_credential_service,
             client: reqwest::Client::new(),
+            event_tx: broadcast::channel(100).0,
+        }
+    }
+
+    /// Stream of daemons going offline and coming back
+    pub fn subscribe(&self) -> broadcast::Receiver<DaemonEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Record a heartbeat, bringing the daemon back online if the monitor marked it offline
+    pub async fn record_heartbeat(
+        &self,
+        mut daemon: Daemon,
+        heartbeat: Option<DaemonHeartbeat>,
+    ) -> Result<Daemon, Error> {
+        let came_online = daemon.base.status == DaemonStatus::Offline;
+
+        daemon.base.last_seen = Utc::now();
+        daemon.base.status = DaemonStatus::Online;
+        // Older daemons send no body
+        if heartbeat.is_some() {
+            daemon.base.health = heartbeat;
+        }
+
+        self.update(&mut daemon).await?;
+
+        if came_online {
+            tracing::info!("Daemon {} is back online", daemon);
+            let _ = self.event_tx.send(DaemonEvent::CameOnline(daemon.clone()));
+        }
+
+        Ok(daemon)
+    }
+
+    /// Mark daemons offline that haven't sent a heartbeat within `DAEMON_OFFLINE_AFTER_SECS`.
+    /// Returns how many were marked.
+    pub async fn mark_offline_daemons(&self) -> Result<usize, Error> {
+        let cutoff = Utc::now() - Duration::seconds(DAEMON_OFFLINE_AFTER_SECS);
+        let mut marked = 0;
+
+        for mut daemon in self.get_all(EntityFilter::unfiltered()).await? {
+            if daemon.base.status == DaemonStatus::Offline || daemon.base.last_seen >= cutoff {
+                continue;
+            }
+
+            daemon.base.status = DaemonStatus::Offline;
+            self.update(&mut daemon).await?;
+            marked += 1;
+
+            tracing::warn!(
+                "Daemon {} went offline, last heartbeat at {}",
+                daemon,
+                daemon.base.last_seen
+            );
+            let _ = self.event_tx.send(DaemonEvent::WentOffline(daemon));
+        }
+
+        Ok(marked)
+    }
+
+    /// Scan settings for a daemon: defaults, then its network's overrides, then its own. The
+    /// network's exclusions and scan limits always apply.
+    pub async fn scan_settings(&self, daemon: &Daemon) -> Result<ScanSettings, Error> {
+        let mut settings = ScanSettings::default();
+
+        let network_settings = self
+            .network_settings_service
+            .for_network(&daemon.base.network_id)
+            .await?;
+        network_settings.scan_settings.apply(&mut settings);
+        daemon.base.scan_settings.apply(&mut settings);
+        settings.exclusions = network_settings.exclusions;
+        settings.limits = network_settings.scan_limits;
+
+        Ok(settings)
+    }
+
+    /// Scan settings for a discovery on a daemon: the daemon's, with the discovery's scan
+    /// profile applied on top
+    async fn discovery_scan_settings(
+        &self,
+        daemon: &Daemon,
+        discovery_type: &DiscoveryType,
+    ) -> Result<ScanSettings, Error> {
+        let mut settings = self.scan_settings(daemon).await?;
+
+        if let DiscoveryType::Network {
+            scan_profile_id: Some(profile_id),
+            ..
+        } = discovery_type
+        {
+            let profile = self
+                .scan_profile_storage
+                .get_by_id(profile_id)
+                .await?
+                .filter(|p| p.base.network_id == daemon.base.network_id)
+                .ok_or_else(|| anyhow::anyhow!("Could not find scan profile {}", profile_id))?;
+            profile.base.apply(&mut settings);
+        }
+
+        Ok(settings)
+    }
+
+    /// Names synced from the DNS servers of a network, merged across servers
+    async fn dns_names(&self, network_id: &Uuid) -> Result<HashMap<IpAddr, String>, Error> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let mut names = HashMap::new();
+
+        for settings in self.dns_zone_storage.get_all(filter).await? {
+            for (ip, name) in settings.base.names {
+                names.entry(ip).or_insert(name);
+            }
         }
+
+        Ok(names)
     }
 
-    /// Send discovery request to daemon
+    /// Send discovery request to daemon, with the scan settings resolved for it and the discovery
     pub async fn send_discovery_request(
         &self,
         daemon_id: &Uuid,
-        request: DaemonDiscoveryRequest,
+        session_id: Uuid,
+        discovery_type: DiscoveryType,
     ) -> Result<(), Error> {
         let daemon = self
             .get_by_id(daemon_id)
             .await?
             .ok_or_else(|| anyhow::anyhow!("Could not find daemon {}", daemon_id))?;
 
+        let (plugin_definitions, custom_definitions): (Vec<_>, Vec<_>) =
+            ServiceDefinitionRegistry::runtime_definitions()
+                .into_iter()
+                .partition(|d| d.plugin().is_some());
+
+        let tailnet = match discovery_type {
+            DiscoveryType::Tailnet => Some(
+                self.network_settings_service
+                    .for_network(&daemon.base.network_id)
+                    .await?
+                    .tailnet
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Tailnet API access isn't configured for this network")
+                    })?,
+            ),
+            _ => None,
+        };
+
+        // Only discoveries that name hosts by their address need the zones
+        let dns_names = match discovery_type {
+            DiscoveryType::Network { .. }
+            | DiscoveryType::ArpSweep { .. }
+            | DiscoveryType::HostRescan { .. } => self.dns_names(&daemon.base.network_id).await?,
+            _ => HashMap::new(),
+        };
+
+        let (ssh_credentials, winrm_credentials) = match discovery_type {
+            DiscoveryType::Network { .. } | DiscoveryType::HostRescan { .. } => (
+                self.ssh_credential_service
+                    .daemon_logins(&daemon.base.network_id)
+                    .await?,
+                self.winrm_credential_service
+                    .daemon_logins(&daemon.base.network_id)
+                    .await?,
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+
+        let scan_settings = self
+            .discovery_scan_settings(&daemon, &discovery_type)
+            .await?;
+
+        let request = DaemonDiscoveryRequest {
+            session_id,
+            discovery_type,
+            scan_settings,
+            service_definitions: custom_definitions
+                .iter()
+                .map(CustomServiceDefinitionBase::from)
+                .collect(),
+            plugins: plugin_definitions
+                .iter()
+                .filter_map(|d| d.plugin().map(|p| p.source().clone()))
+                .collect(),
+            tailnet,
+            dns_names,
+            ssh_credentials,
+            winrm_credentials,
+        };
+
         let endpoint = Endpoint {
             ip: Some(daemon.base.ip),
             port_base: PortBase::new_tcp(daemon.base.port),
@@ -114,4 +321,91 @@ impl DaemonService {
 
         Ok(())
     }
+
+    /// Ask the daemon to stop a network scan with its progress saved. It's resumed by sending
+    /// the session's discovery request again.
+    pub async fn send_discovery_pause(
+        &self,
+        daemon: &Daemon,
+        session_id: Uuid,
+    ) -> Result<(), anyhow::Error> {
+        let endpoint = Endpoint {
+            ip: Some(daemon.base.ip),
+            port_base: PortBase::new_tcp(daemon.base.port),
+            protocol: ApplicationProtocol::Http,
+            path: "/api/discovery/pause".to_string(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}", endpoint))
+            .json(&session_id)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to send discovery pause to daemon {}: HTTP {}",
+                daemon.id,
+                response.status()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Ask the daemon to run a ping, traceroute or port check and report back as it goes
+    pub async fn send_diagnostic_request(
+        &self,
+        daemon: &Daemon,
+        request: DaemonDiagnosticRequest,
+    ) -> Result<(), Error> {
+        let endpoint = Endpoint {
+            ip: Some(daemon.base.ip),
+            port_base: PortBase::new_tcp(daemon.base.port),
+            protocol: ApplicationProtocol::Http,
+            path: "/api/diagnostics/run".to_string(),
+        };
+
+        let response = self
+            .client
+            .post(format!("{}", endpoint))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to send diagnostic request to daemon {}: HTTP {}",
+                daemon.id,
+                response.status()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Session the daemon is currently running, if any
+    pub async fn get_current_session(&self, daemon: &Daemon) -> Result<Option<Uuid>, Error> {
+        let endpoint = Endpoint {
+            ip: Some(daemon.base.ip),
+            port_base: PortBase::new_tcp(daemon.base.port),
+            protocol: ApplicationProtocol::Http,
+            path: "/api/discovery/session".to_string(),
+        };
+
+        let response = self.client.get(format!("{}", endpoint)).send().await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to get current session from daemon {}: HTTP {}",
+                daemon.id,
+                response.status()
+            );
+        }
+
+        let api_response: ApiResponse<Option<Uuid>> = response.json().await?;
+
+        Ok(api_response.data.flatten())
+    }
 }
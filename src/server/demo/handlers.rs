@@ -0,0 +1,26 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    demo::r#impl::generator::DemoSummary,
+    shared::types::api::{ApiResponse, ApiResult},
+};
+use axum::{Router, extract::State, response::Json, routing::post};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/", post(create_demo_network))
+}
+
+/// Create a new network for the user filled with generated demo data
+async fn create_demo_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> ApiResult<Json<ApiResponse<DemoSummary>>> {
+    let summary = state
+        .services
+        .demo_service
+        .create_demo_network(user.0)
+        .await?;
+
+    Ok(Json(ApiResponse::success(summary)))
+}
@@ -0,0 +1,485 @@
+use chrono::{Duration, Utc};
+use cidr::IpCidr;
+use mac_address::MacAddress;
+use rand::Rng;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr};
+use uuid::Uuid;
+
+use crate::server::shared::types::attributes::EntityAttributes;
+use crate::{
+    daemon::discovery::types::base::DiscoveryPhase,
+    server::{
+        daemons::r#impl::{
+            api::{DaemonCapabilities, DiscoveryUpdatePayload},
+            base::{Daemon, DaemonBase},
+        },
+        discovery::r#impl::{
+            base::{Discovery, DiscoveryBase},
+            types::{DiscoveryType, HostNamingFallback, RunType},
+        },
+        groups::r#impl::{
+            base::{Group, GroupBase},
+            types::GroupType,
+        },
+        hosts::r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+            liveness::{HostLiveness, LivenessEvidence},
+            ports::{Port, PortBase},
+            targets::HostTarget,
+        },
+        services::{
+            definitions::ServiceDefinitionRegistry,
+            r#impl::{
+                base::{Service, ServiceBase},
+                bindings::Binding,
+                definitions::ServiceDefinition,
+                patterns::MatchDetails,
+            },
+        },
+        shared::{
+            storage::traits::StorableEntity,
+            types::entities::{DiscoveryMetadata, EntitySource},
+        },
+        subnets::r#impl::{
+            base::{Subnet, SubnetBase},
+            types::{SubnetLayer2, SubnetType},
+        },
+    },
+};
+
+/// Days of discovery history generated for a demo network
+pub const DEMO_HISTORY_DAYS: i64 = 14;
+
+/// Fake network contents, generated in memory and created by `DemoService`
+pub struct DemoNetwork {
+    pub subnets: Vec<Subnet>,
+    pub hosts: Vec<(Host, Vec<Service>)>,
+    pub daemon: Daemon,
+    pub groups: Vec<Group>,
+    pub discoveries: Vec<Discovery>,
+}
+
+/// What was created for a demo network
+#[derive(Debug, Clone, Serialize)]
+pub struct DemoSummary {
+    pub network_id: Uuid,
+    pub subnets: usize,
+    pub hosts: usize,
+    pub services: usize,
+    pub groups: usize,
+    pub discoveries: usize,
+}
+
+struct DemoSubnet {
+    name: &'static str,
+    cidr: [u8; 3],
+    subnet_type: SubnetType,
+    description: &'static str,
+}
+
+struct DemoHost {
+    name: &'static str,
+    description: &'static str,
+    /// (index into the subnets, last octet)
+    interfaces: Vec<(usize, u8)>,
+    /// (service definition id, ports)
+    services: Vec<(&'static str, Vec<PortBase>)>,
+}
+
+const LAN: usize = 0;
+const SERVERS: usize = 1;
+const IOT: usize = 2;
+const GUEST: usize = 3;
+
+fn demo_subnets() -> [DemoSubnet; 4] {
+    [
+        DemoSubnet {
+            name: "Home LAN",
+            cidr: [192, 168, 1],
+            subnet_type: SubnetType::Lan,
+            description: "Desktops, laptops and printers",
+        },
+        DemoSubnet {
+            name: "Servers",
+            cidr: [10, 0, 10],
+            subnet_type: SubnetType::Storage,
+            description: "Homelab servers and storage",
+        },
+        DemoSubnet {
+            name: "IoT",
+            cidr: [192, 168, 20],
+            subnet_type: SubnetType::IoT,
+            description: "Smart home devices, isolated from the LAN",
+        },
+        DemoSubnet {
+            name: "Guest WiFi",
+            cidr: [192, 168, 30],
+            subnet_type: SubnetType::Guest,
+            description: "Internet-only access for visitors",
+        },
+    ]
+}
+
+fn demo_hosts() -> Vec<DemoHost> {
+    vec![
+        DemoHost {
+            name: "router",
+            description: "Edge router routing between all VLANs",
+            interfaces: vec![(LAN, 1), (SERVERS, 1), (IOT, 1), (GUEST, 1)],
+            services: vec![("Gateway", vec![PortBase::Https, PortBase::Ssh])],
+        },
+        DemoHost {
+            name: "pihole",
+            description: "DNS with ad blocking for the whole network",
+            interfaces: vec![(LAN, 2)],
+            services: vec![(
+                "Pi-Hole",
+                vec![PortBase::DnsUdp, PortBase::DnsTcp, PortBase::Http],
+            )],
+        },
+        DemoHost {
+            name: "switch-core",
+            description: "Managed core switch",
+            interfaces: vec![(SERVERS, 2)],
+            services: vec![("Switch", vec![PortBase::Http, PortBase::Telnet])],
+        },
+        DemoHost {
+            name: "ap-living-room",
+            description: "WiFi access point",
+            interfaces: vec![(LAN, 3)],
+            services: vec![("Access Point", vec![PortBase::Https])],
+        },
+        DemoHost {
+            name: "nas",
+            description: "Network storage for backups and media",
+            interfaces: vec![(SERVERS, 5)],
+            services: vec![("Nas Device", vec![PortBase::Nfs, PortBase::Samba])],
+        },
+        DemoHost {
+            name: "pve",
+            description: "Proxmox hypervisor",
+            interfaces: vec![(SERVERS, 10)],
+            services: vec![("Proxmox VE", vec![PortBase::new_tcp(8006)])],
+        },
+        DemoHost {
+            name: "media",
+            description: "Media streaming server",
+            interfaces: vec![(SERVERS, 20)],
+            services: vec![
+                ("Plex Media Server", vec![PortBase::new_tcp(32400)]),
+                ("Jellyfin", vec![PortBase::new_tcp(8096)]),
+            ],
+        },
+        DemoHost {
+            name: "monitoring",
+            description: "Metrics and dashboards, also runs the NetVisor daemon",
+            interfaces: vec![(SERVERS, 30)],
+            services: vec![
+                ("Grafana", vec![PortBase::new_tcp(3000)]),
+                ("Prometheus", vec![PortBase::new_tcp(9090)]),
+                ("Node Exporter", vec![PortBase::new_tcp(9100)]),
+            ],
+        },
+        DemoHost {
+            name: "homeassistant",
+            description: "Home automation hub",
+            interfaces: vec![(IOT, 10)],
+            services: vec![("Home Assistant", vec![PortBase::new_tcp(8123)])],
+        },
+        DemoHost {
+            name: "printer",
+            description: "Office printer",
+            interfaces: vec![(LAN, 50)],
+            services: vec![("Hp Printer", vec![PortBase::Http, PortBase::Ipp])],
+        },
+        DemoHost {
+            name: "laptop",
+            description: "Work laptop",
+            interfaces: vec![(LAN, 101)],
+            services: vec![("Client", vec![])],
+        },
+        DemoHost {
+            name: "guest-phone",
+            description: "A visitor's phone",
+            interfaces: vec![(GUEST, 120)],
+            services: vec![("Client", vec![])],
+        },
+    ]
+}
+
+impl DemoNetwork {
+    pub fn generate(network_id: Uuid) -> Self {
+        let mut rng = rand::rng();
+
+        let daemon_id = Uuid::new_v4();
+        let discovery_type = DiscoveryType::Network {
+            subnet_ids: None,
+            host_naming_fallback: HostNamingFallback::BestService,
+            scan_profile_id: None,
+            parallel_daemon_ids: Vec::new(),
+            shard: None,
+        };
+        let metadata = vec![DiscoveryMetadata::new(discovery_type.clone(), daemon_id)];
+
+        let subnet_specs = demo_subnets();
+        let subnets: Vec<Subnet> = subnet_specs
+            .iter()
+            .map(|spec| {
+                let [a, b, c] = spec.cidr;
+                Subnet::new(SubnetBase {
+                    cidr: IpCidr::new(IpAddr::V4(Ipv4Addr::new(a, b, c, 0)), 24)
+                        .expect("Demo subnet CIDR"),
+                    network_id,
+                    name: spec.name.to_string(),
+                    description: Some(spec.description.to_string()),
+                    subnet_type: spec.subnet_type,
+                    source: EntitySource::Discovery {
+                        metadata: metadata.clone(),
+                    },
+                    layer2: SubnetLayer2::default(),
+                    attributes: EntityAttributes::default(),
+                })
+            })
+            .collect();
+
+        let mut hosts = Vec::new();
+        for spec in demo_hosts() {
+            let interfaces: Vec<Interface> = spec
+                .interfaces
+                .iter()
+                .map(|(subnet, octet)| {
+                    let [a, b, c] = subnet_specs[*subnet].cidr;
+                    Interface::new(InterfaceBase {
+                        subnet_id: subnets[*subnet].id,
+                        ip_address: IpAddr::V4(Ipv4Addr::new(a, b, c, *octet)),
+                        mac_address: Some(MacAddress::new([
+                            0x02,
+                            0x42,
+                            rng.random(),
+                            rng.random(),
+                            rng.random(),
+                            rng.random(),
+                        ])),
+                        name: Some(format!("eth{}", subnet)),
+                    })
+                })
+                .collect();
+
+            let mut host = Host::new(HostBase {
+                name: spec.name.to_string(),
+                hostname: Some(format!("{}.home.arpa", spec.name)),
+                network_id,
+                description: Some(spec.description.to_string()),
+                interfaces,
+                source: EntitySource::Discovery {
+                    metadata: metadata.clone(),
+                },
+                ..HostBase::default()
+            });
+
+            let mut services = Vec::new();
+            for (definition_id, ports) in spec.services {
+                let Some(service_definition) = ServiceDefinitionRegistry::find_by_id(definition_id)
+                else {
+                    tracing::warn!("Demo service definition '{}' not found", definition_id);
+                    continue;
+                };
+
+                let interface_id = host.base.interfaces[0].id;
+                let bindings = if ports.is_empty() {
+                    vec![Binding::new_interface(interface_id)]
+                } else {
+                    ports
+                        .into_iter()
+                        .map(|port_base| {
+                            let port = Port::new(port_base);
+                            let binding = Binding::new_port(port.id, Some(interface_id));
+                            host.base.ports.push(port);
+                            binding
+                        })
+                        .collect()
+                };
+
+                let service = Service::new(ServiceBase {
+                    host_id: host.id,
+                    network_id,
+                    name: service_definition.name().to_string(),
+                    service_definition,
+                    bindings,
+                    virtualization: None,
+                    source: EntitySource::DiscoveryWithMatch {
+                        metadata: metadata.clone(),
+                        details: MatchDetails::new_certain("Demo data"),
+                    },
+                    versions: Vec::new(),
+                    vulnerabilities: Vec::new(),
+                    attributes: EntityAttributes::default(),
+                });
+
+                host.add_service(service.id);
+                services.push(service);
+            }
+
+            if let Some(binding) = services.first().and_then(|s| s.base.bindings.first()) {
+                host.base.target = HostTarget::ServiceBinding(binding.id());
+            }
+
+            host.base.liveness = Some(HostLiveness::from_evidence(&LivenessEvidence {
+                tcp_ports: host.base.ports.len() as u32,
+                has_mac: true,
+                has_hostname: true,
+                ..LivenessEvidence::default()
+            }));
+
+            hosts.push((host, services));
+        }
+
+        let daemon_host = hosts
+            .iter()
+            .map(|(host, _)| host)
+            .find(|host| host.base.name == "monitoring")
+            .expect("Demo daemon host");
+
+        let mut daemon = Daemon::new(DaemonBase {
+            host_id: daemon_host.id,
+            network_id,
+            ip: daemon_host.base.interfaces[0].base.ip_address,
+            last_seen: Utc::now(),
+            port: 60073,
+            capabilities: DaemonCapabilities {
+                has_docker_socket: false,
+                interfaced_subnet_ids: vec![subnets[SERVERS].id],
+                features: None,
+            },
+            scan_settings: Default::default(),
+            status: Default::default(),
+            health: None,
+        });
+        daemon.id = daemon_id;
+
+        let groups = demo_groups(network_id, &hosts);
+
+        let total_to_process = subnets.len() * 254;
+        let discoveries = (1..=DEMO_HISTORY_DAYS)
+            .map(|days_ago| {
+                let started_at = Utc::now() - Duration::days(days_ago);
+                let finished_at = started_at + Duration::seconds(rng.random_range(90..600));
+
+                let mut results = DiscoveryUpdatePayload::new(
+                    Uuid::new_v4(),
+                    daemon_id,
+                    network_id,
+                    discovery_type.clone(),
+                );
+                results.phase = DiscoveryPhase::Complete;
+                results.processed = total_to_process;
+                results.total_to_process = total_to_process;
+                results.started_at = Some(started_at);
+                results.finished_at = Some(finished_at);
+
+                Discovery {
+                    id: Uuid::new_v4(),
+                    created_at: started_at,
+                    updated_at: finished_at,
+                    base: DiscoveryBase {
+                        discovery_type: discovery_type.clone(),
+                        run_type: RunType::Historical { results },
+                        name: "Discovery Run".to_string(),
+                        daemon_id,
+                        network_id,
+                    },
+                }
+            })
+            .collect();
+
+        Self {
+            subnets,
+            hosts,
+            daemon,
+            groups,
+            discoveries,
+        }
+    }
+}
+
+/// First binding of the named host's service with the given definition
+fn binding(hosts: &[(Host, Vec<Service>)], host_name: &str, definition_id: &str) -> Option<Uuid> {
+    hosts
+        .iter()
+        .find(|(host, _)| host.base.name == host_name)
+        .and_then(|(_, services)| {
+            services
+                .iter()
+                .find(|s| s.base.service_definition.id() == definition_id)
+        })
+        .and_then(|s| s.base.bindings.first())
+        .map(|b| b.id())
+}
+
+fn demo_groups(network_id: Uuid, hosts: &[(Host, Vec<Service>)]) -> Vec<Group> {
+    let groups = [
+        (
+            "DNS Resolution",
+            "How clients resolve names",
+            "emerald",
+            GroupType::RequestPath {
+                service_bindings: [
+                    binding(hosts, "laptop", "Client"),
+                    binding(hosts, "pihole", "Pi-Hole"),
+                    binding(hosts, "router", "Gateway"),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            },
+        ),
+        (
+            "Monitoring",
+            "Hosts scraped by Prometheus",
+            "indigo",
+            GroupType::HubAndSpoke {
+                service_bindings: [
+                    binding(hosts, "monitoring", "Prometheus"),
+                    binding(hosts, "nas", "Nas Device"),
+                    binding(hosts, "pve", "Proxmox VE"),
+                    binding(hosts, "media", "Plex Media Server"),
+                    binding(hosts, "homeassistant", "Home Assistant"),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            },
+        ),
+        (
+            "Media Streaming",
+            "Media served from the NAS",
+            "rose",
+            GroupType::RequestPath {
+                service_bindings: [
+                    binding(hosts, "laptop", "Client"),
+                    binding(hosts, "media", "Plex Media Server"),
+                    binding(hosts, "nas", "Nas Device"),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            },
+        ),
+    ];
+
+    groups
+        .into_iter()
+        .map(|(name, description, color, group_type)| {
+            Group::new(GroupBase {
+                name: name.to_string(),
+                network_id,
+                description: Some(description.to_string()),
+                group_type,
+                source: EntitySource::System,
+                color: color.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::service::DaemonService,
+    demo::r#impl::generator::{DemoNetwork, DemoSummary},
+    discovery::service::DiscoveryService,
+    groups::service::GroupService,
+    hosts::service::HostService,
+    networks::{
+        r#impl::{Network, NetworkBase},
+        service::NetworkService,
+    },
+    shared::services::traits::CrudService,
+    subnets::service::SubnetService,
+};
+
+/// Creates generated demo networks so the UI and API can be explored without real scans
+pub struct DemoService {
+    network_service: Arc<NetworkService>,
+    subnet_service: Arc<SubnetService>,
+    host_service: Arc<HostService>,
+    group_service: Arc<GroupService>,
+    daemon_service: Arc<DaemonService>,
+    discovery_service: Arc<DiscoveryService>,
+}
+
+impl DemoService {
+    pub fn new(
+        network_service: Arc<NetworkService>,
+        subnet_service: Arc<SubnetService>,
+        host_service: Arc<HostService>,
+        group_service: Arc<GroupService>,
+        daemon_service: Arc<DaemonService>,
+        discovery_service: Arc<DiscoveryService>,
+    ) -> Self {
+        Self {
+            network_service,
+            subnet_service,
+            host_service,
+            group_service,
+            daemon_service,
+            discovery_service,
+        }
+    }
+
+    /// Create a new network for the user and fill it with demo data
+    pub async fn create_demo_network(&self, user_id: Uuid) -> Result<DemoSummary> {
+        let network = self
+            .network_service
+            .create(Network::new(NetworkBase {
+                name: "Demo Network".to_string(),
+                ..NetworkBase::new(user_id)
+            }))
+            .await?;
+
+        self.network_service.seed_default_data(network.id).await?;
+
+        self.populate(network.id).await
+    }
+
+    /// Add generated subnets, hosts, services, groups and discovery history to a network
+    pub async fn populate(&self, network_id: Uuid) -> Result<DemoSummary> {
+        let demo = DemoNetwork::generate(network_id);
+
+        let mut summary = DemoSummary {
+            network_id,
+            subnets: demo.subnets.len(),
+            hosts: demo.hosts.len(),
+            services: 0,
+            groups: demo.groups.len(),
+            discoveries: demo.discoveries.len(),
+        };
+
+        for subnet in demo.subnets {
+            self.subnet_service.create(subnet).await?;
+        }
+
+        for (host, services) in demo.hosts {
+            let (_, created_services) = self
+                .host_service
+                .create_host_with_services(host, services)
+                .await?;
+            summary.services += created_services.len();
+        }
+
+        self.daemon_service.create(demo.daemon).await?;
+
+        for group in demo.groups {
+            self.group_service.create(group).await?;
+        }
+
+        for discovery in demo.discoveries {
+            self.discovery_service.create(discovery).await?;
+        }
+
+        tracing::info!("Populated demo data for network {}", network_id);
+
+        Ok(summary)
+    }
+}
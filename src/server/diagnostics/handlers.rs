@@ -0,0 +1,164 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    diagnostics::r#impl::{
+        api::DiagnosticRequest,
+        base::{Diagnostic, DiagnosticRun, DiagnosticUpdate},
+    },
+    network_members::handlers::network_role,
+    shared::{
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{
+        Json, Sse,
+        sse::{Event, KeepAlive},
+    },
+    routing::{get, post},
+};
+use futures::Stream;
+use std::{convert::Infallible, sync::Arc};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const MAX_PING_COUNT: u16 = 20;
+const MAX_HOPS: u8 = 64;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", post(start_diagnostic))
+        .route("/{id}", get(get_run))
+        .route("/{id}/events", get(run_events))
+}
+
+/// The run, if the user can see the network it ran in
+async fn get_visible_run(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    id: &Uuid,
+) -> ApiResult<DiagnosticRun> {
+    let not_found = || ApiError::not_found(format!("Diagnostic run '{}' not found", id));
+    let run = state
+        .services
+        .diagnostic_service
+        .get_run(id)
+        .await
+        .ok_or_else(not_found)?;
+
+    network_role(state, &user.0, &run.network_id)
+        .await
+        .map_err(|_| not_found())?;
+
+    Ok(run)
+}
+
+fn validate_diagnostic(diagnostic: &Diagnostic) -> ApiResult<()> {
+    let target = diagnostic.target();
+    if target.trim().is_empty() || target.len() > 253 {
+        return Err(ApiError::bad_request(
+            "Target must be an address or a name of at most 253 characters",
+        ));
+    }
+
+    match diagnostic {
+        Diagnostic::Ping { count, .. } if !(1..=MAX_PING_COUNT).contains(count) => Err(
+            ApiError::bad_request(&format!("Ping count must be 1 to {}", MAX_PING_COUNT)),
+        ),
+        Diagnostic::Traceroute { max_hops, .. } if !(1..=MAX_HOPS).contains(max_hops) => Err(
+            ApiError::bad_request(&format!("Max hops must be 1 to {}", MAX_HOPS)),
+        ),
+        Diagnostic::TcpConnect { port: 0, .. } => Err(ApiError::bad_request("Port can't be 0")),
+        _ => Ok(()),
+    }
+}
+
+/// Run a ping, traceroute or port check from a daemon, e.g. to compare what two sites see
+async fn start_diagnostic(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<DiagnosticRequest>,
+) -> ApiResult<Json<ApiResponse<DiagnosticRun>>> {
+    validate_diagnostic(&request.diagnostic)?;
+
+    let not_found = || ApiError::not_found(format!("Daemon '{}' not found", request.daemon_id));
+    let daemon = state
+        .services
+        .daemon_service
+        .get_by_id(&request.daemon_id)
+        .await?
+        .ok_or_else(not_found)?;
+    network_role(&state, &user.0, &daemon.base.network_id)
+        .await
+        .map_err(|_| not_found())?;
+
+    let run = state
+        .services
+        .diagnostic_service
+        .start(&daemon, request.diagnostic)
+        .await?;
+
+    Ok(Json(ApiResponse::success(run)))
+}
+
+async fn get_run(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DiagnosticRun>>> {
+    let run = get_visible_run(&state, &user, &id).await?;
+
+    Ok(Json(ApiResponse::success(run)))
+}
+
+/// Follow a run: the results so far, then each new one until it finishes
+async fn run_events(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    // Subscribe first so no update between reading the run and streaming is lost
+    let mut rx = state.services.diagnostic_service.subscribe();
+    let run = get_visible_run(&state, &user, &id).await?;
+
+    let stream = async_stream::stream! {
+        for update in &run.updates {
+            yield Ok(update_event(update));
+        }
+        if run.is_finished() {
+            return;
+        }
+
+        loop {
+            match rx.recv().await {
+                // Updates recorded before the run was read were already sent
+                Ok((position, update)) if update.run_id == id && position >= run.updates.len() => {
+                    let finished = update.is_finished();
+                    yield Ok(update_event(&update));
+                    if finished {
+                        break;
+                    }
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!("Diagnostic SSE client lagged by {} messages", n);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn update_event(update: &DiagnosticUpdate) -> Event {
+    let event: &'static str = (&update.event).into();
+
+    Event::default()
+        .event(event)
+        .data(serde_json::to_string(update).unwrap_or_default())
+}
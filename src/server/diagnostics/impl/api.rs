@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::diagnostics::r#impl::base::Diagnostic;
+
+/// Diagnostic to run from one of the network's daemons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticRequest {
+    pub daemon_id: Uuid,
+    pub diagnostic: Diagnostic,
+}
@@ -0,0 +1,102 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, IntoStaticStr};
+use uuid::Uuid;
+
+/// A check a daemon runs from its own vantage point
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Diagnostic {
+    Ping { target: String, count: u16 },
+    Traceroute { target: String, max_hops: u8 },
+    TcpConnect { target: String, port: u16 },
+}
+
+impl Diagnostic {
+    /// Address or name to check, resolved by the daemon
+    pub fn target(&self) -> &str {
+        match self {
+            Diagnostic::Ping { target, .. }
+            | Diagnostic::Traceroute { target, .. }
+            | Diagnostic::TcpConnect { target, .. } => target,
+        }
+    }
+}
+
+/// Diagnostic request from server to daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonDiagnosticRequest {
+    pub run_id: Uuid,
+    pub diagnostic: Diagnostic,
+}
+
+/// One result of a diagnostic, sent by the daemon as soon as it has it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, IntoStaticStr)]
+#[serde(tag = "event", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DiagnosticEvent {
+    Resolved {
+        ip: IpAddr,
+    },
+    /// None when the echo request went unanswered
+    Reply {
+        sequence: u16,
+        rtt_ms: Option<f64>,
+    },
+    /// None for hops that didn't answer
+    Hop {
+        hop: u8,
+        ip: Option<IpAddr>,
+        rtt_ms: Option<f64>,
+    },
+    Connect {
+        open: bool,
+        rtt_ms: Option<f64>,
+        error: Option<String>,
+    },
+    /// Always the last event of a run
+    Finished {
+        error: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticUpdate {
+    pub run_id: Uuid,
+    pub event: DiagnosticEvent,
+    pub at: DateTime<Utc>,
+}
+
+impl DiagnosticUpdate {
+    pub fn new(run_id: Uuid, event: DiagnosticEvent) -> Self {
+        Self {
+            run_id,
+            event,
+            at: Utc::now(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.event, DiagnosticEvent::Finished { .. })
+    }
+}
+
+/// A diagnostic run and the results the daemon has sent so far
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticRun {
+    pub id: Uuid,
+    pub daemon_id: Uuid,
+    pub network_id: Uuid,
+    pub diagnostic: Diagnostic,
+    pub started_at: DateTime<Utc>,
+    pub updates: Vec<DiagnosticUpdate>,
+}
+
+impl DiagnosticRun {
+    pub fn is_finished(&self) -> bool {
+        self.updates.last().is_some_and(|u| u.is_finished())
+    }
+}
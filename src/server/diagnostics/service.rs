@@ -0,0 +1,111 @@
+use crate::server::{
+    daemons::{r#impl::base::Daemon, service::DaemonService},
+    diagnostics::r#impl::base::{
+        DaemonDiagnosticRequest, Diagnostic, DiagnosticRun, DiagnosticUpdate,
+    },
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::{Duration, Utc};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{RwLock, broadcast};
+use uuid::Uuid;
+
+/// Runs are kept this long for late viewers, then dropped
+const RUN_RETENTION: Duration = Duration::hours(1);
+
+/// Updates kept per run; a traceroute of 64 hops fits comfortably
+const MAX_UPDATES: usize = 256;
+
+/// Ping, traceroute and port checks run by daemons on request. Runs live in memory only, as
+/// they are troubleshooting aids rather than history.
+pub struct DiagnosticService {
+    daemon_service: Arc<DaemonService>,
+    runs: RwLock<HashMap<Uuid, DiagnosticRun>>,
+    /// Updates with their position in the run
+    update_tx: broadcast::Sender<(usize, DiagnosticUpdate)>,
+}
+
+impl DiagnosticService {
+    pub fn new(daemon_service: Arc<DaemonService>) -> Self {
+        let (update_tx, _) = broadcast::channel(256);
+
+        Self {
+            daemon_service,
+            runs: RwLock::new(HashMap::new()),
+            update_tx,
+        }
+    }
+
+    /// Ask the daemon to run the diagnostic; its results arrive through `record`
+    pub async fn start(&self, daemon: &Daemon, diagnostic: Diagnostic) -> Result<DiagnosticRun> {
+        let now = Utc::now();
+        let run = DiagnosticRun {
+            id: Uuid::new_v4(),
+            daemon_id: daemon.id,
+            network_id: daemon.base.network_id,
+            diagnostic: diagnostic.clone(),
+            started_at: now,
+            updates: Vec::new(),
+        };
+
+        {
+            let mut runs = self.runs.write().await;
+            runs.retain(|_, r| r.started_at > now - RUN_RETENTION);
+            runs.insert(run.id, run.clone());
+        }
+
+        let request = DaemonDiagnosticRequest {
+            run_id: run.id,
+            diagnostic,
+        };
+        if let Err(e) = self
+            .daemon_service
+            .send_diagnostic_request(daemon, request)
+            .await
+        {
+            self.runs.write().await.remove(&run.id);
+            return Err(e);
+        }
+
+        tracing::info!(
+            "Started {} diagnostic {} on daemon {}",
+            run.diagnostic,
+            run.id,
+            daemon.id
+        );
+
+        Ok(run)
+    }
+
+    pub async fn get_run(&self, run_id: &Uuid) -> Option<DiagnosticRun> {
+        self.runs.read().await.get(run_id).cloned()
+    }
+
+    /// Store a result from the daemon and pass it on to anyone following the run
+    pub async fn record(&self, update: DiagnosticUpdate) -> Result<()> {
+        let position = {
+            let mut runs = self.runs.write().await;
+            let run = runs
+                .get_mut(&update.run_id)
+                .ok_or_else(|| anyhow!("Diagnostic run '{}' not found", update.run_id))?;
+
+            if run.is_finished() {
+                bail!("Diagnostic run '{}' already finished", update.run_id);
+            }
+            // Leave room for the final event, so followers always see the run end
+            if run.updates.len() >= MAX_UPDATES - 1 && !update.is_finished() {
+                return Ok(());
+            }
+            run.updates.push(update.clone());
+            run.updates.len() - 1
+        };
+
+        // No receivers just means nobody is watching
+        let _ = self.update_tx.send((position, update));
+        Ok(())
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<(usize, DiagnosticUpdate)> {
+        self.update_tx.subscribe()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    digests::r#impl::changes::DigestChanges,
+    network_members::handlers::network_role,
+    shared::types::api::{ApiResponse, ApiResult},
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::get,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/{network_id}/pending", get(get_pending_changes))
+}
+
+/// Changes the next digest email for a network would contain
+async fn get_pending_changes(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(network_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DigestChanges>>> {
+    network_role(&state, &user.0, &network_id).await?;
+
+    let changes = state
+        .services
+        .digest_service
+        .pending_changes(&network_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(changes)))
+}
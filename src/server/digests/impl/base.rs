@@ -0,0 +1,46 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::digests::r#impl::changes::InventorySnapshot;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestFrequency {
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn period(&self) -> Duration {
+        match self {
+            DigestFrequency::Daily => Duration::days(1),
+            DigestFrequency::Weekly => Duration::weeks(1),
+        }
+    }
+}
+
+/// Inventory of a network as of its last digest; the next digest reports changes against it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSnapshotBase {
+    pub network_id: Uuid,
+    pub snapshot: InventorySnapshot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSnapshot {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// When the last digest was taken
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: DigestSnapshotBase,
+}
+
+impl Display for DigestSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Digest snapshot for network {}", self.base.network_id)
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{hosts::r#impl::base::Host, services::r#impl::base::Service};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct InventorySnapshot {
+    pub hosts: BTreeMap<Uuid, HostSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct HostSnapshot {
+    pub name: String,
+    pub ports: BTreeSet<String>,
+    pub services: BTreeMap<Uuid, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DigestChanges {
+    pub new_hosts: Vec<String>,
+    pub removed_hosts: Vec<String>,
+    pub changed_hosts: Vec<HostChanges>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HostChanges {
+    pub host: String,
+    pub ports_opened: Vec<String>,
+    pub ports_closed: Vec<String>,
+    pub services_added: Vec<String>,
+    pub services_removed: Vec<String>,
+}
+
+impl InventorySnapshot {
+    pub fn new(hosts: &[Host], services: &[Service]) -> Self {
+        let mut snapshot = Self {
+            hosts: hosts
+                .iter()
+                .map(|host| {
+                    (
+                        host.id,
+                        HostSnapshot {
+                            name: host.base.name.clone(),
+                            ports: host.base.ports.iter().map(|p| p.base.to_string()).collect(),
+                            services: BTreeMap::new(),
+                        },
+                    )
+                })
+                .collect(),
+        };
+
+        for service in services {
+            if let Some(host) = snapshot.hosts.get_mut(&service.base.host_id) {
+                host.services.insert(service.id, service.base.name.clone());
+            }
+        }
+
+        snapshot
+    }
+
+    /// Changes from `previous` to this snapshot
+    pub fn changes_since(&self, previous: &InventorySnapshot) -> DigestChanges {
+        let mut changes = DigestChanges::default();
+
+        for (id, host) in &self.hosts {
+            let Some(old) = previous.hosts.get(id) else {
+                changes.new_hosts.push(host.name.clone());
+                continue;
+            };
+
+            let host_changes = HostChanges {
+                host: host.name.clone(),
+                ports_opened: host.ports.difference(&old.ports).cloned().collect(),
+                ports_closed: old.ports.difference(&host.ports).cloned().collect(),
+                services_added: host
+                    .services
+                    .iter()
+                    .filter(|(id, _)| !old.services.contains_key(id))
+                    .map(|(_, name)| name.clone())
+                    .collect(),
+                services_removed: old
+                    .services
+                    .iter()
+                    .filter(|(id, _)| !host.services.contains_key(id))
+                    .map(|(_, name)| name.clone())
+                    .collect(),
+            };
+
+            if !host_changes.is_empty() {
+                changes.changed_hosts.push(host_changes);
+            }
+        }
+
+        changes.removed_hosts = previous
+            .hosts
+            .iter()
+            .filter(|(id, _)| !self.hosts.contains_key(id))
+            .map(|(_, host)| host.name.clone())
+            .collect();
+
+        changes
+    }
+}
+
+impl HostChanges {
+    pub fn is_empty(&self) -> bool {
+        self.ports_opened.is_empty()
+            && self.ports_closed.is_empty()
+            && self.services_added.is_empty()
+            && self.services_removed.is_empty()
+    }
+}
+
+impl DigestChanges {
+    pub fn is_empty(&self) -> bool {
+        self.new_hosts.is_empty() && self.removed_hosts.is_empty() && self.changed_hosts.is_empty()
+    }
+
+    /// Plain text email body
+    pub fn render(&self, network_name: &str) -> String {
+        let mut body = format!("Inventory changes in network \"{}\"\n", network_name);
+
+        let mut section = |title: &str, items: &[String]| {
+            if !items.is_empty() {
+                let _ = write!(body, "\n{} ({}):\n", title, items.len());
+                for item in items {
+                    let _ = writeln!(body, "  - {}", item);
+                }
+            }
+        };
+
+        section("New hosts", &self.new_hosts);
+        section("Removed hosts", &self.removed_hosts);
+
+        if !self.changed_hosts.is_empty() {
+            let _ = write!(body, "\nChanged hosts ({}):\n", self.changed_hosts.len());
+            for host in &self.changed_hosts {
+                let _ = writeln!(body, "  {}", host.host);
+                for (label, items) in [
+                    ("ports opened", &host.ports_opened),
+                    ("ports closed", &host.ports_closed),
+                    ("services added", &host.services_added),
+                    ("services removed", &host.services_removed),
+                ] {
+                    if !items.is_empty() {
+                        let _ = writeln!(body, "    {}: {}", label, items.join(", "));
+                    }
+                }
+            }
+        }
+
+        body
+    }
+}
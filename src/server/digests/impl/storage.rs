@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    digests::r#impl::base::{DigestSnapshot, DigestSnapshotBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for DigestSnapshot {
+    type BaseData = DigestSnapshotBase;
+
+    fn table_name() -> &'static str {
+        "digest_snapshots"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    network_id,
+                    snapshot,
+                },
+        } = self.clone();
+
+        Ok((
+            vec!["id", "created_at", "updated_at", "network_id", "snapshot"],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&snapshot)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let snapshot: serde_json::Value = row.get("snapshot");
+
+        Ok(DigestSnapshot {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: DigestSnapshotBase {
+                network_id: row.get("network_id"),
+                snapshot: serde_json::from_value(snapshot)?,
+            },
+        })
+    }
+}
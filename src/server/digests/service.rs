@@ -0,0 +1,156 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    digests::r#impl::{
+        base::{DigestFrequency, DigestSnapshot, DigestSnapshotBase},
+        changes::{DigestChanges, InventorySnapshot},
+    },
+    hosts::service::HostService,
+    networks::{r#impl::Network, service::NetworkService},
+    services::service::ServiceService,
+    shared::{
+        email::EmailClient,
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    users::service::UserService,
+};
+
+pub struct DigestService {
+    storage: Arc<GenericPostgresStorage<DigestSnapshot>>,
+    network_service: Arc<NetworkService>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    user_service: Arc<UserService>,
+}
+
+#[async_trait]
+impl CrudService<DigestSnapshot> for DigestService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<DigestSnapshot>> {
+        &self.storage
+    }
+}
+
+impl DigestService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<DigestSnapshot>>,
+        network_service: Arc<NetworkService>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        user_service: Arc<UserService>,
+    ) -> Self {
+        Self {
+            storage,
+            network_service,
+            host_service,
+            service_service,
+            user_service,
+        }
+    }
+
+    async fn current_snapshot(&self, network_id: &Uuid) -> Result<InventorySnapshot> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter).await?;
+
+        Ok(InventorySnapshot::new(&hosts, &services))
+    }
+
+    async fn last_snapshot(&self, network_id: &Uuid) -> Result<Option<DigestSnapshot>> {
+        self.storage
+            .get_one(EntityFilter::unfiltered().network_ids(&[*network_id]))
+            .await
+    }
+
+    /// Changes that the next digest for a network would report
+    pub async fn pending_changes(&self, network_id: &Uuid) -> Result<DigestChanges> {
+        let current = self.current_snapshot(network_id).await?;
+        let previous = self
+            .last_snapshot(network_id)
+            .await?
+            .map(|s| s.base.snapshot)
+            .unwrap_or_default();
+
+        Ok(current.changes_since(&previous))
+    }
+
+    /// Email a digest to the owner of every network whose last digest is older than the
+    /// frequency allows. Networks without a previous digest only get a baseline snapshot.
+    /// Returns the number of digests sent.
+    pub async fn send_due_digests(
+        &self,
+        email_client: &EmailClient,
+        frequency: DigestFrequency,
+    ) -> Result<usize> {
+        let networks = self
+            .network_service
+            .get_all(EntityFilter::unfiltered())
+            .await?;
+        let mut sent = 0;
+
+        for network in networks {
+            match self.send_digest(&network, email_client, frequency).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to send digest for network {}: {}", network, e),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn send_digest(
+        &self,
+        network: &Network,
+        email_client: &EmailClient,
+        frequency: DigestFrequency,
+    ) -> Result<bool> {
+        let current = self.current_snapshot(&network.id).await?;
+
+        let Some(mut last) = self.last_snapshot(&network.id).await? else {
+            self.storage
+                .create(&DigestSnapshot::new(DigestSnapshotBase {
+                    network_id: network.id,
+                    snapshot: current,
+                }))
+                .await?;
+            return Ok(false);
+        };
+
+        if Utc::now() - last.updated_at < frequency.period() {
+            return Ok(false);
+        }
+
+        let changes = current.changes_since(&last.base.snapshot);
+        let mut sent = false;
+
+        if !changes.is_empty() {
+            let user = self.user_service.get_by_id(&network.base.user_id).await?;
+
+            if let Some(user) = user {
+                email_client
+                    .send(
+                        user.base.email.as_str(),
+                        &format!("NetVisor digest for {}", network.base.name),
+                        changes.render(&network.base.name),
+                    )
+                    .await?;
+                sent = true;
+            }
+        }
+
+        // Only advance the snapshot once the digest went out, so failed sends are retried
+        last.base.snapshot = current;
+        self.update(&mut last).await?;
+
+        Ok(sent)
+    }
+}
@@ -1,15 +1,40 @@
+use crate::daemon::discovery::types::base::DiscoveryPhase;
 use crate::server::{
     auth::middleware::{AuthenticatedDaemon, AuthenticatedUser},
     config::AppState,
     daemons::r#impl::api::DiscoveryUpdatePayload,
-    discovery::r#impl::{base::Discovery, types::RunType},
+    discovery::r#impl::{
+        base::Discovery,
+        dhcp::{self, DhcpLeaseImportRequest, DhcpLeaseImportResult},
+        nmap::{self, NmapImportRequest, NmapImportResult},
+        results::DiscoveryResults,
+        types::{DiscoveryType, RunType},
+    },
+    hosts::r#impl::{
+        base::{Host, HostBase},
+        dhcp::HostDhcpLease,
+        interfaces::{Interface, InterfaceBase},
+        liveness::{HostLiveness, LivenessEvidence},
+        ports::{PortBase, TransportProtocol},
+    },
+    network_members::handlers::network_role,
+    services::r#impl::{
+        banners::PortBanner,
+        base::ServiceMatchBaselineParams,
+        endpoints::EndpointResponse,
+        matching::{DiscoveryContext, discover_host},
+    },
     shared::{
         handlers::traits::{
-            create_handler, delete_handler, get_all_handler, get_by_id_handler, update_handler,
+            create_handler, delete_handler, get_accessible, get_all_handler, get_by_id_handler,
+            update_handler,
         },
         services::traits::CrudService,
         storage::filter::EntityFilter,
-        types::api::{ApiError, ApiResponse, ApiResult},
+        types::{
+            api::{ApiError, ApiResponse, ApiResult},
+            entities::{DiscoveryMetadata, EntitySource},
+        },
     },
 };
 use axum::{
@@ -37,8 +62,15 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .route("/start-session", post(start_session))
         .route("/active-sessions", get(get_active_sessions))
         .route("/{session_id}/cancel", post(cancel_discovery))
+        .route("/{session_id}/pause", post(pause_discovery))
+        .route("/{session_id}/resume", post(resume_discovery))
         .route("/{session_id}/update", post(receive_discovery_update))
         .route("/stream", get(discovery_stream))
+        .route("/sessions/{session_id}/events", get(session_events))
+        .route("/sessions/{session_id}/results", get(session_results))
+        .route("/groups/{group_id}", get(get_group_progress))
+        .route("/import/nmap", post(import_nmap))
+        .route("/import/dhcp", post(import_dhcp_leases))
 }
 
 /// Receive discovery progress update from daemon
@@ -60,15 +92,95 @@ async fn receive_discovery_update(
 /// Endpoint to start a discovery session
 async fn start_session(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Json(discovery_id): Json<Uuid>,
 ) -> ApiResult<Json<ApiResponse<DiscoveryUpdatePayload>>> {
-    let mut discovery = state
-        .services
-        .discovery_service
-        .get_by_id(&discovery_id)
-        .await?
-        .ok_or_else(|| ApiError::not_found(format!("Discovery '{}' not found", &discovery_id)))?;
+    let mut discovery: Discovery = get_accessible(&state, &user.0, &discovery_id).await?;
+
+    if matches!(
+        discovery.base.discovery_type,
+        DiscoveryType::NmapImport { .. }
+    ) {
+        return Err(ApiError::bad_request(
+            "Nmap imports are uploaded to /api/discovery/import/nmap",
+        ));
+    }
+
+    if matches!(discovery.base.discovery_type, DiscoveryType::Proxmox { .. }) {
+        return Err(ApiError::bad_request(
+            "Proxmox guests are synced from /api/integrations/proxmox/{service_id}/sync",
+        ));
+    }
+
+    if matches!(discovery.base.discovery_type, DiscoveryType::Vmware { .. }) {
+        return Err(ApiError::bad_request(
+            "VMware VMs are synced from /api/integrations/vmware/{service_id}/sync",
+        ));
+    }
+
+    if matches!(
+        discovery.base.discovery_type,
+        DiscoveryType::Firewall { .. }
+    ) {
+        return Err(ApiError::bad_request(
+            "Firewall leases are synced from /api/integrations/firewall/{service_id}/sync",
+        ));
+    }
+
+    if matches!(discovery.base.discovery_type, DiscoveryType::IpamImport) {
+        return Err(ApiError::bad_request(
+            "Address plans are imported from /api/integrations/ipam",
+        ));
+    }
+
+    let (parallel_daemon_ids, subnet_ids) = match &discovery.base.discovery_type {
+        DiscoveryType::Network {
+            parallel_daemon_ids,
+            subnet_ids,
+            ..
+        } => (
+            parallel_daemon_ids.clone(),
+            subnet_ids.clone().unwrap_or_default(),
+        ),
+        _ => (Vec::new(), Vec::new()),
+    };
+
+    let mut subnets = Vec::new();
+    for subnet_id in &subnet_ids {
+        if let Some(subnet) = state.services.subnet_service.get_by_id(subnet_id).await? {
+            subnets.push(subnet);
+        }
+    }
+
+    // Only offer the scan to daemons that can run it, going by the capabilities they reported
+    for daemon_id in std::iter::once(&discovery.base.daemon_id).chain(&parallel_daemon_ids) {
+        let daemon = state
+            .services
+            .daemon_service
+            .get_by_id(daemon_id)
+            .await?
+            .filter(|daemon| daemon.base.network_id == discovery.base.network_id)
+            .ok_or_else(|| {
+                ApiError::bad_request(&format!(
+                    "Daemon {} isn't in the discovery's network",
+                    daemon_id
+                ))
+            })?;
+
+        let capabilities = &daemon.base.capabilities;
+        if let Some(reason) = capabilities.incompatibility(&discovery.base.discovery_type) {
+            return Err(ApiError::bad_request(&reason));
+        }
+        if let Some(subnet) = subnets
+            .iter()
+            .find(|subnet| !capabilities.supports_cidr(&subnet.base.cidr))
+        {
+            return Err(ApiError::bad_request(&format!(
+                "Daemon {} has no IPv6 address to scan {} from",
+                daemon_id, subnet.base.cidr
+            )));
+        }
+    }
 
     // Update last_run BEFORE moving any fields
     if let RunType::Scheduled {
@@ -100,13 +212,25 @@ async fn start_session(
 
 async fn discovery_stream(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
-) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    user: AuthenticatedUser,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
     let mut rx = state.services.discovery_service.subscribe();
 
+    // Networks the user joins later show up once they reconnect
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
     let stream = async_stream::stream! {
         loop {
             match rx.recv().await {
+                Ok(update) if !network_ids.contains(&update.network_id) => continue,
                 Ok(update) => {
                     let json = serde_json::to_string(&update).unwrap_or_default();
                     yield Ok(Event::default().data(json));
@@ -120,7 +244,7 @@ async fn discovery_stream(
         }
     };
 
-    Sse::new(stream).keep_alive(KeepAlive::default())
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
 /// Get the latest payload from active discovery sessions
@@ -128,7 +252,7 @@ async fn get_active_sessions(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
 ) -> ApiResult<Json<ApiResponse<Vec<DiscoveryUpdatePayload>>>> {
-    let user_filter = EntityFilter::unfiltered().user_id(&user.0);
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
 
     let network_ids: Vec<Uuid> = state
         .services
@@ -148,18 +272,417 @@ async fn get_active_sessions(
     Ok(Json(ApiResponse::success(sessions)))
 }
 
+/// Progress of a network scan split across daemons, merged from the sessions of each
+async fn get_group_progress(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(group_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DiscoveryUpdatePayload>>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    let progress = state
+        .services
+        .discovery_service
+        .group_progress(&group_id, &network_ids)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Discovery group '{}' not found", group_id)))?;
+
+    Ok(Json(ApiResponse::success(progress)))
+}
+
 /// Cancel an active discovery session
 async fn cancel_discovery(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(session_id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<()>>> {
-    state
-        .services
-        .discovery_service
-        .cancel_session(session_id)
-        .await?;
+    let service = &state.services.discovery_service;
+    let session = service
+        .get_session(&session_id)
+        .await
+        .ok_or_else(|| ApiError::not_found(format!("Session '{}' not found", session_id)))?;
+    network_role(&state, &user.0, &session.network_id).await?;
+
+    service.cancel_session(session_id).await?;
 
     tracing::info!("Discovery session was {} cancelled", session_id);
     Ok(Json(ApiResponse::success(())))
 }
+
+/// Pause a running network scan, e.g. during working hours. The daemon keeps the addresses it
+/// has left and scans only those when resumed.
+async fn pause_discovery(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = &state.services.discovery_service;
+    let session = service
+        .get_session(&session_id)
+        .await
+        .ok_or_else(|| ApiError::not_found(format!("Session '{}' not found", session_id)))?;
+    network_role(&state, &user.0, &session.network_id).await?;
+
+    if !matches!(session.discovery_type, DiscoveryType::Network { .. }) {
+        return Err(ApiError::bad_request("Only network scans can be paused"));
+    }
+    if !matches!(
+        session.phase,
+        DiscoveryPhase::Started | DiscoveryPhase::Scanning
+    ) {
+        return Err(ApiError::conflict(&format!(
+            "Session can't be paused while it's in phase: {}",
+            session.phase
+        )));
+    }
+
+    service.pause_session(&session).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Resume a paused network scan where it left off
+async fn resume_discovery(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = &state.services.discovery_service;
+    let session = service
+        .get_session(&session_id)
+        .await
+        .ok_or_else(|| ApiError::not_found(format!("Session '{}' not found", session_id)))?;
+    network_role(&state, &user.0, &session.network_id).await?;
+
+    if !matches!(session.phase, DiscoveryPhase::Paused) {
+        return Err(ApiError::conflict("Only paused sessions can be resumed"));
+    }
+
+    service.resume_session(&session).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Import hosts from an nmap XML report, matching services against its ports and banners as a
+/// network scan would. Gateways aren't known to the server, so none are matched.
+async fn import_nmap(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<NmapImportRequest>,
+) -> ApiResult<Json<ApiResponse<NmapImportResult>>> {
+    let daemon = state
+        .services
+        .daemon_service
+        .get_by_id(&request.daemon_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Daemon '{}' not found", request.daemon_id)))?;
+    let network_id = daemon.base.network_id;
+    network_role(&state, &user.0, &network_id).await?;
+
+    let nmap_hosts =
+        nmap::parse(&request.xml).map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    let subnets = state
+        .services
+        .subnet_service
+        .get_all(EntityFilter::unfiltered().network_ids(&[network_id]))
+        .await?;
+
+    let discovery_type = DiscoveryType::NmapImport {
+        host_naming_fallback: request.host_naming_fallback,
+    };
+    let ctx = DiscoveryContext {
+        daemon_id: &daemon.id,
+        network_id: &network_id,
+        gateway_ips: &[],
+        discovery_type: &discovery_type,
+    };
+
+    let exclusions = state
+        .services
+        .network_settings_service
+        .for_network(&network_id)
+        .await?
+        .exclusions;
+    let mut result = NmapImportResult::default();
+
+    for nmap_host in nmap_hosts {
+        let Some(subnet) = subnets.iter().find(|s| s.base.cidr.contains(&nmap_host.ip)) else {
+            result.skipped.push(nmap_host.ip);
+            continue;
+        };
+        if exclusions.excludes_ip(nmap_host.ip)
+            || nmap_host
+                .mac
+                .is_some_and(|mac| exclusions.excludes_mac(&mac))
+        {
+            result.skipped.push(nmap_host.ip);
+            continue;
+        }
+
+        let interface = Interface::new(InterfaceBase {
+            name: None,
+            subnet_id: subnet.id,
+            ip_address: nmap_host.ip,
+            mac_address: nmap_host.mac,
+        });
+        let all_ports: Vec<PortBase> = nmap_host.ports.iter().map(|p| p.port_base).collect();
+        let endpoint_responses: Vec<EndpointResponse> = nmap_host
+            .ports
+            .iter()
+            .filter_map(|p| p.endpoint_response(nmap_host.ip))
+            .collect();
+        let banners: Vec<PortBanner> = nmap_host
+            .ports
+            .iter()
+            .filter_map(|p| {
+                Some(PortBanner {
+                    port_base: p.port_base,
+                    banner: p.banner.clone()?,
+                })
+            })
+            .collect();
+
+        let evidence = LivenessEvidence {
+            tcp_ports: all_ports
+                .iter()
+                .filter(|p| p.protocol() == TransportProtocol::Tcp)
+                .count() as u32,
+            udp_responses: all_ports
+                .iter()
+                .filter(|p| p.protocol() == TransportProtocol::Udp)
+                .count() as u32,
+            endpoint_responses: endpoint_responses.len() as u32,
+            has_mac: nmap_host.mac.is_some(),
+            has_hostname: nmap_host.hostname.is_some(),
+            ..LivenessEvidence::default()
+        };
+
+        let (mut host, services) = discover_host(
+            &ServiceMatchBaselineParams {
+                subnet,
+                interface: &interface,
+                all_ports: &all_ports,
+                endpoint_responses: &endpoint_responses,
+                banners: &banners,
+                virtualization: &None,
+                upnp_device: &None,
+            },
+            nmap_host.hostname,
+            request.host_naming_fallback,
+            &ctx,
+        );
+        host.base.liveness = Some(HostLiveness::from_evidence(&evidence));
+
+        let (host, _) = state
+            .services
+            .host_service
+            .create_host_with_services(host, services)
+            .await?;
+        result.host_ids.push(host.id);
+    }
+
+    tracing::info!(
+        "Imported {} hosts from nmap report, skipped {} outside known subnets or excluded",
+        result.host_ids.len(),
+        result.skipped.len()
+    );
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// Import hosts from an uploaded DHCP lease file or export. Leases update the hosts already
+/// at their address or MAC, so their hostnames name hosts that were only known by IP.
+async fn import_dhcp_leases(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<DhcpLeaseImportRequest>,
+) -> ApiResult<Json<ApiResponse<DhcpLeaseImportResult>>> {
+    let daemon = state
+        .services
+        .daemon_service
+        .get_by_id(&request.daemon_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Daemon '{}' not found", request.daemon_id)))?;
+    let network_id = daemon.base.network_id;
+    network_role(&state, &user.0, &network_id).await?;
+
+    let leases = dhcp::parse(request.format, &request.leases)
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    let subnets = state
+        .services
+        .subnet_service
+        .get_all(EntityFilter::unfiltered().network_ids(&[network_id]))
+        .await?;
+
+    let discovery_type = DiscoveryType::DhcpLeases {
+        format: request.format,
+        path: None,
+    };
+    let exclusions = state
+        .services
+        .network_settings_service
+        .for_network(&network_id)
+        .await?
+        .exclusions;
+    let now = Utc::now();
+    let mut result = DhcpLeaseImportResult::default();
+
+    for lease in leases {
+        if !lease.is_active(now) {
+            result.expired += 1;
+            continue;
+        }
+        let Some(subnet) = subnets.iter().find(|s| s.base.cidr.contains(&lease.ip)) else {
+            result.skipped.push(lease.ip);
+            continue;
+        };
+        if exclusions.excludes_ip(lease.ip)
+            || lease.mac.is_some_and(|mac| exclusions.excludes_mac(&mac))
+        {
+            result.skipped.push(lease.ip);
+            continue;
+        }
+
+        let host = Host::new(HostBase {
+            name: lease
+                .hostname
+                .clone()
+                .unwrap_or_else(|| lease.ip.to_string()),
+            hostname: lease.hostname.clone(),
+            network_id,
+            interfaces: vec![Interface::new(InterfaceBase {
+                name: None,
+                subnet_id: subnet.id,
+                ip_address: lease.ip,
+                mac_address: lease.mac,
+            })],
+            source: EntitySource::Discovery {
+                metadata: vec![DiscoveryMetadata::new(discovery_type.clone(), daemon.id)],
+            },
+            dhcp_lease: Some(HostDhcpLease {
+                format: request.format,
+                client_hostname: lease.hostname,
+                expires_at: lease.expires_at,
+                collected_at: now,
+            }),
+            ..HostBase::default()
+        });
+
+        let host = state.services.host_service.create_host(host).await?;
+        result.host_ids.push(host.id);
+    }
+
+    tracing::info!(
+        "Imported {} hosts from {} leases, skipped {} outside known subnets or excluded and {} expired",
+        result.host_ids.len(),
+        request.format,
+        result.skipped.len(),
+        result.expired
+    );
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// Follow a single session: its current state, then each update until it finishes
+async fn session_events(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    // Subscribe first so no update between reading the session and streaming is lost
+    let mut rx = state.services.discovery_service.subscribe();
+
+    let session = state
+        .services
+        .discovery_service
+        .get_session(&session_id)
+        .await
+        .ok_or_else(|| ApiError::not_found(format!("Session '{}' not found", session_id)))?;
+
+    network_role(&state, &user.0, &session.network_id).await?;
+
+    let discovery_service = state.services.discovery_service.clone();
+    let stream = async_stream::stream! {
+        let finished = session.phase.is_finished();
+        yield Ok(session_event(&session));
+        if finished {
+            return;
+        }
+
+        loop {
+            match rx.recv().await {
+                Ok(update) if update.session_id == session_id => {
+                    let finished = update.phase.is_finished();
+                    yield Ok(session_event(&update));
+                    if finished {
+                        break;
+                    }
+                }
+                Ok(_) => continue,
+                // Skipped updates may include the last one, so catch up from the session
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let Some(current) = discovery_service.get_session(&session_id).await else {
+                        break;
+                    };
+                    let finished = current.phase.is_finished();
+                    yield Ok(session_event(&current));
+                    if finished {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Hosts created, updated and skipped by a session and its errors per address, so far if it's
+/// still running. Cancelled and failed sessions keep what they found before stopping.
+async fn session_results(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DiscoveryResults>>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    let service = &state.services.discovery_service;
+    let session = match service
+        .get_session(&session_id)
+        .await
+        .filter(|session| network_ids.contains(&session.network_id))
+    {
+        Some(session) => Some(session),
+        None => service.finished_session(&session_id, &network_ids).await?,
+    };
+
+    let session = session
+        .ok_or_else(|| ApiError::not_found(format!("Session '{}' not found", session_id)))?;
+
+    Ok(Json(ApiResponse::success(session.results)))
+}
+
+fn session_event(update: &DiscoveryUpdatePayload) -> Event {
+    Event::default()
+        .event(format!("{:?}", update.phase).to_lowercase())
+        .data(serde_json::to_string(update).unwrap_or_default())
+}
@@ -0,0 +1,323 @@
+use crate::server::{hosts::r#impl::dhcp::LeaseFormat, shared::csv::split_row};
+use anyhow::{Error, anyhow};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, net::IpAddr};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DhcpLeaseImportRequest {
+    /// Daemon the hosts are attributed to; they are added to its network
+    pub daemon_id: Uuid,
+    pub format: LeaseFormat,
+    /// Contents of the lease file or export
+    pub leases: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DhcpLeaseImportResult {
+    /// Hosts created or updated from an active lease
+    pub host_ids: Vec<Uuid>,
+    /// Leases outside every subnet of the network, or for hosts it excludes from discovery
+    pub skipped: Vec<IpAddr>,
+    /// Leases that had already expired
+    pub expired: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DhcpLease {
+    pub ip: IpAddr,
+    pub mac: Option<MacAddress>,
+    pub hostname: Option<String>,
+    /// None for leases that never expire
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl DhcpLease {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// Leases by address. Lease databases are append-only, so a later entry for an address
+/// replaces an earlier one. Released, declined and free leases are left out.
+pub fn parse(format: LeaseFormat, text: &str) -> Result<Vec<DhcpLease>, Error> {
+    let leases = match format {
+        LeaseFormat::IscDhcpd => parse_isc(text),
+        LeaseFormat::Kea => parse_kea(text)?,
+        LeaseFormat::Dnsmasq => parse_dnsmasq(text),
+        LeaseFormat::WindowsDhcp => parse_windows(text)?,
+    };
+
+    let by_ip: BTreeMap<IpAddr, Option<DhcpLease>> = leases.into_iter().collect();
+    Ok(by_ip.into_values().flatten().collect())
+}
+
+/// `lease 10.0.0.5 { ... }` blocks. None marks a lease that's no longer bound.
+fn parse_isc(text: &str) -> Vec<(IpAddr, Option<DhcpLease>)> {
+    let mut leases = Vec::new();
+    let mut current: Option<(DhcpLease, bool)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("lease ")
+            && let Some(ip) = rest.trim_end_matches('{').trim().parse::<IpAddr>().ok()
+        {
+            current = Some((
+                DhcpLease {
+                    ip,
+                    mac: None,
+                    hostname: None,
+                    expires_at: None,
+                },
+                true,
+            ));
+            continue;
+        }
+
+        let Some((lease, bound)) = current.as_mut() else {
+            continue;
+        };
+
+        if line == "}" {
+            let (lease, bound) = current.take().expect("Checked above");
+            leases.push((lease.ip, bound.then_some(lease)));
+            continue;
+        }
+
+        let statement = line.trim_end_matches(';');
+        if let Some(state) = statement.strip_prefix("binding state ") {
+            *bound = matches!(state, "active" | "static");
+        } else if let Some(mac) = statement.strip_prefix("hardware ethernet ") {
+            lease.mac = parse_mac(mac);
+        } else if let Some(hostname) = statement.strip_prefix("client-hostname ") {
+            lease.hostname = non_empty(hostname.trim_matches('"'));
+        } else if let Some(ends) = statement.strip_prefix("ends ") {
+            lease.expires_at = parse_isc_time(ends);
+        }
+    }
+
+    leases
+}
+
+/// `4 2024/03/01 22:00:00`, `epoch 1709330400` or `never`
+fn parse_isc_time(value: &str) -> Option<DateTime<Utc>> {
+    let mut parts = value.split_whitespace();
+    match parts.next()? {
+        "epoch" => DateTime::from_timestamp(parts.next()?.parse().ok()?, 0),
+        _ => {
+            let date_time = parts.collect::<Vec<_>>().join(" ");
+            NaiveDateTime::parse_from_str(&date_time, "%Y/%m/%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        }
+    }
+}
+
+/// Memfile CSV with a header row. State 0 is a bound lease; 1 declined, 2 reclaimed.
+fn parse_kea(text: &str) -> Result<Vec<(IpAddr, Option<DhcpLease>)>, Error> {
+    let mut rows = text.lines().filter(|line| !line.trim().is_empty());
+    let header = split_row(
+        rows.next()
+            .ok_or_else(|| anyhow!("The lease file is empty"))?,
+    );
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim() == name)
+            .ok_or_else(|| anyhow!("Kea lease file has no '{}' column", name))
+    };
+    let (address, hwaddr, expire, hostname, state) = (
+        column("address")?,
+        column("hwaddr")?,
+        column("expire")?,
+        column("hostname")?,
+        column("state")?,
+    );
+
+    Ok(rows
+        .map(split_row)
+        .filter_map(|row| {
+            let ip = row.get(address)?.trim().parse::<IpAddr>().ok()?;
+            let field = |index: usize| row.get(index).map(|f| f.trim()).unwrap_or_default();
+            let lease = DhcpLease {
+                ip,
+                mac: parse_mac(field(hwaddr)),
+                // Kea escapes commas in hostnames
+                hostname: non_empty(field(hostname).replace("&#x2c", ",").trim_end_matches('.')),
+                expires_at: field(expire)
+                    .parse()
+                    .ok()
+                    .and_then(|t| DateTime::from_timestamp(t, 0)),
+            };
+            Some((ip, (field(state) == "0").then_some(lease)))
+        })
+        .collect())
+}
+
+/// `<expiry> <mac> <ip> <hostname> <client id>`, with 0 for leases that never expire and
+/// `*` for unknown hostnames
+fn parse_dnsmasq(text: &str) -> Vec<(IpAddr, Option<DhcpLease>)> {
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [expiry, mac, ip, hostname, ..] = fields.as_slice() else {
+                return None;
+            };
+            let ip = ip.parse::<IpAddr>().ok()?;
+            let expires_at = match expiry.parse::<i64>().ok()? {
+                0 => None,
+                t => Some(DateTime::from_timestamp(t, 0)?),
+            };
+
+            Some((
+                ip,
+                Some(DhcpLease {
+                    ip,
+                    mac: parse_mac(mac),
+                    hostname: non_empty(hostname).filter(|h| h != "*"),
+                    expires_at,
+                }),
+            ))
+        })
+        .collect()
+}
+
+/// `Export-Csv` of `Get-DhcpServerv4Lease`. Expiry times are in the server's locale and
+/// timezone, and are read as UTC.
+fn parse_windows(text: &str) -> Result<Vec<(IpAddr, Option<DhcpLease>)>, Error> {
+    let mut rows = text
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}'))
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+    let header = split_row(
+        rows.next()
+            .ok_or_else(|| anyhow!("The lease export is empty"))?,
+    );
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let address = column("IPAddress")
+        .ok_or_else(|| anyhow!("Windows lease export has no 'IPAddress' column"))?;
+    let (client_id, hostname, expiry, state) = (
+        column("ClientId"),
+        column("HostName"),
+        column("LeaseExpiryTime"),
+        column("AddressState"),
+    );
+
+    Ok(rows
+        .map(split_row)
+        .filter_map(|row| {
+            let ip = row.get(address)?.trim().parse::<IpAddr>().ok()?;
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|i| row.get(i))
+                    .map(|f| f.trim())
+                    .unwrap_or_default()
+            };
+            let lease = DhcpLease {
+                ip,
+                mac: parse_mac(field(client_id)),
+                hostname: non_empty(field(hostname)),
+                expires_at: parse_windows_time(field(expiry)),
+            };
+            // Active, ActiveReservation and the like; not Declined or InactiveReservation
+            let bound = state.is_none() || field(state).starts_with("Active");
+            Some((ip, bound.then_some(lease)))
+        })
+        .collect())
+}
+
+fn parse_windows_time(value: &str) -> Option<DateTime<Utc>> {
+    [
+        "%m/%d/%Y %I:%M:%S %p",
+        "%Y-%m-%d %H:%M:%S",
+        "%d.%m.%Y %H:%M:%S",
+        "%d/%m/%Y %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .map(|t| t.and_utc())
+}
+
+/// Ethernet addresses separated by colons or dashes, as Windows writes them
+fn parse_mac(value: &str) -> Option<MacAddress> {
+    value.trim().replace('-', ":").parse().ok()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn later_isc_leases_replace_earlier_ones() {
+        let text = r#"
+            lease 10.0.0.5 {
+              starts 4 2024/02/29 22:00:00;
+              ends 5 2024/03/01 10:00:00;
+              binding state active;
+              hardware ethernet 00:11:32:aa:bb:cc;
+              client-hostname "nas";
+            }
+            lease 10.0.0.6 {
+              ends epoch 1709330400;
+              binding state active;
+              next binding state free;
+              hardware ethernet 00:11:32:aa:bb:cd;
+            }
+            lease 10.0.0.6 {
+              binding state free;
+            }
+        "#;
+
+        let leases = parse(LeaseFormat::IscDhcpd, text).unwrap();
+
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].ip, ip("10.0.0.5"));
+        assert_eq!(leases[0].hostname.as_deref(), Some("nas"));
+        assert!(leases[0].mac.is_some());
+        assert_eq!(
+            leases[0].expires_at,
+            DateTime::from_timestamp(1709287200, 0)
+        );
+    }
+
+    #[test]
+    fn parses_kea_dnsmasq_and_windows_leases() {
+        let kea = "address,hwaddr,client_id,valid_lifetime,expire,subnet_id,fqdn_fwd,fqdn_rev,hostname,state,user_context\n\
+            10.0.0.7,00:11:32:aa:bb:ce,,3600,1709330400,1,0,0,printer.lan.,0,\n\
+            10.0.0.8,00:11:32:aa:bb:cf,,3600,1709330400,1,0,0,,1,\n";
+        let leases = parse(LeaseFormat::Kea, kea).unwrap();
+        assert_eq!(leases.len(), 1);
+        assert_eq!(leases[0].hostname.as_deref(), Some("printer.lan"));
+
+        let dnsmasq = "0 00:11:32:aa:bb:d0 10.0.0.9 tv 01:00:11:32:aa:bb:d0\n\
+            1709330400 00:11:32:aa:bb:d1 10.0.0.10 * *\n";
+        let leases = parse(LeaseFormat::Dnsmasq, dnsmasq).unwrap();
+        assert_eq!(leases.len(), 2);
+        assert_eq!(leases[0].expires_at, None);
+        assert_eq!(leases[1].hostname, None);
+
+        let windows = "#TYPE Microsoft.Management.Infrastructure.CimInstance\n\
+            \"IPAddress\",\"ScopeId\",\"ClientId\",\"HostName\",\"AddressState\",\"LeaseExpiryTime\"\n\
+            \"10.0.0.11\",\"10.0.0.0\",\"00-11-32-aa-bb-d2\",\"ws1.corp.local\",\"Active\",\"3/1/2024 10:00:00 PM\"\n\
+            \"10.0.0.12\",\"10.0.0.0\",\"00-11-32-aa-bb-d3\",\"\",\"Declined\",\"\"\n";
+        let leases = parse(LeaseFormat::WindowsDhcp, windows).unwrap();
+        assert_eq!(leases.len(), 1);
+        assert!(leases[0].mac.is_some());
+        assert_eq!(
+            leases[0].expires_at,
+            DateTime::from_timestamp(1709330400, 0)
+        );
+
+        assert!(parse(LeaseFormat::Kea, "not,a,lease,file").is_err());
+    }
+}
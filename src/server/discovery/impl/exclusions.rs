@@ -0,0 +1,201 @@
+use std::net::IpAddr;
+
+use cidr::IpCidr;
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+
+use crate::server::hosts::r#impl::{base::Host, ports::PortConfig};
+
+/// What an exclusion rule matches
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ExclusionTarget {
+    Cidr {
+        cidr: IpCidr,
+    },
+    /// Inclusive, e.g. the part of a DHCP pool handed to printers
+    IpRange {
+        start: IpAddr,
+        end: IpAddr,
+    },
+    /// Leading hex digits of a MAC address, e.g. a vendor's OUI `00:1B:A9`. Separators and
+    /// case don't matter.
+    MacPrefix {
+        prefix: String,
+    },
+    /// Never probed on any host
+    Port {
+        port: PortConfig,
+    },
+}
+
+/// Something discovery must leave alone: daemons don't scan it and the server doesn't create
+/// hosts or ports for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExclusionRule {
+    #[serde(flatten)]
+    pub target: ExclusionTarget,
+    /// Why it's excluded, e.g. "crashes when port scanned"
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// A network's exclusion rules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Exclusions(pub Vec<ExclusionRule>);
+
+fn mac_hex(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+impl Exclusions {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn targets(&self) -> impl Iterator<Item = &ExclusionTarget> {
+        self.0.iter().map(|rule| &rule.target)
+    }
+
+    pub fn excludes_ip(&self, ip: IpAddr) -> bool {
+        self.targets().any(|target| match target {
+            ExclusionTarget::Cidr { cidr } => cidr.contains(&ip),
+            ExclusionTarget::IpRange { start, end } => {
+                // Comparing across address families would order every IPv4 address first
+                start.is_ipv4() == ip.is_ipv4() && *start <= ip && ip <= *end
+            }
+            _ => false,
+        })
+    }
+
+    /// Whether any rule needs a host's MAC address, so scanners know to resolve it before
+    /// probing ports
+    pub fn has_mac_rules(&self) -> bool {
+        self.targets()
+            .any(|target| matches!(target, ExclusionTarget::MacPrefix { .. }))
+    }
+
+    pub fn excludes_mac(&self, mac: &MacAddress) -> bool {
+        let mac = mac_hex(&mac.to_string());
+        self.targets().any(|target| match target {
+            ExclusionTarget::MacPrefix { prefix } => mac.starts_with(&mac_hex(prefix)),
+            _ => false,
+        })
+    }
+
+    pub fn excludes_port(&self, port: PortConfig) -> bool {
+        self.targets().any(|target| match target {
+            ExclusionTarget::Port { port: excluded } => *excluded == port,
+            _ => false,
+        })
+    }
+
+    /// Whether any of the host's addresses is excluded
+    pub fn excludes_host(&self, host: &Host) -> bool {
+        host.base.interfaces.iter().any(|interface| {
+            self.excludes_ip(interface.base.ip_address)
+                || interface
+                    .base
+                    .mac_address
+                    .is_some_and(|mac| self.excludes_mac(&mac))
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.0 {
+            match &rule.target {
+                ExclusionTarget::IpRange { start, end } => {
+                    if start.is_ipv4() != end.is_ipv4() {
+                        return Err(format!(
+                            "Range {} - {} mixes IPv4 and IPv6 addresses",
+                            start, end
+                        ));
+                    }
+                    if start > end {
+                        return Err(format!("Range {} - {} ends before it starts", start, end));
+                    }
+                }
+                ExclusionTarget::MacPrefix { prefix } => {
+                    let digits = mac_hex(prefix);
+                    if digits.is_empty()
+                        || digits.len() > 12
+                        || prefix
+                            .chars()
+                            .any(|c| !c.is_ascii_hexdigit() && !matches!(c, ':' | '-' | '.'))
+                    {
+                        return Err(format!("'{}' isn't a MAC address prefix", prefix));
+                    }
+                }
+                ExclusionTarget::Port { port } => {
+                    if port.number == 0 {
+                        return Err("Port numbers must be between 1 and 65535".to_string());
+                    }
+                }
+                ExclusionTarget::Cidr { .. } => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::hosts::r#impl::ports::PortBase;
+
+    fn rule(target: ExclusionTarget) -> ExclusionRule {
+        ExclusionRule {
+            target,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn matches_addresses_macs_and_ports() {
+        let exclusions = Exclusions(vec![
+            rule(ExclusionTarget::Cidr {
+                cidr: "10.0.5.0/24".parse().unwrap(),
+            }),
+            rule(ExclusionTarget::IpRange {
+                start: "192.168.1.200".parse().unwrap(),
+                end: "192.168.1.210".parse().unwrap(),
+            }),
+            rule(ExclusionTarget::MacPrefix {
+                prefix: "00-1b-a9".to_string(),
+            }),
+            rule(ExclusionTarget::Port {
+                port: PortBase::new_tcp(9100).config(),
+            }),
+        ]);
+
+        assert!(exclusions.excludes_ip("10.0.5.17".parse().unwrap()));
+        assert!(exclusions.excludes_ip("192.168.1.205".parse().unwrap()));
+        assert!(!exclusions.excludes_ip("192.168.1.211".parse().unwrap()));
+        assert!(!exclusions.excludes_ip("::1".parse().unwrap()));
+
+        assert!(exclusions.excludes_mac(&"00:1B:A9:12:34:56".parse().unwrap()));
+        assert!(!exclusions.excludes_mac(&"00:1C:A9:12:34:56".parse().unwrap()));
+
+        assert!(exclusions.excludes_port(PortBase::new_tcp(9100).config()));
+        assert!(!exclusions.excludes_port(PortBase::new_udp(9100).config()));
+    }
+
+    #[test]
+    fn rejects_backwards_ranges_and_bad_prefixes() {
+        let range = Exclusions(vec![rule(ExclusionTarget::IpRange {
+            start: "10.0.0.9".parse().unwrap(),
+            end: "10.0.0.1".parse().unwrap(),
+        })]);
+        let prefix = Exclusions(vec![rule(ExclusionTarget::MacPrefix {
+            prefix: "printer".to_string(),
+        })]);
+
+        assert!(range.validate().is_err());
+        assert!(prefix.validate().is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::server::discovery::r#impl::base::Discovery;
+use crate::server::discovery::r#impl::types::DiscoveryType;
 use crate::server::discovery::service::DiscoveryService;
 use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
 
 impl CrudHandlers for Discovery {
     type Service = DiscoveryService;
@@ -8,4 +10,26 @@ impl CrudHandlers for Discovery {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.discovery_service
     }
+
+    fn validate(&self) -> Result<(), String> {
+        if let DiscoveryType::Network {
+            subnet_ids,
+            parallel_daemon_ids,
+            shard,
+            ..
+        } = &self.base.discovery_type
+        {
+            if shard.is_some() {
+                return Err("Address ranges are assigned when a scan is split".to_string());
+            }
+            if !parallel_daemon_ids.is_empty() && subnet_ids.as_ref().is_none_or(|s| s.is_empty()) {
+                return Err("Scans split across daemons need their subnets chosen".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
@@ -1,4 +1,11 @@
 pub mod base;
+pub mod dhcp;
+pub mod exclusions;
 pub mod handlers;
+pub mod nmap;
+pub mod parallel;
+pub mod results;
+pub mod scan_settings;
+pub mod sessions;
 pub mod storage;
 pub mod types;
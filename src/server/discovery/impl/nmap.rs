@@ -0,0 +1,236 @@
+use crate::server::discovery::r#impl::types::HostNamingFallback;
+use crate::server::{
+    hosts::r#impl::ports::{PortBase, TransportProtocol},
+    services::r#impl::{
+        endpoints::{ApplicationProtocol, Endpoint, EndpointResponse},
+        upnp::unescape,
+    },
+};
+use anyhow::{Error, bail};
+use mac_address::MacAddress;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{net::IpAddr, sync::LazyLock};
+use uuid::Uuid;
+
+static HOST_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"(?s)<host[\s>].*?</host>"));
+static ADDRESS_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"<address\s[^>]*>"));
+static HOSTNAME_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"<hostname\s[^>]*>"));
+static PORT_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"(?s)<port\s[^>]*>.*?</port>"));
+static SCRIPT_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"<script\s[^>]*>"));
+/// Any opening or self-closing tag, capturing its name
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| regex(r"<([\w:-]+)(?:[\s/][^>]*)?>"));
+/// Any attribute of a tag, capturing its name and raw value
+static ATTR_RE: LazyLock<Regex> = LazyLock::new(|| regex(r#"\s([\w:-]+)="([^"]*)""#));
+
+fn regex(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("Invalid nmap report pattern")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NmapImportRequest {
+    /// Daemon the hosts are attributed to; they are added to its network
+    pub daemon_id: Uuid,
+    #[serde(default)]
+    pub host_naming_fallback: HostNamingFallback,
+    /// Output of `nmap -oX`, ideally with `-sV` so banners can be matched
+    pub xml: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NmapImportResult {
+    pub host_ids: Vec<Uuid>,
+    /// Hosts outside every subnet of the network, which have nothing to attach an interface to,
+    /// and hosts the network excludes from discovery
+    pub skipped: Vec<IpAddr>,
+}
+
+/// Host from an nmap XML report (`nmap -oX`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NmapHost {
+    pub ip: IpAddr,
+    pub mac: Option<MacAddress>,
+    pub hostname: Option<String>,
+    /// Open ports only
+    pub ports: Vec<NmapPort>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NmapPort {
+    pub port_base: PortBase,
+    /// nmap's service name, e.g. `http` or `ssh`
+    pub service: Option<String>,
+    /// `ssl` when nmap found the service behind TLS
+    pub tunnel: Option<String>,
+    /// Product, version and script output, which endpoint patterns are matched against
+    pub banner: Option<String>,
+}
+
+impl NmapPort {
+    /// Treat what nmap read from a web server as the response from its root path
+    pub fn endpoint_response(&self, ip: IpAddr) -> Option<EndpointResponse> {
+        let service = self.service.as_deref()?;
+        if !service.starts_with("http") {
+            return None;
+        }
+
+        let protocol = if service == "https" || self.tunnel.as_deref() == Some("ssl") {
+            ApplicationProtocol::Https
+        } else {
+            ApplicationProtocol::Http
+        };
+
+        Some(EndpointResponse {
+            endpoint: Endpoint {
+                protocol,
+                ip: Some(ip),
+                port_base: self.port_base,
+                path: "/".to_string(),
+            },
+            response: self.banner.clone()?,
+        })
+    }
+}
+
+/// Hosts that were up, with their open ports
+pub fn parse(xml: &str) -> Result<Vec<NmapHost>, Error> {
+    if !xml.contains("<nmaprun") {
+        bail!("Not an nmap XML report");
+    }
+
+    let hosts = HOST_RE
+        .find_iter(xml)
+        .filter_map(|host| {
+            let host = host.as_str();
+
+            // Hosts nmap listed as down still appear with -v
+            if let Some(status) = tag(host, "status")
+                && attr(status, "state").as_deref() != Some("up")
+            {
+                return None;
+            }
+
+            let mut ip = None;
+            let mut mac = None;
+            for address in ADDRESS_RE.find_iter(host).map(|m| m.as_str()) {
+                match attr(address, "addrtype").as_deref() {
+                    Some("ipv4" | "ipv6") => {
+                        ip = attr(address, "addr").and_then(|a| a.parse().ok())
+                    }
+                    Some("mac") => mac = attr(address, "addr").and_then(|a| a.parse().ok()),
+                    _ => {}
+                }
+            }
+
+            let hostname = HOSTNAME_RE
+                .find_iter(host)
+                .find_map(|h| attr(h.as_str(), "name"));
+
+            let ports = PORT_RE
+                .find_iter(host)
+                .filter_map(|p| parse_port(p.as_str()))
+                .collect();
+
+            Some(NmapHost {
+                ip: ip?,
+                mac,
+                hostname,
+                ports,
+            })
+        })
+        .collect();
+
+    Ok(hosts)
+}
+
+fn parse_port(port: &str) -> Option<NmapPort> {
+    let open = tag(port, "state").and_then(|s| attr(s, "state"));
+    if open.as_deref() != Some("open") {
+        return None;
+    }
+
+    let opening = tag(port, "port")?;
+    let protocol = match attr(opening, "protocol")?.as_str() {
+        "tcp" => TransportProtocol::Tcp,
+        "udp" => TransportProtocol::Udp,
+        _ => return None,
+    };
+    let number: u16 = attr(opening, "portid")?.parse().ok()?;
+
+    let service = tag(port, "service");
+
+    let banner: Vec<String> = service
+        .into_iter()
+        .flat_map(|s| ["product", "version", "extrainfo"].map(|a| attr(s, a)))
+        .chain(
+            SCRIPT_RE
+                .find_iter(port)
+                .map(|s| attr(s.as_str(), "output")),
+        )
+        .flatten()
+        .collect();
+
+    Some(NmapPort {
+        port_base: PortBase::new(number, protocol),
+        service: service.and_then(|s| attr(s, "name")),
+        tunnel: service.and_then(|s| attr(s, "tunnel")),
+        banner: (!banner.is_empty()).then(|| banner.join("\n")),
+    })
+}
+
+/// First opening (or self-closing) tag with the given name
+fn tag<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    TAG_RE
+        .captures_iter(xml)
+        .find(|c| &c[1] == name)
+        .and_then(|c| c.get(0))
+        .map(|m| m.as_str())
+}
+
+fn attr(tag: &str, name: &str) -> Option<String> {
+    ATTR_RE
+        .captures_iter(tag)
+        .find(|c| &c[1] == name)
+        .map(|c| unescape(&c[2]))
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_open_ports_and_banners_of_hosts_that_are_up() {
+        let xml = r#"<?xml version="1.0"?>
+            <nmaprun scanner="nmap" args="nmap -sV -oX - 10.0.0.0/24">
+              <host><status state="up" reason="arp-response"/>
+                <address addr="10.0.0.5" addrtype="ipv4"/>
+                <address addr="00:11:32:AA:BB:CC" addrtype="mac" vendor="Synology"/>
+                <hostnames><hostname name="nas.lan" type="PTR"/></hostnames>
+                <ports>
+                  <port protocol="tcp" portid="5001"><state state="open"/>
+                    <service name="http" product="nginx" tunnel="ssl"/>
+                    <script id="http-title" output="Synology DiskStation"/>
+                  </port>
+                  <port protocol="tcp" portid="23"><state state="closed"/></port>
+                </ports>
+              </host>
+              <host><status state="down"/><address addr="10.0.0.6" addrtype="ipv4"/></host>
+            </nmaprun>"#;
+
+        let hosts = parse(xml).unwrap();
+
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].ip, "10.0.0.5".parse::<IpAddr>().unwrap());
+        assert_eq!(hosts[0].hostname.as_deref(), Some("nas.lan"));
+        assert!(hosts[0].mac.is_some());
+        assert_eq!(hosts[0].ports.len(), 1);
+
+        let response = hosts[0].ports[0].endpoint_response(hosts[0].ip).unwrap();
+        assert_eq!(response.endpoint.protocol, ApplicationProtocol::Https);
+        assert!(response.response.contains("nginx"));
+        assert!(response.response.contains("DiskStation"));
+
+        assert!(parse("<html></html>").is_err());
+    }
+}
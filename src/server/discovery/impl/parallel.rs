@@ -0,0 +1,214 @@
+use std::ops::Range;
+
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    daemon::discovery::types::base::DiscoveryPhase,
+    server::daemons::r#impl::api::DiscoveryUpdatePayload,
+};
+
+/// How much more than an even share of addresses one daemon may get before subnets are split
+/// into address ranges instead of being handed out whole
+const MAX_SUBNET_IMBALANCE: f64 = 1.5;
+
+/// The part of each subnet one daemon scans when a network scan is split across daemons by
+/// address range: the `index`th of `count` equal ranges
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Hash)]
+pub struct ScanShard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl ScanShard {
+    /// Positions, in address order, of this shard's addresses in a subnet of `len` addresses
+    pub fn range(&self, len: usize) -> Range<usize> {
+        let count = self.count.max(1) as usize;
+        let index = (self.index as usize).min(count - 1);
+        (len * index / count)..(len * (index + 1) / count)
+    }
+}
+
+/// Addresses in a subnet, as far as they fit in a `usize`
+pub fn address_count(cidr: &IpCidr) -> usize {
+    let bits = match cidr {
+        IpCidr::V4(_) => 32,
+        IpCidr::V6(_) => 128,
+    };
+    1usize
+        .checked_shl(u32::from(bits - cidr.network_length()))
+        .unwrap_or(usize::MAX)
+}
+
+/// What one daemon scans of a split network scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanAssignment {
+    pub daemon_id: Uuid,
+    pub subnet_ids: Vec<Uuid>,
+    pub shard: Option<ScanShard>,
+}
+
+/// Split subnets, given with their address counts, between daemons. Whole subnets go to the
+/// daemon with the fewest addresses so far, largest first. If that leaves a daemon idle or one
+/// with far more than its share, every daemon scans a range of each subnet instead.
+pub fn assign(daemon_ids: &[Uuid], subnets: &[(Uuid, usize)]) -> Vec<ScanAssignment> {
+    let all_subnets = || subnets.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+
+    if daemon_ids.len() <= 1 {
+        return daemon_ids
+            .iter()
+            .map(|daemon_id| ScanAssignment {
+                daemon_id: *daemon_id,
+                subnet_ids: all_subnets(),
+                shard: None,
+            })
+            .collect();
+    }
+
+    let mut by_size: Vec<&(Uuid, usize)> = subnets.iter().collect();
+    by_size.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mut loads = vec![0usize; daemon_ids.len()];
+    let mut whole: Vec<Vec<Uuid>> = vec![Vec::new(); daemon_ids.len()];
+    for (subnet_id, size) in by_size {
+        let (least_loaded, _) = loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, load)| **load)
+            .unwrap_or((0, &0));
+        loads[least_loaded] = loads[least_loaded].saturating_add(*size);
+        whole[least_loaded].push(*subnet_id);
+    }
+
+    let total: f64 = subnets.iter().map(|(_, size)| *size as f64).sum();
+    let even_share = total / daemon_ids.len() as f64;
+    let balanced = whole.iter().all(|subnets| !subnets.is_empty())
+        && loads
+            .iter()
+            .all(|load| *load as f64 <= even_share * MAX_SUBNET_IMBALANCE);
+
+    if balanced {
+        return daemon_ids
+            .iter()
+            .zip(whole)
+            .map(|(daemon_id, subnet_ids)| ScanAssignment {
+                daemon_id: *daemon_id,
+                subnet_ids,
+                shard: None,
+            })
+            .collect();
+    }
+
+    let count = daemon_ids.len() as u32;
+    daemon_ids
+        .iter()
+        .zip(0..)
+        .map(|(daemon_id, index)| ScanAssignment {
+            daemon_id: *daemon_id,
+            subnet_ids: all_subnets(),
+            shard: Some(ScanShard { index, count }),
+        })
+        .collect()
+}
+
+/// Progress of a split network scan as a whole, from the sessions of its daemons
+pub fn merge_progress(
+    group_id: Uuid,
+    sessions: &[DiscoveryUpdatePayload],
+) -> Option<DiscoveryUpdatePayload> {
+    let first = sessions.first()?;
+
+    let mut merged = DiscoveryUpdatePayload {
+        session_id: group_id,
+        phase: merged_phase(sessions),
+        processed: 0,
+        total_to_process: 0,
+        error: None,
+        started_at: None,
+        finished_at: None,
+        group_id: Some(group_id),
+        results: Default::default(),
+        ..first.clone()
+    };
+
+    for session in sessions {
+        merged.processed += session.processed;
+        merged.total_to_process += session.total_to_process;
+        merged.error = merged.error.or_else(|| session.error.clone());
+        merged.started_at = match (merged.started_at, session.started_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        merged.finished_at = merged.finished_at.max(session.finished_at);
+        merged.results.merge(&session.results);
+    }
+
+    if !merged.phase.is_finished() {
+        merged.finished_at = None;
+    }
+
+    Some(merged)
+}
+
+/// Finished once every daemon has; failed or cancelled if any daemon was. Until then, the phase
+/// of the daemon furthest along.
+fn merged_phase(sessions: &[DiscoveryUpdatePayload]) -> DiscoveryPhase {
+    let any = |phase: fn(&DiscoveryPhase) -> bool| sessions.iter().any(|s| phase(&s.phase));
+
+    if sessions.iter().all(|s| s.phase.is_finished()) {
+        if any(|p| matches!(p, DiscoveryPhase::Failed)) {
+            DiscoveryPhase::Failed
+        } else if any(|p| matches!(p, DiscoveryPhase::Cancelled)) {
+            DiscoveryPhase::Cancelled
+        } else {
+            DiscoveryPhase::Complete
+        }
+    } else if any(|p| matches!(p, DiscoveryPhase::Scanning)) {
+        DiscoveryPhase::Scanning
+    } else if any(|p| matches!(p, DiscoveryPhase::Started)) {
+        DiscoveryPhase::Started
+    } else if any(|p| matches!(p, DiscoveryPhase::Starting)) {
+        DiscoveryPhase::Starting
+    } else if any(|p| matches!(p, DiscoveryPhase::Paused)) {
+        DiscoveryPhase::Paused
+    } else {
+        DiscoveryPhase::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_subnets_are_handed_out_when_they_balance() {
+        let daemons = [Uuid::new_v4(), Uuid::new_v4()];
+        let subnets = [
+            (Uuid::new_v4(), 256),
+            (Uuid::new_v4(), 256),
+            (Uuid::new_v4(), 128),
+            (Uuid::new_v4(), 128),
+        ];
+
+        let assignments = assign(&daemons, &subnets);
+
+        assert!(assignments.iter().all(|a| a.shard.is_none()));
+        assert!(assignments.iter().all(|a| a.subnet_ids.len() == 2));
+    }
+
+    #[test]
+    fn one_large_subnet_is_split_by_address_range() {
+        let daemons = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let subnets = [(Uuid::new_v4(), 65536), (Uuid::new_v4(), 256)];
+
+        let assignments = assign(&daemons, &subnets);
+
+        assert!(assignments.iter().all(|a| a.subnet_ids.len() == 2));
+        let ranges: Vec<_> = assignments
+            .iter()
+            .map(|a| a.shard.unwrap().range(256))
+            .collect();
+        assert_eq!(ranges, vec![0..85, 85..170, 170..256]);
+    }
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::IpAddr,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Errors kept per session; a scan of a large, mostly unreachable range would otherwise send one
+/// for nearly every address with each progress update
+const MAX_ERRORS: usize = 500;
+
+/// What a discovery session did with the hosts it found so far, tallied by the daemon and sent
+/// with each progress update
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscoveryResults {
+    pub hosts_created: usize,
+    pub hosts_updated: usize,
+    /// Found but not reported, such as hosts excluded by MAC address
+    pub hosts_skipped: usize,
+    /// Why scanning or reporting an address failed
+    pub errors: BTreeMap<IpAddr, String>,
+    /// Errors left out once `errors` is full
+    pub errors_omitted: usize,
+    /// Hosts counted already, so a host reported twice, e.g. from two subnets, counts once
+    #[serde(skip)]
+    counted_hosts: HashSet<Uuid>,
+}
+
+impl DiscoveryResults {
+    /// Count a host the server accepted. It was created if it didn't exist before the session
+    /// started.
+    pub fn record_host(
+        &mut self,
+        host_id: Uuid,
+        host_created_at: DateTime<Utc>,
+        started_at: Option<DateTime<Utc>>,
+    ) {
+        if !self.counted_hosts.insert(host_id) {
+            return;
+        }
+
+        if started_at.is_some_and(|started_at| host_created_at >= started_at) {
+            self.hosts_created += 1;
+        } else {
+            self.hosts_updated += 1;
+        }
+    }
+
+    pub fn record_skipped(&mut self) {
+        self.hosts_skipped += 1;
+    }
+
+    /// Add another session's results, as for the daemons of one split scan
+    pub fn merge(&mut self, other: &DiscoveryResults) {
+        self.hosts_created += other.hosts_created;
+        self.hosts_updated += other.hosts_updated;
+        self.hosts_skipped += other.hosts_skipped;
+        self.errors_omitted += other.errors_omitted;
+        for (ip, error) in &other.errors {
+            self.record_error(*ip, error);
+        }
+    }
+
+    pub fn record_error(&mut self, ip: IpAddr, error: impl ToString) {
+        if self.errors.len() < MAX_ERRORS || self.errors.contains_key(&ip) {
+            self.errors.insert(ip, error.to_string());
+        } else {
+            self.errors_omitted += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn hosts_created_during_the_session_count_once() {
+        let started_at = Utc::now();
+        let mut results = DiscoveryResults::default();
+
+        let new_host = Uuid::new_v4();
+        results.record_host(
+            new_host,
+            started_at + Duration::seconds(5),
+            Some(started_at),
+        );
+        results.record_host(
+            new_host,
+            started_at + Duration::seconds(5),
+            Some(started_at),
+        );
+        results.record_host(
+            Uuid::new_v4(),
+            started_at - Duration::days(3),
+            Some(started_at),
+        );
+
+        assert_eq!(results.hosts_created, 1);
+        assert_eq!(results.hosts_updated, 1);
+    }
+}
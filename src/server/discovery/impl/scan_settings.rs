@@ -0,0 +1,420 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+use uuid::Uuid;
+
+use crate::server::{
+    discovery::r#impl::exclusions::Exclusions,
+    hosts::r#impl::ports::{PortBase, PortConfig, TransportProtocol},
+    services::r#impl::base::Service,
+};
+
+/// TCP ports in a quick scan: remote access, web, mail, file sharing and databases
+const QUICK_TCP_PORTS: [u16; 20] = [
+    21, 22, 23, 25, 53, 80, 110, 135, 139, 143, 443, 445, 993, 1433, 3306, 3389, 5432, 5900, 8080,
+    8443,
+];
+
+/// UDP ports in a quick scan
+const QUICK_UDP_PORTS: [u16; 2] = [53, 161];
+
+/// TCP ports a thorough scan covers on top of the standard ones
+const THOROUGH_TCP_RANGE: std::ops::RangeInclusive<u16> = 1..=1024;
+
+/// Bytes on the wire for one probe, averaged over the handshake to an open port and the
+/// SYN and reset exchanged with a closed one
+const PROBE_BYTES: f64 = 200.0;
+
+/// Longest timeout or retry delay a network, daemon or scan profile may set
+const MAX_TIMEOUT_MS: u64 = 60_000;
+
+/// Most connect retries a network, daemon or scan profile may set
+const MAX_TCP_RETRIES: u32 = 10;
+
+/// Which ports a network scan probes
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ScanPorts {
+    /// A few ports that are commonly open, for a fast look at a network
+    Quick,
+    /// Every port a service definition is discovered on
+    #[default]
+    Standard,
+    /// The standard ports and all well-known TCP ports
+    Thorough,
+    /// Only the listed ports
+    Custom { ports: Vec<PortConfig> },
+}
+
+impl ScanPorts {
+    /// The ports to scan, sorted and without duplicates
+    pub fn resolve(&self) -> Vec<PortConfig> {
+        let standard = || {
+            Service::all_discovery_ports()
+                .into_iter()
+                .map(|p| p.config())
+        };
+
+        let mut ports: Vec<PortConfig> = match self {
+            ScanPorts::Quick => QUICK_TCP_PORTS
+                .iter()
+                .map(|n| PortBase::new_tcp(*n).config())
+                .chain(
+                    QUICK_UDP_PORTS
+                        .iter()
+                        .map(|n| PortBase::new_udp(*n).config()),
+                )
+                .collect(),
+            ScanPorts::Standard => standard().collect(),
+            ScanPorts::Thorough => standard()
+                .chain(THOROUGH_TCP_RANGE.map(|n| PortBase::new_tcp(n).config()))
+                .collect(),
+            ScanPorts::Custom { ports } => ports.clone(),
+        };
+
+        ports.sort_by_key(|p| (p.number, p.protocol));
+        ports.dedup();
+        ports
+    }
+
+    /// Port numbers to scan over one protocol, before exclusions
+    pub fn numbers(&self, protocol: TransportProtocol) -> Vec<u16> {
+        self.resolve()
+            .into_iter()
+            .filter(|p| p.protocol == protocol)
+            .map(|p| p.number)
+            .collect()
+    }
+
+    /// Whether ports that only UDP probes or HTTP endpoint definitions know about are tried
+    /// as well as the listed ones
+    pub fn includes_probe_ports(&self) -> bool {
+        matches!(self, ScanPorts::Standard | ScanPorts::Thorough)
+    }
+}
+
+/// Limits for hosts in one subnet, on top of the scan-wide ones
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubnetScanLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_packets_per_second: Option<u32>,
+    /// Hosts in the subnet scanned at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrent_hosts: Option<u32>,
+}
+
+/// How fast a scan may go. Unset limits are sized by the daemon from its file descriptor limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanLimits {
+    /// Cap on probe traffic across the whole scan, estimated from the packets a probe sends
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bandwidth_kbps: Option<u32>,
+    /// Cap on probes sent per second across the whole scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_packets_per_second: Option<u32>,
+    /// Most hosts scanned at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrent_scans: Option<u32>,
+    /// Ports probed at once on each host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port_batch_size: Option<u32>,
+    /// Limits for individual subnets, by subnet id, e.g. a slower pace on a WiFi subnet
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub subnets: BTreeMap<Uuid, SubnetScanLimits>,
+    /// Slow down when probes start timing out or coming back unreachable, and speed back up to
+    /// the limits once they stop. On unless turned off.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adaptive: Option<bool>,
+}
+
+impl ScanLimits {
+    /// Probes per second allowed by the packet rate and bandwidth caps, whichever is lower
+    pub fn max_probes_per_second(&self) -> Option<f64> {
+        let by_bandwidth = self
+            .max_bandwidth_kbps
+            .filter(|kbps| *kbps > 0)
+            .map(|kbps| f64::from(kbps) * 1000.0 / 8.0 / PROBE_BYTES);
+        let by_packets = self
+            .max_packets_per_second
+            .filter(|rate| *rate > 0)
+            .map(f64::from);
+
+        match (by_bandwidth, by_packets) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive.unwrap_or(true)
+    }
+
+    /// Set limits replace those in `limits`; subnet limits replace those of the same subnet
+    pub fn apply(&self, limits: &mut ScanLimits) {
+        if self.max_bandwidth_kbps.is_some() {
+            limits.max_bandwidth_kbps = self.max_bandwidth_kbps;
+        }
+        if self.max_packets_per_second.is_some() {
+            limits.max_packets_per_second = self.max_packets_per_second;
+        }
+        if self.concurrent_scans.is_some() {
+            limits.concurrent_scans = self.concurrent_scans;
+        }
+        if self.port_batch_size.is_some() {
+            limits.port_batch_size = self.port_batch_size;
+        }
+        if self.adaptive.is_some() {
+            limits.adaptive = self.adaptive;
+        }
+        limits.subnets.extend(&self.subnets);
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let subnet_limits = self
+            .subnets
+            .values()
+            .flat_map(|subnet| [subnet.max_packets_per_second, subnet.concurrent_hosts]);
+
+        if [
+            self.max_bandwidth_kbps,
+            self.max_packets_per_second,
+            self.concurrent_scans,
+            self.port_batch_size,
+        ]
+        .into_iter()
+        .chain(subnet_limits)
+        .any(|limit| limit == Some(0))
+        {
+            return Err("Scan limits can't be 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Ports, timeouts, retries and limits the daemon applies while scanning, resolved by the server
+/// from network and daemon overrides and the discovery's scan profile, and delivered with each
+/// discovery request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSettings {
+    /// Timeout for a single TCP connect attempt
+    pub tcp_timeout_ms: u64,
+    /// Additional connect attempts after a timeout
+    pub tcp_retries: u32,
+    /// Delay before retrying a timed out connect
+    pub retry_delay_ms: u64,
+    /// Request and connect timeout for HTTP endpoint probes
+    pub endpoint_timeout_ms: u64,
+    /// Response timeout for UDP probes without a per-port override
+    pub udp_timeout_ms: u64,
+    /// Per-port UDP response timeouts, e.g. a longer one for DHCP on port 67
+    pub udp_timeouts_ms: BTreeMap<u16, u64>,
+    /// Read a banner from each open TCP port, sending a protocol probe where one is known
+    pub grab_banners: bool,
+    /// How long to wait for a banner after connecting
+    pub banner_timeout_ms: u64,
+    /// Ports network scans probe, from the scan profile
+    pub ports: ScanPorts,
+    /// Rate and concurrency limits, from the network's settings and the scan profile
+    pub limits: ScanLimits,
+    /// Addresses, MAC prefixes and ports not to probe, from the network's settings
+    pub exclusions: Exclusions,
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        Self {
+            tcp_timeout_ms: 800,
+            tcp_retries: 1,
+            retry_delay_ms: 100,
+            endpoint_timeout_ms: 800,
+            udp_timeout_ms: 2000,
+            udp_timeouts_ms: BTreeMap::new(),
+            grab_banners: true,
+            banner_timeout_ms: 1000,
+            ports: ScanPorts::default(),
+            limits: ScanLimits::default(),
+            exclusions: Exclusions::default(),
+        }
+    }
+}
+
+impl ScanSettings {
+    pub fn tcp_timeout(&self) -> Duration {
+        Duration::from_millis(self.tcp_timeout_ms)
+    }
+
+    pub fn tcp_max_attempts(&self) -> u32 {
+        self.tcp_retries.saturating_add(1)
+    }
+
+    pub fn retry_delay(&self) -> Duration {
+        Duration::from_millis(self.retry_delay_ms)
+    }
+
+    pub fn endpoint_timeout(&self) -> Duration {
+        Duration::from_millis(self.endpoint_timeout_ms)
+    }
+
+    pub fn udp_timeout(&self, port: u16) -> Duration {
+        Duration::from_millis(
+            self.udp_timeouts_ms
+                .get(&port)
+                .copied()
+                .unwrap_or(self.udp_timeout_ms),
+        )
+    }
+
+    pub fn banner_timeout(&self) -> Duration {
+        Duration::from_millis(self.banner_timeout_ms)
+    }
+}
+
+/// Scan settings stored on a network or daemon. Unset fields inherit from the level below:
+/// daemon overrides apply on top of network overrides, which apply on top of the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSettingsOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub udp_timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub udp_timeouts_ms: BTreeMap<u16, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grab_banners: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub banner_timeout_ms: Option<u64>,
+}
+
+impl ScanSettingsOverrides {
+    pub fn apply(&self, settings: &mut ScanSettings) {
+        if let Some(v) = self.tcp_timeout_ms {
+            settings.tcp_timeout_ms = v;
+        }
+        if let Some(v) = self.tcp_retries {
+            settings.tcp_retries = v;
+        }
+        if let Some(v) = self.retry_delay_ms {
+            settings.retry_delay_ms = v;
+        }
+        if let Some(v) = self.endpoint_timeout_ms {
+            settings.endpoint_timeout_ms = v;
+        }
+        if let Some(v) = self.udp_timeout_ms {
+            settings.udp_timeout_ms = v;
+        }
+        settings.udp_timeouts_ms.extend(&self.udp_timeouts_ms);
+        if let Some(v) = self.grab_banners {
+            settings.grab_banners = v;
+        }
+        if let Some(v) = self.banner_timeout_ms {
+            settings.banner_timeout_ms = v;
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let timeouts = [
+            ("TCP timeout", self.tcp_timeout_ms),
+            ("Endpoint timeout", self.endpoint_timeout_ms),
+            ("UDP timeout", self.udp_timeout_ms),
+            ("Banner timeout", self.banner_timeout_ms),
+        ]
+        .into_iter()
+        .chain(
+            self.udp_timeouts_ms
+                .values()
+                .map(|timeout| ("UDP timeout", Some(*timeout))),
+        );
+
+        for (name, timeout) in timeouts {
+            if let Some(timeout) = timeout
+                && !(1..=MAX_TIMEOUT_MS).contains(&timeout)
+            {
+                return Err(format!(
+                    "{} must be between 1 and {} ms",
+                    name, MAX_TIMEOUT_MS
+                ));
+            }
+        }
+        if self
+            .retry_delay_ms
+            .is_some_and(|delay| delay > MAX_TIMEOUT_MS)
+        {
+            return Err(format!(
+                "Retry delay can't be more than {} ms",
+                MAX_TIMEOUT_MS
+            ));
+        }
+        if self
+            .tcp_retries
+            .is_some_and(|retries| retries > MAX_TCP_RETRIES)
+        {
+            return Err(format!(
+                "TCP retries can't be more than {}",
+                MAX_TCP_RETRIES
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_are_bounded() {
+        assert!(ScanSettingsOverrides::default().validate().is_ok());
+
+        let within = ScanSettingsOverrides {
+            tcp_timeout_ms: Some(MAX_TIMEOUT_MS),
+            tcp_retries: Some(MAX_TCP_RETRIES),
+            retry_delay_ms: Some(0),
+            udp_timeouts_ms: BTreeMap::from([(67, 5000)]),
+            ..Default::default()
+        };
+        assert!(within.validate().is_ok());
+
+        for overrides in [
+            ScanSettingsOverrides {
+                tcp_timeout_ms: Some(0),
+                ..Default::default()
+            },
+            ScanSettingsOverrides {
+                banner_timeout_ms: Some(MAX_TIMEOUT_MS + 1),
+                ..Default::default()
+            },
+            ScanSettingsOverrides {
+                udp_timeouts_ms: BTreeMap::from([(67, 0)]),
+                ..Default::default()
+            },
+            ScanSettingsOverrides {
+                retry_delay_ms: Some(u64::MAX),
+                ..Default::default()
+            },
+            ScanSettingsOverrides {
+                tcp_retries: Some(u32::MAX),
+                ..Default::default()
+            },
+        ] {
+            assert!(overrides.validate().is_err(), "{:?}", overrides);
+        }
+    }
+
+    #[test]
+    fn max_attempts_saturate() {
+        let settings = ScanSettings {
+            tcp_retries: u32::MAX,
+            ..Default::default()
+        };
+        assert_eq!(settings.tcp_max_attempts(), u32::MAX);
+    }
+}
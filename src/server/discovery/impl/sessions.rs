@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::r#impl::api::DiscoveryUpdatePayload,
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+/// A discovery session saved across a server restart, with its place in the daemon's queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedSessionBase {
+    pub daemon_id: Uuid,
+    pub queue_position: i32,
+    pub session: DiscoveryUpdatePayload,
+}
+
+/// ID is the session ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedSession {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: PersistedSessionBase,
+}
+
+impl StorableEntity for PersistedSession {
+    type BaseData = PersistedSessionBase;
+
+    fn table_name() -> &'static str {
+        "discovery_sessions"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: base.session.session_id,
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    daemon_id,
+                    queue_position,
+                    session,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "daemon_id",
+                "queue_position",
+                "session",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(daemon_id),
+                SqlValue::I32(queue_position),
+                SqlValue::Json(serde_json::to_value(&session)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(PersistedSession {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: PersistedSessionBase {
+                daemon_id: row.get("daemon_id"),
+                queue_position: row.get("queue_position"),
+                session: serde_json::from_value(row.get("session"))?,
+            },
+        })
+    }
+}
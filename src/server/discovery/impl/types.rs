@@ -1,11 +1,14 @@
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use serde::Serialize;
+use std::net::IpAddr;
 use strum::{Display, EnumDiscriminants, EnumIter, IntoStaticStr};
 use uuid::Uuid;
 
 use crate::server::{
     daemons::r#impl::api::DiscoveryUpdatePayload,
+    discovery::r#impl::parallel::ScanShard,
+    hosts::r#impl::dhcp::LeaseFormat,
     shared::{
         entities::Entity,
         types::metadata::{EntityMetadataProvider, HasId, TypeMetadataProvider},
@@ -35,12 +38,73 @@ pub enum DiscoveryType {
         subnet_ids: Option<Vec<Uuid>>,
         #[serde(default)]
         host_naming_fallback: HostNamingFallback,
+        /// Ports, timeouts and limits to scan with; the standard ports if unset
+        #[serde(default)]
+        scan_profile_id: Option<Uuid>,
+        /// More daemons in the network that share the scan with the discovery's own, each
+        /// taking some of the subnets or a range of each
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        parallel_daemon_ids: Vec<Uuid>,
+        /// The range of each subnet this session scans, when the server split the scan by address
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shard: Option<ScanShard>,
     },
     Docker {
         host_id: Uuid,
         #[serde(default)]
         host_naming_fallback: HostNamingFallback,
     },
+    // UPnP devices answering an SSDP search on the daemon's subnets
+    Ssdp {
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    // Layer-2 sweep finding hosts by ARP reply alone; None = all interfaced subnets
+    ArpSweep {
+        subnet_ids: Option<Vec<Uuid>>,
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    // Peers of the WireGuard, Tailscale and OpenVPN tunnels on the daemon's host
+    VpnTunnels {
+        host_id: Uuid,
+    },
+    // Devices listed by the Tailscale or Headscale API set up in the network's settings
+    Tailnet,
+    // Hosts read from an uploaded nmap XML report; runs on the server, not on daemons
+    NmapImport {
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
+    // Guests listed by the Proxmox VE API of a Proxmox service; runs on the server, not on daemons
+    Proxmox {
+        service_id: Uuid,
+    },
+    // VMs listed by the vSphere REST API of a vCenter or ESXi service; runs on the server
+    Vmware {
+        service_id: Uuid,
+    },
+    // DHCP leases and ARP entries read from an OPNsense or pfSense service; runs on the server
+    Firewall {
+        service_id: Uuid,
+    },
+    // Subnets and address reservations from phpIPAM or a CSV address plan; runs on the server
+    IpamImport,
+    // Leases of a DHCP server on the daemon's host, read from the path or the format's usual
+    // location; lease files uploaded to the server are attributed to it too
+    DhcpLeases {
+        format: LeaseFormat,
+        path: Option<String>,
+    },
+    // One known host scanned again on request; never stored as a discovery
+    #[strum(disabled)]
+    HostRescan {
+        host_id: Uuid,
+        subnet_id: Uuid,
+        ip: IpAddr,
+        #[serde(default)]
+        host_naming_fallback: HostNamingFallback,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Copy, Deserialize, Eq, PartialEq, Hash, Display, Default)]
@@ -66,6 +130,20 @@ pub enum RunType {
     },
 }
 
+impl DiscoveryType {
+    /// Run by the server itself, never sent to a daemon
+    pub fn runs_on_server(&self) -> bool {
+        matches!(
+            self,
+            DiscoveryType::NmapImport { .. }
+                | DiscoveryType::Proxmox { .. }
+                | DiscoveryType::Vmware { .. }
+                | DiscoveryType::Firewall { .. }
+                | DiscoveryType::IpamImport
+        )
+    }
+}
+
 impl HasId for DiscoveryType {
     fn id(&self) -> &'static str {
         self.into()
@@ -97,6 +175,39 @@ impl TypeMetadataProvider for DiscoveryType {
             DiscoveryType::SelfReport { .. } => {
                 "The daemon reports its own host configuration and network details"
             }
+            DiscoveryType::ArpSweep { .. } => {
+                "Find every host that answers ARP on the daemon's subnets, including devices with no open ports"
+            }
+            DiscoveryType::HostRescan { .. } => {
+                "Scan a single known host again and update its ports and services"
+            }
+            DiscoveryType::NmapImport { .. } => {
+                "Import hosts, open ports and banners from an nmap XML report"
+            }
+            DiscoveryType::Proxmox { .. } => {
+                "Import VMs and containers from the Proxmox VE API and link them to their hypervisor"
+            }
+            DiscoveryType::Ssdp { .. } => {
+                "Find UPnP devices such as routers, TVs and NAS boxes that advertise themselves over SSDP"
+            }
+            DiscoveryType::Tailnet => {
+                "Pull devices from the Tailscale or Headscale API and link them to hosts on the physical network"
+            }
+            DiscoveryType::Vmware { .. } => {
+                "Import VMs and their port groups from vCenter and link them to their ESXi hosts"
+            }
+            DiscoveryType::Firewall { .. } => {
+                "Import DHCP leases, ARP entries and VLANs from an OPNsense or pfSense firewall"
+            }
+            DiscoveryType::IpamImport => {
+                "Import planned subnets and address reservations from phpIPAM or a CSV address plan"
+            }
+            DiscoveryType::DhcpLeases { .. } => {
+                "Read hostnames, MACs and lease expiry from ISC dhcpd, Kea, dnsmasq or Windows DHCP leases"
+            }
+            DiscoveryType::VpnTunnels { .. } => {
+                "Read WireGuard, Tailscale and OpenVPN tunnels on the daemon's host and add their peers"
+            }
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::server::discovery::r#impl::types::RunType;
+use crate::server::discovery::r#impl::types::{DiscoveryType, RunType};
 use crate::server::shared::services::traits::CrudService;
 use crate::server::shared::storage::filter::EntityFilter;
 use crate::server::shared::storage::generic::GenericPostgresStorage;
@@ -12,19 +12,27 @@ use tokio::sync::{RwLock, broadcast};
 use tokio_cron_scheduler::{Job, JobScheduler};
 use uuid::Uuid;
 
-use crate::server::discovery::r#impl::base::Discovery;
+use crate::server::discovery::r#impl::{
+    base::Discovery,
+    parallel::{address_count, assign, merge_progress},
+    sessions::{PersistedSession, PersistedSessionBase},
+};
 use crate::{
     daemon::discovery::types::base::DiscoveryPhase,
-    server::daemons::{
-        r#impl::api::{DaemonDiscoveryRequest, DiscoveryUpdatePayload},
-        service::DaemonService,
+    server::{
+        daemons::{r#impl::api::DiscoveryUpdatePayload, service::DaemonService},
+        network_settings::service::NetworkSettingsService,
+        subnets::r#impl::base::Subnet,
     },
 };
 
 /// Server-side session management for discovery
 pub struct DiscoveryService {
     discovery_storage: Arc<GenericPostgresStorage<Discovery>>,
+    session_storage: Arc<GenericPostgresStorage<PersistedSession>>,
+    subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
     daemon_service: Arc<DaemonService>,
+    network_settings_service: Arc<NetworkSettingsService>,
     sessions: RwLock<HashMap<Uuid, DiscoveryUpdatePayload>>, // session_id -> session state mapping
     daemon_sessions: RwLock<HashMap<Uuid, Vec<Uuid>>>,       // daemon_id -> session_id mapping
     update_tx: broadcast::Sender<DiscoveryUpdatePayload>,
@@ -41,14 +49,20 @@ impl CrudService<Discovery> for DiscoveryService {
 impl DiscoveryService {
     pub async fn new(
         discovery_storage: Arc<GenericPostgresStorage<Discovery>>,
+        session_storage: Arc<GenericPostgresStorage<PersistedSession>>,
+        subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
         daemon_service: Arc<DaemonService>,
+        network_settings_service: Arc<NetworkSettingsService>,
     ) -> Result<Arc<Self>> {
         let (tx, _rx) = broadcast::channel(100); // Buffer 100 messages
         let scheduler = JobScheduler::new().await?;
 
         Ok(Arc::new(Self {
             discovery_storage,
+            session_storage,
+            subnet_storage,
             daemon_service,
+            network_settings_service,
             sessions: RwLock::new(HashMap::new()),
             daemon_sessions: RwLock::new(HashMap::new()),
             update_tx: tx,
@@ -56,9 +70,115 @@ impl DiscoveryService {
         }))
     }
 
+    /// Delete finished discovery runs older than their network's retention period. Returns the
+    /// number of runs deleted.
+    pub async fn prune_expired_history(&self) -> Result<usize> {
+        let mut deleted = 0;
+
+        for settings in self
+            .network_settings_service
+            .get_all(EntityFilter::unfiltered())
+            .await?
+        {
+            let Some(days) = settings.base.discovery_retention_days else {
+                continue;
+            };
+            let cutoff = Utc::now() - chrono::Duration::days(days.into());
+
+            let filter = EntityFilter::unfiltered().network_ids(&[settings.base.network_id]);
+            for discovery in self.discovery_storage.get_all(filter).await? {
+                if let RunType::Historical { results } = &discovery.base.run_type
+                    && results
+                        .finished_at
+                        .is_some_and(|finished| finished < cutoff)
+                {
+                    self.discovery_storage.delete(&discovery.id).await?;
+                    deleted += 1;
+                }
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Finished runs of network scans in a network that completed without errors
+    pub async fn completed_network_scans(
+        &self,
+        network_id: &Uuid,
+    ) -> Result<Vec<DiscoveryUpdatePayload>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+
+        Ok(self
+            .discovery_storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .filter_map(|discovery| match discovery.base.run_type {
+                RunType::Historical { results }
+                    if matches!(results.phase, DiscoveryPhase::Complete)
+                        && matches!(results.discovery_type, DiscoveryType::Network { .. }) =>
+                {
+                    Some(results)
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// A session that has finished, from its historical record in one of the networks
+    pub async fn finished_session(
+        &self,
+        session_id: &Uuid,
+        network_ids: &[Uuid],
+    ) -> Result<Option<DiscoveryUpdatePayload>> {
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        Ok(self
+            .discovery_storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .find_map(|discovery| match discovery.base.run_type {
+                RunType::Historical { results } if results.session_id == *session_id => {
+                    Some(results)
+                }
+                _ => None,
+            }))
+    }
+
+    /// Progress of a network scan split across daemons, merged from its running and finished
+    /// sessions in the networks
+    pub async fn group_progress(
+        &self,
+        group_id: &Uuid,
+        network_ids: &[Uuid],
+    ) -> Result<Option<DiscoveryUpdatePayload>> {
+        let mut sessions: Vec<DiscoveryUpdatePayload> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| s.group_id == Some(*group_id) && network_ids.contains(&s.network_id))
+            .cloned()
+            .collect();
+
+        // A session finishing meanwhile shows up in both
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+        for discovery in self.discovery_storage.get_all(filter).await? {
+            if let RunType::Historical { results } = discovery.base.run_type
+                && results.group_id == Some(*group_id)
+                && !sessions.iter().any(|s| s.session_id == results.session_id)
+            {
+                sessions.push(results);
+            }
+        }
+
+        Ok(merge_progress(*group_id, &sessions))
+    }
+
     /// Create a new scheduled discovery
     pub async fn create_discovery(self: &Arc<Self>, discovery: Discovery) -> Result<Discovery> {
-        let mut created_discovery = if discovery.id == Uuid::nil() {
+        let created_discovery = if discovery.id == Uuid::nil() {
             self.discovery_storage
                 .create(&Discovery::new(discovery.base))
                 .await?
@@ -66,6 +186,15 @@ impl DiscoveryService {
             self.discovery_storage.create(&discovery).await?
         };
 
+        self.activate_discovery(created_discovery).await
+    }
+
+    /// Schedule a discovery that was just stored, if it runs on a schedule. It is disabled if
+    /// it can't be scheduled.
+    pub async fn activate_discovery(
+        self: &Arc<Self>,
+        mut created_discovery: Discovery,
+    ) -> Result<Discovery> {
         // If it's a scheduled discovery, add it to the scheduler
         if matches!(created_discovery.base.run_type, RunType::Scheduled { .. })
             && let Err(e) = Self::schedule_discovery(self, &created_discovery).await
@@ -302,20 +431,140 @@ impl DiscoveryService {
             .collect()
     }
 
-    /// Create a new discovery session
+    /// Create a new discovery session, or one for each daemon of a network scan split across
+    /// daemons
     pub async fn start_session(
         &self,
         discovery: Discovery,
     ) -> Result<DiscoveryUpdatePayload, anyhow::Error> {
-        let session_id = Uuid::new_v4();
+        if let DiscoveryType::Network {
+            parallel_daemon_ids,
+            ..
+        } = &discovery.base.discovery_type
+            && !parallel_daemon_ids.is_empty()
+        {
+            return self.start_parallel_sessions(discovery).await;
+        }
 
-        let session_payload = DiscoveryUpdatePayload::new(
-            session_id,
+        self.queue_session(
             discovery.base.daemon_id,
             discovery.base.network_id,
-            discovery.base.discovery_type.clone(),
+            discovery.base.discovery_type,
+            None,
+        )
+        .await
+    }
+
+    /// Split a network scan between its daemons, by whole subnets or by address range, and
+    /// start a session on each. Returns the session of the discovery's own daemon.
+    async fn start_parallel_sessions(
+        &self,
+        discovery: Discovery,
+    ) -> Result<DiscoveryUpdatePayload> {
+        let DiscoveryType::Network {
+            subnet_ids,
+            host_naming_fallback,
+            scan_profile_id,
+            parallel_daemon_ids,
+            ..
+        } = discovery.base.discovery_type
+        else {
+            return Err(anyhow!("Only network scans can be split across daemons"));
+        };
+        let network_id = discovery.base.network_id;
+
+        let mut daemon_ids = vec![discovery.base.daemon_id];
+        for daemon_id in parallel_daemon_ids {
+            if !daemon_ids.contains(&daemon_id) {
+                daemon_ids.push(daemon_id);
+            }
+        }
+        for daemon_id in &daemon_ids {
+            self.daemon_service
+                .get_by_id(daemon_id)
+                .await?
+                .filter(|daemon| daemon.base.network_id == network_id)
+                .ok_or_else(|| anyhow!("Daemon {} isn't in the discovery's network", daemon_id))?;
+        }
+
+        let subnet_ids = subnet_ids
+            .ok_or_else(|| anyhow!("Scans split across daemons need their subnets chosen"))?;
+        let subnets: Vec<(Uuid, usize)> = self
+            .subnet_storage
+            .get_all(EntityFilter::unfiltered().network_ids(&[network_id]))
+            .await?
+            .into_iter()
+            .filter(|subnet| subnet_ids.contains(&subnet.id))
+            .map(|subnet| (subnet.id, address_count(&subnet.base.cidr)))
+            .collect();
+        if subnets.is_empty() {
+            return Err(anyhow!("None of the discovery's subnets exist"));
+        }
+
+        let group_id = Uuid::new_v4();
+        let mut own_session = None;
+
+        for assignment in assign(&daemon_ids, &subnets) {
+            let session = self
+                .queue_session(
+                    assignment.daemon_id,
+                    network_id,
+                    DiscoveryType::Network {
+                        subnet_ids: Some(assignment.subnet_ids),
+                        host_naming_fallback,
+                        scan_profile_id,
+                        parallel_daemon_ids: Vec::new(),
+                        shard: assignment.shard,
+                    },
+                    Some(group_id),
+                )
+                .await?;
+
+            if assignment.daemon_id == discovery.base.daemon_id {
+                own_session = Some(session);
+            }
+        }
+
+        tracing::info!(
+            "Split discovery {} across {} daemons as group {}",
+            discovery.id,
+            daemon_ids.len(),
+            group_id
         );
 
+        own_session
+            .ok_or_else(|| anyhow!("No session started for daemon {}", discovery.base.daemon_id))
+    }
+
+    /// Queue a session on a daemon, starting it right away if the daemon is idle
+    async fn queue_session(
+        &self,
+        daemon_id: Uuid,
+        network_id: Uuid,
+        discovery_type: DiscoveryType,
+        group_id: Option<Uuid>,
+    ) -> Result<DiscoveryUpdatePayload> {
+        let daemon = self
+            .daemon_service
+            .get_by_id(&daemon_id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find daemon {}", daemon_id))?;
+        if let Some(reason) = daemon.base.capabilities.incompatibility(&discovery_type) {
+            return Err(anyhow!(
+                "Daemon {} can't run {} discovery: {}",
+                daemon_id,
+                discovery_type,
+                reason
+            ));
+        }
+
+        let session_id = Uuid::new_v4();
+
+        let session_payload = DiscoveryUpdatePayload {
+            group_id,
+            ..DiscoveryUpdatePayload::new(session_id, daemon_id, network_id, discovery_type.clone())
+        };
+
         // Add to session map
         self.sessions
             .write()
@@ -323,35 +572,25 @@ impl DiscoveryService {
             .insert(session_id, session_payload.clone());
 
         // Check if daemon has any sessions running
-        let daemon_is_running_discovery = if let Some(daemon_sessions) = self
-            .daemon_sessions
-            .read()
-            .await
-            .get(&discovery.base.daemon_id)
-        {
-            !daemon_sessions.is_empty()
-        } else {
-            false
-        };
+        let daemon_is_running_discovery =
+            if let Some(daemon_sessions) = self.daemon_sessions.read().await.get(&daemon_id) {
+                !daemon_sessions.is_empty()
+            } else {
+                false
+            };
 
         // Add session to queue
         self.daemon_sessions
             .write()
             .await
-            .entry(discovery.base.daemon_id)
+            .entry(daemon_id)
             .or_default()
             .push(session_id);
 
         // Initiate session on daemon if none are running
         if !daemon_is_running_discovery {
             self.daemon_service
-                .send_discovery_request(
-                    &discovery.base.daemon_id,
-                    DaemonDiscoveryRequest {
-                        discovery_type: discovery.base.discovery_type,
-                        session_id,
-                    },
-                )
+                .send_discovery_request(&daemon_id, session_id, discovery_type)
                 .await?;
         }
 
@@ -360,7 +599,7 @@ impl DiscoveryService {
         tracing::info!(
             "Created discovery session {} for daemon {}",
             session_id,
-            discovery.base.daemon_id
+            daemon_id
         );
         Ok(session_payload)
     }
@@ -384,9 +623,15 @@ impl DiscoveryService {
             update.total_to_process
         );
 
-        let _ = self.update_tx.send(update.clone());
+        // Daemons report network scans without their subnets and scan profile, which resuming
+        // a paused session needs, and don't know the group of a split scan
+        *session = DiscoveryUpdatePayload {
+            discovery_type: session.discovery_type.clone(),
+            group_id: session.group_id,
+            ..update.clone()
+        };
 
-        *session = update.clone();
+        let _ = self.update_tx.send(session.clone());
 
         let is_terminal = matches!(
             session.phase,
@@ -457,13 +702,7 @@ impl DiscoveryService {
                 tracing::debug!("Starting next session");
 
                 self.daemon_service
-                    .send_discovery_request(
-                        &daemon_id,
-                        DaemonDiscoveryRequest {
-                            discovery_type,
-                            session_id,
-                        },
-                    )
+                    .send_discovery_request(&daemon_id, session_id, discovery_type)
                     .await?;
             }
         }
@@ -514,6 +753,8 @@ impl DiscoveryService {
                     started_at: session.started_at,
                     finished_at: Some(Utc::now()),
                     discovery_type: session.discovery_type,
+                    results: session.results,
+                    group_id: session.group_id,
                 };
                 let _ = self.update_tx.send(cancelled_update);
 
@@ -521,6 +762,20 @@ impl DiscoveryService {
                 Ok(())
             }
 
+            // Paused sessions aren't running anywhere, so they end here
+            DiscoveryPhase::Paused => {
+                self.update_session(DiscoveryUpdatePayload {
+                    phase: DiscoveryPhase::Cancelled,
+                    error: None,
+                    finished_at: Some(Utc::now()),
+                    ..session
+                })
+                .await?;
+
+                tracing::info!("Cancelled paused session {}", session_id);
+                Ok(())
+            }
+
             // Starting phase: wait briefly then retry
             DiscoveryPhase::Starting => Err(anyhow!(
                 "Session is starting on daemon. Please try again in a moment."
@@ -568,6 +823,45 @@ impl DiscoveryService {
         }
     }
 
+    /// Stop a running network scan so it can be resumed later. It stays at the head of its
+    /// daemon's queue while paused.
+    pub async fn pause_session(&self, session: &DiscoveryUpdatePayload) -> Result<(), Error> {
+        let daemon = self
+            .daemon_service
+            .get_by_id(&session.daemon_id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find daemon {}", session.daemon_id))?;
+
+        self.daemon_service
+            .send_discovery_pause(&daemon, session.session_id)
+            .await?;
+
+        tracing::info!(
+            "Pause request sent to daemon {} for session {}",
+            daemon.id,
+            session.session_id
+        );
+        Ok(())
+    }
+
+    /// Send a paused session to its daemon again, which carries on from its checkpoint
+    pub async fn resume_session(&self, session: &DiscoveryUpdatePayload) -> Result<(), Error> {
+        self.daemon_service
+            .send_discovery_request(
+                &session.daemon_id,
+                session.session_id,
+                session.discovery_type.clone(),
+            )
+            .await?;
+
+        tracing::info!(
+            "Resumed session {} on daemon {}",
+            session.session_id,
+            session.daemon_id
+        );
+        Ok(())
+    }
+
     /// Cleanup old completed sessions (call periodically)
     pub async fn cleanup_old_sessions(&self, max_age_hours: i64) {
         let cutoff = Utc::now() - chrono::Duration::hours(max_age_hours);
@@ -593,4 +887,137 @@ impl DiscoveryService {
             }
         }
     }
+
+    /// Save queued and running sessions so they survive a restart. Returns the number saved.
+    pub async fn persist_sessions(&self) -> Result<usize> {
+        let sessions = self.sessions.read().await;
+        let daemon_sessions = self.daemon_sessions.read().await;
+
+        // Replace whatever an earlier shutdown left behind
+        for stale in self
+            .session_storage
+            .get_all(EntityFilter::unfiltered())
+            .await?
+        {
+            self.session_storage.delete(&stale.id).await?;
+        }
+
+        let mut saved = 0;
+        for (daemon_id, queue) in daemon_sessions.iter() {
+            for (position, session_id) in queue.iter().enumerate() {
+                let Some(session) = sessions.get(session_id) else {
+                    continue;
+                };
+
+                self.session_storage
+                    .create(&PersistedSession::new(PersistedSessionBase {
+                        daemon_id: *daemon_id,
+                        queue_position: position as i32,
+                        session: session.clone(),
+                    }))
+                    .await?;
+                saved += 1;
+            }
+        }
+
+        Ok(saved)
+    }
+
+    /// Load sessions saved by `persist_sessions` back into memory, rebuilding each daemon's
+    /// queue. Call before accepting daemon updates, then `resync_sessions` once serving.
+    pub async fn restore_sessions(&self) -> Result<usize> {
+        let mut persisted = self
+            .session_storage
+            .get_all(EntityFilter::unfiltered())
+            .await?;
+        persisted.sort_by_key(|p| p.base.queue_position);
+
+        let mut sessions = self.sessions.write().await;
+        let mut daemon_sessions = self.daemon_sessions.write().await;
+
+        for p in &persisted {
+            daemon_sessions
+                .entry(p.base.daemon_id)
+                .or_default()
+                .push(p.id);
+            sessions.insert(p.id, p.base.session.clone());
+            self.session_storage.delete(&p.id).await?;
+        }
+
+        Ok(persisted.len())
+    }
+
+    /// Check the session at the head of each daemon's queue against what the daemon is actually
+    /// running. Sessions that were never started are sent again and paused ones stay paused;
+    /// sessions the daemon is no longer running are failed so the rest of the queue can proceed.
+    pub async fn resync_sessions(&self) -> Result<()> {
+        let heads: Vec<DiscoveryUpdatePayload> = {
+            let sessions = self.sessions.read().await;
+            self.daemon_sessions
+                .read()
+                .await
+                .values()
+                .filter_map(|queue| queue.first())
+                .filter_map(|session_id| sessions.get(session_id).cloned())
+                .collect()
+        };
+
+        for head in heads {
+            let Some(daemon) = self.daemon_service.get_by_id(&head.daemon_id).await? else {
+                tracing::warn!(
+                    "Daemon {} for restored session {} no longer exists",
+                    head.daemon_id,
+                    head.session_id
+                );
+                continue;
+            };
+
+            let running = match self.daemon_service.get_current_session(&daemon).await {
+                Ok(running) => running,
+                Err(e) => {
+                    // Unreachable daemons will post their own updates once they are back
+                    tracing::warn!(
+                        "Could not re-sync session {} with daemon {}: {}",
+                        head.session_id,
+                        daemon.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            if running == Some(head.session_id) {
+                tracing::info!(
+                    "Daemon {} is still running session {}",
+                    daemon.id,
+                    head.session_id
+                );
+            } else if matches!(head.phase, DiscoveryPhase::Paused) && running.is_none() {
+                tracing::info!(
+                    "Session {} on daemon {} is still paused",
+                    head.session_id,
+                    daemon.id
+                );
+            } else if matches!(head.phase, DiscoveryPhase::Pending) && running.is_none() {
+                self.daemon_service
+                    .send_discovery_request(&daemon.id, head.session_id, head.discovery_type)
+                    .await?;
+            } else {
+                tracing::warn!(
+                    "Daemon {} is no longer running session {}, marking it failed",
+                    daemon.id,
+                    head.session_id
+                );
+                self.update_session(DiscoveryUpdatePayload {
+                    phase: DiscoveryPhase::Failed,
+                    error: Some("Session was interrupted by a server restart".to_string()),
+                    finished_at: Some(Utc::now()),
+                    ..head
+                })
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,108 @@
+use crate::server::{
+    auth::middleware::AuthenticatedEntity,
+    config::AppState,
+    exports::r#impl::{
+        ansible::AnsibleInventory,
+        api::{ExportQuery, PrometheusSdQuery},
+        prometheus::{PrometheusTargetFilter, PrometheusTargetGroup},
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+    routing::get,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Exports are consumed by external tools, so they return bare JSON rather than an
+/// ApiResponse, and accept a network API key (`Authorization: Bearer <key>`) as well as a
+/// user session.
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/ansible", get(export_ansible))
+        .route("/prometheus", get(export_prometheus))
+}
+
+/// Networks to export: the requested one if the caller can see it, otherwise all of them
+pub async fn export_network_ids(
+    state: &AppState,
+    entity: &AuthenticatedEntity,
+    network_id: Option<Uuid>,
+) -> ApiResult<Vec<Uuid>> {
+    let network_ids: Vec<Uuid> = match entity {
+        AuthenticatedEntity::Daemon(network_id) => vec![*network_id],
+        AuthenticatedEntity::User(user_id) => {
+            let user_filter = EntityFilter::unfiltered().network_member(user_id);
+
+            state
+                .services
+                .network_service
+                .get_all(user_filter)
+                .await?
+                .iter()
+                .map(|n| n.id)
+                .collect()
+        }
+    };
+
+    match network_id {
+        Some(network_id) if network_ids.contains(&network_id) => Ok(vec![network_id]),
+        Some(network_id) => Err(ApiError::not_found(format!(
+            "Network '{}' not found",
+            network_id
+        ))),
+        None => Ok(network_ids),
+    }
+}
+
+/// Dynamic inventory for Ansible
+async fn export_ansible(
+    State(state): State<Arc<AppState>>,
+    entity: AuthenticatedEntity,
+    Query(query): Query<ExportQuery>,
+) -> ApiResult<Json<AnsibleInventory>> {
+    let network_ids = export_network_ids(&state, &entity, query.network_id).await?;
+
+    // Nothing to look up for a user without networks
+    if network_ids.is_empty() {
+        return Ok(Json(AnsibleInventory::default()));
+    }
+
+    let inventory = state
+        .services
+        .export_service
+        .ansible_inventory(&network_ids)
+        .await?;
+
+    Ok(Json(inventory))
+}
+
+/// Scrape targets in Prometheus HTTP service discovery format
+pub async fn export_prometheus(
+    State(state): State<Arc<AppState>>,
+    entity: AuthenticatedEntity,
+    Query(query): Query<PrometheusSdQuery>,
+) -> ApiResult<Json<Vec<PrometheusTargetGroup>>> {
+    let network_ids = export_network_ids(&state, &entity, query.network_id).await?;
+    let filter = PrometheusTargetFilter::parse(query.category.as_deref(), query.label.as_deref())
+        .map_err(|e| ApiError::bad_request(&e))?;
+
+    if network_ids.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let targets = state
+        .services
+        .export_service
+        .prometheus_targets(&network_ids, query.all_services, &filter)
+        .await?;
+
+    Ok(Json(targets))
+}
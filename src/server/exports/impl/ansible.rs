@@ -0,0 +1,49 @@
+use std::{collections::BTreeMap, net::IpAddr};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Ansible dynamic inventory, in the JSON shape expected from inventory scripts
+/// (https://docs.ansible.com/ansible/latest/dev_guide/developing_inventory.html)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AnsibleInventory {
+    #[serde(rename = "_meta")]
+    pub meta: AnsibleMeta,
+    #[serde(flatten)]
+    pub groups: BTreeMap<String, AnsibleGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AnsibleMeta {
+    pub hostvars: BTreeMap<String, AnsibleHostVars>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AnsibleGroup {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnsibleHostVars {
+    /// Address Ansible connects to; the first interface's IP
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ansible_host: Option<IpAddr>,
+    pub netvisor_id: Uuid,
+    pub netvisor_network_id: Uuid,
+    pub netvisor_name: String,
+    pub netvisor_hostname: Option<String>,
+    pub netvisor_ip_addresses: Vec<IpAddr>,
+    pub netvisor_mac_addresses: Vec<String>,
+    pub netvisor_services: Vec<AnsibleServiceVars>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnsibleServiceVars {
+    pub name: String,
+    pub definition: String,
+    pub category: String,
+    pub ports: Vec<String>,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExportQuery {
+    /// Limit the export to one network; defaults to all of the user's networks
+    pub network_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PrometheusSdQuery {
+    /// Limit the export to one network; defaults to all networks the caller can see
+    pub network_id: Option<Uuid>,
+    /// Include every service port, not just services known to serve Prometheus metrics
+    #[serde(default)]
+    pub all_services: bool,
+    /// Comma-separated service categories to include, e.g. `Monitoring,Storage`
+    pub category: Option<String>,
+    /// Comma-separated `name=value` label matchers, e.g. `host_name=nas`. The
+    /// `__meta_netvisor_` prefix may be left out.
+    pub label: Option<String>,
+}
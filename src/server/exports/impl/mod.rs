@@ -0,0 +1,3 @@
+pub mod ansible;
+pub mod api;
+pub mod prometheus;
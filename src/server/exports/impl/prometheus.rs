@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the labels NetVisor attaches to each target group
+pub const META_LABEL_PREFIX: &str = "__meta_netvisor_";
+
+/// One entry of a Prometheus HTTP service discovery response
+/// (https://prometheus.io/docs/prometheus/latest/http_sd/)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PrometheusTargetGroup {
+    /// `host:port` addresses
+    pub targets: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// Narrows target groups to service categories and label values
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrometheusTargetFilter {
+    /// Lowercased category names; empty matches every category
+    pub categories: Vec<String>,
+    /// Label names and the value each must have
+    pub labels: Vec<(String, String)>,
+}
+
+impl PrometheusTargetFilter {
+    /// Parse comma-separated categories (`Monitoring,Storage`) and `name=value` label
+    /// matchers. Label names may leave out the `__meta_netvisor_` prefix.
+    pub fn parse(categories: Option<&str>, labels: Option<&str>) -> Result<Self, String> {
+        let split = |value: Option<&str>| -> Vec<String> {
+            value
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let categories = split(categories)
+            .into_iter()
+            .map(|c| c.to_lowercase())
+            .collect();
+
+        let labels = split(labels)
+            .into_iter()
+            .map(|matcher| {
+                let (name, value) = matcher
+                    .split_once('=')
+                    .ok_or_else(|| format!("Label matcher '{}' must be name=value", matcher))?;
+                let name = name.trim();
+                let name = if name.starts_with("__") {
+                    name.to_string()
+                } else {
+                    format!("{}{}", META_LABEL_PREFIX, name)
+                };
+                Ok((name, value.trim().to_string()))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { categories, labels })
+    }
+
+    pub fn matches(&self, group: &PrometheusTargetGroup) -> bool {
+        let category = group
+            .labels
+            .get(&format!("{}service_category", META_LABEL_PREFIX))
+            .map(|c| c.to_lowercase());
+
+        let category_matches =
+            self.categories.is_empty() || category.is_some_and(|c| self.categories.contains(&c));
+
+        category_matches
+            && self
+                .labels
+                .iter()
+                .all(|(name, value)| group.labels.get(name) == Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_by_category_and_label() {
+        let group = PrometheusTargetGroup {
+            targets: vec!["10.0.0.5:9100".to_string()],
+            labels: BTreeMap::from([
+                (
+                    "__meta_netvisor_service_category".to_string(),
+                    "Monitoring".to_string(),
+                ),
+                ("__meta_netvisor_host_name".to_string(), "nas".to_string()),
+            ]),
+        };
+
+        let filter =
+            PrometheusTargetFilter::parse(Some("monitoring, storage"), Some("host_name=nas"))
+                .unwrap();
+        assert!(filter.matches(&group));
+
+        let filter = PrometheusTargetFilter::parse(Some("Storage"), None).unwrap();
+        assert!(!filter.matches(&group));
+
+        let filter =
+            PrometheusTargetFilter::parse(None, Some("__meta_netvisor_host_name=router")).unwrap();
+        assert!(!filter.matches(&group));
+
+        assert!(PrometheusTargetFilter::parse(None, Some("host_name")).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod handlers;
+pub mod r#impl;
+pub mod service;
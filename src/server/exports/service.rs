@@ -0,0 +1,328 @@
+use crate::server::{
+    exports::r#impl::{
+        ansible::{
+            AnsibleGroup, AnsibleHostVars, AnsibleInventory, AnsibleMeta, AnsibleServiceVars,
+        },
+        prometheus::{PrometheusTargetFilter, PrometheusTargetGroup},
+    },
+    groups::{r#impl::types::GroupType, service::GroupService},
+    hosts::{
+        r#impl::{base::Host, ports::TransportProtocol},
+        service::HostService,
+    },
+    networks::service::NetworkService,
+    services::{
+        r#impl::{base::Service, definitions::ServiceDefinition},
+        service::ServiceService,
+    },
+    shared::{
+        services::traits::CrudService, storage::filter::EntityFilter, types::metadata::HasId,
+    },
+    subnets::service::SubnetService,
+};
+use anyhow::Result;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    net::SocketAddr,
+    sync::Arc,
+};
+use uuid::Uuid;
+
+pub struct ExportService {
+    network_service: Arc<NetworkService>,
+    host_service: Arc<HostService>,
+    subnet_service: Arc<SubnetService>,
+    service_service: Arc<ServiceService>,
+    group_service: Arc<GroupService>,
+}
+
+impl ExportService {
+    pub fn new(
+        network_service: Arc<NetworkService>,
+        host_service: Arc<HostService>,
+        subnet_service: Arc<SubnetService>,
+        service_service: Arc<ServiceService>,
+        group_service: Arc<GroupService>,
+    ) -> Self {
+        Self {
+            network_service,
+            host_service,
+            subnet_service,
+            service_service,
+            group_service,
+        }
+    }
+
+    /// Hosts grouped by network, subnet, service category, service definition and NetVisor group
+    pub async fn ansible_inventory(&self, network_ids: &[Uuid]) -> Result<AnsibleInventory> {
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        let networks = self
+            .network_service
+            .get_all(EntityFilter::unfiltered().entity_ids(network_ids))
+            .await?;
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter.clone()).await?;
+        let groups = self.group_service.get_all(filter).await?;
+
+        let inventory_names = Self::inventory_names(&hosts);
+        let services_by_host: HashMap<Uuid, Vec<&Service>> =
+            services.iter().fold(HashMap::new(), |mut acc, service| {
+                acc.entry(service.base.host_id).or_default().push(service);
+                acc
+            });
+
+        let mut inventory = AnsibleInventory {
+            meta: AnsibleMeta::default(),
+            groups: BTreeMap::new(),
+        };
+        let mut add_to_group = |group: String, host_name: &str| {
+            let hosts = &mut inventory.groups.entry(group).or_default().hosts;
+            if !hosts.iter().any(|h| h == host_name) {
+                hosts.push(host_name.to_string());
+            }
+        };
+
+        for host in &hosts {
+            let name = &inventory_names[&host.id];
+            let host_services = services_by_host.get(&host.id).cloned().unwrap_or_default();
+
+            if let Some(network) = networks.iter().find(|n| n.id == host.base.network_id) {
+                add_to_group(group_name("network", &network.base.name), name);
+            }
+
+            for interface in &host.base.interfaces {
+                if let Some(subnet) = subnets.iter().find(|s| s.id == interface.base.subnet_id) {
+                    add_to_group(group_name("subnet", &subnet.base.name), name);
+                }
+            }
+
+            for service in &host_services {
+                let definition = &service.base.service_definition;
+                add_to_group(
+                    group_name("category", &definition.category().to_string()),
+                    name,
+                );
+                add_to_group(group_name("service", definition.id()), name);
+            }
+        }
+
+        // NetVisor groups link service bindings, so a host is a member if any of its services is
+        for group in &groups {
+            let (GroupType::RequestPath { service_bindings }
+            | GroupType::HubAndSpoke { service_bindings }) = &group.base.group_type;
+            let bindings: HashSet<&Uuid> = service_bindings.iter().collect();
+
+            for service in &services {
+                if service
+                    .base
+                    .bindings
+                    .iter()
+                    .any(|b| bindings.contains(&b.id()))
+                    && let Some(name) = inventory_names.get(&service.base.host_id)
+                {
+                    add_to_group(group_name("group", &group.base.name), name);
+                }
+            }
+        }
+
+        for host in &hosts {
+            let host_services = services_by_host.get(&host.id).cloned().unwrap_or_default();
+            inventory.meta.hostvars.insert(
+                inventory_names[&host.id].clone(),
+                Self::host_vars(host, &host_services),
+            );
+        }
+
+        let children = inventory.groups.keys().cloned().collect();
+        inventory.groups.insert(
+            "all".to_string(),
+            AnsibleGroup {
+                hosts: Vec::new(),
+                children,
+            },
+        );
+
+        Ok(inventory)
+    }
+
+    /// Prometheus HTTP SD target groups, one per service. By default only services known to
+    /// serve metrics are listed, with their metrics path set.
+    pub async fn prometheus_targets(
+        &self,
+        network_ids: &[Uuid],
+        all_services: bool,
+        filter: &PrometheusTargetFilter,
+    ) -> Result<Vec<PrometheusTargetGroup>> {
+        let entity_filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        let hosts = self.host_service.get_all(entity_filter.clone()).await?;
+        let services = self.service_service.get_all(entity_filter).await?;
+
+        let hosts_by_id: HashMap<Uuid, &Host> = hosts.iter().map(|h| (h.id, h)).collect();
+        let mut groups = Vec::new();
+
+        for service in &services {
+            let definition = &service.base.service_definition;
+            let metrics_path = definition.metrics_path();
+
+            if metrics_path.is_none() && !all_services {
+                continue;
+            }
+
+            let Some(host) = hosts_by_id.get(&service.base.host_id) else {
+                continue;
+            };
+
+            let mut targets: Vec<String> = Vec::new();
+            for binding in &service.base.bindings {
+                let Some(port) = binding.port_id().and_then(|id| host.get_port(&id)) else {
+                    continue;
+                };
+
+                if port.base.protocol() != TransportProtocol::Tcp {
+                    continue;
+                }
+
+                // Bindings without an interface listen on all of the host's interfaces
+                let interfaces = match binding.interface_id() {
+                    Some(_) => host
+                        .get_interface(&binding.interface_id())
+                        .into_iter()
+                        .collect(),
+                    None => host.base.interfaces.iter().collect::<Vec<_>>(),
+                };
+
+                for interface in interfaces {
+                    let target =
+                        SocketAddr::new(interface.base.ip_address, port.base.number()).to_string();
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                    }
+                }
+            }
+
+            if targets.is_empty() {
+                continue;
+            }
+
+            let mut labels = BTreeMap::from([
+                (
+                    "__meta_netvisor_network_id".to_string(),
+                    service.base.network_id.to_string(),
+                ),
+                ("__meta_netvisor_host_id".to_string(), host.id.to_string()),
+                (
+                    "__meta_netvisor_host_name".to_string(),
+                    host.base.name.clone(),
+                ),
+                (
+                    "__meta_netvisor_service_id".to_string(),
+                    service.id.to_string(),
+                ),
+                (
+                    "__meta_netvisor_service_name".to_string(),
+                    service.base.name.clone(),
+                ),
+                (
+                    "__meta_netvisor_service_definition".to_string(),
+                    definition.id().to_string(),
+                ),
+                (
+                    "__meta_netvisor_service_category".to_string(),
+                    definition.category().to_string(),
+                ),
+            ]);
+
+            if let Some(hostname) = &host.base.hostname {
+                labels.insert("__meta_netvisor_hostname".to_string(), hostname.clone());
+            }
+
+            if let Some(metrics_path) = metrics_path {
+                labels.insert("__metrics_path__".to_string(), metrics_path.to_string());
+            }
+
+            let group = PrometheusTargetGroup { targets, labels };
+            if filter.matches(&group) {
+                groups.push(group);
+            }
+        }
+
+        Ok(groups)
+    }
+
+    fn host_vars(host: &Host, services: &[&Service]) -> AnsibleHostVars {
+        AnsibleHostVars {
+            ansible_host: host.base.interfaces.first().map(|i| i.base.ip_address),
+            netvisor_id: host.id,
+            netvisor_network_id: host.base.network_id,
+            netvisor_name: host.base.name.clone(),
+            netvisor_hostname: host.base.hostname.clone(),
+            netvisor_ip_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .map(|i| i.base.ip_address)
+                .collect(),
+            netvisor_mac_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .filter_map(|i| i.base.mac_address.map(|m| m.to_string()))
+                .collect(),
+            netvisor_services: services
+                .iter()
+                .map(|service| AnsibleServiceVars {
+                    name: service.base.name.clone(),
+                    definition: service.base.service_definition.id().to_string(),
+                    category: service.base.service_definition.category().to_string(),
+                    ports: service
+                        .base
+                        .bindings
+                        .iter()
+                        .filter_map(|b| b.port_id())
+                        .filter_map(|port_id| host.get_port(&port_id))
+                        .map(|port| port.base.to_string())
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Unique inventory hostname per host: its hostname if known, otherwise its name
+    fn inventory_names(hosts: &[Host]) -> HashMap<Uuid, String> {
+        let mut names = HashMap::new();
+        let mut taken = HashSet::new();
+
+        for host in hosts {
+            let base = host
+                .base
+                .hostname
+                .clone()
+                .unwrap_or_else(|| host.base.name.clone());
+
+            let name = if taken.contains(&base) {
+                format!("{}-{}", base, &host.id.to_string()[..8])
+            } else {
+                base
+            };
+
+            taken.insert(name.clone());
+            names.insert(host.id, name);
+        }
+
+        names
+    }
+}
+
+/// Ansible group names may only contain letters, digits and underscores
+fn group_name(prefix: &str, name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    format!("{}_{}", prefix, name)
+}
@@ -0,0 +1,111 @@
+use crate::server::{
+    auth::middleware::AuthenticatedEntity,
+    config::AppState,
+    grafana::r#impl::{
+        api::{
+            GrafanaMetricOption, GrafanaMetricsRequest, GrafanaQueryRequest, GrafanaQueryResult,
+        },
+        metrics::GrafanaMetric,
+    },
+    shared::{services::traits::CrudService, storage::filter::EntityFilter, types::api::ApiResult},
+};
+use axum::{
+    Router,
+    extract::State,
+    response::Json,
+    routing::{get, post},
+};
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+use uuid::Uuid;
+
+/// Endpoints follow the Grafana JSON datasource protocol and return bare JSON. Grafana can
+/// authenticate with a network API key sent as `Authorization: Bearer <key>`. The
+/// `topology.*` metrics return tables shaped for the node graph panel; the others return
+/// time series.
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(test_connection))
+        .route("/search", post(search_metrics))
+        .route("/metrics", post(list_metrics))
+        .route("/query", post(query))
+}
+
+/// Networks visible to a user, or the network an API key belongs to
+async fn entity_network_ids(
+    state: &AppState,
+    entity: &AuthenticatedEntity,
+) -> ApiResult<Vec<Uuid>> {
+    match entity {
+        AuthenticatedEntity::Daemon(network_id) => Ok(vec![*network_id]),
+        AuthenticatedEntity::User(user_id) => {
+            let user_filter = EntityFilter::unfiltered().network_member(user_id);
+
+            Ok(state
+                .services
+                .network_service
+                .get_all(user_filter)
+                .await?
+                .iter()
+                .map(|n| n.id)
+                .collect())
+        }
+    }
+}
+
+async fn test_connection(_entity: AuthenticatedEntity) -> Json<()> {
+    Json(())
+}
+
+/// Metric names matching the partial name typed in the query editor (legacy `/search`)
+async fn search_metrics(
+    _entity: AuthenticatedEntity,
+    Json(request): Json<GrafanaMetricsRequest>,
+) -> Json<Vec<&'static str>> {
+    let search = request.metric.unwrap_or_default();
+
+    Json(
+        GrafanaMetric::iter()
+            .map(|m| m.name())
+            .filter(|name| name.contains(&search))
+            .collect(),
+    )
+}
+
+async fn list_metrics(
+    _entity: AuthenticatedEntity,
+    Json(request): Json<GrafanaMetricsRequest>,
+) -> Json<Vec<GrafanaMetricOption>> {
+    let search = request.metric.unwrap_or_default();
+
+    Json(
+        GrafanaMetric::iter()
+            .filter(|m| m.name().contains(&search))
+            .map(|m| GrafanaMetricOption {
+                label: format!("{} - {}", m.name(), m.description()),
+                value: m.name().to_string(),
+            })
+            .collect(),
+    )
+}
+
+async fn query(
+    State(state): State<Arc<AppState>>,
+    entity: AuthenticatedEntity,
+    Json(request): Json<GrafanaQueryRequest>,
+) -> ApiResult<Json<Vec<GrafanaQueryResult>>> {
+    let network_ids = entity_network_ids(&state, &entity).await?;
+
+    // Nothing to look up for a user without networks
+    if network_ids.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+
+    let series = state
+        .services
+        .grafana_service
+        .query(&network_ids, &request)
+        .await?;
+
+    Ok(Json(series))
+}
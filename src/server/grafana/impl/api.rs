@@ -0,0 +1,93 @@
+//! Request and response shapes of the Grafana JSON datasource plugin
+//! (https://grafana.com/grafana/plugins/simpod-json-datasource/)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaMetricsRequest {
+    /// Partial metric name typed by the user
+    #[serde(default)]
+    pub metric: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaMetricOption {
+    pub label: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaQueryRequest {
+    pub range: GrafanaTimeRange,
+    pub targets: Vec<GrafanaTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaTimeRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaTarget {
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub ref_id: Option<String>,
+    #[serde(default)]
+    pub hide: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaTimeSeries {
+    pub target: String,
+    /// (value, unix milliseconds) pairs, oldest first
+    pub datapoints: Vec<(f64, i64)>,
+}
+
+/// Rows and typed columns. Grafana turns a table into a data frame, which is how the node
+/// graph panel receives nodes and edges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaTable {
+    /// Always `table`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub columns: Vec<GrafanaColumn>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl GrafanaTable {
+    pub fn new(columns: &[(&str, &str)]) -> Self {
+        Self {
+            kind: "table".to_string(),
+            columns: columns
+                .iter()
+                .map(|(text, kind)| GrafanaColumn {
+                    text: text.to_string(),
+                    kind: kind.to_string(),
+                })
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaColumn {
+    pub text: String,
+    /// `string`, `number` or `time`
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GrafanaQueryResult {
+    TimeSeries(GrafanaTimeSeries),
+    Table(GrafanaTable),
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+
+/// Metrics exposed to Grafana, addressed by their string name in query targets
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, EnumString, IntoStaticStr,
+)]
+pub enum GrafanaMetric {
+    #[strum(serialize = "hosts.count")]
+    HostCount,
+    #[strum(serialize = "services.count")]
+    ServiceCount,
+    /// One series per service definition
+    #[strum(serialize = "services.by_definition")]
+    ServicesByDefinition,
+    /// One series per service category
+    #[strum(serialize = "services.by_category")]
+    ServicesByCategory,
+    #[strum(serialize = "daemons.online")]
+    DaemonsOnline,
+    /// Fraction of daemons that have sent a heartbeat recently
+    #[strum(serialize = "daemons.availability")]
+    DaemonAvailability,
+    #[strum(serialize = "discovery.duration_seconds")]
+    DiscoveryDuration,
+    #[strum(serialize = "discovery.processed")]
+    DiscoveryProcessed,
+    /// 1 or 0 per health check result, one series per service
+    #[strum(serialize = "health.up")]
+    HealthUp,
+    /// One series per service
+    #[strum(serialize = "health.latency_ms")]
+    HealthLatency,
+    /// Hosts and subnets as a node graph panel table
+    #[strum(serialize = "topology.nodes")]
+    TopologyNodes,
+    /// Host interfaces as node graph edges between hosts and subnets
+    #[strum(serialize = "topology.edges")]
+    TopologyEdges,
+}
+
+impl GrafanaMetric {
+    pub fn name(&self) -> &'static str {
+        self.into()
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            GrafanaMetric::HostCount => "Number of hosts over time",
+            GrafanaMetric::ServiceCount => "Number of services over time",
+            GrafanaMetric::ServicesByDefinition => "Current number of services per definition",
+            GrafanaMetric::ServicesByCategory => "Current number of services per category",
+            GrafanaMetric::DaemonsOnline => "Daemons with a recent heartbeat",
+            GrafanaMetric::DaemonAvailability => "Fraction of daemons with a recent heartbeat",
+            GrafanaMetric::DiscoveryDuration => "Duration of each finished discovery run",
+            GrafanaMetric::DiscoveryProcessed => "Targets processed by each finished discovery run",
+            GrafanaMetric::HealthUp => "Health check results per service, 1 when up",
+            GrafanaMetric::HealthLatency => "Health check latency per service",
+            GrafanaMetric::TopologyNodes => "Hosts and subnets, for the node graph panel",
+            GrafanaMetric::TopologyEdges => "Host interfaces, for the node graph panel",
+        }
+    }
+
+    /// Whether the metric reads health check results
+    pub fn needs_health(&self) -> bool {
+        matches!(
+            self,
+            GrafanaMetric::HealthUp | GrafanaMetric::HealthLatency | GrafanaMetric::TopologyNodes
+        )
+    }
+}
@@ -0,0 +1,391 @@
+use crate::server::{
+    daemons::{r#impl::base::Daemon, service::DaemonService},
+    discovery::{
+        r#impl::{base::Discovery, types::RunType},
+        service::DiscoveryService,
+    },
+    grafana::r#impl::{
+        api::{
+            GrafanaQueryRequest, GrafanaQueryResult, GrafanaTable, GrafanaTimeRange,
+            GrafanaTimeSeries,
+        },
+        metrics::GrafanaMetric,
+    },
+    hosts::{r#impl::base::Host, service::HostService},
+    monitoring::{r#impl::base::HealthCheckResult, service::MonitoringService},
+    services::{
+        r#impl::{base::Service, definitions::ServiceDefinition},
+        service::ServiceService,
+    },
+    shared::{
+        services::traits::CrudService, storage::filter::EntityFilter, types::metadata::HasId,
+    },
+    subnets::{r#impl::base::Subnet, service::SubnetService},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
+use uuid::Uuid;
+
+pub struct GrafanaService {
+    host_service: Arc<HostService>,
+    subnet_service: Arc<SubnetService>,
+    service_service: Arc<ServiceService>,
+    daemon_service: Arc<DaemonService>,
+    discovery_service: Arc<DiscoveryService>,
+    monitoring_service: Arc<MonitoringService>,
+}
+
+/// Entities a query is evaluated against, loaded once per request
+struct MetricData {
+    hosts: Vec<Host>,
+    subnets: Vec<Subnet>,
+    services: Vec<Service>,
+    daemons: Vec<Daemon>,
+    discoveries: Vec<Discovery>,
+    /// Only loaded when a health or topology metric is queried
+    health_results: Vec<HealthCheckResult>,
+}
+
+impl GrafanaService {
+    pub fn new(
+        host_service: Arc<HostService>,
+        subnet_service: Arc<SubnetService>,
+        service_service: Arc<ServiceService>,
+        daemon_service: Arc<DaemonService>,
+        discovery_service: Arc<DiscoveryService>,
+        monitoring_service: Arc<MonitoringService>,
+    ) -> Self {
+        Self {
+            host_service,
+            subnet_service,
+            service_service,
+            daemon_service,
+            discovery_service,
+            monitoring_service,
+        }
+    }
+
+    pub async fn query(
+        &self,
+        network_ids: &[Uuid],
+        request: &GrafanaQueryRequest,
+    ) -> Result<Vec<GrafanaQueryResult>> {
+        let metrics: Vec<GrafanaMetric> = request
+            .targets
+            .iter()
+            .filter(|t| !t.hide)
+            .filter_map(|t| t.target.as_deref())
+            .filter_map(|t| GrafanaMetric::from_str(t).ok())
+            .collect();
+
+        if metrics.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+        let services = self.service_service.get_all(filter.clone()).await?;
+
+        let health_results = if metrics.iter().any(|m| m.needs_health()) {
+            let service_ids: Vec<_> = services.iter().map(|s| s.id).collect();
+            self.monitoring_service
+                .results_between(&service_ids, request.range.from, request.range.to)
+                .await?
+        } else {
+            Vec::new()
+        };
+
+        let data = MetricData {
+            hosts: self.host_service.get_all(filter.clone()).await?,
+            subnets: self.subnet_service.get_all(filter.clone()).await?,
+            services,
+            daemons: self.daemon_service.get_all(filter.clone()).await?,
+            discoveries: self.discovery_service.get_all(filter).await?,
+            health_results,
+        };
+
+        Ok(metrics
+            .into_iter()
+            .flat_map(|metric| match metric {
+                GrafanaMetric::TopologyNodes => {
+                    vec![GrafanaQueryResult::Table(topology_nodes(&data))]
+                }
+                GrafanaMetric::TopologyEdges => {
+                    vec![GrafanaQueryResult::Table(topology_edges(&data))]
+                }
+                _ => Self::evaluate(metric, &data, &request.range)
+                    .into_iter()
+                    .map(GrafanaQueryResult::TimeSeries)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    fn evaluate(
+        metric: GrafanaMetric,
+        data: &MetricData,
+        range: &GrafanaTimeRange,
+    ) -> Vec<GrafanaTimeSeries> {
+        let now = range.to.min(Utc::now());
+
+        match metric {
+            GrafanaMetric::HostCount => vec![cumulative_count(
+                metric.name(),
+                data.hosts.iter().map(|h| h.created_at).collect(),
+                range,
+            )],
+            GrafanaMetric::ServiceCount => vec![cumulative_count(
+                metric.name(),
+                data.services.iter().map(|s| s.created_at).collect(),
+                range,
+            )],
+            GrafanaMetric::ServicesByDefinition => grouped_count(&data.services, now, |s| {
+                format!("services.definition.{}", s.base.service_definition.id())
+            }),
+            GrafanaMetric::ServicesByCategory => grouped_count(&data.services, now, |s| {
+                format!("services.category.{}", s.base.service_definition.category())
+            }),
+            GrafanaMetric::DaemonsOnline => {
+                let online = data.daemons.iter().filter(|d| d.is_online()).count();
+                vec![single_point(metric.name(), online as f64, now)]
+            }
+            GrafanaMetric::DaemonAvailability => {
+                let online = data.daemons.iter().filter(|d| d.is_online()).count();
+                let availability = if data.daemons.is_empty() {
+                    0.0
+                } else {
+                    online as f64 / data.daemons.len() as f64
+                };
+                vec![single_point(metric.name(), availability, now)]
+            }
+            GrafanaMetric::DiscoveryDuration | GrafanaMetric::DiscoveryProcessed => {
+                let mut datapoints: Vec<(f64, i64)> = data
+                    .discoveries
+                    .iter()
+                    .filter_map(|d| match &d.base.run_type {
+                        RunType::Historical { results } => Some(results),
+                        _ => None,
+                    })
+                    .filter_map(|results| {
+                        let finished_at = results.finished_at?;
+                        if finished_at < range.from || finished_at > range.to {
+                            return None;
+                        }
+
+                        let value = if metric == GrafanaMetric::DiscoveryDuration {
+                            let started_at = results.started_at?;
+                            (finished_at - started_at).num_milliseconds() as f64 / 1000.0
+                        } else {
+                            results.processed as f64
+                        };
+
+                        Some((value, finished_at.timestamp_millis()))
+                    })
+                    .collect();
+                datapoints.sort_by_key(|(_, timestamp)| *timestamp);
+
+                vec![GrafanaTimeSeries {
+                    target: metric.name().to_string(),
+                    datapoints,
+                }]
+            }
+            GrafanaMetric::HealthUp | GrafanaMetric::HealthLatency => {
+                let mut series: BTreeMap<String, Vec<(f64, i64)>> = BTreeMap::new();
+
+                for result in &data.health_results {
+                    let value = match metric {
+                        GrafanaMetric::HealthUp => Some(if result.base.up { 1.0 } else { 0.0 }),
+                        _ => result.base.latency_ms.map(f64::from),
+                    };
+                    if let Some(value) = value {
+                        series
+                            .entry(service_target(metric, data, &result.base.service_id))
+                            .or_default()
+                            .push((value, result.base.checked_at.timestamp_millis()));
+                    }
+                }
+
+                series
+                    .into_iter()
+                    .map(|(target, datapoints)| GrafanaTimeSeries { target, datapoints })
+                    .collect()
+            }
+            // Tables, answered by query()
+            GrafanaMetric::TopologyNodes | GrafanaMetric::TopologyEdges => Vec::new(),
+        }
+    }
+}
+
+/// `health.up.<host>/<service>`, falling back to the service id for services since deleted
+fn service_target(metric: GrafanaMetric, data: &MetricData, service_id: &Uuid) -> String {
+    let name = data
+        .services
+        .iter()
+        .find(|s| s.id == *service_id)
+        .map(
+            |service| match data.hosts.iter().find(|h| h.id == service.base.host_id) {
+                Some(host) => format!("{}/{}", host.base.name, service.base.name),
+                None => service.base.name.clone(),
+            },
+        )
+        .unwrap_or_else(|| service_id.to_string());
+
+    format!("{}.{}", metric.name(), name)
+}
+
+/// Node graph nodes: subnets, and hosts with arcs for the share of their services whose
+/// latest health check was up, down or that have none
+fn topology_nodes(data: &MetricData) -> GrafanaTable {
+    let mut table = GrafanaTable::new(&[
+        ("id", "string"),
+        ("title", "string"),
+        ("subtitle", "string"),
+        ("mainstat", "string"),
+        ("arc__up", "number"),
+        ("arc__down", "number"),
+        ("arc__unknown", "number"),
+        ("detail__kind", "string"),
+    ]);
+
+    // Results are oldest first, so the last one seen per service wins
+    let latest_up: HashMap<Uuid, bool> = data
+        .health_results
+        .iter()
+        .map(|r| (r.base.service_id, r.base.up))
+        .collect();
+
+    for subnet in &data.subnets {
+        let host_count = data
+            .hosts
+            .iter()
+            .filter(|h| {
+                h.base
+                    .interfaces
+                    .iter()
+                    .any(|i| i.base.subnet_id == subnet.id)
+            })
+            .count();
+
+        table.rows.push(vec![
+            json!(subnet.id),
+            json!(subnet.base.name),
+            json!(subnet.base.cidr.to_string()),
+            json!(format!("{} hosts", host_count)),
+            json!(0.0),
+            json!(0.0),
+            json!(1.0),
+            json!("subnet"),
+        ]);
+    }
+
+    for host in &data.hosts {
+        let statuses: Vec<Option<bool>> = data
+            .services
+            .iter()
+            .filter(|s| s.base.host_id == host.id)
+            .map(|s| latest_up.get(&s.id).copied())
+            .collect();
+
+        let share = |status: Option<bool>| match statuses.len() {
+            0 => 0.0,
+            total => statuses.iter().filter(|s| **s == status).count() as f64 / total as f64,
+        };
+        let (up, down) = (share(Some(true)), share(Some(false)));
+
+        table.rows.push(vec![
+            json!(host.id),
+            json!(host.base.name),
+            json!(
+                host.base
+                    .interfaces
+                    .first()
+                    .map(|i| i.base.ip_address.to_string())
+            ),
+            json!(format!("{} services", statuses.len())),
+            json!(up),
+            json!(down),
+            json!(1.0 - up - down),
+            json!("host"),
+        ]);
+    }
+
+    table
+}
+
+/// Node graph edges: one per host interface, from the host to its subnet
+fn topology_edges(data: &MetricData) -> GrafanaTable {
+    let mut table = GrafanaTable::new(&[
+        ("id", "string"),
+        ("source", "string"),
+        ("target", "string"),
+        ("mainstat", "string"),
+    ]);
+
+    for host in &data.hosts {
+        for interface in &host.base.interfaces {
+            table.rows.push(vec![
+                json!(interface.id),
+                json!(host.id),
+                json!(interface.base.subnet_id),
+                json!(interface.base.ip_address.to_string()),
+            ]);
+        }
+    }
+
+    table
+}
+
+/// Running total of entities by creation time, with points at both ends of the range
+fn cumulative_count(
+    target: &str,
+    mut created: Vec<DateTime<Utc>>,
+    range: &GrafanaTimeRange,
+) -> GrafanaTimeSeries {
+    created.sort();
+
+    let mut count = created.iter().filter(|c| **c < range.from).count();
+    let mut datapoints = vec![(count as f64, range.from.timestamp_millis())];
+
+    for created_at in created
+        .iter()
+        .filter(|c| **c >= range.from && **c <= range.to)
+    {
+        count += 1;
+        datapoints.push((count as f64, created_at.timestamp_millis()));
+    }
+
+    datapoints.push((count as f64, range.to.min(Utc::now()).timestamp_millis()));
+
+    GrafanaTimeSeries {
+        target: target.to_string(),
+        datapoints,
+    }
+}
+
+fn grouped_count(
+    services: &[Service],
+    at: DateTime<Utc>,
+    key: impl Fn(&Service) -> String,
+) -> Vec<GrafanaTimeSeries> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for service in services {
+        *counts.entry(key(service)).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(target, count)| single_point(&target, count as f64, at))
+        .collect()
+}
+
+fn single_point(target: &str, value: f64, at: DateTime<Utc>) -> GrafanaTimeSeries {
+    GrafanaTimeSeries {
+        target: target.to_string(),
+        datapoints: vec![(value, at.timestamp_millis())],
+    }
+}
@@ -0,0 +1,65 @@
+use crate::server::{
+    auth::middleware::AuthenticatedEntity,
+    config::AppState,
+    graphql::r#impl::{
+        schema::{NetVisorSchema, build_schema},
+        types::GraphqlContext,
+    },
+    shared::{services::traits::CrudService, storage::filter::EntityFilter, types::api::ApiResult},
+};
+use async_graphql::http::GraphiQLSource;
+use axum::{
+    Router,
+    extract::State,
+    response::{Html, Json},
+    routing::get,
+};
+use std::sync::{Arc, LazyLock};
+use uuid::Uuid;
+
+static SCHEMA: LazyLock<NetVisorSchema> = LazyLock::new(build_schema);
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(graphiql).post(execute))
+}
+
+/// Networks visible to a user, or the network an API key belongs to
+async fn entity_network_ids(
+    state: &AppState,
+    entity: &AuthenticatedEntity,
+) -> ApiResult<Vec<Uuid>> {
+    match entity {
+        AuthenticatedEntity::Daemon(network_id) => Ok(vec![*network_id]),
+        AuthenticatedEntity::User(user_id) => {
+            let user_filter = EntityFilter::unfiltered().network_member(user_id);
+
+            Ok(state
+                .services
+                .network_service
+                .get_all(user_filter)
+                .await?
+                .iter()
+                .map(|n| n.id)
+                .collect())
+        }
+    }
+}
+
+async fn execute(
+    State(state): State<Arc<AppState>>,
+    entity: AuthenticatedEntity,
+    Json(request): Json<async_graphql::Request>,
+) -> ApiResult<Json<async_graphql::Response>> {
+    let network_ids = entity_network_ids(&state, &entity).await?;
+
+    let response = SCHEMA
+        .execute(request.data(GraphqlContext { state, network_ids }))
+        .await;
+
+    Ok(Json(response))
+}
+
+/// In-browser query editor
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/api/graphql").finish())
+}
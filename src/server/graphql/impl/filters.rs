@@ -0,0 +1,112 @@
+use async_graphql::InputObject;
+use std::net::IpAddr;
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::base::Host, networks::r#impl::Network, services::r#impl::base::Service,
+    subnets::r#impl::base::Subnet,
+};
+
+fn contains_ignore_case(haystack: &str, needle: &Option<String>) -> bool {
+    needle
+        .as_ref()
+        .is_none_or(|needle| haystack.to_lowercase().contains(&needle.to_lowercase()))
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct NetworkFilter {
+    pub id: Option<Uuid>,
+    pub name_contains: Option<String>,
+}
+
+impl NetworkFilter {
+    pub fn matches(&self, network: &Network) -> bool {
+        self.id.is_none_or(|id| id == network.id)
+            && contains_ignore_case(&network.base.name, &self.name_contains)
+    }
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct SubnetFilter {
+    pub id: Option<Uuid>,
+    pub name_contains: Option<String>,
+    /// Subnets whose CIDR contains this address
+    pub contains_ip: Option<String>,
+    /// e.g. `Lan`, `VpnTunnel`
+    pub subnet_type: Option<String>,
+}
+
+impl SubnetFilter {
+    pub fn matches(&self, subnet: &Subnet) -> bool {
+        self.id.is_none_or(|id| id == subnet.id)
+            && contains_ignore_case(&subnet.base.name, &self.name_contains)
+            && self.contains_ip.as_ref().is_none_or(|ip| {
+                ip.parse::<IpAddr>()
+                    .is_ok_and(|ip| subnet.base.cidr.contains(&ip))
+            })
+            && self.subnet_type.as_ref().is_none_or(|subnet_type| {
+                let name: &'static str = (&subnet.base.subnet_type).into();
+                name.eq_ignore_ascii_case(subnet_type)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct HostFilter {
+    pub id: Option<Uuid>,
+    /// Matched against the name and hostname
+    pub name_contains: Option<String>,
+    /// Hosts with an interface on this address
+    pub ip: Option<String>,
+    /// Hosts with this port open, on any protocol
+    pub open_port: Option<u16>,
+    pub hidden: Option<bool>,
+    pub stale: Option<bool>,
+}
+
+impl HostFilter {
+    pub fn matches(&self, host: &Host) -> bool {
+        self.id.is_none_or(|id| id == host.id)
+            && (contains_ignore_case(&host.base.name, &self.name_contains)
+                || host
+                    .base
+                    .hostname
+                    .as_ref()
+                    .is_some_and(|hostname| contains_ignore_case(hostname, &self.name_contains)))
+            && self.ip.as_ref().is_none_or(|ip| {
+                ip.parse::<IpAddr>().is_ok_and(|ip| {
+                    host.base
+                        .interfaces
+                        .iter()
+                        .any(|interface| interface.base.ip_address == ip)
+                })
+            })
+            && self
+                .open_port
+                .is_none_or(|number| host.base.ports.iter().any(|p| p.base.number() == number))
+            && self.hidden.is_none_or(|hidden| hidden == host.base.hidden)
+            && self.stale.is_none_or(|stale| stale == host.base.stale)
+    }
+}
+
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct ServiceFilter {
+    pub id: Option<Uuid>,
+    pub name_contains: Option<String>,
+    /// Name of the service definition the service was matched by, e.g. `Home Assistant`
+    pub definition: Option<String>,
+}
+
+impl ServiceFilter {
+    pub fn matches(&self, service: &Service) -> bool {
+        self.id.is_none_or(|id| id == service.id)
+            && contains_ignore_case(&service.base.name, &self.name_contains)
+            && self.definition.as_ref().is_none_or(|definition| {
+                service
+                    .base
+                    .service_definition
+                    .name()
+                    .eq_ignore_ascii_case(definition)
+            })
+    }
+}
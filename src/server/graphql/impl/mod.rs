@@ -0,0 +1,3 @@
+pub mod filters;
+pub mod schema;
+pub mod types;
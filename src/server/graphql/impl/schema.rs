@@ -0,0 +1,57 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Result, Schema};
+
+use crate::server::{
+    graphql::r#impl::{
+        filters::NetworkFilter,
+        types::{GraphqlContext, NetworkNode},
+    },
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+};
+
+/// Read-only; changes go through the REST API
+pub type NetVisorSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deep enough for network → subnet → host → service → host → ports, but not for runaway
+/// cycles through the back references
+const MAX_DEPTH: usize = 10;
+const MAX_COMPLEXITY: usize = 1000;
+
+pub fn build_schema() -> NetVisorSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Networks the caller owns, is a member of, or that their API key belongs to
+    async fn networks(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<NetworkFilter>,
+    ) -> Result<Vec<NetworkNode>> {
+        let context = GraphqlContext::get(ctx);
+        let filter = filter.unwrap_or_default();
+
+        // An empty id list doesn't filter at all
+        if context.network_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let networks = context
+            .state
+            .services
+            .network_service
+            .get_all(EntityFilter::unfiltered().entity_ids(&context.network_ids))
+            .await?;
+
+        Ok(networks
+            .into_iter()
+            .filter(|network| filter.matches(network))
+            .map(NetworkNode)
+            .collect())
+    }
+}
@@ -0,0 +1,311 @@
+use async_graphql::{Context, Object, Result};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    config::AppState,
+    graphql::r#impl::filters::{HostFilter, ServiceFilter, SubnetFilter},
+    hosts::r#impl::{base::Host, interfaces::Interface, ports::Port},
+    networks::r#impl::Network,
+    services::r#impl::{base::Service, bindings::Binding},
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+    subnets::r#impl::base::Subnet,
+};
+
+/// What resolvers need for one request
+pub struct GraphqlContext {
+    pub state: Arc<AppState>,
+    /// Networks the caller can see; nothing outside them is resolved
+    pub network_ids: Vec<Uuid>,
+}
+
+impl GraphqlContext {
+    pub fn get<'a>(ctx: &'a Context<'_>) -> &'a Self {
+        ctx.data_unchecked::<Self>()
+    }
+
+    async fn hosts(&self, network_id: &Uuid) -> Result<Vec<Host>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        Ok(self.state.services.host_service.get_all(filter).await?)
+    }
+
+    async fn host(&self, id: &Uuid) -> Result<Option<Host>> {
+        let host = self.state.services.host_service.get_by_id(id).await?;
+        Ok(host.filter(|host| self.network_ids.contains(&host.base.network_id)))
+    }
+
+    async fn subnet(&self, id: &Uuid) -> Result<Option<Subnet>> {
+        let subnet = self.state.services.subnet_service.get_by_id(id).await?;
+        Ok(subnet.filter(|subnet| self.network_ids.contains(&subnet.base.network_id)))
+    }
+}
+
+pub struct NetworkNode(pub Network);
+
+#[Object(name = "Network")]
+impl NetworkNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.base.name
+    }
+
+    async fn created_at(&self) -> DateTime<Utc> {
+        self.0.created_at
+    }
+
+    async fn subnets(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<SubnetFilter>,
+    ) -> Result<Vec<SubnetNode>> {
+        let filter = filter.unwrap_or_default();
+        let subnets = GraphqlContext::get(ctx)
+            .state
+            .services
+            .subnet_service
+            .get_all(EntityFilter::unfiltered().network_ids(&[self.0.id]))
+            .await?;
+
+        Ok(subnets
+            .into_iter()
+            .filter(|subnet| filter.matches(subnet))
+            .map(SubnetNode)
+            .collect())
+    }
+
+    async fn hosts(&self, ctx: &Context<'_>, filter: Option<HostFilter>) -> Result<Vec<HostNode>> {
+        let filter = filter.unwrap_or_default();
+        let hosts = GraphqlContext::get(ctx).hosts(&self.0.id).await?;
+
+        Ok(hosts
+            .into_iter()
+            .filter(|host| filter.matches(host))
+            .map(HostNode)
+            .collect())
+    }
+
+    async fn services(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ServiceFilter>,
+    ) -> Result<Vec<ServiceNode>> {
+        let filter = filter.unwrap_or_default();
+        let services = GraphqlContext::get(ctx)
+            .state
+            .services
+            .service_service
+            .get_all(EntityFilter::unfiltered().network_ids(&[self.0.id]))
+            .await?;
+
+        Ok(services
+            .into_iter()
+            .filter(|service| filter.matches(service))
+            .map(ServiceNode)
+            .collect())
+    }
+}
+
+pub struct SubnetNode(pub Subnet);
+
+#[Object(name = "Subnet")]
+impl SubnetNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.base.name
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.base.description.as_deref()
+    }
+
+    async fn cidr(&self) -> String {
+        self.0.base.cidr.to_string()
+    }
+
+    async fn subnet_type(&self) -> &'static str {
+        (&self.0.base.subnet_type).into()
+    }
+
+    /// Hosts with an interface on the subnet
+    async fn hosts(&self, ctx: &Context<'_>, filter: Option<HostFilter>) -> Result<Vec<HostNode>> {
+        let filter = filter.unwrap_or_default();
+        let hosts = GraphqlContext::get(ctx)
+            .hosts(&self.0.base.network_id)
+            .await?;
+
+        Ok(hosts
+            .into_iter()
+            .filter(|host| {
+                host.base
+                    .interfaces
+                    .iter()
+                    .any(|interface| interface.base.subnet_id == self.0.id)
+            })
+            .filter(|host| filter.matches(host))
+            .map(HostNode)
+            .collect())
+    }
+}
+
+pub struct HostNode(pub Host);
+
+#[Object(name = "Host")]
+impl HostNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.base.name
+    }
+
+    async fn hostname(&self) -> Option<&str> {
+        self.0.base.hostname.as_deref()
+    }
+
+    async fn description(&self) -> Option<&str> {
+        self.0.base.description.as_deref()
+    }
+
+    async fn hidden(&self) -> bool {
+        self.0.base.hidden
+    }
+
+    async fn stale(&self) -> bool {
+        self.0.base.stale
+    }
+
+    async fn last_seen_at(&self) -> Option<DateTime<Utc>> {
+        self.0.base.last_seen_at
+    }
+
+    async fn interfaces(&self, subnet_id: Option<Uuid>) -> Vec<InterfaceNode> {
+        self.0
+            .base
+            .interfaces
+            .iter()
+            .filter(|interface| subnet_id.is_none_or(|id| interface.base.subnet_id == id))
+            .cloned()
+            .map(InterfaceNode)
+            .collect()
+    }
+
+    async fn ports(&self) -> Vec<PortNode> {
+        self.0.base.ports.iter().copied().map(PortNode).collect()
+    }
+
+    async fn services(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<ServiceFilter>,
+    ) -> Result<Vec<ServiceNode>> {
+        let filter = filter.unwrap_or_default();
+        let services = GraphqlContext::get(ctx)
+            .state
+            .services
+            .service_service
+            .get_all(EntityFilter::unfiltered().host_id(&self.0.id))
+            .await?;
+
+        Ok(services
+            .into_iter()
+            .filter(|service| filter.matches(service))
+            .map(ServiceNode)
+            .collect())
+    }
+}
+
+pub struct InterfaceNode(pub Interface);
+
+#[Object(name = "Interface")]
+impl InterfaceNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> Option<&str> {
+        self.0.base.name.as_deref()
+    }
+
+    async fn ip_address(&self) -> String {
+        self.0.base.ip_address.to_string()
+    }
+
+    async fn mac_address(&self) -> Option<String> {
+        self.0.base.mac_address.map(|mac| mac.to_string())
+    }
+
+    async fn subnet(&self, ctx: &Context<'_>) -> Result<Option<SubnetNode>> {
+        let subnet = GraphqlContext::get(ctx)
+            .subnet(&self.0.base.subnet_id)
+            .await?;
+        Ok(subnet.map(SubnetNode))
+    }
+}
+
+pub struct PortNode(pub Port);
+
+#[Object(name = "Port")]
+impl PortNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn number(&self) -> u16 {
+        self.0.base.number()
+    }
+
+    /// `tcp` or `udp`
+    async fn protocol(&self) -> String {
+        self.0.base.protocol().to_string().to_lowercase()
+    }
+}
+
+pub struct ServiceNode(pub Service);
+
+#[Object(name = "Service")]
+impl ServiceNode {
+    async fn id(&self) -> Uuid {
+        self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.base.name
+    }
+
+    /// Name of the service definition the service was matched by
+    async fn definition(&self) -> &'static str {
+        self.0.base.service_definition.name()
+    }
+
+    async fn host(&self, ctx: &Context<'_>) -> Result<Option<HostNode>> {
+        let host = GraphqlContext::get(ctx).host(&self.0.base.host_id).await?;
+        Ok(host.map(HostNode))
+    }
+
+    /// Ports on the host the service is bound to
+    async fn ports(&self, ctx: &Context<'_>) -> Result<Vec<PortNode>> {
+        let Some(host) = GraphqlContext::get(ctx).host(&self.0.base.host_id).await? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(host
+            .base
+            .ports
+            .into_iter()
+            .filter(|port| {
+                self.0.base.bindings.iter().any(|binding| {
+                    matches!(binding, Binding::Port { port_id, .. } if *port_id == port.id)
+                })
+            })
+            .map(PortNode)
+            .collect())
+    }
+}
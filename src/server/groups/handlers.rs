@@ -4,7 +4,8 @@ use axum::routing::{delete, get, post, put};
 use crate::server::config::AppState;
 use crate::server::groups::r#impl::base::Group;
 use crate::server::shared::handlers::traits::{
-    create_handler, delete_handler, get_all_handler, get_by_id_handler, update_handler,
+    create_handler, delete_handler, get_all_handler, get_by_id_handler, restore_handler,
+    update_handler,
 };
 use std::sync::Arc;
 
@@ -15,4 +16,5 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .route("/{id}", put(update_handler::<Group>))
         .route("/{id}", delete(delete_handler::<Group>))
         .route("/{id}", get(get_by_id_handler::<Group>))
+        .route("/{id}/restore", post(restore_handler::<Group>))
 }
@@ -2,6 +2,7 @@ use crate::server::{
     groups::{r#impl::base::Group, service::GroupService},
     shared::handlers::traits::CrudHandlers,
 };
+use uuid::Uuid;
 
 impl CrudHandlers for Group {
     type Service = GroupService;
@@ -9,4 +10,8 @@ impl CrudHandlers for Group {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.group_service
     }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
@@ -22,6 +22,18 @@ impl StorableEntity for Group {
         "groups"
     }
 
+    fn sortable_columns() -> &'static [&'static str] {
+        &["name"]
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "description"]
+    }
+
+    fn soft_delete() -> bool {
+        true
+    }
+
     fn get_base(&self) -> Self::BaseData {
         self.base.clone()
     }
@@ -33,6 +45,7 @@ impl StorableEntity for Group {
             id: Uuid::new_v4(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
             base,
         }
     }
@@ -58,6 +71,7 @@ impl StorableEntity for Group {
             id,
             created_at,
             updated_at,
+            deleted_at: _,
             base:
                 Self::BaseData {
                     name,
@@ -108,6 +122,7 @@ impl StorableEntity for Group {
             id: row.get("id"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            deleted_at: row.get("deleted_at"),
             base: GroupBase {
                 name: row.get("name"),
                 description: row.get("description"),
@@ -28,6 +28,22 @@ pub enum GroupType {
     },
 }
 
+impl GroupType {
+    /// Directed (source, target) binding pairs implied by the group
+    pub fn binding_pairs(&self) -> Vec<(Uuid, Uuid)> {
+        match self {
+            GroupType::RequestPath { service_bindings } => service_bindings
+                .windows(2)
+                .map(|window| (window[0], window[1]))
+                .collect(),
+            GroupType::HubAndSpoke { service_bindings } => match service_bindings.split_first() {
+                Some((hub, spokes)) => spokes.iter().map(|spoke| (*hub, *spoke)).collect(),
+                None => Vec::new(),
+            },
+        }
+    }
+}
+
 impl HasId for GroupTypeDiscriminants {
     fn id(&self) -> &'static str {
         self.into()
@@ -1,43 +1,186 @@
 use crate::server::auth::middleware::{AuthenticatedEntity, AuthenticatedUser};
-use crate::server::shared::handlers::traits::{CrudHandlers, get_all_handler, get_by_id_handler};
+use crate::server::shared::handlers::traits::{
+    CrudHandlers, ListQuery, check_access, check_entity_access, get_accessible, get_by_id_handler,
+    restore_handler,
+};
 use crate::server::shared::services::traits::CrudService;
-use crate::server::shared::storage::filter::EntityFilter;
+use crate::server::shared::storage::filter::{EntityFilter, SortDirection};
 use crate::server::shared::storage::traits::StorableEntity;
 use crate::server::{
     config::AppState,
-    hosts::r#impl::{api::HostWithServicesRequest, base::Host},
+    daemons::r#impl::api::DiscoveryUpdatePayload,
+    discovery::r#impl::{
+        base::{Discovery, DiscoveryBase},
+        types::{DiscoveryType, RunType},
+    },
+    exports::handlers::export_network_ids,
+    hosts::r#impl::{
+        api::{
+            HostCsvExportQuery, HostCsvImportRequest, HostCsvImportResponse, HostListQuery,
+            HostReconcileRequest, HostRescanRequest, HostWithServicesRequest,
+        },
+        base::Host,
+        changes::HostChange,
+        csv,
+        service_overrides::HostServiceOverrides,
+    },
+    network_members::handlers::network_role,
     services::r#impl::base::Service,
-    shared::types::api::{ApiError, ApiResponse, ApiResult},
+    shared::types::{
+        api::{ApiError, ApiResponse, ApiResult},
+        entities::EntitySourceDiscriminants,
+    },
 };
 use axum::routing::{delete, get};
 use axum::{
     Router,
-    extract::{Path, State},
-    response::Json,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json},
     routing::{post, put},
 };
+use chrono::Utc;
 use futures::future::try_join_all;
 use itertools::{Either, Itertools};
 use std::sync::Arc;
+use strum::IntoDiscriminant;
 use uuid::Uuid;
 use validator::Validate;
 
 pub fn create_router() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/", get(get_all_handler::<Host>))
+        .route("/", get(get_all_hosts))
         .route("/{id}", delete(delete_handler))
         .route("/{id}", get(get_by_id_handler::<Host>))
         .route("/", post(create_host))
+        .route("/reconcile", post(reconcile_host))
+        .route("/export.csv", get(export_hosts_csv))
+        .route("/import", post(import_hosts_csv))
         .route("/{id}", put(update_host))
+        .route("/{id}/service-overrides", put(update_service_overrides))
+        .route("/{id}/rescan", post(rescan_host))
+        .route("/{id}/changes", get(get_host_changes))
+        .route("/{id}/restore", post(restore_handler::<Host>))
         .route(
             "/{destination_host}/consolidate/{other_host}",
             put(consolidate_hosts),
         )
 }
 
+/// Hosts in the user's networks, optionally only stale or only fresh ones
+async fn get_all_hosts(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<HostListQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<Host>>>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    let mut network_filter = EntityFilter::unfiltered().network_ids(&network_ids);
+    if let Some(stale) = query.stale {
+        network_filter = network_filter.stale(stale);
+    }
+    let network_filter = query.list_query().apply::<Host>(network_filter)?;
+
+    let hosts = state.services.host_service.get_page(network_filter).await?;
+
+    Ok(Json(ApiResponse::page(hosts)))
+}
+
+/// Hosts with their addresses, ports and services as CSV, in the layout the import reads
+async fn export_hosts_csv(
+    State(state): State<Arc<AppState>>,
+    entity: AuthenticatedEntity,
+    Query(query): Query<HostCsvExportQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let network_ids = export_network_ids(&state, &entity, query.network_id).await?;
+
+    // Nothing to look up for a user without networks
+    let text = if network_ids.is_empty() {
+        csv::export(&[], &[], &[])
+    } else {
+        let filter = EntityFilter::unfiltered().network_ids(&network_ids);
+        let hosts = state.services.host_service.get_all(filter.clone()).await?;
+        let services = state
+            .services
+            .service_service
+            .get_all(filter.clone())
+            .await?;
+        let subnets = state.services.subnet_service.get_all(filter).await?;
+        csv::export(&hosts, &services, &subnets)
+    };
+
+    let filename = format!(
+        "attachment; filename=\"hosts-{}.csv\"",
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, filename),
+        ],
+        text,
+    ))
+}
+
+/// Create hosts from CSV rows. A preview only validates; otherwise nothing is imported unless
+/// every row is valid.
+async fn import_hosts_csv(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<HostCsvImportRequest>,
+) -> ApiResult<Json<ApiResponse<HostCsvImportResponse>>> {
+    network_role(&state, &user.0, &request.network_id).await?;
+
+    let subnets = state
+        .services
+        .subnet_service
+        .get_all(EntityFilter::unfiltered().network_ids(&[request.network_id]))
+        .await?;
+
+    let (columns, rows) = csv::parse(&request.csv, &request.mapping, &subnets)
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    let invalid = rows.iter().filter(|row| !row.is_valid()).count();
+    if !request.preview && invalid > 0 {
+        return Err(ApiError::bad_request(&format!(
+            "{} of {} rows have errors; preview the import to see them",
+            invalid,
+            rows.len()
+        )));
+    }
+
+    let mut host_ids = Vec::new();
+    if !request.preview {
+        for row in &rows {
+            let (host, services) = row.to_host(request.network_id, &subnets);
+            let (host, _) = state
+                .services
+                .host_service
+                .create_host_with_services(host, services)
+                .await?;
+            host_ids.push(host.id);
+        }
+    }
+
+    Ok(Json(ApiResponse::success(HostCsvImportResponse {
+        columns,
+        rows,
+        host_ids,
+    })))
+}
+
 async fn create_host(
     State(state): State<Arc<AppState>>,
-    _authenticated: AuthenticatedEntity,
+    authenticated: AuthenticatedEntity,
     Json(request): Json<HostWithServicesRequest>,
 ) -> ApiResult<Json<ApiResponse<HostWithServicesRequest>>> {
     let host_service = &state.services.host_service;
@@ -50,6 +193,27 @@ async fn create_host(
         )));
     }
 
+    check_entity_access(&state, &authenticated, &request.host).await?;
+    if request
+        .services
+        .iter()
+        .flatten()
+        .any(|s| s.base.network_id != request.host.base.network_id)
+    {
+        return Err(ApiError::bad_request(
+            "Services must be in the same network as their host",
+        ));
+    }
+
+    if request.host.base.source.discriminant() == EntitySourceDiscriminants::Discovery
+        && host_service.is_excluded(&request.host).await?
+    {
+        return Err(ApiError::bad_request(&format!(
+            "Host {} is excluded from discovery in this network",
+            request.host
+        )));
+    }
+
     let (host, services) = host_service
         .create_host_with_services(request.host, request.services.unwrap_or_default())
         .await?;
@@ -60,16 +224,81 @@ async fn create_host(
     })))
 }
 
+async fn reconcile_host(
+    State(state): State<Arc<AppState>>,
+    authenticated: AuthenticatedEntity,
+    Json(request): Json<HostReconcileRequest>,
+) -> ApiResult<Json<ApiResponse<HostWithServicesRequest>>> {
+    if let Err(e) = request.host.base.validate() {
+        return Err(ApiError::bad_request(&format!(
+            "Host validation failed: {}",
+            e
+        )));
+    }
+
+    let host_service = &state.services.host_service;
+
+    // Both the network the host is in and the one it's reported in
+    check_entity_access(&state, &authenticated, &request.host).await?;
+    if let Some(existing) = host_service.get_by_id(&request.host.id).await? {
+        check_entity_access(&state, &authenticated, &existing).await?;
+    }
+    if request
+        .services
+        .iter()
+        .any(|s| s.base.network_id != request.host.base.network_id)
+    {
+        return Err(ApiError::bad_request(
+            "Services must be in the same network as their host",
+        ));
+    }
+
+    if host_service.is_excluded(&request.host).await? {
+        return Err(ApiError::bad_request(&format!(
+            "Host {} is excluded from discovery in this network",
+            request.host
+        )));
+    }
+
+    let (host, services) = host_service
+        .reconcile_host(request.host, request.services, request.complete_port_scan)
+        .await?;
+
+    Ok(Json(ApiResponse::success(HostWithServicesRequest {
+        host,
+        services: Some(services),
+    })))
+}
+
 async fn update_host(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Json(mut request): Json<HostWithServicesRequest>,
 ) -> ApiResult<Json<ApiResponse<Host>>> {
     let host_service = &state.services.host_service;
     let service_service = &state.services.service_service;
 
+    if let Err(e) = request.host.base.service_overrides.validate() {
+        return Err(ApiError::bad_request(&e));
+    }
+
+    // Both the network the host is in and the one it's moved to
+    get_accessible::<Host>(&state, &user.0, &request.host.id).await?;
+    check_access(&state, &user.0, &request.host).await?;
+
     // If services is None, don't update services
     if let Some(services) = request.services {
+        for service in &services {
+            if service.base.network_id != request.host.base.network_id {
+                return Err(ApiError::bad_request(
+                    "Services must be in the same network as their host",
+                ));
+            }
+            if service.id != Uuid::nil() {
+                get_accessible::<Service>(&state, &user.0, &service.id).await?;
+            }
+        }
+
         let (create_futures, update_futures): (Vec<_>, Vec<_>) =
             services.into_iter().partition_map(|s| {
                 if s.id == Uuid::nil() {
@@ -95,32 +324,126 @@ async fn update_host(
     Ok(Json(ApiResponse::success(updated_host)))
 }
 
-async fn consolidate_hosts(
+/// Replace the service definitions suppressed or forced on a host. They apply from the next
+/// discovery of the host; services it already has are left alone.
+async fn update_service_overrides(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
-    Path((destination_host_id, other_host_id)): Path<(Uuid, Uuid)>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<HostServiceOverrides>,
 ) -> ApiResult<Json<ApiResponse<Host>>> {
+    if let Err(e) = request.validate() {
+        return Err(ApiError::bad_request(&e));
+    }
+
     let host_service = &state.services.host_service;
+    let mut host: Host = get_accessible(&state, &user.0, &id).await?;
 
-    let destination_host = host_service
-        .get_by_id(&destination_host_id)
+    host.base.service_overrides = request;
+    let updated_host = host_service.update_host(host).await?;
+
+    Ok(Json(ApiResponse::success(updated_host)))
+}
+
+/// Ports opened and closed and services added and removed by discoveries of a host, newest
+/// first unless sorted otherwise
+async fn get_host_changes(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<HostChange>>>> {
+    let host_service = &state.services.host_service;
+    let host = host_service
+        .get_by_id(&id)
         .await?
-        .ok_or_else(|| {
-            ApiError::not_found(format!(
-                "Could not find destination host {}",
-                destination_host_id
-            ))
-        })?;
-    let other_host = host_service
-        .get_by_id(&other_host_id)
+        .ok_or_else(|| ApiError::not_found(format!("Host '{}' not found", id)))?;
+    check_access(&state, &user.0, &host).await?;
+
+    let mut filter = query.apply::<HostChange>(EntityFilter::unfiltered().host_id(&host.id))?;
+    if query.sort.is_none() {
+        filter = filter.sort_by("created_at", SortDirection::Desc);
+    }
+
+    let changes = host_service.changes(filter).await?;
+
+    Ok(Json(ApiResponse::page(changes)))
+}
+
+/// Scan one of a host's interfaces again from a daemon on its subnet. The result updates the host
+/// in place; progress is reported like any other discovery session.
+async fn rescan_host(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(request): Query<HostRescanRequest>,
+) -> ApiResult<Json<ApiResponse<DiscoveryUpdatePayload>>> {
+    let host_service = &state.services.host_service;
+    let host: Host = get_accessible(&state, &user.0, &id).await?;
+
+    let interface = match request.interface_id {
+        Some(interface_id) => host.get_interface(&request.interface_id).ok_or_else(|| {
+            ApiError::not_found(format!("Interface '{}' not found on host", interface_id))
+        })?,
+        None => host
+            .base
+            .interfaces
+            .first()
+            .ok_or_else(|| ApiError::bad_request("Host has no interfaces to scan"))?,
+    };
+
+    let daemon = host_service
+        .owning_daemon(&host, &interface.base.subnet_id)
         .await?
         .ok_or_else(|| {
-            ApiError::not_found(format!(
-                "Could not find host to consolidate {}",
-                other_host_id
-            ))
+            ApiError::conflict("No daemon on this host's subnet is available to rescan it")
         })?;
 
+    let host_naming_fallback = state
+        .services
+        .network_settings_service
+        .for_network(&host.base.network_id)
+        .await?
+        .host_naming_fallback;
+
+    let session = state
+        .services
+        .discovery_service
+        .start_session(Discovery::new(DiscoveryBase {
+            discovery_type: DiscoveryType::HostRescan {
+                host_id: host.id,
+                subnet_id: interface.base.subnet_id,
+                ip: interface.base.ip_address,
+                host_naming_fallback,
+            },
+            run_type: RunType::AdHoc {
+                last_run: Some(Utc::now()),
+            },
+            name: format!("Rescan @ {}", interface.base.ip_address),
+            daemon_id: daemon.id,
+            network_id: host.base.network_id,
+        }))
+        .await?;
+
+    Ok(Json(ApiResponse::success(session)))
+}
+
+async fn consolidate_hosts(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((destination_host_id, other_host_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ApiResponse<Host>>> {
+    let host_service = &state.services.host_service;
+
+    let destination_host: Host = get_accessible(&state, &user.0, &destination_host_id).await?;
+    let other_host: Host = get_accessible(&state, &user.0, &other_host_id).await?;
+
+    if destination_host.base.network_id != other_host.base.network_id {
+        return Err(ApiError::bad_request(
+            "Hosts in different networks can't be consolidated",
+        ));
+    }
+
     let updated_host = host_service
         .consolidate_hosts(destination_host, other_host)
         .await?;
@@ -130,10 +453,11 @@ async fn consolidate_hosts(
 
 pub async fn delete_handler(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<()>>> {
     let service = Host::get_service(&state);
+    get_accessible::<Host>(&state, &user.0, &id).await?;
 
     let daemon_service = &state.services.daemon_service;
 
@@ -144,13 +468,6 @@ pub async fn delete_handler(
         ));
     }
 
-    // Verify entity exists
-    service
-        .get_by_id(&id)
-        .await
-        .map_err(|e| ApiError::internal_error(&e.to_string()))?
-        .ok_or_else(|| ApiError::not_found(format!("Host '{}' not found", id)))?;
-
     service
         .delete(&id)
         .await
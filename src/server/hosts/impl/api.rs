@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::server::{hosts::r#impl::base::Host, services::r#impl::base::Service};
+use crate::server::{
+    hosts::r#impl::{base::Host, ports::PortConfig},
+    services::r#impl::base::Service,
+    shared::handlers::traits::ListQuery,
+};
 
 /// None in services = don't do anything to services, no services to create or update
 /// Some(vec!()) = delete all services
@@ -10,3 +15,90 @@ pub struct HostWithServicesRequest {
     #[serde(default)]
     pub services: Option<Vec<Service>>,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostRescanRequest {
+    /// Interface to scan; the host's first interface if unset
+    #[serde(default)]
+    pub interface_id: Option<Uuid>,
+}
+
+/// A daemon's result for one host, merged into the existing host it describes rather than
+/// created alongside it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostReconcileRequest {
+    pub host: Host,
+    #[serde(default)]
+    pub services: Vec<Service>,
+    /// Every port on the host was probed, so ports it no longer reports are closed
+    #[serde(default)]
+    pub complete_port_scan: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HostListQuery {
+    #[serde(default)]
+    pub stale: Option<bool>,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub limit: Option<u32>,
+    pub cursor: Option<Uuid>,
+    pub sort: Option<String>,
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub field: Option<String>,
+}
+
+impl HostListQuery {
+    pub fn list_query(&self) -> ListQuery {
+        ListQuery {
+            include_deleted: self.include_deleted,
+            limit: self.limit,
+            cursor: self.cursor,
+            sort: self.sort.clone(),
+            q: self.q.clone(),
+            tag: self.tag.clone(),
+            field: self.field.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HostCsvExportQuery {
+    /// Only hosts in this network; all the caller's networks if unset
+    pub network_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HostCsvImportRequest {
+    pub network_id: Uuid,
+    pub csv: String,
+    /// CSV header to read each column from, for files not laid out like an export
+    #[serde(default)]
+    pub mapping: BTreeMap<HostCsvColumn, String>,
+    /// Only validate the rows and report what would be imported
+    #[serde(default)]
+    pub preview: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HostCsvImportResponse {
+    /// CSV header each column was read from
+    pub columns: BTreeMap<HostCsvColumn, String>,
+    pub rows: Vec<HostCsvRow>,
+    /// Hosts created or merged into; empty for a preview
+    pub host_ids: Vec<Uuid>,
+}
+
+/// Host changes other parts of the server react to, such as alert rules
+#[derive(Debug, Clone)]
+pub enum HostEvent {
+    /// Discovery reported a host that wasn't known before
+    Discovered(Host),
+    /// The ping monitor stopped getting replies from a host that was reachable
+    WentOffline(Host),
+    /// The ping monitor got a reply from a host that was unreachable
+    CameOnline(Host),
+    /// A discovery found ports open on a known host that weren't open the time before
+    PortsOpened(Host, Vec<PortConfig>),
+}
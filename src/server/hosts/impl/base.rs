@@ -1,5 +1,16 @@
+use crate::server::hosts::r#impl::device::HostDevice;
+use crate::server::hosts::r#impl::dhcp::HostDhcpLease;
+use crate::server::hosts::r#impl::liveness::HostLiveness;
+use crate::server::hosts::r#impl::os::HostOs;
+use crate::server::hosts::r#impl::reachability::HostReachability;
+use crate::server::hosts::r#impl::service_overrides::HostServiceOverrides;
+use crate::server::hosts::r#impl::snmp::HostSnmp;
+use crate::server::hosts::r#impl::ssh::HostSsh;
 use crate::server::hosts::r#impl::virtualization::HostVirtualization;
+use crate::server::hosts::r#impl::vpn::HostVpnPeer;
+use crate::server::hosts::r#impl::windows::HostWindows;
 use crate::server::shared::types::api::deserialize_empty_string_as_none;
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::shared::types::entities::EntitySource;
 use crate::server::subnets::r#impl::base::Subnet;
 use crate::server::{
@@ -19,6 +30,40 @@ static INVALID_MACS_BYTES: &[[u8; 6]; 2] = &[
     [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
 ];
 
+/// Hostnames devices and OS images ship with, shared by every unrenamed install
+static DEFAULT_HOSTNAMES: &[&str] = &[
+    "localhost",
+    "raspberrypi",
+    "raspberry",
+    "pi",
+    "ubuntu",
+    "ubuntu-server",
+    "debian",
+    "fedora",
+    "archlinux",
+    "alpine",
+    "kali",
+    "dietpi",
+    "openwrt",
+    "lede",
+    "pfsense",
+    "opnsense",
+    "homeassistant",
+    "truenas",
+    "unraid",
+    "proxmox",
+    "pve",
+    "android",
+    "iphone",
+    "ipad",
+    "router",
+    "gateway",
+    "nas",
+    "server",
+    "host",
+    "unknown",
+];
+
 #[derive(Debug, Clone, Serialize, Validate, Deserialize, Eq, PartialEq, Hash)]
 pub struct HostBase {
     #[validate(length(min = 0, max = 100))]
@@ -35,6 +80,46 @@ pub struct HostBase {
     pub source: EntitySource,
     pub virtualization: Option<HostVirtualization>,
     pub hidden: bool,
+    /// Set by network scans; hosts from other sources have no liveness score
+    #[serde(default)]
+    pub liveness: Option<HostLiveness>,
+    /// Set by network scans of hosts with an SNMP agent
+    #[serde(default)]
+    pub snmp: Option<HostSnmp>,
+    /// Set by network scans of hosts the network has SSH credentials for
+    #[serde(default)]
+    pub ssh: Option<HostSsh>,
+    /// Set by network scans of Windows hosts the network has WinRM credentials for
+    #[serde(default)]
+    pub windows: Option<HostWindows>,
+    /// Estimated by network scans
+    #[serde(default)]
+    pub os: Option<HostOs>,
+    /// Kept across rediscovery; only users change it
+    #[serde(default)]
+    pub service_overrides: HostServiceOverrides,
+    /// Last time any discovery reported the host
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+    /// Missed by as many network scans as its network allows; cleared when it's seen again
+    #[serde(default)]
+    pub stale: bool,
+    /// Set by the ping monitor of daemons on the host's subnets
+    #[serde(default)]
+    pub reachability: Option<HostReachability>,
+    /// Set when the host was found as a peer of a VPN tunnel on a daemon host
+    #[serde(default)]
+    pub vpn_peer: Option<HostVpnPeer>,
+    /// Set when a device registry such as Home Assistant's knows the host
+    #[serde(default)]
+    pub device: Option<HostDevice>,
+    /// Set when a DHCP server's leases list the host's address
+    #[serde(default)]
+    pub dhcp_lease: Option<HostDhcpLease>,
+    /// Tags and custom fields set by users
+    #[serde(default)]
+    #[validate(nested)]
+    pub attributes: EntityAttributes,
 }
 
 impl Default for HostBase {
@@ -51,6 +136,19 @@ impl Default for HostBase {
             source: EntitySource::Unknown,
             virtualization: None,
             hidden: false,
+            liveness: None,
+            snmp: None,
+            ssh: None,
+            windows: None,
+            os: None,
+            service_overrides: HostServiceOverrides::default(),
+            last_seen_at: None,
+            stale: false,
+            reachability: None,
+            vpn_peer: None,
+            device: None,
+            dhcp_lease: None,
+            attributes: EntityAttributes::default(),
         }
     }
 }
@@ -60,6 +158,9 @@ pub struct Host {
     pub id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the host was moved to the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     pub base: HostBase,
 }
@@ -165,7 +266,90 @@ impl Host {
         self.base.ports.iter().find(|p| &p.id == port_id)
     }
 
+    /// Whether two discovered hosts in the same network are the same multi-homed machine seen
+    /// from different subnets, e.g. by daemons in each. Interfaces have their own MACs, so this
+    /// relies on the hostname, which must not be one every unrenamed install shares, and hosts
+    /// with interfaces in a shared subnet are never the same machine since that subnet's scan
+    /// would have matched them by IP or MAC.
+    pub fn is_same_machine(&self, other: &Host) -> bool {
+        if self.base.network_id != other.base.network_id {
+            return false;
+        }
+
+        let (Some(a), Some(b)) = (
+            self.base.hostname.as_deref().and_then(normalize_hostname),
+            other.base.hostname.as_deref().and_then(normalize_hostname),
+        ) else {
+            return false;
+        };
+
+        let shares_subnet = self.base.interfaces.iter().any(|ours| {
+            other
+                .base
+                .interfaces
+                .iter()
+                .any(|theirs| ours.base.subnet_id == theirs.base.subnet_id)
+        });
+
+        a == b && !shares_subnet
+    }
+
+    /// Whether two hosts in the same network resolve to the same hostname. Looser than
+    /// `is_same_machine`: it also matches a host whose address and MAC changed, e.g. one with a new
+    /// DHCP lease and a randomized MAC, so it's only used when reconciling discovery results.
+    pub fn has_same_hostname(&self, other: &Host) -> bool {
+        if self.base.network_id != other.base.network_id {
+            return false;
+        }
+
+        match (
+            self.base.hostname.as_deref().and_then(normalize_hostname),
+            other.base.hostname.as_deref().and_then(normalize_hostname),
+        ) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
     pub fn add_service(&mut self, service_id: Uuid) {
         self.base.services.push(service_id);
     }
 }
+
+/// Hostname used to identify a machine, or None for names that don't identify one: addresses
+/// and defaults like `raspberrypi.lan`, whatever the domain
+fn normalize_hostname(hostname: &str) -> Option<String> {
+    let hostname = hostname.trim().trim_end_matches('.').to_lowercase();
+
+    let is_ip = hostname.parse::<IpAddr>().is_ok();
+    let label = hostname.split('.').next().unwrap_or_default();
+    let is_generic = label.is_empty() || DEFAULT_HOSTNAMES.contains(&label);
+
+    (!is_ip && !is_generic).then_some(hostname)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_hostnames_do_not_identify_machines() {
+        for hostname in [
+            "",
+            "localhost",
+            "localhost.localdomain",
+            "raspberrypi",
+            "RaspberryPi.lan.",
+            "ubuntu.home.arpa",
+            "192.168.1.10",
+        ] {
+            assert_eq!(normalize_hostname(hostname), None, "{}", hostname);
+        }
+
+        assert_eq!(
+            normalize_hostname(" NAS-01.lan. ").as_deref(),
+            Some("nas-01.lan")
+        );
+        assert_eq!(normalize_hostname("pi-hole").as_deref(), Some("pi-hole"));
+    }
+}
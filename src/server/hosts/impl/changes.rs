@@ -0,0 +1,158 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::{base::Host, ports::PortConfig},
+    services::r#impl::base::Service,
+};
+
+/// A service that appeared on or disappeared from a host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceChange {
+    pub service_id: Uuid,
+    pub name: String,
+}
+
+/// Ports and services that differ between two consecutive discoveries of a host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostChangeBase {
+    pub host_id: Uuid,
+    pub network_id: Uuid,
+    pub opened_ports: Vec<PortConfig>,
+    pub closed_ports: Vec<PortConfig>,
+    pub added_services: Vec<ServiceChange>,
+    pub removed_services: Vec<ServiceChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostChange {
+    pub id: Uuid,
+    /// When the discovery that found the change finished with the host
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: HostChangeBase,
+}
+
+impl Display for HostChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Change to host {}: {}", self.base.host_id, self.id)
+    }
+}
+
+impl HostChangeBase {
+    /// What changed from `before` to `after`, if anything. Ports are compared by number and
+    /// protocol, services by id.
+    pub fn between(
+        before: &Host,
+        before_services: &[Service],
+        after: &Host,
+        after_services: &[Service],
+    ) -> Option<Self> {
+        let ports = |host: &Host| -> Vec<PortConfig> {
+            let mut ports: Vec<PortConfig> =
+                host.base.ports.iter().map(|p| p.base.config()).collect();
+            ports.sort_by_key(|p| (p.number, p.protocol));
+            ports.dedup();
+            ports
+        };
+        let (ports_before, ports_after) = (ports(before), ports(after));
+
+        let missing_from = |services: &[Service], others: &[Service]| -> Vec<ServiceChange> {
+            services
+                .iter()
+                .filter(|s| !others.iter().any(|o| o.id == s.id))
+                .map(|s| ServiceChange {
+                    service_id: s.id,
+                    name: s.base.name.clone(),
+                })
+                .collect()
+        };
+
+        let change = Self {
+            host_id: after.id,
+            network_id: after.base.network_id,
+            opened_ports: ports_after
+                .iter()
+                .filter(|p| !ports_before.contains(p))
+                .copied()
+                .collect(),
+            closed_ports: ports_before
+                .iter()
+                .filter(|p| !ports_after.contains(p))
+                .copied()
+                .collect(),
+            added_services: missing_from(after_services, before_services),
+            removed_services: missing_from(before_services, after_services),
+        };
+
+        let unchanged = change.opened_ports.is_empty()
+            && change.closed_ports.is_empty()
+            && change.added_services.is_empty()
+            && change.removed_services.is_empty();
+
+        (!unchanged).then_some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::HostBase,
+            ports::{Port, PortBase},
+        },
+        services::r#impl::base::ServiceBase,
+        shared::storage::traits::StorableEntity,
+    };
+
+    fn service(name: &str) -> Service {
+        Service::new(ServiceBase {
+            name: name.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn reports_opened_and_closed_ports_and_services() {
+        let before = Host::new(HostBase {
+            ports: vec![Port::new(PortBase::Ssh), Port::new(PortBase::new_tcp(80))],
+            ..Default::default()
+        });
+        let mut after = before.clone();
+        after.base.ports = vec![
+            Port::new(PortBase::new_tcp(80)),
+            Port::new(PortBase::Telnet),
+        ];
+        let (kept, removed, added) = (service("nginx"), service("OpenSSH"), service("Telnet"));
+
+        let change = HostChangeBase::between(
+            &before,
+            &[kept.clone(), removed.clone()],
+            &after,
+            &[kept, added.clone()],
+        )
+        .unwrap();
+
+        assert_eq!(change.opened_ports, vec![PortBase::Telnet.config()]);
+        assert_eq!(change.closed_ports, vec![PortBase::Ssh.config()]);
+        assert_eq!(change.added_services[0].service_id, added.id);
+        assert_eq!(change.removed_services[0].service_id, removed.id);
+    }
+
+    #[test]
+    fn nothing_changed_when_ports_are_only_reassigned() {
+        let before = Host::new(HostBase {
+            ports: vec![Port::new(PortBase::Ssh)],
+            ..Default::default()
+        });
+        let mut after = before.clone();
+        after.base.ports = vec![Port::new(PortBase::Ssh)];
+
+        assert!(HostChangeBase::between(&before, &[], &after, &[]).is_none());
+    }
+}
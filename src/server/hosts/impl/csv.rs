@@ -0,0 +1,505 @@
+use std::{collections::BTreeMap, net::IpAddr};
+
+use anyhow::{Result, anyhow, bail};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::server::{
+    hosts::r#impl::{
+        base::{Host, HostBase},
+        interfaces::{Interface, InterfaceBase},
+        ports::{Port, PortBase},
+    },
+    services::{
+        definitions::ServiceDefinitionRegistry,
+        r#impl::{
+            base::{Service, ServiceBase},
+            bindings::Binding,
+            definitions::DefaultServiceDefinition,
+        },
+    },
+    shared::{
+        csv::{join_row, split_row},
+        storage::traits::StorableEntity,
+        types::{entities::EntitySource, metadata::HasId},
+    },
+    subnets::r#impl::base::Subnet,
+};
+
+/// Separates the values of list columns, e.g. a host's IP addresses
+const LIST_SEPARATOR: char = ';';
+
+/// Columns of the hosts CSV, in the order they're exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostCsvColumn {
+    Name,
+    Hostname,
+    Description,
+    IpAddresses,
+    MacAddresses,
+    Subnets,
+    Ports,
+    Services,
+    ServiceTypes,
+}
+
+impl HostCsvColumn {
+    pub const ALL: [HostCsvColumn; 9] = [
+        Self::Name,
+        Self::Hostname,
+        Self::Description,
+        Self::IpAddresses,
+        Self::MacAddresses,
+        Self::Subnets,
+        Self::Ports,
+        Self::Services,
+        Self::ServiceTypes,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Hostname => "hostname",
+            Self::Description => "description",
+            Self::IpAddresses => "ip_addresses",
+            Self::MacAddresses => "mac_addresses",
+            Self::Subnets => "subnets",
+            Self::Ports => "ports",
+            Self::Services => "services",
+            Self::ServiceTypes => "service_types",
+        }
+    }
+
+    /// Other headers the column is recognised by when no mapping is given
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Self::Name => &["host", "asset", "asset_name"],
+            Self::Hostname => &["dns_name", "fqdn"],
+            Self::Description => &["notes", "comment"],
+            Self::IpAddresses => &["ip", "ips", "ip_address", "address"],
+            Self::MacAddresses => &["mac", "macs", "mac_address"],
+            Self::Subnets => &["subnet", "cidr"],
+            Self::Ports => &["port", "open_ports"],
+            Self::Services => &["service"],
+            Self::ServiceTypes => &["service_type", "service_definitions"],
+        }
+    }
+}
+
+/// A service listed on a row
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HostCsvService {
+    pub name: String,
+    /// Id of the service definition
+    pub service_type: String,
+}
+
+/// A host read from a row, with what's wrong with it if it can't be imported
+#[derive(Debug, Clone, Serialize)]
+pub struct HostCsvRow {
+    /// Line in the CSV, the header being line 1
+    pub line: usize,
+    pub name: String,
+    pub hostname: Option<String>,
+    pub description: Option<String>,
+    pub ip_addresses: Vec<IpAddr>,
+    /// MAC of the IP address in the same position, if known
+    pub mac_addresses: Vec<Option<MacAddress>>,
+    #[serde(serialize_with = "serialize_ports")]
+    pub ports: Vec<PortBase>,
+    pub services: Vec<HostCsvService>,
+    pub errors: Vec<String>,
+}
+
+fn serialize_ports<S: Serializer>(ports: &[PortBase], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(ports.iter().map(|port| port.to_string()))
+}
+
+impl HostCsvRow {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The host and services to create. Each IP becomes an interface on the subnet containing
+    /// it, paired with the MAC in the same position, and services are bound to every interface.
+    pub fn to_host(&self, network_id: Uuid, subnets: &[Subnet]) -> (Host, Vec<Service>) {
+        let interfaces: Vec<Interface> = self
+            .ip_addresses
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ip)| {
+                let subnet = subnets.iter().find(|s| s.base.cidr.contains(ip))?;
+                Some(Interface::new(InterfaceBase {
+                    subnet_id: subnet.id,
+                    ip_address: *ip,
+                    mac_address: self.mac_addresses.get(i).copied().flatten(),
+                    name: None,
+                }))
+            })
+            .collect();
+
+        let host = Host::new(HostBase {
+            name: self.name.clone(),
+            network_id,
+            hostname: self.hostname.clone(),
+            description: self.description.clone(),
+            ports: self.ports.iter().copied().map(Port::new).collect(),
+            interfaces: interfaces.clone(),
+            source: EntitySource::Manual,
+            ..HostBase::default()
+        });
+
+        let services = self
+            .services
+            .iter()
+            .map(|service| {
+                Service::new(ServiceBase {
+                    host_id: host.id,
+                    network_id,
+                    service_definition: ServiceDefinitionRegistry::find_by_id(
+                        &service.service_type,
+                    )
+                    .unwrap_or_else(|| Box::new(DefaultServiceDefinition)),
+                    name: service.name.clone(),
+                    bindings: interfaces
+                        .iter()
+                        .map(|interface| Binding::Interface {
+                            id: Uuid::new_v4(),
+                            interface_id: interface.id,
+                        })
+                        .collect(),
+                    source: EntitySource::Manual,
+                    ..ServiceBase::default()
+                })
+            })
+            .collect();
+
+        (host, services)
+    }
+}
+
+/// One row per host. List columns hold `;`-separated values, and `services` and
+/// `service_types` line up, so the file can be imported again.
+pub fn export(hosts: &[Host], services: &[Service], subnets: &[Subnet]) -> String {
+    let join = |values: Vec<String>| values.join(&format!("{} ", LIST_SEPARATOR));
+
+    let mut lines = vec![join_row(&HostCsvColumn::ALL.map(|c| c.header()))];
+
+    for host in hosts {
+        let interfaces = &host.base.interfaces;
+        let host_services: Vec<&Service> = services
+            .iter()
+            .filter(|service| service.base.host_id == host.id)
+            .collect();
+
+        let row = HostCsvColumn::ALL.map(|column| match column {
+            HostCsvColumn::Name => host.base.name.clone(),
+            HostCsvColumn::Hostname => host.base.hostname.clone().unwrap_or_default(),
+            HostCsvColumn::Description => host.base.description.clone().unwrap_or_default(),
+            HostCsvColumn::IpAddresses => join(
+                interfaces
+                    .iter()
+                    .map(|i| i.base.ip_address.to_string())
+                    .collect(),
+            ),
+            // Positional, so interfaces without a MAC leave an empty value
+            HostCsvColumn::MacAddresses
+                if interfaces.iter().any(|i| i.base.mac_address.is_some()) =>
+            {
+                join(
+                    interfaces
+                        .iter()
+                        .map(|i| {
+                            i.base
+                                .mac_address
+                                .map(|mac| mac.to_string())
+                                .unwrap_or_default()
+                        })
+                        .collect(),
+                )
+            }
+            HostCsvColumn::MacAddresses => String::new(),
+            HostCsvColumn::Subnets => join(
+                interfaces
+                    .iter()
+                    .filter_map(|i| subnets.iter().find(|s| s.id == i.base.subnet_id))
+                    .map(|s| s.base.cidr.to_string())
+                    .collect(),
+            ),
+            HostCsvColumn::Ports => {
+                join(host.base.ports.iter().map(|p| p.base.to_string()).collect())
+            }
+            HostCsvColumn::Services => {
+                join(host_services.iter().map(|s| s.base.name.clone()).collect())
+            }
+            HostCsvColumn::ServiceTypes => join(
+                host_services
+                    .iter()
+                    .map(|s| s.base.service_definition.id().to_string())
+                    .collect(),
+            ),
+        });
+
+        lines.push(join_row(&row));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Which CSV header each column is read from: the mapped header when given, otherwise one
+/// named like the column
+pub fn resolve_columns(
+    header: &[String],
+    mapping: &BTreeMap<HostCsvColumn, String>,
+) -> Result<BTreeMap<HostCsvColumn, usize>> {
+    let position = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name.trim()))
+    };
+
+    let mut columns = BTreeMap::new();
+    for column in HostCsvColumn::ALL {
+        let index = match mapping.get(&column) {
+            Some(mapped) => Some(position(mapped).ok_or_else(|| {
+                anyhow!(
+                    "Column '{}' is mapped to '{}', which isn't in the CSV",
+                    column.header(),
+                    mapped
+                )
+            })?),
+            None => std::iter::once(column.header())
+                .chain(column.aliases().iter().copied())
+                .find_map(position),
+        };
+
+        if let Some(index) = index {
+            columns.insert(column, index);
+        }
+    }
+
+    if !columns.contains_key(&HostCsvColumn::Name)
+        && !columns.contains_key(&HostCsvColumn::IpAddresses)
+    {
+        bail!("CSV needs a name or ip_addresses column");
+    }
+
+    Ok(columns)
+}
+
+/// Read hosts from CSV with a header row. Rows are checked against the network's subnets;
+/// problems are reported on the row rather than failing the whole file.
+pub fn parse(
+    text: &str,
+    mapping: &BTreeMap<HostCsvColumn, String>,
+    subnets: &[Subnet],
+) -> Result<(BTreeMap<HostCsvColumn, String>, Vec<HostCsvRow>)> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines.next().ok_or_else(|| anyhow!("CSV is empty"))?;
+    let header: Vec<String> = split_row(header)
+        .into_iter()
+        .map(|h| h.trim().to_string())
+        .collect();
+    let columns = resolve_columns(&header, mapping)?;
+
+    let rows = lines
+        .map(|(index, line)| {
+            let fields = split_row(line);
+            let field = |column: HostCsvColumn| {
+                columns
+                    .get(&column)
+                    .and_then(|&i| fields.get(i))
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+            };
+            parse_row(index + 1, field, subnets)
+        })
+        .collect();
+
+    let resolved = columns
+        .into_iter()
+        .map(|(column, index)| (column, header[index].clone()))
+        .collect();
+
+    Ok((resolved, rows))
+}
+
+fn parse_row(
+    line: usize,
+    field: impl Fn(HostCsvColumn) -> Option<String>,
+    subnets: &[Subnet],
+) -> HostCsvRow {
+    let mut errors = Vec::new();
+    let list = |column: HostCsvColumn| -> Vec<String> {
+        field(column)
+            .map(|value| {
+                value
+                    .split(LIST_SEPARATOR)
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let ip_addresses: Vec<IpAddr> = list(HostCsvColumn::IpAddresses)
+        .into_iter()
+        .filter_map(|ip| match ip.parse::<IpAddr>() {
+            Ok(ip) if subnets.iter().any(|s| s.base.cidr.contains(&ip)) => Some(ip),
+            Ok(ip) => {
+                errors.push(format!("No subnet in the network contains {}", ip));
+                None
+            }
+            Err(_) => {
+                errors.push(format!("Invalid IP address '{}'", ip));
+                None
+            }
+        })
+        .collect();
+
+    // Kept in position to pair with the IPs, so empty values stand for an unknown MAC
+    let mac_addresses: Vec<Option<MacAddress>> = field(HostCsvColumn::MacAddresses)
+        .map(|value| {
+            value
+                .split(LIST_SEPARATOR)
+                .map(str::trim)
+                .map(|mac| match mac {
+                    "" => None,
+                    mac => mac
+                        .parse()
+                        .map_err(|_| errors.push(format!("Invalid MAC address '{}'", mac)))
+                        .ok(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if mac_addresses.len() > ip_addresses.len() {
+        errors.push("Each MAC address needs an IP address in the same position".to_string());
+    }
+
+    let ports: Vec<PortBase> = list(HostCsvColumn::Ports)
+        .into_iter()
+        .filter_map(|port| {
+            port.parse()
+                .map_err(|_| errors.push(format!("Invalid port '{}', expected e.g. 22/tcp", port)))
+                .ok()
+        })
+        .collect();
+
+    let names = list(HostCsvColumn::Services);
+    let types = list(HostCsvColumn::ServiceTypes);
+    let services: Vec<HostCsvService> = (0..names.len().max(types.len()))
+        .filter_map(|i| {
+            let service_type = match (types.get(i), names.get(i)) {
+                (Some(service_type), _) => {
+                    if !ServiceDefinitionRegistry::service_exists(service_type) {
+                        errors.push(format!("Unknown service type '{}'", service_type));
+                        return None;
+                    }
+                    service_type.clone()
+                }
+                // Definitions are identified by name, so a well-known name is enough
+                (None, Some(name)) if ServiceDefinitionRegistry::service_exists(name) => {
+                    name.clone()
+                }
+                (None, _) => DefaultServiceDefinition.id().to_string(),
+            };
+
+            Some(HostCsvService {
+                name: names
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_else(|| service_type.clone()),
+                service_type,
+            })
+        })
+        .collect();
+
+    let hostname = field(HostCsvColumn::Hostname);
+    let name = field(HostCsvColumn::Name)
+        .or_else(|| hostname.clone())
+        .or_else(|| ip_addresses.first().map(|ip| ip.to_string()))
+        .unwrap_or_default();
+    if name.is_empty() {
+        errors.push("Row needs a name, hostname or IP address".to_string());
+    }
+
+    let description = field(HostCsvColumn::Description);
+    let base = HostBase {
+        name: name.clone(),
+        description: description.clone(),
+        ..HostBase::default()
+    };
+    if let Err(e) = base.validate() {
+        errors.push(e.to_string());
+    }
+
+    HostCsvRow {
+        line,
+        name,
+        hostname,
+        description,
+        ip_addresses,
+        mac_addresses,
+        ports,
+        services,
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::subnets::r#impl::base::SubnetBase;
+
+    fn subnets() -> Vec<Subnet> {
+        vec![Subnet::new(SubnetBase {
+            cidr: "10.0.0.0/24".parse().unwrap(),
+            ..SubnetBase::default()
+        })]
+    }
+
+    #[test]
+    fn reads_mapped_columns() {
+        let csv = "Asset,IP,Open ports\n\
+                   nas,\"10.0.0.5; 10.0.0.6\",22/tcp;445/tcp\n";
+        let mapping = BTreeMap::from([(HostCsvColumn::Ports, "open ports".to_string())]);
+
+        let (columns, rows) = parse(csv, &mapping, &subnets()).unwrap();
+        assert_eq!(columns[&HostCsvColumn::Name], "Asset");
+        assert_eq!(columns[&HostCsvColumn::Ports], "Open ports");
+
+        let row = &rows[0];
+        assert!(row.is_valid(), "{:?}", row.errors);
+        assert_eq!(row.line, 2);
+        assert_eq!(row.name, "nas");
+        assert_eq!(row.ip_addresses.len(), 2);
+        assert_eq!(row.ports.len(), 2);
+    }
+
+    #[test]
+    fn reports_invalid_rows() {
+        let csv = "name,ip_addresses,ports\n\
+                   a,10.0.1.5,22/tcp\n\
+                   b,not-an-ip,ssh\n";
+
+        let (_, rows) = parse(csv, &BTreeMap::new(), &subnets()).unwrap();
+        assert_eq!(rows[0].errors.len(), 1);
+        assert!(rows[0].errors[0].contains("No subnet"));
+        assert_eq!(rows[1].errors.len(), 2);
+    }
+
+    #[test]
+    fn rejects_mapping_to_missing_header() {
+        let mapping = BTreeMap::from([(HostCsvColumn::Name, "asset".to_string())]);
+        assert!(parse("name\nhost\n", &mapping, &subnets()).is_err());
+        assert!(parse("notes\nhost\n", &BTreeMap::new(), &subnets()).is_err());
+    }
+}
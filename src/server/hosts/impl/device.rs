@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Make and model of a host from a registry that knows it better than its MAC's vendor prefix
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostDevice {
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    /// Room or area the device is assigned to
+    pub area: Option<String>,
+    /// Registry the details were read from, e.g. Home Assistant
+    pub source: String,
+    pub collected_at: DateTime<Utc>,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// DHCP servers whose lease databases or exports can be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, Default)]
+pub enum LeaseFormat {
+    /// `dhcpd.leases` of ISC dhcpd
+    #[default]
+    IscDhcpd,
+    /// Kea's memfile lease CSV, e.g. `kea-leases4.csv`
+    Kea,
+    /// `dnsmasq.leases`
+    Dnsmasq,
+    /// `Get-DhcpServerv4Lease | Export-Csv` on a Windows DHCP server
+    WindowsDhcp,
+}
+
+impl LeaseFormat {
+    /// Where the server usually keeps its leases; Windows leases have to be exported first
+    pub fn default_path(&self) -> Option<&'static str> {
+        match self {
+            LeaseFormat::IscDhcpd => Some("/var/lib/dhcp/dhcpd.leases"),
+            LeaseFormat::Kea => Some("/var/lib/kea/kea-leases4.csv"),
+            LeaseFormat::Dnsmasq => Some("/var/lib/misc/dnsmasq.leases"),
+            LeaseFormat::WindowsDhcp => None,
+        }
+    }
+}
+
+/// The DHCP lease the host's address was last handed out with
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostDhcpLease {
+    pub format: LeaseFormat,
+    /// Name the client asked for, which is often better than its reverse DNS
+    pub client_hostname: Option<String>,
+    /// None for leases that never expire
+    pub expires_at: Option<DateTime<Utc>>,
+    pub collected_at: DateTime<Utc>,
+}
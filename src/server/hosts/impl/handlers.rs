@@ -2,6 +2,7 @@ use crate::server::{
     hosts::{r#impl::base::Host, service::HostService},
     shared::handlers::traits::CrudHandlers,
 };
+use uuid::Uuid;
 
 impl CrudHandlers for Host {
     type Service = HostService;
@@ -9,4 +10,8 @@ impl CrudHandlers for Host {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.host_service
     }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
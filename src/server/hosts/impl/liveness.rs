@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a network scan observed from one IP, collected by the daemon while scanning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LivenessEvidence {
+    pub tcp_ports: u32,
+    /// TCP ports that only answered after a timed out attempt
+    pub tcp_retried: u32,
+    pub udp_responses: u32,
+    pub endpoint_responses: u32,
+    /// Endpoints that answered on a port the port scan saw as closed
+    pub endpoint_only_ports: u32,
+    /// Answered an ARP request, which counts as one response
+    pub arp_reply: bool,
+    pub has_mac: bool,
+    pub has_hostname: bool,
+}
+
+/// How confidently a scanned host is believed to be up, so a single flaky reply can be told
+/// apart from a solid detection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostLiveness {
+    /// 0-100
+    pub confidence: u8,
+    /// TCP ports, UDP probes, HTTP endpoints and ARP requests that answered
+    pub responding_probes: u32,
+    /// Answers that needed a retry or disagreed with the port scan
+    pub inconsistent_responses: u32,
+    pub measured_at: DateTime<Utc>,
+}
+
+impl HostLiveness {
+    /// Below this a host was likely seen through a single flaky response
+    pub const LOW_CONFIDENCE: u8 = 50;
+
+    pub fn from_evidence(evidence: &LivenessEvidence) -> Self {
+        let responding_probes = evidence.tcp_ports
+            + evidence.udp_responses
+            + evidence.endpoint_responses
+            + evidence.arp_reply as u32;
+        let inconsistent_responses = evidence.tcp_retried + evidence.endpoint_only_ports;
+
+        let confidence = if responding_probes == 0 {
+            0
+        } else {
+            // Each further response adds less: 1 → 40, 2 → 60, 3 → 67, 8+ → 75
+            let mut score = 80 - 40 / responding_probes.min(8) as i32;
+            if evidence.has_mac {
+                score += 20;
+            }
+            if evidence.has_hostname {
+                score += 5;
+            }
+            // Only penalize the share of responses that were inconsistent, so one retried port
+            // on a host with many open ports barely matters
+            score -=
+                (30 * inconsistent_responses.min(responding_probes) / responding_probes) as i32;
+            score.clamp(5, 100)
+        };
+
+        Self {
+            confidence: confidence as u8,
+            responding_probes,
+            inconsistent_responses,
+            measured_at: Utc::now(),
+        }
+    }
+
+    pub fn is_low_confidence(&self) -> bool {
+        self.confidence < Self::LOW_CONFIDENCE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn confidence(evidence: LivenessEvidence) -> u8 {
+        HostLiveness::from_evidence(&evidence).confidence
+    }
+
+    fn ports(tcp_ports: u32) -> LivenessEvidence {
+        LivenessEvidence {
+            tcp_ports,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn confidence_rises_with_responses_and_levels_off() {
+        assert_eq!(confidence(LivenessEvidence::default()), 0);
+        assert_eq!(
+            [1, 2, 3, 8, 50].map(|n| confidence(ports(n))),
+            [40, 60, 67, 75, 75]
+        );
+
+        assert!(HostLiveness::from_evidence(&ports(1)).is_low_confidence());
+        assert!(!HostLiveness::from_evidence(&ports(2)).is_low_confidence());
+    }
+
+    #[test]
+    fn identity_raises_confidence() {
+        let arp_only = LivenessEvidence {
+            arp_reply: true,
+            has_mac: true,
+            ..Default::default()
+        };
+        assert_eq!(confidence(arp_only), 60);
+
+        let named = LivenessEvidence {
+            has_mac: true,
+            has_hostname: true,
+            ..ports(8)
+        };
+        assert_eq!(confidence(named), 100);
+    }
+
+    #[test]
+    fn inconsistency_costs_its_share_of_responses() {
+        let retried = |tcp_retried| LivenessEvidence {
+            tcp_retried,
+            ..ports(10)
+        };
+        assert_eq!(confidence(retried(1)), 72);
+        assert_eq!(confidence(retried(10)), 45);
+
+        // A lone port that needed a retry is barely believed, however inconsistent the rest
+        let flaky = LivenessEvidence {
+            tcp_retried: 1,
+            ..ports(1)
+        };
+        assert_eq!(confidence(flaky), 10);
+        let worst = LivenessEvidence {
+            tcp_retried: 5,
+            endpoint_only_ports: 5,
+            ..ports(1)
+        };
+        assert_eq!(confidence(worst), 10);
+    }
+}
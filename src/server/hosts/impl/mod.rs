@@ -1,8 +1,20 @@
 pub mod api;
 pub mod base;
+pub mod changes;
+pub mod csv;
+pub mod device;
+pub mod dhcp;
 pub mod handlers;
 pub mod interfaces;
+pub mod liveness;
+pub mod os;
 pub mod ports;
+pub mod reachability;
+pub mod service_overrides;
+pub mod snmp;
+pub mod ssh;
 pub mod storage;
 pub mod targets;
 pub mod virtualization;
+pub mod vpn;
+pub mod windows;
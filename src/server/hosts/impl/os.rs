@@ -0,0 +1,352 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::server::{
+    hosts::r#impl::{
+        ports::{PortBase, TransportProtocol},
+        ssh::HostSsh,
+        windows::HostWindows,
+    },
+    services::r#impl::banners::PortBanner,
+};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Display,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OsFamily {
+    Windows,
+    Linux,
+    MacOs,
+    Bsd,
+    Ios,
+    Android,
+    /// Router and switch firmware such as Cisco IOS, Junos or RouterOS
+    NetworkOs,
+    /// Printers, cameras and IoT devices running vendor firmware
+    Embedded,
+}
+
+/// What a network scan observed from one host, for guessing its operating system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsEvidence<'a> {
+    /// TTL of an ICMP echo reply
+    pub ttl: Option<u8>,
+    pub ports: &'a [PortBase],
+    pub banners: &'a [PortBanner],
+    pub mac_vendor: Option<&'a str>,
+    pub ssh: Option<&'a HostSsh>,
+    pub windows: Option<&'a HostWindows>,
+}
+
+/// Estimated operating system of a host, from what a scan could see of it from the outside,
+/// or read from the host itself over SSH or WinRM
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostOs {
+    pub family: OsFamily,
+    /// Distribution or product, e.g. "Ubuntu" or "Windows Server 2022 Standard", when a
+    /// banner or the host itself named it
+    pub name: Option<String>,
+    /// 0-100
+    pub confidence: u8,
+    /// The observations that pointed to the family, e.g. "TTL 128"
+    pub signals: Vec<String>,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Families with a score below this are left unknown
+const MIN_SCORE: i32 = 20;
+
+/// Substrings of banners, matched case-insensitively, that name an operating system
+const BANNER_SIGNS: &[(&str, OsFamily, i32, Option<&str>)] = &[
+    ("openssh_for_windows", OsFamily::Windows, 60, None),
+    ("microsoft-iis", OsFamily::Windows, 40, None),
+    ("microsoft ftp service", OsFamily::Windows, 40, None),
+    ("microsoft esmtp", OsFamily::Windows, 40, None),
+    ("(win64)", OsFamily::Windows, 30, None),
+    ("(win32)", OsFamily::Windows, 30, None),
+    ("ubuntu", OsFamily::Linux, 45, Some("Ubuntu")),
+    ("debian", OsFamily::Linux, 45, Some("Debian")),
+    ("raspbian", OsFamily::Linux, 45, Some("Raspberry Pi OS")),
+    ("centos", OsFamily::Linux, 45, Some("CentOS")),
+    (
+        "red hat",
+        OsFamily::Linux,
+        45,
+        Some("Red Hat Enterprise Linux"),
+    ),
+    ("fedora", OsFamily::Linux, 45, Some("Fedora")),
+    ("alpine", OsFamily::Linux, 40, Some("Alpine Linux")),
+    ("freebsd", OsFamily::Bsd, 50, Some("FreeBSD")),
+    ("openbsd", OsFamily::Bsd, 50, Some("OpenBSD")),
+    ("netbsd", OsFamily::Bsd, 50, Some("NetBSD")),
+    ("cisco", OsFamily::NetworkOs, 50, Some("Cisco IOS")),
+    ("rosssh", OsFamily::NetworkOs, 60, Some("RouterOS")),
+    ("mikrotik", OsFamily::NetworkOs, 50, Some("RouterOS")),
+    ("junos", OsFamily::NetworkOs, 50, Some("Junos")),
+    ("dropbear", OsFamily::Embedded, 25, None),
+];
+
+/// Ports whose combination hints at an operating system
+const PORT_SIGNS: &[(u16, OsFamily, i32)] = &[
+    (135, OsFamily::Windows, 25),
+    (139, OsFamily::Windows, 10),
+    (445, OsFamily::Windows, 15),
+    (3389, OsFamily::Windows, 30),
+    (5985, OsFamily::Windows, 30),
+    (5986, OsFamily::Windows, 30),
+    (22, OsFamily::Linux, 10),
+    (111, OsFamily::Linux, 10),
+    (548, OsFamily::MacOs, 25),
+    (3283, OsFamily::MacOs, 25),
+    (62078, OsFamily::Ios, 50),
+    (5555, OsFamily::Android, 40),
+    (8291, OsFamily::NetworkOs, 50),
+    (9100, OsFamily::Embedded, 30),
+    (515, OsFamily::Embedded, 20),
+    (554, OsFamily::Embedded, 20),
+];
+
+/// Substrings of MAC vendors, matched case-insensitively
+const VENDOR_SIGNS: &[(&str, OsFamily, i32)] = &[
+    ("apple", OsFamily::MacOs, 20),
+    ("apple", OsFamily::Ios, 20),
+    ("raspberry pi", OsFamily::Linux, 30),
+    ("microsoft", OsFamily::Windows, 15),
+    ("cisco", OsFamily::NetworkOs, 30),
+    ("juniper", OsFamily::NetworkOs, 30),
+    ("mikrotik", OsFamily::NetworkOs, 30),
+    ("routerboard", OsFamily::NetworkOs, 30),
+    ("ubiquiti", OsFamily::NetworkOs, 25),
+    ("aruba", OsFamily::NetworkOs, 25),
+    ("arista", OsFamily::NetworkOs, 30),
+    ("espressif", OsFamily::Embedded, 40),
+    ("tuya", OsFamily::Embedded, 40),
+    ("hikvision", OsFamily::Embedded, 40),
+    ("samsung", OsFamily::Android, 15),
+    ("xiaomi", OsFamily::Android, 15),
+    ("google", OsFamily::Android, 15),
+];
+
+#[derive(Default)]
+struct Tally {
+    scores: Vec<(OsFamily, i32, Vec<String>)>,
+    name: Option<(OsFamily, String)>,
+}
+
+impl Tally {
+    fn add(&mut self, family: OsFamily, points: i32, signal: String) {
+        match self.scores.iter_mut().find(|(f, _, _)| *f == family) {
+            Some((_, score, signals)) => {
+                *score += points;
+                if !signals.contains(&signal) {
+                    signals.push(signal);
+                }
+            }
+            None => self.scores.push((family, points, vec![signal])),
+        }
+    }
+}
+
+impl HostOs {
+    pub fn fingerprint(evidence: &OsEvidence) -> Option<Self> {
+        // What the host reported about itself beats anything seen from outside
+        if let Some(windows) = evidence.windows {
+            return Some(Self::reported(
+                OsFamily::Windows,
+                windows.os.clone(),
+                "WinRM inventory",
+            ));
+        }
+        if let Some(ssh) = evidence.ssh {
+            let kernel = ssh.kernel.as_deref().unwrap_or_default().to_lowercase();
+            let family = if kernel.starts_with("darwin") {
+                OsFamily::MacOs
+            } else if kernel.contains("bsd") {
+                OsFamily::Bsd
+            } else {
+                OsFamily::Linux
+            };
+            return Some(Self::reported(family, ssh.os.clone(), "SSH inventory"));
+        }
+
+        let mut tally = Tally::default();
+
+        // Stacks start at 64, 128 or 255 and each router hop takes one off
+        match evidence.ttl {
+            Some(ttl @ 1..=64) => {
+                for family in [OsFamily::Linux, OsFamily::MacOs, OsFamily::Bsd] {
+                    tally.add(family, 15, format!("TTL {}", ttl));
+                }
+                for family in [OsFamily::Ios, OsFamily::Android, OsFamily::Embedded] {
+                    tally.add(family, 10, format!("TTL {}", ttl));
+                }
+            }
+            Some(ttl @ 65..=128) => tally.add(OsFamily::Windows, 30, format!("TTL {}", ttl)),
+            Some(ttl @ 129..) => tally.add(OsFamily::NetworkOs, 30, format!("TTL {}", ttl)),
+            _ => {}
+        }
+
+        for port in evidence.ports {
+            if port.protocol() != TransportProtocol::Tcp {
+                continue;
+            }
+            for (number, family, points) in PORT_SIGNS {
+                if port.number() == *number {
+                    tally.add(*family, *points, format!("TCP port {}", number));
+                }
+            }
+        }
+
+        for banner in evidence.banners {
+            let text = banner.banner.to_lowercase();
+            for (sign, family, points, name) in BANNER_SIGNS {
+                if text.contains(sign) {
+                    let port = banner.port_base.number();
+                    tally.add(*family, *points, format!("Banner on port {}", port));
+                    if let (None, Some(name)) = (&tally.name, name) {
+                        tally.name = Some((*family, name.to_string()));
+                    }
+                }
+            }
+        }
+
+        if let Some(vendor) = evidence.mac_vendor {
+            let lowercase = vendor.to_lowercase();
+            for (sign, family, points) in VENDOR_SIGNS {
+                if lowercase.contains(sign) {
+                    tally.add(*family, *points, format!("MAC vendor {}", vendor));
+                }
+            }
+        }
+
+        tally
+            .scores
+            .sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let mut scores = tally.scores.into_iter();
+        let (family, score, signals) = scores.next()?;
+        if score < MIN_SCORE {
+            return None;
+        }
+
+        // A close runner-up means the evidence doesn't tell the two apart
+        let runner_up = scores.next().map(|(_, s, _)| s).unwrap_or(0);
+        let mut confidence = score.min(90);
+        if score - runner_up < 15 {
+            confidence -= 20;
+        }
+
+        let name = tally
+            .name
+            .filter(|(named, _)| *named == family)
+            .map(|(_, name)| name);
+
+        Some(Self {
+            family,
+            name,
+            confidence: confidence.clamp(5, 90) as u8,
+            signals,
+            detected_at: Utc::now(),
+        })
+    }
+
+    fn reported(family: OsFamily, name: Option<String>, signal: &str) -> Self {
+        Self {
+            family,
+            name,
+            confidence: 100,
+            signals: vec![signal.to_string()],
+            detected_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banner(port_base: PortBase, banner: &str) -> PortBanner {
+        PortBanner {
+            port_base,
+            banner: banner.to_string(),
+        }
+    }
+
+    #[test]
+    fn windows_from_ttl_and_ports() {
+        let ports = [
+            PortBase::new_tcp(135),
+            PortBase::new_tcp(445),
+            PortBase::new_tcp(3389),
+        ];
+        let os = HostOs::fingerprint(&OsEvidence {
+            ttl: Some(127),
+            ports: &ports,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(os.family, OsFamily::Windows);
+        assert_eq!(os.confidence, 90);
+        assert!(os.signals.contains(&"TTL 127".to_string()));
+    }
+
+    #[test]
+    fn banner_names_distribution() {
+        let ports = [PortBase::Ssh];
+        let banners = [banner(
+            PortBase::Ssh,
+            "SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13.5",
+        )];
+        let os = HostOs::fingerprint(&OsEvidence {
+            ttl: Some(63),
+            ports: &ports,
+            banners: &banners,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(os.family, OsFamily::Linux);
+        assert_eq!(os.name.as_deref(), Some("Ubuntu"));
+        assert!(os.confidence >= 60);
+    }
+
+    #[test]
+    fn ttl_alone_is_ambiguous() {
+        let os = HostOs::fingerprint(&OsEvidence {
+            ttl: Some(64),
+            ..Default::default()
+        });
+
+        assert!(os.is_none_or(|os| os.confidence < 50));
+    }
+
+    #[test]
+    fn nothing_observed_is_unknown() {
+        assert!(HostOs::fingerprint(&OsEvidence::default()).is_none());
+    }
+
+    #[test]
+    fn inventory_wins() {
+        let windows = HostWindows {
+            hostname: None,
+            domain: None,
+            os: Some("Microsoft Windows Server 2022 Standard".to_string()),
+            os_version: None,
+            shares: Vec::new(),
+            services: Vec::new(),
+            roles: Vec::new(),
+            collected_at: Utc::now(),
+        };
+        let os = HostOs::fingerprint(&OsEvidence {
+            ttl: Some(64),
+            windows: Some(&windows),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(os.family, OsFamily::Windows);
+        assert_eq!(os.confidence, 100);
+    }
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Whether the host answered the ping monitor of the daemon on its subnet, as of the last change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostReachability {
+    pub reachable: bool,
+    /// Round trip of the last reply, when reachable
+    pub latency_ms: Option<u32>,
+    pub checked_at: DateTime<Utc>,
+    pub daemon_id: Uuid,
+}
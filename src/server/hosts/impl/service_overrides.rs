@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::server::services::definitions::ServiceDefinitionRegistry;
+
+/// Service definitions a user has pinned for a host, applied when discovery reports it again
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HostServiceOverrides {
+    /// Definitions discovery never attaches to the host, whatever their confidence
+    pub suppressed: Vec<String>,
+    /// Definitions attached to the host on every discovery, even if their pattern doesn't match
+    pub forced: Vec<String>,
+}
+
+impl HostServiceOverrides {
+    pub fn is_suppressed(&self, definition_id: &str) -> bool {
+        self.suppressed.iter().any(|id| id == definition_id)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(id) = self
+            .suppressed
+            .iter()
+            .chain(self.forced.iter())
+            .find(|id| ServiceDefinitionRegistry::find_by_id(id).is_none())
+        {
+            return Err(format!("Unknown service definition '{}'", id));
+        }
+
+        if let Some(id) = self.forced.iter().find(|id| self.is_suppressed(id)) {
+            return Err(format!(
+                "Service definition '{}' can't be both forced and suppressed",
+                id
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// What an SNMP agent on the host reported, collected by the daemon when UDP 161 answers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostSnmp {
+    pub sys_name: Option<String>,
+    pub sys_descr: Option<String>,
+    /// Rows of ifTable
+    pub interfaces: Vec<SnmpInterface>,
+    /// Rows of ipNetToMediaTable: which addresses the host has seen on each of its interfaces
+    pub port_map: Vec<SnmpPortMapping>,
+    /// Rows of the LLDP-MIB remote systems table
+    pub lldp_neighbors: Vec<LldpNeighbor>,
+    pub collected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SnmpInterface {
+    /// ifIndex
+    pub index: u32,
+    pub description: Option<String>,
+    pub mac_address: Option<MacAddress>,
+    /// Bits per second, as reported by ifSpeed
+    pub speed: Option<u64>,
+    pub oper_up: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SnmpPortMapping {
+    /// ifIndex of the interface the address was learned on
+    pub if_index: u32,
+    pub ip_address: Ipv4Addr,
+    pub mac_address: MacAddress,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LldpNeighbor {
+    /// lldpLocPortNum the neighbor was seen on
+    pub local_port: u32,
+    pub local_port_description: Option<String>,
+    pub chassis_id: String,
+    pub port_id: String,
+    pub port_description: Option<String>,
+    pub system_name: Option<String>,
+}
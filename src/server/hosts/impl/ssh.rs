@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+use crate::server::hosts::r#impl::ports::TransportProtocol;
+
+/// What the daemon read from the host itself after logging in with one of the network's
+/// SSH credentials
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostSsh {
+    pub hostname: Option<String>,
+    /// PRETTY_NAME of /etc/os-release, e.g. "Debian GNU/Linux 12 (bookworm)"
+    pub os: Option<String>,
+    /// `uname -sr`, e.g. "Linux 6.1.0-18-amd64"
+    pub kernel: Option<String>,
+    /// Sockets `ss` reported listening, including ones a firewall hides from scans
+    pub listening: Vec<ListeningSocket>,
+    /// Running docker containers
+    pub containers: Vec<SshContainer>,
+    pub interfaces: Vec<SshInterface>,
+    /// SHA256 fingerprint of the host key the daemon was presented
+    pub host_key_fingerprint: String,
+    pub collected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ListeningSocket {
+    pub protocol: TransportProtocol,
+    /// Unspecified for sockets bound to every address
+    pub address: IpAddr,
+    pub port: u16,
+    pub process: Option<String>,
+}
+
+impl ListeningSocket {
+    /// Whether the socket can be reached from other hosts
+    pub fn is_exposed(&self) -> bool {
+        !self.address.is_loopback()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SshContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Published ports as docker lists them, e.g. "0.0.0.0:8080->80/tcp"
+    pub ports: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SshInterface {
+    pub name: String,
+    pub mac_address: Option<MacAddress>,
+    /// Addresses with their prefix, e.g. "192.168.1.10/24"
+    pub addresses: Vec<String>,
+}
@@ -7,14 +7,25 @@ use uuid::Uuid;
 use crate::server::{
     hosts::r#impl::{
         base::{Host, HostBase},
+        changes::{HostChange, HostChangeBase},
+        device::HostDevice,
+        dhcp::HostDhcpLease,
         interfaces::Interface,
+        liveness::HostLiveness,
+        os::HostOs,
         ports::Port,
+        reachability::HostReachability,
+        service_overrides::HostServiceOverrides,
+        snmp::HostSnmp,
+        ssh::HostSsh,
         targets::HostTarget,
         virtualization::HostVirtualization,
+        vpn::HostVpnPeer,
+        windows::HostWindows,
     },
     shared::{
         storage::traits::{SqlValue, StorableEntity},
-        types::entities::EntitySource,
+        types::{attributes::EntityAttributes, entities::EntitySource},
     },
 };
 
@@ -25,6 +36,22 @@ impl StorableEntity for Host {
         "hosts"
     }
 
+    fn has_attributes() -> bool {
+        true
+    }
+
+    fn sortable_columns() -> &'static [&'static str] {
+        &["name"]
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "hostname", "description", "interfaces"]
+    }
+
+    fn soft_delete() -> bool {
+        true
+    }
+
     fn get_base(&self) -> Self::BaseData {
         self.base.clone()
     }
@@ -36,6 +63,7 @@ impl StorableEntity for Host {
             id: Uuid::new_v4(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
             base,
         }
     }
@@ -61,6 +89,7 @@ impl StorableEntity for Host {
             id,
             created_at,
             updated_at,
+            deleted_at: _,
             base:
                 Self::BaseData {
                     name,
@@ -74,6 +103,19 @@ impl StorableEntity for Host {
                     services,
                     ports,
                     virtualization,
+                    liveness,
+                    snmp,
+                    ssh,
+                    windows,
+                    os,
+                    service_overrides,
+                    last_seen_at,
+                    stale,
+                    reachability,
+                    vpn_peer,
+                    device,
+                    dhcp_lease,
+                    attributes,
                 },
         } = self.clone();
 
@@ -93,6 +135,19 @@ impl StorableEntity for Host {
                 "ports",
                 "virtualization",
                 "interfaces",
+                "liveness",
+                "snmp",
+                "ssh",
+                "windows",
+                "os",
+                "service_overrides",
+                "last_seen_at",
+                "stale",
+                "reachability",
+                "vpn_peer",
+                "device",
+                "dhcp_lease",
+                "attributes",
             ],
             vec![
                 SqlValue::Uuid(id),
@@ -109,6 +164,19 @@ impl StorableEntity for Host {
                 SqlValue::Ports(ports),
                 SqlValue::OptionalHostVirtualization(virtualization),
                 SqlValue::Interfaces(interfaces),
+                SqlValue::Json(serde_json::to_value(liveness)?),
+                SqlValue::Json(serde_json::to_value(snmp)?),
+                SqlValue::Json(serde_json::to_value(ssh)?),
+                SqlValue::Json(serde_json::to_value(windows)?),
+                SqlValue::Json(serde_json::to_value(os)?),
+                SqlValue::Json(serde_json::to_value(&service_overrides)?),
+                SqlValue::OptionTimestamp(last_seen_at),
+                SqlValue::Bool(stale),
+                SqlValue::Json(serde_json::to_value(reachability)?),
+                SqlValue::Json(serde_json::to_value(vpn_peer)?),
+                SqlValue::Json(serde_json::to_value(device)?),
+                SqlValue::Json(serde_json::to_value(dhcp_lease)?),
+                SqlValue::Json(serde_json::to_value(&attributes)?),
             ],
         ))
     }
@@ -131,11 +199,72 @@ impl StorableEntity for Host {
         let virtualization: Option<HostVirtualization> =
             serde_json::from_value(row.get::<serde_json::Value, _>("virtualization"))
                 .or(Err(Error::msg("Failed to deserialize virtualization")))?;
+        let liveness: Option<HostLiveness> = row
+            .get::<Option<serde_json::Value>, _>("liveness")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize liveness")))?
+            .flatten();
+        let snmp: Option<HostSnmp> = row
+            .get::<Option<serde_json::Value>, _>("snmp")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize snmp")))?
+            .flatten();
+        let ssh: Option<HostSsh> = row
+            .get::<Option<serde_json::Value>, _>("ssh")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize ssh")))?
+            .flatten();
+        let windows: Option<HostWindows> = row
+            .get::<Option<serde_json::Value>, _>("windows")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize windows")))?
+            .flatten();
+        let os: Option<HostOs> = row
+            .get::<Option<serde_json::Value>, _>("os")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize os")))?
+            .flatten();
+        let reachability: Option<HostReachability> = row
+            .get::<Option<serde_json::Value>, _>("reachability")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize reachability")))?
+            .flatten();
+        let vpn_peer: Option<HostVpnPeer> = row
+            .get::<Option<serde_json::Value>, _>("vpn_peer")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize vpn_peer")))?
+            .flatten();
+        let device: Option<HostDevice> = row
+            .get::<Option<serde_json::Value>, _>("device")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize device")))?
+            .flatten();
+        let dhcp_lease: Option<HostDhcpLease> = row
+            .get::<Option<serde_json::Value>, _>("dhcp_lease")
+            .map(serde_json::from_value)
+            .transpose()
+            .or(Err(Error::msg("Failed to deserialize dhcp_lease")))?
+            .flatten();
+        let attributes: EntityAttributes =
+            serde_json::from_value(row.get::<serde_json::Value, _>("attributes"))
+                .or(Err(Error::msg("Failed to deserialize attributes")))?;
+        let service_overrides: HostServiceOverrides =
+            serde_json::from_value(row.get::<serde_json::Value, _>("service_overrides"))
+                .or(Err(Error::msg("Failed to deserialize service_overrides")))?;
 
         Ok(Host {
             id: row.get("id"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            deleted_at: row.get("deleted_at"),
             base: HostBase {
                 name: row.get("name"),
                 description: row.get("description"),
@@ -148,6 +277,121 @@ impl StorableEntity for Host {
                 ports,
                 virtualization,
                 interfaces,
+                liveness,
+                snmp,
+                ssh,
+                windows,
+                os,
+                service_overrides,
+                last_seen_at: row.get("last_seen_at"),
+                stale: row.get("stale"),
+                reachability,
+                vpn_peer,
+                device,
+                dhcp_lease,
+                attributes,
+            },
+        })
+    }
+}
+
+impl StorableEntity for HostChange {
+    type BaseData = HostChangeBase;
+
+    fn table_name() -> &'static str {
+        "host_changes"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    host_id,
+                    network_id,
+                    opened_ports,
+                    closed_ports,
+                    added_services,
+                    removed_services,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "host_id",
+                "network_id",
+                "opened_ports",
+                "closed_ports",
+                "added_services",
+                "removed_services",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(host_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&opened_ports)?),
+                SqlValue::Json(serde_json::to_value(&closed_ports)?),
+                SqlValue::Json(serde_json::to_value(&added_services)?),
+                SqlValue::Json(serde_json::to_value(&removed_services)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let opened_ports: serde_json::Value = row.get("opened_ports");
+        let closed_ports: serde_json::Value = row.get("closed_ports");
+        let added_services: serde_json::Value = row.get("added_services");
+        let removed_services: serde_json::Value = row.get("removed_services");
+
+        Ok(HostChange {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: HostChangeBase {
+                host_id: row.get("host_id"),
+                network_id: row.get("network_id"),
+                opened_ports: serde_json::from_value(opened_ports)?,
+                closed_ports: serde_json::from_value(closed_ports)?,
+                added_services: serde_json::from_value(added_services)?,
+                removed_services: serde_json::from_value(removed_services)?,
             },
         })
     }
@@ -13,6 +13,17 @@ use crate::server::shared::{
 #[serde(tag = "type", content = "details")]
 pub enum HostVirtualization {
     Proxmox(ProxmoxVirtualization),
+    Vmware(VmwareVirtualization),
+}
+
+impl HostVirtualization {
+    /// Hypervisor service the host runs on
+    pub fn service_id(&self) -> Uuid {
+        match self {
+            HostVirtualization::Proxmox(proxmox) => proxmox.service_id,
+            HostVirtualization::Vmware(vmware) => vmware.service_id,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Validate, Deserialize, PartialEq, Eq, Hash)]
@@ -22,6 +33,18 @@ pub struct ProxmoxVirtualization {
     pub service_id: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Validate, Deserialize, PartialEq, Eq, Hash)]
+pub struct VmwareVirtualization {
+    pub vm_name: Option<String>,
+    /// vCenter managed object id, e.g. vm-42
+    pub vm_id: Option<String>,
+    /// ESXi or vCenter service; the ESXi host's when it's known
+    pub service_id: Uuid,
+    /// Port groups the VM's NICs are attached to
+    #[serde(default)]
+    pub port_groups: Vec<String>,
+}
+
 impl HasId for HostVirtualization {
     fn id(&self) -> &'static str {
         self.into()
@@ -39,10 +62,16 @@ impl EntityMetadataProvider for HostVirtualization {
 
 impl TypeMetadataProvider for HostVirtualization {
     fn name(&self) -> &'static str {
-        "Proxmox"
+        match self {
+            HostVirtualization::Proxmox(_) => "Proxmox",
+            HostVirtualization::Vmware(_) => "VMware",
+        }
     }
 
     fn description(&self) -> &'static str {
-        "A host running as a Proxmox VM"
+        match self {
+            HostVirtualization::Proxmox(_) => "A host running as a Proxmox VM",
+            HostVirtualization::Vmware(_) => "A host running as a VMware VM",
+        }
     }
 }
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display)]
+pub enum VpnKind {
+    WireGuard,
+    Tailscale,
+    OpenVpn,
+}
+
+/// What the daemon's VPN tunnel reported about the host as one of its peers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostVpnPeer {
+    pub kind: VpnKind,
+    /// Tunnel interface on the daemon host, e.g. `wg0`
+    pub tunnel_interface: String,
+    /// WireGuard or Tailscale node key
+    pub public_key: Option<String>,
+    /// Address the peer's traffic last arrived from, e.g. `203.0.113.7:51820`
+    pub endpoint: Option<String>,
+    /// Networks routed to the peer through the tunnel
+    pub allowed_ips: Vec<IpCidr>,
+    pub latest_handshake: Option<DateTime<Utc>>,
+    pub collected_at: DateTime<Utc>,
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What Windows reported about itself over WinRM, read by the daemon with one of the
+/// network's WinRM credentials
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HostWindows {
+    pub hostname: Option<String>,
+    /// Active Directory domain, or the workgroup of machines outside one
+    pub domain: Option<String>,
+    /// Caption of Win32_OperatingSystem, e.g. "Microsoft Windows Server 2022 Standard"
+    pub os: Option<String>,
+    /// e.g. "10.0.20348"
+    pub os_version: Option<String>,
+    pub shares: Vec<WindowsShare>,
+    /// Running services
+    pub services: Vec<WindowsService>,
+    /// Installed server roles, e.g. "AD-Domain-Services"; empty on client editions
+    pub roles: Vec<String>,
+    pub collected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowsShare {
+    pub name: String,
+    pub path: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowsService {
+    pub name: String,
+    pub display_name: Option<String>,
+    /// Auto, Manual or Disabled
+    pub start_mode: Option<String>,
+}
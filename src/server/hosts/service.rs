@@ -1,27 +1,61 @@
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::{
-    daemons::service::DaemonService,
-    hosts::r#impl::base::Host,
-    services::{r#impl::base::Service, service::ServiceService},
+    daemons::{
+        r#impl::{
+            api::{MonitorTarget, ReachabilityChange},
+            base::Daemon,
+        },
+        service::DaemonService,
+    },
+    discovery::{r#impl::types::DiscoveryType, service::DiscoveryService},
+    hosts::r#impl::{
+        api::HostEvent,
+        base::Host,
+        changes::{HostChange, HostChangeBase},
+        reachability::HostReachability,
+    },
+    network_settings::{
+        r#impl::base::{ApprovalMode, DiscoveryConflictPolicy, StaleHostAction},
+        service::NetworkSettingsService,
+    },
+    services::{
+        definitions::ServiceDefinitionRegistry,
+        r#impl::{
+            base::{Service, ServiceBase},
+            bindings::Binding,
+            patterns::{MatchConfidence, MatchDetails},
+        },
+        service::ServiceService,
+    },
     shared::{
         services::traits::CrudService,
-        storage::{filter::EntityFilter, generic::GenericPostgresStorage, traits::Storage},
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{Page, StorableEntity, Storage},
+        },
         types::entities::{EntitySource, EntitySourceDiscriminants},
     },
 };
 use anyhow::{Error, Result, anyhow};
 use async_trait::async_trait;
+use chrono::Utc;
 use futures::future::{join_all, try_join_all};
 use itertools::{Either, Itertools};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
 use strum::IntoDiscriminant;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
 use uuid::Uuid;
 
 pub struct HostService {
     storage: Arc<GenericPostgresStorage<Host>>,
+    change_storage: Arc<GenericPostgresStorage<HostChange>>,
     service_service: Arc<ServiceService>,
     daemon_service: Arc<DaemonService>,
+    network_settings_service: Arc<NetworkSettingsService>,
+    discovery_service: Arc<DiscoveryService>,
     host_locks: Arc<Mutex<HashMap<Uuid, Arc<Mutex<()>>>>>,
+    event_tx: broadcast::Sender<HostEvent>,
 }
 
 #[async_trait]
@@ -29,22 +63,47 @@ impl CrudService<Host> for HostService {
     fn storage(&self) -> &Arc<GenericPostgresStorage<Host>> {
         &self.storage
     }
+
+    /// Restore a host along with the services that were deleted with it
+    async fn restore(&self, id: &Uuid) -> Result<Option<Host>> {
+        let Some(host) = self.storage.restore(id).await? else {
+            return Ok(None);
+        };
+
+        for service_id in &host.base.services {
+            self.service_service.restore(service_id).await?;
+        }
+
+        Ok(Some(host))
+    }
 }
 
 impl HostService {
     pub fn new(
         storage: Arc<GenericPostgresStorage<Host>>,
+        change_storage: Arc<GenericPostgresStorage<HostChange>>,
         service_service: Arc<ServiceService>,
         daemon_service: Arc<DaemonService>,
+        network_settings_service: Arc<NetworkSettingsService>,
+        discovery_service: Arc<DiscoveryService>,
     ) -> Self {
         Self {
             storage,
+            change_storage,
             service_service,
             daemon_service,
+            network_settings_service,
+            discovery_service,
             host_locks: Arc::new(Mutex::new(HashMap::new())),
+            event_tx: broadcast::channel(100).0,
         }
     }
 
+    /// Stream of host events, for subscribers that outlive a single request
+    pub fn subscribe(&self) -> broadcast::Receiver<HostEvent> {
+        self.event_tx.subscribe()
+    }
+
     async fn get_host_lock(&self, host_id: &Uuid) -> Arc<Mutex<()>> {
         let mut locks = self.host_locks.lock().await;
         locks
@@ -55,12 +114,30 @@ impl HostService {
 
     pub async fn create_host_with_services(
         &self,
-        host: Host,
-        services: Vec<Service>,
+        mut host: Host,
+        mut services: Vec<Service>,
     ) -> Result<(Host, Vec<Service>)> {
+        if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery {
+            self.drop_excluded_ports(&mut host, &mut services).await?;
+        }
+
         // Create host first (handles duplicates via upsert_host)
         let mut created_host = self.create_host(host.clone()).await?;
 
+        // Overrides live on the stored host, so they're only known once it has been found
+        let services = if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery {
+            let (kept, dropped) = self
+                .filter_discovered_services(&created_host, services)
+                .await?;
+            created_host
+                .base
+                .services
+                .retain(|id| !dropped.contains(id));
+            kept
+        } else {
+            services
+        };
+
         // Create services, handling case where created_host was upserted instead of created anew (ie during discovery), which means that host ID + interfaces/port IDs
         // are different from what's mapped to the service and they need to be updated
         let transfer_service_futures = services.into_iter().map(|service| {
@@ -75,7 +152,14 @@ impl HostService {
             .map(|s| self.service_service.create_service(s))
             .collect();
 
-        let created_services = try_join_all(create_service_futures).await?;
+        let mut created_services = try_join_all(create_service_futures).await?;
+
+        if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery {
+            created_services.extend(
+                self.create_forced_services(&host, &created_host, &created_services)
+                    .await?,
+            );
+        }
 
         // Add all successfully created/found services to the host
         for service in &created_services {
@@ -89,10 +173,510 @@ impl HostService {
         Ok((host_with_final_services, created_services))
     }
 
+    /// Merge a daemon's result for one host into the host it describes, found by MAC or IP, then
+    /// by hostname, and apply the network's conflict policy. `complete_port_scan` means every
+    /// port was probed, so ports missing from `host` are closed.
+    pub async fn reconcile_host(
+        &self,
+        mut host: Host,
+        services: Vec<Service>,
+        complete_port_scan: bool,
+    ) -> Result<(Host, Vec<Service>)> {
+        let policy = self
+            .network_settings_service
+            .for_network(&host.base.network_id)
+            .await?
+            .discovery_conflict_policy;
+
+        let filter = EntityFilter::unfiltered().network_ids(&[host.base.network_id]);
+        let all_hosts = self.storage.get_all(filter).await?;
+
+        // create_host matches on id first, so an existing host found here is always the one
+        // merged. Rescans already carry the id of the host they're for.
+        if !all_hosts.iter().any(|h| h.id == host.id)
+            && let Some(existing) = all_hosts
+                .iter()
+                .find(|h| host.eq(h) || host.is_same_machine(h))
+                .or_else(|| all_hosts.iter().find(|h| host.has_same_hostname(h)))
+        {
+            tracing::debug!("Reconciling discovered host {} with {}", host, existing);
+            host.id = existing.id;
+        }
+
+        let previous = match all_hosts.into_iter().find(|h| h.id == host.id) {
+            Some(previous) => {
+                let services = self
+                    .service_service
+                    .get_all(EntityFilter::unfiltered().host_id(&previous.id))
+                    .await?;
+                Some((previous, services))
+            }
+            None => None,
+        };
+
+        let (mut reconciled, created_services) = self
+            .create_host_with_services(host.clone(), services)
+            .await?;
+
+        if policy == DiscoveryConflictPolicy::PreferDiscovered {
+            if host.base.hostname.is_some() {
+                reconciled.base.hostname = host.base.hostname.clone();
+            }
+
+            if complete_port_scan {
+                self.prune_stale_discovery_data(&mut reconciled, &host, &created_services)
+                    .await?;
+            }
+
+            reconciled = self.update_host(reconciled).await?;
+        }
+
+        if let Some((previous, previous_services)) = previous {
+            self.record_changes(&previous, &previous_services, &reconciled)
+                .await?;
+        }
+
+        Ok((reconciled, created_services))
+    }
+
+    /// Store what a discovery changed about a host's ports and services, and announce newly
+    /// opened ports
+    async fn record_changes(
+        &self,
+        previous: &Host,
+        previous_services: &[Service],
+        host: &Host,
+    ) -> Result<()> {
+        let services = self
+            .service_service
+            .get_all(EntityFilter::unfiltered().host_id(&host.id))
+            .await?;
+
+        let Some(change) = HostChangeBase::between(previous, previous_services, host, &services)
+        else {
+            return Ok(());
+        };
+
+        let opened_ports = change.opened_ports.clone();
+        self.change_storage.create(&HostChange::new(change)).await?;
+
+        if !opened_ports.is_empty() {
+            let _ = self
+                .event_tx
+                .send(HostEvent::PortsOpened(host.clone(), opened_ports));
+        }
+
+        Ok(())
+    }
+
+    /// Port and service changes found by discoveries of a host
+    pub async fn changes(&self, filter: EntityFilter) -> Result<Page<HostChange>> {
+        self.change_storage.get_page(filter).await
+    }
+
+    /// Drop discovered services on the scanned interfaces that weren't matched again, and ports
+    /// that are no longer open and no remaining service binds. Ports are tracked per host rather
+    /// than per interface, so they're only pruned on hosts with a single interface.
+    async fn prune_stale_discovery_data(
+        &self,
+        reconciled: &mut Host,
+        scanned: &Host,
+        matched_services: &[Service],
+    ) -> Result<()> {
+        let scanned_interface_ids: Vec<Uuid> = reconciled
+            .base
+            .interfaces
+            .iter()
+            .filter(|i| scanned.base.interfaces.contains(i))
+            .map(|i| i.id)
+            .collect();
+
+        let existing_services = self
+            .service_service
+            .get_all(EntityFilter::unfiltered().host_id(&reconciled.id))
+            .await?;
+
+        let (stale_services, remaining_services): (Vec<_>, Vec<_>) =
+            existing_services.into_iter().partition(|s| {
+                s.base.source.discriminant() == EntitySourceDiscriminants::DiscoveryWithMatch
+                    && !matched_services.iter().any(|m| m.id == s.id)
+                    && !s.base.bindings.is_empty()
+                    && s.base.bindings.iter().all(|b| {
+                        b.interface_id()
+                            .is_some_and(|id| scanned_interface_ids.contains(&id))
+                    })
+            });
+
+        for service in &stale_services {
+            tracing::info!(
+                "Removing {} from host {}: no longer matched",
+                service,
+                reconciled.id
+            );
+        }
+        reconciled
+            .base
+            .services
+            .retain(|id| !stale_services.iter().any(|s| &s.id == id));
+
+        if reconciled.base.interfaces.len() == 1 {
+            let bound_port_ids: Vec<Uuid> = remaining_services
+                .iter()
+                .flat_map(|s| s.to_bound_port_ids())
+                .collect();
+
+            reconciled.base.ports.retain(|port| {
+                scanned.base.ports.iter().any(|p| p.base == port.base)
+                    || bound_port_ids.contains(&port.id)
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Flag hosts missed by too many completed network scans of their subnets, and hide them if
+    /// their network says so. Returns the number of hosts that became stale.
+    pub async fn expire_stale_hosts(&self) -> Result<usize> {
+        let mut expired = 0;
+
+        for settings in self
+            .network_settings_service
+            .get_all(EntityFilter::unfiltered())
+            .await?
+        {
+            let Some(max_missed) = settings.base.stale_after_scans else {
+                continue;
+            };
+            let network_id = settings.base.network_id;
+
+            let scans = self
+                .discovery_service
+                .completed_network_scans(&network_id)
+                .await?;
+            let filter = EntityFilter::unfiltered().network_ids(&[network_id]);
+
+            for mut host in self.storage.get_all(filter).await? {
+                let Some(last_seen_at) = host.base.last_seen_at else {
+                    continue;
+                };
+                if host.base.stale {
+                    continue;
+                }
+
+                let missed = scans
+                    .iter()
+                    .filter(|scan| scan.finished_at.is_some_and(|f| f > last_seen_at))
+                    .filter(|scan| match &scan.discovery_type {
+                        // Scanned only a range of each subnet, which may not include the host
+                        DiscoveryType::Network { shard: Some(_), .. } => false,
+                        DiscoveryType::Network {
+                            subnet_ids: Some(subnet_ids),
+                            ..
+                        } => host
+                            .base
+                            .interfaces
+                            .iter()
+                            .any(|i| subnet_ids.contains(&i.base.subnet_id)),
+                        _ => true,
+                    })
+                    .count();
+
+                if missed < max_missed as usize {
+                    continue;
+                }
+
+                tracing::info!(
+                    "Host {} is stale: missed {} scans since {}",
+                    host,
+                    missed,
+                    last_seen_at
+                );
+                host.base.stale = true;
+                if settings.base.stale_host_action == StaleHostAction::Hide {
+                    host.base.hidden = true;
+                }
+                self.storage.update(&mut host).await?;
+                expired += 1;
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Addresses of hosts on a daemon's subnets, for its ping monitor
+    pub async fn monitor_targets(&self, daemon: &Daemon) -> Result<Vec<MonitorTarget>> {
+        let subnet_ids = &daemon.base.capabilities.interfaced_subnet_ids;
+        let filter = EntityFilter::unfiltered().network_ids(&[daemon.base.network_id]);
+
+        Ok(self
+            .storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .flat_map(|host| {
+                host.base
+                    .interfaces
+                    .into_iter()
+                    .filter(|i| subnet_ids.contains(&i.base.subnet_id))
+                    .map(move |i| MonitorTarget {
+                        host_id: host.id,
+                        ip: i.base.ip_address,
+                    })
+            })
+            .collect())
+    }
+
+    /// Store reachability changes from a daemon's ping monitor. A reply also counts as the host
+    /// being seen. Changes for hosts outside the daemon's network are dropped.
+    pub async fn record_reachability(
+        &self,
+        daemon: &Daemon,
+        changes: Vec<ReachabilityChange>,
+    ) -> Result<()> {
+        for change in changes {
+            let lock = self.get_host_lock(&change.host_id).await;
+            let _guard = lock.lock().await;
+
+            let Some(mut host) = self
+                .get_by_id(&change.host_id)
+                .await?
+                .filter(|host| host.base.network_id == daemon.base.network_id)
+            else {
+                tracing::debug!(
+                    "Dropping reachability of host {} outside daemon {}'s network",
+                    change.host_id,
+                    daemon.id
+                );
+                continue;
+            };
+            let was_reachable = host.base.reachability.as_ref().map(|r| r.reachable);
+
+            host.base.reachability = Some(HostReachability {
+                reachable: change.reachable,
+                latency_ms: change.latency_ms,
+                checked_at: change.checked_at,
+                daemon_id: daemon.id,
+            });
+            if change.reachable && host.base.last_seen_at < Some(change.checked_at) {
+                host.base.last_seen_at = Some(change.checked_at);
+            }
+
+            tracing::debug!(
+                "Host {} at {} is {}",
+                host,
+                change.ip,
+                if change.reachable { "up" } else { "down" }
+            );
+            self.storage.update(&mut host).await?;
+
+            // No event for the first check, since there's nothing to compare against
+            match (was_reachable, change.reachable) {
+                (Some(true), false) => {
+                    let _ = self.event_tx.send(HostEvent::WentOffline(host));
+                }
+                (Some(false), true) => {
+                    let _ = self.event_tx.send(HostEvent::CameOnline(host));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Daemon to rescan a host's interface on subnet `subnet_id`: the most recent one to discover
+    /// the host that still reaches the subnet, otherwise any daemon on the subnet
+    pub async fn owning_daemon(&self, host: &Host, subnet_id: &Uuid) -> Result<Option<Daemon>> {
+        let daemons = self
+            .daemon_service
+            .get_all(EntityFilter::unfiltered().network_ids(&[host.base.network_id]))
+            .await?;
+
+        let on_subnet: Vec<Daemon> = daemons
+            .into_iter()
+            .filter(|d| {
+                d.base
+                    .capabilities
+                    .interfaced_subnet_ids
+                    .contains(subnet_id)
+            })
+            .collect();
+
+        let discovered_by = match &host.base.source {
+            EntitySource::Discovery { metadata } => metadata.iter().map(|m| m.daemon_id).collect(),
+            _ => Vec::new(),
+        };
+
+        Ok(discovered_by
+            .iter()
+            .find_map(|daemon_id| on_subnet.iter().find(|d| &d.id == daemon_id))
+            .or(on_subnet.first())
+            .cloned())
+    }
+
+    /// Whether the host has an address its network excludes from discovery. Discovered hosts
+    /// that are excluded aren't created.
+    pub async fn is_excluded(&self, host: &Host) -> Result<bool> {
+        Ok(self
+            .network_settings_service
+            .for_network(&host.base.network_id)
+            .await?
+            .exclusions
+            .excludes_host(host))
+    }
+
+    /// Remove discovered ports the network excludes, with the services bound to them
+    async fn drop_excluded_ports(
+        &self,
+        host: &mut Host,
+        services: &mut Vec<Service>,
+    ) -> Result<()> {
+        let exclusions = self
+            .network_settings_service
+            .for_network(&host.base.network_id)
+            .await?
+            .exclusions;
+
+        let excluded: Vec<Uuid> = host
+            .base
+            .ports
+            .iter()
+            .filter(|p| exclusions.excludes_port(p.base.config()))
+            .map(|p| p.id)
+            .collect();
+        if excluded.is_empty() {
+            return Ok(());
+        }
+
+        tracing::debug!(
+            "Dropping {} excluded ports from host {}",
+            excluded.len(),
+            host
+        );
+        host.base.ports.retain(|p| !excluded.contains(&p.id));
+        services.retain(|s| !s.to_bound_port_ids().iter().any(|id| excluded.contains(id)));
+        Ok(())
+    }
+
+    /// Split discovered services into those to keep and the ids of those below the network's
+    /// confidence threshold or suppressed on the host
+    async fn filter_discovered_services(
+        &self,
+        host: &Host,
+        services: Vec<Service>,
+    ) -> Result<(Vec<Service>, Vec<Uuid>)> {
+        let min_confidence = self
+            .network_settings_service
+            .for_network(&host.base.network_id)
+            .await?
+            .min_service_confidence;
+
+        let (kept, dropped): (Vec<_>, Vec<_>) = services.into_iter().partition(|service| {
+            let definition_id = service.base.service_definition.id();
+            if host.base.service_overrides.is_suppressed(definition_id) {
+                tracing::debug!("Skipping {} on host {}: suppressed", service, host.id);
+                return false;
+            }
+
+            match &service.base.source {
+                EntitySource::DiscoveryWithMatch { details, .. }
+                    if details.confidence != MatchConfidence::NotApplicable
+                        && details.confidence < min_confidence =>
+                {
+                    tracing::debug!(
+                        "Skipping {} on host {}: {:?} confidence is below {:?}",
+                        service,
+                        host.id,
+                        details.confidence,
+                        min_confidence
+                    );
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        Ok((kept, dropped.iter().map(|s| s.id).collect()))
+    }
+
+    /// Services for forced definitions the host doesn't have yet, bound to the interface discovery
+    /// reported
+    async fn create_forced_services(
+        &self,
+        discovered_host: &Host,
+        created_host: &Host,
+        created_services: &[Service],
+    ) -> Result<Vec<Service>> {
+        let forced = &created_host.base.service_overrides.forced;
+        if forced.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let Some(interface) = created_host
+            .base
+            .interfaces
+            .iter()
+            .find(|i| discovered_host.base.interfaces.contains(i))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let existing_services = self
+            .service_service
+            .get_all(EntityFilter::unfiltered().host_id(&created_host.id))
+            .await?;
+
+        let metadata = match &discovered_host.base.source {
+            EntitySource::Discovery { metadata } => metadata.clone(),
+            _ => Vec::new(),
+        };
+
+        let mut forced_services = Vec::new();
+        for definition_id in forced {
+            let already_present = existing_services
+                .iter()
+                .chain(created_services.iter())
+                .any(|s| definition_id == s.base.service_definition.id());
+            if already_present {
+                continue;
+            }
+
+            let Some(service_definition) = ServiceDefinitionRegistry::find_by_id(definition_id)
+            else {
+                tracing::warn!(
+                    "Forced service definition '{}' on host {} no longer exists",
+                    definition_id,
+                    created_host.id
+                );
+                continue;
+            };
+
+            let service = Service::new(ServiceBase {
+                host_id: created_host.id,
+                network_id: created_host.base.network_id,
+                name: service_definition.name().to_string(),
+                service_definition,
+                bindings: vec![Binding::new_interface(interface.id)],
+                virtualization: None,
+                source: EntitySource::DiscoveryWithMatch {
+                    metadata: metadata.clone(),
+                    details: MatchDetails::new_certain("Forced by the host's service overrides"),
+                },
+                versions: Vec::new(),
+                vulnerabilities: Vec::new(),
+                attributes: EntityAttributes::default(),
+            });
+
+            forced_services.push(self.service_service.create_service(service).await?);
+        }
+
+        Ok(forced_services)
+    }
+
     /// Create a new host
     pub async fn create_host(&self, host: Host) -> Result<Host> {
         // Manually created and needs actual UUID
-        let host = if host.id == Uuid::nil() {
+        let mut host = if host.id == Uuid::nil() {
             Host::new(host.base.clone())
         } else {
             host
@@ -101,12 +685,25 @@ impl HostService {
         let lock = self.get_host_lock(&host.id).await;
         let _guard = lock.lock().await;
 
+        if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery {
+            host.base.last_seen_at = Some(Utc::now());
+        }
+
         tracing::debug!("Creating host {:?}", host);
 
         let filter = EntityFilter::unfiltered().network_ids(&[host.base.network_id]);
-        let all_hosts = self.storage.get_all(filter).await?;
+        let mut all_hosts = self.storage.get_all(filter).await?;
+
+        // Same id first, as sent by rescans of a known host; then the same interface; otherwise
+        // the same multi-homed machine reported from another subnet, typically by another daemon
+        let existing_host = all_hosts
+            .iter()
+            .position(|h| h.id == host.id)
+            .or_else(|| all_hosts.iter().position(|h| host.eq(h)))
+            .or_else(|| all_hosts.iter().position(|h| host.is_same_machine(h)))
+            .map(|index| all_hosts.swap_remove(index));
 
-        let host_from_storage = match all_hosts.into_iter().find(|h| host.eq(h)) {
+        let host_from_storage = match existing_host {
             // If both are from discovery, or if they have the same ID, upsert data
             Some(existing_host)
                 if (host.base.source.discriminant() == EntitySourceDiscriminants::Discovery
@@ -125,9 +722,25 @@ impl HostService {
                 self.upsert_host(existing_host, host).await?
             }
             _ => {
+                // Hosts awaiting approval stay hidden until a user unhides them
+                if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery
+                    && self
+                        .network_settings_service
+                        .for_network(&host.base.network_id)
+                        .await?
+                        .approval_mode
+                        == ApprovalMode::Manual
+                {
+                    host.base.hidden = true;
+                }
+
                 self.storage.create(&host).await?;
                 tracing::info!("Created host {}: {}", host.base.name, host.id);
                 tracing::debug!("Result: {:?}", host);
+
+                if host.base.source.discriminant() == EntitySourceDiscriminants::Discovery {
+                    let _ = self.event_tx.send(HostEvent::Discovered(host.clone()));
+                }
                 host
             }
         };
@@ -162,6 +775,7 @@ impl HostService {
         let mut port_updates = 0;
         let mut hostname_update = false;
         let mut description_update = false;
+        let mut name_update = false;
 
         tracing::debug!(
             "Upserting new host data {:?} to host {:?}",
@@ -192,17 +806,86 @@ impl HostService {
         existing_host.base.services =
             [existing_host.base.services, new_host_data.base.services].concat();
 
+        // A single flaky response is kept as liveness but doesn't count as seeing the host
+        let low_confidence = new_host_data
+            .base
+            .liveness
+            .is_some_and(|liveness| liveness.is_low_confidence());
+
+        let has_hostname = new_host_data.base.hostname.is_some();
+
         // Update other fields if they have more information
         if existing_host.base.hostname.is_none() && new_host_data.base.hostname.is_some() {
             hostname_update = true;
             existing_host.base.hostname = new_host_data.base.hostname;
         }
 
+        // Hosts named after their IP take the first real name discovered for them, though not
+        // a service name guessed from a low confidence sighting
+        if existing_host.base.name.parse::<IpAddr>().is_ok()
+            && new_host_data.base.name.parse::<IpAddr>().is_err()
+            && (!low_confidence || has_hostname)
+        {
+            name_update = true;
+            existing_host.base.name = new_host_data.base.name;
+        }
+
         if existing_host.base.description.is_none() && new_host_data.base.description.is_some() {
             description_update = true;
             existing_host.base.description = new_host_data.base.description;
         }
 
+        // Liveness reflects the latest scan rather than the best one
+        if new_host_data.base.liveness.is_some() {
+            existing_host.base.liveness = new_host_data.base.liveness;
+        }
+
+        if new_host_data.base.snmp.is_some() {
+            existing_host.base.snmp = new_host_data.base.snmp;
+        }
+
+        if new_host_data.base.ssh.is_some() {
+            existing_host.base.ssh = new_host_data.base.ssh;
+        }
+
+        if new_host_data.base.windows.is_some() {
+            existing_host.base.windows = new_host_data.base.windows;
+        }
+
+        if new_host_data.base.os.is_some() {
+            existing_host.base.os = new_host_data.base.os;
+        }
+
+        if new_host_data.base.vpn_peer.is_some() {
+            existing_host.base.vpn_peer = new_host_data.base.vpn_peer;
+        }
+
+        if new_host_data.base.device.is_some() {
+            existing_host.base.device = new_host_data.base.device;
+        }
+
+        if new_host_data.base.dhcp_lease.is_some() {
+            existing_host.base.dhcp_lease = new_host_data.base.dhcp_lease;
+        }
+
+        if !low_confidence && new_host_data.base.last_seen_at > existing_host.base.last_seen_at {
+            existing_host.base.last_seen_at = new_host_data.base.last_seen_at;
+
+            // Stale hosts were hidden by expire_stale_hosts, so they come back when seen again
+            if existing_host.base.stale {
+                existing_host.base.stale = false;
+                if self
+                    .network_settings_service
+                    .for_network(&existing_host.base.network_id)
+                    .await?
+                    .stale_host_action
+                    == StaleHostAction::Hide
+                {
+                    existing_host.base.hidden = false;
+                }
+            }
+        }
+
         // Update entity source for new discovery session data
         existing_host.base.source = match (existing_host.base.source, new_host_data.base.source) {
             (
@@ -247,6 +930,9 @@ impl HostService {
         if hostname_update {
             data.push("new hostname".to_string())
         }
+        if name_update {
+            data.push("new name".to_string())
+        }
         if description_update {
             data.push("new description".to_string())
         }
@@ -0,0 +1,125 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::dns::{
+        r#impl::{
+            api::{DnsSyncReport, DnsZoneSettingsRequest},
+            base::DnsZoneSettings,
+        },
+        service::DnsZoneService,
+    },
+    services::r#impl::base::Service,
+    shared::{
+        handlers::traits::get_accessible,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{service_id}/settings", get(get_settings))
+        .route("/{service_id}/settings", put(set_settings))
+        .route("/{service_id}/settings", delete(delete_settings))
+        .route("/{service_id}/sync", post(sync))
+}
+
+/// A DNS server service in one of the user's networks
+async fn dns_service(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    service_id: Uuid,
+) -> ApiResult<Service> {
+    let service: Service = get_accessible(state, &user.0, &service_id).await?;
+
+    if !DnsZoneService::is_dns_server(&service) {
+        return Err(ApiError::bad_request(&format!(
+            "Service '{}' isn't a DNS server",
+            service.base.name
+        )));
+    }
+
+    Ok(service)
+}
+
+async fn get_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DnsZoneSettings>>> {
+    dns_service(&state, &user, service_id).await?;
+
+    let settings = state
+        .services
+        .dns_zone_service
+        .get_settings(&service_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No DNS zones configured for service '{}'",
+                service_id
+            ))
+        })?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+/// Store the server address and zones to read
+async fn set_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+    Json(request): Json<DnsZoneSettingsRequest>,
+) -> ApiResult<Json<ApiResponse<DnsZoneSettings>>> {
+    let service = dns_service(&state, &user, service_id).await?;
+
+    if request.zones.iter().all(|z| z.trim().is_empty()) && !request.reverse_zones {
+        return Err(ApiError::bad_request(
+            "Configure at least one forward zone, or enable reverse zones",
+        ));
+    }
+
+    let settings = state
+        .services
+        .dns_zone_service
+        .set_settings(&service, request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+async fn delete_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    dns_service(&state, &user, service_id).await?;
+
+    state
+        .services
+        .dns_zone_service
+        .delete_settings(&service_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Read the server's zones and name the hosts they cover
+async fn sync(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<DnsSyncReport>>> {
+    dns_service(&state, &user, service_id).await?;
+
+    let report = state.services.dns_zone_service.sync(&service_id).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
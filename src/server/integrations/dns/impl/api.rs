@@ -0,0 +1,45 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Zones to read from a bind9, PowerDNS, Unbound or other DNS service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsZoneSettingsRequest {
+    #[serde(default)]
+    pub server: Option<String>,
+    #[serde(default)]
+    pub zones: Vec<String>,
+    #[serde(default = "default_reverse_zones")]
+    pub reverse_zones: bool,
+}
+
+fn default_reverse_zones() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DnsSyncReport {
+    pub zones: Vec<DnsZoneTransfer>,
+    /// Addresses answered one PTR query at a time, for reverse zones that refused a transfer
+    pub queried: usize,
+    /// Addresses with a name after the sync
+    pub names: usize,
+    /// Hosts that were given a name from the zones
+    pub renamed: Vec<DnsHostName>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsZoneTransfer {
+    pub zone: String,
+    pub records: usize,
+    /// Why the transfer failed, e.g. the server refused it
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsHostName {
+    pub host_id: Uuid,
+    pub ip: IpAddr,
+    pub name: String,
+}
@@ -0,0 +1,39 @@
+use std::{collections::BTreeMap, fmt::Display, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Zones to read from a DNS service, and the names they held at the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsZoneSettingsBase {
+    pub service_id: Uuid,
+    pub network_id: Uuid,
+    /// Address and port to query, e.g. 10.0.0.53:53; the service's first interface when unset
+    pub server: Option<String>,
+    /// Forward zones to transfer, e.g. lab.example.com
+    pub zones: Vec<String>,
+    /// Also read the in-addr.arpa zones of the network's IPv4 subnets
+    pub reverse_zones: bool,
+    pub names: BTreeMap<IpAddr, String>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsZoneSettings {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: DnsZoneSettingsBase,
+}
+
+impl Display for DnsZoneSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DNS zone settings for service {}: {}",
+            self.base.service_id, self.id
+        )
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api;
+pub mod base;
+pub mod storage;
+pub mod zone;
@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, net::IpAddr};
+
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    integrations::dns::r#impl::base::{DnsZoneSettings, DnsZoneSettingsBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for DnsZoneSettings {
+    type BaseData = DnsZoneSettingsBase;
+
+    fn table_name() -> &'static str {
+        "dns_zone_settings"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    service_id,
+                    network_id,
+                    server,
+                    zones,
+                    reverse_zones,
+                    names,
+                    last_synced_at,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "service_id",
+                "network_id",
+                "server",
+                "zones",
+                "reverse_zones",
+                "names",
+                "last_synced_at",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(service_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::OptionalString(server),
+                SqlValue::Json(serde_json::to_value(&zones)?),
+                SqlValue::Bool(reverse_zones),
+                SqlValue::Json(serde_json::to_value(&names)?),
+                SqlValue::OptionTimestamp(last_synced_at),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let zones: Vec<String> = serde_json::from_value(row.get::<serde_json::Value, _>("zones"))
+            .or(Err(Error::msg("Failed to deserialize zones")))?;
+        let names: BTreeMap<IpAddr, String> =
+            serde_json::from_value(row.get::<serde_json::Value, _>("names"))
+                .or(Err(Error::msg("Failed to deserialize names")))?;
+
+        Ok(DnsZoneSettings {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: DnsZoneSettingsBase {
+                service_id: row.get("service_id"),
+                network_id: row.get("network_id"),
+                server: row.get("server"),
+                zones,
+                reverse_zones: row.get("reverse_zones"),
+                names,
+                last_synced_at: row.get("last_synced_at"),
+            },
+        })
+    }
+}
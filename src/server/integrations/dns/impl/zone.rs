@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use anyhow::Result;
+use cidr::IpCidr;
+use trust_dns_resolver::proto::{
+    op::{Message, MessageType, OpCode, Query},
+    rr::{Name, RData, Record, RecordType},
+    serialize::binary::BinEncodable,
+};
+
+/// Subnets of this prefix or longer are read as the /24 zones they span, larger ones as the
+/// zone of their /16 or /8
+const MIN_SPANNED_PREFIX: u32 = 22;
+
+/// A zone transfer (AXFR) of the zone, or a query for one name and type
+pub fn query(name: &str, record_type: RecordType, id: u16) -> Result<Vec<u8>> {
+    let mut message = Message::new();
+    message
+        .set_id(id)
+        .set_message_type(MessageType::Query)
+        .set_op_code(OpCode::Query)
+        .set_recursion_desired(record_type != RecordType::AXFR)
+        .add_query(Query::query(fqdn(name)?, record_type));
+
+    Ok(message.to_bytes()?)
+}
+
+fn fqdn(name: &str) -> Result<Name> {
+    let mut name = Name::from_ascii(name.trim_end_matches('.'))?;
+    name.set_fqdn(true);
+    Ok(name)
+}
+
+/// in-addr.arpa zones holding the PTR records of an IPv4 subnet. Reverse zones are
+/// delegated on octet boundaries, so a /22 is four /24 zones and a /20 lives in its /16.
+pub fn reverse_zones(cidr: &IpCidr) -> Vec<String> {
+    let IpCidr::V4(cidr) = cidr else {
+        return Vec::new();
+    };
+    let first = u32::from(cidr.first_address());
+    let prefix = cidr.network_length() as u32;
+
+    let (zone_prefix, count) = match prefix {
+        24.. => (24, 1),
+        p if p >= MIN_SPANNED_PREFIX => (24, 1 << (24 - p)),
+        16..=23 => (16, 1),
+        8..=15 => (8, 1),
+        _ => return Vec::new(),
+    };
+
+    (0..count)
+        .map(|index| {
+            let network =
+                (first & (u32::MAX << (32 - zone_prefix))) + (index << (32 - zone_prefix));
+            let octets = Ipv4Addr::from(network).octets();
+            let mut labels: Vec<String> = octets[..(zone_prefix / 8) as usize]
+                .iter()
+                .rev()
+                .map(u8::to_string)
+                .collect();
+            labels.push("in-addr.arpa".to_string());
+            labels.join(".")
+        })
+        .collect()
+}
+
+pub fn ptr_name(ip: Ipv4Addr) -> String {
+    let [a, b, c, d] = ip.octets();
+    format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+}
+
+/// Address of an in-addr.arpa name; other reverse names aren't read
+pub fn ptr_ip(name: &str) -> Option<IpAddr> {
+    let labels: Vec<&str> = name
+        .trim_end_matches('.')
+        .strip_suffix(".in-addr.arpa")?
+        .split('.')
+        .collect();
+    if labels.len() != 4 {
+        return None;
+    }
+
+    let mut octets = [0u8; 4];
+    for (octet, label) in octets.iter_mut().zip(labels.iter().rev()) {
+        *octet = label.parse().ok()?;
+    }
+    Some(IpAddr::V4(Ipv4Addr::from(octets)))
+}
+
+/// Names read from zone records. PTR records are what an address is meant to be called,
+/// so they win over A and AAAA records naming the same address.
+#[derive(Debug, Default)]
+pub struct ZoneNames {
+    reverse: BTreeMap<IpAddr, String>,
+    forward: BTreeMap<IpAddr, String>,
+}
+
+impl ZoneNames {
+    pub fn add(&mut self, record: &Record) {
+        let (ip, name, names) = match record.data() {
+            Some(RData::A(a)) => (IpAddr::V4(a.0), record.name(), &mut self.forward),
+            Some(RData::AAAA(aaaa)) => (IpAddr::V6(aaaa.0), record.name(), &mut self.forward),
+            Some(RData::PTR(ptr)) => {
+                let Some(ip) = ptr_ip(&record.name().to_ascii()) else {
+                    return;
+                };
+                (ip, &ptr.0, &mut self.reverse)
+            }
+            _ => return,
+        };
+
+        if name.is_wildcard() {
+            return;
+        }
+        let name = name.to_ascii().trim_end_matches('.').to_lowercase();
+        if !name.is_empty() {
+            names.entry(ip).or_insert(name);
+        }
+    }
+
+    pub fn into_names(self) -> BTreeMap<IpAddr, String> {
+        let mut names = self.forward;
+        names.extend(self.reverse);
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cidr::Ipv4Cidr;
+    use trust_dns_resolver::proto::rr::rdata::{A, PTR};
+
+    fn cidr(ip: [u8; 4], prefix: u8) -> IpCidr {
+        IpCidr::V4(Ipv4Cidr::new(Ipv4Addr::from(ip), prefix).unwrap())
+    }
+
+    #[test]
+    fn reverse_zones_follow_octet_boundaries() {
+        assert_eq!(
+            reverse_zones(&cidr([10, 1, 2, 0], 24)),
+            vec!["2.1.10.in-addr.arpa"]
+        );
+        assert_eq!(
+            reverse_zones(&cidr([10, 1, 2, 64], 26)),
+            vec!["2.1.10.in-addr.arpa"]
+        );
+        assert_eq!(
+            reverse_zones(&cidr([10, 1, 4, 0], 22)),
+            vec![
+                "4.1.10.in-addr.arpa",
+                "5.1.10.in-addr.arpa",
+                "6.1.10.in-addr.arpa",
+                "7.1.10.in-addr.arpa"
+            ]
+        );
+        assert_eq!(
+            reverse_zones(&cidr([172, 16, 0, 0], 20)),
+            vec!["16.172.in-addr.arpa"]
+        );
+        assert_eq!(
+            reverse_zones(&cidr([10, 0, 0, 0], 8)),
+            vec!["10.in-addr.arpa"]
+        );
+    }
+
+    #[test]
+    fn ptr_names_round_trip() {
+        let ip = Ipv4Addr::new(192, 168, 1, 20);
+        assert_eq!(ptr_name(ip), "20.1.168.192.in-addr.arpa");
+        assert_eq!(ptr_ip(&format!("{}.", ptr_name(ip))), Some(IpAddr::V4(ip)));
+        assert_eq!(ptr_ip("1.168.192.in-addr.arpa."), None);
+    }
+
+    #[test]
+    fn ptr_records_win_over_address_records() {
+        let ip = Ipv4Addr::new(10, 0, 0, 5);
+        let mut names = ZoneNames::default();
+        names.add(&Record::from_rdata(
+            Name::from_ascii("www.lab.example.").unwrap(),
+            300,
+            RData::A(A(ip)),
+        ));
+        names.add(&Record::from_rdata(
+            Name::from_ascii("*.lab.example.").unwrap(),
+            300,
+            RData::A(A(Ipv4Addr::new(10, 0, 0, 6))),
+        ));
+        names.add(&Record::from_rdata(
+            Name::from_ascii("5.0.0.10.in-addr.arpa.").unwrap(),
+            300,
+            RData::PTR(PTR(Name::from_ascii("NAS.lab.example.").unwrap())),
+        ));
+
+        let names = names.into_names();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[&IpAddr::V4(ip)], "nas.lab.example");
+    }
+}
@@ -0,0 +1,384 @@
+use crate::server::{
+    hosts::service::HostService,
+    integrations::dns::r#impl::{
+        api::{DnsHostName, DnsSyncReport, DnsZoneSettingsRequest, DnsZoneTransfer},
+        base::{DnsZoneSettings, DnsZoneSettingsBase},
+        zone::{self, ZoneNames},
+    },
+    services::{
+        r#impl::{base::Service, categories::ServiceCategory},
+        service::ServiceService,
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    subnets::{r#impl::base::Subnet, service::SubnetService},
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+use cidr::IpCidr;
+use futures::{StreamExt, stream};
+use rand::Rng;
+use std::{
+    collections::BTreeSet,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket, lookup_host},
+    time::timeout,
+};
+use trust_dns_resolver::proto::{
+    op::{Message, ResponseCode},
+    rr::{Record, RecordType},
+};
+use uuid::Uuid;
+
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(30);
+const QUERY_TIMEOUT: Duration = Duration::from_millis(800);
+const QUERY_CONCURRENCY: usize = 32;
+/// Subnets with more addresses than this aren't queried address by address when their
+/// reverse zone can't be transferred
+const MAX_QUERIED_ADDRESSES: u32 = 1024;
+
+/// Reads the zones of a DNS service, by transfer where the server allows it and PTR queries
+/// where it doesn't, so hosts are named from what the network's DNS says they're called.
+/// The names are also sent to daemons with each scan, ahead of their own reverse lookups.
+pub struct DnsZoneService {
+    storage: Arc<GenericPostgresStorage<DnsZoneSettings>>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    subnet_service: Arc<SubnetService>,
+}
+
+impl DnsZoneService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<DnsZoneSettings>>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        subnet_service: Arc<SubnetService>,
+    ) -> Self {
+        Self {
+            storage,
+            host_service,
+            service_service,
+            subnet_service,
+        }
+    }
+
+    /// Whether the service is a DNS server, e.g. bind9, PowerDNS or Unbound
+    pub fn is_dns_server(service: &Service) -> bool {
+        service.base.service_definition.category() == ServiceCategory::DNS
+    }
+
+    pub async fn get_settings(&self, service_id: &Uuid) -> Result<Option<DnsZoneSettings>> {
+        self.storage
+            .get_one(EntityFilter::unfiltered().service_id(service_id))
+            .await
+    }
+
+    /// Store the zones to read from a service, keeping the names of the last sync
+    pub async fn set_settings(
+        &self,
+        service: &Service,
+        request: DnsZoneSettingsRequest,
+    ) -> Result<DnsZoneSettings> {
+        let zones: BTreeSet<String> = request
+            .zones
+            .iter()
+            .map(|zone| zone.trim().trim_end_matches('.').to_lowercase())
+            .filter(|zone| !zone.is_empty())
+            .collect();
+
+        let base = DnsZoneSettingsBase {
+            service_id: service.id,
+            network_id: service.base.network_id,
+            server: request
+                .server
+                .map(|server| server.trim().to_string())
+                .filter(|server| !server.is_empty()),
+            zones: zones.into_iter().collect(),
+            reverse_zones: request.reverse_zones,
+            names: Default::default(),
+            last_synced_at: None,
+        };
+
+        match self.get_settings(&service.id).await? {
+            Some(mut existing) => {
+                existing.base = DnsZoneSettingsBase {
+                    names: existing.base.names,
+                    last_synced_at: existing.base.last_synced_at,
+                    ..base
+                };
+                self.storage.update(&mut existing).await
+            }
+            None => self.storage.create(&DnsZoneSettings::new(base)).await,
+        }
+    }
+
+    pub async fn delete_settings(&self, service_id: &Uuid) -> Result<()> {
+        let settings = self
+            .get_settings(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No DNS zones configured for service '{}'", service_id))?;
+        self.storage.delete(&settings.id).await
+    }
+
+    /// Transfer the configured forward zones and the reverse zones of the network's subnets,
+    /// then name hosts that have no hostname, or are named after their address
+    pub async fn sync(&self, service_id: &Uuid) -> Result<DnsSyncReport> {
+        let mut settings = self
+            .get_settings(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No DNS zones configured for service '{}'", service_id))?;
+        let service = self
+            .service_service
+            .get_by_id(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
+
+        let server = match &settings.base.server {
+            Some(server) => resolve_server(server).await?,
+            None => {
+                let host = self
+                    .host_service
+                    .get_by_id(&service.base.host_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Host '{}' not found", service.base.host_id))?;
+                let interface = host
+                    .base
+                    .interfaces
+                    .first()
+                    .ok_or_else(|| anyhow!("DNS server has no interface to query"))?;
+                SocketAddr::new(interface.base.ip_address, 53)
+            }
+        };
+
+        let filter = EntityFilter::unfiltered().network_ids(&[service.base.network_id]);
+        let mut report = DnsSyncReport::default();
+        let mut names = ZoneNames::default();
+
+        for zone in &settings.base.zones {
+            transfer_into(server, zone, &mut names, &mut report).await;
+        }
+
+        if settings.base.reverse_zones {
+            let subnets = self.subnet_service.get_all(filter.clone()).await?;
+            let zones: BTreeSet<String> = subnets
+                .iter()
+                .flat_map(|s| zone::reverse_zones(&s.base.cidr))
+                .collect();
+
+            for zone in &zones {
+                transfer_into(server, zone, &mut names, &mut report).await;
+            }
+
+            let refused: BTreeSet<String> = report
+                .zones
+                .iter()
+                .filter(|t| t.error.is_some())
+                .map(|t| t.zone.clone())
+                .collect();
+            for subnet in &subnets {
+                if zone::reverse_zones(&subnet.base.cidr)
+                    .iter()
+                    .any(|zone| refused.contains(zone))
+                {
+                    report.queried += query_subnet(server, subnet, &mut names).await;
+                }
+            }
+        }
+
+        let names = names.into_names();
+
+        for mut host in self.host_service.get_all(filter).await? {
+            let Some((ip, name)) = host
+                .base
+                .interfaces
+                .iter()
+                .find_map(|i| names.get_key_value(&i.base.ip_address))
+            else {
+                continue;
+            };
+
+            let named_after_ip = host
+                .base
+                .interfaces
+                .iter()
+                .any(|i| host.base.name == i.base.ip_address.to_string());
+            if host.base.hostname.is_some() && !named_after_ip {
+                continue;
+            }
+
+            if host.base.hostname.is_none() {
+                host.base.hostname = Some(name.clone());
+            }
+            if named_after_ip {
+                host.base.name = name.clone();
+            }
+
+            let host = self.host_service.update_host(host).await?;
+            report.renamed.push(DnsHostName {
+                host_id: host.id,
+                ip: *ip,
+                name: name.clone(),
+            });
+        }
+
+        report.names = names.len();
+        settings.base.names = names;
+        settings.base.last_synced_at = Some(Utc::now());
+        self.storage.update(&mut settings).await?;
+
+        tracing::info!(
+            "DNS sync for service {}: {} names, {} hosts renamed",
+            service.id,
+            report.names,
+            report.renamed.len()
+        );
+
+        Ok(report)
+    }
+}
+
+/// Address and port of a server given as an address, a name, or either with a port
+async fn resolve_server(server: &str) -> Result<SocketAddr> {
+    if let Ok(ip) = server.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, 53));
+    }
+
+    let target = match server.contains(':') {
+        true => server.to_string(),
+        false => format!("{}:53", server),
+    };
+    lookup_host(&target)
+        .await?
+        .next()
+        .ok_or_else(|| anyhow!("Could not resolve DNS server '{}'", server))
+}
+
+async fn transfer_into(
+    server: SocketAddr,
+    zone: &str,
+    names: &mut ZoneNames,
+    report: &mut DnsSyncReport,
+) {
+    let transfer = match transfer(server, zone).await {
+        Ok(records) => {
+            records.iter().for_each(|record| names.add(record));
+            DnsZoneTransfer {
+                zone: zone.to_string(),
+                records: records.len(),
+                error: None,
+            }
+        }
+        Err(e) => {
+            tracing::debug!("Transfer of {} from {} failed: {}", zone, server, e);
+            DnsZoneTransfer {
+                zone: zone.to_string(),
+                records: 0,
+                error: Some(e.to_string()),
+            }
+        }
+    };
+    report.zones.push(transfer);
+}
+
+/// AXFR over TCP. The transfer starts and ends with the zone's SOA record and may span
+/// several messages, each prefixed by its length.
+async fn transfer(server: SocketAddr, zone: &str) -> Result<Vec<Record>> {
+    timeout(TRANSFER_TIMEOUT, async {
+        let id: u16 = rand::rng().random();
+        let request = zone::query(zone, RecordType::AXFR, id)?;
+
+        let mut stream = TcpStream::connect(server).await?;
+        stream
+            .write_all(&(request.len() as u16).to_be_bytes())
+            .await?;
+        stream.write_all(&request).await?;
+
+        let mut records = Vec::new();
+        let mut soa_records = 0;
+        while soa_records < 2 {
+            let mut length = [0u8; 2];
+            stream.read_exact(&mut length).await?;
+            let mut buffer = vec![0u8; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut buffer).await?;
+
+            let message = Message::from_vec(&buffer)?;
+            if message.id() != id {
+                bail!("Reply doesn't match the transfer request");
+            }
+            if message.response_code() != ResponseCode::NoError {
+                bail!("Server answered {}", message.response_code());
+            }
+            if message.answers().is_empty() {
+                bail!("Server ended the transfer early");
+            }
+
+            for record in message.answers() {
+                if record.record_type() == RecordType::SOA {
+                    soa_records += 1;
+                }
+                records.push(record.clone());
+            }
+        }
+
+        Ok(records)
+    })
+    .await
+    .map_err(|_| anyhow!("Transfer timed out"))?
+}
+
+/// PTR query for every address of a small IPv4 subnet, for servers that refuse transfers.
+/// Returns how many addresses had a name.
+async fn query_subnet(server: SocketAddr, subnet: &Subnet, names: &mut ZoneNames) -> usize {
+    let IpCidr::V4(cidr) = subnet.base.cidr else {
+        return 0;
+    };
+    let host_bits = 32 - cidr.network_length() as u32;
+    if host_bits > MAX_QUERIED_ADDRESSES.ilog2() {
+        return 0;
+    }
+
+    let first = u32::from(cidr.first_address());
+    let answers: Vec<Vec<Record>> = stream::iter(0..1u32 << host_bits)
+        .map(|offset| query_ptr(server, Ipv4Addr::from(first + offset)))
+        .buffer_unordered(QUERY_CONCURRENCY)
+        .filter_map(|answer| async move { answer.ok().filter(|records| !records.is_empty()) })
+        .collect()
+        .await;
+
+    for records in &answers {
+        records.iter().for_each(|record| names.add(record));
+    }
+    answers.len()
+}
+
+async fn query_ptr(server: SocketAddr, ip: Ipv4Addr) -> Result<Vec<Record>> {
+    let id: u16 = rand::rng().random();
+    let request = zone::query(&zone::ptr_name(ip), RecordType::PTR, id)?;
+
+    let local: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.send_to(&request, server).await?;
+
+    let mut buffer = [0u8; 512];
+    let (length, _) = timeout(QUERY_TIMEOUT, socket.recv_from(&mut buffer)).await??;
+    let message = Message::from_vec(&buffer[..length])?;
+    if message.id() != id {
+        bail!("Reply doesn't match the query");
+    }
+
+    Ok(message.answers().to_vec())
+}
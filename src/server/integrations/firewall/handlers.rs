@@ -0,0 +1,133 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::firewall::{
+        r#impl::{
+            api::{FirewallCredentialsRequest, FirewallSyncQuery, FirewallSyncReport},
+            base::FirewallCredentials,
+            tables::FirewallPlatform,
+        },
+        service::FirewallService,
+    },
+    services::r#impl::base::Service,
+    shared::{
+        handlers::traits::get_accessible,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{service_id}/credentials", get(get_credentials))
+        .route("/{service_id}/credentials", put(set_credentials))
+        .route("/{service_id}/credentials", delete(delete_credentials))
+        .route("/{service_id}/sync", post(sync))
+}
+
+/// An OPNsense or pfSense service in one of the user's networks
+async fn firewall_service(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    service_id: Uuid,
+) -> ApiResult<(Service, FirewallPlatform)> {
+    let service: Service = get_accessible(state, &user.0, &service_id).await?;
+
+    let Some(platform) = FirewallService::platform(&service) else {
+        return Err(ApiError::bad_request(&format!(
+            "Service '{}' isn't an OPNsense or pfSense service",
+            service.base.name
+        )));
+    };
+
+    Ok((service, platform))
+}
+
+async fn get_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<FirewallCredentials>>> {
+    firewall_service(&state, &user, service_id).await?;
+
+    let credentials = state
+        .services
+        .firewall_service
+        .get_credentials(&service_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No firewall credentials for service '{}'",
+                service_id
+            ))
+        })?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+/// Store the API key used to read the firewall's tables
+async fn set_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+    Json(request): Json<FirewallCredentialsRequest>,
+) -> ApiResult<Json<ApiResponse<FirewallCredentials>>> {
+    let (service, platform) = firewall_service(&state, &user, service_id).await?;
+
+    let has_secret = request.api_secret.as_ref().is_some_and(|s| !s.is_empty());
+    if request.api_key.is_empty() || (platform == FirewallPlatform::OpnSense && !has_secret) {
+        return Err(ApiError::bad_request(match platform {
+            FirewallPlatform::OpnSense => "API key and secret are required",
+            FirewallPlatform::PfSense => "API key is required",
+        }));
+    }
+
+    let credentials = state
+        .services
+        .firewall_service
+        .set_credentials(&service, request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+async fn delete_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    firewall_service(&state, &user, service_id).await?;
+
+    state
+        .services
+        .firewall_service
+        .delete_credentials(&service_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Read the firewall's leases and ARP table and link each address to its host
+async fn sync(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+    Query(query): Query<FirewallSyncQuery>,
+) -> ApiResult<Json<ApiResponse<FirewallSyncReport>>> {
+    firewall_service(&state, &user, service_id).await?;
+
+    let report = state
+        .services
+        .firewall_service
+        .sync(&service_id, query.scan_new_hosts)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -0,0 +1,79 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::integrations::firewall::r#impl::tables::FirewallPlatform;
+
+/// API credentials to store for an OPNsense or pfSense service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallCredentialsRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    pub api_key: String,
+    #[serde(default)]
+    pub api_secret: Option<String>,
+    #[serde(default)]
+    pub verify_tls: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FirewallSyncQuery {
+    /// Queue a rescan of each new host, so its ports and services are found without scanning
+    /// whole subnets
+    #[serde(default)]
+    pub scan_new_hosts: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallSyncReport {
+    pub platform: FirewallPlatform,
+    /// Known hosts the firewall knew an address of
+    pub linked: Vec<FirewallNeighborLink>,
+    /// Hosts created for addresses in known subnets that weren't known yet
+    pub created: Vec<FirewallNeighborLink>,
+    /// Addresses outside every known subnet
+    pub skipped: Vec<IpAddr>,
+    pub vlans: Vec<FirewallVlanLink>,
+    pub aliases: Vec<FirewallAliasLink>,
+    /// Rescan sessions queued for the created hosts
+    pub session_ids: Vec<Uuid>,
+}
+
+impl FirewallSyncReport {
+    pub fn new(platform: FirewallPlatform) -> Self {
+        Self {
+            platform,
+            linked: Vec::new(),
+            created: Vec::new(),
+            skipped: Vec::new(),
+            vlans: Vec::new(),
+            aliases: Vec::new(),
+            session_ids: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallNeighborLink {
+    pub ip: IpAddr,
+    pub hostname: Option<String>,
+    pub host_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallVlanLink {
+    pub tag: u16,
+    pub interface: String,
+    pub description: Option<String>,
+    /// Subnets whose addresses were seen on the VLAN
+    pub subnet_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallAliasLink {
+    pub name: String,
+    pub kind: String,
+    /// Known hosts with an address listed in the alias
+    pub host_ids: Vec<Uuid>,
+}
@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// API credentials for an OPNsense or pfSense service, used to read its leases and ARP table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallCredentialsBase {
+    pub service_id: Uuid,
+    pub network_id: Uuid,
+    /// Base URL of the web UI, e.g. https://fw.lab; the service's first interface when unset
+    pub url: Option<String>,
+    #[serde(serialize_with = "serialize_api_key")]
+    pub api_key: String,
+    /// OPNsense authenticates with a key and secret; the pfSense REST API package with the
+    /// key alone
+    #[serde(serialize_with = "serialize_api_secret")]
+    pub api_secret: Option<String>,
+    /// Firewalls usually serve a self-signed certificate, so it isn't verified by default
+    pub verify_tls: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+fn serialize_api_key<S>(_api_key: &String, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("***REDACTED***")
+}
+
+fn serialize_api_secret<S>(api_secret: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match api_secret {
+        Some(_) => serializer.serialize_str("***REDACTED***"),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallCredentials {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: FirewallCredentialsBase,
+}
+
+impl Display for FirewallCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Firewall credentials for service {}: {}",
+            self.base.service_id, self.id
+        )
+    }
+}
@@ -0,0 +1,320 @@
+use std::net::IpAddr;
+
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+pub enum FirewallPlatform {
+    #[strum(serialize = "OPNsense")]
+    OpnSense,
+    /// Through the pfSense REST API package, since pfSense has no API of its own
+    #[strum(serialize = "pfSense")]
+    PfSense,
+}
+
+impl FirewallPlatform {
+    pub fn leases_path(&self) -> &'static str {
+        match self {
+            FirewallPlatform::OpnSense => "/api/dhcpv4/leases/searchLease",
+            FirewallPlatform::PfSense => "/api/v2/status/dhcp_server/leases",
+        }
+    }
+
+    pub fn arp_path(&self) -> &'static str {
+        match self {
+            FirewallPlatform::OpnSense => "/api/diagnostics/interface/search_arp",
+            FirewallPlatform::PfSense => "/api/v2/diagnostics/arp_table",
+        }
+    }
+
+    pub fn aliases_path(&self) -> &'static str {
+        match self {
+            FirewallPlatform::OpnSense => "/api/firewall/alias/searchItem",
+            FirewallPlatform::PfSense => "/api/v2/firewall/aliases",
+        }
+    }
+
+    pub fn vlans_path(&self) -> &'static str {
+        match self {
+            FirewallPlatform::OpnSense => "/api/interfaces/vlan_settings/searchItem",
+            FirewallPlatform::PfSense => "/api/v2/interface/vlans",
+        }
+    }
+}
+
+/// An address the firewall knows about, from its DHCP leases, its ARP table or both
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallNeighbor {
+    pub ip: IpAddr,
+    pub mac: Option<MacAddress>,
+    pub hostname: Option<String>,
+    /// Device the address was seen on, e.g. igb0 or vlan01
+    pub interface: Option<String>,
+    /// Whether the address was handed out by the firewall's DHCP server
+    pub leased: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallAlias {
+    pub name: String,
+    /// host, network, port, url...
+    pub kind: String,
+    pub entries: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallVlan {
+    /// Device of the VLAN, e.g. vlan01 on OPNsense or igb0.20 on pfSense
+    pub interface: String,
+    pub parent: String,
+    pub tag: u16,
+    pub description: Option<String>,
+}
+
+/// OPNsense wraps search results in `rows`, the pfSense REST API every response in `data`.
+/// Older OPNsense endpoints return a bare array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Rows<T> {
+    OpnSense { rows: Vec<T> },
+    PfSense { data: Vec<T> },
+    Bare(Vec<T>),
+}
+
+impl<T> Rows<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Rows::OpnSense { rows } => rows,
+            Rows::PfSense { data } => data,
+            Rows::Bare(rows) => rows,
+        }
+    }
+}
+
+/// The APIs report missing values as empty strings
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+/// Add or merge a neighbor by IP, keeping what the first source knew
+pub fn merge_neighbor(neighbors: &mut Vec<FirewallNeighbor>, neighbor: FirewallNeighbor) {
+    match neighbors.iter_mut().find(|n| n.ip == neighbor.ip) {
+        Some(existing) => {
+            existing.mac = existing.mac.or(neighbor.mac);
+            existing.hostname = existing.hostname.take().or(neighbor.hostname);
+            existing.interface = existing.interface.take().or(neighbor.interface);
+            existing.leased |= neighbor.leased;
+        }
+        None => neighbors.push(neighbor),
+    }
+}
+
+#[derive(Deserialize)]
+struct Lease {
+    #[serde(alias = "ip")]
+    address: IpAddr,
+    mac: Option<String>,
+    hostname: Option<String>,
+}
+
+/// DHCP leases, added to `neighbors`
+pub fn parse_leases(
+    body: &str,
+    neighbors: &mut Vec<FirewallNeighbor>,
+) -> Result<(), serde_json::Error> {
+    let leases: Rows<Lease> = serde_json::from_str(body)?;
+
+    for lease in leases.into_vec() {
+        merge_neighbor(
+            neighbors,
+            FirewallNeighbor {
+                ip: lease.address,
+                mac: lease.mac.and_then(|mac| mac.parse().ok()),
+                hostname: non_empty(lease.hostname),
+                interface: None,
+                leased: true,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ArpEntry {
+    #[serde(alias = "ip_address")]
+    ip: IpAddr,
+    #[serde(alias = "mac_address")]
+    mac: Option<String>,
+    hostname: Option<String>,
+    #[serde(alias = "interface")]
+    intf: Option<String>,
+}
+
+/// ARP table entries, added to `neighbors`. Incomplete entries have no MAC and are skipped.
+pub fn parse_arp_table(
+    body: &str,
+    neighbors: &mut Vec<FirewallNeighbor>,
+) -> Result<(), serde_json::Error> {
+    let entries: Rows<ArpEntry> = serde_json::from_str(body)?;
+
+    for entry in entries.into_vec() {
+        let Some(mac) = entry.mac.and_then(|mac| mac.parse::<MacAddress>().ok()) else {
+            continue;
+        };
+        merge_neighbor(
+            neighbors,
+            FirewallNeighbor {
+                ip: entry.ip,
+                mac: Some(mac),
+                // OPNsense shows "?" when reverse lookup fails
+                hostname: non_empty(entry.hostname).filter(|h| h != "?"),
+                interface: non_empty(entry.intf),
+                leased: false,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Alias {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    /// OPNsense: entries separated by newlines or commas
+    content: Option<String>,
+    /// pfSense
+    address: Option<Vec<String>>,
+}
+
+pub fn parse_aliases(body: &str) -> Result<Vec<FirewallAlias>, serde_json::Error> {
+    let aliases: Rows<Alias> = serde_json::from_str(body)?;
+
+    Ok(aliases
+        .into_vec()
+        .into_iter()
+        .map(|alias| {
+            let entries = match (alias.address, alias.content) {
+                (Some(address), _) => address,
+                (None, Some(content)) => content
+                    .split(['\n', ','])
+                    .map(|e| e.trim().to_string())
+                    .filter(|e| !e.is_empty())
+                    .collect(),
+                (None, None) => Vec::new(),
+            };
+            FirewallAlias {
+                name: alias.name,
+                kind: alias.kind,
+                entries,
+            }
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct Vlan {
+    #[serde(rename = "if")]
+    parent: String,
+    /// A string on OPNsense, a number on pfSense
+    tag: Value,
+    vlanif: Option<String>,
+    descr: Option<String>,
+}
+
+pub fn parse_vlans(body: &str) -> Result<Vec<FirewallVlan>, serde_json::Error> {
+    let vlans: Rows<Vlan> = serde_json::from_str(body)?;
+
+    Ok(vlans
+        .into_vec()
+        .into_iter()
+        .filter_map(|vlan| {
+            let tag = match &vlan.tag {
+                Value::Number(n) => u16::try_from(n.as_u64()?).ok()?,
+                Value::String(s) => s.parse().ok()?,
+                _ => return None,
+            };
+            Some(FirewallVlan {
+                interface: non_empty(vlan.vlanif)
+                    .unwrap_or_else(|| format!("{}.{}", vlan.parent, tag)),
+                parent: vlan.parent,
+                tag,
+                description: non_empty(vlan.descr),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_opnsense_tables() {
+        let mut neighbors = Vec::new();
+        parse_leases(
+            r#"{"total": 1, "rowCount": 1, "current": 1, "rows": [
+                {"address": "192.168.20.10", "mac": "aa:bb:cc:00:00:01", "hostname": "printer", "if": "opt1", "state": "active"}
+            ]}"#,
+            &mut neighbors,
+        )
+        .unwrap();
+        parse_arp_table(
+            r#"{"total": 2, "rows": [
+                {"mac": "aa:bb:cc:00:00:01", "ip": "192.168.20.10", "intf": "vlan01", "hostname": "?", "expired": false},
+                {"mac": "(incomplete)", "ip": "192.168.20.99", "intf": "vlan01", "hostname": ""}
+            ]}"#,
+            &mut neighbors,
+        )
+        .unwrap();
+
+        assert_eq!(neighbors.len(), 1);
+        assert!(neighbors[0].leased);
+        assert_eq!(neighbors[0].hostname.as_deref(), Some("printer"));
+        assert_eq!(neighbors[0].interface.as_deref(), Some("vlan01"));
+
+        let aliases = parse_aliases(
+            r#"{"rows": [{"name": "cameras", "type": "host", "content": "192.168.20.30\n192.168.20.31", "description": ""}]}"#,
+        )
+        .unwrap();
+        assert_eq!(aliases[0].entries.len(), 2);
+
+        let vlans = parse_vlans(
+            r#"{"rows": [{"if": "igb0", "tag": "20", "pcp": "0", "descr": "IoT", "vlanif": "vlan01"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(vlans[0].tag, 20);
+        assert_eq!(vlans[0].interface, "vlan01");
+    }
+
+    #[test]
+    fn parses_pfsense_tables() {
+        let mut neighbors = Vec::new();
+        parse_arp_table(
+            r#"{"code": 200, "status": "ok", "data": [
+                {"hostname": "nas.home.arpa", "ip_address": "192.168.1.5", "mac_address": "aa:bb:cc:00:00:05", "interface": "igb1", "type": "ethernet"}
+            ]}"#,
+            &mut neighbors,
+        )
+        .unwrap();
+        assert_eq!(neighbors[0].hostname.as_deref(), Some("nas.home.arpa"));
+        assert!(!neighbors[0].leased);
+
+        let aliases = parse_aliases(
+            r#"{"code": 200, "data": [{"name": "servers", "type": "host", "address": ["192.168.1.5"], "descr": ""}]}"#,
+        )
+        .unwrap();
+        assert_eq!(aliases[0].entries, vec!["192.168.1.5"]);
+
+        let vlans = parse_vlans(
+            r#"{"code": 200, "data": [{"if": "igb1", "tag": 30, "vlanif": "igb1.30"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(vlans[0].tag, 30);
+        assert_eq!(vlans[0].parent, "igb1");
+    }
+}
@@ -0,0 +1,517 @@
+use crate::server::{
+    discovery::{
+        r#impl::{
+            base::{Discovery, DiscoveryBase},
+            types::{DiscoveryType, RunType},
+        },
+        service::DiscoveryService,
+    },
+    hosts::{
+        r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+        },
+        service::HostService,
+    },
+    integrations::{
+        firewall::r#impl::{
+            api::{
+                FirewallAliasLink, FirewallCredentialsRequest, FirewallNeighborLink,
+                FirewallSyncReport, FirewallVlanLink,
+            },
+            base::{FirewallCredentials, FirewallCredentialsBase},
+            tables::{
+                FirewallAlias, FirewallNeighbor, FirewallPlatform, FirewallVlan, parse_aliases,
+                parse_arp_table, parse_leases, parse_vlans,
+            },
+        },
+        guests::find_guest_host,
+    },
+    network_settings::service::NetworkSettingsService,
+    services::{
+        definitions::{opn_sense::OpnSense, pf_sense::PfSense},
+        r#impl::base::Service,
+        service::ServiceService,
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+        types::{
+            entities::{DiscoveryMetadata, EntitySource},
+            metadata::HasId,
+        },
+    },
+    subnets::{r#impl::base::Subnet, service::SubnetService},
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use uuid::Uuid;
+
+/// Reads the DHCP leases, ARP table, aliases and VLANs of an OPNsense or pfSense service. The
+/// firewall already knows every address on the networks it routes, so hosts are created from
+/// its tables rather than found by sweeping subnets.
+pub struct FirewallService {
+    storage: Arc<GenericPostgresStorage<FirewallCredentials>>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    subnet_service: Arc<SubnetService>,
+    discovery_service: Arc<DiscoveryService>,
+    network_settings_service: Arc<NetworkSettingsService>,
+}
+
+impl FirewallService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<FirewallCredentials>>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        subnet_service: Arc<SubnetService>,
+        discovery_service: Arc<DiscoveryService>,
+        network_settings_service: Arc<NetworkSettingsService>,
+    ) -> Self {
+        Self {
+            storage,
+            host_service,
+            service_service,
+            subnet_service,
+            discovery_service,
+            network_settings_service,
+        }
+    }
+
+    /// Platform of an OPNsense or pfSense service
+    pub fn platform(service: &Service) -> Option<FirewallPlatform> {
+        let definition_id = service.base.service_definition.id();
+        if definition_id == OpnSense.id() {
+            Some(FirewallPlatform::OpnSense)
+        } else if definition_id == PfSense.id() {
+            Some(FirewallPlatform::PfSense)
+        } else {
+            None
+        }
+    }
+
+    pub async fn get_credentials(&self, service_id: &Uuid) -> Result<Option<FirewallCredentials>> {
+        self.storage
+            .get_one(EntityFilter::unfiltered().service_id(service_id))
+            .await
+    }
+
+    /// Store the API credentials for a service, replacing any previous ones
+    pub async fn set_credentials(
+        &self,
+        service: &Service,
+        request: FirewallCredentialsRequest,
+    ) -> Result<FirewallCredentials> {
+        let base = FirewallCredentialsBase {
+            service_id: service.id,
+            network_id: service.base.network_id,
+            url: request
+                .url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            api_key: request.api_key,
+            api_secret: request.api_secret.filter(|secret| !secret.is_empty()),
+            verify_tls: request.verify_tls,
+            last_synced_at: None,
+        };
+
+        match self.get_credentials(&service.id).await? {
+            Some(mut existing) => {
+                existing.base = FirewallCredentialsBase {
+                    last_synced_at: existing.base.last_synced_at,
+                    ..base
+                };
+                self.storage.update(&mut existing).await
+            }
+            None => self.storage.create(&FirewallCredentials::new(base)).await,
+        }
+    }
+
+    pub async fn delete_credentials(&self, service_id: &Uuid) -> Result<()> {
+        let credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No firewall credentials for service '{}'", service_id))?;
+        self.storage.delete(&credentials.id).await
+    }
+
+    /// Link each address the firewall knows to its host, by MAC, then IP, then name, and create
+    /// hosts for the rest that fall in known subnets. VLAN tags are filled in on the subnets
+    /// whose addresses were seen on a VLAN interface.
+    pub async fn sync(
+        &self,
+        service_id: &Uuid,
+        scan_new_hosts: bool,
+    ) -> Result<FirewallSyncReport> {
+        let mut credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No firewall credentials for service '{}'", service_id))?;
+        let service = self
+            .service_service
+            .get_by_id(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
+        let platform = Self::platform(&service)
+            .ok_or_else(|| anyhow!("Service '{}' isn't OPNsense or pfSense", service_id))?;
+        let firewall_host = self
+            .host_service
+            .get_by_id(&service.base.host_id)
+            .await?
+            .ok_or_else(|| anyhow!("Host '{}' not found", service.base.host_id))?;
+
+        let url = match &credentials.base.url {
+            Some(url) => url.clone(),
+            None => {
+                let interface = firewall_host
+                    .base
+                    .interfaces
+                    .first()
+                    .ok_or_else(|| anyhow!("Firewall has no interface to reach the API on"))?;
+                format!("https://{}", url_host(interface.base.ip_address))
+            }
+        };
+
+        let client = FirewallClient::new(&url, platform, &credentials.base)?;
+        let (neighbors, aliases, vlans) = client.tables().await?;
+
+        let filter = EntityFilter::unfiltered().network_ids(&[service.base.network_id]);
+        let mut hosts = self.host_service.get_all(filter.clone()).await?;
+        let mut subnets = self.subnet_service.get_all(filter).await?;
+
+        let mut report = FirewallSyncReport::new(platform);
+
+        for vlan in vlans {
+            let link = self.apply_vlan(&vlan, &neighbors, &mut subnets).await?;
+            report.vlans.push(link);
+        }
+
+        // Hosts are attributed to a daemon on their subnet, so rescans have one to run on
+        let mut daemon_ids: HashMap<Uuid, Option<Uuid>> = HashMap::new();
+
+        for neighbor in neighbors {
+            if firewall_host
+                .base
+                .interfaces
+                .iter()
+                .any(|i| i.base.ip_address == neighbor.ip)
+            {
+                continue;
+            }
+
+            let Some(subnet) = subnets.iter().find(|s| s.base.cidr.contains(&neighbor.ip)) else {
+                report.skipped.push(neighbor.ip);
+                continue;
+            };
+
+            let macs: Vec<_> = neighbor.mac.into_iter().collect();
+            if let Some(index) = find_guest_host(
+                neighbor.hostname.as_deref(),
+                &macs,
+                &[neighbor.ip],
+                &hosts,
+                &[firewall_host.id],
+            ) {
+                let mut host = hosts[index].clone();
+                let mut changed = false;
+
+                if let Some(interface) = host
+                    .base
+                    .interfaces
+                    .iter_mut()
+                    .find(|i| i.base.ip_address == neighbor.ip)
+                    && interface.base.mac_address.is_none()
+                    && neighbor.mac.is_some()
+                {
+                    interface.base.mac_address = neighbor.mac;
+                    changed = true;
+                }
+                if host.base.hostname.is_none() && neighbor.hostname.is_some() {
+                    host.base.hostname = neighbor.hostname.clone();
+                    changed = true;
+                }
+
+                if changed {
+                    hosts[index] = self.host_service.update_host(host).await?;
+                }
+                report.linked.push(FirewallNeighborLink {
+                    ip: neighbor.ip,
+                    hostname: neighbor.hostname,
+                    host_id: hosts[index].id,
+                });
+                continue;
+            }
+
+            let daemon_id = match daemon_ids.get(&subnet.id) {
+                Some(daemon_id) => *daemon_id,
+                None => {
+                    let daemon_id = self
+                        .host_service
+                        .owning_daemon(&firewall_host, &subnet.id)
+                        .await?
+                        .map(|d| d.id);
+                    daemon_ids.insert(subnet.id, daemon_id);
+                    daemon_id
+                }
+            };
+            let Some(daemon_id) = daemon_id else {
+                report.skipped.push(neighbor.ip);
+                continue;
+            };
+
+            let host = self
+                .host_service
+                .create_host(Host::new(HostBase {
+                    name: neighbor
+                        .hostname
+                        .clone()
+                        .unwrap_or_else(|| neighbor.ip.to_string()),
+                    hostname: neighbor.hostname.clone(),
+                    network_id: service.base.network_id,
+                    description: Some(match neighbor.leased {
+                        true => format!("DHCP lease from {}", platform),
+                        false => format!("ARP entry on {}", platform),
+                    }),
+                    interfaces: vec![Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: neighbor.ip,
+                        mac_address: neighbor.mac,
+                        name: None,
+                    })],
+                    source: EntitySource::Discovery {
+                        metadata: vec![DiscoveryMetadata::new(
+                            DiscoveryType::Firewall {
+                                service_id: service.id,
+                            },
+                            daemon_id,
+                        )],
+                    },
+                    ..HostBase::default()
+                }))
+                .await?;
+
+            if scan_new_hosts {
+                let session_id = self.queue_rescan(&host, daemon_id).await?;
+                report.session_ids.push(session_id);
+            }
+
+            report.created.push(FirewallNeighborLink {
+                ip: neighbor.ip,
+                hostname: neighbor.hostname,
+                host_id: host.id,
+            });
+            hosts.push(host);
+        }
+
+        report.aliases = link_aliases(aliases, &hosts);
+
+        credentials.base.last_synced_at = Some(Utc::now());
+        self.storage.update(&mut credentials).await?;
+
+        tracing::info!(
+            "{} sync for service {}: {} linked, {} created, {} skipped",
+            platform,
+            service.id,
+            report.linked.len(),
+            report.created.len(),
+            report.skipped.len()
+        );
+
+        Ok(report)
+    }
+
+    /// Tag the subnets seen on a VLAN interface, unless a daemon already reported their tag
+    async fn apply_vlan(
+        &self,
+        vlan: &FirewallVlan,
+        neighbors: &[FirewallNeighbor],
+        subnets: &mut [Subnet],
+    ) -> Result<FirewallVlanLink> {
+        let mut subnet_ids = Vec::new();
+
+        for neighbor in neighbors
+            .iter()
+            .filter(|n| n.interface.as_deref() == Some(vlan.interface.as_str()))
+        {
+            let Some(subnet) = subnets
+                .iter_mut()
+                .find(|s| s.base.cidr.contains(&neighbor.ip))
+            else {
+                continue;
+            };
+            if subnet_ids.contains(&subnet.id) {
+                continue;
+            }
+            subnet_ids.push(subnet.id);
+
+            if subnet.base.layer2.vlan_id.is_none() {
+                subnet.base.layer2.vlan_id = Some(vlan.tag);
+                subnet.base.layer2.parent_interface = Some(vlan.parent.clone());
+                *subnet = self.subnet_service.update(subnet).await?;
+            }
+        }
+
+        Ok(FirewallVlanLink {
+            tag: vlan.tag,
+            interface: vlan.interface.clone(),
+            description: vlan.description.clone(),
+            subnet_ids,
+        })
+    }
+
+    async fn queue_rescan(&self, host: &Host, daemon_id: Uuid) -> Result<Uuid> {
+        let interface = host
+            .base
+            .interfaces
+            .first()
+            .ok_or_else(|| anyhow!("Host '{}' has no interface to scan", host.id))?;
+        let host_naming_fallback = self
+            .network_settings_service
+            .for_network(&host.base.network_id)
+            .await?
+            .host_naming_fallback;
+
+        let session = self
+            .discovery_service
+            .start_session(Discovery::new(DiscoveryBase {
+                discovery_type: DiscoveryType::HostRescan {
+                    host_id: host.id,
+                    subnet_id: interface.base.subnet_id,
+                    ip: interface.base.ip_address,
+                    host_naming_fallback,
+                },
+                run_type: RunType::AdHoc {
+                    last_run: Some(Utc::now()),
+                },
+                name: format!("Rescan @ {}", interface.base.ip_address),
+                daemon_id,
+                network_id: host.base.network_id,
+            }))
+            .await?;
+
+        Ok(session.session_id)
+    }
+}
+
+/// Known hosts behind each alias entry that is an address
+fn link_aliases(aliases: Vec<FirewallAlias>, hosts: &[Host]) -> Vec<FirewallAliasLink> {
+    aliases
+        .into_iter()
+        .map(|alias| {
+            let host_ids = alias
+                .entries
+                .iter()
+                .filter_map(|entry| entry.parse::<IpAddr>().ok())
+                .filter_map(|ip| {
+                    hosts
+                        .iter()
+                        .find(|h| h.base.interfaces.iter().any(|i| i.base.ip_address == ip))
+                        .map(|h| h.id)
+                })
+                .collect();
+            FirewallAliasLink {
+                name: alias.name,
+                kind: alias.kind,
+                host_ids,
+            }
+        })
+        .collect()
+}
+
+fn url_host(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{}]", ip),
+    }
+}
+
+struct FirewallClient {
+    client: reqwest::Client,
+    url: String,
+    platform: FirewallPlatform,
+    api_key: String,
+    api_secret: Option<String>,
+}
+
+impl FirewallClient {
+    fn new(
+        url: &str,
+        platform: FirewallPlatform,
+        credentials: &FirewallCredentialsBase,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(!credentials.verify_tls)
+            .build()?;
+
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            platform,
+            api_key: credentials.api_key.clone(),
+            api_secret: credentials.api_secret.clone(),
+        })
+    }
+
+    async fn get(&self, path: &str) -> Result<String> {
+        let request = self.client.get(format!("{}{}", self.url, path));
+        // OPNsense takes the key and secret as basic auth
+        let request = match self.platform {
+            FirewallPlatform::OpnSense => {
+                request.basic_auth(&self.api_key, self.api_secret.as_ref())
+            }
+            FirewallPlatform::PfSense => request.header("X-API-Key", &self.api_key),
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            bail!(
+                "{} API {} returned HTTP {}",
+                self.platform,
+                path,
+                response.status()
+            );
+        }
+
+        Ok(response.text().await?)
+    }
+
+    /// Leases and ARP entries merged by address, then aliases and VLANs. Leases, aliases and
+    /// VLANs are optional: the DHCP server may be off or not the one the API reads (e.g. Kea
+    /// on OPNsense), and the key may lack the privileges.
+    async fn tables(
+        &self,
+    ) -> Result<(Vec<FirewallNeighbor>, Vec<FirewallAlias>, Vec<FirewallVlan>)> {
+        let mut neighbors = Vec::new();
+
+        match self.get(self.platform.leases_path()).await {
+            Ok(body) => parse_leases(&body, &mut neighbors)?,
+            Err(e) => tracing::warn!("Failed to read DHCP leases: {}", e),
+        }
+
+        let body = self.get(self.platform.arp_path()).await?;
+        parse_arp_table(&body, &mut neighbors)?;
+
+        let aliases = match self.get(self.platform.aliases_path()).await {
+            Ok(body) => parse_aliases(&body)?,
+            Err(e) => {
+                tracing::warn!("Failed to read firewall aliases: {}", e);
+                Vec::new()
+            }
+        };
+
+        let vlans = match self.get(self.platform.vlans_path()).await {
+            Ok(body) => parse_vlans(&body)?,
+            Err(e) => {
+                tracing::warn!("Failed to read VLANs: {}", e);
+                Vec::new()
+            }
+        };
+
+        Ok((neighbors, aliases, vlans))
+    }
+}
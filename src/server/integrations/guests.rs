@@ -0,0 +1,51 @@
+use std::net::IpAddr;
+
+use mac_address::MacAddress;
+use uuid::Uuid;
+
+use crate::server::hosts::r#impl::base::Host;
+
+/// Index of the host a hypervisor's guest runs as: by MAC, then IP, then name. Hypervisor
+/// hosts are skipped so a guest sharing a name with its node isn't linked to it.
+pub fn find_guest_host(
+    name: Option<&str>,
+    macs: &[MacAddress],
+    ips: &[IpAddr],
+    hosts: &[Host],
+    hypervisor_host_ids: &[Uuid],
+) -> Option<usize> {
+    let candidates = || {
+        hosts
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| !hypervisor_host_ids.contains(&h.id))
+    };
+
+    candidates()
+        .find(|(_, h)| {
+            h.base
+                .interfaces
+                .iter()
+                .filter_map(|i| i.base.mac_address)
+                .any(|mac| macs.contains(&mac))
+        })
+        .or_else(|| {
+            candidates().find(|(_, h)| {
+                h.base
+                    .interfaces
+                    .iter()
+                    .any(|i| ips.contains(&i.base.ip_address))
+            })
+        })
+        .or_else(|| {
+            let name = name?;
+            candidates().find(|(_, h)| {
+                h.base.name.eq_ignore_ascii_case(name)
+                    || h.base
+                        .hostname
+                        .as_deref()
+                        .is_some_and(|hostname| hostname.eq_ignore_ascii_case(name))
+            })
+        })
+        .map(|(index, _)| index)
+}
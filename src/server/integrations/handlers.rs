@@ -0,0 +1,23 @@
+use crate::server::{
+    config::AppState,
+    integrations::{
+        dns::handlers as dns_handlers, firewall::handlers as firewall_handlers,
+        home_assistant::handlers as home_assistant_handlers, ipam::handlers as ipam_handlers,
+        prometheus::handlers as prometheus_handlers, proxmox::handlers as proxmox_handlers,
+        uptime_kuma::handlers as uptime_kuma_handlers, vmware::handlers as vmware_handlers,
+    },
+};
+use axum::Router;
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .nest("/uptime-kuma", uptime_kuma_handlers::create_router())
+        .nest("/proxmox", proxmox_handlers::create_router())
+        .nest("/vmware", vmware_handlers::create_router())
+        .nest("/firewall", firewall_handlers::create_router())
+        .nest("/dns", dns_handlers::create_router())
+        .nest("/home-assistant", home_assistant_handlers::create_router())
+        .nest("/ipam", ipam_handlers::create_router())
+        .nest("/prometheus", prometheus_handlers::create_router())
+}
@@ -0,0 +1,34 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::home_assistant::r#impl::api::{HaImportReport, HaImportRequest},
+    network_members::handlers::network_role,
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+};
+use axum::{Router, extract::State, response::Json, routing::post};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/import", post(import_registry))
+}
+
+/// Name hosts and record their make and model from Home Assistant's device registry
+async fn import_registry(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<HaImportRequest>,
+) -> ApiResult<Json<ApiResponse<HaImportReport>>> {
+    network_role(&state, &user.0, &request.network_id).await?;
+
+    if request.access_token.is_empty() {
+        return Err(ApiError::bad_request("Access token is required"));
+    }
+
+    let report = state
+        .services
+        .home_assistant_registry_service
+        .import(&request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
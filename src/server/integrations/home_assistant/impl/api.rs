@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::integrations::home_assistant::r#impl::registry::HaRegistryDevice;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HaImportRequest {
+    pub network_id: Uuid,
+    /// Base URL of the Home Assistant instance, e.g. http://10.0.0.5:8123
+    pub url: String,
+    /// Long-lived access token, created from the user's profile page
+    pub access_token: String,
+    /// Keep host names as they are and only record make, model and area
+    #[serde(default)]
+    pub keep_names: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HaImportReport {
+    pub linked: Vec<HaDeviceLink>,
+    /// Devices whose MACs and IPs match no known host
+    pub unmatched: Vec<HaRegistryDevice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HaDeviceLink {
+    pub device_id: String,
+    pub name: String,
+    pub host_id: Uuid,
+}
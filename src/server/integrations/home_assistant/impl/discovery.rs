@@ -0,0 +1,152 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::server::{hosts::r#impl::base::Host, integrations::mqtt::client::availability_topic};
+
+pub const PAYLOAD_ONLINE: &str = "online";
+pub const PAYLOAD_OFFLINE: &str = "offline";
+
+/// Device entry shared by all entities of a host, so Home Assistant groups them together
+#[derive(Debug, Clone, Serialize)]
+pub struct HaDevice {
+    pub identifiers: Vec<String>,
+    pub name: String,
+    /// (type, value) pairs, e.g. ("mac", "aa:bb:cc:dd:ee:ff"), that let Home Assistant merge
+    /// this device with ones reported by other integrations
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub connections: Vec<(String, String)>,
+    pub manufacturer: String,
+}
+
+/// Discovery config for a connectivity binary sensor
+#[derive(Debug, Clone, Serialize)]
+pub struct HaBinarySensorConfig {
+    pub name: String,
+    pub unique_id: String,
+    pub device_class: String,
+    pub state_topic: String,
+    pub payload_on: String,
+    pub payload_off: String,
+    pub availability_topic: String,
+    pub json_attributes_topic: String,
+    pub device: HaDevice,
+}
+
+/// Discovery config for a device tracker
+#[derive(Debug, Clone, Serialize)]
+pub struct HaDeviceTrackerConfig {
+    pub name: String,
+    pub unique_id: String,
+    pub state_topic: String,
+    pub payload_home: String,
+    pub payload_not_home: String,
+    pub source_type: String,
+    pub availability_topic: String,
+    pub json_attributes_topic: String,
+    pub device: HaDevice,
+}
+
+/// Extra host details shown as entity attributes
+#[derive(Debug, Clone, Serialize)]
+pub struct HaHostAttributes {
+    pub netvisor_id: Uuid,
+    pub network_id: Uuid,
+    pub hostname: Option<String>,
+    pub ip_addresses: Vec<String>,
+    pub mac_addresses: Vec<String>,
+    pub last_seen: Option<String>,
+}
+
+pub fn state_topic(topic_prefix: &str, host_id: &Uuid) -> String {
+    format!("{}/hosts/{}/state", topic_prefix, host_id)
+}
+
+pub fn attributes_topic(topic_prefix: &str, host_id: &Uuid) -> String {
+    format!("{}/hosts/{}/attributes", topic_prefix, host_id)
+}
+
+pub fn binary_sensor_config_topic(discovery_prefix: &str, host_id: &Uuid) -> String {
+    format!(
+        "{}/binary_sensor/netvisor/{}/config",
+        discovery_prefix, host_id
+    )
+}
+
+pub fn device_tracker_config_topic(discovery_prefix: &str, host_id: &Uuid) -> String {
+    format!(
+        "{}/device_tracker/netvisor/{}/config",
+        discovery_prefix, host_id
+    )
+}
+
+impl HaDevice {
+    pub fn for_host(host: &Host) -> Self {
+        Self {
+            identifiers: vec![format!("netvisor_{}", host.id)],
+            name: host.base.name.clone(),
+            connections: host
+                .base
+                .interfaces
+                .iter()
+                .filter_map(|i| i.base.mac_address)
+                .map(|mac| ("mac".to_string(), mac.to_string().to_lowercase()))
+                .collect(),
+            manufacturer: "NetVisor".to_string(),
+        }
+    }
+}
+
+impl HaBinarySensorConfig {
+    pub fn for_host(host: &Host, topic_prefix: &str) -> Self {
+        Self {
+            name: "Presence".to_string(),
+            unique_id: format!("netvisor_{}_presence", host.id),
+            device_class: "connectivity".to_string(),
+            state_topic: state_topic(topic_prefix, &host.id),
+            payload_on: PAYLOAD_ONLINE.to_string(),
+            payload_off: PAYLOAD_OFFLINE.to_string(),
+            availability_topic: availability_topic(topic_prefix),
+            json_attributes_topic: attributes_topic(topic_prefix, &host.id),
+            device: HaDevice::for_host(host),
+        }
+    }
+}
+
+impl HaDeviceTrackerConfig {
+    pub fn for_host(host: &Host, topic_prefix: &str) -> Self {
+        Self {
+            name: "Tracker".to_string(),
+            unique_id: format!("netvisor_{}_tracker", host.id),
+            state_topic: state_topic(topic_prefix, &host.id),
+            payload_home: PAYLOAD_ONLINE.to_string(),
+            payload_not_home: PAYLOAD_OFFLINE.to_string(),
+            source_type: "router".to_string(),
+            availability_topic: availability_topic(topic_prefix),
+            json_attributes_topic: attributes_topic(topic_prefix, &host.id),
+            device: HaDevice::for_host(host),
+        }
+    }
+}
+
+impl HaHostAttributes {
+    pub fn for_host(host: &Host) -> Self {
+        Self {
+            netvisor_id: host.id,
+            network_id: host.base.network_id,
+            hostname: host.base.hostname.clone(),
+            ip_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .map(|i| i.base.ip_address.to_string())
+                .collect(),
+            mac_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .filter_map(|i| i.base.mac_address.map(|m| m.to_string()))
+                .collect(),
+            last_seen: host.base.last_seen_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod api;
+pub mod discovery;
+pub mod registry;
+pub mod websocket;
@@ -0,0 +1,189 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A device from Home Assistant's device registry, with the addresses its integrations report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HaRegistryDevice {
+    pub id: String,
+    /// The name the user gave it, or the one its integration reported
+    pub name: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    pub area: Option<String>,
+    pub macs: Vec<MacAddress>,
+    pub ips: Vec<IpAddr>,
+}
+
+#[derive(Deserialize)]
+struct Device {
+    id: String,
+    name: Option<String>,
+    name_by_user: Option<String>,
+    manufacturer: Option<String>,
+    model: Option<String>,
+    area_id: Option<String>,
+    #[serde(default)]
+    connections: Vec<(String, String)>,
+    /// "service" for integrations' virtual devices, such as a weather forecast
+    entry_type: Option<String>,
+    disabled_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Entity {
+    entity_id: String,
+    device_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct State {
+    entity_id: String,
+    #[serde(default)]
+    attributes: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct Area {
+    area_id: String,
+    name: String,
+}
+
+/// State attributes integrations use for a device's address
+const IP_ATTRIBUTES: &[&str] = &["ip", "ip_address", "ip_addr", "host"];
+const MAC_ATTRIBUTES: &[&str] = &["mac", "mac_address"];
+
+fn string_values<'a>(
+    attributes: &'a Map<String, Value>,
+    keys: &'a [&str],
+) -> impl Iterator<Item = &'a str> + 'a {
+    keys.iter().filter_map(|key| attributes.get(*key)?.as_str())
+}
+
+/// Join the results of `config/device_registry/list`, `config/entity_registry/list`,
+/// `get_states` and `config/area_registry/list`. Devices with neither a MAC nor an IP, which
+/// can't be matched to a host, are left out, as are disabled and virtual ones.
+pub fn parse_registry(
+    devices: Value,
+    entities: Value,
+    states: Value,
+    areas: Value,
+) -> Result<Vec<HaRegistryDevice>, serde_json::Error> {
+    let devices: Vec<Device> = serde_json::from_value(devices)?;
+    let entities: Vec<Entity> = serde_json::from_value(entities)?;
+    let states: Vec<State> = serde_json::from_value(states)?;
+    let areas: Vec<Area> = serde_json::from_value(areas)?;
+
+    let area_names: HashMap<String, String> =
+        areas.into_iter().map(|a| (a.area_id, a.name)).collect();
+    let attributes: HashMap<String, Map<String, Value>> = states
+        .into_iter()
+        .map(|s| (s.entity_id, s.attributes))
+        .collect();
+
+    let mut registry = Vec::new();
+
+    for device in devices {
+        if device.entry_type.as_deref() == Some("service") || device.disabled_by.is_some() {
+            continue;
+        }
+
+        let mut macs: Vec<MacAddress> = device
+            .connections
+            .iter()
+            .filter(|(kind, _)| kind == "mac")
+            .filter_map(|(_, mac)| mac.parse().ok())
+            .collect();
+        let mut ips: Vec<IpAddr> = Vec::new();
+
+        let device_attributes = entities
+            .iter()
+            .filter(|e| e.device_id.as_deref() == Some(device.id.as_str()))
+            .filter_map(|e| attributes.get(&e.entity_id));
+        for attributes in device_attributes {
+            for ip in
+                string_values(attributes, IP_ATTRIBUTES).filter_map(|ip| ip.parse::<IpAddr>().ok())
+            {
+                if !ip.is_loopback() && !ips.contains(&ip) {
+                    ips.push(ip);
+                }
+            }
+            for mac in string_values(attributes, MAC_ATTRIBUTES)
+                .filter_map(|mac| mac.parse::<MacAddress>().ok())
+            {
+                if !macs.contains(&mac) {
+                    macs.push(mac);
+                }
+            }
+        }
+
+        if macs.is_empty() && ips.is_empty() {
+            continue;
+        }
+
+        let Some(name) = device.name_by_user.or(device.name) else {
+            continue;
+        };
+
+        registry.push(HaRegistryDevice {
+            id: device.id,
+            name,
+            manufacturer: device.manufacturer,
+            model: device.model,
+            area: device.area_id.and_then(|id| area_names.get(&id).cloned()),
+            macs,
+            ips,
+        });
+    }
+
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn joins_devices_with_entity_addresses() {
+        let devices = json!([
+            {"id": "d1", "name": "Shelly Plug S", "name_by_user": "Kettle", "manufacturer": "Shelly",
+             "model": "SHPLG-S", "area_id": "kitchen", "connections": [["mac", "c4:5b:be:01:02:03"]],
+             "entry_type": null, "disabled_by": null},
+            {"id": "d2", "name": "Sun", "manufacturer": null, "model": null, "area_id": null,
+             "connections": [], "entry_type": "service", "disabled_by": null},
+            {"id": "d3", "name": "Living Room TV", "manufacturer": "LG", "model": "OLED55C1",
+             "area_id": null, "connections": [], "entry_type": null, "disabled_by": null}
+        ]);
+        let entities = json!([
+            {"entity_id": "switch.kettle", "device_id": "d1"},
+            {"entity_id": "sensor.kettle_ip", "device_id": "d1"},
+            {"entity_id": "media_player.tv", "device_id": "d3"},
+            {"entity_id": "sun.sun", "device_id": "d2"}
+        ]);
+        let states = json!([
+            {"entity_id": "switch.kettle", "state": "off", "attributes": {}},
+            {"entity_id": "sensor.kettle_ip", "state": "192.168.1.40", "attributes": {"ip": "192.168.1.40"}},
+            {"entity_id": "media_player.tv", "state": "on", "attributes": {"host": "192.168.1.41", "mac": "A8:23:FE:01:02:03"}},
+            {"entity_id": "sun.sun", "state": "above_horizon", "attributes": {}}
+        ]);
+        let areas = json!([{"area_id": "kitchen", "name": "Kitchen"}]);
+
+        let registry = parse_registry(devices, entities, states, areas).unwrap();
+        assert_eq!(registry.len(), 2);
+
+        assert_eq!(registry[0].name, "Kettle");
+        assert_eq!(registry[0].area.as_deref(), Some("Kitchen"));
+        assert_eq!(registry[0].macs.len(), 1);
+        assert_eq!(
+            registry[0].ips,
+            vec!["192.168.1.40".parse::<IpAddr>().unwrap()]
+        );
+
+        assert_eq!(registry[1].model.as_deref(), Some("OLED55C1"));
+        assert_eq!(registry[1].macs.len(), 1);
+        assert_eq!(registry[1].ips.len(), 1);
+    }
+}
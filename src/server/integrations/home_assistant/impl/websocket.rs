@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+use url::Url;
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Minimal client for Home Assistant's WebSocket API. The device, entity and area registries
+/// aren't exposed over REST, so this implements just enough to authenticate and run commands.
+pub struct HaSocket {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_id: u64,
+}
+
+impl HaSocket {
+    pub async fn connect(base_url: &str, access_token: &str) -> Result<Self> {
+        let mut url = Url::parse(base_url)?.join("api/websocket")?;
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        url.set_scheme(scheme)
+            .map_err(|_| anyhow!("Invalid Home Assistant URL: {}", base_url))?;
+
+        let (ws, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+        let mut socket = Self { ws, next_id: 1 };
+
+        socket.wait_for(|m| m["type"] == "auth_required").await?;
+        socket
+            .send(json!({ "type": "auth", "access_token": access_token }))
+            .await?;
+        let response = socket
+            .wait_for(|m| m["type"] == "auth_ok" || m["type"] == "auth_invalid")
+            .await?;
+        if response["type"] != "auth_ok" {
+            bail!("Home Assistant rejected the access token");
+        }
+
+        Ok(socket)
+    }
+
+    /// Run a command and return its result
+    pub async fn call(&mut self, command: &str) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.send(json!({ "id": id, "type": command })).await?;
+        let mut response = self
+            .wait_for(|m| m["type"] == "result" && m["id"] == id)
+            .await?;
+
+        if response["success"] != true {
+            let message = response["error"]["message"]
+                .as_str()
+                .unwrap_or("Unknown error");
+            bail!("Home Assistant {} failed: {}", command, message);
+        }
+
+        Ok(response["result"].take())
+    }
+
+    pub async fn close(mut self) -> Result<()> {
+        self.ws.close(None).await?;
+        Ok(())
+    }
+
+    async fn send(&mut self, message: Value) -> Result<()> {
+        self.ws
+            .send(Message::Text(message.to_string().into()))
+            .await?;
+        Ok(())
+    }
+
+    /// Read messages until one matches, skipping events and unrelated results
+    async fn wait_for(&mut self, matches: impl Fn(&Value) -> bool) -> Result<Value> {
+        tokio::time::timeout(RESPONSE_TIMEOUT, async {
+            loop {
+                match self.ws.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        let message: Value = serde_json::from_str(text.as_str())?;
+                        if matches(&message) {
+                            return Ok(message);
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        bail!("Home Assistant closed the connection")
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for Home Assistant"))?
+    }
+}
@@ -0,0 +1,4 @@
+pub mod handlers;
+pub mod r#impl;
+pub mod registry;
+pub mod service;
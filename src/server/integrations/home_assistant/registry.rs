@@ -0,0 +1,93 @@
+use crate::server::{
+    hosts::{r#impl::device::HostDevice, service::HostService},
+    integrations::{
+        guests::find_guest_host,
+        home_assistant::r#impl::{
+            api::{HaDeviceLink, HaImportReport, HaImportRequest},
+            registry::parse_registry,
+            websocket::HaSocket,
+        },
+    },
+    shared::{
+        services::traits::CrudService, storage::filter::EntityFilter, types::entities::EntitySource,
+    },
+};
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+
+/// Reads Home Assistant's device registry and records the name, make and model of each device
+/// on the host with its MAC or IP. Integrations know IoT devices far better than their MAC's
+/// vendor prefix does.
+pub struct HomeAssistantRegistryService {
+    host_service: Arc<HostService>,
+}
+
+impl HomeAssistantRegistryService {
+    pub fn new(host_service: Arc<HostService>) -> Self {
+        Self { host_service }
+    }
+
+    /// Link registry devices to the network's hosts, by MAC then IP. Hosts named by hand keep
+    /// their name.
+    pub async fn import(&self, request: &HaImportRequest) -> Result<HaImportReport> {
+        let mut socket = HaSocket::connect(&request.url, &request.access_token).await?;
+        let devices = socket.call("config/device_registry/list").await?;
+        let entities = socket.call("config/entity_registry/list").await?;
+        let states = socket.call("get_states").await?;
+        let areas = socket.call("config/area_registry/list").await?;
+        if let Err(e) = socket.close().await {
+            tracing::debug!("Failed to close Home Assistant connection: {}", e);
+        }
+
+        let registry = parse_registry(devices, entities, states, areas)?;
+
+        let filter = EntityFilter::unfiltered().network_ids(&[request.network_id]);
+        let mut hosts = self.host_service.get_all(filter).await?;
+
+        let mut report = HaImportReport::default();
+
+        for device in registry {
+            let Some(index) = find_guest_host(None, &device.macs, &device.ips, &hosts, &[]) else {
+                report.unmatched.push(device);
+                continue;
+            };
+
+            let mut host = hosts[index].clone();
+            host.base.device = Some(HostDevice {
+                manufacturer: device.manufacturer.clone(),
+                model: device.model.clone(),
+                area: device.area.clone(),
+                source: "Home Assistant".to_string(),
+                collected_at: Utc::now(),
+            });
+            if !request.keep_names && !matches!(host.base.source, EntitySource::Manual) {
+                host.base.name = device.name.clone();
+            }
+            for interface in host.base.interfaces.iter_mut() {
+                if interface.base.mac_address.is_none()
+                    && device.ips.contains(&interface.base.ip_address)
+                    && let [mac] = device.macs.as_slice()
+                {
+                    interface.base.mac_address = Some(*mac);
+                }
+            }
+
+            hosts[index] = self.host_service.update_host(host).await?;
+            report.linked.push(HaDeviceLink {
+                device_id: device.id,
+                name: device.name,
+                host_id: hosts[index].id,
+            });
+        }
+
+        tracing::info!(
+            "Home Assistant import for network {}: {} linked, {} unmatched",
+            request.network_id,
+            report.linked.len(),
+            report.unmatched.len()
+        );
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,148 @@
+use crate::server::{
+    hosts::{r#impl::base::Host, service::HostService},
+    integrations::{
+        home_assistant::r#impl::discovery::{
+            HaBinarySensorConfig, HaDeviceTrackerConfig, HaHostAttributes, PAYLOAD_OFFLINE,
+            PAYLOAD_ONLINE, attributes_topic, binary_sensor_config_topic,
+            device_tracker_config_topic, state_topic,
+        },
+        mqtt::client::MqttClient,
+    },
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Publishes host presence to Home Assistant over MQTT, using MQTT discovery so each host shows
+/// up as a device with a connectivity binary sensor and a device tracker
+pub struct HomeAssistantService {
+    host_service: Arc<HostService>,
+    mqtt: Arc<MqttClient>,
+    discovery_prefix: String,
+    /// Hosts not seen by discovery for this long are reported as offline
+    offline_after: Duration,
+    /// Last published state per host, so only changes are sent
+    published: Mutex<HashMap<Uuid, &'static str>>,
+}
+
+impl HomeAssistantService {
+    pub fn new(
+        host_service: Arc<HostService>,
+        mqtt: Arc<MqttClient>,
+        discovery_prefix: String,
+        offline_after_secs: i64,
+    ) -> Self {
+        Self {
+            host_service,
+            mqtt,
+            discovery_prefix,
+            offline_after: Duration::seconds(offline_after_secs),
+            published: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The ping monitor's verdict where a daemon watches the host, otherwise whether discovery
+    /// saw it recently. Edits to the host don't count.
+    pub fn is_online(&self, host: &Host) -> bool {
+        match &host.base.reachability {
+            Some(reachability) => reachability.reachable,
+            None => host
+                .base
+                .last_seen_at
+                .is_some_and(|last_seen| Utc::now() - last_seen < self.offline_after),
+        }
+    }
+
+    /// Publish discovery configs for new hosts, state for changed hosts, and remove entities
+    /// of deleted or hidden hosts
+    pub async fn publish_presence(&self) -> Result<()> {
+        let hosts: Vec<Host> = self
+            .host_service
+            .get_all(EntityFilter::unfiltered())
+            .await?
+            .into_iter()
+            .filter(|h| !h.base.hidden)
+            .collect();
+
+        let mut published = self.published.lock().await;
+        let current: HashSet<Uuid> = hosts.iter().map(|h| h.id).collect();
+
+        for host in &hosts {
+            let state = if self.is_online(host) {
+                PAYLOAD_ONLINE
+            } else {
+                PAYLOAD_OFFLINE
+            };
+
+            let previous = published.get(&host.id).copied();
+            if previous.is_none() {
+                self.mqtt
+                    .publish_json(
+                        &binary_sensor_config_topic(&self.discovery_prefix, &host.id),
+                        &HaBinarySensorConfig::for_host(host, self.mqtt.topic_prefix()),
+                        true,
+                    )
+                    .await?;
+                self.mqtt
+                    .publish_json(
+                        &device_tracker_config_topic(&self.discovery_prefix, &host.id),
+                        &HaDeviceTrackerConfig::for_host(host, self.mqtt.topic_prefix()),
+                        true,
+                    )
+                    .await?;
+            }
+
+            self.mqtt
+                .publish_json(
+                    &attributes_topic(self.mqtt.topic_prefix(), &host.id),
+                    &HaHostAttributes::for_host(host),
+                    true,
+                )
+                .await?;
+
+            if previous != Some(state) {
+                self.mqtt
+                    .publish(
+                        &state_topic(self.mqtt.topic_prefix(), &host.id),
+                        state,
+                        true,
+                    )
+                    .await?;
+                published.insert(host.id, state);
+            }
+        }
+
+        // An empty retained config removes the entity from Home Assistant
+        let removed: Vec<Uuid> = published
+            .keys()
+            .filter(|id| !current.contains(id))
+            .copied()
+            .collect();
+
+        for host_id in removed {
+            self.mqtt
+                .publish(
+                    &binary_sensor_config_topic(&self.discovery_prefix, &host_id),
+                    "",
+                    true,
+                )
+                .await?;
+            self.mqtt
+                .publish(
+                    &device_tracker_config_topic(&self.discovery_prefix, &host_id),
+                    "",
+                    true,
+                )
+                .await?;
+            published.remove(&host_id);
+        }
+
+        Ok(())
+    }
+}
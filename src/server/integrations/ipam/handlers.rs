@@ -0,0 +1,149 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    daemons::r#impl::base::Daemon,
+    hosts::r#impl::base::Host,
+    integrations::ipam::r#impl::{
+        addresses::{AddressStatus, IpamSubnetReport},
+        api::{
+            CsvImportRequest, IpamImportReport, IpamReconcileReport, IpamReserveRequest,
+            PhpIpamImportRequest,
+        },
+        csv,
+    },
+    network_members::handlers::network_role,
+    shared::{
+        handlers::traits::get_accessible,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    subnets::r#impl::base::Subnet,
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{get, post},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/phpipam", post(import_phpipam))
+        .route("/csv", post(import_csv))
+        .route("/{network_id}/reconcile", get(reconcile))
+        .route("/subnets/{subnet_id}", get(get_subnet_addresses))
+        .route("/subnets/{subnet_id}/reservations", post(reserve_address))
+}
+
+/// Import subnets and address reservations from phpIPAM
+async fn import_phpipam(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<PhpIpamImportRequest>,
+) -> ApiResult<Json<ApiResponse<IpamImportReport>>> {
+    let daemon: Daemon = get_accessible(&state, &user.0, &request.daemon_id).await?;
+
+    if request.app_id.is_empty() {
+        return Err(ApiError::bad_request("App ID is required"));
+    }
+    if request.token.is_none() && request.username.is_none() {
+        return Err(ApiError::bad_request(
+            "An app token or username is required",
+        ));
+    }
+
+    let report = state
+        .services
+        .ipam_service
+        .import_phpipam(&daemon, &request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Import subnets and address reservations from a CSV address plan
+async fn import_csv(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<CsvImportRequest>,
+) -> ApiResult<Json<ApiResponse<IpamImportReport>>> {
+    let daemon: Daemon = get_accessible(&state, &user.0, &request.daemon_id).await?;
+
+    let plan = csv::parse(&request.csv).map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    let report = state
+        .services
+        .ipam_service
+        .import_plan(&daemon, plan)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Planned hosts a scan has confirmed or not found, and hosts the plan doesn't expect
+async fn reconcile(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(network_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<IpamReconcileReport>>> {
+    network_role(&state, &user.0, &network_id).await?;
+
+    let report = state.services.ipam_service.reconcile(network_id).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Every address in the subnet with its status and the subnet's utilization
+async fn get_subnet_addresses(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(subnet_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<IpamSubnetReport>>> {
+    let subnet: Subnet = get_accessible(&state, &user.0, &subnet_id).await?;
+
+    let report = state.services.ipam_service.subnet_report(&subnet).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Reserve a free address in the subnet for a host that's expected there
+async fn reserve_address(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(subnet_id): Path<Uuid>,
+    Json(request): Json<IpamReserveRequest>,
+) -> ApiResult<Json<ApiResponse<Host>>> {
+    let subnet: Subnet = get_accessible(&state, &user.0, &subnet_id).await?;
+    let daemon: Daemon = get_accessible(&state, &user.0, &request.daemon_id).await?;
+
+    if daemon.base.network_id != subnet.base.network_id {
+        return Err(ApiError::bad_request(
+            "The daemon must be in the subnet's network",
+        ));
+    }
+    if !subnet.base.cidr.contains(&request.ip) {
+        return Err(ApiError::bad_request(&format!(
+            "{} isn't in {}",
+            request.ip, subnet.base.cidr
+        )));
+    }
+
+    let service = &state.services.ipam_service;
+    let taken = service
+        .subnet_report(&subnet)
+        .await?
+        .addresses
+        .iter()
+        .any(|a| a.ip == request.ip && a.status != AddressStatus::Free);
+    if taken {
+        return Err(ApiError::conflict(&format!(
+            "{} is already in use or reserved",
+            request.ip
+        )));
+    }
+
+    let host = service.reserve(&daemon, &subnet, request).await?;
+
+    Ok(Json(ApiResponse::success(host)))
+}
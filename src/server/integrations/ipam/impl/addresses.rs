@@ -0,0 +1,242 @@
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::base::Host,
+    integrations::ipam::service::{is_discovered, is_planned},
+    services::r#impl::{base::Service, definitions::ServiceDefinitionExt},
+    subnets::r#impl::base::Subnet,
+};
+
+/// Subnets with more usable addresses than this only list the ones in use
+pub const MAX_LISTED_ADDRESSES: u128 = 65536;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AddressStatus {
+    /// Held by a host with a gateway service bound to it
+    Gateway,
+    Discovered,
+    /// Reserved in the address plan, but no scan has found it
+    Reserved,
+    Free,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IpamAddress {
+    pub ip: IpAddr,
+    pub status: AddressStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_name: Option<String>,
+    /// Whether the address plan reserves it
+    #[serde(default)]
+    pub reserved: bool,
+    /// Reserved, but expected hosts weren't found by any scan
+    #[serde(default)]
+    pub expected_but_absent: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpamSubnetReport {
+    pub subnet_id: Uuid,
+    pub cidr: String,
+    /// Addresses hosts can use, leaving out the network and broadcast addresses
+    pub usable: u128,
+    pub used: u128,
+    pub utilization: f64,
+    /// False when the subnet is too large to list free addresses
+    pub includes_free: bool,
+    pub addresses: Vec<IpamAddress>,
+}
+
+/// Every usable address in the subnet, with the hosts holding them. Gateways win over
+/// other hosts at the same address, and discovered hosts over reservations.
+pub fn subnet_addresses(subnet: &Subnet, hosts: &[Host], services: &[Service]) -> IpamSubnetReport {
+    let mut used: BTreeMap<IpAddr, IpamAddress> = BTreeMap::new();
+
+    for host in hosts {
+        let gateway_bindings: Vec<Option<Uuid>> = services
+            .iter()
+            .filter(|s| s.base.host_id == host.id && s.base.service_definition.is_gateway())
+            .flat_map(|s| s.to_bound_interface_ids())
+            .collect();
+        let reserved = is_planned(&host.base.source);
+        let absent = reserved && !is_discovered(&host.base.source);
+
+        for interface in &host.base.interfaces {
+            let ip = interface.base.ip_address;
+            if interface.base.subnet_id != subnet.id || !subnet.base.cidr.contains(&ip) {
+                continue;
+            }
+
+            let status = if gateway_bindings
+                .iter()
+                .any(|binding| binding.is_none_or(|id| id == interface.id))
+            {
+                AddressStatus::Gateway
+            } else if absent {
+                AddressStatus::Reserved
+            } else {
+                AddressStatus::Discovered
+            };
+
+            let address = IpamAddress {
+                ip,
+                status,
+                host_id: Some(host.id),
+                host_name: Some(host.base.name.clone()),
+                reserved,
+                expected_but_absent: absent,
+            };
+
+            match used.get(&ip) {
+                Some(existing) if existing.status <= address.status => {}
+                _ => {
+                    used.insert(ip, address);
+                }
+            }
+        }
+    }
+
+    let (first, usable) = usable_range(subnet);
+    let includes_free = usable <= MAX_LISTED_ADDRESSES;
+    let used_count = used.len() as u128;
+
+    let addresses = match (first, includes_free) {
+        (IpAddr::V4(first), true) => (0..usable as u32)
+            .map(|offset| IpAddr::V4(Ipv4Addr::from(u32::from(first) + offset)))
+            .map(|ip| {
+                used.remove(&ip).unwrap_or(IpamAddress {
+                    ip,
+                    status: AddressStatus::Free,
+                    host_id: None,
+                    host_name: None,
+                    reserved: false,
+                    expected_but_absent: false,
+                })
+            })
+            .collect(),
+        _ => used.into_values().collect(),
+    };
+
+    IpamSubnetReport {
+        subnet_id: subnet.id,
+        cidr: subnet.base.cidr.to_string(),
+        usable,
+        used: used_count,
+        utilization: if usable == 0 {
+            0.0
+        } else {
+            (used_count as f64 / usable as f64 * 10000.0).round() / 100.0
+        },
+        includes_free,
+        addresses,
+    }
+}
+
+/// First usable address and how many there are. IPv4 subnets of /30 and wider lose their
+/// network and broadcast addresses; IPv6 subnets are only ever listed by what's in use.
+fn usable_range(subnet: &Subnet) -> (IpAddr, u128) {
+    let cidr = &subnet.base.cidr;
+    let first = cidr.first_address();
+    let host_bits = match first {
+        IpAddr::V4(_) => 32 - cidr.network_length() as u32,
+        IpAddr::V6(_) => 128 - cidr.network_length() as u32,
+    };
+    let size = if host_bits >= 128 {
+        u128::MAX
+    } else {
+        1u128 << host_bits
+    };
+
+    match first {
+        IpAddr::V4(ip) if host_bits >= 2 => {
+            (IpAddr::V4(Ipv4Addr::from(u32::from(ip) + 1)), size - 2)
+        }
+        _ => (first, size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        discovery::r#impl::types::DiscoveryType,
+        hosts::r#impl::{
+            base::HostBase,
+            interfaces::{Interface, InterfaceBase},
+        },
+        shared::{
+            storage::traits::StorableEntity,
+            types::entities::{DiscoveryMetadata, EntitySource},
+        },
+        subnets::r#impl::base::SubnetBase,
+    };
+    use cidr::{IpCidr, Ipv4Cidr};
+
+    fn host(subnet: &Subnet, ip: [u8; 4], discovery: &[DiscoveryType]) -> Host {
+        Host::new(HostBase {
+            name: format!("host-{}", ip[3]),
+            network_id: subnet.base.network_id,
+            interfaces: vec![Interface::new(InterfaceBase {
+                subnet_id: subnet.id,
+                ip_address: IpAddr::V4(Ipv4Addr::from(ip)),
+                mac_address: None,
+                name: None,
+            })],
+            source: EntitySource::Discovery {
+                metadata: discovery
+                    .iter()
+                    .map(|t| DiscoveryMetadata::new(t.clone(), Uuid::new_v4()))
+                    .collect(),
+            },
+            ..HostBase::default()
+        })
+    }
+
+    #[test]
+    fn lists_every_usable_address() {
+        let subnet = Subnet::new(SubnetBase {
+            cidr: IpCidr::V4(Ipv4Cidr::new(Ipv4Addr::new(10, 0, 0, 0), 29).unwrap()),
+            ..SubnetBase::default()
+        });
+        let hosts = vec![
+            host(&subnet, [10, 0, 0, 2], &[DiscoveryType::IpamImport]),
+            host(
+                &subnet,
+                [10, 0, 0, 3],
+                &[
+                    DiscoveryType::IpamImport,
+                    DiscoveryType::Network {
+                        subnet_ids: None,
+                        host_naming_fallback: Default::default(),
+                        scan_profile_id: None,
+                        parallel_daemon_ids: Vec::new(),
+                        shard: None,
+                    },
+                ],
+            ),
+        ];
+
+        let report = subnet_addresses(&subnet, &hosts, &[]);
+
+        assert_eq!(report.usable, 6);
+        assert_eq!(report.used, 2);
+        assert_eq!(report.utilization, 33.33);
+        assert_eq!(report.addresses.len(), 6);
+        assert_eq!(report.addresses[0].status, AddressStatus::Free);
+        assert_eq!(report.addresses[1].status, AddressStatus::Reserved);
+        assert!(report.addresses[1].expected_but_absent);
+        assert_eq!(report.addresses[2].status, AddressStatus::Discovered);
+        assert!(report.addresses[2].reserved && !report.addresses[2].expected_but_absent);
+    }
+}
@@ -0,0 +1,61 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhpIpamImportRequest {
+    /// Daemon the plan is attributed to; it's added to the daemon's network
+    pub daemon_id: Uuid,
+    /// Base URL of phpIPAM, e.g. https://ipam.lab
+    pub url: String,
+    /// API application id, as configured under Administration > API
+    pub app_id: String,
+    /// App code of an application using token security; otherwise a login is used
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub verify_tls: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsvImportRequest {
+    /// Daemon the plan is attributed to; it's added to the daemon's network
+    pub daemon_id: Uuid,
+    pub csv: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpamImportReport {
+    pub subnet_ids: Vec<Uuid>,
+    pub host_ids: Vec<Uuid>,
+    /// Reservations outside every planned and known subnet
+    pub skipped: Vec<IpAddr>,
+}
+
+/// How the network compares to its imported address plan
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpamReconcileReport {
+    /// Planned hosts a scan has also found
+    pub confirmed: Vec<Uuid>,
+    /// Planned hosts no scan has found yet
+    pub missing: Vec<Uuid>,
+    /// Hosts found in planned subnets at addresses the plan doesn't reserve
+    pub unplanned: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpamReserveRequest {
+    /// Daemon the reservation is attributed to, as with imported plans
+    pub daemon_id: Uuid,
+    pub ip: IpAddr,
+    /// Name the expected host goes by until a scan finds it
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
@@ -0,0 +1,126 @@
+use anyhow::{Result, anyhow, bail};
+use cidr::IpInet;
+
+use crate::server::{
+    integrations::ipam::r#impl::plan::{IpamAddress, IpamPlan, IpamSubnet, non_empty},
+    shared::csv::split_row,
+};
+
+/// Read an address plan from CSV with a header row. Columns are matched by name, in any
+/// order: `subnet` (CIDR), `ip`, `hostname`, `mac`, `description` and `vlan`. A row with a
+/// subnet and no IP plans the subnet, with `description` as its name; a row with an IP
+/// reserves the address, and its subnet when given.
+pub fn parse(text: &str) -> Result<IpamPlan> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (_, header) = lines.next().ok_or_else(|| anyhow!("CSV is empty"))?;
+    let header: Vec<String> = split_row(header)
+        .into_iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+
+    let subnet_column = column(&["subnet", "cidr", "network"]);
+    let ip_column = column(&["ip", "ip_address", "address"]);
+    let hostname_column = column(&["hostname", "name", "dns_name"]);
+    let mac_column = column(&["mac", "mac_address"]);
+    let description_column = column(&["description", "notes"]);
+    let vlan_column = column(&["vlan", "vlan_id"]);
+
+    if subnet_column.is_none() && ip_column.is_none() {
+        bail!("CSV needs a subnet or ip column");
+    }
+
+    let mut plan = IpamPlan::default();
+
+    for (index, line) in lines {
+        let row = split_row(line);
+        let field =
+            |column: Option<usize>| non_empty(column.and_then(|c| row.get(c)).map(|f| f.as_str()));
+        let line_number = index + 1;
+
+        let description = field(description_column);
+        let ip = field(ip_column)
+            .map(|ip| {
+                ip.parse()
+                    .map_err(|_| anyhow!("Line {}: invalid IP '{}'", line_number, ip))
+            })
+            .transpose()?;
+
+        if let Some(subnet) = field(subnet_column) {
+            let cidr = subnet
+                .parse::<IpInet>()
+                .map_err(|_| anyhow!("Line {}: invalid subnet '{}'", line_number, subnet))?
+                .network();
+            let vlan_id = field(vlan_column)
+                .map(|vlan| {
+                    vlan.parse::<u16>()
+                        .map_err(|_| anyhow!("Line {}: invalid VLAN '{}'", line_number, vlan))
+                })
+                .transpose()?;
+            plan.add_subnet(IpamSubnet {
+                cidr,
+                name: if ip.is_none() {
+                    description.clone()
+                } else {
+                    None
+                },
+                vlan_id,
+            });
+        }
+
+        if let Some(ip) = ip {
+            let mac = field(mac_column)
+                .map(|mac| {
+                    mac.parse()
+                        .map_err(|_| anyhow!("Line {}: invalid MAC '{}'", line_number, mac))
+                })
+                .transpose()?;
+            plan.add_address(IpamAddress {
+                ip,
+                hostname: field(hostname_column),
+                mac,
+                description,
+            });
+        }
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_subnets_and_reservations() {
+        let csv = "Subnet,IP,Hostname,MAC,Description,VLAN\n\
+                   10.0.20.0/24,,,,IoT,20\n\
+                   10.0.20.0/24,10.0.20.5,hub,aa:bb:cc:00:11:22,\"Zigbee hub, hallway\",20\n\
+                   \n\
+                   10.0.30.1/24,10.0.30.10,nas,,,\n";
+
+        let plan = parse(csv).unwrap();
+        assert_eq!(plan.subnets.len(), 2);
+        assert_eq!(plan.subnets[0].name.as_deref(), Some("IoT"));
+        assert_eq!(plan.subnets[0].vlan_id, Some(20));
+        assert_eq!(plan.subnets[1].cidr.to_string(), "10.0.30.0/24");
+
+        assert_eq!(plan.addresses.len(), 2);
+        assert_eq!(
+            plan.addresses[0].description.as_deref(),
+            Some("Zigbee hub, hallway")
+        );
+        assert!(plan.addresses[0].mac.is_some());
+        assert_eq!(plan.addresses[1].hostname.as_deref(), Some("nas"));
+    }
+
+    #[test]
+    fn reports_the_line_of_invalid_values() {
+        let error = parse("ip,hostname\n10.0.0.1,a\n10.0.0.300,b\n").unwrap_err();
+        assert!(error.to_string().starts_with("Line 3"));
+    }
+}
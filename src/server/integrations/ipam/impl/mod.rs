@@ -0,0 +1,5 @@
+pub mod addresses;
+pub mod api;
+pub mod csv;
+pub mod phpipam;
+pub mod plan;
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use cidr::IpInet;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::server::integrations::ipam::r#impl::plan::{
+    IpamAddress, IpamPlan, IpamSubnet, non_empty,
+};
+
+/// Address tag phpIPAM gives the members of a DHCP range, which aren't reservations
+const DHCP_TAG: &str = "4";
+
+/// phpIPAM wraps every payload in `data`, which is missing when a list is empty
+#[derive(Deserialize)]
+struct PhpIpamResponse {
+    data: Option<Vec<HashMap<String, Value>>>,
+}
+
+fn rows(body: &str) -> Result<Vec<HashMap<String, Value>>, serde_json::Error> {
+    let response: PhpIpamResponse = serde_json::from_str(body)?;
+    Ok(response.data.unwrap_or_default())
+}
+
+/// phpIPAM returns numbers as strings, except in some versions and fields
+fn text(row: &HashMap<String, Value>, key: &str) -> Option<String> {
+    match row.get(key)? {
+        Value::String(s) => non_empty(Some(s)),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// VLAN numbers by phpIPAM VLAN id, from `/vlan/`
+pub fn parse_vlans(body: &str) -> Result<HashMap<String, u16>, serde_json::Error> {
+    Ok(rows(body)?
+        .iter()
+        .filter_map(|row| Some((text(row, "vlanId")?, text(row, "number")?.parse().ok()?)))
+        .collect())
+}
+
+/// Subnets from `/subnets/`, added to `plan`. Folders have no subnet and are skipped.
+pub fn parse_subnets(
+    body: &str,
+    vlans: &HashMap<String, u16>,
+    plan: &mut IpamPlan,
+) -> Result<(), serde_json::Error> {
+    for row in &rows(body)? {
+        let (Some(subnet), Some(mask)) = (text(row, "subnet"), text(row, "mask")) else {
+            continue;
+        };
+        let Ok(inet) = format!("{}/{}", subnet, mask).parse::<IpInet>() else {
+            continue;
+        };
+
+        plan.add_subnet(IpamSubnet {
+            cidr: inet.network(),
+            name: text(row, "description"),
+            vlan_id: text(row, "vlanId").and_then(|id| vlans.get(&id).copied()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Address reservations from `/addresses/`, added to `plan`
+pub fn parse_addresses(body: &str, plan: &mut IpamPlan) -> Result<(), serde_json::Error> {
+    for row in &rows(body)? {
+        if text(row, "tag").as_deref() == Some(DHCP_TAG) {
+            continue;
+        }
+        let Some(ip) = text(row, "ip").and_then(|ip| ip.parse().ok()) else {
+            continue;
+        };
+
+        plan.add_address(IpamAddress {
+            ip,
+            hostname: text(row, "hostname"),
+            mac: text(row, "mac").and_then(|mac| mac.parse().ok()),
+            description: text(row, "description"),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_subnets_and_addresses() {
+        let vlans =
+            parse_vlans(r#"{"code": 200, "success": true, "data": [{"vlanId": "3", "number": "20", "name": "IoT"}]}"#)
+                .unwrap();
+
+        let mut plan = IpamPlan::default();
+        parse_subnets(
+            r#"{"code": 200, "success": true, "data": [
+                {"id": "7", "subnet": "10.0.20.0", "mask": "24", "description": "IoT", "vlanId": "3", "isFolder": "0"},
+                {"id": "8", "subnet": null, "mask": null, "description": "Branch offices", "vlanId": null, "isFolder": "1"}
+            ]}"#,
+            &vlans,
+            &mut plan,
+        )
+        .unwrap();
+        assert_eq!(plan.subnets.len(), 1);
+        assert_eq!(plan.subnets[0].vlan_id, Some(20));
+
+        parse_addresses(
+            r#"{"code": 200, "success": true, "data": [
+                {"id": "1", "subnetId": "7", "ip": "10.0.20.5", "hostname": "hub", "mac": "aa:bb:cc:00:11:22", "description": "", "tag": "2"},
+                {"id": "2", "subnetId": "7", "ip": "10.0.20.100", "hostname": "", "mac": "", "description": "", "tag": "4"}
+            ]}"#,
+            &mut plan,
+        )
+        .unwrap();
+        assert_eq!(plan.addresses.len(), 1);
+        assert_eq!(plan.addresses[0].hostname.as_deref(), Some("hub"));
+        assert_eq!(plan.addresses[0].description, None);
+
+        parse_addresses(
+            r#"{"code": 200, "success": true, "message": "No addresses found"}"#,
+            &mut plan,
+        )
+        .unwrap();
+    }
+}
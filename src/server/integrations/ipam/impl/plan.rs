@@ -0,0 +1,50 @@
+use std::net::IpAddr;
+
+use cidr::IpCidr;
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+
+/// Subnets and address reservations read from an IPAM
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpamPlan {
+    pub subnets: Vec<IpamSubnet>,
+    pub addresses: Vec<IpamAddress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpamSubnet {
+    pub cidr: IpCidr,
+    pub name: Option<String>,
+    pub vlan_id: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpamAddress {
+    pub ip: IpAddr,
+    pub hostname: Option<String>,
+    pub mac: Option<MacAddress>,
+    pub description: Option<String>,
+}
+
+impl IpamPlan {
+    /// Add a subnet unless one with the same CIDR is already planned
+    pub fn add_subnet(&mut self, subnet: IpamSubnet) {
+        if !self.subnets.iter().any(|s| s.cidr == subnet.cidr) {
+            self.subnets.push(subnet);
+        }
+    }
+
+    pub fn add_address(&mut self, address: IpamAddress) {
+        if !self.addresses.iter().any(|a| a.ip == address.ip) {
+            self.addresses.push(address);
+        }
+    }
+}
+
+/// IPAMs leave unset fields empty rather than null
+pub fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
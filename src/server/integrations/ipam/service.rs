@@ -0,0 +1,323 @@
+use crate::server::{
+    daemons::r#impl::base::Daemon,
+    discovery::r#impl::types::DiscoveryType,
+    hosts::{
+        r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+        },
+        service::HostService,
+    },
+    integrations::ipam::r#impl::{
+        addresses::{IpamSubnetReport, subnet_addresses},
+        api::{IpamImportReport, IpamReconcileReport, IpamReserveRequest, PhpIpamImportRequest},
+        phpipam::{parse_addresses, parse_subnets, parse_vlans},
+        plan::IpamPlan,
+    },
+    services::service::ServiceService,
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::entities::{DiscoveryMetadata, EntitySource},
+    },
+    subnets::{
+        r#impl::{
+            base::{Subnet, SubnetBase},
+            types::SubnetType,
+        },
+        service::SubnetService,
+    },
+};
+use anyhow::{Result, anyhow, bail};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::{collections::HashSet, sync::Arc};
+use uuid::Uuid;
+
+/// Pre-populates subnets and expected hosts from an address plan, kept in phpIPAM or a CSV.
+/// Planned hosts carry `IpamImport` discovery metadata, so once a scan finds them they can be
+/// told apart from those the plan expects but the network doesn't have.
+pub struct IpamService {
+    host_service: Arc<HostService>,
+    subnet_service: Arc<SubnetService>,
+    service_service: Arc<ServiceService>,
+}
+
+impl IpamService {
+    pub fn new(
+        host_service: Arc<HostService>,
+        subnet_service: Arc<SubnetService>,
+        service_service: Arc<ServiceService>,
+    ) -> Self {
+        Self {
+            host_service,
+            subnet_service,
+            service_service,
+        }
+    }
+
+    pub async fn import_phpipam(
+        &self,
+        daemon: &Daemon,
+        request: &PhpIpamImportRequest,
+    ) -> Result<IpamImportReport> {
+        let client = PhpIpamClient::connect(request).await?;
+
+        let vlans = match client.get("vlan/").await {
+            Ok(body) => parse_vlans(&body)?,
+            Err(e) => {
+                tracing::warn!("Failed to read phpIPAM VLANs: {}", e);
+                Default::default()
+            }
+        };
+
+        let mut plan = IpamPlan::default();
+        parse_subnets(&client.get("subnets/").await?, &vlans, &mut plan)?;
+        parse_addresses(&client.get("addresses/").await?, &mut plan)?;
+
+        self.import_plan(daemon, plan).await
+    }
+
+    /// Create the plan's subnets, then a host for each reservation inside a known subnet.
+    /// Existing subnets and hosts are merged into rather than duplicated.
+    pub async fn import_plan(&self, daemon: &Daemon, plan: IpamPlan) -> Result<IpamImportReport> {
+        let network_id = daemon.base.network_id;
+        let source = || EntitySource::Discovery {
+            metadata: vec![DiscoveryMetadata::new(DiscoveryType::IpamImport, daemon.id)],
+        };
+
+        let mut report = IpamImportReport::default();
+
+        for planned in &plan.subnets {
+            let mut subnet = self
+                .subnet_service
+                .create(Subnet::new(SubnetBase {
+                    cidr: planned.cidr,
+                    network_id,
+                    name: planned
+                        .name
+                        .clone()
+                        .unwrap_or_else(|| planned.cidr.to_string()),
+                    description: None,
+                    subnet_type: SubnetType::Unknown,
+                    source: source(),
+                    ..SubnetBase::default()
+                }))
+                .await?;
+
+            if subnet.base.layer2.vlan_id.is_none() && planned.vlan_id.is_some() {
+                subnet.base.layer2.vlan_id = planned.vlan_id;
+                subnet = self.subnet_service.update(&mut subnet).await?;
+            }
+            report.subnet_ids.push(subnet.id);
+        }
+
+        let subnets = self
+            .subnet_service
+            .get_all(EntityFilter::unfiltered().network_ids(&[network_id]))
+            .await?;
+
+        for address in plan.addresses {
+            let Some(subnet) = subnets.iter().find(|s| s.base.cidr.contains(&address.ip)) else {
+                report.skipped.push(address.ip);
+                continue;
+            };
+
+            let host = self
+                .host_service
+                .create_host(Host::new(HostBase {
+                    name: address
+                        .hostname
+                        .clone()
+                        .unwrap_or_else(|| address.ip.to_string()),
+                    hostname: address.hostname,
+                    network_id,
+                    description: address.description,
+                    interfaces: vec![Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: address.ip,
+                        mac_address: address.mac,
+                        name: None,
+                    })],
+                    source: source(),
+                    ..HostBase::default()
+                }))
+                .await?;
+            report.host_ids.push(host.id);
+        }
+
+        tracing::info!(
+            "IPAM import for network {}: {} subnets, {} hosts, {} skipped",
+            network_id,
+            report.subnet_ids.len(),
+            report.host_ids.len(),
+            report.skipped.len()
+        );
+
+        Ok(report)
+    }
+
+    /// Compare the network's hosts with its imported plan. Planned hosts are confirmed once
+    /// any other discovery has merged into them; hosts without a reservation are unplanned
+    /// only when they sit in a subnet the plan covers.
+    pub async fn reconcile(&self, network_id: Uuid) -> Result<IpamReconcileReport> {
+        let filter = EntityFilter::unfiltered().network_ids(&[network_id]);
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter).await?;
+
+        let mut planned_subnet_ids: HashSet<Uuid> = subnets
+            .iter()
+            .filter(|s| is_planned(&s.base.source))
+            .map(|s| s.id)
+            .collect();
+        planned_subnet_ids.extend(
+            hosts
+                .iter()
+                .filter(|h| is_planned(&h.base.source))
+                .flat_map(|h| h.base.interfaces.iter().map(|i| i.base.subnet_id)),
+        );
+
+        let mut report = IpamReconcileReport::default();
+
+        for host in &hosts {
+            if is_planned(&host.base.source) {
+                if is_discovered(&host.base.source) {
+                    report.confirmed.push(host.id);
+                } else {
+                    report.missing.push(host.id);
+                }
+            } else if host
+                .base
+                .interfaces
+                .iter()
+                .any(|i| planned_subnet_ids.contains(&i.base.subnet_id))
+            {
+                report.unplanned.push(host.id);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Every address in the subnet with its status, and how much of the subnet is in use
+    pub async fn subnet_report(&self, subnet: &Subnet) -> Result<IpamSubnetReport> {
+        let filter = EntityFilter::unfiltered().network_ids(&[subnet.base.network_id]);
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter).await?;
+
+        Ok(subnet_addresses(subnet, &hosts, &services))
+    }
+
+    /// Reserve an address with a planned host, as an address plan import would. It's
+    /// expected but absent until a scan finds a host there.
+    pub async fn reserve(
+        &self,
+        daemon: &Daemon,
+        subnet: &Subnet,
+        request: IpamReserveRequest,
+    ) -> Result<Host> {
+        self.host_service
+            .create_host(Host::new(HostBase {
+                name: request
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| request.ip.to_string()),
+                hostname: request.name,
+                network_id: subnet.base.network_id,
+                description: request.description,
+                interfaces: vec![Interface::new(InterfaceBase {
+                    subnet_id: subnet.id,
+                    ip_address: request.ip,
+                    mac_address: None,
+                    name: None,
+                })],
+                source: EntitySource::Discovery {
+                    metadata: vec![DiscoveryMetadata::new(DiscoveryType::IpamImport, daemon.id)],
+                },
+                ..HostBase::default()
+            }))
+            .await
+    }
+}
+
+pub(crate) fn is_planned(source: &EntitySource) -> bool {
+    match source {
+        EntitySource::Discovery { metadata } => metadata
+            .iter()
+            .any(|m| matches!(m.discovery_type, DiscoveryType::IpamImport)),
+        _ => false,
+    }
+}
+
+/// Whether anything besides an IPAM import has seen the entity
+pub(crate) fn is_discovered(source: &EntitySource) -> bool {
+    match source {
+        EntitySource::Discovery { metadata } => metadata
+            .iter()
+            .any(|m| !matches!(m.discovery_type, DiscoveryType::IpamImport)),
+        _ => false,
+    }
+}
+
+/// phpIPAM's REST API under `/api/{app_id}/`, authenticated with an app token or a session
+/// token from logging in
+struct PhpIpamClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl PhpIpamClient {
+    async fn connect(request: &PhpIpamImportRequest) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(!request.verify_tls)
+            .build()?;
+        let base_url = format!(
+            "{}/api/{}/",
+            request.url.trim_end_matches('/'),
+            request.app_id
+        );
+
+        let token = match (&request.token, &request.username) {
+            (Some(token), _) => token.clone(),
+            (None, Some(username)) => {
+                let response = client
+                    .post(format!("{}user/", base_url))
+                    .basic_auth(username, request.password.as_ref())
+                    .send()
+                    .await?;
+                if !response.status().is_success() {
+                    bail!("phpIPAM login returned HTTP {}", response.status());
+                }
+                let body: Value = response.json().await?;
+                body["data"]["token"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("phpIPAM login returned no token"))?
+                    .to_string()
+            }
+            (None, None) => bail!("An app token or username is required"),
+        };
+
+        Ok(Self {
+            client,
+            base_url,
+            token,
+        })
+    }
+
+    /// GET a controller. phpIPAM answers 404 for an empty list, which is read as one.
+    async fn get(&self, path: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("token", &self.token)
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(response.text().await?),
+            StatusCode::NOT_FOUND => Ok(r#"{"data": []}"#.to_string()),
+            status => bail!("phpIPAM API {} returned HTTP {}", path, status),
+        }
+    }
+}
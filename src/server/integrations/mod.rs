@@ -0,0 +1,11 @@
+pub mod dns;
+pub mod firewall;
+pub mod guests;
+pub mod handlers;
+pub mod home_assistant;
+pub mod ipam;
+pub mod mqtt;
+pub mod prometheus;
+pub mod proxmox;
+pub mod uptime_kuma;
+pub mod vmware;
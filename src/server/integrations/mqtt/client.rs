@@ -0,0 +1,124 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS, Transport};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Broker address and credentials, from the server config
+#[derive(Debug, Clone)]
+pub struct MqttConnection {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub tls: bool,
+    /// Verify the broker with this PEM CA instead of the system roots
+    pub ca_cert_path: Option<PathBuf>,
+    /// Prefix of every topic NetVisor publishes to
+    pub topic_prefix: String,
+}
+
+pub struct MqttClient {
+    client: AsyncClient,
+    topic_prefix: String,
+}
+
+impl MqttClient {
+    /// Connect to the broker and drive its event loop in the background
+    pub fn connect(connection: &MqttConnection) -> Result<Self> {
+        let mut options = MqttOptions::new(
+            format!("netvisor-{}", &Uuid::new_v4().to_string()[..8]),
+            &connection.host,
+            connection.port,
+        );
+        options.set_keep_alive(Duration::from_secs(30));
+
+        // Brokers set NetVisor's availability to offline via the last will when the
+        // connection drops
+        let availability_topic = availability_topic(&connection.topic_prefix);
+        options.set_last_will(LastWill::new(
+            &availability_topic,
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+        ));
+
+        if let Some(username) = &connection.username {
+            options.set_credentials(username, connection.password.clone().unwrap_or_default());
+        }
+
+        if connection.tls {
+            let transport = match &connection.ca_cert_path {
+                Some(path) => Transport::tls(std::fs::read(path)?, None, None),
+                None => Transport::tls_with_default_config(),
+            };
+            options.set_transport(transport);
+        }
+
+        let (client, mut event_loop) = AsyncClient::new(options, 100);
+
+        let availability_client = client.clone();
+        tokio::spawn(async move {
+            loop {
+                match event_loop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        tracing::info!("Connected to MQTT broker");
+                        if let Err(e) = availability_client
+                            .publish(&availability_topic, QoS::AtLeastOnce, true, "online")
+                            .await
+                        {
+                            tracing::warn!("Failed to publish MQTT availability: {}", e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("MQTT connection error: {}", e);
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            client,
+            topic_prefix: connection.topic_prefix.clone(),
+        })
+    }
+
+    /// `<prefix>/<suffix>`
+    pub fn topic(&self, suffix: &str) -> String {
+        format!("{}/{}", self.topic_prefix, suffix)
+    }
+
+    pub fn topic_prefix(&self) -> &str {
+        &self.topic_prefix
+    }
+
+    pub async fn publish(
+        &self,
+        topic: &str,
+        payload: impl Into<Vec<u8>>,
+        retain: bool,
+    ) -> Result<()> {
+        self.client
+            .publish(topic, QoS::AtLeastOnce, retain, payload)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn publish_json<T: Serialize>(
+        &self,
+        topic: &str,
+        payload: &T,
+        retain: bool,
+    ) -> Result<()> {
+        self.publish(topic, serde_json::to_vec(payload)?, retain)
+            .await
+    }
+}
+
+/// Topic NetVisor publishes its own availability to
+pub fn availability_topic(prefix: &str) -> String {
+    format!("{}/status", prefix)
+}
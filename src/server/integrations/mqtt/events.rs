@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    daemon::discovery::types::base::DiscoveryPhase,
+    server::{
+        daemons::r#impl::api::DiscoveryUpdatePayload,
+        hosts::r#impl::{api::HostEvent, base::Host},
+        integrations::mqtt::client::MqttClient,
+    },
+};
+
+/// Event published to `<prefix>/events/<event>`, e.g. `netvisor/events/host_offline`
+#[derive(Debug, Clone, Serialize)]
+pub struct MqttEvent {
+    pub event: &'static str,
+    pub network_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MqttHostPayload {
+    pub id: Uuid,
+    pub name: String,
+    pub hostname: Option<String>,
+    pub ip_addresses: Vec<String>,
+    pub mac_addresses: Vec<String>,
+}
+
+impl MqttHostPayload {
+    pub fn for_host(host: &Host) -> Self {
+        Self {
+            id: host.id,
+            name: host.base.name.clone(),
+            hostname: host.base.hostname.clone(),
+            ip_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .map(|i| i.base.ip_address.to_string())
+                .collect(),
+            mac_addresses: host
+                .base
+                .interfaces
+                .iter()
+                .filter_map(|i| i.base.mac_address.map(|m| m.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl MqttEvent {
+    pub fn from_host_event(event: &HostEvent) -> Result<Self> {
+        let (name, host) = match event {
+            HostEvent::Discovered(host) => ("host_discovered", host),
+            HostEvent::WentOffline(host) => ("host_offline", host),
+            HostEvent::CameOnline(host) => ("host_online", host),
+            HostEvent::PortsOpened(host, _) => ("host_ports_opened", host),
+        };
+
+        let mut data = serde_json::to_value(MqttHostPayload::for_host(host))?;
+        if let HostEvent::PortsOpened(_, ports) = event {
+            data["opened_ports"] = serde_json::to_value(ports)?;
+        }
+
+        Ok(Self {
+            event: name,
+            network_id: host.base.network_id,
+            timestamp: Utc::now(),
+            data,
+        })
+    }
+
+    /// Finished discovery runs; progress updates aren't published
+    pub fn from_discovery_update(update: &DiscoveryUpdatePayload) -> Result<Option<Self>> {
+        let name = match update.phase {
+            DiscoveryPhase::Complete => "discovery_complete",
+            DiscoveryPhase::Failed => "discovery_failed",
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Self {
+            event: name,
+            network_id: update.network_id,
+            timestamp: Utc::now(),
+            data: serde_json::to_value(update)?,
+        }))
+    }
+}
+
+/// Publishes host and discovery events, unretained, for automations to react to
+pub struct MqttEventPublisher {
+    mqtt: Arc<MqttClient>,
+}
+
+impl MqttEventPublisher {
+    pub fn new(mqtt: Arc<MqttClient>) -> Self {
+        Self { mqtt }
+    }
+
+    pub async fn publish(&self, event: &MqttEvent) -> Result<()> {
+        let topic = self.mqtt.topic(&format!("events/{}", event.event));
+        self.mqtt.publish_json(&topic, event, false).await
+    }
+}
@@ -0,0 +1,10 @@
+use crate::server::{config::AppState, exports::handlers::export_prometheus};
+use axum::{Router, routing::get};
+use std::sync::Arc;
+
+/// Prometheus `http_sd_configs` endpoint. It serves the same target groups as
+/// `/api/export/prometheus`, and takes the same network API key or session, so a scrape job
+/// can point at it with `authorization: { credentials: <key> }`.
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/targets", get(export_prometheus))
+}
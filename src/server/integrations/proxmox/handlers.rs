@@ -0,0 +1,125 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::proxmox::r#impl::{
+        api::{ProxmoxCredentialsRequest, ProxmoxSyncReport},
+        base::ProxmoxCredentials,
+    },
+    services::{definitions::proxmox::Proxmox, r#impl::base::Service},
+    shared::{
+        handlers::traits::get_accessible,
+        types::{
+            api::{ApiError, ApiResponse, ApiResult},
+            metadata::HasId,
+        },
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{service_id}/credentials", get(get_credentials))
+        .route("/{service_id}/credentials", put(set_credentials))
+        .route("/{service_id}/credentials", delete(delete_credentials))
+        .route("/{service_id}/sync", post(sync))
+}
+
+/// A Proxmox service in one of the user's networks
+async fn proxmox_service(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    service_id: Uuid,
+) -> ApiResult<Service> {
+    let service: Service = get_accessible(state, &user.0, &service_id).await?;
+
+    if service.base.service_definition.id() != Proxmox.id() {
+        return Err(ApiError::bad_request(&format!(
+            "Service '{}' isn't a Proxmox service",
+            service.base.name
+        )));
+    }
+
+    Ok(service)
+}
+
+async fn get_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<ProxmoxCredentials>>> {
+    proxmox_service(&state, &user, service_id).await?;
+
+    let credentials = state
+        .services
+        .proxmox_service
+        .get_credentials(&service_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No Proxmox credentials for service '{}'",
+                service_id
+            ))
+        })?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+/// Store the API token used to list the service's guests
+async fn set_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+    Json(request): Json<ProxmoxCredentialsRequest>,
+) -> ApiResult<Json<ApiResponse<ProxmoxCredentials>>> {
+    let service = proxmox_service(&state, &user, service_id).await?;
+
+    if request.token_id.is_empty() || request.token_secret.is_empty() {
+        return Err(ApiError::bad_request(
+            "Token id and secret are required, e.g. root@pam!netvisor",
+        ));
+    }
+
+    let credentials = state
+        .services
+        .proxmox_service
+        .set_credentials(&service, request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+async fn delete_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    proxmox_service(&state, &user, service_id).await?;
+
+    state
+        .services
+        .proxmox_service
+        .delete_credentials(&service_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// List the service's VMs and containers and link them to their hosts
+async fn sync(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<ProxmoxSyncReport>>> {
+    proxmox_service(&state, &user, service_id).await?;
+
+    let report = state.services.proxmox_service.sync(&service_id).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::integrations::proxmox::r#impl::guests::ProxmoxGuest;
+
+/// API token to store for a Proxmox service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxCredentialsRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    pub token_id: String,
+    pub token_secret: String,
+    #[serde(default)]
+    pub verify_tls: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProxmoxSyncReport {
+    /// Known hosts now marked as guests of the Proxmox service
+    pub linked: Vec<ProxmoxGuestLink>,
+    /// Guests that weren't known yet, created in the subnet their address falls in
+    pub created: Vec<ProxmoxGuestLink>,
+    /// Guests with no known host and no address in a known subnet, e.g. stopped VMs
+    pub unmatched: Vec<ProxmoxGuest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxGuestLink {
+    pub vmid: u32,
+    pub name: Option<String>,
+    pub host_id: Uuid,
+}
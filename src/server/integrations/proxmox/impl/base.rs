@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Proxmox VE API token for a Proxmox service, used to list its guests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxCredentialsBase {
+    pub service_id: Uuid,
+    pub network_id: Uuid,
+    /// Base URL of the API, e.g. https://10.0.0.2:8006; the service's first interface on port
+    /// 8006 when unset
+    pub url: Option<String>,
+    /// Full token id, e.g. root@pam!netvisor
+    pub token_id: String,
+    #[serde(serialize_with = "serialize_token_secret")]
+    pub token_secret: String,
+    /// Proxmox installs a self-signed certificate, so it isn't verified by default
+    pub verify_tls: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+fn serialize_token_secret<S>(_secret: &String, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("***REDACTED***")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxmoxCredentials {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: ProxmoxCredentialsBase,
+}
+
+impl Display for ProxmoxCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Proxmox credentials for service {}: {}",
+            self.base.service_id, self.id
+        )
+    }
+}
@@ -0,0 +1,259 @@
+use std::net::IpAddr;
+
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxmoxGuestKind {
+    #[strum(serialize = "VM")]
+    Qemu,
+    #[strum(serialize = "LXC")]
+    Lxc,
+}
+
+impl ProxmoxGuestKind {
+    /// Path segment of the guest's API endpoints
+    pub fn api_path(&self) -> &'static str {
+        match self {
+            ProxmoxGuestKind::Qemu => "qemu",
+            ProxmoxGuestKind::Lxc => "lxc",
+        }
+    }
+}
+
+/// A VM or container and the addresses it was found to have
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxmoxGuest {
+    pub kind: ProxmoxGuestKind,
+    pub vmid: u32,
+    pub name: Option<String>,
+    pub node: String,
+    pub running: bool,
+    pub macs: Vec<MacAddress>,
+    pub ips: Vec<IpAddr>,
+}
+
+impl ProxmoxGuest {
+    pub fn add_mac(&mut self, mac: MacAddress) {
+        if !self.macs.contains(&mac) {
+            self.macs.push(mac);
+        }
+    }
+
+    /// Loopback and link-local addresses aren't reachable from the network, so they're dropped
+    pub fn add_ip(&mut self, ip: IpAddr) {
+        let link_local = match ip {
+            IpAddr::V4(ip) => ip.is_link_local(),
+            IpAddr::V6(ip) => ip.is_unicast_link_local(),
+        };
+        if !ip.is_loopback() && !link_local && !self.ips.contains(&ip) {
+            self.ips.push(ip);
+        }
+    }
+}
+
+/// Every Proxmox API response wraps its payload in `data`
+#[derive(Deserialize)]
+pub struct ProxmoxResponse<T> {
+    pub data: T,
+}
+
+/// Entry of `/cluster/resources?type=vm`
+#[derive(Deserialize)]
+struct ClusterResource {
+    #[serde(rename = "type")]
+    kind: String,
+    vmid: u32,
+    name: Option<String>,
+    node: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    template: u8,
+}
+
+/// Guests listed by `/cluster/resources?type=vm`, without their addresses. Templates are
+/// skipped since they never run.
+pub fn parse_cluster_resources(body: &str) -> Result<Vec<ProxmoxGuest>, serde_json::Error> {
+    let response: ProxmoxResponse<Vec<ClusterResource>> = serde_json::from_str(body)?;
+
+    Ok(response
+        .data
+        .into_iter()
+        .filter(|r| r.template == 0)
+        .filter_map(|r| {
+            let kind = match r.kind.as_str() {
+                "qemu" => ProxmoxGuestKind::Qemu,
+                "lxc" => ProxmoxGuestKind::Lxc,
+                _ => return None,
+            };
+            Some(ProxmoxGuest {
+                kind,
+                vmid: r.vmid,
+                name: r.name,
+                node: r.node,
+                running: r.status == "running",
+                macs: Vec::new(),
+                ips: Vec::new(),
+            })
+        })
+        .collect())
+}
+
+/// Read the `netN` entries of a guest config. VMs give the MAC as the NIC model's value
+/// (`virtio=BC:24:11:..`), containers as `hwaddr` along with any static `ip`/`ip6`.
+pub fn apply_config(guest: &mut ProxmoxGuest, body: &str) -> Result<(), serde_json::Error> {
+    let response: ProxmoxResponse<Map<String, Value>> = serde_json::from_str(body)?;
+
+    let nics = response
+        .data
+        .iter()
+        .filter(|(key, _)| {
+            key.strip_prefix("net")
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|(_, value)| value.as_str());
+
+    for nic in nics {
+        for (key, value) in nic.split(',').filter_map(|option| option.split_once('=')) {
+            match key {
+                // Also "dhcp", "auto" or "manual"
+                "ip" | "ip6" => {
+                    if let Some(ip) = value.split('/').next().and_then(|ip| ip.parse().ok()) {
+                        guest.add_ip(ip);
+                    }
+                }
+                _ => {
+                    if let Ok(mac) = value.parse::<MacAddress>() {
+                        guest.add_mac(mac);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct AgentInterfaces {
+    #[serde(default)]
+    result: Vec<AgentInterface>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct AgentInterface {
+    hardware_address: Option<String>,
+    #[serde(default)]
+    ip_addresses: Vec<AgentIpAddress>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct AgentIpAddress {
+    ip_address: IpAddr,
+}
+
+/// Addresses reported by a VM's guest agent via `/agent/network-get-interfaces`
+pub fn apply_agent_interfaces(
+    guest: &mut ProxmoxGuest,
+    body: &str,
+) -> Result<(), serde_json::Error> {
+    let response: ProxmoxResponse<AgentInterfaces> = serde_json::from_str(body)?;
+
+    for interface in response.data.result {
+        // The loopback interface reports an all-zero MAC
+        if let Some(mac) = interface
+            .hardware_address
+            .and_then(|mac| mac.parse::<MacAddress>().ok())
+            .filter(|mac| mac.bytes() != [0; 6])
+        {
+            guest.add_mac(mac);
+        }
+        for address in interface.ip_addresses {
+            guest.add_ip(address.ip_address);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct LxcInterface {
+    hwaddr: Option<String>,
+    inet: Option<String>,
+    inet6: Option<String>,
+}
+
+/// Addresses of a running container from `/lxc/{vmid}/interfaces`
+pub fn apply_lxc_interfaces(guest: &mut ProxmoxGuest, body: &str) -> Result<(), serde_json::Error> {
+    let response: ProxmoxResponse<Vec<LxcInterface>> = serde_json::from_str(body)?;
+
+    for interface in response.data {
+        if let Some(mac) = interface
+            .hwaddr
+            .and_then(|mac| mac.parse::<MacAddress>().ok())
+            .filter(|mac| mac.bytes() != [0; 6])
+        {
+            guest.add_mac(mac);
+        }
+        let addresses = [interface.inet, interface.inet6];
+        for cidr in addresses
+            .iter()
+            .flatten()
+            .flat_map(|a| a.split_whitespace())
+        {
+            if let Some(ip) = cidr.split('/').next().and_then(|ip| ip.parse().ok()) {
+                guest.add_ip(ip);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_guests_and_their_addresses() {
+        let resources = r#"{"data": [
+            {"type": "qemu", "vmid": 100, "name": "web", "node": "pve1", "status": "running", "template": 0},
+            {"type": "lxc", "vmid": 101, "name": "dns", "node": "pve1", "status": "stopped"},
+            {"type": "qemu", "vmid": 9000, "name": "debian-template", "node": "pve1", "status": "stopped", "template": 1}
+        ]}"#;
+        let mut guests = parse_cluster_resources(resources).unwrap();
+        assert_eq!(guests.len(), 2);
+        assert!(guests[0].running);
+        assert_eq!(guests[1].kind, ProxmoxGuestKind::Lxc);
+
+        let vm_config = r#"{"data": {"net0": "virtio=BC:24:11:AA:BB:CC,bridge=vmbr0,firewall=1", "name": "web"}}"#;
+        apply_config(&mut guests[0], vm_config).unwrap();
+        let agent = r#"{"data": {"result": [
+            {"name": "lo", "hardware-address": "00:00:00:00:00:00", "ip-addresses": [{"ip-address": "127.0.0.1", "ip-address-type": "ipv4", "prefix": 8}]},
+            {"name": "eth0", "hardware-address": "bc:24:11:aa:bb:cc", "ip-addresses": [
+                {"ip-address": "192.168.1.10", "ip-address-type": "ipv4", "prefix": 24},
+                {"ip-address": "fe80::be24:11ff:feaa:bbcc", "ip-address-type": "ipv6", "prefix": 64}
+            ]}
+        ]}}"#;
+        apply_agent_interfaces(&mut guests[0], agent).unwrap();
+        assert_eq!(guests[0].macs.len(), 1);
+        assert_eq!(
+            guests[0].ips,
+            vec!["192.168.1.10".parse::<IpAddr>().unwrap()]
+        );
+
+        let ct_config = r#"{"data": {"net0": "name=eth0,bridge=vmbr0,hwaddr=BC:24:11:00:11:22,ip=192.168.1.20/24,type=veth", "net1": "name=eth1,bridge=vmbr1,hwaddr=BC:24:11:00:11:23,ip=dhcp"}}"#;
+        apply_config(&mut guests[1], ct_config).unwrap();
+        assert_eq!(guests[1].macs.len(), 2);
+        assert_eq!(
+            guests[1].ips,
+            vec!["192.168.1.20".parse::<IpAddr>().unwrap()]
+        );
+    }
+}
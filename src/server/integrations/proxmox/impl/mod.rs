@@ -0,0 +1,4 @@
+pub mod api;
+pub mod base;
+pub mod guests;
+pub mod storage;
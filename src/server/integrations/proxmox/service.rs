@@ -0,0 +1,351 @@
+use crate::server::{
+    discovery::r#impl::types::DiscoveryType,
+    hosts::{
+        r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+            virtualization::{HostVirtualization, ProxmoxVirtualization},
+        },
+        service::HostService,
+    },
+    integrations::{
+        guests::find_guest_host,
+        proxmox::r#impl::{
+            api::{ProxmoxCredentialsRequest, ProxmoxGuestLink, ProxmoxSyncReport},
+            base::{ProxmoxCredentials, ProxmoxCredentialsBase},
+            guests::{
+                ProxmoxGuest, ProxmoxGuestKind, apply_agent_interfaces, apply_config,
+                apply_lxc_interfaces, parse_cluster_resources,
+            },
+        },
+    },
+    services::{r#impl::base::Service, service::ServiceService},
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+        types::entities::{DiscoveryMetadata, EntitySource},
+    },
+    subnets::service::SubnetService,
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+use std::{net::IpAddr, sync::Arc};
+use uuid::Uuid;
+
+/// Port the Proxmox VE API listens on
+const PROXMOX_API_PORT: u16 = 8006;
+
+/// Lists the VMs and containers of a Proxmox service through the Proxmox VE API and marks the
+/// matching hosts as its guests, which is what draws `HostVirtualization` edges to them
+pub struct ProxmoxService {
+    storage: Arc<GenericPostgresStorage<ProxmoxCredentials>>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    subnet_service: Arc<SubnetService>,
+}
+
+impl ProxmoxService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<ProxmoxCredentials>>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        subnet_service: Arc<SubnetService>,
+    ) -> Self {
+        Self {
+            storage,
+            host_service,
+            service_service,
+            subnet_service,
+        }
+    }
+
+    pub async fn get_credentials(&self, service_id: &Uuid) -> Result<Option<ProxmoxCredentials>> {
+        self.storage
+            .get_one(EntityFilter::unfiltered().service_id(service_id))
+            .await
+    }
+
+    /// Store the API token for a service, replacing any previous one
+    pub async fn set_credentials(
+        &self,
+        service: &Service,
+        request: ProxmoxCredentialsRequest,
+    ) -> Result<ProxmoxCredentials> {
+        let base = ProxmoxCredentialsBase {
+            service_id: service.id,
+            network_id: service.base.network_id,
+            url: request
+                .url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            token_id: request.token_id,
+            token_secret: request.token_secret,
+            verify_tls: request.verify_tls,
+            last_synced_at: None,
+        };
+
+        match self.get_credentials(&service.id).await? {
+            Some(mut existing) => {
+                existing.base = ProxmoxCredentialsBase {
+                    last_synced_at: existing.base.last_synced_at,
+                    ..base
+                };
+                self.storage.update(&mut existing).await
+            }
+            None => self.storage.create(&ProxmoxCredentials::new(base)).await,
+        }
+    }
+
+    pub async fn delete_credentials(&self, service_id: &Uuid) -> Result<()> {
+        let credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No Proxmox credentials for service '{}'", service_id))?;
+        self.storage.delete(&credentials.id).await
+    }
+
+    /// List the service's guests and link each to the host it runs as: by MAC, then IP, then
+    /// name. Guests with no host yet are created if one of their addresses is in a known
+    /// subnet.
+    pub async fn sync(&self, service_id: &Uuid) -> Result<ProxmoxSyncReport> {
+        let mut credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No Proxmox credentials for service '{}'", service_id))?;
+        let service = self
+            .service_service
+            .get_by_id(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
+        let proxmox_host = self
+            .host_service
+            .get_by_id(&service.base.host_id)
+            .await?
+            .ok_or_else(|| anyhow!("Host '{}' not found", service.base.host_id))?;
+
+        let url = match &credentials.base.url {
+            Some(url) => url.clone(),
+            None => {
+                let interface =
+                    proxmox_host.base.interfaces.first().ok_or_else(|| {
+                        anyhow!("Proxmox host has no interface to reach the API on")
+                    })?;
+                format!(
+                    "https://{}:{}",
+                    url_host(interface.base.ip_address),
+                    PROXMOX_API_PORT
+                )
+            }
+        };
+
+        let client = ProxmoxClient::new(&url, &credentials.base)?;
+        let guests = client.guests().await?;
+
+        let filter = EntityFilter::unfiltered().network_ids(&[service.base.network_id]);
+        let mut hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter).await?;
+
+        // Guests created here are attributed to the daemon that would rescan the hypervisor
+        let daemon_id = match proxmox_host.base.interfaces.first() {
+            Some(interface) => self
+                .host_service
+                .owning_daemon(&proxmox_host, &interface.base.subnet_id)
+                .await?
+                .map(|d| d.id),
+            None => None,
+        };
+
+        let mut report = ProxmoxSyncReport::default();
+
+        for guest in guests {
+            let virtualization = HostVirtualization::Proxmox(ProxmoxVirtualization {
+                vm_name: guest.name.clone(),
+                vm_id: Some(guest.vmid.to_string()),
+                service_id: service.id,
+            });
+
+            if let Some(index) = find_guest_host(
+                guest.name.as_deref(),
+                &guest.macs,
+                &guest.ips,
+                &hosts,
+                &[proxmox_host.id],
+            ) {
+                let mut host = hosts[index].clone();
+                let mut changed = host.base.virtualization.as_ref() != Some(&virtualization);
+                host.base.virtualization = Some(virtualization);
+
+                // The API knows MACs that a routed scan can't see
+                for interface in host.base.interfaces.iter_mut() {
+                    if interface.base.mac_address.is_none()
+                        && guest.ips.contains(&interface.base.ip_address)
+                        && let [mac] = guest.macs.as_slice()
+                    {
+                        interface.base.mac_address = Some(*mac);
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    hosts[index] = self.host_service.update_host(host).await?;
+                }
+                report.linked.push(ProxmoxGuestLink {
+                    vmid: guest.vmid,
+                    name: guest.name.clone(),
+                    host_id: hosts[index].id,
+                });
+                continue;
+            }
+
+            let interfaces: Vec<Interface> = guest
+                .ips
+                .iter()
+                .filter_map(|ip| {
+                    let subnet = subnets.iter().find(|s| s.base.cidr.contains(ip))?;
+                    Some(Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: *ip,
+                        mac_address: (guest.macs.len() == 1).then(|| guest.macs[0]),
+                        name: None,
+                    }))
+                })
+                .collect();
+
+            let (Some(daemon_id), false) = (daemon_id, interfaces.is_empty()) else {
+                report.unmatched.push(guest);
+                continue;
+            };
+
+            let name = guest
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{} {}", guest.kind, guest.vmid));
+            let host = self
+                .host_service
+                .create_host(Host::new(HostBase {
+                    name: name.clone(),
+                    hostname: guest.name.clone(),
+                    network_id: service.base.network_id,
+                    description: Some(format!("Proxmox {} {}", guest.kind, guest.vmid)),
+                    interfaces,
+                    source: EntitySource::Discovery {
+                        metadata: vec![DiscoveryMetadata::new(
+                            DiscoveryType::Proxmox {
+                                service_id: service.id,
+                            },
+                            daemon_id,
+                        )],
+                    },
+                    virtualization: Some(virtualization),
+                    ..HostBase::default()
+                }))
+                .await?;
+
+            report.created.push(ProxmoxGuestLink {
+                vmid: guest.vmid,
+                name: guest.name.clone(),
+                host_id: host.id,
+            });
+            hosts.push(host);
+        }
+
+        credentials.base.last_synced_at = Some(Utc::now());
+        self.storage.update(&mut credentials).await?;
+
+        tracing::info!(
+            "Proxmox sync for service {}: {} linked, {} created, {} unmatched",
+            service.id,
+            report.linked.len(),
+            report.created.len(),
+            report.unmatched.len()
+        );
+
+        Ok(report)
+    }
+}
+
+fn url_host(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{}]", ip),
+    }
+}
+
+struct ProxmoxClient {
+    client: reqwest::Client,
+    url: String,
+    authorization: String,
+}
+
+impl ProxmoxClient {
+    fn new(url: &str, credentials: &ProxmoxCredentialsBase) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(!credentials.verify_tls)
+            .build()?;
+
+        Ok(Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            authorization: format!(
+                "PVEAPIToken={}={}",
+                credentials.token_id, credentials.token_secret
+            ),
+        })
+    }
+
+    async fn get(&self, path: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(format!("{}/api2/json{}", self.url, path))
+            .header(reqwest::header::AUTHORIZATION, &self.authorization)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            bail!("Proxmox API {} returned HTTP {}", path, response.status());
+        }
+
+        Ok(response.text().await?)
+    }
+
+    /// Every guest in the cluster with the MACs from its config and, if it's running, the
+    /// addresses reported by the guest agent or container
+    async fn guests(&self) -> Result<Vec<ProxmoxGuest>> {
+        let mut guests = parse_cluster_resources(&self.get("/cluster/resources?type=vm").await?)?;
+
+        for guest in guests.iter_mut() {
+            let base = format!(
+                "/nodes/{}/{}/{}",
+                guest.node,
+                guest.kind.api_path(),
+                guest.vmid
+            );
+
+            match self.get(&format!("{}/config", base)).await {
+                Ok(body) => apply_config(guest, &body)?,
+                Err(e) => tracing::warn!("Failed to read config of guest {}: {}", guest.vmid, e),
+            }
+
+            if !guest.running {
+                continue;
+            }
+
+            // VMs without the guest agent installed only have their configured MACs
+            type Apply = fn(&mut ProxmoxGuest, &str) -> Result<(), serde_json::Error>;
+            let (path, apply): (&str, Apply) = match guest.kind {
+                ProxmoxGuestKind::Qemu => ("agent/network-get-interfaces", apply_agent_interfaces),
+                ProxmoxGuestKind::Lxc => ("interfaces", apply_lxc_interfaces),
+            };
+            match self.get(&format!("{}/{}", base, path)).await {
+                Ok(body) => apply(guest, &body)?,
+                Err(e) => tracing::debug!("No addresses for guest {}: {}", guest.vmid, e),
+            }
+        }
+
+        Ok(guests)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::uptime_kuma::r#impl::api::{UptimeKumaSyncReport, UptimeKumaSyncRequest},
+    network_members::handlers::network_role,
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+};
+use axum::{Router, extract::State, response::Json, routing::post};
+use std::sync::Arc;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/sync", post(sync))
+}
+
+/// Import monitors from Uptime Kuma and optionally push unmonitored services back
+async fn sync(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<UptimeKumaSyncRequest>,
+) -> ApiResult<Json<ApiResponse<UptimeKumaSyncReport>>> {
+    network_role(&state, &user.0, &request.network_id).await?;
+
+    if request.push_new_services && (request.username.is_none() || request.password.is_none()) {
+        return Err(ApiError::bad_request(
+            "Username and password are required to push monitors",
+        ));
+    }
+
+    let report = state.services.uptime_kuma_service.sync(&request).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::integrations::uptime_kuma::r#impl::metrics::UptimeKumaMonitor;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeKumaSyncRequest {
+    pub network_id: Uuid,
+    /// Base URL of the Uptime Kuma instance, e.g. http://10.0.0.5:3001
+    pub url: String,
+    /// API key used to read monitors from the /metrics endpoint
+    pub api_key: String,
+    /// Create monitors in Uptime Kuma for services that no monitor matches
+    #[serde(default)]
+    pub push_new_services: bool,
+    /// Uptime Kuma login, required when pushing; its API keys can't create monitors
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UptimeKumaSyncReport {
+    pub matched: Vec<UptimeKumaMonitorMatch>,
+    /// Monitors whose target isn't a known host
+    pub unmatched: Vec<UptimeKumaMonitor>,
+    pub pushed: Vec<UptimeKumaPushedMonitor>,
+    /// Services that could not be pushed, with the reason
+    pub push_errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeKumaMonitorMatch {
+    pub monitor: UptimeKumaMonitor,
+    pub host_id: Uuid,
+    /// None when the monitor targets the host but no service on the monitored port
+    pub service_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeKumaPushedMonitor {
+    pub service_id: Uuid,
+    pub monitor_id: Option<u64>,
+    pub name: String,
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Monitor as listed by Uptime Kuma's Prometheus /metrics endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UptimeKumaMonitor {
+    pub name: String,
+    pub monitor_type: String,
+    pub url: Option<String>,
+    pub hostname: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl UptimeKumaMonitor {
+    /// Host and port the monitor checks, from its URL or its hostname/port fields
+    pub fn target(&self) -> Option<(String, Option<u16>)> {
+        if let Some(url) = self.url.as_deref().and_then(|u| Url::parse(u).ok())
+            && let Some(host) = url.host_str()
+        {
+            let host = host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
+            return Some((host, url.port_or_known_default()));
+        }
+
+        self.hostname.clone().map(|hostname| (hostname, self.port))
+    }
+
+    /// Parse monitors from Prometheus exposition text; each monitor has one
+    /// `monitor_status` sample
+    pub fn parse_metrics(text: &str) -> Vec<Self> {
+        text.lines()
+            .filter_map(|line| line.strip_prefix("monitor_status{"))
+            .map(parse_labels)
+            .filter_map(|labels| {
+                // Uptime Kuma renders missing fields as the string "null"
+                let get = |key: &str| {
+                    labels
+                        .get(key)
+                        .filter(|v| !v.is_empty() && v.as_str() != "null")
+                        .cloned()
+                };
+
+                Some(Self {
+                    name: get("monitor_name")?,
+                    monitor_type: get("monitor_type").unwrap_or_default(),
+                    url: get("monitor_url").filter(|u| u != "https://"),
+                    hostname: get("monitor_hostname"),
+                    port: get("monitor_port").and_then(|p| p.parse().ok()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parse `key="value",key2="value2"}` with backslash escapes, up to the closing brace
+fn parse_labels(input: &str) -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    let mut chars = input.chars().peekable();
+
+    loop {
+        let key: String = chars
+            .by_ref()
+            .skip_while(|c| *c == ',' || c.is_whitespace())
+            .take_while(|c| *c != '=' && *c != '}')
+            .collect();
+
+        if key.is_empty() || chars.next() != Some('"') {
+            break;
+        }
+
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some(escaped) => value.push(escaped),
+                    None => break,
+                },
+                '"' => break,
+                c => value.push(c),
+            }
+        }
+
+        labels.insert(key, value);
+    }
+
+    labels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_keep_escaped_quotes_commas_and_braces() {
+        let labels = parse_labels(r#"monitor_name="Web \"prod\", eu {a}",monitor_type="http"} 1"#);
+
+        assert_eq!(labels["monitor_name"], r#"Web "prod", eu {a}"#);
+        assert_eq!(labels["monitor_type"], "http");
+        assert_eq!(labels.len(), 2);
+    }
+
+    #[test]
+    fn monitors_are_read_from_status_samples_only() {
+        let text = r#"# HELP monitor_status Monitor Status (1 = UP, 0= DOWN)
+monitor_status{monitor_name="NAS, main",monitor_type="port",monitor_url="https://",monitor_hostname="10.0.0.5",monitor_port="445"} 1
+monitor_response_time{monitor_name="NAS, main",monitor_type="port",monitor_url="https://",monitor_hostname="10.0.0.5",monitor_port="445"} 12
+monitor_status{monitor_name="Site",monitor_type="http",monitor_url="https://example.com:8443/",monitor_hostname="null",monitor_port="null"} 0
+monitor_status{monitor_type="http"} 1
+"#;
+
+        let monitors = UptimeKumaMonitor::parse_metrics(text);
+
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[0].name, "NAS, main");
+        assert_eq!(monitors[0].url, None);
+        assert_eq!(
+            monitors[0].target(),
+            Some(("10.0.0.5".to_string(), Some(445)))
+        );
+        assert_eq!(monitors[1].hostname, None);
+        assert_eq!(
+            monitors[1].target(),
+            Some(("example.com".to_string(), Some(8443)))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+use url::Url;
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Minimal Socket.IO (Engine.IO v4, websocket transport) client. Uptime Kuma only exposes
+/// monitor management over Socket.IO, so this implements just enough to emit events and wait
+/// for their acknowledgements.
+pub struct UptimeKumaSocket {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    next_ack_id: u64,
+}
+
+impl UptimeKumaSocket {
+    pub async fn connect(base_url: &str) -> Result<Self> {
+        let mut url = Url::parse(base_url)?.join("socket.io/")?;
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        url.set_scheme(scheme)
+            .map_err(|_| anyhow!("Invalid Uptime Kuma URL: {}", base_url))?;
+        url.set_query(Some("EIO=4&transport=websocket"));
+
+        let (ws, _) = tokio_tungstenite::connect_async(url.as_str()).await?;
+        let mut socket = Self { ws, next_ack_id: 0 };
+
+        // Engine.IO open, then connect to the default namespace
+        socket.wait_for(|packet| packet.starts_with('0')).await?;
+        socket.send("40").await?;
+        socket.wait_for(|packet| packet.starts_with("40")).await?;
+
+        Ok(socket)
+    }
+
+    pub async fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        let response = self
+            .call(
+                "login",
+                json!({ "username": username, "password": password, "token": "" }),
+            )
+            .await?;
+
+        Self::check_ok(&response, "login")
+    }
+
+    /// Create a monitor, returning its ID
+    pub async fn add_monitor(&mut self, monitor: Value) -> Result<Option<u64>> {
+        let response = self.call("add", monitor).await?;
+        Self::check_ok(&response, "add monitor")?;
+
+        Ok(response.get("monitorID").and_then(|id| id.as_u64()))
+    }
+
+    pub async fn close(mut self) -> Result<()> {
+        self.ws.close(None).await?;
+        Ok(())
+    }
+
+    fn check_ok(response: &Value, action: &str) -> Result<()> {
+        if response.get("ok").and_then(|ok| ok.as_bool()) == Some(true) {
+            return Ok(());
+        }
+
+        let msg = response
+            .get("msg")
+            .and_then(|m| m.as_str())
+            .unwrap_or("Unknown error");
+        bail!("Uptime Kuma {} failed: {}", action, msg)
+    }
+
+    /// Emit an event and wait for its acknowledgement
+    async fn call(&mut self, event: &str, argument: Value) -> Result<Value> {
+        let ack_id = self.next_ack_id;
+        self.next_ack_id += 1;
+
+        self.send(&format!("42{}{}", ack_id, json!([event, argument])))
+            .await?;
+
+        let prefix = format!("43{}[", ack_id);
+        let packet = self.wait_for(|packet| packet.starts_with(&prefix)).await?;
+
+        let arguments: Vec<Value> = serde_json::from_str(&packet[prefix.len() - 1..])?;
+        Ok(arguments.into_iter().next().unwrap_or(Value::Null))
+    }
+
+    async fn send(&mut self, packet: &str) -> Result<()> {
+        self.ws.send(Message::Text(packet.into())).await?;
+        Ok(())
+    }
+
+    /// Read packets until one matches, answering pings and skipping unrelated events
+    async fn wait_for(&mut self, matches: impl Fn(&str) -> bool) -> Result<String> {
+        tokio::time::timeout(RESPONSE_TIMEOUT, async {
+            loop {
+                match self.ws.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        let packet = text.as_str();
+                        if packet == "2" {
+                            self.send("3").await?;
+                        } else if matches(packet) {
+                            return Ok(packet.to_string());
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        bail!("Uptime Kuma closed the connection")
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for Uptime Kuma"))?
+    }
+}
@@ -0,0 +1,227 @@
+use crate::server::{
+    hosts::{
+        r#impl::{base::Host, ports::TransportProtocol},
+        service::HostService,
+    },
+    integrations::uptime_kuma::r#impl::{
+        api::{
+            UptimeKumaMonitorMatch, UptimeKumaPushedMonitor, UptimeKumaSyncReport,
+            UptimeKumaSyncRequest,
+        },
+        metrics::UptimeKumaMonitor,
+        socket::UptimeKumaSocket,
+    },
+    services::{r#impl::base::Service, service::ServiceService},
+    shared::{services::traits::CrudService, storage::filter::EntityFilter},
+};
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+use std::{collections::HashSet, net::IpAddr, sync::Arc};
+use uuid::Uuid;
+
+pub struct UptimeKumaService {
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    client: reqwest::Client,
+}
+
+impl UptimeKumaService {
+    pub fn new(host_service: Arc<HostService>, service_service: Arc<ServiceService>) -> Self {
+        Self {
+            host_service,
+            service_service,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Match Uptime Kuma monitors to hosts and services in a network, optionally creating
+    /// monitors for services that aren't monitored yet
+    pub async fn sync(&self, request: &UptimeKumaSyncRequest) -> Result<UptimeKumaSyncReport> {
+        let filter = EntityFilter::unfiltered().network_ids(&[request.network_id]);
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter).await?;
+
+        let monitors = self.fetch_monitors(request).await?;
+        let mut report = UptimeKumaSyncReport::default();
+
+        for monitor in monitors {
+            match Self::match_monitor(&monitor, &hosts, &services) {
+                Some((host_id, service_id)) => report.matched.push(UptimeKumaMonitorMatch {
+                    monitor,
+                    host_id,
+                    service_id,
+                }),
+                None => report.unmatched.push(monitor),
+            }
+        }
+
+        if request.push_new_services {
+            let monitored: HashSet<Uuid> =
+                report.matched.iter().filter_map(|m| m.service_id).collect();
+
+            let new_monitors: Vec<(&Service, Value)> = services
+                .iter()
+                .filter(|s| !monitored.contains(&s.id))
+                .filter_map(|service| {
+                    let host = hosts.iter().find(|h| h.id == service.base.host_id)?;
+                    Some((service, Self::monitor_for(service, host)?))
+                })
+                .collect();
+
+            if !new_monitors.is_empty() {
+                self.push_monitors(request, new_monitors, &mut report)
+                    .await?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn fetch_monitors(
+        &self,
+        request: &UptimeKumaSyncRequest,
+    ) -> Result<Vec<UptimeKumaMonitor>> {
+        let url = format!("{}/metrics", request.url.trim_end_matches('/'));
+
+        // Uptime Kuma API keys are sent as the basic auth password with an empty username
+        let response = self
+            .client
+            .get(&url)
+            .basic_auth("", Some(&request.api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Failed to read monitors from Uptime Kuma: HTTP {}",
+                response.status()
+            );
+        }
+
+        Ok(UptimeKumaMonitor::parse_metrics(&response.text().await?))
+    }
+
+    async fn push_monitors(
+        &self,
+        request: &UptimeKumaSyncRequest,
+        new_monitors: Vec<(&Service, Value)>,
+        report: &mut UptimeKumaSyncReport,
+    ) -> Result<()> {
+        let (Some(username), Some(password)) = (&request.username, &request.password) else {
+            return Err(anyhow!(
+                "Username and password are required to push monitors to Uptime Kuma"
+            ));
+        };
+
+        let mut socket = UptimeKumaSocket::connect(&request.url).await?;
+        socket.login(username, password).await?;
+
+        for (service, monitor) in new_monitors {
+            match socket.add_monitor(monitor).await {
+                Ok(monitor_id) => report.pushed.push(UptimeKumaPushedMonitor {
+                    service_id: service.id,
+                    monitor_id,
+                    name: service.base.name.clone(),
+                }),
+                Err(e) => report
+                    .push_errors
+                    .push(format!("{}: {}", service.base.name, e)),
+            }
+        }
+
+        socket.close().await
+    }
+
+    /// Host matched by interface IP, hostname or name; service matched by bound port number
+    fn match_monitor(
+        monitor: &UptimeKumaMonitor,
+        hosts: &[Host],
+        services: &[Service],
+    ) -> Option<(Uuid, Option<Uuid>)> {
+        let (target, port) = monitor.target()?;
+        let target_ip = target.parse::<IpAddr>().ok();
+
+        let host = hosts.iter().find(|host| match target_ip {
+            Some(ip) => host.base.interfaces.iter().any(|i| i.base.ip_address == ip),
+            None => {
+                host.base
+                    .hostname
+                    .as_deref()
+                    .is_some_and(|h| h.eq_ignore_ascii_case(&target))
+                    || host.base.name.eq_ignore_ascii_case(&target)
+            }
+        })?;
+
+        let service_id = port.and_then(|port| {
+            services
+                .iter()
+                .filter(|s| s.base.host_id == host.id)
+                .find(|s| {
+                    s.base
+                        .bindings
+                        .iter()
+                        .filter_map(|b| b.port_id())
+                        .filter_map(|id| host.get_port(&id))
+                        .any(|p| p.base.number() == port)
+                })
+                .map(|s| s.id)
+        });
+
+        Some((host.id, service_id))
+    }
+
+    /// Uptime Kuma monitor for a service's first TCP port: HTTP(S) for web ports, otherwise a
+    /// TCP port check
+    fn monitor_for(service: &Service, host: &Host) -> Option<Value> {
+        let (interface, port) = service.base.bindings.iter().find_map(|binding| {
+            let port = host.get_port(&binding.port_id()?)?;
+            if port.base.protocol() != TransportProtocol::Tcp {
+                return None;
+            }
+
+            let interface = match binding.interface_id() {
+                Some(_) => host.get_interface(&binding.interface_id())?,
+                None => host.base.interfaces.first()?,
+            };
+
+            Some((interface, port.base.number()))
+        })?;
+
+        let ip = interface.base.ip_address;
+        let name = format!("{} ({})", service.base.name, host.base.name);
+
+        let mut monitor = json!({
+            "name": name,
+            "interval": 60,
+            "retryInterval": 60,
+            "maxretries": 0,
+            "accepted_statuscodes": ["200-299"],
+            "notificationIDList": {},
+        });
+
+        let scheme = match port {
+            80 | 8080 => Some("http"),
+            443 | 8443 => Some("https"),
+            _ => None,
+        };
+
+        let host_str = match ip {
+            IpAddr::V4(_) => ip.to_string(),
+            IpAddr::V6(_) => format!("[{}]", ip),
+        };
+
+        match scheme {
+            Some(scheme) => {
+                monitor["type"] = json!("http");
+                monitor["url"] = json!(format!("{}://{}:{}", scheme, host_str, port));
+            }
+            None => {
+                monitor["type"] = json!("port");
+                monitor["hostname"] = json!(ip.to_string());
+                monitor["port"] = json!(port);
+            }
+        }
+
+        Some(monitor)
+    }
+}
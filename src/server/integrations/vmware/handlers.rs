@@ -0,0 +1,127 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    integrations::vmware::r#impl::{
+        api::{VmwareCredentialsRequest, VmwareSyncReport},
+        base::VmwareCredentials,
+    },
+    services::{
+        definitions::{vmware_esxi::VmwareEsxi, vmware_vcenter::VmwareVcenter},
+        r#impl::base::Service,
+    },
+    shared::{
+        handlers::traits::get_accessible,
+        types::{
+            api::{ApiError, ApiResponse, ApiResult},
+            metadata::HasId,
+        },
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{service_id}/credentials", get(get_credentials))
+        .route("/{service_id}/credentials", put(set_credentials))
+        .route("/{service_id}/credentials", delete(delete_credentials))
+        .route("/{service_id}/sync", post(sync))
+}
+
+/// An ESXi or vCenter service in one of the user's networks
+async fn vmware_service(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    service_id: Uuid,
+) -> ApiResult<Service> {
+    let service: Service = get_accessible(state, &user.0, &service_id).await?;
+
+    let definition_id = service.base.service_definition.id();
+    if definition_id != VmwareEsxi.id() && definition_id != VmwareVcenter.id() {
+        return Err(ApiError::bad_request(&format!(
+            "Service '{}' isn't an ESXi or vCenter service",
+            service.base.name
+        )));
+    }
+
+    Ok(service)
+}
+
+async fn get_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<VmwareCredentials>>> {
+    vmware_service(&state, &user, service_id).await?;
+
+    let credentials = state
+        .services
+        .vmware_service
+        .get_credentials(&service_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "No VMware credentials for service '{}'",
+                service_id
+            ))
+        })?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+/// Store the vCenter login used to list the service's VMs
+async fn set_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+    Json(request): Json<VmwareCredentialsRequest>,
+) -> ApiResult<Json<ApiResponse<VmwareCredentials>>> {
+    let service = vmware_service(&state, &user, service_id).await?;
+
+    if request.username.is_empty() || request.password.is_empty() {
+        return Err(ApiError::bad_request("Username and password are required"));
+    }
+
+    let credentials = state
+        .services
+        .vmware_service
+        .set_credentials(&service, request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(credentials)))
+}
+
+async fn delete_credentials(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    vmware_service(&state, &user, service_id).await?;
+
+    state
+        .services
+        .vmware_service
+        .delete_credentials(&service_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// List the VMs known to vCenter and link them to their hosts
+async fn sync(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<VmwareSyncReport>>> {
+    vmware_service(&state, &user, service_id).await?;
+
+    let report = state.services.vmware_service.sync(&service_id).await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
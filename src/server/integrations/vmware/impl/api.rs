@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::integrations::vmware::r#impl::inventory::VsphereVm;
+
+/// vSphere login to store for a vCenter or ESXi service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmwareCredentialsRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub verify_tls: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct VmwareSyncReport {
+    /// ESXi hosts managed by vCenter and the known host each was matched to
+    pub esxi_hosts: Vec<VmwareEsxiHostLink>,
+    /// Known hosts now marked as VMs
+    pub linked: Vec<VmwareVmLink>,
+    /// VMs that weren't known yet, created in the subnet their address falls in
+    pub created: Vec<VmwareVmLink>,
+    /// VMs with no known host and no address in a known subnet, e.g. powered off VMs
+    pub unmatched: Vec<VsphereVm>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmwareEsxiHostLink {
+    pub name: String,
+    pub host_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmwareVmLink {
+    pub vm_id: String,
+    pub name: String,
+    pub host_id: Uuid,
+}
@@ -0,0 +1,48 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// vSphere login for a vCenter or ESXi service, used to list its VMs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmwareCredentialsBase {
+    pub service_id: Uuid,
+    pub network_id: Uuid,
+    /// Base URL of the vSphere REST API, e.g. https://vcenter.lab; the service's first
+    /// interface when unset
+    pub url: Option<String>,
+    /// e.g. administrator@vsphere.local; a read-only role is enough
+    pub username: String,
+    #[serde(serialize_with = "serialize_password")]
+    pub password: String,
+    /// vCenter and ESXi ship with self-signed certificates, so they aren't verified by default
+    pub verify_tls: bool,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+fn serialize_password<S>(_password: &String, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("***REDACTED***")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VmwareCredentials {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: VmwareCredentialsBase,
+}
+
+impl Display for VmwareCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "VMware credentials for service {}: {}",
+            self.base.service_id, self.id
+        )
+    }
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, net::IpAddr};
+
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+
+/// ESXi host as listed by `/api/vcenter/host`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VsphereHost {
+    /// Managed object id, e.g. host-10
+    pub host: String,
+    /// Name the host was added to vCenter with, usually its FQDN or IP
+    pub name: String,
+}
+
+/// A VM, its NICs and the addresses VMware Tools reports for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VsphereVm {
+    /// Managed object id, e.g. vm-42
+    pub vm_id: String,
+    pub name: String,
+    /// Name of the ESXi host the VM runs on
+    pub esxi_host: Option<String>,
+    pub powered_on: bool,
+    pub nics: Vec<VsphereNic>,
+    pub ips: Vec<IpAddr>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VsphereNic {
+    pub mac: Option<MacAddress>,
+    pub port_group: Option<String>,
+}
+
+impl VsphereVm {
+    pub fn macs(&self) -> Vec<MacAddress> {
+        self.nics.iter().filter_map(|n| n.mac).collect()
+    }
+
+    pub fn port_groups(&self) -> Vec<String> {
+        let mut port_groups: Vec<String> = Vec::new();
+        for port_group in self.nics.iter().filter_map(|n| n.port_group.as_ref()) {
+            if !port_groups.contains(port_group) {
+                port_groups.push(port_group.clone());
+            }
+        }
+        port_groups
+    }
+
+    pub fn port_group_of(&self, mac: &MacAddress) -> Option<&str> {
+        self.nics
+            .iter()
+            .find(|n| n.mac.as_ref() == Some(mac))
+            .and_then(|n| n.port_group.as_deref())
+    }
+}
+
+/// Port group names by network id, from `/api/vcenter/network`
+pub fn parse_networks(body: &str) -> Result<HashMap<String, String>, serde_json::Error> {
+    #[derive(Deserialize)]
+    struct Network {
+        network: String,
+        name: String,
+    }
+
+    let networks: Vec<Network> = serde_json::from_str(body)?;
+    Ok(networks.into_iter().map(|n| (n.network, n.name)).collect())
+}
+
+/// VMs listed by `/api/vcenter/vm`, without their NICs
+pub fn parse_vms(body: &str, esxi_host: Option<&str>) -> Result<Vec<VsphereVm>, serde_json::Error> {
+    #[derive(Deserialize)]
+    struct VmSummary {
+        vm: String,
+        name: String,
+        power_state: String,
+    }
+
+    let vms: Vec<VmSummary> = serde_json::from_str(body)?;
+    Ok(vms
+        .into_iter()
+        .map(|vm| VsphereVm {
+            vm_id: vm.vm,
+            name: vm.name,
+            esxi_host: esxi_host.map(str::to_string),
+            powered_on: vm.power_state == "POWERED_ON",
+            nics: Vec::new(),
+            ips: Vec::new(),
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct VmInfo {
+    #[serde(default)]
+    nics: HashMap<String, VmNic>,
+}
+
+#[derive(Deserialize)]
+struct VmNic {
+    mac_address: Option<String>,
+    backing: Option<VmNicBacking>,
+}
+
+#[derive(Deserialize)]
+struct VmNicBacking {
+    network: Option<String>,
+    network_name: Option<String>,
+}
+
+/// NICs from `/api/vcenter/vm/{vm}`, with the port group each is attached to
+pub fn apply_vm_info(
+    vm: &mut VsphereVm,
+    body: &str,
+    networks: &HashMap<String, String>,
+) -> Result<(), serde_json::Error> {
+    let info: VmInfo = serde_json::from_str(body)?;
+
+    // Keyed by device key; sorted so NICs keep their order between syncs
+    let mut nics: Vec<(String, VmNic)> = info.nics.into_iter().collect();
+    nics.sort_by(|a, b| a.0.cmp(&b.0));
+
+    vm.nics = nics
+        .into_iter()
+        .map(|(_, nic)| VsphereNic {
+            mac: nic.mac_address.and_then(|mac| mac.parse().ok()),
+            port_group: nic.backing.and_then(|backing| {
+                backing
+                    .network_name
+                    .or_else(|| networks.get(backing.network.as_deref()?).cloned())
+            }),
+        })
+        .collect();
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct GuestInterface {
+    #[serde(default)]
+    ip: Option<GuestIpConfig>,
+}
+
+#[derive(Deserialize)]
+struct GuestIpConfig {
+    #[serde(default)]
+    ip_addresses: Vec<GuestIpAddress>,
+}
+
+#[derive(Deserialize)]
+struct GuestIpAddress {
+    ip_address: IpAddr,
+}
+
+/// Addresses from `/api/vcenter/vm/{vm}/guest/networking/interfaces`, which needs VMware Tools
+/// running in the guest. Loopback and link-local addresses are dropped.
+pub fn apply_guest_interfaces(vm: &mut VsphereVm, body: &str) -> Result<(), serde_json::Error> {
+    let interfaces: Vec<GuestInterface> = serde_json::from_str(body)?;
+
+    for address in interfaces
+        .into_iter()
+        .filter_map(|i| i.ip)
+        .flat_map(|ip| ip.ip_addresses)
+    {
+        let ip = address.ip_address;
+        let link_local = match ip {
+            IpAddr::V4(ip) => ip.is_link_local(),
+            IpAddr::V6(ip) => ip.is_unicast_link_local(),
+        };
+        if !ip.is_loopback() && !link_local && !vm.ips.contains(&ip) {
+            vm.ips.push(ip);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_vm_nics_and_guest_addresses() {
+        let networks = parse_networks(
+            r#"[{"network": "network-13", "name": "VM Network", "type": "STANDARD_PORTGROUP"},
+                {"network": "dvportgroup-20", "name": "IoT", "type": "DISTRIBUTED_PORTGROUP"}]"#,
+        )
+        .unwrap();
+
+        let mut vms = parse_vms(
+            r#"[{"vm": "vm-42", "name": "web", "power_state": "POWERED_ON", "cpu_count": 2}]"#,
+            Some("esxi1.lab"),
+        )
+        .unwrap();
+        let vm = &mut vms[0];
+        assert!(vm.powered_on);
+
+        apply_vm_info(
+            vm,
+            r#"{"name": "web", "nics": {
+                "4001": {"mac_address": "00:50:56:aa:bb:02", "backing": {"type": "DISTRIBUTED_PORTGROUP", "network": "dvportgroup-20"}},
+                "4000": {"mac_address": "00:50:56:aa:bb:01", "backing": {"type": "STANDARD_PORTGROUP", "network": "network-13", "network_name": "VM Network"}}
+            }}"#,
+            &networks,
+        )
+        .unwrap();
+        assert_eq!(vm.port_groups(), vec!["VM Network", "IoT"]);
+
+        apply_guest_interfaces(
+            vm,
+            r#"[{"mac_address": "00:50:56:aa:bb:01", "nic": "4000", "ip": {"ip_addresses": [
+                {"ip_address": "192.168.1.50", "prefix_length": 24, "state": "PREFERRED"},
+                {"ip_address": "fe80::250:56ff:feaa:bb01", "prefix_length": 64, "state": "UNKNOWN"}
+            ]}}]"#,
+        )
+        .unwrap();
+        assert_eq!(vm.ips, vec!["192.168.1.50".parse::<IpAddr>().unwrap()]);
+        assert_eq!(
+            vm.port_group_of(&"00:50:56:aa:bb:02".parse().unwrap()),
+            Some("IoT")
+        );
+    }
+}
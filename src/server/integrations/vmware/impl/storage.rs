@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    integrations::vmware::r#impl::base::{VmwareCredentials, VmwareCredentialsBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for VmwareCredentials {
+    type BaseData = VmwareCredentialsBase;
+
+    fn table_name() -> &'static str {
+        "vmware_credentials"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    service_id,
+                    network_id,
+                    url,
+                    username,
+                    password,
+                    verify_tls,
+                    last_synced_at,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "service_id",
+                "network_id",
+                "url",
+                "username",
+                "password",
+                "verify_tls",
+                "last_synced_at",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(service_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::OptionalString(url),
+                SqlValue::String(username),
+                SqlValue::String(password),
+                SqlValue::Bool(verify_tls),
+                SqlValue::OptionTimestamp(last_synced_at),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(VmwareCredentials {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: VmwareCredentialsBase {
+                service_id: row.get("service_id"),
+                network_id: row.get("network_id"),
+                url: row.get("url"),
+                username: row.get("username"),
+                password: row.get("password"),
+                verify_tls: row.get("verify_tls"),
+                last_synced_at: row.get("last_synced_at"),
+            },
+        })
+    }
+}
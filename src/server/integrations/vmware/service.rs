@@ -0,0 +1,423 @@
+use crate::server::{
+    discovery::r#impl::types::DiscoveryType,
+    hosts::{
+        r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+            virtualization::{HostVirtualization, VmwareVirtualization},
+        },
+        service::HostService,
+    },
+    integrations::{
+        guests::find_guest_host,
+        vmware::r#impl::{
+            api::{VmwareCredentialsRequest, VmwareEsxiHostLink, VmwareSyncReport, VmwareVmLink},
+            base::{VmwareCredentials, VmwareCredentialsBase},
+            inventory::{
+                VsphereHost, VsphereVm, apply_guest_interfaces, apply_vm_info, parse_networks,
+                parse_vms,
+            },
+        },
+    },
+    services::{
+        definitions::vmware_esxi::VmwareEsxi, r#impl::base::Service, service::ServiceService,
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+        types::{
+            entities::{DiscoveryMetadata, EntitySource},
+            metadata::HasId,
+        },
+    },
+    subnets::service::SubnetService,
+};
+use anyhow::{Result, anyhow, bail};
+use chrono::Utc;
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use uuid::Uuid;
+
+/// Lists the VMs managed by vCenter through the vSphere REST API and marks the matching hosts
+/// as VMs of the ESXi host they run on. Standalone ESXi hosts don't serve this API, so the
+/// credentials of an ESXi service should point at its vCenter.
+pub struct VmwareService {
+    storage: Arc<GenericPostgresStorage<VmwareCredentials>>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    subnet_service: Arc<SubnetService>,
+}
+
+impl VmwareService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<VmwareCredentials>>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        subnet_service: Arc<SubnetService>,
+    ) -> Self {
+        Self {
+            storage,
+            host_service,
+            service_service,
+            subnet_service,
+        }
+    }
+
+    pub async fn get_credentials(&self, service_id: &Uuid) -> Result<Option<VmwareCredentials>> {
+        self.storage
+            .get_one(EntityFilter::unfiltered().service_id(service_id))
+            .await
+    }
+
+    /// Store the vSphere login for a service, replacing any previous one
+    pub async fn set_credentials(
+        &self,
+        service: &Service,
+        request: VmwareCredentialsRequest,
+    ) -> Result<VmwareCredentials> {
+        let base = VmwareCredentialsBase {
+            service_id: service.id,
+            network_id: service.base.network_id,
+            url: request
+                .url
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            username: request.username,
+            password: request.password,
+            verify_tls: request.verify_tls,
+            last_synced_at: None,
+        };
+
+        match self.get_credentials(&service.id).await? {
+            Some(mut existing) => {
+                existing.base = VmwareCredentialsBase {
+                    last_synced_at: existing.base.last_synced_at,
+                    ..base
+                };
+                self.storage.update(&mut existing).await
+            }
+            None => self.storage.create(&VmwareCredentials::new(base)).await,
+        }
+    }
+
+    pub async fn delete_credentials(&self, service_id: &Uuid) -> Result<()> {
+        let credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No VMware credentials for service '{}'", service_id))?;
+        self.storage.delete(&credentials.id).await
+    }
+
+    /// Match each ESXi host to a known host, then link each VM to the host it runs as: by
+    /// MAC, then IP, then name. VMs with no host yet are created if one of their addresses is
+    /// in a known subnet.
+    pub async fn sync(&self, service_id: &Uuid) -> Result<VmwareSyncReport> {
+        let mut credentials = self
+            .get_credentials(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("No VMware credentials for service '{}'", service_id))?;
+        let service = self
+            .service_service
+            .get_by_id(service_id)
+            .await?
+            .ok_or_else(|| anyhow!("Service '{}' not found", service_id))?;
+        let service_host = self
+            .host_service
+            .get_by_id(&service.base.host_id)
+            .await?
+            .ok_or_else(|| anyhow!("Host '{}' not found", service.base.host_id))?;
+
+        let url = match &credentials.base.url {
+            Some(url) => url.clone(),
+            None => {
+                let interface =
+                    service_host.base.interfaces.first().ok_or_else(|| {
+                        anyhow!("VMware host has no interface to reach the API on")
+                    })?;
+                format!("https://{}", url_host(interface.base.ip_address))
+            }
+        };
+
+        let client = VsphereClient::login(&url, &credentials.base).await?;
+        let inventory = client.inventory().await;
+        client.logout().await;
+        let (esxi_hosts, vms) = inventory?;
+
+        let filter = EntityFilter::unfiltered().network_ids(&[service.base.network_id]);
+        let mut hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter).await?;
+
+        let mut report = VmwareSyncReport::default();
+
+        // VMs are drawn from the ESXi service of the host they run on, if it was discovered
+        let mut hypervisor_host_ids = vec![service_host.id];
+        let mut hypervisor_service_ids: HashMap<String, Uuid> = HashMap::new();
+        for esxi_host in &esxi_hosts {
+            let host = find_esxi_host(esxi_host, &hosts);
+            if let Some(host) = host {
+                hypervisor_host_ids.push(host.id);
+                if let Some(esxi_service) = services.iter().find(|s| {
+                    s.base.host_id == host.id && s.base.service_definition.id() == VmwareEsxi.id()
+                }) {
+                    hypervisor_service_ids.insert(esxi_host.name.clone(), esxi_service.id);
+                }
+            }
+            report.esxi_hosts.push(VmwareEsxiHostLink {
+                name: esxi_host.name.clone(),
+                host_id: host.map(|h| h.id),
+            });
+        }
+
+        // VMs created here are attributed to the daemon that would rescan the vCenter host
+        let daemon_id = match service_host.base.interfaces.first() {
+            Some(interface) => self
+                .host_service
+                .owning_daemon(&service_host, &interface.base.subnet_id)
+                .await?
+                .map(|d| d.id),
+            None => None,
+        };
+
+        for vm in vms {
+            let macs = vm.macs();
+            let virtualization = HostVirtualization::Vmware(VmwareVirtualization {
+                vm_name: Some(vm.name.clone()),
+                vm_id: Some(vm.vm_id.clone()),
+                service_id: vm
+                    .esxi_host
+                    .as_ref()
+                    .and_then(|name| hypervisor_service_ids.get(name))
+                    .copied()
+                    .unwrap_or(service.id),
+                port_groups: vm.port_groups(),
+            });
+
+            if let Some(index) =
+                find_guest_host(Some(&vm.name), &macs, &vm.ips, &hosts, &hypervisor_host_ids)
+            {
+                let mut host = hosts[index].clone();
+                let mut changed = host.base.virtualization.as_ref() != Some(&virtualization);
+                host.base.virtualization = Some(virtualization);
+
+                for interface in host.base.interfaces.iter_mut() {
+                    // The API knows MACs that a routed scan can't see
+                    if interface.base.mac_address.is_none()
+                        && vm.ips.contains(&interface.base.ip_address)
+                        && let [mac] = macs.as_slice()
+                    {
+                        interface.base.mac_address = Some(*mac);
+                        changed = true;
+                    }
+                    if interface.base.name.is_none()
+                        && let Some(port_group) = interface
+                            .base
+                            .mac_address
+                            .and_then(|mac| vm.port_group_of(&mac))
+                    {
+                        interface.base.name = Some(port_group.to_string());
+                        changed = true;
+                    }
+                }
+
+                if changed {
+                    hosts[index] = self.host_service.update_host(host).await?;
+                }
+                report.linked.push(VmwareVmLink {
+                    vm_id: vm.vm_id.clone(),
+                    name: vm.name.clone(),
+                    host_id: hosts[index].id,
+                });
+                continue;
+            }
+
+            let mac = (macs.len() == 1).then(|| macs[0]);
+            let interfaces: Vec<Interface> = vm
+                .ips
+                .iter()
+                .filter_map(|ip| {
+                    let subnet = subnets.iter().find(|s| s.base.cidr.contains(ip))?;
+                    Some(Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: *ip,
+                        mac_address: mac,
+                        name: mac
+                            .and_then(|mac| vm.port_group_of(&mac))
+                            .map(str::to_string),
+                    }))
+                })
+                .collect();
+
+            let (Some(daemon_id), false) = (daemon_id, interfaces.is_empty()) else {
+                report.unmatched.push(vm);
+                continue;
+            };
+
+            let host = self
+                .host_service
+                .create_host(Host::new(HostBase {
+                    name: vm.name.clone(),
+                    hostname: None,
+                    network_id: service.base.network_id,
+                    description: Some(format!("VMware VM {}", vm.vm_id)),
+                    interfaces,
+                    source: EntitySource::Discovery {
+                        metadata: vec![DiscoveryMetadata::new(
+                            DiscoveryType::Vmware {
+                                service_id: service.id,
+                            },
+                            daemon_id,
+                        )],
+                    },
+                    virtualization: Some(virtualization),
+                    ..HostBase::default()
+                }))
+                .await?;
+
+            report.created.push(VmwareVmLink {
+                vm_id: vm.vm_id.clone(),
+                name: vm.name.clone(),
+                host_id: host.id,
+            });
+            hosts.push(host);
+        }
+
+        credentials.base.last_synced_at = Some(Utc::now());
+        self.storage.update(&mut credentials).await?;
+
+        tracing::info!(
+            "VMware sync for service {}: {} linked, {} created, {} unmatched",
+            service.id,
+            report.linked.len(),
+            report.created.len(),
+            report.unmatched.len()
+        );
+
+        Ok(report)
+    }
+}
+
+/// Known host an ESXi host was added to vCenter as, by IP or by full or short hostname
+fn find_esxi_host<'a>(esxi_host: &VsphereHost, hosts: &'a [Host]) -> Option<&'a Host> {
+    if let Ok(ip) = esxi_host.name.parse::<IpAddr>() {
+        return hosts
+            .iter()
+            .find(|h| h.base.interfaces.iter().any(|i| i.base.ip_address == ip));
+    }
+
+    let short_name = esxi_host.name.split('.').next().unwrap_or(&esxi_host.name);
+    hosts.iter().find(|h| {
+        [Some(h.base.name.as_str()), h.base.hostname.as_deref()]
+            .into_iter()
+            .flatten()
+            .any(|name| {
+                name.eq_ignore_ascii_case(&esxi_host.name) || name.eq_ignore_ascii_case(short_name)
+            })
+    })
+}
+
+fn url_host(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{}]", ip),
+    }
+}
+
+struct VsphereClient {
+    client: reqwest::Client,
+    url: String,
+    session: String,
+}
+
+impl VsphereClient {
+    /// Open an API session with the stored login
+    async fn login(url: &str, credentials: &VmwareCredentialsBase) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(!credentials.verify_tls)
+            .build()?;
+        let url = url.trim_end_matches('/').to_string();
+
+        let response = client
+            .post(format!("{}/api/session", url))
+            .basic_auth(&credentials.username, Some(&credentials.password))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            bail!("vSphere login failed: HTTP {}", response.status());
+        }
+
+        let session: String = response.json().await?;
+
+        Ok(Self {
+            client,
+            url,
+            session,
+        })
+    }
+
+    async fn logout(&self) {
+        let result = self
+            .client
+            .delete(format!("{}/api/session", self.url))
+            .header("vmware-api-session-id", &self.session)
+            .send()
+            .await;
+        if let Err(e) = result {
+            tracing::debug!("Failed to close vSphere session: {}", e);
+        }
+    }
+
+    async fn get(&self, path: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(format!("{}{}", self.url, path))
+            .header("vmware-api-session-id", &self.session)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            bail!("vSphere API {} returned HTTP {}", path, response.status());
+        }
+
+        Ok(response.text().await?)
+    }
+
+    /// ESXi hosts and their VMs, with NICs and, for powered on VMs with VMware Tools, their
+    /// addresses
+    async fn inventory(&self) -> Result<(Vec<VsphereHost>, Vec<VsphereVm>)> {
+        let networks = parse_networks(&self.get("/api/vcenter/network").await?)?;
+        let esxi_hosts: Vec<VsphereHost> =
+            serde_json::from_str(&self.get("/api/vcenter/host").await?)?;
+
+        let mut vms = Vec::new();
+        for esxi_host in &esxi_hosts {
+            let body = self
+                .get(&format!("/api/vcenter/vm?hosts={}", esxi_host.host))
+                .await?;
+            vms.extend(parse_vms(&body, Some(&esxi_host.name))?);
+        }
+
+        for vm in vms.iter_mut() {
+            match self.get(&format!("/api/vcenter/vm/{}", vm.vm_id)).await {
+                Ok(body) => apply_vm_info(vm, &body, &networks)?,
+                Err(e) => tracing::warn!("Failed to read VM {}: {}", vm.vm_id, e),
+            }
+
+            if !vm.powered_on {
+                continue;
+            }
+
+            let path = format!("/api/vcenter/vm/{}/guest/networking/interfaces", vm.vm_id);
+            match self.get(&path).await {
+                Ok(body) => apply_guest_interfaces(vm, &body)?,
+                Err(e) => tracing::debug!("No guest addresses for VM {}: {}", vm.vm_id, e),
+            }
+        }
+
+        Ok((esxi_hosts, vms))
+    }
+}
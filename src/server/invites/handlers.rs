@@ -0,0 +1,161 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    invites::{
+        r#impl::{
+            api::{CreateInviteRequest, InviteDetails, InviteResponse},
+            base::Invite,
+        },
+        service::InviteService,
+    },
+    network_members::handlers::require_admin,
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use chrono::Duration;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Invites to the network that can still be accepted
+pub async fn get_invites(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<Invite>>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let invites = state.services.invite_service.pending(&id).await?;
+
+    Ok(Json(ApiResponse::success(invites)))
+}
+
+/// Invite someone without an account, emailing them the link when SMTP is configured
+pub async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<CreateInviteRequest>,
+) -> ApiResult<Json<ApiResponse<InviteResponse>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let expires_in_days = request
+        .expires_in_days
+        .unwrap_or(InviteService::DEFAULT_EXPIRY_DAYS);
+    if !(1..=90).contains(&expires_in_days) {
+        return Err(ApiError::bad_request(
+            "Invites must expire within 1 to 90 days",
+        ));
+    }
+
+    if state
+        .services
+        .user_service
+        .get_one(EntityFilter::unfiltered().email(&request.email))
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::conflict(&format!(
+            "{} already has an account; add them as a member instead",
+            request.email
+        )));
+    }
+
+    let network = state
+        .services
+        .network_service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Network '{}' not found", id)))?;
+
+    let (invite, token) = state
+        .services
+        .invite_service
+        .create(
+            &id,
+            request.email,
+            request.role,
+            &user.0,
+            Duration::days(expires_in_days),
+        )
+        .await?;
+
+    let reloadable = state.reloadable();
+    let public_url = reloadable.config.public_url.as_deref();
+    let link = InviteService::link(public_url, &token);
+
+    // A relative link is no use in an email, so it's only sent when the server knows its URL
+    let mut emailed = false;
+    if let (Some(email_client), Some(_)) = (&reloadable.email_client, public_url) {
+        match state
+            .services
+            .invite_service
+            .send(&invite, &network.base.name, &link, email_client)
+            .await
+        {
+            Ok(()) => emailed = true,
+            Err(e) => tracing::warn!("Failed to email invite {}: {}", invite.id, e),
+        }
+    }
+
+    Ok(Json(ApiResponse::success(InviteResponse {
+        invite,
+        link,
+        emailed,
+    })))
+}
+
+/// Withdraw an invite so its link stops working
+pub async fn delete_invite(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, invite_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let service = &state.services.invite_service;
+    match service.get_by_id(&invite_id).await? {
+        Some(invite) if invite.base.network_id == id => service.delete(&invite_id).await?,
+        _ => {
+            return Err(ApiError::not_found(format!(
+                "Invite '{}' not found",
+                invite_id
+            )));
+        }
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Look up an invite link before signing up. Unauthenticated, since the invitee has no account.
+pub async fn get_invite(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> ApiResult<Json<ApiResponse<InviteDetails>>> {
+    let invite = state
+        .services
+        .invite_service
+        .find_pending(&token)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Invite link is invalid or has expired".to_string()))?;
+
+    let network = state
+        .services
+        .network_service
+        .get_by_id(&invite.base.network_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Invite link is invalid or has expired".to_string()))?;
+
+    Ok(Json(ApiResponse::success(InviteDetails {
+        email: invite.base.email,
+        network_name: network.base.name,
+        role: invite.base.role,
+        expires_at: invite.base.expires_at,
+    })))
+}
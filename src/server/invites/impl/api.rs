@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use email_address::EmailAddress;
+use serde::{Deserialize, Serialize};
+
+use crate::server::{invites::r#impl::base::Invite, network_members::r#impl::base::NetworkRole};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateInviteRequest {
+    pub email: EmailAddress,
+    pub role: NetworkRole,
+    /// Defaults to a week
+    pub expires_in_days: Option<i64>,
+}
+
+/// A new invite with its link, which can't be retrieved again later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteResponse {
+    pub invite: Invite,
+    /// Relative to the web UI unless `public_url` is configured
+    pub link: String,
+    /// Whether the link was emailed to the invitee
+    pub emailed: bool,
+}
+
+/// What the sign-up page shows for an invite link, before the invitee has an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteDetails {
+    pub email: EmailAddress,
+    pub network_name: String,
+    pub role: NetworkRole,
+    pub expires_at: DateTime<Utc>,
+}
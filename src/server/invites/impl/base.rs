@@ -0,0 +1,52 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use email_address::EmailAddress;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::network_members::r#impl::base::NetworkRole;
+
+/// An invitation for someone without an account to sign up and join a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteBase {
+    pub email: EmailAddress,
+    pub network_id: Uuid,
+    /// Role the invitee gets in the network once they accept
+    pub role: NetworkRole,
+    /// SHA-256 of the token in the invite link; the token itself is never stored
+    #[serde(skip_serializing, default)]
+    pub token_hash: String,
+    /// The admin who sent the invite
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    /// The user created when the invite was accepted
+    pub accepted_by: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invite {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: InviteBase,
+}
+
+impl Invite {
+    /// Not yet accepted and not expired
+    pub fn is_pending(&self) -> bool {
+        self.base.accepted_at.is_none() && self.base.expires_at > Utc::now()
+    }
+}
+
+impl Display for Invite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invite for {} to network {}: {}",
+            self.base.email, self.base.network_id, self.id
+        )
+    }
+}
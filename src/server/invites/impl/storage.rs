@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    invites::r#impl::base::{Invite, InviteBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for Invite {
+    type BaseData = InviteBase;
+
+    fn table_name() -> &'static str {
+        "invites"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    email,
+                    network_id,
+                    role,
+                    token_hash,
+                    invited_by,
+                    expires_at,
+                    accepted_at,
+                    accepted_by,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "email",
+                "network_id",
+                "role",
+                "token_hash",
+                "invited_by",
+                "expires_at",
+                "accepted_at",
+                "accepted_by",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Email(email),
+                SqlValue::Uuid(network_id),
+                SqlValue::String(role.to_string()),
+                SqlValue::String(token_hash),
+                SqlValue::Uuid(invited_by),
+                SqlValue::Timestamp(expires_at),
+                SqlValue::OptionTimestamp(accepted_at),
+                SqlValue::OptionalUuid(accepted_by),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let email: String = row.get("email");
+        let role: String = row.get("role");
+
+        Ok(Invite {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: InviteBase {
+                email: email.parse()?,
+                network_id: row.get("network_id"),
+                role: role.parse()?,
+                token_hash: row.get("token_hash"),
+                invited_by: row.get("invited_by"),
+                expires_at: row.get("expires_at"),
+                accepted_at: row.get("accepted_at"),
+                accepted_by: row.get("accepted_by"),
+            },
+        })
+    }
+}
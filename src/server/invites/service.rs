@@ -0,0 +1,150 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use email_address::EmailAddress;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    invites::r#impl::base::{Invite, InviteBase},
+    network_members::{r#impl::base::NetworkRole, service::NetworkMemberService},
+    shared::{
+        email::EmailClient,
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+};
+
+pub struct InviteService {
+    storage: Arc<GenericPostgresStorage<Invite>>,
+    network_member_service: Arc<NetworkMemberService>,
+}
+
+#[async_trait]
+impl CrudService<Invite> for InviteService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<Invite>> {
+        &self.storage
+    }
+}
+
+impl InviteService {
+    pub const DEFAULT_EXPIRY_DAYS: i64 = 7;
+
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<Invite>>,
+        network_member_service: Arc<NetworkMemberService>,
+    ) -> Self {
+        Self {
+            storage,
+            network_member_service,
+        }
+    }
+
+    fn hash_token(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
+    /// Returns the invite along with the token for its link
+    pub async fn create(
+        &self,
+        network_id: &Uuid,
+        email: EmailAddress,
+        role: NetworkRole,
+        invited_by: &Uuid,
+        expires_in: Duration,
+    ) -> Result<(Invite, String)> {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+        let invite = Invite::new(InviteBase {
+            email,
+            network_id: *network_id,
+            role,
+            token_hash: Self::hash_token(&token),
+            invited_by: *invited_by,
+            expires_at: Utc::now() + expires_in,
+            accepted_at: None,
+            accepted_by: None,
+        });
+
+        Ok((self.storage.create(&invite).await?, token))
+    }
+
+    /// The invite behind a link, if it can still be accepted
+    pub async fn find_pending(&self, token: &str) -> Result<Option<Invite>> {
+        let filter = EntityFilter::unfiltered().invite_token_hash(&Self::hash_token(token));
+
+        Ok(self
+            .storage
+            .get_one(filter)
+            .await?
+            .filter(|invite| invite.is_pending()))
+    }
+
+    /// Invites to the network that haven't been accepted or expired
+    pub async fn pending(&self, network_id: &Uuid) -> Result<Vec<Invite>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+
+        Ok(self
+            .storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .filter(|invite| invite.is_pending())
+            .collect())
+    }
+
+    /// Mark the invite used and give the new user their role in the network
+    pub async fn accept(&self, mut invite: Invite, user_id: &Uuid) -> Result<Invite> {
+        self.network_member_service
+            .set_member(
+                &invite.base.network_id,
+                user_id,
+                invite.base.role,
+                &invite.base.invited_by,
+            )
+            .await?;
+
+        invite.base.accepted_at = Some(Utc::now());
+        invite.base.accepted_by = Some(*user_id);
+        self.storage.update(&mut invite).await
+    }
+
+    /// Link for the sign-up page, absolute when the server knows where the web UI is served
+    pub fn link(public_url: Option<&str>, token: &str) -> String {
+        format!(
+            "{}/invite?token={}",
+            public_url.unwrap_or_default().trim_end_matches('/'),
+            token
+        )
+    }
+
+    pub async fn send(
+        &self,
+        invite: &Invite,
+        network_name: &str,
+        link: &str,
+        email_client: &EmailClient,
+    ) -> Result<()> {
+        let body = format!(
+            "You've been invited to join the network \"{}\" on NetVisor.\n\n\
+             Set a password to create your account:\n{}\n\n\
+             This link expires on {}.",
+            network_name,
+            link,
+            invite.base.expires_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        email_client
+            .send(
+                invite.base.email.as_str(),
+                &format!("Invitation to {} on NetVisor", network_name),
+                body,
+            )
+            .await
+    }
+}
@@ -0,0 +1,125 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    daemons::r#impl::api::DiscoveryUpdatePayload,
+    live::r#impl::api::{LiveMessage, LiveQuery},
+    shared::{
+        events::EntityEvent,
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{
+        Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::Response,
+    routing::get,
+};
+use futures::{SinkExt, StreamExt};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// How often pending topology invalidations are flushed to a client
+const INVALIDATION_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(ws_handler))
+}
+
+/// Stream discovery progress, entity changes and topology invalidations for the user's networks
+async fn ws_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<LiveQuery>,
+    ws: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let mut network_ids: HashSet<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    if let Some(network_id) = query.network_id {
+        if !network_ids.contains(&network_id) {
+            return Err(ApiError::not_found(format!(
+                "Network '{}' not found",
+                network_id
+            )));
+        }
+        network_ids = HashSet::from([network_id]);
+    }
+
+    // Subscribe before upgrading so nothing published during the handshake is missed
+    let entity_rx = state.storage.events.subscribe();
+    let discovery_rx = state.services.discovery_service.subscribe();
+
+    Ok(ws.on_upgrade(move |socket| stream_updates(socket, network_ids, entity_rx, discovery_rx)))
+}
+
+async fn stream_updates(
+    socket: WebSocket,
+    network_ids: HashSet<Uuid>,
+    mut entity_rx: broadcast::Receiver<EntityEvent>,
+    mut discovery_rx: broadcast::Receiver<DiscoveryUpdatePayload>,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut invalidated: HashSet<Uuid> = HashSet::new();
+    let mut flush = tokio::time::interval(INVALIDATION_INTERVAL);
+
+    loop {
+        let messages = tokio::select! {
+            event = entity_rx.recv() => match event {
+                Ok(event) => match event.network_id {
+                    Some(network_id) if network_ids.contains(&network_id) => {
+                        invalidated.insert(network_id);
+                        vec![LiveMessage::Entity(event)]
+                    }
+                    _ => vec![],
+                },
+                Err(RecvError::Lagged(skipped)) => vec![LiveMessage::Lagged { skipped }],
+                Err(RecvError::Closed) => break,
+            },
+            update = discovery_rx.recv() => match update {
+                Ok(update) if network_ids.contains(&update.network_id) => {
+                    vec![LiveMessage::Discovery(update)]
+                }
+                Ok(_) => vec![],
+                Err(RecvError::Lagged(skipped)) => vec![LiveMessage::Lagged { skipped }],
+                Err(RecvError::Closed) => break,
+            },
+            _ = flush.tick() => invalidated
+                .drain()
+                .map(|network_id| LiveMessage::TopologyInvalidated { network_id })
+                .collect(),
+            message = receiver.next() => match message {
+                // Pings are answered by the socket itself; clients have nothing else to say
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => vec![],
+            },
+        };
+
+        for message in messages {
+            let json = match serde_json::to_string(&message) {
+                Ok(json) => json,
+                Err(e) => {
+                    tracing::warn!("Failed to serialize live update: {}", e);
+                    continue;
+                }
+            };
+            if sender.send(Message::Text(json.into())).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    let _ = sender.close().await;
+}
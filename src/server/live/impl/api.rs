@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{daemons::r#impl::api::DiscoveryUpdatePayload, shared::events::EntityEvent};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LiveQuery {
+    /// Only stream updates for this network instead of all of the user's networks
+    pub network_id: Option<Uuid>,
+}
+
+/// Message sent to WebSocket clients
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum LiveMessage {
+    Discovery(DiscoveryUpdatePayload),
+    Entity(EntityEvent),
+    /// Entities shown in the network's topology changed, so it should be re-fetched. Sent at
+    /// most once a second per network.
+    TopologyInvalidated {
+        network_id: Uuid,
+    },
+    /// The client fell behind and missed messages; it should reload what it's showing
+    Lagged {
+        skipped: u64,
+    },
+}
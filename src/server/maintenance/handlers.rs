@@ -0,0 +1,107 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    maintenance::r#impl::{
+        consistency::ConsistencyReport,
+        orphans::{OrphanCleanupRequest, OrphanReport},
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::State,
+    response::Json,
+    routing::{get, post},
+};
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/orphans", get(get_orphans))
+        .route("/orphans/cleanup", post(cleanup_orphans))
+        .route("/consistency", get(check_consistency))
+}
+
+async fn user_network_ids(state: &AppState, user_id: &Uuid) -> ApiResult<Vec<Uuid>> {
+    let user_filter = EntityFilter::unfiltered().network_member(user_id);
+
+    Ok(state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect())
+}
+
+/// Report orphaned records in the user's networks without changing anything
+async fn get_orphans(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> ApiResult<Json<ApiResponse<OrphanReport>>> {
+    let network_ids = user_network_ids(&state, &user.0).await?;
+
+    // Nothing to look up for a user without networks
+    if network_ids.is_empty() {
+        return Ok(Json(ApiResponse::success(OrphanReport::default())));
+    }
+
+    let report = state
+        .services
+        .maintenance_service
+        .find_orphans(&network_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Remove orphaned records in the user's networks
+async fn cleanup_orphans(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<OrphanCleanupRequest>,
+) -> ApiResult<Json<ApiResponse<OrphanReport>>> {
+    let network_ids = user_network_ids(&state, &user.0).await?;
+
+    if network_ids.is_empty() {
+        return Ok(Json(ApiResponse::success(OrphanReport::default())));
+    }
+
+    let report = state
+        .services
+        .maintenance_service
+        .cleanup_orphans(&network_ids, request.dry_run)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Validate cross-entity invariants in the user's networks
+async fn check_consistency(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+) -> ApiResult<Json<ApiResponse<ConsistencyReport>>> {
+    let network_ids = user_network_ids(&state, &user.0).await?;
+
+    if network_ids.is_empty() {
+        return Ok(Json(ApiResponse::success(ConsistencyReport {
+            checked_at: Utc::now(),
+            violations: Vec::new(),
+        })));
+    }
+
+    let report = state
+        .services
+        .maintenance_service
+        .check_consistency(&network_ids)
+        .await?;
+
+    Ok(Json(ApiResponse::success(report)))
+}
@@ -0,0 +1,105 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    pub checked_at: DateTime<Utc>,
+    pub violations: Vec<ConsistencyViolation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyViolation {
+    #[serde(flatten)]
+    pub kind: ViolationKind,
+    pub suggested_fix: String,
+}
+
+impl From<ViolationKind> for ConsistencyViolation {
+    fn from(kind: ViolationKind) -> Self {
+        Self {
+            suggested_fix: kind.suggested_fix(),
+            kind,
+        }
+    }
+}
+
+/// Cross-entity invariant that does not hold
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "violation")]
+pub enum ViolationKind {
+    /// Host interface references a subnet that does not exist
+    MissingInterfaceSubnet {
+        host_id: Uuid,
+        interface_id: Uuid,
+        subnet_id: Uuid,
+    },
+    /// Host interface IP is outside the CIDR of its subnet
+    InterfaceOutsideSubnet {
+        host_id: Uuid,
+        interface_id: Uuid,
+        subnet_id: Uuid,
+        ip_address: IpAddr,
+    },
+    /// Group member binding does not belong to any service
+    UnresolvedGroupBinding { group_id: Uuid, binding_id: Uuid },
+    /// Service binding references a port or interface its host does not have
+    UnresolvedServiceBinding {
+        service_id: Uuid,
+        host_id: Uuid,
+        binding_id: Uuid,
+    },
+    /// Same IP and MAC appear on more than one interface in a network
+    DuplicateInterface {
+        network_id: Uuid,
+        ip_address: IpAddr,
+        mac_address: MacAddress,
+        host_ids: Vec<Uuid>,
+        interface_ids: Vec<Uuid>,
+    },
+    /// Service belongs to a different network than its host
+    ServiceNetworkMismatch {
+        service_id: Uuid,
+        host_id: Uuid,
+        service_network_id: Uuid,
+        host_network_id: Uuid,
+    },
+}
+
+impl ViolationKind {
+    pub fn suggested_fix(&self) -> String {
+        match self {
+            ViolationKind::MissingInterfaceSubnet { subnet_id, .. } => format!(
+                "Recreate subnet {} or remove the interface from the host (POST /api/maintenance/orphans/cleanup)",
+                subnet_id
+            ),
+            ViolationKind::InterfaceOutsideSubnet { ip_address, .. } => format!(
+                "Move the interface to the subnet containing {} or correct the subnet CIDR",
+                ip_address
+            ),
+            ViolationKind::UnresolvedGroupBinding { group_id, .. } => format!(
+                "Remove the binding from group {} (POST /api/maintenance/orphans/cleanup)",
+                group_id
+            ),
+            ViolationKind::UnresolvedServiceBinding { service_id, .. } => format!(
+                "Rebind service {} to an existing port or interface, or remove the binding",
+                service_id
+            ),
+            ViolationKind::DuplicateInterface { host_ids, .. } if host_ids.len() > 1 => {
+                "Consolidate the hosts sharing this interface (PUT /api/hosts/{destination_host}/consolidate/{other_host})".to_string()
+            }
+            ViolationKind::DuplicateInterface { .. } => {
+                "Remove the duplicate interface from the host".to_string()
+            }
+            ViolationKind::ServiceNetworkMismatch {
+                host_network_id, ..
+            } => format!(
+                "Move the service to network {} to match its host",
+                host_network_id
+            ),
+        }
+    }
+}
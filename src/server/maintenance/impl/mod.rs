@@ -0,0 +1,2 @@
+pub mod consistency;
+pub mod orphans;
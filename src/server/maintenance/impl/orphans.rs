@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Records left dangling by partial failures, e.g. a host deleted while its services were
+/// still being written
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OrphanReport {
+    pub services: Vec<OrphanedService>,
+    pub interfaces: Vec<OrphanedInterface>,
+    pub bindings: Vec<OrphanedBinding>,
+    pub group_bindings: Vec<OrphanedGroupBinding>,
+    pub host_service_refs: Vec<OrphanedHostServiceRef>,
+    /// Whether the orphans listed were removed, or only reported
+    pub removed: bool,
+}
+
+impl OrphanReport {
+    pub fn total(&self) -> usize {
+        self.services.len()
+            + self.interfaces.len()
+            + self.bindings.len()
+            + self.group_bindings.len()
+            + self.host_service_refs.len()
+    }
+}
+
+/// Service whose host no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedService {
+    pub service_id: Uuid,
+    pub host_id: Uuid,
+    pub name: String,
+}
+
+/// Host interface pointing at a subnet that no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedInterface {
+    pub host_id: Uuid,
+    pub interface_id: Uuid,
+    pub subnet_id: Uuid,
+}
+
+/// Service binding to a port or interface that is missing from its host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedBinding {
+    pub service_id: Uuid,
+    pub binding_id: Uuid,
+    pub missing_port_id: Option<Uuid>,
+    pub missing_interface_id: Option<Uuid>,
+}
+
+/// Group member referencing a binding no service has anymore
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedGroupBinding {
+    pub group_id: Uuid,
+    pub binding_id: Uuid,
+}
+
+/// Host service list entry for a service that no longer exists
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedHostServiceRef {
+    pub host_id: Uuid,
+    pub service_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OrphanCleanupRequest {
+    /// Report orphans without removing them
+    #[serde(default)]
+    pub dry_run: bool,
+}
@@ -0,0 +1,437 @@
+use crate::server::{
+    groups::{
+        r#impl::{base::Group, types::GroupType},
+        service::GroupService,
+    },
+    hosts::{r#impl::base::Host, service::HostService},
+    maintenance::r#impl::{
+        consistency::{ConsistencyReport, ConsistencyViolation, ViolationKind},
+        orphans::{
+            OrphanReport, OrphanedBinding, OrphanedGroupBinding, OrphanedHostServiceRef,
+            OrphanedInterface, OrphanedService,
+        },
+    },
+    services::{r#impl::base::Service, service::ServiceService},
+    shared::{
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, traits::Storage},
+    },
+    subnets::{r#impl::base::Subnet, service::SubnetService},
+};
+use anyhow::Result;
+use chrono::Utc;
+use itertools::Itertools;
+use mac_address::MacAddress;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+/// Soft-deleted hosts, services, subnets and groups can be restored for this long
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+pub struct MaintenanceService {
+    host_service: Arc<HostService>,
+    subnet_service: Arc<SubnetService>,
+    service_service: Arc<ServiceService>,
+    group_service: Arc<GroupService>,
+}
+
+impl MaintenanceService {
+    pub fn new(
+        host_service: Arc<HostService>,
+        subnet_service: Arc<SubnetService>,
+        service_service: Arc<ServiceService>,
+        group_service: Arc<GroupService>,
+    ) -> Self {
+        Self {
+            host_service,
+            subnet_service,
+            service_service,
+            group_service,
+        }
+    }
+
+    /// Find orphaned records in the given networks, or across all networks if none are given
+    pub async fn find_orphans(&self, network_ids: &[Uuid]) -> Result<OrphanReport> {
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter.clone()).await?;
+        let groups = self.group_service.get_all(filter).await?;
+
+        Ok(Self::collect_orphans(&hosts, &subnets, &services, &groups))
+    }
+
+    /// Validate cross-entity invariants in the given networks, or across all networks if none
+    /// are given
+    pub async fn check_consistency(&self, network_ids: &[Uuid]) -> Result<ConsistencyReport> {
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let subnets = self.subnet_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter.clone()).await?;
+        let groups = self.group_service.get_all(filter).await?;
+
+        let violations = Self::collect_violations(&hosts, &subnets, &services, &groups)
+            .into_iter()
+            .map(ConsistencyViolation::from)
+            .collect();
+
+        Ok(ConsistencyReport {
+            checked_at: Utc::now(),
+            violations,
+        })
+    }
+
+    /// Permanently remove entities that have been in the trash longer than the retention period
+    pub async fn purge_trash(&self) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::Duration::days(TRASH_RETENTION_DAYS);
+        let filter = EntityFilter::unfiltered()
+            .include_deleted()
+            .deleted_before(cutoff);
+
+        Ok(self
+            .host_service
+            .storage()
+            .delete_all(filter.clone())
+            .await?
+            + self
+                .service_service
+                .storage()
+                .delete_all(filter.clone())
+                .await?
+            + self
+                .subnet_service
+                .storage()
+                .delete_all(filter.clone())
+                .await?
+            + self.group_service.storage().delete_all(filter).await?)
+    }
+
+    /// Find orphaned records and remove them unless this is a dry run
+    pub async fn cleanup_orphans(
+        &self,
+        network_ids: &[Uuid],
+        dry_run: bool,
+    ) -> Result<OrphanReport> {
+        let mut report = self.find_orphans(network_ids).await?;
+
+        if dry_run || report.total() == 0 {
+            return Ok(report);
+        }
+
+        for orphan in &report.services {
+            self.service_service
+                .delete_service(&orphan.service_id)
+                .await?;
+        }
+
+        // Interfaces and stale service references live on the host record
+        let mut host_fixes: HashMap<Uuid, (HashSet<Uuid>, HashSet<Uuid>)> = HashMap::new();
+        for orphan in &report.interfaces {
+            host_fixes
+                .entry(orphan.host_id)
+                .or_default()
+                .0
+                .insert(orphan.interface_id);
+        }
+        for orphan in &report.host_service_refs {
+            host_fixes
+                .entry(orphan.host_id)
+                .or_default()
+                .1
+                .insert(orphan.service_id);
+        }
+
+        for (host_id, (interface_ids, service_ids)) in host_fixes {
+            if let Some(mut host) = self.host_service.get_by_id(&host_id).await? {
+                host.base
+                    .interfaces
+                    .retain(|i| !interface_ids.contains(&i.id));
+                host.base.services.retain(|s| !service_ids.contains(s));
+                self.host_service.update(&mut host).await?;
+            }
+        }
+
+        let mut binding_fixes: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        for orphan in &report.bindings {
+            binding_fixes
+                .entry(orphan.service_id)
+                .or_default()
+                .insert(orphan.binding_id);
+        }
+
+        // update_service also drops the removed bindings from any groups
+        for (service_id, binding_ids) in binding_fixes {
+            if let Some(mut service) = self.service_service.get_by_id(&service_id).await? {
+                service
+                    .base
+                    .bindings
+                    .retain(|b| !binding_ids.contains(&b.id()));
+                self.service_service.update_service(service).await?;
+            }
+        }
+
+        let mut group_fixes: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        for orphan in &report.group_bindings {
+            group_fixes
+                .entry(orphan.group_id)
+                .or_default()
+                .insert(orphan.binding_id);
+        }
+
+        for (group_id, binding_ids) in group_fixes {
+            if let Some(mut group) = self.group_service.get_by_id(&group_id).await? {
+                match &mut group.base.group_type {
+                    GroupType::RequestPath { service_bindings }
+                    | GroupType::HubAndSpoke { service_bindings } => {
+                        service_bindings.retain(|b| !binding_ids.contains(b))
+                    }
+                }
+                self.group_service.update(&mut group).await?;
+            }
+        }
+
+        report.removed = true;
+        tracing::info!("Removed {} orphaned records", report.total());
+
+        Ok(report)
+    }
+
+    fn collect_violations(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        services: &[Service],
+        groups: &[Group],
+    ) -> Vec<ViolationKind> {
+        let subnets_by_id: HashMap<Uuid, &Subnet> = subnets.iter().map(|s| (s.id, s)).collect();
+        let hosts_by_id: HashMap<Uuid, &Host> = hosts.iter().map(|h| (h.id, h)).collect();
+        let mut violations = Vec::new();
+
+        for host in hosts {
+            for interface in &host.base.interfaces {
+                match subnets_by_id.get(&interface.base.subnet_id) {
+                    None => violations.push(ViolationKind::MissingInterfaceSubnet {
+                        host_id: host.id,
+                        interface_id: interface.id,
+                        subnet_id: interface.base.subnet_id,
+                    }),
+                    Some(subnet) if !subnet.base.cidr.contains(&interface.base.ip_address) => {
+                        violations.push(ViolationKind::InterfaceOutsideSubnet {
+                            host_id: host.id,
+                            interface_id: interface.id,
+                            subnet_id: subnet.id,
+                            ip_address: interface.base.ip_address,
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        // BTreeMap keeps the report order stable between runs
+        let mut interfaces_by_address: BTreeMap<_, Vec<(Uuid, Uuid)>> = BTreeMap::new();
+        for host in hosts {
+            for interface in &host.base.interfaces {
+                if let Some(mac_address) = interface.base.mac_address {
+                    interfaces_by_address
+                        .entry((
+                            host.base.network_id,
+                            interface.base.ip_address,
+                            mac_address.bytes(),
+                        ))
+                        .or_default()
+                        .push((host.id, interface.id));
+                }
+            }
+        }
+
+        for ((network_id, ip_address, mac_address), entries) in interfaces_by_address {
+            if entries.len() > 1 {
+                violations.push(ViolationKind::DuplicateInterface {
+                    network_id,
+                    ip_address,
+                    mac_address: MacAddress::new(mac_address),
+                    host_ids: entries
+                        .iter()
+                        .map(|(host_id, _)| *host_id)
+                        .unique()
+                        .collect(),
+                    interface_ids: entries
+                        .iter()
+                        .map(|(_, interface_id)| *interface_id)
+                        .collect(),
+                });
+            }
+        }
+
+        for service in services {
+            let Some(host) = hosts_by_id.get(&service.base.host_id) else {
+                continue;
+            };
+
+            if service.base.network_id != host.base.network_id {
+                violations.push(ViolationKind::ServiceNetworkMismatch {
+                    service_id: service.id,
+                    host_id: host.id,
+                    service_network_id: service.base.network_id,
+                    host_network_id: host.base.network_id,
+                });
+            }
+
+            for binding in &service.base.bindings {
+                let port_missing = binding
+                    .port_id()
+                    .is_some_and(|p| host.get_port(&p).is_none());
+                let interface_missing = binding
+                    .interface_id()
+                    .is_some_and(|i| host.get_interface(&Some(i)).is_none());
+
+                if port_missing || interface_missing {
+                    violations.push(ViolationKind::UnresolvedServiceBinding {
+                        service_id: service.id,
+                        host_id: host.id,
+                        binding_id: binding.id(),
+                    });
+                }
+            }
+        }
+
+        let binding_ids: HashSet<Uuid> = services
+            .iter()
+            .flat_map(|s| s.base.bindings.iter().map(|b| b.id()))
+            .collect();
+
+        for group in groups {
+            let (GroupType::RequestPath { service_bindings }
+            | GroupType::HubAndSpoke { service_bindings }) = &group.base.group_type;
+
+            for binding_id in service_bindings {
+                if !binding_ids.contains(binding_id) {
+                    violations.push(ViolationKind::UnresolvedGroupBinding {
+                        group_id: group.id,
+                        binding_id: *binding_id,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    fn collect_orphans(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        services: &[Service],
+        groups: &[Group],
+    ) -> OrphanReport {
+        let subnet_ids: HashSet<Uuid> = subnets.iter().map(|s| s.id).collect();
+        let service_ids: HashSet<Uuid> = services.iter().map(|s| s.id).collect();
+        let hosts_by_id: HashMap<Uuid, &Host> = hosts.iter().map(|h| (h.id, h)).collect();
+
+        let orphaned_services: Vec<OrphanedService> = services
+            .iter()
+            .filter(|s| !hosts_by_id.contains_key(&s.base.host_id))
+            .map(|s| OrphanedService {
+                service_id: s.id,
+                host_id: s.base.host_id,
+                name: s.base.name.clone(),
+            })
+            .collect();
+
+        let orphaned_interfaces: Vec<OrphanedInterface> = hosts
+            .iter()
+            .flat_map(|h| {
+                h.base
+                    .interfaces
+                    .iter()
+                    .filter(|i| !subnet_ids.contains(&i.base.subnet_id))
+                    .map(move |i| OrphanedInterface {
+                        host_id: h.id,
+                        interface_id: i.id,
+                        subnet_id: i.base.subnet_id,
+                    })
+            })
+            .collect();
+
+        let orphaned_interface_ids: HashSet<Uuid> =
+            orphaned_interfaces.iter().map(|i| i.interface_id).collect();
+
+        let orphaned_bindings: Vec<OrphanedBinding> = services
+            .iter()
+            .filter_map(|s| hosts_by_id.get(&s.base.host_id).map(|h| (s, *h)))
+            .flat_map(|(s, host)| {
+                s.base
+                    .bindings
+                    .iter()
+                    .filter_map(|b| {
+                        let missing_port_id = b.port_id().filter(|p| host.get_port(p).is_none());
+                        let missing_interface_id = b.interface_id().filter(|i| {
+                            host.get_interface(&Some(*i)).is_none()
+                                || orphaned_interface_ids.contains(i)
+                        });
+
+                        (missing_port_id.is_some() || missing_interface_id.is_some()).then_some(
+                            OrphanedBinding {
+                                service_id: s.id,
+                                binding_id: b.id(),
+                                missing_port_id,
+                                missing_interface_id,
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // Bindings reported above are dropped from groups when their service is updated or
+        // deleted, so only report group members that reference no binding at all
+        let known_binding_ids: HashSet<Uuid> = services
+            .iter()
+            .flat_map(|s| s.base.bindings.iter().map(|b| b.id()))
+            .collect();
+
+        let orphaned_group_bindings: Vec<OrphanedGroupBinding> = groups
+            .iter()
+            .flat_map(|g| {
+                let (GroupType::RequestPath { service_bindings }
+                | GroupType::HubAndSpoke { service_bindings }) = &g.base.group_type;
+
+                service_bindings
+                    .iter()
+                    .filter(|b| !known_binding_ids.contains(b))
+                    .map(|b| OrphanedGroupBinding {
+                        group_id: g.id,
+                        binding_id: *b,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let host_service_refs: Vec<OrphanedHostServiceRef> = hosts
+            .iter()
+            .flat_map(|h| {
+                h.base
+                    .services
+                    .iter()
+                    .filter(|s| !service_ids.contains(s))
+                    .map(move |s| OrphanedHostServiceRef {
+                        host_id: h.id,
+                        service_id: *s,
+                    })
+            })
+            .collect();
+
+        OrphanReport {
+            services: orphaned_services,
+            interfaces: orphaned_interfaces,
+            bindings: orphaned_bindings,
+            group_bindings: orphaned_group_bindings,
+            host_service_refs,
+            removed: false,
+        }
+    }
+}
@@ -1,13 +1,42 @@
 pub mod api_keys;
+pub mod apply;
+pub mod archives;
+pub mod audit;
 pub mod auth;
+pub mod backups;
 pub mod config;
 pub mod daemons;
+pub mod demo;
+pub mod diagnostics;
+pub mod digests;
 pub mod discovery;
+pub mod exports;
+pub mod grafana;
+pub mod graphql;
 pub mod groups;
 pub mod hosts;
+pub mod integrations;
+pub mod invites;
+pub mod live;
+pub mod maintenance;
+pub mod monitoring;
+pub mod network_members;
+pub mod network_settings;
 pub mod networks;
+pub mod node_pins;
+pub mod notes;
+pub mod notifications;
+pub mod policies;
+pub mod reports;
+pub mod scan_profiles;
+pub mod service_definitions;
 pub mod services;
 pub mod shared;
+pub mod ssh_credentials;
 pub mod subnets;
 pub mod topology;
+pub mod topology_snapshots;
 pub mod users;
+pub mod vulnerabilities;
+pub mod webhooks;
+pub mod winrm_credentials;
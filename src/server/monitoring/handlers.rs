@@ -0,0 +1,88 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    monitoring::r#impl::{api::ServiceHealth, base::HealthCheck},
+    services::r#impl::base::Service,
+    shared::{
+        handlers::traits::{
+            CrudHandlers, delete_handler, get_accessible, get_all_handler, get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<HealthCheck>))
+        .route("/", post(create_handler))
+        .route("/{id}", put(update_handler))
+        .route("/{id}", delete(delete_handler::<HealthCheck>))
+        .route("/{id}", get(get_by_id_handler::<HealthCheck>))
+}
+
+async fn create_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<HealthCheck>,
+) -> ApiResult<Json<ApiResponse<HealthCheck>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    // The check takes its network from the service
+    get_accessible::<Service>(&state, &user.0, &request.base.service_id).await?;
+
+    let service = HealthCheck::get_service(&state);
+    let created = service.create(request).await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+async fn update_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<HealthCheck>,
+) -> ApiResult<Json<ApiResponse<HealthCheck>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let service = HealthCheck::get_service(&state);
+    let existing: HealthCheck = get_accessible(&state, &user.0, &id).await?;
+
+    // Results refer to the check's service, so checks can't move between services
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+    request.base.service_id = existing.base.service_id;
+    request.base.network_id = existing.base.network_id;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// A service's health checks with their latest results and uptime rollups
+pub async fn get_service_health(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(service_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<ServiceHealth>>> {
+    get_accessible::<Service>(&state, &user.0, &service_id).await?;
+
+    let health = state
+        .services
+        .monitoring_service
+        .service_health(&service_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(health)))
+}
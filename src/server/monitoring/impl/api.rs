@@ -0,0 +1,41 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::monitoring::r#impl::{
+    base::{HealthCheck, HealthCheckKind, HealthCheckResult},
+    rollups::HealthRollup,
+};
+
+/// A health check resolved to an address, as sent to the daemon that runs it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HealthCheckTarget {
+    pub check_id: Uuid,
+    pub ip: IpAddr,
+    pub port: u16,
+    pub kind: HealthCheckKind,
+    pub interval_seconds: u32,
+    pub timeout_ms: u32,
+}
+
+/// Outcome of a health check run, sent by daemons in batches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckReport {
+    pub check_id: Uuid,
+    pub checked_at: DateTime<Utc>,
+    pub up: bool,
+    pub latency_ms: Option<u32>,
+    pub status_code: Option<u16>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceHealth {
+    pub service_id: Uuid,
+    pub checks: Vec<HealthCheck>,
+    /// Most recent result of each check
+    pub latest: Vec<HealthCheckResult>,
+    pub rollups: Vec<HealthRollup>,
+}
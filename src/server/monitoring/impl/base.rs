@@ -0,0 +1,175 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::monitoring::r#impl::api::HealthCheckReport;
+
+/// How a daemon probes a service
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum HealthCheckKind {
+    /// Up if a TCP connection to the port is accepted
+    Tcp,
+    /// Up if a GET request gets the expected status and, if set, a body containing the
+    /// expected text
+    Http {
+        #[serde(default = "default_path")]
+        path: String,
+        #[serde(default)]
+        https: bool,
+        /// Any 2xx status when unset
+        expected_status: Option<u16>,
+        expected_body: Option<String>,
+    },
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckBase {
+    pub service_id: Uuid,
+    /// Set from the service when the check is created
+    #[serde(default)]
+    pub network_id: Uuid,
+    pub kind: HealthCheckKind,
+    /// Port to probe; the service's first TCP port when unset
+    pub port: Option<u16>,
+    pub interval_seconds: i32,
+    pub timeout_ms: i32,
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: HealthCheckBase,
+}
+
+impl Display for HealthCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Health check for service {}: {}",
+            self.base.service_id, self.id
+        )
+    }
+}
+
+/// Outcome of a single run of a health check, as reported by a daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckResultBase {
+    pub check_id: Uuid,
+    pub service_id: Uuid,
+    pub daemon_id: Uuid,
+    pub checked_at: DateTime<Utc>,
+    pub up: bool,
+    pub latency_ms: Option<i32>,
+    /// HTTP status, for HTTP checks that got a response
+    pub status_code: Option<i32>,
+    /// Why the check failed
+    pub message: Option<String>,
+}
+
+impl HealthCheckResultBase {
+    /// The result of a daemon's report on the check. None if the daemon isn't in the check's
+    /// network, so daemons can't write results for other networks' services.
+    pub fn from_report(
+        check: &HealthCheck,
+        report: HealthCheckReport,
+        daemon_id: Uuid,
+        daemon_network_id: Uuid,
+    ) -> Option<Self> {
+        if check.base.network_id != daemon_network_id || report.check_id != check.id {
+            return None;
+        }
+
+        Some(Self {
+            check_id: check.id,
+            service_id: check.base.service_id,
+            daemon_id,
+            checked_at: report.checked_at,
+            up: report.up,
+            latency_ms: report.latency_ms.and_then(|l| l.try_into().ok()),
+            status_code: report.status_code.map(i32::from),
+            message: report.message,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheckResult {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: HealthCheckResultBase,
+}
+
+impl Display for HealthCheckResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Health check {} result at {}: {}",
+            self.base.check_id,
+            self.base.checked_at,
+            if self.base.up { "up" } else { "down" }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::shared::storage::traits::StorableEntity;
+
+    fn check(network_id: Uuid) -> HealthCheck {
+        HealthCheck::new(HealthCheckBase {
+            service_id: Uuid::new_v4(),
+            network_id,
+            kind: HealthCheckKind::Tcp,
+            port: Some(22),
+            interval_seconds: 60,
+            timeout_ms: 1000,
+            is_enabled: true,
+        })
+    }
+
+    fn report(check: &HealthCheck) -> HealthCheckReport {
+        HealthCheckReport {
+            check_id: check.id,
+            checked_at: Utc::now(),
+            up: true,
+            latency_ms: Some(3),
+            status_code: None,
+            message: None,
+        }
+    }
+
+    #[test]
+    fn daemons_only_report_on_checks_in_their_network() {
+        let network_id = Uuid::new_v4();
+        let daemon_id = Uuid::new_v4();
+        let check = check(network_id);
+
+        let result =
+            HealthCheckResultBase::from_report(&check, report(&check), daemon_id, network_id)
+                .unwrap();
+        assert_eq!(result.check_id, check.id);
+        assert_eq!(result.service_id, check.base.service_id);
+        assert_eq!(result.daemon_id, daemon_id);
+        assert_eq!(result.latency_ms, Some(3));
+
+        let other_network = Uuid::new_v4();
+        assert!(
+            HealthCheckResultBase::from_report(&check, report(&check), daemon_id, other_network)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use crate::server::monitoring::r#impl::base::{HealthCheck, HealthCheckKind};
+use crate::server::monitoring::service::MonitoringService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+/// Shortest interval a check can run at
+const MIN_INTERVAL_SECONDS: i32 = 10;
+
+impl CrudHandlers for HealthCheck {
+    type Service = MonitoringService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.monitoring_service
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.interval_seconds < MIN_INTERVAL_SECONDS {
+            return Err(format!(
+                "Interval must be at least {} seconds",
+                MIN_INTERVAL_SECONDS
+            ));
+        }
+
+        if self.base.timeout_ms <= 0 || self.base.timeout_ms > self.base.interval_seconds * 1000 {
+            return Err("Timeout must be positive and shorter than the interval".to_string());
+        }
+
+        if let HealthCheckKind::Http { path, .. } = &self.base.kind
+            && !path.starts_with('/')
+        {
+            return Err(format!("Path '{}' must start with '/'", path));
+        }
+
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
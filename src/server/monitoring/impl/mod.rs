@@ -0,0 +1,5 @@
+pub mod api;
+pub mod base;
+pub mod handlers;
+pub mod rollups;
+pub mod storage;
@@ -0,0 +1,126 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::server::monitoring::r#impl::base::HealthCheckResult;
+
+/// Windows health is summarized over, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum HealthWindow {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+}
+
+impl HealthWindow {
+    pub fn duration(&self) -> Duration {
+        match self {
+            HealthWindow::Day => Duration::days(1),
+            HealthWindow::Week => Duration::weeks(1),
+            HealthWindow::Month => Duration::days(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthRollup {
+    pub window: HealthWindow,
+    pub checks: usize,
+    pub failures: usize,
+    /// Share of checks that were up, None without any checks in the window
+    pub uptime_percent: Option<f64>,
+    /// Over checks that were up
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<i32>,
+}
+
+impl HealthRollup {
+    pub fn compute(
+        window: HealthWindow,
+        results: &[HealthCheckResult],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let since = now - window.duration();
+        let in_window: Vec<&HealthCheckResult> = results
+            .iter()
+            .filter(|r| r.base.checked_at > since && r.base.checked_at <= now)
+            .collect();
+
+        let checks = in_window.len();
+        let failures = in_window.iter().filter(|r| !r.base.up).count();
+        let latencies: Vec<i32> = in_window
+            .iter()
+            .filter(|r| r.base.up)
+            .filter_map(|r| r.base.latency_ms)
+            .collect();
+
+        Self {
+            window,
+            checks,
+            failures,
+            uptime_percent: (checks > 0)
+                .then(|| (checks - failures) as f64 / checks as f64 * 100.0),
+            avg_latency_ms: (!latencies.is_empty())
+                .then(|| latencies.iter().map(|l| *l as f64).sum::<f64>() / latencies.len() as f64),
+            max_latency_ms: latencies.iter().max().copied(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::monitoring::r#impl::base::HealthCheckResultBase;
+    use crate::server::shared::storage::traits::StorableEntity;
+    use uuid::Uuid;
+
+    fn result(
+        now: DateTime<Utc>,
+        hours_ago: i64,
+        up: bool,
+        latency_ms: Option<i32>,
+    ) -> HealthCheckResult {
+        HealthCheckResult::new(HealthCheckResultBase {
+            check_id: Uuid::nil(),
+            service_id: Uuid::nil(),
+            daemon_id: Uuid::nil(),
+            checked_at: now - Duration::hours(hours_ago),
+            up,
+            latency_ms,
+            status_code: None,
+            message: None,
+        })
+    }
+
+    #[test]
+    fn test_rollup_windows() {
+        let now = Utc::now();
+        let results = vec![
+            result(now, 1, true, Some(10)),
+            result(now, 2, true, Some(30)),
+            result(now, 3, false, None),
+            result(now, 48, false, None),
+        ];
+
+        let day = HealthRollup::compute(HealthWindow::Day, &results, now);
+        assert_eq!(day.checks, 3);
+        assert_eq!(day.failures, 1);
+        assert_eq!(day.avg_latency_ms, Some(20.0));
+        assert_eq!(day.max_latency_ms, Some(30));
+
+        let week = HealthRollup::compute(HealthWindow::Week, &results, now);
+        assert_eq!(week.checks, 4);
+        assert_eq!(week.uptime_percent, Some(50.0));
+    }
+
+    #[test]
+    fn test_rollup_without_results() {
+        let rollup = HealthRollup::compute(HealthWindow::Month, &[], Utc::now());
+        assert_eq!(rollup.checks, 0);
+        assert_eq!(rollup.uptime_percent, None);
+        assert_eq!(rollup.avg_latency_ms, None);
+    }
+}
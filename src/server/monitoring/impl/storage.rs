@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    monitoring::r#impl::base::{
+        HealthCheck, HealthCheckBase, HealthCheckResult, HealthCheckResultBase,
+    },
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for HealthCheck {
+    type BaseData = HealthCheckBase;
+
+    fn table_name() -> &'static str {
+        "health_checks"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    service_id,
+                    network_id,
+                    kind,
+                    port,
+                    interval_seconds,
+                    timeout_ms,
+                    is_enabled,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "service_id",
+                "network_id",
+                "kind",
+                "port",
+                "interval_seconds",
+                "timeout_ms",
+                "is_enabled",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(service_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&kind)?),
+                SqlValue::OptionalI32(port.map(i32::from)),
+                SqlValue::I32(interval_seconds),
+                SqlValue::I32(timeout_ms),
+                SqlValue::Bool(is_enabled),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let kind: serde_json::Value = row.get("kind");
+        let port = row
+            .get::<Option<i32>, _>("port")
+            .map(u16::try_from)
+            .transpose()?;
+
+        Ok(HealthCheck {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: HealthCheckBase {
+                service_id: row.get("service_id"),
+                network_id: row.get("network_id"),
+                kind: serde_json::from_value(kind)?,
+                port,
+                interval_seconds: row.get("interval_seconds"),
+                timeout_ms: row.get("timeout_ms"),
+                is_enabled: row.get("is_enabled"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for HealthCheckResult {
+    type BaseData = HealthCheckResultBase;
+
+    fn table_name() -> &'static str {
+        "health_check_results"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    check_id,
+                    service_id,
+                    daemon_id,
+                    checked_at,
+                    up,
+                    latency_ms,
+                    status_code,
+                    message,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "check_id",
+                "service_id",
+                "daemon_id",
+                "checked_at",
+                "up",
+                "latency_ms",
+                "status_code",
+                "message",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(check_id),
+                SqlValue::Uuid(service_id),
+                SqlValue::Uuid(daemon_id),
+                SqlValue::Timestamp(checked_at),
+                SqlValue::Bool(up),
+                SqlValue::OptionalI32(latency_ms),
+                SqlValue::OptionalI32(status_code),
+                SqlValue::OptionalString(message),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(HealthCheckResult {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: HealthCheckResultBase {
+                check_id: row.get("check_id"),
+                service_id: row.get("service_id"),
+                daemon_id: row.get("daemon_id"),
+                checked_at: row.get("checked_at"),
+                up: row.get("up"),
+                latency_ms: row.get("latency_ms"),
+                status_code: row.get("status_code"),
+                message: row.get("message"),
+            },
+        })
+    }
+}
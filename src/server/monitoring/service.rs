@@ -0,0 +1,273 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use strum::IntoEnumIterator;
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::{r#impl::base::Daemon, service::DaemonService},
+    hosts::{
+        r#impl::{base::Host, ports::TransportProtocol},
+        service::HostService,
+    },
+    monitoring::r#impl::{
+        api::{HealthCheckReport, HealthCheckTarget, ServiceHealth},
+        base::{HealthCheck, HealthCheckBase, HealthCheckResult, HealthCheckResultBase},
+        rollups::{HealthRollup, HealthWindow},
+    },
+    services::{r#impl::base::Service, service::ServiceService},
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+};
+
+/// Results older than this are deleted; matches the longest rollup window
+const RESULT_RETENTION_DAYS: i64 = 30;
+
+pub struct MonitoringService {
+    storage: Arc<GenericPostgresStorage<HealthCheck>>,
+    result_storage: Arc<GenericPostgresStorage<HealthCheckResult>>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    daemon_service: Arc<DaemonService>,
+}
+
+#[async_trait]
+impl CrudService<HealthCheck> for MonitoringService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<HealthCheck>> {
+        &self.storage
+    }
+}
+
+impl MonitoringService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<HealthCheck>>,
+        result_storage: Arc<GenericPostgresStorage<HealthCheckResult>>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        daemon_service: Arc<DaemonService>,
+    ) -> Self {
+        Self {
+            storage,
+            result_storage,
+            host_service,
+            service_service,
+            daemon_service,
+        }
+    }
+
+    /// Create a check in its service's network
+    pub async fn create(&self, check: HealthCheck) -> Result<HealthCheck> {
+        let service = self
+            .service_service
+            .get_by_id(&check.base.service_id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find service {}", check.base.service_id))?;
+
+        let check = HealthCheck::new(HealthCheckBase {
+            network_id: service.base.network_id,
+            ..check.base
+        });
+
+        self.storage.create(&check).await
+    }
+
+    /// Enabled checks in the daemon's network that it should run. A check is run by the oldest
+    /// daemon with an interface in the subnet of the address it probes, so it isn't run twice.
+    pub async fn targets_for_daemon(&self, daemon: &Daemon) -> Result<Vec<HealthCheckTarget>> {
+        let network_filter = EntityFilter::unfiltered().network_ids(&[daemon.base.network_id]);
+
+        let checks = self.storage.get_all(network_filter.clone()).await?;
+        if checks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let daemons = self.daemon_service.get_all(network_filter.clone()).await?;
+        let services: HashMap<Uuid, Service> = self
+            .service_service
+            .get_all(network_filter.clone())
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s))
+            .collect();
+        let hosts: HashMap<Uuid, Host> = self
+            .host_service
+            .get_all(network_filter)
+            .await?
+            .into_iter()
+            .map(|h| (h.id, h))
+            .collect();
+
+        Ok(checks
+            .into_iter()
+            .filter(|check| check.base.is_enabled)
+            .filter_map(|check| {
+                let service = services.get(&check.base.service_id)?;
+                let host = hosts.get(&service.base.host_id)?;
+                let (ip, port, subnet_id) = Self::resolve_address(&check, service, host)?;
+
+                let runner = daemons.iter().find(|d| {
+                    d.base
+                        .capabilities
+                        .interfaced_subnet_ids
+                        .contains(&subnet_id)
+                })?;
+                if runner.id != daemon.id {
+                    return None;
+                }
+
+                Some(HealthCheckTarget {
+                    check_id: check.id,
+                    ip,
+                    port,
+                    kind: check.base.kind,
+                    interval_seconds: check.base.interval_seconds.try_into().ok()?,
+                    timeout_ms: check.base.timeout_ms.try_into().ok()?,
+                })
+            })
+            .collect())
+    }
+
+    /// Address a check probes, with the subnet it's in: the check's port or the service's first
+    /// TCP port, on the interface that port is bound to
+    fn resolve_address(
+        check: &HealthCheck,
+        service: &Service,
+        host: &Host,
+    ) -> Option<(IpAddr, u16, Uuid)> {
+        let bound_port = service.base.bindings.iter().find_map(|binding| {
+            let port = host.get_port(&binding.port_id()?)?;
+            let matches = match check.base.port {
+                Some(number) => port.base.number() == number,
+                None => port.base.protocol() == TransportProtocol::Tcp,
+            };
+            matches.then_some((binding.interface_id(), port.base.number()))
+        });
+
+        let (interface_id, port) = match (bound_port, check.base.port) {
+            (Some(bound), _) => bound,
+            // A port the service isn't bound to, on the service's interface
+            (None, Some(number)) => (
+                service.base.bindings.iter().find_map(|b| b.interface_id()),
+                number,
+            ),
+            (None, None) => return None,
+        };
+
+        let interface = match interface_id {
+            Some(_) => host.get_interface(&interface_id)?,
+            None => host.base.interfaces.first()?,
+        };
+
+        Some((interface.base.ip_address, port, interface.base.subnet_id))
+    }
+
+    /// Store results reported by a daemon. Results for checks deleted since the daemon fetched
+    /// them, or in another network, are dropped.
+    pub async fn record_results(
+        &self,
+        daemon: &Daemon,
+        reports: Vec<HealthCheckReport>,
+    ) -> Result<()> {
+        for report in reports {
+            let Some(check) = self.storage.get_by_id(&report.check_id).await? else {
+                continue;
+            };
+
+            let Some(base) = HealthCheckResultBase::from_report(
+                &check,
+                report,
+                daemon.id,
+                daemon.base.network_id,
+            ) else {
+                tracing::warn!(
+                    "Dropping result of check {} from daemon {} in another network",
+                    check.id,
+                    daemon.id
+                );
+                continue;
+            };
+
+            self.result_storage
+                .create(&HealthCheckResult::new(base))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// A service's checks, their latest results and uptime over each window
+    pub async fn service_health(&self, service_id: &Uuid) -> Result<ServiceHealth> {
+        let now = Utc::now();
+        let checks = self
+            .storage
+            .get_all(EntityFilter::unfiltered().service_id(service_id))
+            .await?;
+
+        let results = self
+            .result_storage
+            .get_all(
+                EntityFilter::unfiltered()
+                    .service_id(service_id)
+                    .checked_after(now - HealthWindow::Month.duration()),
+            )
+            .await?;
+
+        // Results are ordered oldest first, so the last one seen per check wins
+        let latest: HashMap<Uuid, &HealthCheckResult> =
+            results.iter().map(|r| (r.base.check_id, r)).collect();
+
+        Ok(ServiceHealth {
+            service_id: *service_id,
+            latest: checks
+                .iter()
+                .filter_map(|c| latest.get(&c.id).map(|r| (*r).clone()))
+                .collect(),
+            rollups: HealthWindow::iter()
+                .map(|window| HealthRollup::compute(window, &results, now))
+                .collect(),
+            checks,
+        })
+    }
+
+    /// Results of the services' checks between two times, oldest first
+    pub async fn results_between(
+        &self,
+        service_ids: &[Uuid],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<HealthCheckResult>> {
+        // An empty service filter would match every service
+        if service_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut results = self
+            .result_storage
+            .get_all(
+                EntityFilter::unfiltered()
+                    .service_ids(service_ids)
+                    .checked_after(from)
+                    .checked_before(to),
+            )
+            .await?;
+        results.sort_by_key(|r| r.base.checked_at);
+
+        Ok(results)
+    }
+
+    /// Delete results older than the retention period
+    pub async fn prune_results(&self) -> Result<u64> {
+        let cutoff = Utc::now() - Duration::days(RESULT_RETENTION_DAYS);
+
+        self.result_storage
+            .delete_all(EntityFilter::unfiltered().checked_before(cutoff))
+            .await
+    }
+}
@@ -0,0 +1,165 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::r#impl::{
+        api::{AddNetworkMemberRequest, NetworkMemberView, UpdateNetworkMemberRequest},
+        base::{NetworkMember, NetworkRole},
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The user's role in the network. Networks they have no access to are reported as not found,
+/// so other tenants' networks can't be probed for.
+pub async fn network_role(
+    state: &AppState,
+    user_id: &Uuid,
+    network_id: &Uuid,
+) -> ApiResult<NetworkRole> {
+    state
+        .services
+        .network_member_service
+        .role(network_id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Network '{}' not found", network_id)))
+}
+
+pub async fn require_admin(state: &AppState, user_id: &Uuid, network_id: &Uuid) -> ApiResult<()> {
+    match network_role(state, user_id, network_id).await? {
+        NetworkRole::Admin => Ok(()),
+        NetworkRole::Member => Err(ApiError::forbidden("Only network admins can do this")),
+    }
+}
+
+/// Everyone with access to the network
+pub async fn get_members(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<NetworkMemberView>>>> {
+    network_role(&state, &user.0, &id).await?;
+
+    let members = state.services.network_member_service.members(&id).await?;
+
+    Ok(Json(ApiResponse::success(members)))
+}
+
+/// Give an existing user access to the network
+pub async fn add_member(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<AddNetworkMemberRequest>,
+) -> ApiResult<Json<ApiResponse<NetworkMember>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let member = state
+        .services
+        .user_service
+        .get_one(EntityFilter::unfiltered().email(&request.email))
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("No user with email '{}'", request.email)))?;
+
+    if state
+        .services
+        .network_member_service
+        .role(&id, &member.id)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::conflict(&format!(
+            "{} already has access to this network",
+            request.email
+        )));
+    }
+
+    let member = state
+        .services
+        .network_member_service
+        .set_member(&id, &member.id, request.role, &user.0)
+        .await?;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn update_member(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<UpdateNetworkMemberRequest>,
+) -> ApiResult<Json<ApiResponse<NetworkMember>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let service = &state.services.network_member_service;
+    match service.role(&id, &user_id).await? {
+        None => {
+            return Err(ApiError::not_found(format!(
+                "User '{}' is not a member of this network",
+                user_id
+            )));
+        }
+        Some(_) if is_owner(&state, &id, &user_id).await? => {
+            return Err(ApiError::bad_request(
+                "The network's owner is always an admin",
+            ));
+        }
+        Some(_) => {}
+    }
+
+    let member = service
+        .set_member(&id, &user_id, request.role, &user.0)
+        .await?;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+/// Revoke a member's access. Members can remove themselves; removing anyone else takes an admin.
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, user_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    if user_id == user.0 {
+        network_role(&state, &user.0, &id).await?;
+    } else {
+        require_admin(&state, &user.0, &id).await?;
+    }
+
+    if is_owner(&state, &id, &user_id).await? {
+        return Err(ApiError::bad_request(
+            "The network's owner can't be removed",
+        ));
+    }
+
+    if !state
+        .services
+        .network_member_service
+        .remove_member(&id, &user_id)
+        .await?
+    {
+        return Err(ApiError::not_found(format!(
+            "User '{}' is not a member of this network",
+            user_id
+        )));
+    }
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn is_owner(state: &AppState, network_id: &Uuid, user_id: &Uuid) -> ApiResult<bool> {
+    Ok(state
+        .services
+        .network_service
+        .get_by_id(network_id)
+        .await?
+        .is_some_and(|network| network.base.user_id == *user_id))
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use email_address::EmailAddress;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::network_members::r#impl::base::NetworkRole;
+
+/// Give an existing user access to a network
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddNetworkMemberRequest {
+    pub email: EmailAddress,
+    pub role: NetworkRole,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateNetworkMemberRequest {
+    pub role: NetworkRole,
+}
+
+/// Someone with access to a network, including its owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMemberView {
+    pub user_id: Uuid,
+    pub email: EmailAddress,
+    pub role: NetworkRole,
+    pub is_owner: bool,
+    /// When the member was added; the network's creation for its owner
+    pub added_at: DateTime<Utc>,
+}
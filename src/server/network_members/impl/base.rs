@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+use uuid::Uuid;
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display, EnumString,
+)]
+pub enum NetworkRole {
+    /// Can also rename the network and manage its members. A network's owner is always an
+    /// admin.
+    Admin,
+    Member,
+}
+
+/// Access to a network for a user other than its owner
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMemberBase {
+    pub network_id: Uuid,
+    pub user_id: Uuid,
+    pub role: NetworkRole,
+    /// The admin who added the member
+    pub added_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkMember {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NetworkMemberBase,
+}
+
+impl Display for NetworkMember {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} of network {}: {}",
+            self.base.role, self.base.network_id, self.base.user_id
+        )
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    network_members::r#impl::{
+        api::NetworkMemberView,
+        base::{NetworkMember, NetworkMemberBase, NetworkRole},
+    },
+    networks::service::NetworkService,
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    users::service::UserService,
+};
+
+pub struct NetworkMemberService {
+    storage: Arc<GenericPostgresStorage<NetworkMember>>,
+    network_service: Arc<NetworkService>,
+    user_service: Arc<UserService>,
+}
+
+#[async_trait]
+impl CrudService<NetworkMember> for NetworkMemberService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<NetworkMember>> {
+        &self.storage
+    }
+}
+
+impl NetworkMemberService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<NetworkMember>>,
+        network_service: Arc<NetworkService>,
+        user_service: Arc<UserService>,
+    ) -> Self {
+        Self {
+            storage,
+            network_service,
+            user_service,
+        }
+    }
+
+    async fn membership(&self, network_id: &Uuid, user_id: &Uuid) -> Result<Option<NetworkMember>> {
+        let filter = EntityFilter::unfiltered()
+            .network_ids(&[*network_id])
+            .user_id(user_id);
+
+        self.storage.get_one(filter).await
+    }
+
+    /// The user's role in the network, or None if they have no access to it
+    pub async fn role(&self, network_id: &Uuid, user_id: &Uuid) -> Result<Option<NetworkRole>> {
+        let Some(network) = self.network_service.get_by_id(network_id).await? else {
+            return Ok(None);
+        };
+
+        if network.base.user_id == *user_id {
+            return Ok(Some(NetworkRole::Admin));
+        }
+
+        Ok(self
+            .membership(network_id, user_id)
+            .await?
+            .map(|member| member.base.role))
+    }
+
+    /// The network's owner followed by its members, oldest first
+    pub async fn members(&self, network_id: &Uuid) -> Result<Vec<NetworkMemberView>> {
+        let Some(network) = self.network_service.get_by_id(network_id).await? else {
+            return Ok(Vec::new());
+        };
+
+        let mut views = Vec::new();
+        if let Some(owner) = self.user_service.get_by_id(&network.base.user_id).await? {
+            views.push(NetworkMemberView {
+                user_id: owner.id,
+                email: owner.base.email,
+                role: NetworkRole::Admin,
+                is_owner: true,
+                added_at: network.created_at,
+            });
+        }
+
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        for member in self.storage.get_all(filter).await? {
+            // Members are removed along with their user, so this only skips a concurrent delete
+            let Some(user) = self.user_service.get_by_id(&member.base.user_id).await? else {
+                continue;
+            };
+
+            views.push(NetworkMemberView {
+                user_id: user.id,
+                email: user.base.email,
+                role: member.base.role,
+                is_owner: false,
+                added_at: member.created_at,
+            });
+        }
+
+        Ok(views)
+    }
+
+    /// Give the user access to the network, or change their role if they already have it
+    pub async fn set_member(
+        &self,
+        network_id: &Uuid,
+        user_id: &Uuid,
+        role: NetworkRole,
+        added_by: &Uuid,
+    ) -> Result<NetworkMember> {
+        match self.membership(network_id, user_id).await? {
+            Some(mut member) => {
+                member.base.role = role;
+                self.storage.update(&mut member).await
+            }
+            None => {
+                let member = NetworkMember::new(NetworkMemberBase {
+                    network_id: *network_id,
+                    user_id: *user_id,
+                    role,
+                    added_by: *added_by,
+                });
+                self.storage.create(&member).await
+            }
+        }
+    }
+
+    /// Returns whether the user was a member
+    pub async fn remove_member(&self, network_id: &Uuid, user_id: &Uuid) -> Result<bool> {
+        match self.membership(network_id, user_id).await? {
+            Some(member) => {
+                self.storage.delete(&member.id).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
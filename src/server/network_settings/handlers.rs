@@ -0,0 +1,96 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    network_settings::r#impl::base::{NetworkSettings, NetworkSettingsBase},
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::get,
+};
+use std::sync::Arc;
+use tokio_cron_scheduler::Job;
+use uuid::Uuid;
+use validator::Validate;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new().route(
+        "/{network_id}",
+        get(get_network_settings).put(update_network_settings),
+    )
+}
+
+/// Settings for a network, with defaults for anything never saved
+async fn get_network_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(network_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<NetworkSettingsBase>>> {
+    network_role(&state, &user.0, &network_id).await?;
+
+    let settings = state
+        .services
+        .network_settings_service
+        .for_network(&network_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
+
+async fn update_network_settings(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(network_id): Path<Uuid>,
+    Json(mut request): Json<NetworkSettingsBase>,
+) -> ApiResult<Json<ApiResponse<NetworkSettings>>> {
+    network_role(&state, &user.0, &network_id).await?;
+
+    request.network_id = network_id;
+
+    if let Err(e) = request.validate() {
+        return Err(ApiError::bad_request(&format!(
+            "Settings validation failed: {}",
+            e
+        )));
+    }
+
+    if Job::new_async(request.discovery_schedule.as_str(), |_, _| {
+        Box::pin(async {})
+    })
+    .is_err()
+    {
+        return Err(ApiError::bad_request(&format!(
+            "Invalid discovery schedule '{}'",
+            request.discovery_schedule
+        )));
+    }
+
+    if let Err(e) = request.scan_settings.validate() {
+        return Err(ApiError::bad_request(&e));
+    }
+
+    if let Err(e) = request.exclusions.validate() {
+        return Err(ApiError::bad_request(&e));
+    }
+
+    if let Err(e) = request.scan_limits.validate() {
+        return Err(ApiError::bad_request(&e));
+    }
+
+    if let Some(tailnet) = &request.tailnet
+        && let Err(e) = tailnet.validate()
+    {
+        return Err(ApiError::bad_request(&e));
+    }
+
+    let settings = state
+        .services
+        .network_settings_service
+        .save(request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(settings)))
+}
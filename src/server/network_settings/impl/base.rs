@@ -0,0 +1,187 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::server::{
+    discovery::r#impl::{
+        exclusions::Exclusions,
+        scan_settings::{ScanLimits, ScanSettingsOverrides},
+        types::HostNamingFallback,
+    },
+    services::r#impl::{categories::ServiceCategory, patterns::MatchConfidence},
+};
+
+/// Cron schedule for the discoveries created when a daemon registers
+pub const DEFAULT_DISCOVERY_SCHEDULE: &str = "0 0 0 * * *";
+
+/// Whether discovered hosts show up right away or wait for a user to approve them
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ApprovalMode {
+    #[default]
+    Automatic,
+    /// New hosts from discovery are created hidden; unhiding one approves it
+    Manual,
+}
+
+/// How data from a discovery is merged into a host that already exists
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DiscoveryConflictPolicy {
+    /// Discovery fills in fields that are empty and adds new ports and services
+    #[default]
+    KeepExisting,
+    /// The latest discovery wins: it replaces the hostname, and full scans remove ports that
+    /// closed and discovered services that no longer match
+    PreferDiscovered,
+}
+
+/// What happens to a host once it's stale
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StaleHostAction {
+    /// Only mark it stale
+    #[default]
+    Flag,
+    /// Mark it stale and hide it until it's seen again
+    Hide,
+}
+
+/// Topology display options used when a client has no preferences of its own
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TopologyDefaults {
+    pub group_docker_bridges_by_host: bool,
+    pub hide_vm_title_on_docker_container: bool,
+    pub hide_ports: bool,
+    pub left_zone_service_categories: Vec<ServiceCategory>,
+    pub hide_service_categories: Vec<ServiceCategory>,
+    pub show_gateway_in_left_zone: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TailnetProvider {
+    #[default]
+    Tailscale,
+    /// Self-hosted Headscale control server
+    Headscale,
+}
+
+/// API access to the network's tailnet, used by `Tailnet` discoveries
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TailnetSettings {
+    #[serde(default)]
+    pub provider: TailnetProvider,
+    /// Control server URL; required for Headscale, defaults to https://api.tailscale.com
+    pub url: Option<String>,
+    /// Tailscale tailnet name; `-` is the API key's default tailnet
+    #[serde(default = "default_tailnet")]
+    pub tailnet: String,
+    pub api_key: String,
+}
+
+fn default_tailnet() -> String {
+    "-".to_string()
+}
+
+impl TailnetSettings {
+    pub fn base_url(&self) -> &str {
+        self.url
+            .as_deref()
+            .unwrap_or("https://api.tailscale.com")
+            .trim_end_matches('/')
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.api_key.is_empty() {
+            return Err("Tailnet API key is required".to_string());
+        }
+        if self.provider == TailnetProvider::Headscale && self.url.is_none() {
+            return Err("Headscale needs the URL of its control server".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Defaults applied to everything in a network. Networks without stored settings use
+/// `NetworkSettingsBase::new`.
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct NetworkSettingsBase {
+    pub network_id: Uuid,
+    /// Naming fallback for discoveries created by the server
+    #[serde(default)]
+    pub host_naming_fallback: HostNamingFallback,
+    #[validate(length(min = 1, max = 100))]
+    pub discovery_schedule: String,
+    /// Scan timeouts and retries; daemons may override them
+    #[serde(default)]
+    pub scan_settings: ScanSettingsOverrides,
+    /// Addresses, MAC prefixes and ports discovery leaves alone
+    #[serde(default)]
+    pub exclusions: Exclusions,
+    /// Probe rate and concurrency limits for discovery; scan profiles may override them
+    #[serde(default)]
+    pub scan_limits: ScanLimits,
+    /// Days to keep finished discovery runs; kept forever if unset
+    #[validate(range(min = 1))]
+    pub discovery_retention_days: Option<i32>,
+    #[serde(default)]
+    pub approval_mode: ApprovalMode,
+    #[serde(default)]
+    pub topology: TopologyDefaults,
+    /// Discovered services matched with less confidence are dropped. Services without a
+    /// confidence, such as generic ones, are always kept.
+    #[serde(default = "default_min_service_confidence")]
+    pub min_service_confidence: MatchConfidence,
+    #[serde(default)]
+    pub discovery_conflict_policy: DiscoveryConflictPolicy,
+    /// Completed network scans of a host's subnet it can miss before it's stale; never stale if
+    /// unset
+    #[validate(range(min = 1))]
+    #[serde(default)]
+    pub stale_after_scans: Option<i32>,
+    #[serde(default)]
+    pub stale_host_action: StaleHostAction,
+    #[serde(default)]
+    pub tailnet: Option<TailnetSettings>,
+}
+
+fn default_min_service_confidence() -> MatchConfidence {
+    MatchConfidence::Low
+}
+
+impl NetworkSettingsBase {
+    pub fn new(network_id: Uuid) -> Self {
+        Self {
+            network_id,
+            host_naming_fallback: HostNamingFallback::default(),
+            discovery_schedule: DEFAULT_DISCOVERY_SCHEDULE.to_string(),
+            scan_settings: ScanSettingsOverrides::default(),
+            exclusions: Exclusions::default(),
+            scan_limits: ScanLimits::default(),
+            discovery_retention_days: None,
+            approval_mode: ApprovalMode::default(),
+            topology: TopologyDefaults::default(),
+            min_service_confidence: default_min_service_confidence(),
+            discovery_conflict_policy: DiscoveryConflictPolicy::default(),
+            stale_after_scans: None,
+            stale_host_action: StaleHostAction::default(),
+            tailnet: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkSettings {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NetworkSettingsBase,
+}
+
+impl Display for NetworkSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Settings for network {}", self.base.network_id)
+    }
+}
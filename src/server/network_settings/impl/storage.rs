@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    network_settings::r#impl::base::{NetworkSettings, NetworkSettingsBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for NetworkSettings {
+    type BaseData = NetworkSettingsBase;
+
+    fn table_name() -> &'static str {
+        "network_settings"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    network_id,
+                    host_naming_fallback,
+                    discovery_schedule,
+                    scan_settings,
+                    exclusions,
+                    scan_limits,
+                    discovery_retention_days,
+                    approval_mode,
+                    topology,
+                    min_service_confidence,
+                    discovery_conflict_policy,
+                    stale_after_scans,
+                    stale_host_action,
+                    tailnet,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "network_id",
+                "host_naming_fallback",
+                "discovery_schedule",
+                "scan_settings",
+                "exclusions",
+                "scan_limits",
+                "discovery_retention_days",
+                "approval_mode",
+                "topology",
+                "min_service_confidence",
+                "discovery_conflict_policy",
+                "stale_after_scans",
+                "stale_host_action",
+                "tailnet",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(host_naming_fallback)?),
+                SqlValue::String(discovery_schedule),
+                SqlValue::Json(serde_json::to_value(&scan_settings)?),
+                SqlValue::Json(serde_json::to_value(&exclusions)?),
+                SqlValue::Json(serde_json::to_value(&scan_limits)?),
+                SqlValue::OptionalI32(discovery_retention_days),
+                SqlValue::Json(serde_json::to_value(approval_mode)?),
+                SqlValue::Json(serde_json::to_value(&topology)?),
+                SqlValue::Json(serde_json::to_value(min_service_confidence)?),
+                SqlValue::Json(serde_json::to_value(discovery_conflict_policy)?),
+                SqlValue::OptionalI32(stale_after_scans),
+                SqlValue::Json(serde_json::to_value(stale_host_action)?),
+                SqlValue::Json(serde_json::to_value(&tailnet)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(NetworkSettings {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: NetworkSettingsBase {
+                network_id: row.get("network_id"),
+                host_naming_fallback: serde_json::from_value(row.get("host_naming_fallback"))?,
+                discovery_schedule: row.get("discovery_schedule"),
+                scan_settings: serde_json::from_value(row.get("scan_settings"))?,
+                exclusions: serde_json::from_value(row.get("exclusions"))?,
+                scan_limits: serde_json::from_value(row.get("scan_limits"))?,
+                discovery_retention_days: row.get("discovery_retention_days"),
+                approval_mode: serde_json::from_value(row.get("approval_mode"))?,
+                topology: serde_json::from_value(row.get("topology"))?,
+                min_service_confidence: serde_json::from_value(row.get("min_service_confidence"))?,
+                discovery_conflict_policy: serde_json::from_value(
+                    row.get("discovery_conflict_policy"),
+                )?,
+                stale_after_scans: row.get("stale_after_scans"),
+                stale_host_action: serde_json::from_value(row.get("stale_host_action"))?,
+                tailnet: row
+                    .get::<Option<serde_json::Value>, _>("tailnet")
+                    .map(serde_json::from_value)
+                    .transpose()?
+                    .flatten(),
+            },
+        })
+    }
+}
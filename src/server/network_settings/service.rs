@@ -0,0 +1,58 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    network_settings::r#impl::base::{NetworkSettings, NetworkSettingsBase},
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+};
+
+pub struct NetworkSettingsService {
+    storage: Arc<GenericPostgresStorage<NetworkSettings>>,
+}
+
+#[async_trait]
+impl CrudService<NetworkSettings> for NetworkSettingsService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<NetworkSettings>> {
+        &self.storage
+    }
+}
+
+impl NetworkSettingsService {
+    pub fn new(storage: Arc<GenericPostgresStorage<NetworkSettings>>) -> Self {
+        Self { storage }
+    }
+
+    /// Settings for a network, or the defaults if none have been saved
+    pub async fn for_network(&self, network_id: &Uuid) -> Result<NetworkSettingsBase> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+
+        Ok(self
+            .storage
+            .get_one(filter)
+            .await?
+            .map(|settings| settings.base)
+            .unwrap_or_else(|| NetworkSettingsBase::new(*network_id)))
+    }
+
+    /// Replace a network's settings, creating them on first save
+    pub async fn save(&self, base: NetworkSettingsBase) -> Result<NetworkSettings> {
+        let filter = EntityFilter::unfiltered().network_ids(&[base.network_id]);
+
+        match self.storage.get_one(filter).await? {
+            Some(mut settings) => {
+                settings.base = base;
+                self.storage.update(&mut settings).await
+            }
+            None => self.storage.create(&NetworkSettings::new(base)).await,
+        }
+    }
+}
@@ -1,42 +1,127 @@
-use crate::server::shared::handlers::traits::{
-    create_handler, delete_handler, get_by_id_handler, update_handler,
-};
+use crate::server::shared::handlers::traits::{CrudHandlers, get_by_id_handler};
 use crate::server::{
+    archives::handlers::{export_network, import_body_limit, import_network},
     auth::middleware::AuthenticatedUser,
     config::AppState,
+    invites::handlers::{create_invite, delete_invite, get_invites},
+    network_members::handlers::{
+        add_member, get_members, remove_member, require_admin, update_member,
+    },
     networks::r#impl::Network,
     shared::{
         services::traits::CrudService,
         storage::filter::EntityFilter,
-        types::api::{ApiResponse, ApiResult},
+        types::api::{ApiError, ApiResponse, ApiResult},
     },
 };
 use axum::{
     Router,
-    extract::State,
+    extract::{Path, State},
     response::Json,
     routing::{delete, get, post, put},
 };
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub fn create_router() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/", post(create_handler::<Network>))
+        .route("/", post(create_network))
         .route("/", get(get_all_networks))
-        .route("/{id}", put(update_handler::<Network>))
-        .route("/{id}", delete(delete_handler::<Network>))
+        .route("/{id}", put(update_network))
+        .route("/{id}", delete(delete_network))
         .route("/{id}", get(get_by_id_handler::<Network>))
+        .route("/{id}/export", get(export_network))
+        .route(
+            "/{id}/import",
+            post(import_network).layer(import_body_limit()),
+        )
+        .route("/{id}/members", get(get_members))
+        .route("/{id}/members", post(add_member))
+        .route("/{id}/members/{user_id}", put(update_member))
+        .route("/{id}/members/{user_id}", delete(remove_member))
+        .route("/{id}/invites", get(get_invites))
+        .route("/{id}/invites", post(create_invite))
+        .route("/{id}/invites/{invite_id}", delete(delete_invite))
 }
 
+/// Networks the user owns or is a member of
 async fn get_all_networks(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
 ) -> ApiResult<Json<ApiResponse<Vec<Network>>>> {
     let service = &state.services.network_service;
 
-    let filter = EntityFilter::unfiltered().user_id(&user.0);
+    let filter = EntityFilter::unfiltered().network_member(&user.0);
 
     let networks = service.get_all(filter).await?;
 
     Ok(Json(ApiResponse::success(networks)))
 }
+
+/// Create a network owned by the user
+async fn create_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(mut request): Json<Network>,
+) -> ApiResult<Json<ApiResponse<Network>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&format!(
+            "Network validation failed: {}",
+            err
+        )));
+    }
+
+    request.base.user_id = user.0;
+
+    let created = state.services.network_service.create(request).await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+/// Admins can rename a network; ownership can't be changed
+async fn update_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<Network>,
+) -> ApiResult<Json<ApiResponse<Network>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let service = &state.services.network_service;
+    let existing = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Network '{}' not found", id)))?;
+
+    request.id = id;
+    request.base.user_id = existing.base.user_id;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// Only a network's owner can delete it
+async fn delete_network(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    require_admin(&state, &user.0, &id).await?;
+
+    let service = &state.services.network_service;
+    let network = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Network '{}' not found", id)))?;
+
+    if network.base.user_id != user.0 {
+        return Err(ApiError::forbidden(
+            "Only the network's owner can delete it",
+        ));
+    }
+
+    service.delete(&id).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
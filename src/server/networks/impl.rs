@@ -49,6 +49,10 @@ impl CrudHandlers for Network {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.network_service
     }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.id)
+    }
 }
 
 impl StorableEntity for Network {
@@ -58,6 +62,14 @@ impl StorableEntity for Network {
         "networks"
     }
 
+    fn sortable_columns() -> &'static [&'static str] {
+        &["name"]
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name"]
+    }
+
     fn get_base(&self) -> Self::BaseData {
         self.base.clone()
     }
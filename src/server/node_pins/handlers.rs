@@ -0,0 +1,65 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    node_pins::r#impl::{api::PinNodeRequest, base::NodePin},
+    shared::{
+        handlers::traits::get_all_handler,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<NodePin>))
+        .route("/{node_id}", put(pin_node))
+        .route("/{node_id}", delete(unpin_node))
+}
+
+/// Keep a node at a position across regenerations
+async fn pin_node(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(node_id): Path<Uuid>,
+    Json(request): Json<PinNodeRequest>,
+) -> ApiResult<Json<ApiResponse<NodePin>>> {
+    network_role(&state, &user.0, &request.network_id).await?;
+
+    let service = &state.services.node_pin_service;
+    // Repinning moves the pin to the requested network, so the current one must be the user's too
+    if let Some(existing) = service.get_pin(&node_id).await? {
+        network_role(&state, &user.0, &existing.base.network_id).await?;
+    }
+
+    let pin = service
+        .pin(request.network_id, node_id, request.position)
+        .await?;
+
+    Ok(Json(ApiResponse::success(pin)))
+}
+
+/// Let the optimizer place a node again
+async fn unpin_node(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(node_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = &state.services.node_pin_service;
+    let pin = service
+        .get_pin(&node_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Node '{}' is not pinned", node_id)))?;
+    network_role(&state, &user.0, &pin.base.network_id).await?;
+
+    service.unpin(&node_id).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology::types::base::Ixy;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PinNodeRequest {
+    pub network_id: Uuid,
+    pub position: Ixy,
+}
@@ -0,0 +1,32 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology::types::base::Ixy;
+
+/// Position a user dragged a topology node to, kept across regenerations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePinBase {
+    pub network_id: Uuid,
+    /// Subnet or interface node id
+    pub node_id: Uuid,
+    /// Absolute for subnets, relative to the subnet for interfaces
+    pub position: Ixy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePin {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NodePinBase,
+}
+
+impl Display for NodePin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Pin of node {}: {}", self.base.node_id, self.id)
+    }
+}
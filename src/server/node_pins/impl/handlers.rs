@@ -0,0 +1,20 @@
+use crate::server::node_pins::r#impl::base::NodePin;
+use crate::server::node_pins::service::NodePinService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+impl CrudHandlers for NodePin {
+    type Service = NodePinService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.node_pin_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Node pin"
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+use crate::server::{
+    node_pins::r#impl::base::{NodePin, NodePinBase},
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    topology::types::base::Ixy,
+};
+
+pub struct NodePinService {
+    storage: Arc<GenericPostgresStorage<NodePin>>,
+}
+
+#[async_trait]
+impl CrudService<NodePin> for NodePinService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<NodePin>> {
+        &self.storage
+    }
+}
+
+impl NodePinService {
+    pub fn new(storage: Arc<GenericPostgresStorage<NodePin>>) -> Self {
+        Self { storage }
+    }
+
+    /// Pin a node, or move it if it's already pinned
+    pub async fn pin(&self, network_id: Uuid, node_id: Uuid, position: Ixy) -> Result<NodePin> {
+        let filter = EntityFilter::unfiltered().node_id(&node_id);
+
+        match self.storage.get_one(filter).await? {
+            Some(mut pin) => {
+                pin.base.network_id = network_id;
+                pin.base.position = position;
+                self.storage.update(&mut pin).await
+            }
+            None => {
+                let pin = NodePin::new(NodePinBase {
+                    network_id,
+                    node_id,
+                    position,
+                });
+                self.storage.create(&pin).await
+            }
+        }
+    }
+
+    pub async fn get_pin(&self, node_id: &Uuid) -> Result<Option<NodePin>> {
+        let filter = EntityFilter::unfiltered().node_id(node_id);
+        self.storage.get_one(filter).await
+    }
+
+    /// Returns whether the node was pinned
+    pub async fn unpin(&self, node_id: &Uuid) -> Result<bool> {
+        let filter = EntityFilter::unfiltered().node_id(node_id);
+
+        match self.storage.get_one(filter).await? {
+            Some(pin) => {
+                self.storage.delete(&pin.id).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Pinned positions by node id
+    pub async fn positions(&self, network_ids: &[Uuid]) -> Result<HashMap<Uuid, Ixy>> {
+        let filter = EntityFilter::unfiltered().network_ids(network_ids);
+
+        Ok(self
+            .storage
+            .get_all(filter)
+            .await?
+            .into_iter()
+            .map(|pin| (pin.base.node_id, pin.base.position))
+            .collect())
+    }
+}
@@ -0,0 +1,315 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    notes::{
+        r#impl::{
+            api::{AttachmentQuery, CreateNoteRequest, NotesQuery, UpdateNoteRequest},
+            base::{Note, NoteAttachment, NoteEntity, NoteRevision},
+        },
+        service::NoteService,
+    },
+    shared::{
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Json},
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MAX_BODY_LENGTH: usize = 100_000;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_notes))
+        .route("/", post(create_note))
+        .route("/{id}", put(update_note))
+        .route("/{id}", delete(delete_note))
+        .route("/{id}/revisions", get(get_revisions))
+        .route("/{id}/attachments", get(get_attachments))
+        .route("/{id}/attachments", post(upload_attachment))
+        .route(
+            "/{id}/attachments/{attachment_id}",
+            get(download_attachment),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}",
+            delete(delete_attachment),
+        )
+}
+
+/// Network of the host, service or subnet, if the user can see it
+async fn entity_network_id(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    entity_type: NoteEntity,
+    entity_id: &Uuid,
+) -> ApiResult<Uuid> {
+    let services = &state.services;
+    let network_id = match entity_type {
+        NoteEntity::Host => services
+            .host_service
+            .get_by_id(entity_id)
+            .await?
+            .map(|host| host.base.network_id),
+        NoteEntity::Service => services
+            .service_service
+            .get_by_id(entity_id)
+            .await?
+            .map(|service| service.base.network_id),
+        NoteEntity::Subnet => services
+            .subnet_service
+            .get_by_id(entity_id)
+            .await?
+            .map(|subnet| subnet.base.network_id),
+    };
+
+    let not_found = || ApiError::not_found(format!("{} '{}' not found", entity_type, entity_id));
+    let network_id = network_id.ok_or_else(not_found)?;
+    network_role(state, &user.0, &network_id)
+        .await
+        .map_err(|_| not_found())?;
+
+    Ok(network_id)
+}
+
+/// The note, if the user can see its network
+async fn get_note(state: &AppState, user: &AuthenticatedUser, id: &Uuid) -> ApiResult<Note> {
+    let not_found = || ApiError::not_found(format!("Note '{}' not found", id));
+    let note = state
+        .services
+        .note_service
+        .get_by_id(id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    network_role(state, &user.0, &note.base.network_id)
+        .await
+        .map_err(|_| not_found())?;
+
+    Ok(note)
+}
+
+fn validate_body(body: &str) -> ApiResult<()> {
+    if body.trim().is_empty() {
+        return Err(ApiError::bad_request("Notes can't be empty"));
+    }
+    if body.len() > MAX_BODY_LENGTH {
+        return Err(ApiError::bad_request(&format!(
+            "Notes can be at most {} characters",
+            MAX_BODY_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Notes on a host, service or subnet
+async fn get_notes(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<NotesQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<Note>>>> {
+    let notes = state
+        .services
+        .note_service
+        .notes_on(&query.entity_id)
+        .await?;
+
+    // Checked against the notes' network, since the entity may have been deleted since
+    if let Some(note) = notes.first() {
+        network_role(&state, &user.0, &note.base.network_id)
+            .await
+            .map_err(|_| ApiError::not_found(format!("'{}' not found", query.entity_id)))?;
+    }
+
+    Ok(Json(ApiResponse::success(notes)))
+}
+
+async fn create_note(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<CreateNoteRequest>,
+) -> ApiResult<Json<ApiResponse<Note>>> {
+    validate_body(&request.body)?;
+    let network_id =
+        entity_network_id(&state, &user, request.entity_type, &request.entity_id).await?;
+
+    let note = state
+        .services
+        .note_service
+        .create_note(
+            network_id,
+            request.entity_type,
+            request.entity_id,
+            request.body,
+            user.0,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(note)))
+}
+
+/// Edit a note, keeping its previous body as a revision
+async fn update_note(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateNoteRequest>,
+) -> ApiResult<Json<ApiResponse<Note>>> {
+    validate_body(&request.body)?;
+    let note = get_note(&state, &user, &id).await?;
+
+    if let Some(revision) = request.revision
+        && revision != note.base.revision
+    {
+        return Err(ApiError::conflict(&format!(
+            "The note was edited since revision {}; it's at revision {} now",
+            revision, note.base.revision
+        )));
+    }
+
+    let note = state
+        .services
+        .note_service
+        .edit(note, request.body, user.0)
+        .await?;
+
+    Ok(Json(ApiResponse::success(note)))
+}
+
+async fn delete_note(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    get_note(&state, &user, &id).await?;
+    state.services.note_service.delete(&id).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn get_revisions(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<NoteRevision>>>> {
+    get_note(&state, &user, &id).await?;
+    let revisions = state.services.note_service.revisions(&id).await?;
+
+    Ok(Json(ApiResponse::success(revisions)))
+}
+
+async fn get_attachments(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<NoteAttachment>>>> {
+    get_note(&state, &user, &id).await?;
+    let attachments = state.services.note_service.attachments(&id).await?;
+
+    Ok(Json(ApiResponse::success(attachments)))
+}
+
+/// Attach the request body as a file, named by the `filename` query parameter
+async fn upload_attachment(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AttachmentQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<Json<ApiResponse<NoteAttachment>>> {
+    let note = get_note(&state, &user, &id).await?;
+    let service = &state.services.note_service;
+
+    let filename = query.filename.trim();
+    if filename.is_empty() || filename.len() > 255 || filename.contains(['/', '\\']) {
+        return Err(ApiError::bad_request(
+            "Filename must be 1 to 255 characters without slashes",
+        ));
+    }
+    if body.is_empty() || body.len() > NoteService::MAX_ATTACHMENT_BYTES {
+        return Err(ApiError::bad_request(&format!(
+            "Attachments must be 1 byte to {} KiB",
+            NoteService::MAX_ATTACHMENT_BYTES / 1024
+        )));
+    }
+    if service.attachments(&id).await?.len() >= NoteService::MAX_ATTACHMENTS {
+        return Err(ApiError::bad_request(&format!(
+            "A note can have at most {} attachments",
+            NoteService::MAX_ATTACHMENTS
+        )));
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+
+    let attachment = service
+        .attach(
+            &note,
+            filename.to_string(),
+            content_type,
+            body.to_vec(),
+            user.0,
+        )
+        .await?;
+
+    Ok(Json(ApiResponse::success(attachment)))
+}
+
+async fn download_attachment(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<impl IntoResponse> {
+    get_note(&state, &user, &id).await?;
+    let attachment = state
+        .services
+        .note_service
+        .attachment(&id, &attachment_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Attachment '{}' not found", attachment_id)))?;
+
+    let disposition = format!(
+        "attachment; filename=\"{}\"",
+        attachment.base.filename.replace('"', "")
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, attachment.base.content_type),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        attachment.base.data,
+    ))
+}
+
+async fn delete_attachment(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    get_note(&state, &user, &id).await?;
+    let service = &state.services.note_service;
+
+    if service.attachment(&id, &attachment_id).await?.is_none() {
+        return Err(ApiError::not_found(format!(
+            "Attachment '{}' not found",
+            attachment_id
+        )));
+    }
+    service.delete_attachment(&attachment_id).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::notes::r#impl::base::NoteEntity;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotesQuery {
+    pub entity_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNoteRequest {
+    pub entity_type: NoteEntity,
+    pub entity_id: Uuid,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateNoteRequest {
+    pub body: String,
+    /// Revision the edit was based on. When set and the note has moved on since, the edit is
+    /// refused rather than overwriting someone else's.
+    #[serde(default)]
+    pub revision: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AttachmentQuery {
+    pub filename: String,
+}
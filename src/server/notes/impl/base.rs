@@ -0,0 +1,113 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+use uuid::Uuid;
+use validator::Validate;
+
+/// What a note documents
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display, EnumString,
+)]
+pub enum NoteEntity {
+    Host,
+    Service,
+    Subnet,
+}
+
+/// Markdown documentation on a host, service or subnet
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct NoteBase {
+    pub network_id: Uuid,
+    pub entity_type: NoteEntity,
+    pub entity_id: Uuid,
+    #[validate(length(min = 1, max = 100000))]
+    pub body: String,
+    /// Starts at 1 and goes up with every edit; earlier bodies are kept as revisions
+    pub revision: i32,
+    pub author_id: Uuid,
+    pub edited_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NoteBase,
+}
+
+impl Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Note on {} {}: {}",
+            self.base.entity_type, self.base.entity_id, self.id
+        )
+    }
+}
+
+/// A note's body as it was before an edit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteRevisionBase {
+    pub note_id: Uuid,
+    pub network_id: Uuid,
+    pub revision: i32,
+    pub body: String,
+    /// Who wrote this revision
+    pub edited_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteRevision {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NoteRevisionBase,
+}
+
+impl Display for NoteRevision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Revision {} of note {}: {}",
+            self.base.revision, self.base.note_id, self.id
+        )
+    }
+}
+
+/// A small file kept with a note, such as a config excerpt or a photo of a rack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteAttachmentBase {
+    pub note_id: Uuid,
+    pub network_id: Uuid,
+    pub filename: String,
+    pub content_type: String,
+    pub size: i32,
+    /// Downloaded separately, so lists stay small
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    pub uploaded_by: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteAttachment {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: NoteAttachmentBase,
+}
+
+impl Display for NoteAttachment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Attachment {} of note {}: {}",
+            self.base.filename, self.base.note_id, self.id
+        )
+    }
+}
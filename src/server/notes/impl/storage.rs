@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    notes::r#impl::base::{
+        Note, NoteAttachment, NoteAttachmentBase, NoteBase, NoteRevision, NoteRevisionBase,
+    },
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for Note {
+    type BaseData = NoteBase;
+
+    fn table_name() -> &'static str {
+        "notes"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    network_id,
+                    entity_type,
+                    entity_id,
+                    body,
+                    revision,
+                    author_id,
+                    edited_by,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "network_id",
+                "entity_type",
+                "entity_id",
+                "body",
+                "revision",
+                "author_id",
+                "edited_by",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::String(entity_type.to_string()),
+                SqlValue::Uuid(entity_id),
+                SqlValue::String(body),
+                SqlValue::I32(revision),
+                SqlValue::Uuid(author_id),
+                SqlValue::Uuid(edited_by),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let entity_type: String = row.get("entity_type");
+
+        Ok(Note {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: NoteBase {
+                network_id: row.get("network_id"),
+                entity_type: entity_type.parse()?,
+                entity_id: row.get("entity_id"),
+                body: row.get("body"),
+                revision: row.get("revision"),
+                author_id: row.get("author_id"),
+                edited_by: row.get("edited_by"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for NoteRevision {
+    type BaseData = NoteRevisionBase;
+
+    fn table_name() -> &'static str {
+        "note_revisions"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    note_id,
+                    network_id,
+                    revision,
+                    body,
+                    edited_by,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "note_id",
+                "network_id",
+                "revision",
+                "body",
+                "edited_by",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(note_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::I32(revision),
+                SqlValue::String(body),
+                SqlValue::Uuid(edited_by),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(NoteRevision {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: NoteRevisionBase {
+                note_id: row.get("note_id"),
+                network_id: row.get("network_id"),
+                revision: row.get("revision"),
+                body: row.get("body"),
+                edited_by: row.get("edited_by"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for NoteAttachment {
+    type BaseData = NoteAttachmentBase;
+
+    fn table_name() -> &'static str {
+        "note_attachments"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    note_id,
+                    network_id,
+                    filename,
+                    content_type,
+                    size,
+                    data,
+                    uploaded_by,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "note_id",
+                "network_id",
+                "filename",
+                "content_type",
+                "size",
+                "data",
+                "uploaded_by",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(note_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::String(filename),
+                SqlValue::String(content_type),
+                SqlValue::I32(size),
+                SqlValue::Bytes(data),
+                SqlValue::Uuid(uploaded_by),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(NoteAttachment {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: NoteAttachmentBase {
+                note_id: row.get("note_id"),
+                network_id: row.get("network_id"),
+                filename: row.get("filename"),
+                content_type: row.get("content_type"),
+                size: row.get("size"),
+                data: row.get("data"),
+                uploaded_by: row.get("uploaded_by"),
+            },
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    notes::r#impl::base::{
+        Note, NoteAttachment, NoteAttachmentBase, NoteBase, NoteEntity, NoteRevision,
+        NoteRevisionBase,
+    },
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+};
+
+/// Notes keep every earlier body as a revision. Revisions and attachments are removed with
+/// their note by the database.
+pub struct NoteService {
+    storage: Arc<GenericPostgresStorage<Note>>,
+    revision_storage: Arc<GenericPostgresStorage<NoteRevision>>,
+    attachment_storage: Arc<GenericPostgresStorage<NoteAttachment>>,
+}
+
+#[async_trait]
+impl CrudService<Note> for NoteService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<Note>> {
+        &self.storage
+    }
+}
+
+impl NoteService {
+    pub const MAX_ATTACHMENT_BYTES: usize = 1024 * 1024;
+    pub const MAX_ATTACHMENTS: usize = 20;
+
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<Note>>,
+        revision_storage: Arc<GenericPostgresStorage<NoteRevision>>,
+        attachment_storage: Arc<GenericPostgresStorage<NoteAttachment>>,
+    ) -> Self {
+        Self {
+            storage,
+            revision_storage,
+            attachment_storage,
+        }
+    }
+
+    /// Notes on a host, service or subnet, oldest first
+    pub async fn notes_on(&self, entity_id: &Uuid) -> Result<Vec<Note>> {
+        self.storage
+            .get_all(EntityFilter::unfiltered().noted_entity_id(entity_id))
+            .await
+    }
+
+    pub async fn create_note(
+        &self,
+        network_id: Uuid,
+        entity_type: NoteEntity,
+        entity_id: Uuid,
+        body: String,
+        user_id: Uuid,
+    ) -> Result<Note> {
+        let note = Note::new(NoteBase {
+            network_id,
+            entity_type,
+            entity_id,
+            body,
+            revision: 1,
+            author_id: user_id,
+            edited_by: user_id,
+        });
+
+        self.storage.create(&note).await
+    }
+
+    /// Replace the body, keeping the current one as a revision
+    pub async fn edit(&self, mut note: Note, body: String, user_id: Uuid) -> Result<Note> {
+        if note.base.body == body {
+            return Ok(note);
+        }
+
+        let revision = NoteRevision::new(NoteRevisionBase {
+            note_id: note.id,
+            network_id: note.base.network_id,
+            revision: note.base.revision,
+            body: std::mem::replace(&mut note.base.body, body),
+            edited_by: note.base.edited_by,
+        });
+        self.revision_storage.create(&revision).await?;
+
+        note.base.revision += 1;
+        note.base.edited_by = user_id;
+        self.storage.update(&mut note).await
+    }
+
+    /// Earlier bodies of the note, newest first
+    pub async fn revisions(&self, note_id: &Uuid) -> Result<Vec<NoteRevision>> {
+        let mut revisions = self
+            .revision_storage
+            .get_all(EntityFilter::unfiltered().note_id(note_id))
+            .await?;
+        revisions.sort_by_key(|r| std::cmp::Reverse(r.base.revision));
+        Ok(revisions)
+    }
+
+    pub async fn attachments(&self, note_id: &Uuid) -> Result<Vec<NoteAttachment>> {
+        self.attachment_storage
+            .get_all(EntityFilter::unfiltered().note_id(note_id))
+            .await
+    }
+
+    /// The attachment, if it belongs to the note
+    pub async fn attachment(
+        &self,
+        note_id: &Uuid,
+        attachment_id: &Uuid,
+    ) -> Result<Option<NoteAttachment>> {
+        Ok(self
+            .attachment_storage
+            .get_by_id(attachment_id)
+            .await?
+            .filter(|attachment| attachment.base.note_id == *note_id))
+    }
+
+    pub async fn attach(
+        &self,
+        note: &Note,
+        filename: String,
+        content_type: String,
+        data: Vec<u8>,
+        user_id: Uuid,
+    ) -> Result<NoteAttachment> {
+        let attachment = NoteAttachment::new(NoteAttachmentBase {
+            note_id: note.id,
+            network_id: note.base.network_id,
+            filename,
+            content_type,
+            size: data.len() as i32,
+            data,
+            uploaded_by: user_id,
+        });
+
+        self.attachment_storage.create(&attachment).await
+    }
+
+    pub async fn delete_attachment(&self, attachment_id: &Uuid) -> Result<()> {
+        self.attachment_storage.delete(attachment_id).await
+    }
+}
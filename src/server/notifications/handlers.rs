@@ -0,0 +1,135 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    notifications::r#impl::{
+        api::Alert,
+        base::{AlertEvent, AlertRule, Channel},
+    },
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, create_handler, delete_handler, get_accessible,
+            get_all_handler, get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/channels", get(get_all_handler::<Channel>))
+        .route("/channels", post(create_handler::<Channel>))
+        .route("/channels/{id}", put(update_channel_handler))
+        .route("/channels/{id}", delete(delete_handler::<Channel>))
+        .route("/channels/{id}", get(get_by_id_handler::<Channel>))
+        .route("/channels/{id}/test", post(test_channel_handler))
+        .route("/rules", get(get_all_handler::<AlertRule>))
+        .route("/rules", post(create_rule_handler))
+        .route("/rules/{id}", put(update_rule_handler))
+        .route("/rules/{id}", delete(delete_handler::<AlertRule>))
+        .route("/rules/{id}", get(get_by_id_handler::<AlertRule>))
+}
+
+async fn update_channel_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<Channel>,
+) -> ApiResult<Json<ApiResponse<Channel>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let service = Channel::get_service(&state);
+    let existing: Channel = get_accessible(&state, &user.0, &id).await?;
+    check_access(&state, &user.0, &request).await?;
+
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// Send a test alert to a channel, enabled or not, and report whether it was delivered
+async fn test_channel_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = Channel::get_service(&state);
+    let channel: Channel = get_accessible(&state, &user.0, &id).await?;
+
+    let alert = Alert::new(
+        AlertEvent::Test,
+        channel.base.network_id,
+        "NetVisor test alert".to_string(),
+        format!("Test alert for channel '{}'", channel.base.name),
+    );
+
+    // SMTP settings can be reloaded, so use the current client
+    let email_client = state.reloadable().email_client.clone();
+    service
+        .send(&channel, &alert, email_client.as_deref())
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+async fn create_rule_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<AlertRule>,
+) -> ApiResult<Json<ApiResponse<AlertRule>>> {
+    validate_rule(&state, &request).await?;
+    check_access(&state, &user.0, &request).await?;
+
+    let created = AlertRule::get_service(&state).create(request).await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+async fn update_rule_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<AlertRule>,
+) -> ApiResult<Json<ApiResponse<AlertRule>>> {
+    validate_rule(&state, &request).await?;
+
+    let service = AlertRule::get_service(&state);
+    let existing: AlertRule = get_accessible(&state, &user.0, &id).await?;
+    check_access(&state, &user.0, &request).await?;
+
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+async fn validate_rule(state: &AppState, rule: &AlertRule) -> Result<(), ApiError> {
+    if let Err(err) = rule.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let unknown = AlertRule::get_service(state).unknown_channels(rule).await?;
+    if let Some(id) = unknown.first() {
+        return Err(ApiError::bad_request(&format!(
+            "Notification channel '{}' not found in the rule's network",
+            id
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    daemon::discovery::types::base::DiscoveryPhase,
+    server::{
+        daemons::r#impl::api::{DaemonEvent, DiscoveryUpdatePayload},
+        hosts::r#impl::{api::HostEvent, base::Host, ports::PortBase},
+        notifications::r#impl::base::AlertEvent,
+        policies::r#impl::api::PolicyViolationEvent,
+    },
+};
+
+/// A notification as sent to channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub event: AlertEvent,
+    pub network_id: Uuid,
+    pub title: String,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Alert {
+    pub fn new(event: AlertEvent, network_id: Uuid, title: String, message: String) -> Self {
+        Self {
+            event,
+            network_id,
+            title,
+            message,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn from_host_event(event: &HostEvent) -> Self {
+        let (alert_event, host, title) = match event {
+            HostEvent::Discovered(host) => {
+                (AlertEvent::HostDiscovered, host, "New host discovered")
+            }
+            HostEvent::WentOffline(host) => (AlertEvent::HostOffline, host, "Host went offline"),
+            HostEvent::CameOnline(host) => (AlertEvent::HostOnline, host, "Host is back online"),
+            HostEvent::PortsOpened(host, ports) => {
+                let ports: Vec<String> = ports
+                    .iter()
+                    .map(|p| PortBase::Custom(*p).to_string())
+                    .collect();
+
+                return Self::new(
+                    AlertEvent::PortOpened,
+                    host.base.network_id,
+                    format!("New open port detected: {}", host.base.name),
+                    format!("{} opened on {}", ports.join(", "), describe_host(host)),
+                );
+            }
+        };
+
+        Self::new(
+            alert_event,
+            host.base.network_id,
+            format!("{}: {}", title, host.base.name),
+            describe_host(host),
+        )
+    }
+
+    /// Alert for a discovery update, if it reports a failed run
+    pub fn from_discovery_update(update: &DiscoveryUpdatePayload) -> Option<Self> {
+        if !matches!(update.phase, DiscoveryPhase::Failed) {
+            return None;
+        }
+
+        Some(Self::new(
+            AlertEvent::DiscoveryFailed,
+            update.network_id,
+            format!("Discovery failed: {}", update.discovery_type),
+            format!(
+                "Session {} on daemon {} failed after processing {} of {}: {}",
+                update.session_id,
+                update.daemon_id,
+                update.processed,
+                update.total_to_process,
+                update.error.as_deref().unwrap_or("unknown error")
+            ),
+        ))
+    }
+
+    pub fn from_daemon_event(event: &DaemonEvent) -> Self {
+        let (alert_event, daemon, title) = match event {
+            DaemonEvent::WentOffline(daemon) => {
+                (AlertEvent::DaemonOffline, daemon, "Daemon went offline")
+            }
+            DaemonEvent::CameOnline(daemon) => {
+                (AlertEvent::DaemonOnline, daemon, "Daemon is back online")
+            }
+        };
+
+        Self::new(
+            alert_event,
+            daemon.base.network_id,
+            format!("{}: {}", title, daemon.base.ip),
+            format!(
+                "Daemon {} at {}, last heartbeat at {}",
+                daemon.id,
+                daemon.base.ip,
+                daemon.base.last_seen.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+        )
+    }
+
+    pub fn from_policy_violation(event: &PolicyViolationEvent) -> Self {
+        Self::new(
+            AlertEvent::PolicyViolation,
+            event.violation.base.network_id,
+            format!(
+                "{} policy violated: {}",
+                event.policy.base.severity, event.policy.base.name
+            ),
+            event.violation.base.message.clone(),
+        )
+    }
+}
+
+fn describe_host(host: &Host) -> String {
+    let addresses: Vec<String> = host
+        .base
+        .interfaces
+        .iter()
+        .map(|i| i.base.ip_address.to_string())
+        .collect();
+
+    match (&host.base.hostname, addresses.is_empty()) {
+        (Some(hostname), false) => format!("{} ({})", hostname, addresses.join(", ")),
+        (Some(hostname), true) => hostname.clone(),
+        (None, false) => addresses.join(", "),
+        (None, true) => host.id.to_string(),
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::notifications::r#impl::channels::ChannelKind;
+
+/// Events alert rules can match
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum AlertEvent {
+    HostDiscovered,
+    HostOffline,
+    HostOnline,
+    /// A known host has a port open that wasn't before
+    PortOpened,
+    DiscoveryFailed,
+    /// A policy evaluation found a new violation
+    PolicyViolation,
+    /// A daemon stopped sending heartbeats
+    DaemonOffline,
+    DaemonOnline,
+    /// Sent by the channel test endpoint; never matched by rules
+    Test,
+}
+
+/// Somewhere alerts are sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelBase {
+    pub name: String,
+    pub network_id: Uuid,
+    pub kind: ChannelKind,
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Channel {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: ChannelBase,
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
+
+/// Which events in a network are sent to which channels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleBase {
+    pub name: String,
+    pub network_id: Uuid,
+    pub events: Vec<AlertEvent>,
+    pub channel_ids: Vec<Uuid>,
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: AlertRuleBase,
+}
+
+impl Display for AlertRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
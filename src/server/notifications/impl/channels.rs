@@ -0,0 +1,256 @@
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::server::{notifications::r#impl::api::Alert, shared::email::EmailClient};
+
+/// What channels need to deliver an alert. The email client is only set when SMTP is configured.
+pub struct NotificationContext<'a> {
+    pub client: &'a reqwest::Client,
+    pub email_client: Option<&'a EmailClient>,
+}
+
+/// A service alerts can be delivered to
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()>;
+
+    /// Check settings before the channel is stored
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ChannelKind {
+    Email(EmailChannel),
+    Slack(SlackChannel),
+    Discord(DiscordChannel),
+    Telegram(TelegramChannel),
+    Ntfy(NtfyChannel),
+    Gotify(GotifyChannel),
+    Webhook(WebhookChannel),
+}
+
+impl ChannelKind {
+    pub fn channel(&self) -> &dyn NotificationChannel {
+        match self {
+            ChannelKind::Email(c) => c,
+            ChannelKind::Slack(c) => c,
+            ChannelKind::Discord(c) => c,
+            ChannelKind::Telegram(c) => c,
+            ChannelKind::Ntfy(c) => c,
+            ChannelKind::Gotify(c) => c,
+            ChannelKind::Webhook(c) => c,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailChannel {
+    pub to: Vec<String>,
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let email_client = context
+            .email_client
+            .ok_or_else(|| anyhow!("SMTP is not configured"))?;
+
+        for to in &self.to {
+            email_client
+                .send(to, &alert.title, alert.message.clone())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.to.is_empty() {
+            return Err("At least one recipient is required".to_string());
+        }
+        match self
+            .to
+            .iter()
+            .find(|to| !email_address::EmailAddress::is_valid(to))
+        {
+            Some(to) => Err(format!("Invalid email address '{}'", to)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Slack incoming webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlackChannel {
+    pub webhook_url: String,
+}
+
+#[async_trait]
+impl NotificationChannel for SlackChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let body = json!({ "text": format!("*{}*\n{}", alert.title, alert.message) });
+        post_json(context.client, &self.webhook_url, &body).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_url(&self.webhook_url)
+    }
+}
+
+/// Discord channel webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscordChannel {
+    pub webhook_url: String,
+}
+
+#[async_trait]
+impl NotificationChannel for DiscordChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let body = json!({ "content": format!("**{}**\n{}", alert.title, alert.message) });
+        post_json(context.client, &self.webhook_url, &body).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_url(&self.webhook_url)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelegramChannel {
+    pub bot_token: String,
+    pub chat_id: String,
+}
+
+#[async_trait]
+impl NotificationChannel for TelegramChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token);
+        let body = json!({
+            "chat_id": self.chat_id,
+            "text": format!("{}\n{}", alert.title, alert.message),
+        });
+        post_json(context.client, &url, &body).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.bot_token.is_empty() || self.chat_id.is_empty() {
+            return Err("Bot token and chat id are required".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NtfyChannel {
+    #[serde(default = "default_ntfy_server")]
+    pub server_url: String,
+    pub topic: String,
+    /// Access token for protected topics
+    pub token: Option<String>,
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+#[async_trait]
+impl NotificationChannel for NtfyChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let url = format!("{}/{}", self.server_url.trim_end_matches('/'), self.topic);
+        let mut request = context
+            .client
+            .post(url)
+            .header("Title", &alert.title)
+            .body(alert.message.clone());
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        check_response(request.send().await?).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.topic.is_empty() || self.topic.contains('/') {
+            return Err(format!("Invalid ntfy topic '{}'", self.topic));
+        }
+        validate_url(&self.server_url)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GotifyChannel {
+    pub server_url: String,
+    /// Application token
+    pub token: String,
+    #[serde(default = "default_gotify_priority")]
+    pub priority: u8,
+}
+
+fn default_gotify_priority() -> u8 {
+    5
+}
+
+#[async_trait]
+impl NotificationChannel for GotifyChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        let url = format!("{}/message", self.server_url.trim_end_matches('/'));
+        let request = context
+            .client
+            .post(url)
+            .header("X-Gotify-Key", &self.token)
+            .json(&json!({
+                "title": alert.title,
+                "message": alert.message,
+                "priority": self.priority,
+            }));
+
+        check_response(request.send().await?).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_url(&self.server_url)
+    }
+}
+
+/// Any endpoint accepting the alert as JSON. Signed, templated delivery is what `/api/webhooks`
+/// is for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookChannel {
+    pub url: String,
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    async fn send(&self, context: &NotificationContext<'_>, alert: &Alert) -> Result<()> {
+        post_json(context.client, &self.url, &serde_json::to_value(alert)?).await
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_url(&self.url)
+    }
+}
+
+async fn post_json(client: &reqwest::Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    check_response(client.post(url).json(body).send().await?).await
+}
+
+async fn check_response(response: reqwest::Response) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("HTTP {}: {}", status, body);
+    }
+    Ok(())
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("Invalid URL '{}'", url)),
+    }
+}
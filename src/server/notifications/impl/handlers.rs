@@ -0,0 +1,53 @@
+use crate::server::notifications::r#impl::base::{AlertEvent, AlertRule, Channel};
+use crate::server::notifications::service::{AlertService, ChannelService};
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+impl CrudHandlers for Channel {
+    type Service = ChannelService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.channel_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Notification channel"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.base.kind.channel().validate()
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
+
+impl CrudHandlers for AlertRule {
+    type Service = AlertService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.alert_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Alert rule"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.events.is_empty() {
+            return Err("At least one event is required".to_string());
+        }
+        if self.base.events.contains(&AlertEvent::Test) {
+            return Err("Test events can't be used in rules".to_string());
+        }
+        if self.base.channel_ids.is_empty() {
+            return Err("At least one channel is required".to_string());
+        }
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
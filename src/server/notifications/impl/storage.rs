@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    notifications::r#impl::base::{AlertRule, AlertRuleBase, Channel, ChannelBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for Channel {
+    type BaseData = ChannelBase;
+
+    fn table_name() -> &'static str {
+        "notification_channels"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    network_id,
+                    kind,
+                    is_enabled,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "name",
+                "network_id",
+                "kind",
+                "is_enabled",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(name),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&kind)?),
+                SqlValue::Bool(is_enabled),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let kind: serde_json::Value = row.get("kind");
+
+        Ok(Channel {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: ChannelBase {
+                name: row.get("name"),
+                network_id: row.get("network_id"),
+                kind: serde_json::from_value(kind)?,
+                is_enabled: row.get("is_enabled"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for AlertRule {
+    type BaseData = AlertRuleBase;
+
+    fn table_name() -> &'static str {
+        "alert_rules"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    network_id,
+                    events,
+                    channel_ids,
+                    is_enabled,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "name",
+                "network_id",
+                "events",
+                "channel_ids",
+                "is_enabled",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(name),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&events)?),
+                SqlValue::UuidArray(channel_ids),
+                SqlValue::Bool(is_enabled),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let events: serde_json::Value = row.get("events");
+        let channel_ids: serde_json::Value = row.get("channel_ids");
+
+        Ok(AlertRule {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: AlertRuleBase {
+                name: row.get("name"),
+                network_id: row.get("network_id"),
+                events: serde_json::from_value(events)?,
+                channel_ids: serde_json::from_value(channel_ids)?,
+                is_enabled: row.get("is_enabled"),
+            },
+        })
+    }
+}
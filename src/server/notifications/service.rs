@@ -0,0 +1,138 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::server::{
+    notifications::r#impl::{
+        api::Alert,
+        base::{AlertRule, Channel},
+        channels::NotificationContext,
+    },
+    shared::{
+        email::EmailClient,
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, generic::GenericPostgresStorage, traits::Storage},
+    },
+};
+
+pub struct ChannelService {
+    storage: Arc<GenericPostgresStorage<Channel>>,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl CrudService<Channel> for ChannelService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<Channel>> {
+        &self.storage
+    }
+}
+
+impl ChannelService {
+    pub fn new(storage: Arc<GenericPostgresStorage<Channel>>) -> Self {
+        Self {
+            storage,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Deliver an alert to a single channel, whether or not it's enabled
+    pub async fn send(
+        &self,
+        channel: &Channel,
+        alert: &Alert,
+        email_client: Option<&EmailClient>,
+    ) -> Result<()> {
+        let context = NotificationContext {
+            client: &self.client,
+            email_client,
+        };
+
+        channel.base.kind.channel().send(&context, alert).await
+    }
+}
+
+pub struct AlertService {
+    storage: Arc<GenericPostgresStorage<AlertRule>>,
+    channel_service: Arc<ChannelService>,
+}
+
+#[async_trait]
+impl CrudService<AlertRule> for AlertService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<AlertRule>> {
+        &self.storage
+    }
+}
+
+impl AlertService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<AlertRule>>,
+        channel_service: Arc<ChannelService>,
+    ) -> Self {
+        Self {
+            storage,
+            channel_service,
+        }
+    }
+
+    /// Channel ids that don't exist in the rule's network
+    pub async fn unknown_channels(&self, rule: &AlertRule) -> Result<Vec<Uuid>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[rule.base.network_id]);
+        let channels = self.channel_service.get_all(filter).await?;
+
+        Ok(rule
+            .base
+            .channel_ids
+            .iter()
+            .filter(|id| !channels.iter().any(|c| &c.id == *id))
+            .copied()
+            .collect())
+    }
+
+    /// Send an alert to the channels of every enabled rule in its network that matches it.
+    /// A channel in several matching rules gets the alert once. Failures are logged.
+    pub async fn dispatch(&self, alert: &Alert, email_client: Option<&EmailClient>) -> Result<()> {
+        let filter = EntityFilter::unfiltered().network_ids(&[alert.network_id]);
+        let rules = self.storage.get_all(filter).await?;
+
+        let mut channel_ids: Vec<Uuid> = rules
+            .iter()
+            .filter(|r| r.base.is_enabled && r.base.events.contains(&alert.event))
+            .flat_map(|r| r.base.channel_ids.iter().copied())
+            .collect();
+        channel_ids.sort();
+        channel_ids.dedup();
+
+        for channel_id in channel_ids {
+            let result = async {
+                let channel = self
+                    .channel_service
+                    .get_by_id(&channel_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Channel {} no longer exists", channel_id))?;
+
+                if channel.base.is_enabled {
+                    self.channel_service
+                        .send(&channel, alert, email_client)
+                        .await?;
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Failed to send {:?} alert to channel {}: {}",
+                    alert.event,
+                    channel_id,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
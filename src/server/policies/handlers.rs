@@ -0,0 +1,125 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    policies::r#impl::{
+        api::{EvaluatePoliciesQuery, PolicyViolationListQuery},
+        base::{Policy, PolicyViolation},
+    },
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, create_handler, delete_handler, get_all_handler,
+            get_by_id_handler,
+        },
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<Policy>))
+        .route("/", post(create_handler::<Policy>))
+        .route("/violations", get(get_violations))
+        .route("/evaluate", post(evaluate_policies))
+        .route("/{id}", put(update_policy))
+        .route("/{id}", delete(delete_handler::<Policy>))
+        .route("/{id}", get(get_by_id_handler::<Policy>))
+}
+
+async fn update_policy(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<Policy>,
+) -> ApiResult<Json<ApiResponse<Policy>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let service = Policy::get_service(&state);
+    let existing = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Policy '{}' not found", id)))?;
+    check_access(&state, &user.0, &existing).await?;
+
+    // Violations refer to the policy's network, so policies can't move between networks
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+    request.base.network_id = existing.base.network_id;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+async fn member_network_ids(state: &AppState, user_id: &Uuid) -> ApiResult<Vec<Uuid>> {
+    let user_filter = EntityFilter::unfiltered().network_member(user_id);
+
+    Ok(state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect())
+}
+
+/// Violations in the user's networks, open ones only unless resolved ones are asked for
+async fn get_violations(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<PolicyViolationListQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<PolicyViolation>>>> {
+    let network_ids = member_network_ids(&state, &user.0).await?;
+
+    let mut filter = EntityFilter::unfiltered().network_ids(&network_ids);
+    if let Some(policy_id) = &query.policy_id {
+        filter = filter.policy_id(policy_id);
+    }
+    if !query.include_resolved {
+        filter = filter.resolved(false);
+    }
+    let filter = query.list_query().apply::<PolicyViolation>(filter)?;
+
+    let violations = state.services.policy_service.violations(filter).await?;
+
+    Ok(Json(ApiResponse::page(violations)))
+}
+
+/// Evaluate a network's policies now instead of waiting for the next discovery run. Returns
+/// its open violations.
+async fn evaluate_policies(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<EvaluatePoliciesQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<PolicyViolation>>>> {
+    if !member_network_ids(&state, &user.0)
+        .await?
+        .contains(&query.network_id)
+    {
+        return Err(ApiError::not_found(format!(
+            "Network '{}' not found",
+            query.network_id
+        )));
+    }
+
+    let service = &state.services.policy_service;
+    service.evaluate(query.network_id).await?;
+
+    let filter = EntityFilter::unfiltered()
+        .network_ids(&[query.network_id])
+        .resolved(false);
+    let violations = service.violations(filter).await?;
+
+    Ok(Json(ApiResponse::page(violations)))
+}
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::server::{
+    policies::r#impl::base::{Policy, PolicyViolation},
+    shared::handlers::traits::ListQuery,
+};
+
+/// A violation an evaluation found that wasn't open before
+#[derive(Debug, Clone)]
+pub struct PolicyViolationEvent {
+    pub policy: Policy,
+    pub violation: PolicyViolation,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EvaluatePoliciesQuery {
+    pub network_id: Uuid,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PolicyViolationListQuery {
+    /// Only violations of this policy
+    pub policy_id: Option<Uuid>,
+    /// Include violations that have since been resolved
+    #[serde(default)]
+    pub include_resolved: bool,
+    pub limit: Option<u32>,
+    pub cursor: Option<Uuid>,
+    pub sort: Option<String>,
+    pub q: Option<String>,
+}
+
+impl PolicyViolationListQuery {
+    pub fn list_query(&self) -> ListQuery {
+        ListQuery {
+            limit: self.limit,
+            cursor: self.cursor,
+            sort: self.sort.clone(),
+            q: self.q.clone(),
+            ..Default::default()
+        }
+    }
+}
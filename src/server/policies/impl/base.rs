@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::ports::PortConfig, services::r#impl::categories::ServiceCategory,
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum_macros::Display,
+    EnumString,
+)]
+pub enum PolicySeverity {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+/// What a policy forbids
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PolicyCondition {
+    /// None of the ports may be open on hosts in the subnets, e.g. 23/tcp for "no Telnet
+    /// anywhere". Empty subnets means every host in the network.
+    ForbiddenPorts {
+        ports: Vec<PortConfig>,
+        #[serde(default)]
+        subnet_ids: Vec<Uuid>,
+    },
+    /// Services in the categories must not be reachable from the subnets, e.g. databases from
+    /// the IoT subnet
+    NotReachableFrom {
+        subnet_ids: Vec<Uuid>,
+        service_categories: Vec<ServiceCategory>,
+    },
+}
+
+/// A rule hosts and services in a network are checked against after each discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyBase {
+    pub name: String,
+    pub network_id: Uuid,
+    pub description: Option<String>,
+    pub condition: PolicyCondition,
+    #[serde(default)]
+    pub severity: PolicySeverity,
+    pub is_enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Policy {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: PolicyBase,
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
+
+/// A host or service breaking a policy. Opened when an evaluation first finds it and
+/// resolved when one no longer does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyViolationBase {
+    pub policy_id: Uuid,
+    pub network_id: Uuid,
+    pub host_id: Uuid,
+    pub service_id: Option<Uuid>,
+    /// The forbidden port, for port policies
+    pub port: Option<PortConfig>,
+    pub message: String,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    pub id: Uuid,
+    /// When the violation was first found
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: PolicyViolationBase,
+}
+
+impl Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Violation of policy {}: {}",
+            self.base.policy_id, self.id
+        )
+    }
+}
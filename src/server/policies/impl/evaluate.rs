@@ -0,0 +1,275 @@
+use std::collections::BTreeSet;
+
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::r#impl::base::Daemon,
+    hosts::r#impl::{
+        base::Host,
+        ports::{PortBase, PortConfig},
+    },
+    policies::r#impl::base::PolicyCondition,
+    services::r#impl::base::Service,
+    shared::types::entities::EntitySource,
+    subnets::r#impl::base::Subnet,
+};
+
+/// A network's entities as of the end of a discovery run
+pub struct PolicyContext<'a> {
+    pub hosts: &'a [Host],
+    pub services: &'a [Service],
+    pub subnets: &'a [Subnet],
+    pub daemons: &'a [Daemon],
+}
+
+/// A host or service an evaluation found breaking a policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyFinding {
+    pub host_id: Uuid,
+    pub service_id: Option<Uuid>,
+    pub port: Option<PortConfig>,
+    pub message: String,
+}
+
+impl PolicyContext<'_> {
+    fn subnet_name(&self, id: &Uuid) -> String {
+        self.subnets
+            .iter()
+            .find(|s| &s.id == id)
+            .map(|s| s.base.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn host_subnets(&self, host_id: &Uuid) -> BTreeSet<Uuid> {
+        self.hosts
+            .iter()
+            .find(|h| &h.id == host_id)
+            .map(|h| h.base.interfaces.iter().map(|i| i.base.subnet_id).collect())
+            .unwrap_or_default()
+    }
+
+    /// Subnets a service can be reached from: those of the interfaces it listens on, and
+    /// those of the daemons that discovered it, since their scans got through to it
+    fn reachable_from(&self, host: &Host, service: &Service) -> BTreeSet<Uuid> {
+        let mut subnets = BTreeSet::new();
+
+        for binding in &service.base.bindings {
+            match binding.interface_id() {
+                Some(interface_id) => subnets.extend(
+                    host.base
+                        .interfaces
+                        .iter()
+                        .filter(|i| i.id == interface_id)
+                        .map(|i| i.base.subnet_id),
+                ),
+                None => subnets.extend(host.base.interfaces.iter().map(|i| i.base.subnet_id)),
+            }
+        }
+
+        if let EntitySource::DiscoveryWithMatch { metadata, .. } = &service.base.source {
+            for daemon_id in metadata.iter().map(|m| m.daemon_id) {
+                if let Some(daemon) = self.daemons.iter().find(|d| d.id == daemon_id) {
+                    subnets.extend(self.host_subnets(&daemon.base.host_id));
+                }
+            }
+        }
+
+        subnets
+    }
+}
+
+impl PolicyCondition {
+    /// Everything in the network breaking the condition
+    pub fn evaluate(&self, context: &PolicyContext) -> Vec<PolicyFinding> {
+        match self {
+            PolicyCondition::ForbiddenPorts { ports, subnet_ids } => context
+                .hosts
+                .iter()
+                .filter(|host| {
+                    subnet_ids.is_empty()
+                        || host
+                            .base
+                            .interfaces
+                            .iter()
+                            .any(|i| subnet_ids.contains(&i.base.subnet_id))
+                })
+                .flat_map(|host| {
+                    host.base
+                        .ports
+                        .iter()
+                        .filter(|port| ports.contains(&port.base.config()))
+                        .map(move |port| PolicyFinding {
+                            host_id: host.id,
+                            service_id: context
+                                .services
+                                .iter()
+                                .find(|s| {
+                                    s.base.host_id == host.id
+                                        && s.base
+                                            .bindings
+                                            .iter()
+                                            .any(|b| b.port_id() == Some(port.id))
+                                })
+                                .map(|s| s.id),
+                            port: Some(port.base.config()),
+                            message: format!(
+                                "Port {} is open on {}",
+                                PortBase::Custom(port.base.config()),
+                                host.base.name
+                            ),
+                        })
+                })
+                .collect(),
+            PolicyCondition::NotReachableFrom {
+                subnet_ids,
+                service_categories,
+            } => context
+                .services
+                .iter()
+                .filter(|s| service_categories.contains(&s.base.service_definition.category()))
+                .filter_map(|service| {
+                    let host = context
+                        .hosts
+                        .iter()
+                        .find(|h| h.id == service.base.host_id)?;
+                    let subnet_id = context
+                        .reachable_from(host, service)
+                        .into_iter()
+                        .find(|id| subnet_ids.contains(id))?;
+
+                    Some(PolicyFinding {
+                        host_id: host.id,
+                        service_id: Some(service.id),
+                        port: None,
+                        message: format!(
+                            "{} on {} is reachable from {}",
+                            service.base.name,
+                            host.base.name,
+                            context.subnet_name(&subnet_id)
+                        ),
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::HostBase,
+            interfaces::{Interface, InterfaceBase},
+            ports::Port,
+        },
+        services::{
+            definitions::mysql::MySql,
+            r#impl::{base::ServiceBase, bindings::Binding, categories::ServiceCategory},
+        },
+        shared::storage::traits::StorableEntity,
+    };
+
+    fn interface(subnet_id: Uuid, last_octet: u8) -> Interface {
+        Interface::new(InterfaceBase {
+            subnet_id,
+            ip_address: IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)),
+            mac_address: None,
+            name: None,
+        })
+    }
+
+    fn database(host: &Host, binding: Binding) -> Service {
+        Service::new(ServiceBase {
+            host_id: host.id,
+            network_id: host.base.network_id,
+            service_definition: Box::new(MySql),
+            name: "MySQL".to_string(),
+            bindings: vec![binding],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn finds_forbidden_ports_in_scoped_subnets() {
+        let (lan, iot) = (Uuid::new_v4(), Uuid::new_v4());
+        let telnet = Port::new(PortBase::Telnet);
+        let camera = Host::new(HostBase {
+            name: "camera".to_string(),
+            interfaces: vec![interface(iot, 10)],
+            ports: vec![telnet, Port::new(PortBase::new_tcp(80))],
+            ..Default::default()
+        });
+        let switch = Host::new(HostBase {
+            name: "switch".to_string(),
+            interfaces: vec![interface(lan, 2)],
+            ports: vec![Port::new(PortBase::Telnet)],
+            ..Default::default()
+        });
+        let hosts = [camera.clone(), switch];
+        let context = PolicyContext {
+            hosts: &hosts,
+            services: &[],
+            subnets: &[],
+            daemons: &[],
+        };
+
+        let condition = PolicyCondition::ForbiddenPorts {
+            ports: vec![PortBase::Telnet.config()],
+            subnet_ids: vec![iot],
+        };
+        let findings = condition.evaluate(&context);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].host_id, camera.id);
+        assert_eq!(findings[0].port, Some(telnet.base.config()));
+        assert_eq!(findings[0].message, "Port 23/tcp is open on camera");
+
+        let anywhere = PolicyCondition::ForbiddenPorts {
+            ports: vec![PortBase::Telnet.config()],
+            subnet_ids: vec![],
+        };
+        assert_eq!(anywhere.evaluate(&context).len(), 2);
+    }
+
+    #[test]
+    fn finds_services_reachable_from_subnets() {
+        let (lan, iot) = (Uuid::new_v4(), Uuid::new_v4());
+        let port = Port::new(PortBase::new_tcp(3306));
+        let (lan_interface, iot_interface) = (interface(lan, 2), interface(iot, 3));
+        let server = Host::new(HostBase {
+            name: "server".to_string(),
+            interfaces: vec![lan_interface.clone(), iot_interface],
+            ports: vec![port],
+            ..Default::default()
+        });
+        let condition = PolicyCondition::NotReachableFrom {
+            subnet_ids: vec![iot],
+            service_categories: vec![ServiceCategory::Database],
+        };
+
+        let listening_everywhere = [database(&server, Binding::new_port(port.id, None))];
+        let hosts = [server.clone()];
+        let context = PolicyContext {
+            hosts: &hosts,
+            services: &listening_everywhere,
+            subnets: &[],
+            daemons: &[],
+        };
+        let findings = condition.evaluate(&context);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].service_id, Some(listening_everywhere[0].id));
+
+        let listening_on_lan = [database(
+            &server,
+            Binding::new_port(port.id, Some(lan_interface.id)),
+        )];
+        let context = PolicyContext {
+            services: &listening_on_lan,
+            ..context
+        };
+        assert!(condition.evaluate(&context).is_empty());
+    }
+}
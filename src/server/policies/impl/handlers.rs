@@ -0,0 +1,48 @@
+use crate::server::policies::r#impl::base::{Policy, PolicyCondition};
+use crate::server::policies::service::PolicyService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+impl CrudHandlers for Policy {
+    type Service = PolicyService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.policy_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Policy"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.name.trim().is_empty() {
+            return Err("Policy name is required".to_string());
+        }
+        match &self.base.condition {
+            PolicyCondition::ForbiddenPorts { ports, .. } => {
+                if ports.is_empty() {
+                    return Err("At least one port is required".to_string());
+                }
+                if ports.iter().any(|p| p.number == 0) {
+                    return Err("Port numbers must be between 1 and 65535".to_string());
+                }
+            }
+            PolicyCondition::NotReachableFrom {
+                subnet_ids,
+                service_categories,
+            } => {
+                if subnet_ids.is_empty() {
+                    return Err("At least one subnet is required".to_string());
+                }
+                if service_categories.is_empty() {
+                    return Err("At least one service category is required".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
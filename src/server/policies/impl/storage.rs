@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::ports::PortConfig,
+    policies::r#impl::base::{Policy, PolicyBase, PolicyViolation, PolicyViolationBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for Policy {
+    type BaseData = PolicyBase;
+
+    fn table_name() -> &'static str {
+        "policies"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "description"]
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    network_id,
+                    description,
+                    condition,
+                    severity,
+                    is_enabled,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "name",
+                "network_id",
+                "description",
+                "condition",
+                "severity",
+                "is_enabled",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(name),
+                SqlValue::Uuid(network_id),
+                SqlValue::OptionalString(description),
+                SqlValue::Json(serde_json::to_value(&condition)?),
+                SqlValue::String(severity.to_string()),
+                SqlValue::Bool(is_enabled),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let condition: serde_json::Value = row.get("condition");
+        let severity: String = row.get("severity");
+
+        Ok(Policy {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: PolicyBase {
+                name: row.get("name"),
+                network_id: row.get("network_id"),
+                description: row.get("description"),
+                condition: serde_json::from_value(condition)?,
+                severity: severity.parse()?,
+                is_enabled: row.get("is_enabled"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for PolicyViolation {
+    type BaseData = PolicyViolationBase;
+
+    fn table_name() -> &'static str {
+        "policy_violations"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["message"]
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    policy_id,
+                    network_id,
+                    host_id,
+                    service_id,
+                    port,
+                    message,
+                    resolved_at,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "policy_id",
+                "network_id",
+                "host_id",
+                "service_id",
+                "port",
+                "message",
+                "resolved_at",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(policy_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::Uuid(host_id),
+                SqlValue::OptionalUuid(service_id),
+                SqlValue::Json(serde_json::to_value(port)?),
+                SqlValue::String(message),
+                SqlValue::OptionTimestamp(resolved_at),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let port: Option<PortConfig> = row
+            .get::<Option<serde_json::Value>, _>("port")
+            .map(serde_json::from_value)
+            .transpose()?
+            .flatten();
+
+        Ok(PolicyViolation {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: PolicyViolationBase {
+                policy_id: row.get("policy_id"),
+                network_id: row.get("network_id"),
+                host_id: row.get("host_id"),
+                service_id: row.get("service_id"),
+                port,
+                message: row.get("message"),
+                resolved_at: row.get("resolved_at"),
+            },
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::{Mutex, broadcast};
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::r#impl::base::Daemon,
+    hosts::r#impl::base::Host,
+    policies::r#impl::{
+        api::PolicyViolationEvent,
+        base::{Policy, PolicyViolation, PolicyViolationBase},
+        evaluate::PolicyContext,
+    },
+    services::r#impl::base::Service,
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{Page, StorableEntity, Storage},
+        },
+    },
+    subnets::r#impl::base::Subnet,
+};
+
+pub struct PolicyService {
+    storage: Arc<GenericPostgresStorage<Policy>>,
+    violation_storage: Arc<GenericPostgresStorage<PolicyViolation>>,
+    host_storage: Arc<GenericPostgresStorage<Host>>,
+    service_storage: Arc<GenericPostgresStorage<Service>>,
+    subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+    daemon_storage: Arc<GenericPostgresStorage<Daemon>>,
+    event_tx: broadcast::Sender<PolicyViolationEvent>,
+    /// Held while evaluating, so runs finishing together don't open the same violation twice
+    evaluation_lock: Mutex<()>,
+}
+
+#[async_trait]
+impl CrudService<Policy> for PolicyService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<Policy>> {
+        &self.storage
+    }
+}
+
+impl PolicyService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<Policy>>,
+        violation_storage: Arc<GenericPostgresStorage<PolicyViolation>>,
+        host_storage: Arc<GenericPostgresStorage<Host>>,
+        service_storage: Arc<GenericPostgresStorage<Service>>,
+        subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+        daemon_storage: Arc<GenericPostgresStorage<Daemon>>,
+    ) -> Self {
+        Self {
+            storage,
+            violation_storage,
+            host_storage,
+            service_storage,
+            subnet_storage,
+            daemon_storage,
+            event_tx: broadcast::channel(100).0,
+            evaluation_lock: Mutex::new(()),
+        }
+    }
+
+    /// Violations as they're opened, for alerting
+    pub fn subscribe(&self) -> broadcast::Receiver<PolicyViolationEvent> {
+        self.event_tx.subscribe()
+    }
+
+    pub async fn violations(&self, filter: EntityFilter) -> Result<Page<PolicyViolation>> {
+        self.violation_storage.get_page(filter).await
+    }
+
+    /// Check a network against its enabled policies. Findings without an open violation open
+    /// one, and open violations that weren't found again are resolved. Returns how many
+    /// violations were opened.
+    pub async fn evaluate(&self, network_id: Uuid) -> Result<usize> {
+        let _guard = self.evaluation_lock.lock().await;
+        let filter = || EntityFilter::unfiltered().network_ids(&[network_id]);
+
+        let policies = self.storage.get_all(filter()).await?;
+        let hosts = self.host_storage.get_all(filter()).await?;
+        let services = self.service_storage.get_all(filter()).await?;
+        let subnets = self.subnet_storage.get_all(filter()).await?;
+        let daemons = self.daemon_storage.get_all(filter()).await?;
+        let context = PolicyContext {
+            hosts: &hosts,
+            services: &services,
+            subnets: &subnets,
+            daemons: &daemons,
+        };
+
+        let mut open = self
+            .violation_storage
+            .get_all(filter().resolved(false))
+            .await?;
+        let mut opened = 0;
+
+        for policy in policies.iter().filter(|p| p.base.is_enabled) {
+            for finding in policy.base.condition.evaluate(&context) {
+                if let Some(index) = open.iter().position(|v| {
+                    v.base.policy_id == policy.id
+                        && v.base.host_id == finding.host_id
+                        && v.base.service_id == finding.service_id
+                        && v.base.port == finding.port
+                }) {
+                    open.swap_remove(index);
+                    continue;
+                }
+
+                let violation = self
+                    .violation_storage
+                    .create(&PolicyViolation::new(PolicyViolationBase {
+                        policy_id: policy.id,
+                        network_id,
+                        host_id: finding.host_id,
+                        service_id: finding.service_id,
+                        port: finding.port,
+                        message: finding.message,
+                        resolved_at: None,
+                    }))
+                    .await?;
+                opened += 1;
+
+                let _ = self.event_tx.send(PolicyViolationEvent {
+                    policy: policy.clone(),
+                    violation,
+                });
+            }
+        }
+
+        // Not found again, or their policy has been disabled
+        let now = Utc::now();
+        for mut violation in open {
+            violation.base.resolved_at = Some(now);
+            self.violation_storage.update(&mut violation).await?;
+        }
+
+        Ok(opened)
+    }
+}
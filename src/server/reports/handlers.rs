@@ -0,0 +1,120 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    reports::r#impl::base::ReportSchedule,
+    shared::{
+        handlers::traits::{CrudHandlers, get_accessible, get_all_handler, get_by_id_handler},
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::Html,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<ReportSchedule>))
+        .route("/", post(create_handler))
+        .route("/{id}/preview", get(preview_handler))
+        .route("/{id}/send", post(send_handler))
+        .route("/{id}", put(update_handler))
+        .route("/{id}", delete(delete_handler))
+        .route("/{id}", get(get_by_id_handler::<ReportSchedule>))
+}
+
+pub async fn create_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(schedule): Json<ReportSchedule>,
+) -> ApiResult<Json<ApiResponse<ReportSchedule>>> {
+    if let Err(err) = schedule.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+    network_role(&state, &user.0, &schedule.base.network_id).await?;
+
+    let schedule = ReportSchedule::get_service(&state)
+        .create_schedule(schedule)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(schedule)))
+}
+
+pub async fn update_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<ReportSchedule>,
+) -> ApiResult<Json<ApiResponse<ReportSchedule>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+    get_accessible::<ReportSchedule>(&state, &user.0, &id).await?;
+    network_role(&state, &user.0, &request.base.network_id).await?;
+
+    request.id = id;
+    let updated = ReportSchedule::get_service(&state)
+        .update_schedule(request)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+pub async fn delete_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    get_accessible::<ReportSchedule>(&state, &user.0, &id).await?;
+
+    ReportSchedule::get_service(&state)
+        .delete_schedule(&id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Render the report the schedule would send next, as HTML
+pub async fn preview_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Html<String>> {
+    let schedule: ReportSchedule = get_accessible(&state, &user.0, &id).await?;
+
+    let report = ReportSchedule::get_service(&state)
+        .build_report(&schedule)
+        .await?;
+    let html = report
+        .render_html()
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?;
+
+    Ok(Html(html))
+}
+
+/// Send the schedule's report now instead of waiting for its next run
+pub async fn send_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    get_accessible::<ReportSchedule>(&state, &user.0, &id).await?;
+
+    let Some(email_client) = state.reloadable().email_client.clone() else {
+        return Err(ApiError::bad_request("SMTP is not configured"));
+    };
+
+    ReportSchedule::get_service(&state)
+        .send_report(&id, &email_client)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(())))
+}
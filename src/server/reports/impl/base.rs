@@ -0,0 +1,50 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::digests::r#impl::changes::InventorySnapshot;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportScheduleBase {
+    pub network_id: Uuid,
+    pub name: String,
+    /// Cron expression with seconds, as for scheduled discoveries, e.g. `0 0 8 * * Mon`
+    pub cron_schedule: String,
+    /// Email addresses to send the report to; the network owner when empty
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    pub is_enabled: bool,
+    /// Inventory as of the last report, which the next one reports changes against
+    #[serde(default, skip_serializing)]
+    pub snapshot: Option<InventorySnapshot>,
+    #[serde(default)]
+    pub last_sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSchedule {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: ReportScheduleBase,
+}
+
+impl ReportSchedule {
+    /// Start of the period the next report covers
+    pub fn period_start(&self) -> DateTime<Utc> {
+        self.base.last_sent_at.unwrap_or(self.created_at)
+    }
+
+    pub fn previous_snapshot(&self) -> InventorySnapshot {
+        self.base.snapshot.clone().unwrap_or_default()
+    }
+}
+
+impl Display for ReportSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Report schedule {}: {}", self.base.name, self.id)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::server::reports::r#impl::base::ReportSchedule;
+use crate::server::reports::service::ReportService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+impl CrudHandlers for ReportSchedule {
+    type Service = ReportService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.report_service
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.name.trim().is_empty() {
+            return Err("Report name is required".to_string());
+        }
+
+        match self
+            .base
+            .recipients
+            .iter()
+            .find(|r| r.parse::<lettre::Address>().is_err())
+        {
+            Some(recipient) => Err(format!("Invalid recipient '{}'", recipient)),
+            None => Ok(()),
+        }
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
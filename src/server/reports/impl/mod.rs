@@ -0,0 +1,4 @@
+pub mod base;
+pub mod handlers;
+pub mod report;
+pub mod storage;
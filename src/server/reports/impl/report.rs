@@ -0,0 +1,138 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tera::{Context, Tera};
+
+use crate::server::digests::r#impl::changes::DigestChanges;
+
+/// Summary of a network over the period since its previous report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub network_name: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub host_count: usize,
+    /// New and removed hosts, and hosts whose ports or services changed
+    pub changes: DigestChanges,
+    pub failed_discoveries: Vec<FailedDiscovery>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedDiscovery {
+    pub name: String,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+/// Self-contained HTML with inline styles, so it renders the same in mail clients and prints
+/// cleanly to PDF from a browser
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{ network_name }} report</title>
+</head>
+<body style="font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; color: #1f2937; max-width: 720px; margin: 0 auto; padding: 24px;">
+<h1 style="font-size: 22px; margin-bottom: 4px;">{{ network_name }}</h1>
+<p style="color: #6b7280; margin-top: 0;">{{ period_start | date(format="%Y-%m-%d %H:%M") }} to {{ period_end | date(format="%Y-%m-%d %H:%M") }} UTC &middot; {{ host_count }} hosts</p>
+
+<table style="border-collapse: collapse; margin: 16px 0;">
+<tr>
+<td style="padding: 8px 16px; background: #ecfdf5;"><strong>{{ changes.new_hosts | length }}</strong> new hosts</td>
+<td style="padding: 8px 16px; background: #fef2f2;"><strong>{{ changes.removed_hosts | length }}</strong> disappeared hosts</td>
+<td style="padding: 8px 16px; background: #eff6ff;"><strong>{{ changes.changed_hosts | length }}</strong> changed hosts</td>
+<td style="padding: 8px 16px; background: #fffbeb;"><strong>{{ failed_discoveries | length }}</strong> failed discoveries</td>
+</tr>
+</table>
+
+{% if changes.new_hosts %}
+<h2 style="font-size: 17px;">New hosts</h2>
+<ul>{% for host in changes.new_hosts %}<li>{{ host }}</li>{% endfor %}</ul>
+{% endif %}
+
+{% if changes.removed_hosts %}
+<h2 style="font-size: 17px;">Disappeared hosts</h2>
+<ul>{% for host in changes.removed_hosts %}<li>{{ host }}</li>{% endfor %}</ul>
+{% endif %}
+
+{% if changes.changed_hosts %}
+<h2 style="font-size: 17px;">Port and service changes</h2>
+<table style="border-collapse: collapse; width: 100%;">
+<tr style="text-align: left; border-bottom: 1px solid #d1d5db;"><th style="padding: 6px;">Host</th><th style="padding: 6px;">Opened</th><th style="padding: 6px;">Closed</th><th style="padding: 6px;">Services</th></tr>
+{% for host in changes.changed_hosts %}
+<tr style="border-bottom: 1px solid #e5e7eb; vertical-align: top;">
+<td style="padding: 6px;">{{ host.host }}</td>
+<td style="padding: 6px; color: #b91c1c;">{{ host.ports_opened | join(sep=", ") }}</td>
+<td style="padding: 6px; color: #047857;">{{ host.ports_closed | join(sep=", ") }}</td>
+<td style="padding: 6px;">{% for service in host.services_added %}+{{ service }} {% endfor %}{% for service in host.services_removed %}&minus;{{ service }} {% endfor %}</td>
+</tr>
+{% endfor %}
+</table>
+{% endif %}
+
+{% if failed_discoveries %}
+<h2 style="font-size: 17px;">Failed discoveries</h2>
+<ul>{% for run in failed_discoveries %}<li><strong>{{ run.name }}</strong>{% if run.finished_at %} at {{ run.finished_at | date(format="%Y-%m-%d %H:%M") }}{% endif %}{% if run.error %}: {{ run.error }}{% endif %}</li>{% endfor %}</ul>
+{% endif %}
+
+{% if not changes.new_hosts and not changes.removed_hosts and not changes.changed_hosts and not failed_discoveries %}
+<p>Nothing changed in this period.</p>
+{% endif %}
+</body>
+</html>
+"#;
+
+impl Report {
+    pub fn subject(&self) -> String {
+        format!(
+            "NetVisor report for {}: {} new, {} disappeared",
+            self.network_name,
+            self.changes.new_hosts.len(),
+            self.changes.removed_hosts.len()
+        )
+    }
+
+    pub fn render_html(&self) -> Result<String> {
+        let context = Context::from_serialize(self)?;
+
+        Tera::one_off(TEMPLATE, &context, true)
+            .map_err(|e| anyhow!("Failed to render report: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::digests::r#impl::changes::HostChanges;
+
+    #[test]
+    fn renders_changes_escaped() {
+        let report = Report {
+            network_name: "Home <lab>".to_string(),
+            period_start: Utc::now(),
+            period_end: Utc::now(),
+            host_count: 3,
+            changes: DigestChanges {
+                new_hosts: vec!["printer".to_string()],
+                removed_hosts: Vec::new(),
+                changed_hosts: vec![HostChanges {
+                    host: "nas".to_string(),
+                    ports_opened: vec!["8080/tcp".to_string()],
+                    ..HostChanges::default()
+                }],
+            },
+            failed_discoveries: vec![FailedDiscovery {
+                name: "Nightly scan".to_string(),
+                finished_at: None,
+                error: Some("Daemon unreachable".to_string()),
+            }],
+        };
+
+        let html = report.render_html().unwrap();
+        assert!(html.contains("Home &lt;lab&gt;"));
+        assert!(html.contains("<li>printer</li>"));
+        assert!(html.contains("8080/tcp"));
+        assert!(html.contains("Nightly scan"));
+        assert!(!html.contains("Nothing changed"));
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    reports::r#impl::base::{ReportSchedule, ReportScheduleBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for ReportSchedule {
+    type BaseData = ReportScheduleBase;
+
+    fn table_name() -> &'static str {
+        "report_schedules"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    network_id,
+                    name,
+                    cron_schedule,
+                    recipients,
+                    is_enabled,
+                    snapshot,
+                    last_sent_at,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "network_id",
+                "name",
+                "cron_schedule",
+                "recipients",
+                "is_enabled",
+                "snapshot",
+                "last_sent_at",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::String(name),
+                SqlValue::String(cron_schedule),
+                SqlValue::Json(serde_json::to_value(&recipients)?),
+                SqlValue::Bool(is_enabled),
+                SqlValue::Json(serde_json::to_value(&snapshot)?),
+                SqlValue::OptionTimestamp(last_sent_at),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let recipients: serde_json::Value = row.get("recipients");
+        let snapshot: Option<serde_json::Value> = row.get("snapshot");
+
+        Ok(ReportSchedule {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: ReportScheduleBase {
+                network_id: row.get("network_id"),
+                name: row.get("name"),
+                cron_schedule: row.get("cron_schedule"),
+                recipients: serde_json::from_value(recipients)?,
+                is_enabled: row.get("is_enabled"),
+                // Stored as JSON null when there's no snapshot yet
+                snapshot: serde_json::from_value(snapshot.unwrap_or_default())?,
+                last_sent_at: row.get("last_sent_at"),
+            },
+        })
+    }
+}
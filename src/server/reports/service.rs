@@ -0,0 +1,267 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::{RwLock, broadcast};
+use tokio_cron_scheduler::{Job, JobScheduler};
+use uuid::Uuid;
+
+use crate::{
+    daemon::discovery::types::base::DiscoveryPhase,
+    server::{
+        digests::r#impl::changes::InventorySnapshot,
+        discovery::{r#impl::types::RunType, service::DiscoveryService},
+        hosts::service::HostService,
+        networks::service::NetworkService,
+        reports::r#impl::{
+            base::{ReportSchedule, ReportScheduleBase},
+            report::{FailedDiscovery, Report},
+        },
+        services::service::ServiceService,
+        shared::{
+            email::EmailClient,
+            services::traits::CrudService,
+            storage::{
+                filter::EntityFilter,
+                generic::GenericPostgresStorage,
+                traits::{StorableEntity, Storage},
+            },
+        },
+        users::service::UserService,
+    },
+};
+
+/// Periodic network reports, run on their own cron schedules. Jobs only announce that a
+/// report is due; sending is left to the subscriber, which holds the current SMTP settings.
+pub struct ReportService {
+    storage: Arc<GenericPostgresStorage<ReportSchedule>>,
+    network_service: Arc<NetworkService>,
+    host_service: Arc<HostService>,
+    service_service: Arc<ServiceService>,
+    discovery_service: Arc<DiscoveryService>,
+    user_service: Arc<UserService>,
+    scheduler: Arc<RwLock<JobScheduler>>,
+    jobs: RwLock<HashMap<Uuid, Uuid>>, // schedule_id -> job_id mapping
+    due_tx: broadcast::Sender<Uuid>,
+}
+
+#[async_trait]
+impl CrudService<ReportSchedule> for ReportService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<ReportSchedule>> {
+        &self.storage
+    }
+}
+
+impl ReportService {
+    pub async fn new(
+        storage: Arc<GenericPostgresStorage<ReportSchedule>>,
+        network_service: Arc<NetworkService>,
+        host_service: Arc<HostService>,
+        service_service: Arc<ServiceService>,
+        discovery_service: Arc<DiscoveryService>,
+        user_service: Arc<UserService>,
+    ) -> Result<Arc<Self>> {
+        let (due_tx, _rx) = broadcast::channel(100);
+        let scheduler = JobScheduler::new().await?;
+
+        Ok(Arc::new(Self {
+            storage,
+            network_service,
+            host_service,
+            service_service,
+            discovery_service,
+            user_service,
+            scheduler: Arc::new(RwLock::new(scheduler)),
+            jobs: RwLock::new(HashMap::new()),
+            due_tx,
+        }))
+    }
+
+    async fn current_snapshot(&self, network_id: &Uuid) -> Result<InventorySnapshot> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let hosts = self.host_service.get_all(filter.clone()).await?;
+        let services = self.service_service.get_all(filter).await?;
+
+        Ok(InventorySnapshot::new(&hosts, &services))
+    }
+
+    /// Create a schedule with the current inventory as its baseline, so the first report
+    /// only covers changes made after it was set up
+    pub async fn create_schedule(&self, schedule: ReportSchedule) -> Result<ReportSchedule> {
+        let snapshot = self.current_snapshot(&schedule.base.network_id).await?;
+        let schedule = self
+            .storage
+            .create(&ReportSchedule::new(ReportScheduleBase {
+                snapshot: Some(snapshot),
+                last_sent_at: None,
+                ..schedule.base
+            }))
+            .await?;
+
+        self.schedule(&schedule).await?;
+        Ok(schedule)
+    }
+
+    pub async fn update_schedule(&self, mut schedule: ReportSchedule) -> Result<ReportSchedule> {
+        let existing = self
+            .get_by_id(&schedule.id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find report schedule {}", schedule.id))?;
+
+        schedule.base.snapshot = existing.base.snapshot;
+        schedule.base.last_sent_at = existing.base.last_sent_at;
+
+        let updated = self.update(&mut schedule).await?;
+        self.schedule(&updated).await?;
+        Ok(updated)
+    }
+
+    pub async fn delete_schedule(&self, id: &Uuid) -> Result<()> {
+        self.unschedule(id).await;
+        self.delete(id).await
+    }
+
+    /// Add every enabled schedule to the scheduler and start it
+    pub async fn start_scheduler(&self) -> Result<()> {
+        let schedules = self.get_all(EntityFilter::unfiltered()).await?;
+        let mut count = 0;
+
+        for schedule in schedules.iter().filter(|s| s.base.is_enabled) {
+            match self.schedule(schedule).await {
+                Ok(()) => count += 1,
+                Err(e) => tracing::warn!("Failed to schedule {}: {}", schedule, e),
+            }
+        }
+
+        self.scheduler.write().await.start().await?;
+        tracing::info!("Report scheduler started with {} jobs", count);
+
+        Ok(())
+    }
+
+    async fn unschedule(&self, schedule_id: &Uuid) {
+        if let Some(job_id) = self.jobs.write().await.remove(schedule_id) {
+            let _ = self.scheduler.write().await.remove(&job_id).await;
+        }
+    }
+
+    /// Replace the schedule's job, if any, with one for its current cron expression
+    async fn schedule(&self, schedule: &ReportSchedule) -> Result<()> {
+        self.unschedule(&schedule.id).await;
+
+        if !schedule.base.is_enabled {
+            return Ok(());
+        }
+
+        let schedule_id = schedule.id;
+        let due_tx = self.due_tx.clone();
+
+        let job = Job::new_async(schedule.base.cron_schedule.as_str(), move |_uuid, _lock| {
+            let due_tx = due_tx.clone();
+
+            Box::pin(async move {
+                tracing::debug!("Report schedule {} is due", schedule_id);
+                let _ = due_tx.send(schedule_id);
+            })
+        })?;
+
+        let job_id = self.scheduler.write().await.add(job).await?;
+        self.jobs.write().await.insert(schedule_id, job_id);
+
+        tracing::info!(
+            "Scheduled report {} with cron: {}",
+            schedule_id,
+            schedule.base.cron_schedule
+        );
+        Ok(())
+    }
+
+    /// Stream of schedule ids whose report is due
+    pub fn subscribe(&self) -> broadcast::Receiver<Uuid> {
+        self.due_tx.subscribe()
+    }
+
+    /// Report on everything that changed since the schedule's last report
+    pub async fn build_report(&self, schedule: &ReportSchedule) -> Result<Report> {
+        let network_id = schedule.base.network_id;
+        let network = self
+            .network_service
+            .get_by_id(&network_id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find network {}", network_id))?;
+
+        let current = self.current_snapshot(&network_id).await?;
+        let period_start = schedule.period_start();
+
+        let failed_discoveries = self
+            .discovery_service
+            .get_all(EntityFilter::unfiltered().network_ids(&[network_id]))
+            .await?
+            .into_iter()
+            .filter_map(|discovery| match discovery.base.run_type {
+                RunType::Historical { results }
+                    if matches!(results.phase, DiscoveryPhase::Failed)
+                        && results.finished_at.is_some_and(|f| f > period_start) =>
+                {
+                    Some(FailedDiscovery {
+                        name: discovery.base.name,
+                        finished_at: results.finished_at,
+                        error: results.error,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        Ok(Report {
+            network_name: network.base.name,
+            period_start,
+            period_end: Utc::now(),
+            host_count: current.hosts.len(),
+            changes: current.changes_since(&schedule.previous_snapshot()),
+            failed_discoveries,
+        })
+    }
+
+    /// Email a schedule's report to its recipients, or the network owner when it has none,
+    /// then start the next period from now
+    pub async fn send_report(&self, id: &Uuid, email_client: &EmailClient) -> Result<()> {
+        let mut schedule = self
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find report schedule {}", id))?;
+
+        let report = self.build_report(&schedule).await?;
+        let html = report.render_html()?;
+
+        let recipients = if schedule.base.recipients.is_empty() {
+            let network = self
+                .network_service
+                .get_by_id(&schedule.base.network_id)
+                .await?
+                .ok_or_else(|| anyhow!("Could not find network {}", schedule.base.network_id))?;
+
+            self.user_service
+                .get_by_id(&network.base.user_id)
+                .await?
+                .map(|user| vec![user.base.email.to_string()])
+                .unwrap_or_default()
+        } else {
+            schedule.base.recipients.clone()
+        };
+
+        for recipient in &recipients {
+            email_client
+                .send_html(recipient, &report.subject(), html.clone())
+                .await?;
+        }
+
+        // Only advance the period once the report went out, so failed sends are retried
+        schedule.base.snapshot = Some(self.current_snapshot(&schedule.base.network_id).await?);
+        schedule.base.last_sent_at = Some(report.period_end);
+        self.update(&mut schedule).await?;
+
+        tracing::info!("Sent {} to {} recipients", schedule, recipients.len());
+        Ok(())
+    }
+}
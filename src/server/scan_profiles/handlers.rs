@@ -0,0 +1,56 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    scan_profiles::r#impl::base::ScanProfile,
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, create_handler, delete_handler, get_all_handler,
+            get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<ScanProfile>))
+        .route("/", post(create_handler::<ScanProfile>))
+        .route("/{id}", put(update_scan_profile))
+        .route("/{id}", delete(delete_handler::<ScanProfile>))
+        .route("/{id}", get(get_by_id_handler::<ScanProfile>))
+}
+
+async fn update_scan_profile(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<ScanProfile>,
+) -> ApiResult<Json<ApiResponse<ScanProfile>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let service = ScanProfile::get_service(&state);
+    let existing = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Scan profile '{}' not found", id)))?;
+    check_access(&state, &user.0, &existing).await?;
+
+    // Scheduled discoveries refer to the profile, and only run it on daemons in its network
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+    request.base.network_id = existing.base.network_id;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
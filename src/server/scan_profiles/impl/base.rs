@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::discovery::r#impl::scan_settings::{
+    ScanLimits, ScanPorts, ScanSettings, ScanSettingsOverrides,
+};
+
+/// A named set of ports, timeouts and limits that network discoveries can scan with, e.g. a
+/// quick daytime scan and a thorough one overnight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProfileBase {
+    pub name: String,
+    pub network_id: Uuid,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub ports: ScanPorts,
+    /// Applied on top of the network's and daemon's scan settings
+    #[serde(default)]
+    pub settings: ScanSettingsOverrides,
+    /// Applied on top of the network's scan limits
+    #[serde(default)]
+    pub limits: ScanLimits,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProfile {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: ScanProfileBase,
+}
+
+impl Display for ScanProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
+
+impl ScanProfileBase {
+    pub fn apply(&self, settings: &mut ScanSettings) {
+        self.settings.apply(settings);
+        settings.ports = self.ports.clone();
+        self.limits.apply(&mut settings.limits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::hosts::r#impl::ports::{PortBase, TransportProtocol};
+
+    #[test]
+    fn profile_overrides_ports_and_timeouts() {
+        let profile = ScanProfileBase {
+            name: "Web only".to_string(),
+            network_id: Uuid::new_v4(),
+            description: None,
+            ports: ScanPorts::Custom {
+                ports: vec![
+                    PortBase::new_tcp(443).config(),
+                    PortBase::new_tcp(80).config(),
+                    PortBase::new_tcp(443).config(),
+                ],
+            },
+            settings: ScanSettingsOverrides {
+                tcp_timeout_ms: Some(2000),
+                ..Default::default()
+            },
+            limits: ScanLimits {
+                max_bandwidth_kbps: Some(160),
+                ..Default::default()
+            },
+        };
+
+        let mut settings = ScanSettings::default();
+        profile.apply(&mut settings);
+
+        assert_eq!(settings.tcp_timeout_ms, 2000);
+        assert_eq!(settings.tcp_retries, ScanSettings::default().tcp_retries);
+        assert_eq!(
+            settings.ports.numbers(TransportProtocol::Tcp),
+            vec![80, 443]
+        );
+        assert!(settings.ports.numbers(TransportProtocol::Udp).is_empty());
+        assert_eq!(settings.limits.max_probes_per_second(), Some(100.0));
+    }
+}
@@ -0,0 +1,38 @@
+use crate::server::discovery::r#impl::scan_settings::ScanPorts;
+use crate::server::scan_profiles::r#impl::base::ScanProfile;
+use crate::server::scan_profiles::service::ScanProfileService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+use uuid::Uuid;
+
+impl CrudHandlers for ScanProfile {
+    type Service = ScanProfileService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.scan_profile_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Scan profile"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.name.trim().is_empty() {
+            return Err("Scan profile name is required".to_string());
+        }
+        if let ScanPorts::Custom { ports } = &self.base.ports {
+            if ports.is_empty() {
+                return Err("At least one port is required".to_string());
+            }
+            if ports.iter().any(|p| p.number == 0) {
+                return Err("Port numbers must be between 1 and 65535".to_string());
+            }
+        }
+        self.base.settings.validate()?;
+        self.base.limits.validate()?;
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    scan_profiles::r#impl::base::{ScanProfile, ScanProfileBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for ScanProfile {
+    type BaseData = ScanProfileBase;
+
+    fn table_name() -> &'static str {
+        "scan_profiles"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "description"]
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    network_id,
+                    description,
+                    ports,
+                    settings,
+                    limits,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "name",
+                "network_id",
+                "description",
+                "ports",
+                "settings",
+                "limits",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(name),
+                SqlValue::Uuid(network_id),
+                SqlValue::OptionalString(description),
+                SqlValue::Json(serde_json::to_value(&ports)?),
+                SqlValue::Json(serde_json::to_value(&settings)?),
+                SqlValue::Json(serde_json::to_value(limits)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(ScanProfile {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: ScanProfileBase {
+                name: row.get("name"),
+                network_id: row.get("network_id"),
+                description: row.get("description"),
+                ports: serde_json::from_value(row.get("ports"))?,
+                settings: serde_json::from_value(row.get("settings"))?,
+                limits: serde_json::from_value(row.get("limits"))?,
+            },
+        })
+    }
+}
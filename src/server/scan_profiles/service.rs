@@ -0,0 +1,26 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::server::{
+    scan_profiles::r#impl::base::ScanProfile,
+    shared::{services::traits::CrudService, storage::generic::GenericPostgresStorage},
+};
+
+/// Scan profiles that network discoveries refer to. The daemon service resolves a discovery's
+/// profile into the scan settings it sends.
+pub struct ScanProfileService {
+    storage: Arc<GenericPostgresStorage<ScanProfile>>,
+}
+
+#[async_trait]
+impl CrudService<ScanProfile> for ScanProfileService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<ScanProfile>> {
+        &self.storage
+    }
+}
+
+impl ScanProfileService {
+    pub fn new(storage: Arc<GenericPostgresStorage<ScanProfile>>) -> Self {
+        Self { storage }
+    }
+}
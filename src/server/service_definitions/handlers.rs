@@ -0,0 +1,304 @@
+use crate::server::{
+    auth::middleware::{AuthenticatedOperator, AuthenticatedUser},
+    config::AppState,
+    discovery::r#impl::types::{DiscoveryType, HostNamingFallback},
+    hosts::r#impl::{
+        base::Host,
+        interfaces::{Interface, InterfaceBase},
+        ports::PortBase,
+    },
+    service_definitions::r#impl::{
+        api::{PatternTestRequest, PatternTestResult},
+        base::CustomServiceDefinition,
+    },
+    services::{
+        definitions::ServiceDefinitionRegistry,
+        r#impl::{
+            banners::PortBanner,
+            base::{
+                DiscoverySessionServiceMatchParams, ServiceMatchBaselineParams,
+                ServiceMatchServiceParams,
+            },
+            definitions::ServiceDefinition,
+            endpoints::{ApplicationProtocol, Endpoint, EndpointResponse},
+            matching::{DiscoveryContext, discover_host},
+        },
+    },
+    shared::{
+        handlers::traits::{CrudHandlers, get_accessible, get_by_id_handler},
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, traits::StorableEntity},
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    subnets::r#impl::base::{Subnet, SubnetBase},
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+};
+use cidr::IpCidr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler))
+        .route("/", post(create_handler))
+        .route("/{id}", put(update_handler))
+        .route("/{id}", delete(delete_handler))
+        .route("/{id}", get(get_by_id_handler::<CustomServiceDefinition>))
+        .route("/{id}/test", post(test_pattern_handler))
+}
+
+/// Definitions added through the API. Built-in definitions and ones loaded from files are listed
+/// in `/api/metadata`. Custom definitions apply to every network, so only operators manage them.
+async fn get_all_handler(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+) -> ApiResult<Json<ApiResponse<Vec<CustomServiceDefinition>>>> {
+    let service = CustomServiceDefinition::get_service(&state);
+    let definitions = service.get_all(EntityFilter::unfiltered()).await?;
+
+    Ok(Json(ApiResponse::success(definitions)))
+}
+
+async fn create_handler(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+    Json(mut request): Json<CustomServiceDefinition>,
+) -> ApiResult<Json<ApiResponse<CustomServiceDefinition>>> {
+    request.base.name = request.base.name.trim().to_string();
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let name = request.base.name.as_str();
+    if ServiceDefinitionRegistry::is_builtin(name) {
+        return Err(ApiError::conflict(&format!(
+            "'{}' is a built-in service definition",
+            name
+        )));
+    }
+
+    let service = CustomServiceDefinition::get_service(&state);
+    if service.get_by_name(name).await?.is_some() {
+        return Err(ApiError::conflict(&format!(
+            "Custom service definition '{}' already exists",
+            name
+        )));
+    }
+
+    let created = service.create(request).await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+async fn update_handler(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<CustomServiceDefinition>,
+) -> ApiResult<Json<ApiResponse<CustomServiceDefinition>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let service = CustomServiceDefinition::get_service(&state);
+    let existing = service.get_by_id(&id).await?.ok_or_else(|| {
+        ApiError::not_found(format!("Custom service definition '{}' not found", id))
+    })?;
+
+    // Services refer to their definition by name
+    if request.base.name.trim() != existing.base.name {
+        return Err(ApiError::bad_request(
+            "Custom service definitions can't be renamed",
+        ));
+    }
+
+    request.id = existing.id;
+    request.created_at = existing.created_at;
+    request.base.name = existing.base.name;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+async fn delete_handler(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    let service = CustomServiceDefinition::get_service(&state);
+    let existing = service.get_by_id(&id).await?.ok_or_else(|| {
+        ApiError::not_found(format!("Custom service definition '{}' not found", id))
+    })?;
+
+    if service.is_in_use(&existing.base.name).await? {
+        return Err(ApiError::conflict(&format!(
+            "Custom service definition '{}' is used by existing services",
+            existing.base.name
+        )));
+    }
+
+    service.delete(&id).await?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+/// Run any service definition's pattern, built-in or custom, against a host or raw scan data.
+/// Gateways aren't known to the server, so `is_gateway` never matches here.
+async fn test_pattern_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<String>,
+    Json(request): Json<PatternTestRequest>,
+) -> ApiResult<Json<ApiResponse<PatternTestResult>>> {
+    let service_definition = ServiceDefinitionRegistry::find_by_id(&id)
+        .ok_or_else(|| ApiError::not_found(format!("Service definition '{}' not found", id)))?;
+
+    let mut all_ports: Vec<PortBase> = request
+        .ports
+        .iter()
+        .map(|p| parse_port(p))
+        .collect::<Result<_, _>>()?;
+
+    let (subnet, interface) = match (request.host_id, request.ip) {
+        (Some(host_id), _) => {
+            let host = get_accessible::<Host>(&state, &user.0, &host_id).await?;
+            let interface = host
+                .base
+                .interfaces
+                .first()
+                .cloned()
+                .ok_or_else(|| ApiError::bad_request("Host has no interfaces"))?;
+            let subnet = state
+                .services
+                .subnet_service
+                .get_by_id(&interface.base.subnet_id)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::not_found(format!("Subnet '{}' not found", interface.base.subnet_id))
+                })?;
+
+            all_ports.extend(host.base.ports.iter().map(|p| p.base));
+            (subnet, interface)
+        }
+        (None, Some(ip)) => {
+            let subnet = Subnet::new(SubnetBase {
+                cidr: IpCidr::new_host(ip),
+                ..SubnetBase::default()
+            });
+            let interface = Interface::new(InterfaceBase {
+                subnet_id: subnet.id,
+                ip_address: ip,
+                mac_address: None,
+                name: None,
+            });
+            (subnet, interface)
+        }
+        (None, None) => return Err(ApiError::bad_request("Either host_id or ip is required")),
+    };
+
+    all_ports.sort_by_key(|p| (p.number(), p.protocol()));
+    all_ports.dedup();
+
+    let endpoint_responses: Vec<EndpointResponse> = request
+        .endpoint_responses
+        .iter()
+        .map(|r| {
+            Ok(EndpointResponse {
+                endpoint: Endpoint {
+                    protocol: if r.https {
+                        ApplicationProtocol::Https
+                    } else {
+                        ApplicationProtocol::Http
+                    },
+                    ip: Some(interface.base.ip_address),
+                    port_base: parse_port(&r.port)?,
+                    path: r.path.clone(),
+                },
+                response: r.response.clone(),
+            })
+        })
+        .collect::<Result<_, ApiError>>()?;
+
+    let banners: Vec<PortBanner> = request
+        .banners
+        .iter()
+        .map(|b| {
+            Ok(PortBanner {
+                port_base: parse_port(&b.port)?,
+                banner: b.banner.clone(),
+            })
+        })
+        .collect::<Result<_, ApiError>>()?;
+
+    let baseline_params = ServiceMatchBaselineParams {
+        subnet: &subnet,
+        interface: &interface,
+        all_ports: &all_ports,
+        endpoint_responses: &endpoint_responses,
+        banners: &banners,
+        virtualization: &None,
+        upnp_device: &None,
+    };
+
+    let discovery_type = DiscoveryType::Network {
+        subnet_ids: None,
+        host_naming_fallback: HostNamingFallback::default(),
+        scan_profile_id: None,
+        parallel_daemon_ids: Vec::new(),
+        shard: None,
+    };
+    let daemon_id = Uuid::nil();
+    let host_id = Uuid::nil();
+    let network_id = subnet.base.network_id;
+    let matched_services = Vec::new();
+
+    let params = DiscoverySessionServiceMatchParams {
+        host_id: &host_id,
+        gateway_ips: &[],
+        daemon_id: &daemon_id,
+        network_id: &network_id,
+        discovery_type: &discovery_type,
+        baseline_params: &baseline_params,
+        service_params: ServiceMatchServiceParams {
+            service_definition: service_definition.clone(),
+            matched_services: &matched_services,
+            unbound_ports: &all_ports,
+        },
+    };
+
+    let pattern = service_definition.discovery_pattern();
+    let details = pattern.matches(&params).ok().map(|result| result.details);
+    let evaluation = pattern.evaluate(&params);
+
+    let (_, services) = discover_host(
+        &baseline_params,
+        None,
+        HostNamingFallback::default(),
+        &DiscoveryContext {
+            daemon_id: &daemon_id,
+            network_id: &network_id,
+            gateway_ips: &[],
+            discovery_type: &discovery_type,
+        },
+    );
+
+    Ok(Json(ApiResponse::success(PatternTestResult {
+        matched: details.is_some(),
+        details,
+        evaluation,
+        matched_services: services
+            .iter()
+            .map(|s| s.base.service_definition.name().to_string())
+            .collect(),
+    })))
+}
+
+fn parse_port(port: &str) -> Result<PortBase, ApiError> {
+    port.parse()
+        .map_err(|e| ApiError::bad_request(&format!("Invalid port '{}': {}", port, e)))
+}
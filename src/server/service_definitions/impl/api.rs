@@ -0,0 +1,55 @@
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::services::r#impl::patterns::{MatchDetails, PatternEvaluation};
+
+/// What to test a service definition's pattern against: a host, raw scan data, or a host plus
+/// data that isn't stored with it, such as endpoint responses
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatternTestRequest {
+    /// Host whose interface, subnet and open ports are used
+    pub host_id: Option<Uuid>,
+    /// Address to test when no host is given
+    pub ip: Option<IpAddr>,
+    /// Open ports, e.g. `8096/tcp`
+    #[serde(default)]
+    pub ports: Vec<String>,
+    #[serde(default)]
+    pub endpoint_responses: Vec<PatternTestEndpointResponse>,
+    #[serde(default)]
+    pub banners: Vec<PatternTestBanner>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatternTestEndpointResponse {
+    pub port: String,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default)]
+    pub https: bool,
+    pub response: String,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatternTestBanner {
+    pub port: String,
+    pub banner: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternTestResult {
+    pub matched: bool,
+    /// What would be stored on the service, when the pattern matched
+    pub details: Option<MatchDetails>,
+    /// Every node of the pattern, whether or not the whole pattern matched
+    pub evaluation: PatternEvaluation,
+    /// Services discovery would create from the same data, best first. A definition whose
+    /// pattern matches may still lose its ports to one matched before it.
+    pub matched_services: Vec<String>,
+}
@@ -0,0 +1,59 @@
+use std::fmt::Display;
+
+use anyhow::{Error, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::services::r#impl::{categories::ServiceCategory, patterns::Pattern};
+
+/// Service definition written by a user rather than compiled in, so in-house apps can be
+/// matched during discovery
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CustomServiceDefinitionBase {
+    /// Also the definition's id, so it must not clash with a built-in definition. < 25 characters.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_category")]
+    pub category: ServiceCategory,
+    pub logo_url: Option<String>,
+    /// Textual discovery pattern, e.g. `port(8443) AND endpoint(8443, "/", "Acme Portal")`;
+    /// see [`crate::server::services::r#impl::pattern_parser::parse_pattern`]
+    pub discovery_pattern: String,
+}
+
+fn default_category() -> ServiceCategory {
+    ServiceCategory::Custom
+}
+
+impl CustomServiceDefinitionBase {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.name.trim().is_empty() || self.name.len() >= 25 {
+            bail!("Name must be between 1 and 24 characters");
+        }
+        if self.description.len() >= 100 {
+            bail!("Description must be less than 100 characters");
+        }
+        if let Err(e) = Pattern::parse(&self.discovery_pattern) {
+            bail!("Invalid discovery pattern: {}", e);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomServiceDefinition {
+    pub id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: CustomServiceDefinitionBase,
+}
+
+impl Display for CustomServiceDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
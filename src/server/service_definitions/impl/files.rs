@@ -0,0 +1,94 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::server::service_definitions::r#impl::base::CustomServiceDefinitionBase;
+
+/// Contents of a definitions file: a `services` list in YAML, or `[[services]]` tables in TOML
+#[derive(Debug, Deserialize)]
+struct DefinitionsFile {
+    #[serde(default)]
+    services: Vec<CustomServiceDefinitionBase>,
+}
+
+/// Definitions from every `.yaml`, `.yml` and `.toml` file in a directory
+pub fn load_dir(dir: &Path) -> Result<Vec<CustomServiceDefinitionBase>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read service definitions from {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+
+    // Later files replace earlier definitions with the same name, so make that deterministic
+    paths.sort();
+
+    let mut definitions = Vec::new();
+    for path in paths {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            continue;
+        };
+        if !matches!(extension, "yaml" | "yml" | "toml") {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let file = parse(&contents, extension)
+            .with_context(|| format!("Invalid service definitions file {}", path.display()))?;
+        definitions.extend(file);
+    }
+
+    Ok(definitions)
+}
+
+fn parse(contents: &str, extension: &str) -> Result<Vec<CustomServiceDefinitionBase>> {
+    let file: DefinitionsFile = match extension {
+        "yaml" | "yml" => serde_yaml::from_str(contents)?,
+        "toml" => config::Config::builder()
+            .add_source(config::File::from_str(contents, config::FileFormat::Toml))
+            .build()?
+            .try_deserialize()?,
+        _ => bail!("Unsupported service definitions format '{}'", extension),
+    };
+
+    for definition in &file.services {
+        definition
+            .validate()
+            .with_context(|| format!("Invalid service definition '{}'", definition.name))?;
+    }
+
+    Ok(file.services)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::services::r#impl::categories::ServiceCategory;
+
+    #[test]
+    fn parses_yaml_and_toml_definitions() {
+        let yaml = r#"
+services:
+  - name: Acme Portal
+    description: Internal customer portal
+    category: Web
+    discovery_pattern: 'endpoint(8443, "/", "Acme Portal")'
+"#;
+        let toml = r#"
+[[services]]
+name = "Build Agent"
+logo_url = "/logos/build-agent.svg"
+discovery_pattern = "port(9000) AND banner(9000, 'agent-v2')"
+"#;
+
+        let from_yaml = parse(yaml, "yaml").unwrap();
+        assert_eq!(from_yaml.len(), 1);
+        assert_eq!(from_yaml[0].category, ServiceCategory::Web);
+
+        let from_toml = parse(toml, "toml").unwrap();
+        assert_eq!(from_toml[0].name, "Build Agent");
+        assert_eq!(from_toml[0].category, ServiceCategory::Custom);
+
+        let invalid = "services:\n  - name: Broken\n    discovery_pattern: 'port(80) AND'\n";
+        assert!(parse(invalid, "yml").is_err());
+    }
+}
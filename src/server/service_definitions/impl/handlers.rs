@@ -0,0 +1,15 @@
+use crate::server::service_definitions::r#impl::base::CustomServiceDefinition;
+use crate::server::service_definitions::service::CustomServiceDefinitionService;
+use crate::server::shared::handlers::traits::CrudHandlers;
+
+impl CrudHandlers for CustomServiceDefinition {
+    type Service = CustomServiceDefinitionService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.custom_service_definition_service
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.base.validate().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod api;
+pub mod base;
+pub mod files;
+pub mod handlers;
+pub mod plugins;
+pub mod runtime;
+pub mod storage;
@@ -0,0 +1,416 @@
+//! Service definitions whose matching logic lives in a WASM module, for detectors that need more
+//! than a textual pattern can express (what `Pattern::Custom` does for compiled-in definitions).
+//!
+//! A plugin module exports:
+//! - `memory`
+//! - `alloc(len: i32) -> i32`, returning a buffer the host writes input into
+//! - `definition() -> i64`, a JSON [`PluginDefinition`] at `ptr << 32 | len`
+//! - `matches(ptr: i32, len: i32) -> i32`, given a JSON [`PluginMatchInput`]; non-zero is a match
+//!
+//! Modules get no imports, so they can't reach the filesystem, network or clock, and every call
+//! runs in a fresh instance with bounded fuel and memory. A plugin's `discovery_pattern` decides
+//! which ports and endpoints daemons probe and must match before `matches` is called.
+
+use std::{fs, net::IpAddr, path::Path, sync::Arc};
+
+use anyhow::{Context, Error, Result, anyhow, bail};
+use base64ct::{Base64, Encoding};
+use mac_address::MacAddress;
+use serde::{Deserialize, Serialize};
+use wasmi::{Config, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::server::{
+    service_definitions::r#impl::base::CustomServiceDefinitionBase,
+    services::r#impl::{
+        base::{DiscoverySessionServiceMatchParams, ServiceMatchBaselineParams},
+        patterns::MatchConfidence,
+        upnp::UpnpDevice,
+        virtualization::ServiceVirtualization,
+    },
+    subnets::r#impl::types::SubnetType,
+};
+
+/// Instructions a single call may execute before it is aborted
+const FUEL_PER_CALL: u64 = 50_000_000;
+
+/// Linear memory a plugin instance may grow to
+const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Largest module accepted, before compilation
+const MAX_MODULE_BYTES: usize = 8 * 1024 * 1024;
+
+/// What a plugin's `definition()` export returns
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDefinition {
+    #[serde(flatten)]
+    pub base: CustomServiceDefinitionBase,
+    /// Confidence of a match reported by the plugin
+    #[serde(default = "default_confidence")]
+    pub confidence: MatchConfidence,
+}
+
+fn default_confidence() -> MatchConfidence {
+    MatchConfidence::Medium
+}
+
+/// A plugin module as sent to daemons with discovery requests, so they can match it too
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PluginModule {
+    /// File stem of the module on the server, used in logs
+    pub name: String,
+    /// Base64-encoded module
+    pub wasm: String,
+}
+
+/// What a plugin's `matches` export is given about a host
+#[derive(Debug, Serialize)]
+pub struct PluginMatchInput<'a> {
+    pub ip: IpAddr,
+    pub mac_address: Option<MacAddress>,
+    pub subnet_type: &'a SubnetType,
+    /// Open ports no other service has bound yet, e.g. `8443/tcp`
+    pub unbound_ports: Vec<String>,
+    pub endpoint_responses: Vec<PluginEndpointResponse<'a>>,
+    pub banners: Vec<PluginBanner<'a>>,
+    pub upnp_device: Option<&'a UpnpDevice>,
+    pub docker_container: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PluginEndpointResponse<'a> {
+    pub port: String,
+    pub path: &'a str,
+    pub response: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PluginBanner<'a> {
+    pub port: String,
+    pub banner: &'a str,
+}
+
+impl<'a> PluginMatchInput<'a> {
+    pub fn new(params: &'a DiscoverySessionServiceMatchParams) -> Self {
+        let ServiceMatchBaselineParams {
+            subnet,
+            interface,
+            endpoint_responses,
+            banners,
+            virtualization,
+            upnp_device,
+            ..
+        } = params.baseline_params;
+
+        Self {
+            ip: interface.base.ip_address,
+            mac_address: interface.base.mac_address,
+            subnet_type: &subnet.base.subnet_type,
+            unbound_ports: params
+                .service_params
+                .unbound_ports
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            endpoint_responses: endpoint_responses
+                .iter()
+                .map(|r| PluginEndpointResponse {
+                    port: r.endpoint.port_base.to_string(),
+                    path: &r.endpoint.path,
+                    response: &r.response,
+                })
+                .collect(),
+            banners: banners
+                .iter()
+                .map(|b| PluginBanner {
+                    port: b.port_base.to_string(),
+                    banner: &b.banner,
+                })
+                .collect(),
+            upnp_device: upnp_device.as_ref(),
+            docker_container: matches!(virtualization, Some(ServiceVirtualization::Docker(..))),
+        }
+    }
+}
+
+/// A compiled, validated plugin module
+pub struct WasmPlugin {
+    engine: Engine,
+    module: Module,
+    source: PluginModule,
+    definition: PluginDefinition,
+}
+
+impl WasmPlugin {
+    pub fn new(source: PluginModule) -> Result<Self> {
+        let wasm = Base64::decode_vec(&source.wasm)
+            .map_err(|e| anyhow!("Plugin {} is not valid base64: {}", source.name, e))?;
+        if wasm.len() > MAX_MODULE_BYTES {
+            bail!(
+                "Plugin {} is larger than {} bytes",
+                source.name,
+                MAX_MODULE_BYTES
+            );
+        }
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &wasm[..])
+            .map_err(|e| anyhow!("Plugin {} is not a valid module: {}", source.name, e))?;
+
+        let (mut store, instance) = instantiate(&engine, &module, &source.name)?;
+        let definition = instance
+            .get_typed_func::<(), u64>(&store, "definition")
+            .map_err(|_| anyhow!("Plugin {} does not export definition()", source.name))?;
+        // Checked up front so a broken plugin fails at load rather than on every match
+        instance
+            .get_typed_func::<(u32, u32), u32>(&store, "matches")
+            .map_err(|_| anyhow!("Plugin {} does not export matches()", source.name))?;
+
+        let packed = definition.call(&mut store, ())?;
+        let definition: PluginDefinition = serde_json::from_slice(&read_packed(
+            &store, &instance, packed,
+        )?)
+        .with_context(|| format!("Plugin {} returned an invalid definition", source.name))?;
+        definition
+            .base
+            .validate()
+            .with_context(|| format!("Plugin {} returned an invalid definition", source.name))?;
+
+        Ok(Self {
+            engine,
+            module,
+            source,
+            definition,
+        })
+    }
+
+    /// Read a `.wasm` file into a module that can be shipped to daemons
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let wasm = fs::read(path)?;
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("plugin")
+            .to_string();
+
+        Self::new(PluginModule {
+            name,
+            wasm: Base64::encode_string(&wasm),
+        })
+    }
+
+    /// Every `.wasm` file in a directory
+    pub fn load_dir(dir: &Path) -> Result<Vec<Arc<Self>>> {
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read plugins from {}", dir.display()))?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        paths.sort();
+
+        paths
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+            .map(|path| Self::load_file(path).map(Arc::new))
+            .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.source.name
+    }
+
+    pub fn source(&self) -> &PluginModule {
+        &self.source
+    }
+
+    pub fn definition(&self) -> &PluginDefinition {
+        &self.definition
+    }
+
+    /// Run the plugin's `matches` export against a host
+    pub fn matches(&self, params: &DiscoverySessionServiceMatchParams) -> Result<bool> {
+        self.matches_input(&serde_json::to_vec(&PluginMatchInput::new(params))?)
+    }
+
+    /// Run `matches` on a serialized [`PluginMatchInput`]
+    fn matches_input(&self, input: &[u8]) -> Result<bool> {
+        let (mut store, instance) = self.instantiate()?;
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| anyhow!("Plugin {} does not export memory", self.name()))?;
+        let alloc = instance.get_typed_func::<u32, u32>(&store, "alloc")?;
+        let matches = instance.get_typed_func::<(u32, u32), u32>(&store, "matches")?;
+
+        let len = u32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory
+            .write(&mut store, ptr as usize, input)
+            .map_err(|e| anyhow!("Plugin {} returned an invalid buffer: {}", self.name(), e))?;
+
+        Ok(matches.call(&mut store, (ptr, len))? != 0)
+    }
+
+    fn instantiate(&self) -> Result<(Store<StoreLimits>, Instance)> {
+        instantiate(&self.engine, &self.module, self.name())
+    }
+}
+
+/// A fresh instance with bounded fuel and memory. No host functions are linked, so modules that
+/// import anything fail to instantiate.
+fn instantiate(
+    engine: &Engine,
+    module: &Module,
+    name: &str,
+) -> Result<(Store<StoreLimits>, Instance)> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY_BYTES)
+        .instances(1)
+        .build();
+    let mut store = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(FUEL_PER_CALL)?;
+
+    let linker = Linker::<StoreLimits>::new(engine);
+    let instance = linker
+        .instantiate(&mut store, module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| anyhow!("Failed to instantiate plugin {}: {}", name, e))?;
+
+    Ok((store, instance))
+}
+
+/// Bytes at a `ptr << 32 | len` returned by a plugin
+fn read_packed(store: &Store<StoreLimits>, instance: &Instance, packed: u64) -> Result<Vec<u8>> {
+    let memory = instance
+        .get_memory(store, "memory")
+        .ok_or_else(|| anyhow!("Plugin does not export memory"))?;
+
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    // Checked before allocating, so a bogus length can't make the server allocate 4GiB
+    if ptr
+        .checked_add(len)
+        .is_none_or(|end| end > memory.data_size(store))
+    {
+        bail!("Plugin returned a buffer outside its memory");
+    }
+
+    let mut buf = vec![0u8; len];
+    memory
+        .read(store, ptr, &mut buf)
+        .map_err(|e| Error::msg(format!("Plugin returned an invalid buffer: {}", e)))?;
+
+    Ok(buf)
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WasmPlugin({})", self.source.name)
+    }
+}
+
+impl PartialEq for WasmPlugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for WasmPlugin {}
+
+impl std::hash::Hash for WasmPlugin {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFINITION: &str = r#"{"name":"Acme Portal","discovery_pattern":"port(8443)"}"#;
+
+    /// Matches when the input contains "acme", read as a little-endian i32
+    const MATCHES_ACME: &str = r#"
+        (local $end i32)
+        (local.set $end (i32.sub (i32.add (local.get $ptr) (local.get $len)) (i32.const 4)))
+        (block $done
+          (loop $scan
+            (br_if $done (i32.gt_s (local.get $ptr) (local.get $end)))
+            (if (i32.eq (i32.load (local.get $ptr)) (i32.const 0x656d6361))
+              (then (return (i32.const 1))))
+            (local.set $ptr (i32.add (local.get $ptr) (i32.const 1)))
+            (br $scan)))
+        (i32.const 0)"#;
+
+    /// A plugin with `DEFINITION` at offset 0 and a bump allocator above it. `definition()`
+    /// returns `packed`, and `matches` runs `matches_body`.
+    fn plugin(imports: &str, packed: u64, matches_body: &str) -> Result<WasmPlugin> {
+        let wat = format!(
+            r#"(module
+              {imports}
+              (memory (export "memory") 1)
+              (data (i32.const 0) "{data}")
+              (global $next (mut i32) (i32.const 1024))
+              (func (export "alloc") (param $len i32) (result i32)
+                (global.get $next)
+                (global.set $next (i32.add (global.get $next) (local.get $len))))
+              (func (export "definition") (result i64)
+                (i64.const {packed}))
+              (func (export "matches") (param $ptr i32) (param $len i32) (result i32)
+                {matches_body}))"#,
+            data = DEFINITION.replace('"', "\\\""),
+        );
+
+        WasmPlugin::new(PluginModule {
+            name: "acme".to_string(),
+            wasm: Base64::encode_string(&wat::parse_str(&wat).unwrap()),
+        })
+    }
+
+    fn acme_plugin() -> WasmPlugin {
+        plugin("", DEFINITION.len() as u64, MATCHES_ACME).unwrap()
+    }
+
+    #[test]
+    fn loads_definition_and_matches() {
+        let plugin = acme_plugin();
+        assert_eq!(plugin.definition().base.name, "Acme Portal");
+        assert_eq!(plugin.definition().confidence, MatchConfidence::Medium);
+
+        assert!(
+            plugin
+                .matches_input(br#"{"banners":[{"port":"8443/tcp","banner":"acme/2.1"}]}"#)
+                .unwrap()
+        );
+        assert!(!plugin.matches_input(br#"{"banners":[]}"#).unwrap());
+        // Each call gets a fresh instance, so the allocator starts over
+        assert!(plugin.matches_input(b"acme").unwrap());
+    }
+
+    #[test]
+    fn aborts_calls_that_run_out_of_fuel() {
+        let plugin = plugin(
+            "",
+            DEFINITION.len() as u64,
+            "(loop $spin (br $spin)) (i32.const 0)",
+        )
+        .unwrap();
+
+        assert!(plugin.matches_input(b"acme").is_err());
+    }
+
+    #[test]
+    fn rejects_modules_with_imports() {
+        let imports = r#"(import "env" "log" (func $log (param i32 i32)))"#;
+        let err = plugin(imports, DEFINITION.len() as u64, "(i32.const 1)").unwrap_err();
+        assert!(err.to_string().contains("Failed to instantiate"));
+    }
+
+    #[test]
+    fn rejects_definitions_outside_memory() {
+        // 4GiB at offset 0, and a valid length at an offset past the single 64KiB page
+        for packed in [0xffff_ffff, (0x1_0000 << 32) | DEFINITION.len() as u64] {
+            let err = plugin("", packed, "(i32.const 1)").unwrap_err();
+            assert!(err.to_string().contains("outside its memory"), "{}", err);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use anyhow::Error;
+
+use crate::server::{
+    service_definitions::r#impl::{base::CustomServiceDefinitionBase, plugins::WasmPlugin},
+    services::r#impl::{
+        categories::ServiceCategory, definitions::ServiceDefinition, patterns::Pattern,
+    },
+};
+
+/// Strings of runtime definitions, leaked once each so definitions can hand out `&'static str`
+/// like compiled-in ones. Daemons receive the same definitions with every discovery request, so
+/// interning keeps that from leaking on every run.
+static INTERNED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+fn intern(s: &str) -> &'static str {
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.get(s) {
+        return existing;
+    }
+
+    let leaked: &'static str = Box::leak(s.to_string().into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+/// A [`CustomServiceDefinitionBase`] registered with the
+/// [`ServiceDefinitionRegistry`](crate::server::services::definitions::ServiceDefinitionRegistry)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuntimeServiceDefinition {
+    name: &'static str,
+    description: &'static str,
+    category: ServiceCategory,
+    logo_url: &'static str,
+    discovery_pattern: &'static str,
+    /// Matching logic the pattern is combined with, for definitions from a plugin
+    plugin: Option<Arc<WasmPlugin>>,
+}
+
+impl RuntimeServiceDefinition {
+    pub fn new(base: &CustomServiceDefinitionBase) -> Result<Self, Error> {
+        base.validate()?;
+
+        Ok(Self {
+            name: intern(&base.name),
+            description: intern(&base.description),
+            category: base.category,
+            logo_url: intern(base.logo_url.as_deref().unwrap_or_default()),
+            discovery_pattern: intern(&base.discovery_pattern),
+            plugin: None,
+        })
+    }
+
+    pub fn from_plugin(plugin: Arc<WasmPlugin>) -> Result<Self, Error> {
+        Ok(Self {
+            plugin: Some(plugin.clone()),
+            ..Self::new(&plugin.definition().base)?
+        })
+    }
+
+    pub fn plugin(&self) -> Option<&Arc<WasmPlugin>> {
+        self.plugin.as_ref()
+    }
+}
+
+impl From<&RuntimeServiceDefinition> for CustomServiceDefinitionBase {
+    fn from(definition: &RuntimeServiceDefinition) -> Self {
+        Self {
+            name: definition.name.to_string(),
+            description: definition.description.to_string(),
+            category: definition.category,
+            logo_url: Some(definition.logo_url.to_string()).filter(|url| !url.is_empty()),
+            discovery_pattern: definition.discovery_pattern.to_string(),
+        }
+    }
+}
+
+impl ServiceDefinition for RuntimeServiceDefinition {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    fn category(&self) -> ServiceCategory {
+        self.category
+    }
+    fn discovery_pattern(&self) -> Pattern<'_> {
+        // Validated in new()
+        let pattern = Pattern::parse(self.discovery_pattern).unwrap_or(Pattern::None);
+
+        match &self.plugin {
+            Some(plugin) => Pattern::AllOf(vec![pattern, Pattern::Plugin(plugin)]),
+            None => pattern,
+        }
+    }
+    fn logo_url(&self) -> &'static str {
+        self.logo_url
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    service_definitions::r#impl::base::{CustomServiceDefinition, CustomServiceDefinitionBase},
+    shared::storage::traits::{SqlValue, StorableEntity},
+};
+
+impl StorableEntity for CustomServiceDefinition {
+    type BaseData = CustomServiceDefinitionBase;
+
+    fn table_name() -> &'static str {
+        "custom_service_definitions"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    description,
+                    category,
+                    logo_url,
+                    discovery_pattern,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "name",
+                "description",
+                "category",
+                "logo_url",
+                "discovery_pattern",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(name),
+                SqlValue::String(description),
+                SqlValue::String(category.to_string()),
+                SqlValue::OptionalString(logo_url),
+                SqlValue::String(discovery_pattern),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(CustomServiceDefinition {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: CustomServiceDefinitionBase {
+                name: row.get("name"),
+                description: row.get("description"),
+                category: serde_json::from_value(serde_json::Value::String(row.get("category")))?,
+                logo_url: row.get("logo_url"),
+                discovery_pattern: row.get("discovery_pattern"),
+            },
+        })
+    }
+}
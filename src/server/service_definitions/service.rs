@@ -0,0 +1,112 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    service_definitions::r#impl::{
+        base::CustomServiceDefinition, runtime::RuntimeServiceDefinition,
+    },
+    services::{definitions::ServiceDefinitionRegistry, service::ServiceService},
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+        types::metadata::HasId,
+    },
+};
+
+pub struct CustomServiceDefinitionService {
+    storage: Arc<GenericPostgresStorage<CustomServiceDefinition>>,
+    service_service: Arc<ServiceService>,
+}
+
+#[async_trait]
+impl CrudService<CustomServiceDefinition> for CustomServiceDefinitionService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<CustomServiceDefinition>> {
+        &self.storage
+    }
+}
+
+impl CustomServiceDefinitionService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<CustomServiceDefinition>>,
+        service_service: Arc<ServiceService>,
+    ) -> Self {
+        Self {
+            storage,
+            service_service,
+        }
+    }
+
+    /// Register every stored definition with the registry. Stored definitions replace ones
+    /// loaded from files with the same name.
+    pub async fn register_stored(&self) -> Result<usize> {
+        let definitions = self.storage.get_all(EntityFilter::unfiltered()).await?;
+
+        for definition in &definitions {
+            ServiceDefinitionRegistry::register(RuntimeServiceDefinition::new(&definition.base)?)?;
+        }
+
+        Ok(definitions.len())
+    }
+
+    pub async fn get_by_name(&self, name: &str) -> Result<Option<CustomServiceDefinition>> {
+        let definitions = self.storage.get_all(EntityFilter::unfiltered()).await?;
+        Ok(definitions.into_iter().find(|d| d.base.name == name))
+    }
+
+    /// Whether any service was matched to or created with the definition
+    pub async fn is_in_use(&self, name: &str) -> Result<bool> {
+        let services = self
+            .service_service
+            .get_all(EntityFilter::unfiltered())
+            .await?;
+
+        Ok(services
+            .iter()
+            .any(|s| s.base.service_definition.id() == name))
+    }
+
+    pub async fn create(
+        &self,
+        definition: CustomServiceDefinition,
+    ) -> Result<CustomServiceDefinition> {
+        let runtime = RuntimeServiceDefinition::new(&definition.base)?;
+
+        let created = self
+            .storage
+            .create(&CustomServiceDefinition::new(definition.base))
+            .await?;
+        ServiceDefinitionRegistry::register(runtime)?;
+
+        Ok(created)
+    }
+
+    pub async fn update(
+        &self,
+        definition: &mut CustomServiceDefinition,
+    ) -> Result<CustomServiceDefinition> {
+        let runtime = RuntimeServiceDefinition::new(&definition.base)?;
+
+        let updated = self.storage.update(definition).await?;
+        ServiceDefinitionRegistry::register(runtime)?;
+
+        Ok(updated)
+    }
+
+    pub async fn delete(&self, id: &Uuid) -> Result<()> {
+        let definition = self
+            .get_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find custom service definition {}", id))?;
+
+        self.storage.delete(id).await?;
+        ServiceDefinitionRegistry::unregister(&definition.base.name);
+
+        Ok(())
+    }
+}
@@ -22,6 +22,10 @@ impl ServiceDefinition for Cloudflared {
         Pattern::Endpoint(PortBase::Http, "/metrics", "cloudflared")
     }
 
+    fn metrics_path(&self) -> Option<&'static str> {
+        Some("/metrics")
+    }
+
     fn logo_url(&self) -> &'static str {
         "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/cloudflare.svg"
     }
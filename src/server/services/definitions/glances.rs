@@ -1,30 +1,13 @@
 use crate::server::hosts::r#impl::ports::PortBase;
-use crate::server::services::definitions::{ServiceDefinitionFactory, create_service};
-use crate::server::services::r#impl::categories::ServiceCategory;
-use crate::server::services::r#impl::definitions::ServiceDefinition;
+use crate::server::services::definitions::service_definition;
 use crate::server::services::r#impl::patterns::Pattern;
 
-#[derive(Default, Clone, Eq, PartialEq, Hash)]
-pub struct Glances;
-
-impl ServiceDefinition for Glances {
-    fn name(&self) -> &'static str {
-        "Glances"
-    }
-    fn description(&self) -> &'static str {
-        "An open-source system cross-platform monitoring tool."
-    }
-    fn category(&self) -> ServiceCategory {
-        ServiceCategory::Monitoring
-    }
-
-    fn discovery_pattern(&self) -> Pattern<'_> {
-        Pattern::Endpoint(PortBase::new_tcp(61208), "/", "Glances")
-    }
-
-    fn logo_url(&self) -> &'static str {
-        "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/glances.svg"
+service_definition! {
+    pub struct Glances {
+        name: "Glances",
+        description: "An open-source system cross-platform monitoring tool.",
+        category: Monitoring,
+        discovery_pattern: Pattern::Endpoint(PortBase::new_tcp(61208), "/", "Glances"),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/glances.svg",
     }
 }
-
-inventory::submit!(ServiceDefinitionFactory::new(create_service::<Glances>));
@@ -1,30 +1,13 @@
 use crate::server::hosts::r#impl::ports::PortBase;
-use crate::server::services::definitions::{ServiceDefinitionFactory, create_service};
-use crate::server::services::r#impl::categories::ServiceCategory;
-use crate::server::services::r#impl::definitions::ServiceDefinition;
+use crate::server::services::definitions::service_definition;
 use crate::server::services::r#impl::patterns::Pattern;
 
-#[derive(Default, Clone, Eq, PartialEq, Hash)]
-pub struct Grafana;
-
-impl ServiceDefinition for Grafana {
-    fn name(&self) -> &'static str {
-        "Grafana"
-    }
-    fn description(&self) -> &'static str {
-        "Analytics and monitoring visualization platform"
-    }
-    fn category(&self) -> ServiceCategory {
-        ServiceCategory::Monitoring
-    }
-
-    fn discovery_pattern(&self) -> Pattern<'_> {
-        Pattern::Endpoint(PortBase::Http, "/", "grafana")
-    }
-
-    fn logo_url(&self) -> &'static str {
-        "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/grafana.svg"
+service_definition! {
+    pub struct Grafana {
+        name: "Grafana",
+        description: "Analytics and monitoring visualization platform",
+        category: Monitoring,
+        discovery_pattern: Pattern::Endpoint(PortBase::Http, "/", "grafana"),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/grafana.svg",
     }
 }
-
-inventory::submit!(ServiceDefinitionFactory::new(create_service::<Grafana>));
@@ -1,6 +1,9 @@
+use crate::server::service_definitions::r#impl::runtime::RuntimeServiceDefinition;
 use crate::server::services::r#impl::definitions::ServiceDefinition;
 use crate::server::shared::types::metadata::HasId;
+use anyhow::{Error, bail};
 use inventory;
+use std::sync::{LazyLock, RwLock};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ServiceDefinitionFactory(pub fn() -> Box<dyn ServiceDefinition>);
@@ -24,29 +27,178 @@ where
 
 inventory::collect!(ServiceDefinitionFactory);
 
+/// Declare a service definition: the unit struct, its `ServiceDefinition` impl and its
+/// registration. Optional fields must follow the required ones in the order shown; definitions
+/// needing anything else (e.g. patterns that depend on `self`) implement the trait by hand.
+///
+/// ```ignore
+/// service_definition! {
+///     pub struct Gatus {
+///         name: "Gatus",
+///         description: "Automated developer-oriented status page",
+///         category: Monitoring,
+///         discovery_pattern: Pattern::Endpoint(PortBase::HttpAlt, "/manifest.json", "Gatus"),
+///         // optional
+///         is_generic: false,
+///         logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/gatus.svg",
+///         logo_needs_white_background: false,
+///         metrics_path: "/metrics",
+///     }
+/// }
+/// ```
+macro_rules! service_definition {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $ident:ident {
+            name: $name:expr,
+            description: $description:expr,
+            category: $category:ident,
+            discovery_pattern: $pattern:expr
+            $(, is_generic: $is_generic:expr)?
+            $(, logo_url: $logo_url:expr)?
+            $(, logo_needs_white_background: $white_background:expr)?
+            $(, metrics_path: $metrics_path:expr)?
+            $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Default, Clone, Eq, PartialEq, Hash)]
+        $vis struct $ident;
+
+        impl $crate::server::services::r#impl::definitions::ServiceDefinition for $ident {
+            fn name(&self) -> &'static str {
+                $name
+            }
+            fn description(&self) -> &'static str {
+                $description
+            }
+            fn category(&self) -> $crate::server::services::r#impl::categories::ServiceCategory {
+                $crate::server::services::r#impl::categories::ServiceCategory::$category
+            }
+
+            fn discovery_pattern(&self) -> $crate::server::services::r#impl::patterns::Pattern<'_> {
+                $pattern
+            }
+
+            $(
+                fn is_generic(&self) -> bool {
+                    $is_generic
+                }
+            )?
+
+            $(
+                fn logo_url(&self) -> &'static str {
+                    $logo_url
+                }
+            )?
+
+            $(
+                fn logo_needs_white_background(&self) -> bool {
+                    $white_background
+                }
+            )?
+
+            $(
+                fn metrics_path(&self) -> Option<&'static str> {
+                    Some($metrics_path)
+                }
+            )?
+        }
+
+        inventory::submit!($crate::server::services::definitions::ServiceDefinitionFactory::new(
+            $crate::server::services::definitions::create_service::<$ident>
+        ));
+    };
+}
+
+pub(crate) use service_definition;
+
 pub struct ServiceDefinitionRegistry;
 
+/// Definitions registered at runtime, from files or the API on the server and from discovery
+/// requests on daemons
+static RUNTIME_DEFINITIONS: LazyLock<RwLock<Vec<RuntimeServiceDefinition>>> =
+    LazyLock::new(Default::default);
+
 impl ServiceDefinitionRegistry {
     /// Get all registered services as instances
     pub fn all_service_definitions() -> Vec<Box<dyn ServiceDefinition>> {
         inventory::iter::<ServiceDefinitionFactory>()
             .map(|factory| factory.create())
+            .chain(
+                Self::runtime_definitions()
+                    .into_iter()
+                    .map(|d| Box::new(d) as Box<dyn ServiceDefinition>),
+            )
             .collect()
     }
 
     pub fn service_exists(id: &str) -> bool {
-        inventory::iter::<ServiceDefinitionFactory>().any(|factory| factory.create().id() == id)
+        Self::is_builtin(id) || Self::runtime_definitions().iter().any(|d| d.id() == id)
     }
 
     pub fn find_by_id(id: &str) -> Option<Box<dyn ServiceDefinition>> {
-        inventory::iter::<ServiceDefinitionFactory>().find_map(|factory| {
-            let service_definition = factory.create();
-            if service_definition.id() == id {
-                Some(service_definition)
-            } else {
-                None
-            }
-        })
+        inventory::iter::<ServiceDefinitionFactory>()
+            .find_map(|factory| {
+                let service_definition = factory.create();
+                if service_definition.id() == id {
+                    Some(service_definition)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| {
+                Self::runtime_definitions()
+                    .into_iter()
+                    .find(|d| d.id() == id)
+                    .map(|d| Box::new(d) as Box<dyn ServiceDefinition>)
+            })
+    }
+
+    /// Whether the definition is compiled in rather than registered at runtime
+    pub fn is_builtin(id: &str) -> bool {
+        inventory::iter::<ServiceDefinitionFactory>().any(|factory| factory.create().id() == id)
+    }
+
+    pub fn runtime_definitions() -> Vec<RuntimeServiceDefinition> {
+        RUNTIME_DEFINITIONS
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Register a definition at runtime, replacing any runtime definition with the same name
+    pub fn register(definition: RuntimeServiceDefinition) -> Result<(), Error> {
+        if Self::is_builtin(definition.id()) {
+            bail!("'{}' is a built-in service definition", definition.id());
+        }
+
+        let mut definitions = RUNTIME_DEFINITIONS
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        definitions.retain(|d| d.id() != definition.id());
+        definitions.push(definition);
+
+        Ok(())
+    }
+
+    pub fn unregister(id: &str) {
+        RUNTIME_DEFINITIONS
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|d| d.id() != id);
+    }
+
+    /// Replace every runtime definition, e.g. with the set a daemon receives from the server
+    pub fn replace_runtime_definitions(definitions: Vec<RuntimeServiceDefinition>) {
+        let definitions = definitions
+            .into_iter()
+            .filter(|d| !Self::is_builtin(d.id()))
+            .collect();
+
+        *RUNTIME_DEFINITIONS
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = definitions;
     }
 }
 
@@ -108,6 +260,7 @@ pub mod linkstack;
 pub mod lubelogger;
 pub mod mealie;
 pub mod memos;
+pub mod mysql;
 pub mod nas_device;
 pub mod nest_protect;
 pub mod nest_thermostat;
@@ -116,8 +269,10 @@ pub mod netvisor_daemon;
 pub mod netvisor_server;
 pub mod next_cloud;
 pub mod nginx_proxy_manager;
+pub mod node_exporter;
 pub mod nut;
 pub mod open_media_vault;
+pub mod open_ssh;
 pub mod open_webui;
 pub mod opn_sense;
 pub mod overseerr;
@@ -138,6 +293,7 @@ pub mod proxmox;
 pub mod qbittorrent;
 pub mod qnap;
 pub mod radarr;
+pub mod redis;
 pub mod restic;
 pub mod ring_doorbell;
 pub mod roku;
@@ -156,6 +312,8 @@ pub mod unifi_access_point;
 pub mod unifi_controller;
 pub mod uptime_kuma;
 pub mod vaultwarden;
+pub mod vmware_esxi;
+pub mod vmware_vcenter;
 pub mod web_service;
 pub mod wg_dashboard;
 pub mod workstation;
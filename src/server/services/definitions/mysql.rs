@@ -0,0 +1,13 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::service_definition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+service_definition! {
+    pub struct MySql {
+        name: "MySQL",
+        description: "MySQL or MariaDB database server",
+        category: Database,
+        discovery_pattern: Pattern::Banner(PortBase::new_tcp(3306), "mysql"),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/mysql.svg",
+    }
+}
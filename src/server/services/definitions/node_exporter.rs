@@ -0,0 +1,14 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::service_definition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+service_definition! {
+    pub struct NodeExporter {
+        name: "Node Exporter",
+        description: "Prometheus exporter for hardware and OS metrics",
+        category: Monitoring,
+        discovery_pattern: Pattern::Endpoint(PortBase::new_tcp(9100), "/metrics", "node_exporter"),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/prometheus.svg",
+        metrics_path: "/metrics",
+    }
+}
@@ -0,0 +1,12 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::service_definition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+service_definition! {
+    pub struct OpenSsh {
+        name: "OpenSSH",
+        description: "Secure shell server",
+        category: Development,
+        discovery_pattern: Pattern::Banner(PortBase::Ssh, "SSH-2.0-OpenSSH"),
+    }
+}
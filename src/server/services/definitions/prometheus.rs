@@ -1,33 +1,17 @@
 use crate::server::hosts::r#impl::ports::PortBase;
-use crate::server::services::definitions::{ServiceDefinitionFactory, create_service};
-use crate::server::services::r#impl::categories::ServiceCategory;
-use crate::server::services::r#impl::definitions::ServiceDefinition;
+use crate::server::services::definitions::service_definition;
 use crate::server::services::r#impl::patterns::Pattern;
 
-#[derive(Default, Clone, Eq, PartialEq, Hash)]
-pub struct Prometheus;
-
-impl ServiceDefinition for Prometheus {
-    fn name(&self) -> &'static str {
-        "Prometheus"
-    }
-    fn description(&self) -> &'static str {
-        "Time-series monitoring and alerting system"
-    }
-    fn category(&self) -> ServiceCategory {
-        ServiceCategory::Monitoring
-    }
-
-    fn discovery_pattern(&self) -> Pattern<'_> {
-        Pattern::AnyOf(vec![
+service_definition! {
+    pub struct Prometheus {
+        name: "Prometheus",
+        description: "Time-series monitoring and alerting system",
+        category: Monitoring,
+        discovery_pattern: Pattern::AnyOf(vec![
             Pattern::Endpoint(PortBase::Http, "/metrics", "Prometheus"),
             Pattern::Endpoint(PortBase::Http, "/graph", "Prometheus"),
-        ])
-    }
-
-    fn logo_url(&self) -> &'static str {
-        "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/prometheus.svg"
+        ]),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/prometheus.svg",
+        metrics_path: "/metrics",
     }
 }
-
-inventory::submit!(ServiceDefinitionFactory::new(create_service::<Prometheus>));
@@ -0,0 +1,16 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::service_definition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+service_definition! {
+    pub struct Redis {
+        name: "Redis",
+        description: "In-memory key-value store",
+        category: Database,
+        discovery_pattern: Pattern::AnyOf(vec![
+            Pattern::Banner(PortBase::new_tcp(6379), "+PONG"),
+            Pattern::Banner(PortBase::new_tcp(6379), "-NOAUTH"),
+        ]),
+        logo_url: "https://cdn.jsdelivr.net/gh/homarr-labs/dashboard-icons/svg/redis.svg",
+    }
+}
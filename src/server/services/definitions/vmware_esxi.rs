@@ -0,0 +1,29 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::{ServiceDefinitionFactory, create_service};
+use crate::server::services::r#impl::categories::ServiceCategory;
+use crate::server::services::r#impl::definitions::ServiceDefinition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+#[derive(Default, Clone, Eq, PartialEq, Hash)]
+pub struct VmwareEsxi;
+
+impl ServiceDefinition for VmwareEsxi {
+    fn name(&self) -> &'static str {
+        "VMware ESXi"
+    }
+    fn description(&self) -> &'static str {
+        "Bare-metal hypervisor from VMware"
+    }
+    fn category(&self) -> ServiceCategory {
+        ServiceCategory::Virtualization
+    }
+
+    fn discovery_pattern(&self) -> Pattern<'_> {
+        Pattern::AnyOf(vec![
+            Pattern::Endpoint(PortBase::Https, "/", "VMware ESXi"),
+            Pattern::Endpoint(PortBase::Https, "/ui/", "VMware Host Client"),
+        ])
+    }
+}
+
+inventory::submit!(ServiceDefinitionFactory::new(create_service::<VmwareEsxi>));
@@ -0,0 +1,31 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+use crate::server::services::definitions::{ServiceDefinitionFactory, create_service};
+use crate::server::services::r#impl::categories::ServiceCategory;
+use crate::server::services::r#impl::definitions::ServiceDefinition;
+use crate::server::services::r#impl::patterns::Pattern;
+
+#[derive(Default, Clone, Eq, PartialEq, Hash)]
+pub struct VmwareVcenter;
+
+impl ServiceDefinition for VmwareVcenter {
+    fn name(&self) -> &'static str {
+        "VMware vCenter"
+    }
+    fn description(&self) -> &'static str {
+        "Management server for VMware ESXi hosts and their VMs"
+    }
+    fn category(&self) -> ServiceCategory {
+        ServiceCategory::Virtualization
+    }
+
+    fn discovery_pattern(&self) -> Pattern<'_> {
+        Pattern::AnyOf(vec![
+            Pattern::Endpoint(PortBase::Https, "/", "VMware vCenter"),
+            Pattern::Endpoint(PortBase::Https, "/ui/", "vSphere Client"),
+        ])
+    }
+}
+
+inventory::submit!(ServiceDefinitionFactory::new(
+    create_service::<VmwareVcenter>
+));
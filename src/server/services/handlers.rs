@@ -1,16 +1,62 @@
+use crate::server::auth::middleware::AuthenticatedUser;
 use crate::server::shared::handlers::traits::{
-    create_handler, delete_handler, get_all_handler, get_by_id_handler, update_handler,
+    create_handler, delete_handler, get_by_id_handler, restore_handler, update_handler,
+};
+use crate::server::shared::services::traits::CrudService;
+use crate::server::shared::storage::filter::EntityFilter;
+use crate::server::shared::types::api::{ApiResponse, ApiResult};
+use crate::server::{
+    config::AppState,
+    monitoring::handlers::get_service_health,
+    services::r#impl::{api::ServiceListQuery, base::Service},
 };
-use crate::server::{config::AppState, services::r#impl::base::Service};
-use axum::Router;
 use axum::routing::{delete, get, post, put};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+};
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub fn create_router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", post(create_handler::<Service>))
-        .route("/", get(get_all_handler::<Service>))
+        .route("/", get(get_all_services))
         .route("/{id}", put(update_handler::<Service>))
         .route("/{id}", delete(delete_handler::<Service>))
         .route("/{id}", get(get_by_id_handler::<Service>))
+        .route("/{id}/restore", post(restore_handler::<Service>))
+        .route("/{id}/health", get(get_service_health))
+}
+
+/// Services in the user's networks, optionally only those with known vulnerabilities
+async fn get_all_services(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<ServiceListQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<Service>>>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    let mut network_filter = EntityFilter::unfiltered().network_ids(&network_ids);
+    if let Some(has_vulnerabilities) = query.has_vulnerabilities {
+        network_filter = network_filter.has_vulnerabilities(has_vulnerabilities);
+    }
+    let network_filter = query.list_query().apply::<Service>(network_filter)?;
+
+    let services = state
+        .services
+        .service_service
+        .get_page(network_filter)
+        .await?;
+
+    Ok(Json(ApiResponse::page(services)))
 }
@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::server::shared::handlers::traits::ListQuery;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServiceListQuery {
+    /// Only services with, or only services without, known vulnerabilities
+    #[serde(default)]
+    pub has_vulnerabilities: Option<bool>,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub limit: Option<u32>,
+    pub cursor: Option<Uuid>,
+    pub sort: Option<String>,
+    pub q: Option<String>,
+    pub tag: Option<String>,
+    pub field: Option<String>,
+}
+
+impl ServiceListQuery {
+    pub fn list_query(&self) -> ListQuery {
+        ListQuery {
+            include_deleted: self.include_deleted,
+            limit: self.limit,
+            cursor: self.cursor,
+            sort: self.sort.clone(),
+            q: self.q.clone(),
+            tag: self.tag.clone(),
+            field: self.field.clone(),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use crate::server::hosts::r#impl::ports::PortBase;
+
+/// What an open TCP port sent on connect, or in answer to a protocol probe such as SMTP `EHLO`
+/// or Redis `PING`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortBanner {
+    pub port_base: PortBase,
+    pub banner: String,
+}
@@ -2,17 +2,22 @@ use crate::server::discovery::r#impl::types::DiscoveryType;
 use crate::server::hosts::r#impl::interfaces::Interface;
 use crate::server::hosts::r#impl::ports::PortBase;
 use crate::server::services::definitions::ServiceDefinitionRegistry;
+use crate::server::services::r#impl::banners::PortBanner;
 use crate::server::services::r#impl::bindings::Binding;
 use crate::server::services::r#impl::definitions::ServiceDefinitionExt;
 use crate::server::services::r#impl::definitions::{DefaultServiceDefinition, ServiceDefinition};
 use crate::server::services::r#impl::endpoints::{Endpoint, EndpointResponse};
 use crate::server::services::r#impl::patterns::{MatchConfidence, MatchReason, MatchResult};
+use crate::server::services::r#impl::upnp::UpnpDevice;
+use crate::server::services::r#impl::versions::ServiceVersion;
 use crate::server::services::r#impl::virtualization::{
     DockerVirtualization, ServiceVirtualization,
 };
 use crate::server::shared::storage::traits::StorableEntity;
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
 use crate::server::subnets::r#impl::base::Subnet;
+use crate::server::vulnerabilities::r#impl::base::ServiceVulnerability;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -31,6 +36,17 @@ pub struct ServiceBase {
     pub bindings: Vec<Binding>,
     pub virtualization: Option<ServiceVirtualization>,
     pub source: EntitySource,
+    /// Software versions the service announced in banners and HTTP responses
+    #[serde(default)]
+    pub versions: Vec<ServiceVersion>,
+    /// Known vulnerabilities of `versions`, most severe first; set by the vulnerability feed
+    /// sync
+    #[serde(default)]
+    pub vulnerabilities: Vec<ServiceVulnerability>,
+    /// Tags and custom fields set by users
+    #[serde(default)]
+    #[validate(nested)]
+    pub attributes: EntityAttributes,
 }
 
 impl Default for ServiceBase {
@@ -43,6 +59,9 @@ impl Default for ServiceBase {
             bindings: Vec::new(),
             virtualization: None,
             source: EntitySource::Unknown,
+            versions: Vec::new(),
+            vulnerabilities: Vec::new(),
+            attributes: EntityAttributes::default(),
         }
     }
 }
@@ -52,6 +71,9 @@ pub struct Service {
     pub id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the service was deleted; it stays restorable until the trash is purged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     #[validate(nested)]
     pub base: ServiceBase,
@@ -74,7 +96,10 @@ pub struct ServiceMatchBaselineParams<'a> {
     pub interface: &'a Interface,
     pub all_ports: &'a Vec<PortBase>,
     pub endpoint_responses: &'a Vec<EndpointResponse>,
+    pub banners: &'a Vec<PortBanner>,
     pub virtualization: &'a Option<ServiceVirtualization>,
+    /// Device description, if the host answered an SSDP search
+    pub upnp_device: &'a Option<UpnpDevice>,
 }
 
 #[derive(Debug, Clone)]
@@ -204,6 +229,8 @@ impl Service {
         let ServiceMatchBaselineParams {
             interface,
             virtualization,
+            banners,
+            endpoint_responses,
             ..
         } = baseline_params;
 
@@ -254,6 +281,9 @@ impl Service {
                 vec![Binding::new_interface(interface.id)]
             };
 
+            let matched_ports: Vec<PortBase> = result.ports.iter().map(|p| p.base).collect();
+            let versions = ServiceVersion::detect(&matched_ports, banners, endpoint_responses);
+
             let service = Service::new(ServiceBase {
                 host_id: *host_id,
                 network_id: *network_id,
@@ -265,6 +295,9 @@ impl Service {
                     metadata: vec![discovery_metadata],
                     details: result.details.clone(),
                 },
+                versions,
+                vulnerabilities: Vec::new(),
+                attributes: EntityAttributes::default(),
             });
 
             Some((service, result))
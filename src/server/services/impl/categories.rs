@@ -3,7 +3,7 @@ use strum_macros::{Display, EnumDiscriminants, EnumIter, IntoStaticStr};
 
 use crate::server::shared::{
     entities::Entity,
-    types::metadata::{EntityMetadataProvider, HasId},
+    types::metadata::{EntityMetadataProvider, HasId, TypeMetadataProvider},
 };
 
 #[derive(
@@ -145,3 +145,39 @@ impl EntityMetadataProvider for ServiceCategory {
         }
     }
 }
+
+impl TypeMetadataProvider for ServiceCategory {
+    fn name(&self) -> &'static str {
+        match self {
+            ServiceCategory::NetworkCore => "Network Core",
+            ServiceCategory::NetworkAccess => "Network Access",
+            ServiceCategory::NetworkSecurity => "Network Security",
+
+            ServiceCategory::Storage => "Storage",
+            ServiceCategory::Backup => "Backup",
+            ServiceCategory::Media => "Media",
+            ServiceCategory::HomeAutomation => "Home Automation",
+            ServiceCategory::Virtualization => "Virtualization",
+
+            ServiceCategory::DNS => "DNS",
+            ServiceCategory::VPN => "VPN",
+            ServiceCategory::Monitoring => "Monitoring",
+            ServiceCategory::AdBlock => "Ad Blocking",
+            ServiceCategory::ReverseProxy => "Reverse Proxy",
+
+            ServiceCategory::Workstation => "Workstation",
+            ServiceCategory::Mobile => "Mobile",
+            ServiceCategory::IoT => "IoT",
+            ServiceCategory::Printer => "Printer",
+
+            ServiceCategory::Web => "Web",
+            ServiceCategory::Database => "Database",
+            ServiceCategory::Development => "Development",
+            ServiceCategory::Dashboard => "Dashboard",
+
+            ServiceCategory::Unknown => "Unknown",
+            ServiceCategory::Custom => "Custom",
+            ServiceCategory::Netvisor => "NetVisor",
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::server::services::definitions::ServiceDefinitionRegistry;
 use crate::server::services::definitions::docker_daemon::Docker;
 use crate::server::services::definitions::proxmox::Proxmox;
+use crate::server::services::definitions::vmware_esxi::VmwareEsxi;
+use crate::server::services::definitions::vmware_vcenter::VmwareVcenter;
 use crate::server::services::r#impl::categories::ServiceCategory;
 use crate::server::services::r#impl::patterns::Pattern;
 use crate::server::shared::types::metadata::TypeMetadataProvider;
@@ -45,6 +47,11 @@ pub trait ServiceDefinition: HasId + DynClone + DynHash + DynEq + Send + Sync {
     fn logo_needs_white_background(&self) -> bool {
         false
     }
+
+    /// Path of a Prometheus metrics endpoint served on the service's ports, if it has one
+    fn metrics_path(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 impl<T: ServiceDefinition> HasId for T
@@ -84,6 +91,10 @@ impl ServiceDefinition for Box<dyn ServiceDefinition> {
     fn logo_needs_white_background(&self) -> bool {
         ServiceDefinition::logo_needs_white_background(&**self)
     }
+
+    fn metrics_path(&self) -> Option<&'static str> {
+        ServiceDefinition::metrics_path(&**self)
+    }
 }
 
 // Helper methods to be used in rest of codebase, not overridable by definition implementations
@@ -120,7 +131,9 @@ impl ServiceDefinitionExt for Box<dyn ServiceDefinition> {
     fn manages_virtualization(&self) -> Option<&'static str> {
         let id = self.id();
         match id {
-            _ if id == Proxmox.id() => Some("vms"),
+            _ if id == Proxmox.id() || id == VmwareEsxi.id() || id == VmwareVcenter.id() => {
+                Some("vms")
+            }
             _ if id == Docker.id() => Some("containers"),
             _ => None,
         }
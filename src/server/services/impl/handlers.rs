@@ -2,6 +2,7 @@ use crate::server::{
     services::{r#impl::base::Service, service::ServiceService},
     shared::handlers::traits::CrudHandlers,
 };
+use uuid::Uuid;
 
 impl CrudHandlers for Service {
     type Service = ServiceService;
@@ -9,4 +10,8 @@ impl CrudHandlers for Service {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.service_service
     }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
@@ -0,0 +1,205 @@
+use crate::server::shared::types::attributes::EntityAttributes;
+use crate::server::{
+    discovery::r#impl::types::{DiscoveryType, HostNamingFallback},
+    hosts::r#impl::{
+        base::{Host, HostBase},
+        ports::{Port, PortBase},
+        service_overrides::HostServiceOverrides,
+        targets::HostTarget,
+    },
+    services::{
+        definitions::{ServiceDefinitionRegistry, gateway::Gateway},
+        r#impl::{
+            base::{
+                DiscoverySessionServiceMatchParams, Service, ServiceMatchBaselineParams,
+                ServiceMatchServiceParams,
+            },
+            bindings::Binding,
+            definitions::{ServiceDefinition, ServiceDefinitionExt},
+            patterns::MatchConfidence,
+        },
+    },
+    shared::types::{
+        entities::{DiscoveryMetadata, EntitySource},
+        metadata::HasId,
+    },
+};
+use std::net::IpAddr;
+use uuid::Uuid;
+
+/// Who discovered a host and what they know about its network
+pub struct DiscoveryContext<'a> {
+    pub daemon_id: &'a Uuid,
+    pub network_id: &'a Uuid,
+    pub gateway_ips: &'a [IpAddr],
+    pub discovery_type: &'a DiscoveryType,
+}
+
+/// Build a host from what was observed on one interface, matching services against its ports
+/// and endpoint responses and naming it after its hostname or best service
+pub fn discover_host(
+    params: &ServiceMatchBaselineParams,
+    hostname: Option<String>,
+    host_naming_fallback: HostNamingFallback,
+    ctx: &DiscoveryContext,
+) -> (Host, Vec<Service>) {
+    let interface = params.interface;
+
+    let mut host = Host::new(HostBase {
+        name: "Unknown Device".to_string(),
+        hostname: hostname.clone(),
+        target: HostTarget::None,
+        network_id: *ctx.network_id,
+        description: None,
+        interfaces: vec![interface.clone()],
+        services: Vec::new(),
+        ports: Vec::new(),
+        source: EntitySource::Discovery {
+            metadata: vec![DiscoveryMetadata::new(
+                ctx.discovery_type.clone(),
+                *ctx.daemon_id,
+            )],
+        },
+        virtualization: None,
+        hidden: false,
+        liveness: None,
+        snmp: None,
+        ssh: None,
+        windows: None,
+        os: None,
+        service_overrides: HostServiceOverrides::default(),
+        last_seen_at: None,
+        stale: false,
+        reachability: None,
+        vpn_peer: None,
+        device: None,
+        dhcp_lease: None,
+        attributes: EntityAttributes::default(),
+    });
+
+    let services = discover_services(&mut host, params, ctx);
+
+    // Determine host's name
+    let best_service_name = services
+        .iter()
+        .find(|s| !ServiceDefinitionExt::is_generic(&s.base.service_definition))
+        .map(|s| s.base.service_definition.name().to_string());
+
+    if let Some(hostname) = hostname {
+        host.base.name = hostname;
+        if host.base.target == HostTarget::None {
+            host.base.target = HostTarget::Hostname
+        }
+    } else if host_naming_fallback == HostNamingFallback::BestService
+        && let Some(best_service_name) = best_service_name
+    {
+        host.base.name = best_service_name
+    } else if host_naming_fallback == HostNamingFallback::Ip {
+        host.base.name = interface.base.ip_address.to_string()
+    } else if let Some(best_service_name) = best_service_name {
+        host.base.name = best_service_name
+    } else {
+        host.base.name = interface.base.ip_address.to_string()
+    }
+
+    (host, services)
+}
+
+fn discover_services(
+    host: &mut Host,
+    baseline_params: &ServiceMatchBaselineParams,
+    ctx: &DiscoveryContext,
+) -> Vec<Service> {
+    let ServiceMatchBaselineParams { all_ports, .. } = baseline_params;
+
+    let mut services = Vec::new();
+
+    // Need to track which ports are bound vs open for services to bind to
+    let mut l4_unbound_ports = all_ports.to_vec();
+
+    let mut sorted_service_definitions: Vec<Box<dyn ServiceDefinition>> =
+        ServiceDefinitionRegistry::all_service_definitions()
+            .into_iter()
+            .collect();
+
+    sorted_service_definitions.sort_by_key(|s| {
+        if !ServiceDefinitionExt::is_generic(s) {
+            0 // Highest priority - non-generic services
+        } else if ServiceDefinitionExt::is_generic(s) && s.id() != Gateway.id() {
+            1 // Generic services that aren't Gateway
+        } else {
+            2 // Generic gateways need to go last, as other services may be classified as gateway first
+        }
+    });
+
+    // Add services from detected ports
+    for service_definition in sorted_service_definitions {
+        let service_params = ServiceMatchServiceParams {
+            service_definition,
+            matched_services: &services,
+            unbound_ports: &l4_unbound_ports,
+        };
+
+        let params: DiscoverySessionServiceMatchParams<'_> = DiscoverySessionServiceMatchParams {
+            service_params,
+            baseline_params,
+            daemon_id: ctx.daemon_id,
+            discovery_type: ctx.discovery_type,
+            network_id: ctx.network_id,
+            gateway_ips: ctx.gateway_ips,
+            host_id: &host.id,
+        };
+
+        if let Some((service, mut result)) = Service::from_discovery(params) {
+            // If there's a endpoint match + host target is hostname or none, use a binding as the host target
+            if let (Some(binding), true) = (
+                service.base.bindings.iter().find(|b| {
+                    match b {
+                        Binding::Interface { .. } => false,
+                        Binding::Port { port_id, .. } => {
+                            if let Some(port) = host.get_port(port_id) {
+                                return result.endpoint.iter().any(|e| e.port_base == port.base);
+                            }
+                            false
+                        }
+                    };
+                    false
+                }),
+                matches!(host.base.target, HostTarget::Hostname | HostTarget::None),
+            ) {
+                host.base.target = HostTarget::ServiceBinding(binding.id())
+            }
+
+            // Add any bound ports to host ports array, remove from open ports
+            let bound_port_bases: Vec<PortBase> = result.ports.iter().map(|p| p.base).collect();
+
+            host.base.ports.append(&mut result.ports);
+
+            // Add new service
+            l4_unbound_ports.retain(|p| !bound_port_bases.contains(p));
+            services.push(service);
+        }
+    }
+
+    services.sort_by_key(|a| {
+        -(match &a.base.source {
+            EntitySource::DiscoveryWithMatch { details, .. } => {
+                (details.confidence as i32)
+                    + if a.base.service_definition.has_logo() {
+                        1
+                    } else {
+                        0
+                    }
+            }
+            _ => MatchConfidence::NotApplicable as i32,
+        })
+    });
+
+    services.iter().for_each(|s| host.add_service(s.id));
+
+    host.base
+        .ports
+        .extend(l4_unbound_ports.into_iter().map(Port::new));
+
+    services
+}
@@ -1,9 +1,15 @@
+pub mod api;
+pub mod banners;
 pub mod base;
 pub mod bindings;
 pub mod categories;
 pub mod definitions;
 pub mod endpoints;
 pub mod handlers;
+pub mod matching;
+pub mod pattern_parser;
 pub mod patterns;
 pub mod storage;
+pub mod upnp;
+pub mod versions;
 pub mod virtualization;
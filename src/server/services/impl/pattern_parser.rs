@@ -0,0 +1,437 @@
+use anyhow::{Error, anyhow, bail};
+use strum::IntoEnumIterator;
+
+use crate::server::{
+    hosts::r#impl::ports::{PortBase, TransportProtocol},
+    services::r#impl::patterns::Pattern,
+    shared::types::metadata::HasId,
+    subnets::r#impl::types::SubnetType,
+};
+
+/// Parses the textual form of a discovery pattern, e.g.
+/// `port(8096) AND endpoint(8096, "/web", "jellyfin")`.
+///
+/// - `port(N)`, `port(N, udp)`
+/// - `endpoint(N, "path", "expected response")`, `banner(N, "expected banner")`
+/// - `subnet_type(IoT)`, `mac_vendor("Sonos, Inc.")`, `upnp("Synology")`
+/// - `is_gateway`, `docker_container`
+/// - `AND`, `OR`, `NOT` and parentheses; `AND` binds tighter than `OR`
+///
+/// Strings are quoted with `"` or `'` and have no escapes, so they borrow from the input.
+pub fn parse_pattern(input: &str) -> Result<Pattern<'_>, Error> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens, pos: 0 };
+
+    let pattern = parser.parse_or()?;
+
+    if let Some((token, offset)) = parser.tokens.get(parser.pos) {
+        bail!("Unexpected {} at position {}", token, offset);
+    }
+
+    Ok(pattern)
+}
+
+impl<'a> Pattern<'a> {
+    /// See [`parse_pattern`]
+    pub fn parse(input: &'a str) -> Result<Self, Error> {
+        parse_pattern(input)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    LParen,
+    RParen,
+    Comma,
+    And,
+    Or,
+    Not,
+    Ident(&'a str),
+    Number(u16),
+    Str(&'a str),
+}
+
+impl std::fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Ident(s) => write!(f, "'{}'", s),
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+        }
+    }
+}
+
+/// Tokens with their byte offset in the input
+fn tokenize(input: &str) -> Result<Vec<(Token<'_>, usize)>, Error> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+
+        let token = match c {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'(' => {
+                i += 1;
+                Token::LParen
+            }
+            b')' => {
+                i += 1;
+                Token::RParen
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b'!' => {
+                i += 1;
+                Token::Not
+            }
+            b'&' | b'|' if bytes.get(i + 1) == Some(&c) => {
+                i += 2;
+                if c == b'&' { Token::And } else { Token::Or }
+            }
+            b'"' | b'\'' => {
+                let end = input[i + 1..]
+                    .find(c as char)
+                    .ok_or_else(|| anyhow!("Unterminated string at position {}", start))?;
+                i += end + 2;
+                Token::Str(&input[start + 1..start + 1 + end])
+            }
+            b'0'..=b'9' => {
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let number = input[start..i]
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid port number at position {}", start))?;
+                Token::Number(number)
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                let word = &input[start..i];
+                match word.to_ascii_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Ident(word),
+                }
+            }
+            _ => bail!(
+                "Unexpected character '{}' at position {}",
+                input[start..].chars().next().unwrap_or_default(),
+                start
+            ),
+        };
+
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token<'a>, usize)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.pos).map(|(t, _)| *t)
+    }
+
+    fn next(&mut self) -> Result<(Token<'a>, usize), Error> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| anyhow!("Unexpected end of pattern"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token<'a>) -> Result<(), Error> {
+        let (token, offset) = self.next()?;
+        if token != expected {
+            bail!(
+                "Expected {} but found {} at position {}",
+                expected,
+                token,
+                offset
+            );
+        }
+        Ok(())
+    }
+
+    fn parse_or(&mut self) -> Result<Pattern<'a>, Error> {
+        let mut patterns = vec![self.parse_and()?];
+        while self.peek() == Some(Token::Or) {
+            self.pos += 1;
+            patterns.push(self.parse_and()?);
+        }
+
+        Ok(if patterns.len() == 1 {
+            patterns.remove(0)
+        } else {
+            Pattern::AnyOf(patterns)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Pattern<'a>, Error> {
+        let mut patterns = vec![self.parse_unary()?];
+        while self.peek() == Some(Token::And) {
+            self.pos += 1;
+            patterns.push(self.parse_unary()?);
+        }
+
+        Ok(if patterns.len() == 1 {
+            patterns.remove(0)
+        } else {
+            Pattern::AllOf(patterns)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Pattern<'a>, Error> {
+        match self.next()? {
+            (Token::Not, _) => Ok(Pattern::Not(Box::new(self.parse_unary()?))),
+            (Token::LParen, _) => {
+                let pattern = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(pattern)
+            }
+            (Token::Ident(name), offset) => self.parse_call(name, offset),
+            (token, offset) => bail!(
+                "Expected a pattern but found {} at position {}",
+                token,
+                offset
+            ),
+        }
+    }
+
+    /// Arguments of a call, or none if the name isn't followed by parentheses
+    fn parse_args(&mut self) -> Result<Vec<(Token<'a>, usize)>, Error> {
+        let mut args = Vec::new();
+        if self.peek() != Some(Token::LParen) {
+            return Ok(args);
+        }
+        self.pos += 1;
+
+        if self.peek() == Some(Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+
+        loop {
+            let (token, offset) = self.next()?;
+            match token {
+                Token::Number(_) | Token::Str(_) | Token::Ident(_) => args.push((token, offset)),
+                _ => bail!(
+                    "Expected an argument but found {} at position {}",
+                    token,
+                    offset
+                ),
+            }
+
+            match self.next()? {
+                (Token::Comma, _) => continue,
+                (Token::RParen, _) => break,
+                (token, offset) => {
+                    bail!(
+                        "Expected ',' or ')' but found {} at position {}",
+                        token,
+                        offset
+                    )
+                }
+            }
+        }
+
+        Ok(args)
+    }
+
+    fn parse_call(&mut self, name: &'a str, offset: usize) -> Result<Pattern<'a>, Error> {
+        let args = self.parse_args()?;
+        let arity = |expected: &[usize]| -> Result<(), Error> {
+            if !expected.contains(&args.len()) {
+                bail!(
+                    "{}() at position {} takes {} argument(s), got {}",
+                    name,
+                    offset,
+                    expected
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" or "),
+                    args.len()
+                );
+            }
+            Ok(())
+        };
+
+        match name.to_ascii_lowercase().as_str() {
+            "port" => {
+                arity(&[1, 2])?;
+                let number = number_arg(&args[0])?;
+                let protocol = match args.get(1) {
+                    Some(arg) => protocol_arg(arg)?,
+                    None => TransportProtocol::Tcp,
+                };
+                Ok(Pattern::Port(PortBase::new(number, protocol)))
+            }
+            "endpoint" => {
+                arity(&[3])?;
+                Ok(Pattern::Endpoint(
+                    PortBase::new_tcp(number_arg(&args[0])?),
+                    string_arg(&args[1])?,
+                    string_arg(&args[2])?,
+                ))
+            }
+            "banner" => {
+                arity(&[2])?;
+                Ok(Pattern::Banner(
+                    PortBase::new_tcp(number_arg(&args[0])?),
+                    string_arg(&args[1])?,
+                ))
+            }
+            "subnet_type" => {
+                arity(&[1])?;
+                let (Token::Ident(value) | Token::Str(value), arg_offset) = args[0] else {
+                    bail!("Expected a subnet type at position {}", args[0].1);
+                };
+                SubnetType::iter()
+                    .find(|t| t.id().eq_ignore_ascii_case(value))
+                    .map(Pattern::SubnetIsType)
+                    .ok_or_else(|| {
+                        anyhow!("Unknown subnet type '{}' at position {}", value, arg_offset)
+                    })
+            }
+            "mac_vendor" => {
+                arity(&[1])?;
+                Ok(Pattern::MacVendor(string_arg(&args[0])?))
+            }
+            "upnp" => {
+                arity(&[1])?;
+                Ok(Pattern::Upnp(string_arg(&args[0])?))
+            }
+            "is_gateway" => {
+                arity(&[0])?;
+                Ok(Pattern::IsGateway)
+            }
+            "docker_container" => {
+                arity(&[0])?;
+                Ok(Pattern::DockerContainer)
+            }
+            _ => bail!("Unknown pattern '{}' at position {}", name, offset),
+        }
+    }
+}
+
+fn number_arg(arg: &(Token, usize)) -> Result<u16, Error> {
+    match arg {
+        (Token::Number(n), _) if *n > 0 => Ok(*n),
+        (token, offset) => bail!(
+            "Expected a port number but found {} at position {}",
+            token,
+            offset
+        ),
+    }
+}
+
+fn string_arg<'a>(arg: &(Token<'a>, usize)) -> Result<&'a str, Error> {
+    match arg {
+        (Token::Str(s), _) => Ok(s),
+        (token, offset) => bail!(
+            "Expected a string but found {} at position {}",
+            token,
+            offset
+        ),
+    }
+}
+
+fn protocol_arg(arg: &(Token, usize)) -> Result<TransportProtocol, Error> {
+    match arg {
+        (Token::Ident(s), _) if s.eq_ignore_ascii_case("tcp") => Ok(TransportProtocol::Tcp),
+        (Token::Ident(s), _) if s.eq_ignore_ascii_case("udp") => Ok(TransportProtocol::Udp),
+        (token, offset) => bail!(
+            "Expected tcp or udp but found {} at position {}",
+            token,
+            offset
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_pattern;
+    use crate::server::{
+        hosts::r#impl::ports::PortBase, services::r#impl::patterns::Pattern,
+        subnets::r#impl::types::SubnetType,
+    };
+
+    /// `Pattern` has no `PartialEq` because of `Pattern::Custom`
+    fn assert_same(actual: Pattern, expected: Pattern) {
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn parses_and_or_precedence() {
+        let pattern =
+            parse_pattern(r#"port(8096) AND endpoint(8096, "/web", "jellyfin") OR is_gateway"#)
+                .unwrap();
+
+        assert_same(
+            pattern,
+            Pattern::AnyOf(vec![
+                Pattern::AllOf(vec![
+                    Pattern::Port(PortBase::new_tcp(8096)),
+                    Pattern::Endpoint(PortBase::new_tcp(8096), "/web", "jellyfin"),
+                ]),
+                Pattern::IsGateway,
+            ]),
+        );
+    }
+
+    #[test]
+    fn parses_not_and_arguments() {
+        let pattern =
+            parse_pattern("NOT (port(53, udp) || subnet_type(iot)) && mac_vendor('Sonos, Inc.')")
+                .unwrap();
+
+        assert_same(
+            pattern,
+            Pattern::AllOf(vec![
+                Pattern::Not(Box::new(Pattern::AnyOf(vec![
+                    Pattern::Port(PortBase::new_udp(53)),
+                    Pattern::SubnetIsType(SubnetType::IoT),
+                ]))),
+                Pattern::MacVendor("Sonos, Inc."),
+            ]),
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_position() {
+        let error = parse_pattern("port(80) AND").unwrap_err().to_string();
+        assert!(error.contains("end of pattern"), "{}", error);
+
+        let error = parse_pattern("port(80) banana(1)").unwrap_err().to_string();
+        assert!(error.contains("position 9"), "{}", error);
+
+        let error = parse_pattern("endpoint(80, \"/\")")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("takes 3 argument"), "{}", error);
+    }
+}
@@ -1,6 +1,7 @@
 use std::net::IpAddr;
 
 use crate::server::{
+    service_definitions::r#impl::plugins::WasmPlugin,
     services::{
         definitions::ServiceDefinitionRegistry,
         r#impl::{
@@ -32,6 +33,18 @@ pub struct MatchResult {
     pub details: MatchDetails,
 }
 
+/// Outcome of one node of a pattern, evaluated on its own
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternEvaluation {
+    pub pattern: &'static str,
+    pub matched: bool,
+    /// Why the node matched, or why it didn't
+    pub reason: String,
+    pub confidence: Option<MatchConfidence>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<PatternEvaluation>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MatchDetails {
     pub reason: MatchReason,
@@ -105,6 +118,10 @@ pub enum Pattern<'a> {
     /// expected response: &str - String to match on in response
     Endpoint(PortBase, &'a str, &'a str),
 
+    /// Whether the banner read from a TCP port contains the provided str (case-insensitive),
+    /// e.g. `SSH-2.0-OpenSSH` or a Redis `+PONG`
+    Banner(PortBase, &'a str),
+
     /// Whether the subnet that the host was found on matches a subnet type
     SubnetIsType(SubnetType),
 
@@ -112,7 +129,7 @@ pub enum Pattern<'a> {
     IsGateway,
 
     /// Whether the vendor derived from the mac address (https://gist.github.com/aallan/b4bb86db86079509e6159810ae9bd3e4) matches the provided str
-    MacVendor(&'static str),
+    MacVendor(&'a str),
 
     /// Custom evaluation of discovery match params
     /// fn - constraint function
@@ -126,9 +143,16 @@ pub enum Pattern<'a> {
         MatchConfidence,
     ),
 
+    /// Whether the host's UPnP device description has a friendly name, manufacturer, model or
+    /// device type containing the provided str (case-insensitive)
+    Upnp(&'a str),
+
     /// Whether the host is a docker container
     DockerContainer,
 
+    /// Evaluated by a WASM plugin's `matches` export
+    Plugin(&'a WasmPlugin),
+
     /// No match pattern (only added manually or by the system)
     None,
 }
@@ -168,7 +192,9 @@ impl Pattern<'_> {
             subnet,
             interface,
             endpoint_responses,
+            banners,
             virtualization,
+            upnp_device,
             ..
         } = baseline_params;
 
@@ -260,6 +286,36 @@ impl Pattern<'_> {
                 }
             }
 
+            Pattern::Banner(port_base, expected) => {
+                if let Some(actual) = banners.iter().find(|actual| {
+                    actual.port_base.number() == port_base.number()
+                        && unbound_ports.contains(&actual.port_base)
+                        && actual
+                            .banner
+                            .to_lowercase()
+                            .contains(&expected.to_lowercase())
+                }) {
+                    Ok(MatchResult {
+                        ports: vec![Port::new(actual.port_base)],
+                        endpoint: None,
+                        mac_vendor: None,
+                        details: MatchDetails {
+                            reason: MatchReason::Reason(format!(
+                                "Banner from port {} contained \"{}\"",
+                                actual.port_base, expected
+                            )),
+                            confidence: MatchConfidence::High,
+                        },
+                    })
+                } else {
+                    Err(anyhow!(
+                        "Banner from port {} did not contain \"{}\"",
+                        port_base,
+                        expected
+                    ))
+                }
+            }
+
             Pattern::MacVendor(vendor_string) => {
                 if let Some(mac) = interface.base.mac_address {
                     let Ok(oui_db) = Oui::default() else {
@@ -307,6 +363,28 @@ impl Pattern<'_> {
                 }
             }
 
+            Pattern::Upnp(value) => match upnp_device {
+                Some(device) => match device.find(value) {
+                    Some(field) => Ok(MatchResult {
+                        ports: vec![],
+                        endpoint: None,
+                        mac_vendor: None,
+                        details: MatchDetails {
+                            reason: MatchReason::Reason(format!(
+                                "UPnP device description at {} contains \"{}\"",
+                                device.location, field
+                            )),
+                            confidence: MatchConfidence::High,
+                        },
+                    }),
+                    None => Err(anyhow!(
+                        "UPnP device description does not contain \"{}\"",
+                        value
+                    )),
+                },
+                None => Err(anyhow!("Host did not answer an SSDP search")),
+            },
+
             Pattern::Not(pattern) => match pattern.matches(params) {
                 Ok(result) => Err(anyhow!("{}", result.details.reason)),
                 Err(e) => Ok(MatchResult {
@@ -542,14 +620,59 @@ impl Pattern<'_> {
                 _ => Err(anyhow!("Service is not running in a docker container")),
             },
 
+            Pattern::Plugin(plugin) => match plugin.matches(params) {
+                Ok(true) => Ok(MatchResult {
+                    ports: vec![],
+                    endpoint: None,
+                    mac_vendor: None,
+                    details: MatchDetails {
+                        reason: MatchReason::Reason(format!("Plugin {} matched", plugin.name())),
+                        confidence: plugin.definition().confidence,
+                    },
+                }),
+                Ok(false) => Err(anyhow!("Plugin {} did not match", plugin.name())),
+                Err(e) => {
+                    tracing::warn!("Plugin {} failed: {}", plugin.name(), e);
+                    Err(anyhow!("Plugin {} failed: {}", plugin.name(), e))
+                }
+            },
+
             Pattern::None => Err(anyhow!("No match pattern provided")),
         }
     }
 
+    /// Evaluate every node of the pattern, so it can be seen which parts did and didn't match
+    pub fn evaluate(&self, params: &DiscoverySessionServiceMatchParams) -> PatternEvaluation {
+        let (matched, reason, confidence) = match self.matches(params) {
+            Ok(result) => (
+                true,
+                result.details.reason_string(),
+                Some(result.details.confidence),
+            ),
+            Err(e) => (false, e.to_string(), None),
+        };
+
+        let children = match self {
+            Pattern::AnyOf(patterns) | Pattern::AllOf(patterns) => {
+                patterns.iter().map(|p| p.evaluate(params)).collect()
+            }
+            Pattern::Not(pattern) => vec![pattern.evaluate(params)],
+            _ => vec![],
+        };
+
+        PatternEvaluation {
+            pattern: PatternDiscriminants::from(self).into(),
+            matched,
+            reason,
+            confidence,
+            children,
+        }
+    }
+
     /// Get all ports which need to be scanned for a given service's match pattern
     pub fn ports(&self) -> Vec<PortBase> {
         match self {
-            Pattern::Port(port) => vec![*port],
+            Pattern::Port(port) | Pattern::Banner(port, _) => vec![*port],
             Pattern::AnyOf(patterns) | Pattern::AllOf(patterns) => {
                 patterns.iter().flat_map(|p| p.ports().to_vec()).collect()
             }
@@ -9,12 +9,14 @@ use crate::server::{
         base::{Service, ServiceBase},
         bindings::Binding,
         definitions::ServiceDefinition,
+        versions::ServiceVersion,
         virtualization::ServiceVirtualization,
     },
     shared::{
         storage::traits::{SqlValue, StorableEntity},
-        types::entities::EntitySource,
+        types::{attributes::EntityAttributes, entities::EntitySource},
     },
+    vulnerabilities::r#impl::base::ServiceVulnerability,
 };
 
 impl StorableEntity for Service {
@@ -24,6 +26,22 @@ impl StorableEntity for Service {
         "services"
     }
 
+    fn has_attributes() -> bool {
+        true
+    }
+
+    fn sortable_columns() -> &'static [&'static str] {
+        &["name"]
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "service_definition"]
+    }
+
+    fn soft_delete() -> bool {
+        true
+    }
+
     fn get_base(&self) -> Self::BaseData {
         self.base.clone()
     }
@@ -35,6 +53,7 @@ impl StorableEntity for Service {
             id: Uuid::new_v4(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
             base,
         }
     }
@@ -60,6 +79,7 @@ impl StorableEntity for Service {
             id,
             created_at,
             updated_at,
+            deleted_at: _,
             base:
                 Self::BaseData {
                     name,
@@ -69,6 +89,9 @@ impl StorableEntity for Service {
                     virtualization,
                     bindings,
                     source,
+                    versions,
+                    vulnerabilities,
+                    attributes,
                 },
         } = self.clone();
 
@@ -84,6 +107,9 @@ impl StorableEntity for Service {
                 "virtualization",
                 "bindings",
                 "source",
+                "versions",
+                "vulnerabilities",
+                "attributes",
             ],
             vec![
                 SqlValue::Uuid(id),
@@ -96,6 +122,9 @@ impl StorableEntity for Service {
                 SqlValue::OptionalServiceVirtualization(virtualization),
                 SqlValue::Bindings(bindings),
                 SqlValue::EntitySource(source),
+                SqlValue::Json(serde_json::to_value(&versions)?),
+                SqlValue::Json(serde_json::to_value(&vulnerabilities)?),
+                SqlValue::Json(serde_json::to_value(&attributes)?),
             ],
         ))
     }
@@ -113,11 +142,22 @@ impl StorableEntity for Service {
         let source: EntitySource =
             serde_json::from_value(row.get::<serde_json::Value, _>("source"))
                 .or(Err(Error::msg("Failed to deserialize source")))?;
+        let versions: Vec<ServiceVersion> =
+            serde_json::from_value(row.get::<serde_json::Value, _>("versions"))
+                .or(Err(Error::msg("Failed to deserialize versions")))?;
+        let vulnerabilities: Vec<ServiceVulnerability> =
+            serde_json::from_value(row.get::<serde_json::Value, _>("vulnerabilities"))
+                .or(Err(Error::msg("Failed to deserialize vulnerabilities")))?;
+
+        let attributes: EntityAttributes =
+            serde_json::from_value(row.get::<serde_json::Value, _>("attributes"))
+                .or(Err(Error::msg("Failed to deserialize attributes")))?;
 
         Ok(Service {
             id: row.get("id"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            deleted_at: row.get("deleted_at"),
             base: ServiceBase {
                 name: row.get("name"),
                 network_id: row.get("network_id"),
@@ -126,6 +166,9 @@ impl StorableEntity for Service {
                 virtualization,
                 bindings,
                 source,
+                versions,
+                vulnerabilities,
+                attributes,
             },
         })
     }
@@ -0,0 +1,105 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Root device from a UPnP device description, fetched from the `LOCATION` of an SSDP reply
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpnpDevice {
+    /// URL the description was fetched from
+    pub location: String,
+    pub device_type: Option<String>,
+    pub friendly_name: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model_name: Option<String>,
+}
+
+impl UpnpDevice {
+    /// Read the root device's fields from a description document. Embedded devices come after
+    /// the root device's own fields, so the first occurrence of each element is used.
+    pub fn from_description(location: &str, xml: &str) -> Self {
+        Self {
+            location: location.to_string(),
+            device_type: element(xml, "deviceType"),
+            friendly_name: element(xml, "friendlyName"),
+            manufacturer: element(xml, "manufacturer"),
+            model_name: element(xml, "modelName"),
+        }
+    }
+
+    /// `manufacturer modelName`, if either is known
+    pub fn model(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.manufacturer, &self.model_name]
+            .into_iter()
+            .flatten()
+            .map(|s| s.as_str())
+            .collect();
+
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+
+    /// Whether the friendly name, manufacturer, model or device type contains `value`,
+    /// ignoring case. Returns the field that matched.
+    pub fn find(&self, value: &str) -> Option<&str> {
+        let value = value.to_lowercase();
+
+        [
+            &self.friendly_name,
+            &self.manufacturer,
+            &self.model_name,
+            &self.device_type,
+        ]
+        .into_iter()
+        .flatten()
+        .find(|field| field.to_lowercase().contains(&value))
+        .map(|field| field.as_str())
+    }
+}
+
+fn element(xml: &str, name: &str) -> Option<String> {
+    let re = Regex::new(&format!(
+        r"(?s)<(?:\w+:)?{name}\s*>(.*?)</(?:\w+:)?{name}\s*>"
+    ))
+    .ok()?;
+
+    re.captures(xml)
+        .map(|c| unescape(c[1].trim()))
+        .filter(|s| !s.is_empty())
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_root_device_fields() {
+        let xml = r#"<?xml version="1.0"?>
+            <root xmlns="urn:schemas-upnp-org:device-1-0">
+              <device>
+                <deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType>
+                <friendlyName>Living Room TV</friendlyName>
+                <manufacturer>Samsung Electronics</manufacturer>
+                <modelName>UE55 &amp; Co</modelName>
+                <deviceList>
+                  <device><friendlyName>Embedded</friendlyName></device>
+                </deviceList>
+              </device>
+            </root>"#;
+
+        let device = UpnpDevice::from_description("http://10.0.0.5:9197/dmr", xml);
+
+        assert_eq!(device.friendly_name.as_deref(), Some("Living Room TV"));
+        assert_eq!(
+            device.model().as_deref(),
+            Some("Samsung Electronics UE55 & Co")
+        );
+        assert_eq!(device.find("samsung"), Some("Samsung Electronics"));
+        assert_eq!(device.find("Roku"), None);
+    }
+}
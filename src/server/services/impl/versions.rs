@@ -0,0 +1,181 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::server::{
+    hosts::r#impl::ports::PortBase,
+    services::r#impl::{banners::PortBanner, endpoints::EndpointResponse},
+};
+
+/// Software and version a service announced in a banner or HTTP response
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ServiceVersion {
+    /// e.g. "nginx"
+    pub product: String,
+    /// e.g. "1.18.0"
+    pub version: String,
+    /// CPE 2.3 name the vulnerability feed is searched with
+    pub cpe: String,
+}
+
+/// Product, CPE vendor and CPE product, and a pattern capturing the version. Patterns accept
+/// both raw banners, e.g. `nginx/1.18.0`, and nmap's product and version, e.g. `nginx 1.18.0`.
+const PRODUCTS: &[(&str, &str, &str, &str)] = &[
+    ("OpenSSH", "openbsd", "openssh", r"OpenSSH[_ -](\d+\.\d+)"),
+    (
+        "Dropbear",
+        "dropbear_ssh_project",
+        "dropbear_ssh",
+        r"(?i)dropbear(?:_| sshd )(\d+\.\d+)",
+    ),
+    ("nginx", "f5", "nginx", r"nginx[/ ](\d+\.\d+\.\d+)"),
+    (
+        "Apache HTTP Server",
+        "apache",
+        "http_server",
+        r"Apache(?:/| httpd )(\d+\.\d+\.\d+)",
+    ),
+    (
+        "Apache Tomcat",
+        "apache",
+        "tomcat",
+        r"Apache Tomcat/(\d+\.\d+\.\d+)",
+    ),
+    (
+        "lighttpd",
+        "lighttpd",
+        "lighttpd",
+        r"lighttpd[/ ](\d+\.\d+\.\d+)",
+    ),
+    (
+        "Microsoft IIS",
+        "microsoft",
+        "internet_information_services",
+        r"Microsoft[- ]IIS(?:/| httpd )(\d+\.\d+)",
+    ),
+    ("Jetty", "eclipse", "jetty", r"Jetty\((\d+\.\d+\.\d+)"),
+    (
+        "OpenSSL",
+        "openssl",
+        "openssl",
+        r"OpenSSL/(\d+\.\d+\.\d+[a-z]?)",
+    ),
+    ("PHP", "php", "php", r"PHP/(\d+\.\d+\.\d+)"),
+    ("vsftpd", "beasts", "vsftpd", r"(?i)vsftpd (\d+\.\d+\.\d+)"),
+    (
+        "ProFTPD",
+        "proftpd",
+        "proftpd",
+        r"ProFTPD (\d+\.\d+\.\d+[a-z]?)",
+    ),
+    (
+        "Exim",
+        "exim",
+        "exim",
+        r"Exim (?:smtpd )?(\d+\.\d+(?:\.\d+)?)",
+    ),
+    ("Redis", "redis", "redis", r"redis_version:(\d+\.\d+\.\d+)"),
+    ("MariaDB", "mariadb", "mariadb", r"(\d+\.\d+\.\d+)-MariaDB"),
+    (
+        "Samba",
+        "samba",
+        "samba",
+        r"Samba (?:smbd )?(\d+\.\d+\.\d+)",
+    ),
+];
+
+static PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    PRODUCTS
+        .iter()
+        .map(|(_, _, _, pattern)| Regex::new(pattern).expect("Invalid version pattern"))
+        .collect()
+});
+
+impl ServiceVersion {
+    /// Versions in banners and endpoint responses of the given ports, each product once
+    pub fn detect(
+        ports: &[PortBase],
+        banners: &[PortBanner],
+        endpoint_responses: &[EndpointResponse],
+    ) -> Vec<Self> {
+        let texts = banners
+            .iter()
+            .filter(|b| ports.contains(&b.port_base))
+            .map(|b| b.banner.as_str())
+            .chain(
+                endpoint_responses
+                    .iter()
+                    .filter(|r| ports.contains(&r.endpoint.port_base))
+                    .map(|r| r.response.as_str()),
+            );
+
+        let mut versions: Vec<Self> = Vec::new();
+        for text in texts {
+            for ((product, vendor, cpe_product, _), pattern) in PRODUCTS.iter().zip(&*PATTERNS) {
+                let Some(version) = pattern.captures(text).and_then(|c| c.get(1)) else {
+                    continue;
+                };
+                if versions.iter().any(|v| v.product == *product) {
+                    continue;
+                }
+
+                let version = version.as_str().to_string();
+                versions.push(Self {
+                    product: product.to_string(),
+                    cpe: format!("cpe:2.3:a:{}:{}:{}", vendor, cpe_product, version),
+                    version,
+                });
+            }
+        }
+
+        versions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banner(port_base: PortBase, banner: &str) -> PortBanner {
+        PortBanner {
+            port_base,
+            banner: banner.to_string(),
+        }
+    }
+
+    #[test]
+    fn detects_ssh_and_web_server_versions() {
+        let banners = [
+            banner(PortBase::Ssh, "SSH-2.0-OpenSSH_8.9p1 Ubuntu-3ubuntu0.4"),
+            banner(
+                PortBase::new_tcp(80),
+                "HTTP/1.1 200 OK\r\nServer: Apache/2.4.41 (Ubuntu) OpenSSL/1.1.1f",
+            ),
+        ];
+
+        let versions =
+            ServiceVersion::detect(&[PortBase::Ssh, PortBase::new_tcp(80)], &banners, &[]);
+        let found: Vec<(&str, &str)> = versions
+            .iter()
+            .map(|v| (v.product.as_str(), v.version.as_str()))
+            .collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("OpenSSH", "8.9"),
+                ("Apache HTTP Server", "2.4.41"),
+                ("OpenSSL", "1.1.1f")
+            ]
+        );
+        assert_eq!(versions[0].cpe, "cpe:2.3:a:openbsd:openssh:8.9");
+    }
+
+    #[test]
+    fn ignores_other_ports() {
+        let banners = [banner(PortBase::Ssh, "SSH-2.0-OpenSSH_9.6")];
+
+        assert!(ServiceVersion::detect(&[PortBase::new_tcp(80)], &banners, &[]).is_empty());
+    }
+}
@@ -134,6 +134,21 @@ impl ServiceService {
             existing_service.base.virtualization = Some(virtualization.clone())
         }
 
+        // Vulnerabilities of versions that changed are looked up again on the next feed sync
+        if !new_service_data.base.versions.is_empty() {
+            let previous_versions = std::mem::take(&mut existing_service.base.versions);
+            let new_versions = &new_service_data.base.versions;
+            existing_service
+                .base
+                .vulnerabilities
+                .retain(|vulnerability| {
+                    new_versions.iter().any(|v| {
+                        v.product == vulnerability.product && previous_versions.contains(v)
+                    })
+                });
+            existing_service.base.versions = new_versions.clone();
+        }
+
         existing_service.base.source = match (
             existing_service.base.source,
             new_service_data.base.source.clone(),
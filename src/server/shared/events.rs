@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EntityOperation {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// A change to a stored entity, published by storage so every code path that writes it is
+/// covered
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityEvent {
+    /// e.g. `host`
+    pub entity: &'static str,
+    pub operation: EntityOperation,
+    pub entity_id: Uuid,
+    pub network_id: Option<Uuid>,
+    /// The entity after the change, or as it was before a deletion
+    pub data: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl EntityEvent {
+    /// e.g. `host.created`
+    pub fn name(&self) -> String {
+        format!("{}.{}", self.entity, self.operation)
+    }
+}
+
+/// Broadcast of entity changes. Subscribers that fall behind miss events rather than slowing
+/// down writes.
+pub struct EventBus {
+    tx: broadcast::Sender<EntityEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tx: broadcast::channel(capacity).0,
+        }
+    }
+
+    pub fn publish(&self, event: EntityEvent) {
+        // Nobody listening is fine
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EntityEvent> {
+        self.tx.subscribe()
+    }
+}
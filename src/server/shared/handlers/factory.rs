@@ -1,22 +1,60 @@
+use crate::server::auth::middleware::AuthenticatedOperator;
 use crate::server::config::PublicConfigResponse;
 use crate::server::discovery::r#impl::types::DiscoveryType;
 use crate::server::groups::r#impl::types::GroupType;
 use crate::server::hosts::r#impl::ports::PortBase;
 use crate::server::services::definitions::ServiceDefinitionRegistry;
+use crate::server::services::r#impl::categories::ServiceCategory;
 use crate::server::shared::entities::Entity;
 use crate::server::shared::types::metadata::{MetadataProvider, MetadataRegistry};
 use crate::server::subnets::r#impl::types::SubnetType;
 use crate::server::topology::types::edges::EdgeType;
 use crate::server::{
-    auth::handlers as auth_handlers, config::AppState, daemons::handlers as daemon_handlers,
-    discovery::handlers as discovery_handlers, groups::handlers as group_handlers,
-    hosts::handlers as host_handlers, networks::handlers as network_handlers,
-    services::handlers as service_handlers, shared::types::api::ApiResponse,
-    subnets::handlers as subnet_handlers, topology::handlers as topology_handlers,
+    apply::handlers as apply_handlers,
+    audit::handlers as audit_handlers,
+    auth::handlers as auth_handlers,
+    backups::handlers as backup_handlers,
+    config::AppState,
+    daemons::handlers as daemon_handlers,
+    demo::handlers as demo_handlers,
+    diagnostics::handlers as diagnostic_handlers,
+    digests::handlers as digest_handlers,
+    discovery::handlers as discovery_handlers,
+    exports::handlers as export_handlers,
+    grafana::handlers as grafana_handlers,
+    graphql::handlers as graphql_handlers,
+    groups::handlers as group_handlers,
+    hosts::handlers as host_handlers,
+    integrations::handlers as integration_handlers,
+    live::handlers as live_handlers,
+    maintenance::handlers as maintenance_handlers,
+    monitoring::handlers as monitoring_handlers,
+    network_settings::handlers as network_settings_handlers,
+    networks::handlers as network_handlers,
+    node_pins::handlers as node_pin_handlers,
+    notes::handlers as note_handlers,
+    notifications::handlers as notification_handlers,
+    policies::handlers as policy_handlers,
+    reports::handlers as report_handlers,
+    scan_profiles::handlers as scan_profile_handlers,
+    service_definitions::handlers as service_definition_handlers,
+    services::handlers as service_handlers,
+    shared::types::api::{ApiError, ApiResponse, ApiResult},
+    ssh_credentials::handlers as ssh_credential_handlers,
+    subnets::handlers as subnet_handlers,
+    topology::handlers as topology_handlers,
+    topology_snapshots::handlers as topology_snapshot_handlers,
     users::handlers as user_handlers,
+    webhooks::handlers as webhook_handlers,
+    winrm_credentials::handlers as winrm_credential_handlers,
 };
-use axum::extract::State;
-use axum::{Json, Router, routing::get};
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, header::ACCEPT_LANGUAGE};
+use axum::{
+    Json, Router,
+    routing::{get, post},
+};
+use serde::Deserialize;
 use std::sync::Arc;
 use strum::{IntoDiscriminant, IntoEnumIterator};
 
@@ -26,19 +64,71 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .nest("/api/groups", group_handlers::create_router())
         .nest("/api/daemons", daemon_handlers::create_router())
         .nest("/api/discovery", discovery_handlers::create_router())
+        .nest("/api/diagnostics", diagnostic_handlers::create_router())
         .nest("/api/subnets", subnet_handlers::create_router())
+        .nest(
+            "/api/ssh-credentials",
+            ssh_credential_handlers::create_router(),
+        )
+        .nest(
+            "/api/winrm-credentials",
+            winrm_credential_handlers::create_router(),
+        )
+        .nest(
+            "/api/topology/snapshots",
+            topology_snapshot_handlers::create_router(),
+        )
+        .nest("/api/topology/pins", node_pin_handlers::create_router())
         .nest("/api/topology", topology_handlers::create_router())
         .nest("/api/services", service_handlers::create_router())
+        .nest("/api/notes", note_handlers::create_router())
+        .nest(
+            "/api/service-definitions",
+            service_definition_handlers::create_router(),
+        )
         .nest("/api/networks", network_handlers::create_router())
         .nest("/api/users", user_handlers::create_router())
         .nest("/api/auth", auth_handlers::create_router())
+        .nest("/api/maintenance", maintenance_handlers::create_router())
+        .nest("/api/health-checks", monitoring_handlers::create_router())
+        .nest("/api/apply", apply_handlers::create_router())
+        .nest("/api/export", export_handlers::create_router())
+        .nest("/api/grafana", grafana_handlers::create_router())
+        .nest("/api/graphql", graphql_handlers::create_router())
+        .nest("/api/integrations", integration_handlers::create_router())
+        .nest("/api/webhooks", webhook_handlers::create_router())
+        .nest("/api/notifications", notification_handlers::create_router())
+        .nest("/api/policies", policy_handlers::create_router())
+        .nest("/api/scan-profiles", scan_profile_handlers::create_router())
+        .nest("/api/digests", digest_handlers::create_router())
+        .nest("/api/reports", report_handlers::create_router())
+        .nest("/api/demo", demo_handlers::create_router())
+        .nest("/api/system/backups", backup_handlers::create_router())
+        .nest("/api/audit", audit_handlers::create_router())
+        .nest(
+            "/api/network-settings",
+            network_settings_handlers::create_router(),
+        )
+        .nest("/api/ws", live_handlers::create_router())
         .route("/api/health", get(get_health))
         .route("/api/metadata", get(get_metadata_registry))
+        .route("/api/metadata/locales", get(get_metadata_locales))
         .route("/api/config", get(get_public_config))
+        .route("/api/config/reload", post(reload_config))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MetadataQuery {
+    /// Locale to translate names and descriptions into; falls back to Accept-Language
+    pub locale: Option<String>,
 }
 
-async fn get_metadata_registry() -> Json<ApiResponse<MetadataRegistry>> {
-    let registry = MetadataRegistry {
+async fn get_metadata_registry(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<MetadataQuery>,
+    headers: HeaderMap,
+) -> Json<ApiResponse<MetadataRegistry>> {
+    let mut registry = MetadataRegistry {
         service_definitions: ServiceDefinitionRegistry::all_service_definitions()
             .iter()
             .map(|t| t.to_metadata())
@@ -51,11 +141,29 @@ async fn get_metadata_registry() -> Json<ApiResponse<MetadataRegistry>> {
         entities: Entity::iter().map(|e| e.to_metadata()).collect(),
         ports: PortBase::iter().map(|p| p.to_metadata()).collect(),
         discovery_types: DiscoveryType::iter().map(|d| d.to_metadata()).collect(),
+        service_categories: ServiceCategory::iter().map(|c| c.to_metadata()).collect(),
     };
 
+    let requested = query.locale.or_else(|| {
+        headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+    });
+
+    if let Some(catalog) = requested.and_then(|locale| state.locales.resolve(&locale)) {
+        registry.localize(catalog);
+    }
+
     Json(ApiResponse::success(registry))
 }
 
+async fn get_metadata_locales(
+    State(state): State<Arc<AppState>>,
+) -> Json<ApiResponse<Vec<String>>> {
+    Json(ApiResponse::success(state.locales.locales()))
+}
+
 async fn get_health() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("Netvisor Server Running".to_string()))
 }
@@ -63,18 +171,30 @@ async fn get_health() -> Json<ApiResponse<String>> {
 pub async fn get_public_config(
     State(state): State<Arc<AppState>>,
 ) -> Json<ApiResponse<PublicConfigResponse>> {
+    let reloadable = state.reloadable();
+
     Json(ApiResponse::success(PublicConfigResponse {
         server_port: state.config.server_port,
-        disable_registration: state.config.disable_registration,
-        oidc_enabled: state.config.oidc_client_id.is_some()
-            && state.config.oidc_client_secret.is_some()
-            && state.config.oidc_issuer_url.is_some()
-            && state.config.oidc_provider_name.is_some()
-            && state.config.oidc_redirect_url.is_some(),
-        oidc_provider_name: state
+        disable_registration: reloadable.config.disable_registration,
+        oidc_enabled: reloadable.oidc_client.is_some()
+            && reloadable.config.oidc_provider_name.is_some(),
+        oidc_provider_name: reloadable
             .config
             .oidc_provider_name
             .clone()
             .unwrap_or("OIDC Provider".to_string()),
     }))
 }
+
+/// Re-read the reloadable settings (log level, registration, operators, OIDC, SMTP) without a
+/// restart
+async fn reload_config(
+    State(state): State<Arc<AppState>>,
+    _operator: AuthenticatedOperator,
+) -> ApiResult<Json<ApiResponse<Vec<&'static str>>>> {
+    let changed = state
+        .reload_config()
+        .map_err(|e| ApiError::internal_error(&format!("Failed to reload config: {}", e)))?;
+
+    Ok(Json(ApiResponse::success(changed)))
+}
@@ -1,15 +1,18 @@
 use crate::server::{
-    auth::middleware::AuthenticatedUser,
+    auth::middleware::{AuthenticatedEntity, AuthenticatedUser},
     config::AppState,
     shared::{
         services::traits::CrudService,
-        storage::{filter::EntityFilter, traits::StorableEntity},
+        storage::{
+            filter::{EntityFilter, SortDirection},
+            traits::StorableEntity,
+        },
         types::api::{ApiError, ApiResponse, ApiResult},
     },
 };
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json,
     routing::{delete, get, post, put},
 };
@@ -35,6 +38,164 @@ where
     fn validate(&self) -> Result<(), String> {
         Ok(())
     }
+
+    /// Network the entity belongs to. The standard handlers only let the network's owner and
+    /// members see or change it; entities without a network aren't scoped.
+    fn network_id(&self) -> Option<Uuid> {
+        None
+    }
+}
+
+/// Not found rather than forbidden for entities in networks the user has no access to, so
+/// other tenants' entities can't be probed for
+pub async fn check_access<T>(state: &AppState, user_id: &Uuid, entity: &T) -> ApiResult<()>
+where
+    T: CrudHandlers,
+{
+    let Some(network_id) = entity.network_id() else {
+        return Ok(());
+    };
+
+    let role = state
+        .services
+        .network_member_service
+        .role(&network_id, user_id)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?;
+
+    match role {
+        Some(_) => Ok(()),
+        None => Err(ApiError::not_found(format!(
+            "{} '{}' not found",
+            T::entity_name(),
+            entity.id()
+        ))),
+    }
+}
+
+/// As `check_access`, for endpoints daemons call too: a daemon can only reach entities in its
+/// own network
+pub async fn check_entity_access<T>(
+    state: &AppState,
+    authenticated: &AuthenticatedEntity,
+    entity: &T,
+) -> ApiResult<()>
+where
+    T: CrudHandlers,
+{
+    match authenticated {
+        AuthenticatedEntity::User(user_id) => check_access(state, user_id, entity).await,
+        AuthenticatedEntity::Daemon(network_id) => match entity.network_id() {
+            Some(entity_network_id) if entity_network_id != *network_id => Err(
+                ApiError::not_found(format!("{} '{}' not found", T::entity_name(), entity.id())),
+            ),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// The entity with the given id, for handlers beyond the standard ones. Not found if it doesn't
+/// exist or is in a network the user has no access to.
+pub async fn get_accessible<T>(state: &AppState, user_id: &Uuid, id: &Uuid) -> ApiResult<T>
+where
+    T: CrudHandlers,
+{
+    let entity = T::get_service(state)
+        .get_by_id(id)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("{} '{}' not found", T::entity_name(), id)))?;
+
+    check_access(state, user_id, &entity).await?;
+
+    Ok(entity)
+}
+
+/// Query parameters of list endpoints
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ListQuery {
+    /// Include soft-deleted entities, marked by their `deleted_at`
+    #[serde(default)]
+    pub include_deleted: bool,
+    /// Page size; everything is returned when unset
+    pub limit: Option<u32>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<Uuid>,
+    /// Comma-separated columns, each prefixed with `-` to sort descending, e.g. `-updated_at,name`
+    pub sort: Option<String>,
+    /// Text to search for in the entity's names and descriptions
+    pub q: Option<String>,
+    /// Only entities with this tag
+    pub tag: Option<String>,
+    /// Only entities with a custom field of this value, as `key:value`
+    pub field: Option<String>,
+}
+
+impl ListQuery {
+    pub const MAX_LIMIT: u32 = 1000;
+
+    /// Add the query's paging, sorting and search to a filter for `T`
+    pub fn apply<T: StorableEntity>(&self, mut filter: EntityFilter) -> ApiResult<EntityFilter> {
+        if self.include_deleted {
+            filter = filter.include_deleted();
+        }
+
+        if let Some(limit) = self.limit {
+            if !(1..=Self::MAX_LIMIT).contains(&limit) {
+                return Err(ApiError::bad_request(&format!(
+                    "limit must be between 1 and {}",
+                    Self::MAX_LIMIT
+                )));
+            }
+            filter = filter.limit(limit);
+        }
+
+        if let Some(cursor) = self.cursor {
+            filter = filter.after(cursor);
+        }
+
+        for key in self.sort.iter().flat_map(|sort| sort.split(',')) {
+            let key = key.trim();
+            let (name, direction) = match key.strip_prefix('-') {
+                Some(name) => (name, SortDirection::Desc),
+                None => (key, SortDirection::Asc),
+            };
+
+            let column = ["created_at", "updated_at"]
+                .iter()
+                .chain(T::sortable_columns())
+                .copied()
+                .find(|column| *column == name)
+                .ok_or_else(|| {
+                    ApiError::bad_request(&format!("Can't sort {} by '{}'", T::table_name(), name))
+                })?;
+            filter = filter.sort_by(column, direction);
+        }
+
+        if (self.tag.is_some() || self.field.is_some()) && !T::has_attributes() {
+            return Err(ApiError::bad_request(&format!(
+                "{} have no tags or custom fields",
+                T::table_name()
+            )));
+        }
+
+        if let Some(tag) = &self.tag {
+            filter = filter.tag(tag);
+        }
+
+        if let Some(field) = &self.field {
+            let (key, value) = field.split_once(':').ok_or_else(|| {
+                ApiError::bad_request("Custom field filters must look like 'key:value'")
+            })?;
+            filter = filter.custom_field(key, value);
+        }
+
+        if let Some(q) = self.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+            filter = filter.search(q);
+        }
+
+        Ok(filter)
+    }
 }
 
 /// Create a standard CRUD router
@@ -52,7 +213,7 @@ where
 
 pub async fn create_handler<T>(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Json(request): Json<T>,
 ) -> ApiResult<Json<ApiResponse<T>>>
 where
@@ -66,6 +227,8 @@ where
         )));
     }
 
+    check_access(&state, &user.0, &request).await?;
+
     let service = T::get_service(&state);
     let created = service
         .create(request)
@@ -78,11 +241,12 @@ where
 pub async fn get_all_handler<T>(
     State(state): State<Arc<AppState>>,
     user: AuthenticatedUser,
+    Query(query): Query<ListQuery>,
 ) -> ApiResult<Json<ApiResponse<Vec<T>>>>
 where
     T: CrudHandlers + 'static,
 {
-    let user_filter = EntityFilter::unfiltered().user_id(&user.0);
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
 
     let network_ids: Vec<Uuid> = state
         .services
@@ -94,20 +258,20 @@ where
         .map(|n| n.id())
         .collect();
 
-    let network_filter = EntityFilter::unfiltered().network_ids(&network_ids);
+    let network_filter = query.apply::<T>(EntityFilter::unfiltered().network_ids(&network_ids))?;
 
     let service = T::get_service(&state);
-    let entities = service
-        .get_all(network_filter)
+    let page = service
+        .get_page(network_filter)
         .await
         .map_err(|e| ApiError::internal_error(&e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(entities)))
+    Ok(Json(ApiResponse::page(page)))
 }
 
 pub async fn get_by_id_handler<T>(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<T>>>
 where
@@ -120,27 +284,41 @@ where
         .map_err(|e| ApiError::internal_error(&e.to_string()))?
         .ok_or_else(|| ApiError::not_found(format!("{} '{}' not found", T::entity_name(), id)))?;
 
+    check_access(&state, &user.0, &entity).await?;
+
     Ok(Json(ApiResponse::success(entity)))
 }
 
 pub async fn update_handler<T>(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(id): Path<Uuid>,
     Json(mut request): Json<T>,
 ) -> ApiResult<Json<ApiResponse<T>>>
 where
     T: CrudHandlers + 'static,
 {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&format!(
+            "{} validation failed: {}",
+            T::entity_name(),
+            err
+        )));
+    }
+
     let service = T::get_service(&state);
 
     // Verify entity exists
-    service
+    let existing = service
         .get_by_id(&id)
         .await
         .map_err(|e| ApiError::internal_error(&e.to_string()))?
         .ok_or_else(|| ApiError::not_found(format!("{} '{}' not found", T::entity_name(), id)))?;
 
+    // Both where the entity is and where the update would move it
+    check_access(&state, &user.0, &existing).await?;
+    check_access(&state, &user.0, &request).await?;
+
     let updated = service
         .update(&mut request)
         .await
@@ -151,7 +329,7 @@ where
 
 pub async fn delete_handler<T>(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<()>>>
 where
@@ -160,12 +338,14 @@ where
     let service = T::get_service(&state);
 
     // Verify entity exists
-    service
+    let existing = service
         .get_by_id(&id)
         .await
         .map_err(|e| ApiError::internal_error(&e.to_string()))?
         .ok_or_else(|| ApiError::not_found(format!("{} '{}' not found", T::entity_name(), id)))?;
 
+    check_access(&state, &user.0, &existing).await?;
+
     service
         .delete(&id)
         .await
@@ -173,3 +353,32 @@ where
 
     Ok(Json(ApiResponse::success(())))
 }
+
+/// Take a soft-deleted entity out of the trash
+pub async fn restore_handler<T>(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<T>>>
+where
+    T: CrudHandlers + 'static,
+{
+    let service = T::get_service(&state);
+
+    let trashed = service
+        .get_one(EntityFilter::unfiltered().entity_id(&id).include_deleted())
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?;
+    if let Some(trashed) = &trashed {
+        check_access(&state, &user.0, trashed).await?;
+    }
+    let restored = service
+        .restore(&id)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?
+        .ok_or_else(|| {
+            ApiError::not_found(format!("{} '{}' is not in the trash", T::entity_name(), id))
+        })?;
+
+    Ok(Json(ApiResponse::success(restored)))
+}
@@ -1,4 +1,7 @@
+pub mod csv;
+pub mod email;
 pub mod entities;
+pub mod events;
 pub mod handlers;
 pub mod services;
 pub mod storage;
@@ -1,10 +1,47 @@
 use crate::server::{
-    api_keys::service::ApiKeyService, auth::service::AuthService, daemons::service::DaemonService,
-    discovery::service::DiscoveryService, groups::service::GroupService,
-    hosts::service::HostService, networks::service::NetworkService,
-    services::service::ServiceService, shared::storage::factory::StorageFactory,
-    subnets::service::SubnetService, topology::service::main::TopologyService,
+    api_keys::service::ApiKeyService,
+    apply::service::ApplyService,
+    archives::service::ArchiveService,
+    audit::service::AuditService,
+    auth::service::AuthService,
+    daemons::service::DaemonService,
+    demo::service::DemoService,
+    diagnostics::service::DiagnosticService,
+    digests::service::DigestService,
+    discovery::service::DiscoveryService,
+    exports::service::ExportService,
+    grafana::service::GrafanaService,
+    groups::service::GroupService,
+    hosts::service::HostService,
+    integrations::{
+        dns::service::DnsZoneService, firewall::service::FirewallService,
+        home_assistant::registry::HomeAssistantRegistryService, ipam::service::IpamService,
+        proxmox::service::ProxmoxService, uptime_kuma::service::UptimeKumaService,
+        vmware::service::VmwareService,
+    },
+    invites::service::InviteService,
+    maintenance::service::MaintenanceService,
+    monitoring::service::MonitoringService,
+    network_members::service::NetworkMemberService,
+    network_settings::service::NetworkSettingsService,
+    networks::service::NetworkService,
+    node_pins::service::NodePinService,
+    notes::service::NoteService,
+    notifications::service::{AlertService, ChannelService},
+    policies::service::PolicyService,
+    reports::service::ReportService,
+    scan_profiles::service::ScanProfileService,
+    service_definitions::service::CustomServiceDefinitionService,
+    services::service::ServiceService,
+    shared::storage::factory::StorageFactory,
+    ssh_credentials::service::SshCredentialService,
+    subnets::service::SubnetService,
+    topology::service::main::TopologyService,
+    topology_snapshots::service::TopologySnapshotService,
     users::service::UserService,
+    vulnerabilities::service::VulnerabilityService,
+    webhooks::service::WebhookService,
+    winrm_credentials::service::WinrmCredentialService,
 };
 use anyhow::Result;
 use std::sync::Arc;
@@ -13,25 +50,96 @@ pub struct ServiceFactory {
     pub user_service: Arc<UserService>,
     pub auth_service: Arc<AuthService>,
     pub network_service: Arc<NetworkService>,
+    pub network_member_service: Arc<NetworkMemberService>,
+    pub invite_service: Arc<InviteService>,
     pub host_service: Arc<HostService>,
     pub group_service: Arc<GroupService>,
     pub subnet_service: Arc<SubnetService>,
     pub daemon_service: Arc<DaemonService>,
+    pub diagnostic_service: Arc<DiagnosticService>,
     pub topology_service: Arc<TopologyService>,
+    pub node_pin_service: Arc<NodePinService>,
+    pub note_service: Arc<NoteService>,
+    pub topology_snapshot_service: Arc<TopologySnapshotService>,
     pub service_service: Arc<ServiceService>,
     pub discovery_service: Arc<DiscoveryService>,
     pub api_key_service: Arc<ApiKeyService>,
+    pub maintenance_service: Arc<MaintenanceService>,
+    pub apply_service: Arc<ApplyService>,
+    pub archive_service: Arc<ArchiveService>,
+    pub export_service: Arc<ExportService>,
+    pub grafana_service: Arc<GrafanaService>,
+    pub uptime_kuma_service: Arc<UptimeKumaService>,
+    pub home_assistant_registry_service: Arc<HomeAssistantRegistryService>,
+    pub ipam_service: Arc<IpamService>,
+    pub proxmox_service: Arc<ProxmoxService>,
+    pub vmware_service: Arc<VmwareService>,
+    pub firewall_service: Arc<FirewallService>,
+    pub dns_zone_service: Arc<DnsZoneService>,
+    pub ssh_credential_service: Arc<SshCredentialService>,
+    pub winrm_credential_service: Arc<WinrmCredentialService>,
+    pub webhook_service: Arc<WebhookService>,
+    pub digest_service: Arc<DigestService>,
+    pub report_service: Arc<ReportService>,
+    pub network_settings_service: Arc<NetworkSettingsService>,
+    pub demo_service: Arc<DemoService>,
+    pub custom_service_definition_service: Arc<CustomServiceDefinitionService>,
+    pub monitoring_service: Arc<MonitoringService>,
+    pub channel_service: Arc<ChannelService>,
+    pub alert_service: Arc<AlertService>,
+    pub audit_service: Arc<AuditService>,
+    pub vulnerability_service: Arc<VulnerabilityService>,
+    pub policy_service: Arc<PolicyService>,
+    pub scan_profile_service: Arc<ScanProfileService>,
 }
 
 impl ServiceFactory {
     pub async fn new(storage: &StorageFactory) -> Result<Self> {
         let api_key_service = Arc::new(ApiKeyService::new(storage.api_keys.clone()));
-        let daemon_service = Arc::new(DaemonService::new(storage.daemons.clone()));
+        let audit_service = Arc::new(AuditService::new(storage.audit_log.clone()));
+        let network_settings_service = Arc::new(NetworkSettingsService::new(
+            storage.network_settings.clone(),
+        ));
+        let ssh_credential_service = Arc::new(SshCredentialService::new(
+            storage.ssh_credentials.clone(),
+            storage.hosts.clone(),
+            storage.subnets.clone(),
+        ));
+        let winrm_credential_service = Arc::new(WinrmCredentialService::new(
+            storage.winrm_credentials.clone(),
+            storage.hosts.clone(),
+            storage.subnets.clone(),
+        ));
+        let scan_profile_service = Arc::new(ScanProfileService::new(storage.scan_profiles.clone()));
+        let daemon_service = Arc::new(DaemonService::new(
+            storage.daemons.clone(),
+            network_settings_service.clone(),
+            storage.dns_zone_settings.clone(),
+            storage.scan_profiles.clone(),
+            ssh_credential_service.clone(),
+            winrm_credential_service.clone(),
+        ));
+        let diagnostic_service = Arc::new(DiagnosticService::new(daemon_service.clone()));
         let group_service = Arc::new(GroupService::new(storage.groups.clone()));
+        let webhook_service = Arc::new(WebhookService::new(
+            storage.webhooks.clone(),
+            storage.webhook_deliveries.clone(),
+        ));
+        let channel_service = Arc::new(ChannelService::new(storage.notification_channels.clone()));
+        let alert_service = Arc::new(AlertService::new(
+            storage.alert_rules.clone(),
+            channel_service.clone(),
+        ));
 
         // Already implements Arc internally due to scheduler + sessions
-        let discovery_service =
-            DiscoveryService::new(storage.discovery.clone(), daemon_service.clone()).await?;
+        let discovery_service = DiscoveryService::new(
+            storage.discovery.clone(),
+            storage.discovery_sessions.clone(),
+            storage.subnets.clone(),
+            daemon_service.clone(),
+            network_settings_service.clone(),
+        )
+        .await?;
 
         let service_service = Arc::new(ServiceService::new(
             storage.services.clone(),
@@ -40,8 +148,11 @@ impl ServiceFactory {
 
         let host_service = Arc::new(HostService::new(
             storage.hosts.clone(),
+            storage.host_changes.clone(),
             service_service.clone(),
             daemon_service.clone(),
+            network_settings_service.clone(),
+            discovery_service.clone(),
         ));
 
         let subnet_service = Arc::new(SubnetService::new(
@@ -51,11 +162,35 @@ impl ServiceFactory {
 
         let _ = service_service.set_host_service(host_service.clone());
 
+        let custom_service_definition_service = Arc::new(CustomServiceDefinitionService::new(
+            storage.custom_service_definitions.clone(),
+            service_service.clone(),
+        ));
+
+        let node_pin_service = Arc::new(NodePinService::new(storage.node_pins.clone()));
+        let note_service = Arc::new(NoteService::new(
+            storage.notes.clone(),
+            storage.note_revisions.clone(),
+            storage.note_attachments.clone(),
+        ));
+
         let topology_service = Arc::new(TopologyService::new(
             host_service.clone(),
             subnet_service.clone(),
             group_service.clone(),
             service_service.clone(),
+            node_pin_service.clone(),
+        ));
+        let topology_snapshot_service = Arc::new(TopologySnapshotService::new(
+            storage.topology_snapshots.clone(),
+            topology_service.clone(),
+        ));
+
+        let maintenance_service = Arc::new(MaintenanceService::new(
+            host_service.clone(),
+            subnet_service.clone(),
+            service_service.clone(),
+            group_service.clone(),
         ));
 
         let network_service = Arc::new(NetworkService::new(
@@ -68,19 +203,188 @@ impl ServiceFactory {
             network_service.clone(),
         ));
         let auth_service = Arc::new(AuthService::new(user_service.clone()));
+        let network_member_service = Arc::new(NetworkMemberService::new(
+            storage.network_members.clone(),
+            network_service.clone(),
+            user_service.clone(),
+        ));
+        let invite_service = Arc::new(InviteService::new(
+            storage.invites.clone(),
+            network_member_service.clone(),
+        ));
+
+        let digest_service = Arc::new(DigestService::new(
+            storage.digest_snapshots.clone(),
+            network_service.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            user_service.clone(),
+        ));
+
+        let report_service = ReportService::new(
+            storage.report_schedules.clone(),
+            network_service.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            discovery_service.clone(),
+            user_service.clone(),
+        )
+        .await?;
+
+        let demo_service = Arc::new(DemoService::new(
+            network_service.clone(),
+            subnet_service.clone(),
+            host_service.clone(),
+            group_service.clone(),
+            daemon_service.clone(),
+            discovery_service.clone(),
+        ));
+
+        let apply_service = Arc::new(ApplyService::new(
+            network_service.clone(),
+            subnet_service.clone(),
+            daemon_service.clone(),
+            discovery_service.clone(),
+            group_service.clone(),
+        ));
+
+        let archive_service = Arc::new(ArchiveService::new(
+            subnet_service.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            group_service.clone(),
+            node_pin_service.clone(),
+            discovery_service.clone(),
+            daemon_service.clone(),
+        ));
+
+        let export_service = Arc::new(ExportService::new(
+            network_service.clone(),
+            host_service.clone(),
+            subnet_service.clone(),
+            service_service.clone(),
+            group_service.clone(),
+        ));
+
+        let uptime_kuma_service = Arc::new(UptimeKumaService::new(
+            host_service.clone(),
+            service_service.clone(),
+        ));
+
+        let home_assistant_registry_service =
+            Arc::new(HomeAssistantRegistryService::new(host_service.clone()));
+        let ipam_service = Arc::new(IpamService::new(
+            host_service.clone(),
+            subnet_service.clone(),
+            service_service.clone(),
+        ));
+
+        let proxmox_service = Arc::new(ProxmoxService::new(
+            storage.proxmox_credentials.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            subnet_service.clone(),
+        ));
+
+        let vmware_service = Arc::new(VmwareService::new(
+            storage.vmware_credentials.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            subnet_service.clone(),
+        ));
+
+        let firewall_service = Arc::new(FirewallService::new(
+            storage.firewall_credentials.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            subnet_service.clone(),
+            discovery_service.clone(),
+            network_settings_service.clone(),
+        ));
+
+        let dns_zone_service = Arc::new(DnsZoneService::new(
+            storage.dns_zone_settings.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            subnet_service.clone(),
+        ));
+
+        let monitoring_service = Arc::new(MonitoringService::new(
+            storage.health_checks.clone(),
+            storage.health_check_results.clone(),
+            host_service.clone(),
+            service_service.clone(),
+            daemon_service.clone(),
+        ));
+
+        let vulnerability_service = Arc::new(VulnerabilityService::new(
+            storage.vulnerability_feed.clone(),
+            storage.services.clone(),
+        ));
+
+        let policy_service = Arc::new(PolicyService::new(
+            storage.policies.clone(),
+            storage.policy_violations.clone(),
+            storage.hosts.clone(),
+            storage.services.clone(),
+            storage.subnets.clone(),
+            storage.daemons.clone(),
+        ));
+
+        let grafana_service = Arc::new(GrafanaService::new(
+            host_service.clone(),
+            subnet_service.clone(),
+            service_service.clone(),
+            daemon_service.clone(),
+            discovery_service.clone(),
+            monitoring_service.clone(),
+        ));
 
         Ok(Self {
             user_service,
             auth_service,
             network_service,
+            network_member_service,
+            invite_service,
             host_service,
             group_service,
             subnet_service,
             daemon_service,
+            diagnostic_service,
             topology_service,
+            node_pin_service,
+            note_service,
+            topology_snapshot_service,
             service_service,
             discovery_service,
             api_key_service,
+            maintenance_service,
+            apply_service,
+            archive_service,
+            export_service,
+            grafana_service,
+            uptime_kuma_service,
+            home_assistant_registry_service,
+            ipam_service,
+            proxmox_service,
+            vmware_service,
+            firewall_service,
+            dns_zone_service,
+            ssh_credential_service,
+            winrm_credential_service,
+            webhook_service,
+            digest_service,
+            report_service,
+            network_settings_service,
+            demo_service,
+            custom_service_definition_service,
+            monitoring_service,
+            channel_service,
+            alert_service,
+            audit_service,
+            vulnerability_service,
+            policy_service,
+            scan_profile_service,
         })
     }
 }
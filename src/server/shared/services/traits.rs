@@ -5,7 +5,7 @@ use uuid::Uuid;
 use crate::server::shared::storage::{
     filter::EntityFilter,
     generic::GenericPostgresStorage,
-    traits::{StorableEntity, Storage},
+    traits::{Page, StorableEntity, Storage},
 };
 
 /// Helper trait for services that use generic storage
@@ -40,6 +40,11 @@ where
         self.storage().get_all(filter).await
     }
 
+    /// Get one page of entities with filter, with the total across pages
+    async fn get_page(&self, filter: EntityFilter) -> Result<Page<T>, anyhow::Error> {
+        self.storage().get_page(filter).await
+    }
+
     /// Get one entities with filter
     async fn get_one(&self, filter: EntityFilter) -> Result<Option<T>, anyhow::Error> {
         self.storage().get_one(filter).await
@@ -54,4 +59,9 @@ where
     async fn delete(&self, id: &Uuid) -> Result<(), anyhow::Error> {
         self.storage().delete(id).await
     }
+
+    /// Take a soft-deleted entity out of the trash
+    async fn restore(&self, id: &Uuid) -> Result<Option<T>, anyhow::Error> {
+        self.storage().restore(id).await
+    }
 }
@@ -5,24 +5,83 @@ use tower_sessions::{Expiry, SessionManagerLayer};
 use tower_sessions_sqlx_store::PostgresStore;
 
 use crate::server::{
-    api_keys::r#impl::base::ApiKey, daemons::r#impl::base::Daemon,
-    discovery::r#impl::base::Discovery, groups::r#impl::base::Group, hosts::r#impl::base::Host,
-    networks::r#impl::Network, services::r#impl::base::Service,
-    shared::storage::generic::GenericPostgresStorage, subnets::r#impl::base::Subnet,
+    api_keys::r#impl::base::ApiKey,
+    audit::r#impl::base::AuditEntry,
+    daemons::r#impl::base::Daemon,
+    digests::r#impl::base::DigestSnapshot,
+    discovery::r#impl::{base::Discovery, sessions::PersistedSession},
+    groups::r#impl::base::Group,
+    hosts::r#impl::{base::Host, changes::HostChange},
+    integrations::{
+        dns::r#impl::base::DnsZoneSettings, firewall::r#impl::base::FirewallCredentials,
+        proxmox::r#impl::base::ProxmoxCredentials, vmware::r#impl::base::VmwareCredentials,
+    },
+    invites::r#impl::base::Invite,
+    monitoring::r#impl::base::{HealthCheck, HealthCheckResult},
+    network_members::r#impl::base::NetworkMember,
+    network_settings::r#impl::base::NetworkSettings,
+    networks::r#impl::Network,
+    node_pins::r#impl::base::NodePin,
+    notes::r#impl::base::{Note, NoteAttachment, NoteRevision},
+    notifications::r#impl::base::{AlertRule, Channel},
+    policies::r#impl::base::{Policy, PolicyViolation},
+    reports::r#impl::base::ReportSchedule,
+    scan_profiles::r#impl::base::ScanProfile,
+    service_definitions::r#impl::base::CustomServiceDefinition,
+    services::r#impl::base::Service,
+    shared::{events::EventBus, storage::generic::GenericPostgresStorage},
+    ssh_credentials::r#impl::base::SshCredential,
+    subnets::r#impl::base::Subnet,
+    topology_snapshots::r#impl::base::TopologySnapshot,
     users::r#impl::base::User,
+    vulnerabilities::r#impl::base::VulnerabilityFeedEntry,
+    webhooks::r#impl::base::{Webhook, WebhookDelivery},
+    winrm_credentials::r#impl::base::WinrmCredential,
 };
 
 pub struct StorageFactory {
     pub sessions: SessionManagerLayer<PostgresStore>,
+    /// Changes to hosts, groups, services and subnets
+    pub events: Arc<EventBus>,
     pub api_keys: Arc<GenericPostgresStorage<ApiKey>>,
     pub users: Arc<GenericPostgresStorage<User>>,
     pub networks: Arc<GenericPostgresStorage<Network>>,
+    pub network_members: Arc<GenericPostgresStorage<NetworkMember>>,
+    pub invites: Arc<GenericPostgresStorage<Invite>>,
     pub hosts: Arc<GenericPostgresStorage<Host>>,
+    pub host_changes: Arc<GenericPostgresStorage<HostChange>>,
     pub groups: Arc<GenericPostgresStorage<Group>>,
     pub daemons: Arc<GenericPostgresStorage<Daemon>>,
     pub subnets: Arc<GenericPostgresStorage<Subnet>>,
     pub services: Arc<GenericPostgresStorage<Service>>,
     pub discovery: Arc<GenericPostgresStorage<Discovery>>,
+    pub discovery_sessions: Arc<GenericPostgresStorage<PersistedSession>>,
+    pub webhooks: Arc<GenericPostgresStorage<Webhook>>,
+    pub webhook_deliveries: Arc<GenericPostgresStorage<WebhookDelivery>>,
+    pub digest_snapshots: Arc<GenericPostgresStorage<DigestSnapshot>>,
+    pub report_schedules: Arc<GenericPostgresStorage<ReportSchedule>>,
+    pub network_settings: Arc<GenericPostgresStorage<NetworkSettings>>,
+    pub custom_service_definitions: Arc<GenericPostgresStorage<CustomServiceDefinition>>,
+    pub health_checks: Arc<GenericPostgresStorage<HealthCheck>>,
+    pub health_check_results: Arc<GenericPostgresStorage<HealthCheckResult>>,
+    pub notification_channels: Arc<GenericPostgresStorage<Channel>>,
+    pub alert_rules: Arc<GenericPostgresStorage<AlertRule>>,
+    pub topology_snapshots: Arc<GenericPostgresStorage<TopologySnapshot>>,
+    pub node_pins: Arc<GenericPostgresStorage<NodePin>>,
+    pub notes: Arc<GenericPostgresStorage<Note>>,
+    pub note_revisions: Arc<GenericPostgresStorage<NoteRevision>>,
+    pub note_attachments: Arc<GenericPostgresStorage<NoteAttachment>>,
+    pub proxmox_credentials: Arc<GenericPostgresStorage<ProxmoxCredentials>>,
+    pub vmware_credentials: Arc<GenericPostgresStorage<VmwareCredentials>>,
+    pub firewall_credentials: Arc<GenericPostgresStorage<FirewallCredentials>>,
+    pub dns_zone_settings: Arc<GenericPostgresStorage<DnsZoneSettings>>,
+    pub ssh_credentials: Arc<GenericPostgresStorage<SshCredential>>,
+    pub winrm_credentials: Arc<GenericPostgresStorage<WinrmCredential>>,
+    pub vulnerability_feed: Arc<GenericPostgresStorage<VulnerabilityFeedEntry>>,
+    pub policies: Arc<GenericPostgresStorage<Policy>>,
+    pub policy_violations: Arc<GenericPostgresStorage<PolicyViolation>>,
+    pub scan_profiles: Arc<GenericPostgresStorage<ScanProfile>>,
+    pub audit_log: Arc<GenericPostgresStorage<AuditEntry>>,
 }
 
 pub async fn create_session_store(
@@ -48,18 +107,96 @@ impl StorageFactory {
         sqlx::migrate!("./migrations").run(&pool).await?;
 
         let sessions = create_session_store(pool.clone(), use_secure_session_cookies).await?;
+        let events = Arc::new(EventBus::new(256));
 
         Ok(Self {
             sessions,
-            discovery: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            api_keys: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            users: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            networks: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            hosts: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            groups: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            discovery: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("discovery")),
+            discovery_sessions: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            api_keys: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("api_key")),
+            users: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("user")),
+            networks: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("network")),
+            network_members: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("network_member"),
+            ),
+            invites: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("invite")),
+            hosts: Arc::new(
+                GenericPostgresStorage::new(pool.clone())
+                    .with_events(events.clone(), "host")
+                    .with_audit("host"),
+            ),
+            host_changes: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            groups: Arc::new(
+                GenericPostgresStorage::new(pool.clone())
+                    .with_events(events.clone(), "group")
+                    .with_audit("group"),
+            ),
             daemons: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            subnets: Arc::new(GenericPostgresStorage::new(pool.clone())),
-            services: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            subnets: Arc::new(
+                GenericPostgresStorage::new(pool.clone())
+                    .with_events(events.clone(), "subnet")
+                    .with_audit("subnet"),
+            ),
+            services: Arc::new(
+                GenericPostgresStorage::new(pool.clone())
+                    .with_events(events.clone(), "service")
+                    .with_audit("service"),
+            ),
+            webhooks: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("webhook")),
+            webhook_deliveries: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            digest_snapshots: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            report_schedules: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("report_schedule"),
+            ),
+            network_settings: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("network_settings"),
+            ),
+            custom_service_definitions: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("service_definition"),
+            ),
+            health_checks: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("health_check"),
+            ),
+            health_check_results: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            notification_channels: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("notification_channel"),
+            ),
+            alert_rules: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("alert_rule"),
+            ),
+            topology_snapshots: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            node_pins: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("node_pin")),
+            notes: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("note")),
+            note_revisions: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            note_attachments: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("note_attachment"),
+            ),
+            proxmox_credentials: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("proxmox_credentials"),
+            ),
+            vmware_credentials: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("vmware_credentials"),
+            ),
+            firewall_credentials: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("firewall_credentials"),
+            ),
+            dns_zone_settings: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("dns_zone_settings"),
+            ),
+            ssh_credentials: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("ssh_credential"),
+            ),
+            winrm_credentials: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("winrm_credential"),
+            ),
+            vulnerability_feed: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            policies: Arc::new(GenericPostgresStorage::new(pool.clone()).with_audit("policy")),
+            policy_violations: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            scan_profiles: Arc::new(
+                GenericPostgresStorage::new(pool.clone()).with_audit("scan_profile"),
+            ),
+            audit_log: Arc::new(GenericPostgresStorage::new(pool.clone())),
+            events,
         })
     }
 }
@@ -1,13 +1,34 @@
+use chrono::{DateTime, Utc};
 use email_address::EmailAddress;
 use uuid::Uuid;
 
 use crate::server::shared::storage::traits::SqlValue;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    fn sql(self) -> &'static str {
+        match self {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        }
+    }
+}
+
 /// Builder pattern for common WHERE clauses
 #[derive(Clone)]
 pub struct EntityFilter {
     conditions: Vec<String>,
     values: Vec<SqlValue>,
+    include_deleted: bool,
+    sort: Vec<(&'static str, SortDirection)>,
+    limit: Option<u32>,
+    after: Option<Uuid>,
+    search: Option<String>,
 }
 
 impl EntityFilter {
@@ -15,6 +36,11 @@ impl EntityFilter {
         Self {
             conditions: Vec::new(),
             values: Vec::new(),
+            include_deleted: false,
+            sort: Vec::new(),
+            limit: None,
+            after: None,
+            search: None,
         }
     }
 
@@ -46,8 +72,11 @@ impl EntityFilter {
         self
     }
 
+    /// Only rows in these networks. No networks matches no rows, so a user without any sees
+    /// nothing rather than everyone's.
     pub fn network_ids(mut self, ids: &[Uuid]) -> Self {
         if ids.is_empty() {
+            self.conditions.push("FALSE".to_string());
             return self;
         }
 
@@ -74,6 +103,69 @@ impl EntityFilter {
         self
     }
 
+    /// Networks the user owns or has been added to as a member
+    pub fn network_member(mut self, user_id: &Uuid) -> Self {
+        self.conditions.push(format!(
+            "(user_id = ${0} OR id IN (SELECT network_id FROM network_members WHERE user_id = ${0}))",
+            self.values.len() + 1
+        ));
+        self.values.push(SqlValue::Uuid(*user_id));
+        self
+    }
+
+    pub fn stale(mut self, stale: bool) -> Self {
+        self.conditions
+            .push(format!("stale = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Bool(stale));
+        self
+    }
+
+    /// Services with or without known vulnerabilities
+    pub fn has_vulnerabilities(mut self, has_vulnerabilities: bool) -> Self {
+        let comparison = if has_vulnerabilities { ">" } else { "=" };
+        self.conditions.push(format!(
+            "jsonb_array_length(vulnerabilities) {} 0",
+            comparison
+        ));
+        self
+    }
+
+    /// Entities with the tag
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.conditions
+            .push(format!("attributes -> 'tags' ? ${}", self.values.len() + 1));
+        self.values.push(SqlValue::String(tag.to_string()));
+        self
+    }
+
+    /// Entities whose custom field has the value, compared as text, so `12` and `true` match
+    /// numbers and booleans too
+    pub fn custom_field(mut self, key: &str, value: &str) -> Self {
+        self.conditions.push(format!(
+            "attributes -> 'custom_fields' ->> ${} = ${}",
+            self.values.len() + 1,
+            self.values.len() + 2
+        ));
+        self.values.push(SqlValue::String(key.to_string()));
+        self.values.push(SqlValue::String(value.to_string()));
+        self
+    }
+
+    /// Notes on this host, service or subnet
+    pub fn noted_entity_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("entity_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
+    pub fn note_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("note_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
     pub fn host_id(mut self, id: &Uuid) -> Self {
         self.conditions
             .push(format!("host_id = ${}", self.values.len() + 1));
@@ -81,6 +173,122 @@ impl EntityFilter {
         self
     }
 
+    pub fn node_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("node_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
+    pub fn service_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("service_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
+    pub fn service_ids(mut self, ids: &[Uuid]) -> Self {
+        if ids.is_empty() {
+            return self;
+        }
+
+        let placeholders: Vec<String> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("${}", self.values.len() + i + 1))
+            .collect();
+
+        self.conditions
+            .push(format!("service_id IN ({})", placeholders.join(", ")));
+
+        for id in ids {
+            self.values.push(SqlValue::Uuid(*id));
+        }
+
+        self
+    }
+
+    pub fn checked_after(mut self, time: DateTime<Utc>) -> Self {
+        self.conditions
+            .push(format!("checked_at > ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(time));
+        self
+    }
+
+    pub fn checked_before(mut self, time: DateTime<Utc>) -> Self {
+        self.conditions
+            .push(format!("checked_at < ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(time));
+        self
+    }
+
+    pub fn webhook_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("webhook_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
+    pub fn policy_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("policy_id = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Uuid(*id));
+        self
+    }
+
+    /// Policy violations that have, or haven't, been resolved
+    pub fn resolved(mut self, resolved: bool) -> Self {
+        let check = if resolved { "IS NOT NULL" } else { "IS NULL" };
+        self.conditions.push(format!("resolved_at {}", check));
+        self
+    }
+
+    /// Webhook deliveries waiting for a retry that is due
+    pub fn due_webhook_deliveries(mut self, now: DateTime<Utc>) -> Self {
+        self.conditions.push("status = 'Pending'".to_string());
+        self.conditions
+            .push(format!("next_attempt_at <= ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(now));
+        self
+    }
+
+    pub fn delivery_finished(mut self) -> Self {
+        self.conditions.push("status <> 'Pending'".to_string());
+        self
+    }
+
+    pub fn created_before(mut self, time: DateTime<Utc>) -> Self {
+        self.conditions
+            .push(format!("created_at < ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(time));
+        self
+    }
+
+    pub fn created_after(mut self, time: DateTime<Utc>) -> Self {
+        self.conditions
+            .push(format!("created_at >= ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(time));
+        self
+    }
+
+    /// Audit entries with a change to this kind of entity
+    pub fn changed_entity(mut self, entity: &str) -> Self {
+        self.conditions
+            .push(format!("changes @> ${}", self.values.len() + 1));
+        self.values
+            .push(SqlValue::Json(serde_json::json!([{ "entity": entity }])));
+        self
+    }
+
+    /// Audit entries with a change to this entity
+    pub fn changed_entity_id(mut self, id: &Uuid) -> Self {
+        self.conditions
+            .push(format!("changes @> ${}", self.values.len() + 1));
+        self.values
+            .push(SqlValue::Json(serde_json::json!([{ "entity_id": id }])));
+        self
+    }
+
     pub fn api_key(mut self, api_key: String) -> Self {
         self.conditions
             .push(format!("key = ${}", self.values.len() + 1));
@@ -88,6 +296,13 @@ impl EntityFilter {
         self
     }
 
+    pub fn invite_token_hash(mut self, token_hash: &str) -> Self {
+        self.conditions
+            .push(format!("token_hash = ${}", self.values.len() + 1));
+        self.values.push(SqlValue::String(token_hash.to_string()));
+        self
+    }
+
     pub fn scheduled_discovery(mut self) -> Self {
         self.conditions
             .push("run_type->>'type' = 'Scheduled'".to_string());
@@ -112,6 +327,143 @@ impl EntityFilter {
         self
     }
 
+    /// Also match soft-deleted entities, which are otherwise left out
+    pub fn include_deleted(mut self) -> Self {
+        self.include_deleted = true;
+        self
+    }
+
+    pub fn includes_deleted(&self) -> bool {
+        self.include_deleted
+    }
+
+    pub fn not_deleted(mut self) -> Self {
+        self.conditions.push("deleted_at IS NULL".to_string());
+        self
+    }
+
+    pub fn deleted_before(mut self, time: DateTime<Utc>) -> Self {
+        self.conditions
+            .push(format!("deleted_at < ${}", self.values.len() + 1));
+        self.values.push(SqlValue::Timestamp(time));
+        self
+    }
+
+    /// Sort by the column before any sorts added later. Rows are sorted by creation when no
+    /// sort is given, and ties are always broken by id.
+    pub fn sort_by(mut self, column: &'static str, direction: SortDirection) -> Self {
+        self.sort.push((column, direction));
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn get_limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    /// Only rows after this one in the sort order
+    pub fn after(mut self, cursor: Uuid) -> Self {
+        self.after = Some(cursor);
+        self
+    }
+
+    pub fn get_after(&self) -> Option<Uuid> {
+        self.after
+    }
+
+    /// Case-insensitive substring search across the entity's search columns
+    pub fn search(mut self, query: &str) -> Self {
+        self.search = Some(query.to_string());
+        self
+    }
+
+    /// Match the search, if any, against the given columns
+    pub fn with_search_columns(mut self, columns: &[&str]) -> Self {
+        let Some(query) = self.search.take() else {
+            return self;
+        };
+
+        if columns.is_empty() {
+            return self;
+        }
+
+        let placeholder = self.values.len() + 1;
+        let matches: Vec<String> = columns
+            .iter()
+            .map(|column| format!("CAST({} AS TEXT) ILIKE ${}", column, placeholder))
+            .collect();
+
+        self.conditions.push(format!("({})", matches.join(" OR ")));
+        self.values
+            .push(SqlValue::String(format!("%{}%", escape_like(&query))));
+        self
+    }
+
+    /// Only rows after the cursor row. Compares against the cursor row's own values, so the
+    /// sort columns must not be nullable.
+    pub fn with_cursor(mut self, table: &str) -> Self {
+        let Some(cursor) = self.after.take() else {
+            return self;
+        };
+
+        let placeholder = self.values.len() + 1;
+        let keys = self.sort_keys();
+        let cursor_value = |column: &str| {
+            format!(
+                "(SELECT {} FROM {} WHERE id = ${})",
+                column, table, placeholder
+            )
+        };
+
+        let alternatives: Vec<String> = (0..keys.len())
+            .map(|i| {
+                let mut terms: Vec<String> = keys[..i]
+                    .iter()
+                    .map(|(column, _)| format!("{} = {}", column, cursor_value(column)))
+                    .collect();
+
+                let (column, direction) = keys[i];
+                let operator = match direction {
+                    SortDirection::Asc => ">",
+                    SortDirection::Desc => "<",
+                };
+                terms.push(format!("{} {} {}", column, operator, cursor_value(column)));
+
+                format!("({})", terms.join(" AND "))
+            })
+            .collect();
+
+        self.conditions
+            .push(format!("({})", alternatives.join(" OR ")));
+        self.values.push(SqlValue::Uuid(cursor));
+        self
+    }
+
+    fn sort_keys(&self) -> Vec<(&'static str, SortDirection)> {
+        let mut keys = if self.sort.is_empty() {
+            vec![("created_at", SortDirection::Asc)]
+        } else {
+            self.sort.clone()
+        };
+        keys.retain(|(column, _)| *column != "id");
+        keys.push(("id", SortDirection::Asc));
+        keys
+    }
+
+    pub fn to_order_clause(&self) -> String {
+        let keys: Vec<String> = self
+            .sort_keys()
+            .iter()
+            .map(|(column, direction)| format!("{} {}", column, direction.sql()))
+            .collect();
+
+        format!("ORDER BY {}", keys.join(", "))
+    }
+
     pub fn to_where_clause(&self) -> String {
         if self.conditions.is_empty() {
             String::new()
@@ -124,3 +476,55 @@ impl EntityFilter {
         &self.values
     }
 }
+
+/// Match `%` and `_` literally in ILIKE patterns
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_by_sort_keys_with_id_tiebreaker() {
+        let filter = EntityFilter::unfiltered().sort_by("name", SortDirection::Desc);
+        assert_eq!(filter.to_order_clause(), "ORDER BY name DESC, id ASC");
+
+        let filter = EntityFilter::unfiltered();
+        assert_eq!(filter.to_order_clause(), "ORDER BY created_at ASC, id ASC");
+    }
+
+    #[test]
+    fn cursor_continues_after_the_cursor_row() {
+        let filter = EntityFilter::unfiltered()
+            .sort_by("name", SortDirection::Asc)
+            .after(Uuid::nil())
+            .with_cursor("hosts");
+
+        assert_eq!(
+            filter.to_where_clause(),
+            "WHERE ((name > (SELECT name FROM hosts WHERE id = $1)) OR \
+             (name = (SELECT name FROM hosts WHERE id = $1) AND id > (SELECT id FROM hosts WHERE id = $1)))"
+        );
+    }
+
+    #[test]
+    fn no_networks_match_no_rows() {
+        let filter = EntityFilter::unfiltered().network_ids(&[]).stale(false);
+        assert_eq!(filter.to_where_clause(), "WHERE FALSE AND stale = $1");
+        assert_eq!(filter.values().len(), 1);
+
+        let network_id = Uuid::new_v4();
+        let filter = EntityFilter::unfiltered().network_ids(&[network_id]);
+        assert_eq!(filter.to_where_clause(), "WHERE network_id IN ($1)");
+    }
+
+    #[test]
+    fn escapes_like_wildcards() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+    }
+}
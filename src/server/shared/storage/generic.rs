@@ -1,18 +1,62 @@
-use crate::server::shared::storage::{
-    filter::EntityFilter,
-    traits::{SqlValue, StorableEntity, Storage},
+use crate::server::{
+    audit::r#impl::changes,
+    shared::{
+        events::{EntityEvent, EntityOperation, EventBus},
+        storage::{
+            filter::EntityFilter,
+            traits::{Page, SqlValue, StorableEntity, Storage},
+        },
+    },
 };
 use async_trait::async_trait;
 use chrono::Utc;
-use sqlx::{PgPool, Postgres, postgres::PgArguments};
-use std::{fmt::Display, marker::PhantomData};
+use serde::Serialize;
+use sqlx::{PgConnection, PgPool, Postgres, Row, postgres::PgArguments};
+use std::{fmt::Display, marker::PhantomData, sync::Arc};
 use uuid::Uuid;
 
+type EventPublisher<T> = Box<dyn Fn(&T, EntityOperation) + Send + Sync>;
+type AuditSerializer<T> = Box<dyn Fn(&T) -> serde_json::Value + Send + Sync>;
+
 pub struct GenericPostgresStorage<T: StorableEntity> {
     pool: PgPool,
+    publisher: Option<EventPublisher<T>>,
+    auditor: Option<(&'static str, AuditSerializer<T>)>,
     _phantom: PhantomData<T>,
 }
 
+impl<T: StorableEntity + Serialize> GenericPostgresStorage<T> {
+    /// Publish an event to `events` for every create, update and delete
+    pub fn with_events(mut self, events: Arc<EventBus>, entity: &'static str) -> Self {
+        self.publisher = Some(Box::new(move |item: &T, operation| {
+            let data = serde_json::to_value(item).unwrap_or_default();
+            let network_id = data
+                .get("network_id")
+                .and_then(|id| serde_json::from_value(id.clone()).ok());
+
+            events.publish(EntityEvent {
+                entity,
+                operation,
+                entity_id: item.id(),
+                network_id,
+                data,
+                timestamp: Utc::now(),
+            });
+        }));
+        self
+    }
+
+    /// Record changes made while handling an API request in the audit log, with the entity
+    /// before and after
+    pub fn with_audit(mut self, entity: &'static str) -> Self {
+        self.auditor = Some((
+            entity,
+            Box::new(|item: &T| serde_json::to_value(item).unwrap_or_default()),
+        ));
+        self
+    }
+}
+
 impl<T: StorableEntity> GenericPostgresStorage<T>
 where
     T: Display,
@@ -20,10 +64,77 @@ where
     pub fn new(pool: PgPool) -> Self {
         Self {
             pool,
+            publisher: None,
+            auditor: None,
             _phantom: PhantomData,
         }
     }
 
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Insert the entity as part of a transaction. Nothing is published or audited until the
+    /// caller commits and passes it to `created`.
+    pub async fn create_in(
+        &self,
+        conn: &mut PgConnection,
+        entity: &T,
+    ) -> Result<(), anyhow::Error> {
+        let (columns, values) = entity.to_params()?;
+        let query_str = Self::build_insert_query(&columns);
+
+        let mut query = sqlx::query(&query_str);
+        for value in &values {
+            query = Self::bind_value(query, value)?;
+        }
+
+        query.execute(conn).await?;
+        Ok(())
+    }
+
+    /// Publish and audit an entity inserted by `create_in` once its transaction has committed
+    pub fn created(&self, entity: &T) {
+        tracing::info!("Created {}: {}", T::table_name(), entity);
+        self.publish(entity, EntityOperation::Created);
+        self.audit(entity.id(), EntityOperation::Created, None, Some(entity));
+    }
+
+    fn publish(&self, entity: &T, operation: EntityOperation) {
+        if let Some(publisher) = &self.publisher {
+            publisher(entity, operation);
+        }
+    }
+
+    /// Whether the entity as it was before a write is needed for the audit log
+    fn auditing(&self) -> bool {
+        self.auditor.is_some() && changes::is_collecting()
+    }
+
+    fn audit(&self, id: Uuid, operation: EntityOperation, old: Option<&T>, new: Option<&T>) {
+        if let Some((entity, serialize)) = &self.auditor {
+            changes::record(
+                *entity,
+                id,
+                operation,
+                old.map(serialize),
+                new.map(serialize),
+            );
+        }
+    }
+
+    /// Leave soft-deleted entities out unless the filter asks for them, and turn the filter's
+    /// search into a condition on the entity's search columns
+    fn scoped(filter: EntityFilter) -> EntityFilter {
+        let filter = filter.with_search_columns(T::search_columns());
+
+        if T::soft_delete() && !filter.includes_deleted() {
+            filter.not_deleted()
+        } else {
+            filter
+        }
+    }
+
     /// Generate INSERT query dynamically
     fn build_insert_query(columns: &[&str]) -> String {
         let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("${}", i)).collect();
@@ -63,8 +174,10 @@ where
             SqlValue::String(v) => query.bind(v),
             SqlValue::U16(v) => query.bind(Into::<i32>::into(*v)),
             SqlValue::I32(v) => query.bind(v),
+            SqlValue::OptionalI32(v) => query.bind(v),
             SqlValue::Bool(v) => query.bind(v),
             SqlValue::Json(v) => query.bind(v),
+            SqlValue::Bytes(v) => query.bind(v),
             SqlValue::Timestamp(v) => query.bind(v),
             SqlValue::OptionTimestamp(v) => query.bind(v),
             SqlValue::UuidArray(v) => query.bind(serde_json::to_value(v)?),
@@ -97,16 +210,9 @@ where
     T: Display,
 {
     async fn create(&self, entity: &T) -> Result<T, anyhow::Error> {
-        let (columns, values) = entity.to_params()?;
-        let query_str = Self::build_insert_query(&columns);
-
-        let mut query = sqlx::query(&query_str);
-        for value in &values {
-            query = Self::bind_value(query, value)?;
-        }
-
-        query.execute(&self.pool).await?;
-        tracing::info!("Created {}: {}", T::table_name(), entity);
+        self.create_in(&mut *self.pool.acquire().await?, entity)
+            .await?;
+        self.created(entity);
         Ok(entity.clone())
     }
 
@@ -116,6 +222,7 @@ where
     }
 
     async fn get_one(&self, filter: EntityFilter) -> Result<Option<T>, anyhow::Error> {
+        let filter = Self::scoped(filter);
         let query_str = format!(
             "SELECT * FROM {} {}",
             T::table_name(),
@@ -136,11 +243,17 @@ where
     }
 
     async fn get_all(&self, filter: EntityFilter) -> Result<Vec<T>, anyhow::Error> {
-        let query_str = format!(
-            "SELECT * FROM {} {} ORDER BY created_at ASC",
+        let filter = Self::scoped(filter).with_cursor(T::table_name());
+        // Ties are broken by id so the same rows always come back in the same order
+        let mut query_str = format!(
+            "SELECT * FROM {} {} {}",
             T::table_name(),
-            filter.to_where_clause()
+            filter.to_where_clause(),
+            filter.to_order_clause()
         );
+        if let Some(limit) = filter.get_limit() {
+            query_str.push_str(&format!(" LIMIT {}", limit));
+        }
 
         let mut query = sqlx::query(&query_str);
         for value in filter.values() {
@@ -151,7 +264,67 @@ where
         rows.into_iter().map(|r| T::from_row(&r)).collect()
     }
 
+    async fn count(&self, filter: EntityFilter) -> Result<u64, anyhow::Error> {
+        let filter = Self::scoped(filter);
+        let query_str = format!(
+            "SELECT COUNT(*) FROM {} {}",
+            T::table_name(),
+            filter.to_where_clause()
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for value in filter.values() {
+            query = Self::bind_value(query, value)?;
+        }
+
+        let count: i64 = query.fetch_one(&self.pool).await?.try_get(0)?;
+        Ok(count as u64)
+    }
+
+    async fn get_page(&self, filter: EntityFilter) -> Result<Page<T>, anyhow::Error> {
+        // Everything at once, so the items are the count
+        if filter.get_limit().is_none() && filter.get_after().is_none() {
+            let items = self.get_all(filter).await?;
+            return Ok(Page {
+                total: items.len() as u64,
+                items,
+                next_cursor: None,
+            });
+        }
+
+        let total = self.count(filter.clone()).await?;
+
+        let Some(limit) = filter.get_limit() else {
+            return Ok(Page {
+                items: self.get_all(filter).await?,
+                total,
+                next_cursor: None,
+            });
+        };
+
+        // One extra row tells whether there's another page
+        let mut items = self.get_all(filter.limit(limit + 1)).await?;
+        let next_cursor = if items.len() > limit as usize {
+            items.truncate(limit as usize);
+            items.last().map(|item| item.id())
+        } else {
+            None
+        };
+
+        Ok(Page {
+            items,
+            total,
+            next_cursor,
+        })
+    }
+
     async fn update(&self, entity: &mut T) -> Result<T, anyhow::Error> {
+        let previous = if self.auditing() {
+            self.get_by_id(&entity.id()).await?
+        } else {
+            None
+        };
+
         entity.set_updated_at(Utc::now());
 
         let (columns, values) = entity.to_params()?;
@@ -165,16 +338,100 @@ where
         tracing::info!("Updated {}", entity);
 
         query.execute(&self.pool).await?;
+        self.publish(entity, EntityOperation::Updated);
+        self.audit(
+            entity.id(),
+            EntityOperation::Updated,
+            previous.as_ref(),
+            Some(entity),
+        );
         Ok(entity.clone())
     }
 
     async fn delete(&self, id: &Uuid) -> Result<(), anyhow::Error> {
-        let query_str = format!("DELETE FROM {} WHERE id = $1", T::table_name());
+        // Subscribers and the audit log get the entity as it was, so it has to be read first
+        let existing = if self.publisher.is_some() || self.auditing() {
+            self.get_by_id(id).await?
+        } else {
+            None
+        };
+
+        let query_str = if T::soft_delete() {
+            format!(
+                "UPDATE {} SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+                T::table_name()
+            )
+        } else {
+            format!("DELETE FROM {} WHERE id = $1", T::table_name())
+        };
 
         sqlx::query(&query_str).bind(id).execute(&self.pool).await?;
 
         tracing::info!("Deleted {} with id: {}", T::table_name(), id);
 
+        if let Some(existing) = existing {
+            self.publish(&existing, EntityOperation::Deleted);
+            self.audit(*id, EntityOperation::Deleted, Some(&existing), None);
+        }
+
         Ok(())
     }
+
+    async fn delete_all(&self, filter: EntityFilter) -> Result<u64, anyhow::Error> {
+        // An unfiltered delete is never intended
+        if filter.to_where_clause().is_empty() {
+            anyhow::bail!(
+                "Refusing to delete all {} without a filter",
+                T::table_name()
+            );
+        }
+
+        let query_str = format!(
+            "DELETE FROM {} {}",
+            T::table_name(),
+            filter.to_where_clause()
+        );
+
+        let mut query = sqlx::query(&query_str);
+        for value in filter.values() {
+            query = Self::bind_value(query, value)?;
+        }
+
+        let deleted = query.execute(&self.pool).await?.rows_affected();
+
+        tracing::debug!("Deleted {} {}", deleted, T::table_name());
+
+        Ok(deleted)
+    }
+
+    async fn restore(&self, id: &Uuid) -> Result<Option<T>, anyhow::Error> {
+        if !T::soft_delete() {
+            return Ok(None);
+        }
+
+        let query_str = format!(
+            "UPDATE {} SET deleted_at = NULL, updated_at = NOW() \
+             WHERE id = $1 AND deleted_at IS NOT NULL",
+            T::table_name()
+        );
+
+        let restored = sqlx::query(&query_str)
+            .bind(id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        if restored == 0 {
+            return Ok(None);
+        }
+
+        let entity = self.get_by_id(id).await?;
+        if let Some(entity) = &entity {
+            tracing::info!("Restored {}", entity);
+            self.publish(entity, EntityOperation::Created);
+            self.audit(*id, EntityOperation::Created, None, Some(entity));
+        }
+
+        Ok(entity)
+    }
 }
@@ -2,11 +2,13 @@ use cidr::Ipv4Cidr;
 use std::net::{IpAddr, Ipv4Addr};
 use uuid::Uuid;
 
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::{
     hosts::r#impl::{
         base::{Host, HostBase},
         interfaces::{Interface, InterfaceBase},
         ports::{Port, PortBase},
+        service_overrides::HostServiceOverrides,
         targets::HostTarget,
     },
     networks::r#impl::{Network, NetworkBase},
@@ -20,7 +22,7 @@ use crate::server::{
     shared::{storage::traits::StorableEntity, types::entities::EntitySource},
     subnets::r#impl::{
         base::{Subnet, SubnetBase},
-        types::SubnetType,
+        types::{SubnetLayer2, SubnetType},
     },
     users::r#impl::base::{User, UserBase},
 };
@@ -49,6 +51,8 @@ pub fn create_wan_subnet(network_id: Uuid) -> Subnet {
         ),
         subnet_type: SubnetType::Internet,
         source: EntitySource::System,
+        layer2: SubnetLayer2::default(),
+        attributes: EntityAttributes::default(),
     };
 
     Subnet::new(base)
@@ -69,6 +73,8 @@ pub fn create_remote_subnet(network_id: Uuid) -> Subnet {
         ),
         subnet_type: SubnetType::Remote,
         source: EntitySource::System,
+        layer2: SubnetLayer2::default(),
+        attributes: EntityAttributes::default(),
     };
 
     Subnet::new(base)
@@ -93,6 +99,19 @@ pub fn create_remote_host(remote_subnet: &Subnet, network_id: Uuid) -> (Host, Se
         source: EntitySource::System,
         virtualization: None,
         hidden: false,
+        liveness: None,
+        snmp: None,
+        ssh: None,
+        windows: None,
+        os: None,
+        service_overrides: HostServiceOverrides::default(),
+        last_seen_at: None,
+        stale: false,
+        reachability: None,
+        vpn_peer: None,
+        device: None,
+        dhcp_lease: None,
+        attributes: EntityAttributes::default(),
     };
 
     let mut host = Host::new(base);
@@ -105,6 +124,9 @@ pub fn create_remote_host(remote_subnet: &Subnet, network_id: Uuid) -> (Host, Se
         bindings: vec![binding],
         virtualization: None,
         source: EntitySource::System,
+        versions: Vec::new(),
+        vulnerabilities: Vec::new(),
+        attributes: EntityAttributes::default(),
     });
 
     host.base.target = HostTarget::ServiceBinding(binding_id);
@@ -135,6 +157,19 @@ pub fn create_internet_connectivity_host(
         source: EntitySource::System,
         virtualization: None,
         hidden: false,
+        liveness: None,
+        snmp: None,
+        ssh: None,
+        windows: None,
+        os: None,
+        service_overrides: HostServiceOverrides::default(),
+        last_seen_at: None,
+        stale: false,
+        reachability: None,
+        vpn_peer: None,
+        device: None,
+        dhcp_lease: None,
+        attributes: EntityAttributes::default(),
     };
 
     let mut host = Host::new(base);
@@ -147,6 +182,9 @@ pub fn create_internet_connectivity_host(
         bindings: vec![binding],
         virtualization: None,
         source: EntitySource::System,
+        versions: Vec::new(),
+        vulnerabilities: Vec::new(),
+        attributes: EntityAttributes::default(),
     });
 
     host.base.target = HostTarget::ServiceBinding(binding_id);
@@ -175,6 +213,19 @@ pub fn create_public_dns_host(internet_subnet: &Subnet, network_id: Uuid) -> (Ho
         source: EntitySource::System,
         virtualization: None,
         hidden: false,
+        liveness: None,
+        snmp: None,
+        ssh: None,
+        windows: None,
+        os: None,
+        service_overrides: HostServiceOverrides::default(),
+        last_seen_at: None,
+        stale: false,
+        reachability: None,
+        vpn_peer: None,
+        device: None,
+        dhcp_lease: None,
+        attributes: EntityAttributes::default(),
     };
 
     let mut host = Host::new(base);
@@ -187,6 +238,9 @@ pub fn create_public_dns_host(internet_subnet: &Subnet, network_id: Uuid) -> (Ho
         bindings: vec![binding],
         virtualization: None,
         source: EntitySource::System,
+        versions: Vec::new(),
+        vulnerabilities: Vec::new(),
+        attributes: EntityAttributes::default(),
     });
 
     host.base.target = HostTarget::ServiceBinding(binding_id);
@@ -21,14 +21,33 @@ use crate::server::{
     subnets::r#impl::types::SubnetType,
 };
 
+/// One page of a list, and where the next one starts
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Matching entities across all pages
+    pub total: u64,
+    /// Pass to `EntityFilter::after` for the next page; None on the last page
+    pub next_cursor: Option<Uuid>,
+}
+
 #[async_trait]
 pub trait Storage<T: StorableEntity>: Send + Sync {
     async fn create(&self, entity: &T) -> Result<T, anyhow::Error>;
     async fn get_by_id(&self, id: &Uuid) -> Result<Option<T>, anyhow::Error>;
     async fn get_all(&self, filter: EntityFilter) -> Result<Vec<T>, anyhow::Error>;
     async fn get_one(&self, filter: EntityFilter) -> Result<Option<T>, anyhow::Error>;
+    /// Entities matching the filter, ignoring its cursor and limit
+    async fn count(&self, filter: EntityFilter) -> Result<u64, anyhow::Error>;
+    async fn get_page(&self, filter: EntityFilter) -> Result<Page<T>, anyhow::Error>;
     async fn update(&self, entity: &mut T) -> Result<T, anyhow::Error>;
     async fn delete(&self, id: &Uuid) -> Result<(), anyhow::Error>;
+    /// Delete every entity matching the filter, returning how many were deleted. Unlike the
+    /// other writes, this publishes no entity events and isn't audited, and soft-deleted
+    /// entities are removed for good.
+    async fn delete_all(&self, filter: EntityFilter) -> Result<u64, anyhow::Error>;
+    /// Take a soft-deleted entity out of the trash. Returns None if it isn't in the trash.
+    async fn restore(&self, id: &Uuid) -> Result<Option<T>, anyhow::Error>;
 }
 
 pub trait StorableEntity: Sized + Clone + Send + Sync + 'static {
@@ -41,6 +60,28 @@ pub trait StorableEntity: Sized + Clone + Send + Sync + 'static {
     /// Entity metadata
     fn table_name() -> &'static str;
 
+    /// Whether deleting only moves the entity to the trash by setting its `deleted_at` column.
+    /// Soft-deleted entities are left out of queries unless the filter includes them.
+    fn soft_delete() -> bool {
+        false
+    }
+
+    /// Whether the entity has an `attributes` column of tags and custom fields
+    fn has_attributes() -> bool {
+        false
+    }
+
+    /// Columns lists can be sorted by besides `created_at` and `updated_at`. Only non-nullable
+    /// columns, which keyset cursors rely on.
+    fn sortable_columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Columns the text search of lists matches against
+    fn search_columns() -> &'static [&'static str] {
+        &[]
+    }
+
     /// Primary key
     fn id(&self) -> Uuid;
     fn created_at(&self) -> DateTime<Utc>;
@@ -63,9 +104,11 @@ pub enum SqlValue {
     String(String),
     OptionalString(Option<String>),
     I32(i32),
+    OptionalI32(Option<i32>),
     U16(u16),
     Bool(bool),
     Json(serde_json::Value),
+    Bytes(Vec<u8>),
     Email(EmailAddress),
     Timestamp(DateTime<Utc>),
     OptionTimestamp(Option<DateTime<Utc>>),
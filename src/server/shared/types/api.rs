@@ -1,5 +1,8 @@
 use axum::{Json, http::StatusCode, response::Response};
 use serde::{Deserialize, Deserializer, Serialize, de::DeserializeOwned};
+use uuid::Uuid;
+
+use crate::server::shared::storage::traits::Page;
 
 pub type ApiResult<T> = Result<T, ApiError>;
 
@@ -8,6 +11,17 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Set on paginated lists
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pagination {
+    /// Matching items across all pages
+    pub total: u64,
+    /// Pass as `cursor` for the next page; None on the last page
+    pub next_cursor: Option<Uuid>,
 }
 
 impl<T> ApiResponse<T> {
@@ -16,6 +30,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            pagination: None,
         }
     }
 
@@ -24,6 +39,19 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message),
+            pagination: None,
+        }
+    }
+}
+
+impl<T> ApiResponse<Vec<T>> {
+    pub fn page(page: Page<T>) -> Self {
+        Self {
+            pagination: Some(Pagination {
+                total: page.total,
+                next_cursor: page.next_cursor,
+            }),
+            ..Self::success(page.items)
         }
     }
 }
@@ -62,6 +90,10 @@ impl ApiError {
     pub fn unauthorized(message: String) -> Self {
         Self::new(StatusCode::UNAUTHORIZED, message.to_string())
     }
+
+    pub fn too_many_requests(message: &str) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, message.to_string())
+    }
 }
 
 impl axum::response::IntoResponse for ApiError {
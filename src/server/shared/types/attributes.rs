@@ -0,0 +1,127 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display};
+use validator::{Validate, ValidationError};
+
+const MAX_ENTRIES: usize = 50;
+const MAX_LENGTH: usize = 64;
+
+/// Value of a custom field, typed by its JSON: text, number, boolean, or a `YYYY-MM-DD` date
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum CustomFieldValue {
+    Boolean(bool),
+    Number(serde_json::Number),
+    Date(NaiveDate),
+    Text(String),
+}
+
+impl Display for CustomFieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Boolean(value) => write!(f, "{}", value),
+            Self::Number(value) => write!(f, "{}", value),
+            Self::Date(value) => write!(f, "{}", value.format("%Y-%m-%d")),
+            Self::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Tags and custom fields users attach to hosts, services and subnets. Discovery never sets
+/// them, so they survive rescans.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, PartialEq, Eq, Hash)]
+pub struct EntityAttributes {
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_custom_fields"))]
+    pub custom_fields: BTreeMap<String, CustomFieldValue>,
+}
+
+impl EntityAttributes {
+    /// Badge text for each tag and custom field, e.g. `prod` and `rack: B4`
+    pub fn badges(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .cloned()
+            .chain(
+                self.custom_fields
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, value)),
+            )
+            .collect()
+    }
+}
+
+fn validate_name(name: &str, code: &'static str) -> Result<(), ValidationError> {
+    if name.trim().is_empty() || name.len() > MAX_LENGTH || name.trim() != name {
+        return Err(ValidationError::new(code).with_message(
+            format!(
+                "'{}' must be 1 to {} characters without surrounding spaces",
+                name, MAX_LENGTH
+            )
+            .into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_ENTRIES {
+        return Err(ValidationError::new("too_many_tags")
+            .with_message(format!("At most {} tags are allowed", MAX_ENTRIES).into()));
+    }
+    tags.iter()
+        .try_for_each(|tag| validate_name(tag, "invalid_tag"))
+}
+
+fn validate_custom_fields(
+    fields: &BTreeMap<String, CustomFieldValue>,
+) -> Result<(), ValidationError> {
+    if fields.len() > MAX_ENTRIES {
+        return Err(ValidationError::new("too_many_custom_fields")
+            .with_message(format!("At most {} custom fields are allowed", MAX_ENTRIES).into()));
+    }
+    fields
+        .keys()
+        .try_for_each(|key| validate_name(key, "invalid_custom_field"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_typed_values() {
+        let attributes: EntityAttributes = serde_json::from_str(
+            r#"{"tags": ["prod"], "custom_fields": {"rack": "B4", "u": 12, "managed": true, "since": "2024-03-01"}}"#,
+        )
+        .unwrap();
+
+        let fields = &attributes.custom_fields;
+        assert_eq!(fields["rack"], CustomFieldValue::Text("B4".to_string()));
+        assert!(matches!(fields["u"], CustomFieldValue::Number(_)));
+        assert_eq!(fields["managed"], CustomFieldValue::Boolean(true));
+        assert!(matches!(fields["since"], CustomFieldValue::Date(_)));
+        assert_eq!(
+            attributes.badges(),
+            [
+                "prod",
+                "managed: true",
+                "rack: B4",
+                "since: 2024-03-01",
+                "u: 12"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_blank_tags() {
+        let attributes = EntityAttributes {
+            tags: vec![" ".to_string()],
+            ..Default::default()
+        };
+        assert!(attributes.validate().is_err());
+    }
+}
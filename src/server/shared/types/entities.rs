@@ -49,6 +49,9 @@ impl Default for DiscoveryMetadata {
             discovery_type: DiscoveryType::Network {
                 subnet_ids: None,
                 host_naming_fallback: HostNamingFallback::BestService,
+                scan_profile_id: None,
+                parallel_daemon_ids: Vec::new(),
+                shard: None,
             },
             daemon_id: Uuid::new_v4(),
             date: Utc::now(),
@@ -0,0 +1,97 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+
+/// Translations for one locale, keyed by `<section>.<id>.<field>`
+pub type LocaleCatalog = HashMap<String, String>;
+
+/// Catalogs built into the binary; English is the source language and has no catalog
+const EMBEDDED_CATALOGS: &[(&str, &str)] = &[("de", include_str!("../../../../locales/de.json"))];
+
+pub struct LocaleRegistry {
+    catalogs: HashMap<String, LocaleCatalog>,
+}
+
+impl LocaleRegistry {
+    /// Load the embedded catalogs, then `<locale>.json` files from `dir` if given. Entries from
+    /// files override embedded ones, so deployments can fix or add translations without a rebuild.
+    pub fn load(dir: Option<&Path>) -> Result<Self> {
+        let mut catalogs: HashMap<String, LocaleCatalog> = HashMap::new();
+
+        for (locale, contents) in EMBEDDED_CATALOGS {
+            let catalog: LocaleCatalog = serde_json::from_str(contents)
+                .with_context(|| format!("Invalid embedded locale catalog '{}'", locale))?;
+            catalogs.insert(locale.to_string(), catalog);
+        }
+
+        if let Some(dir) = dir {
+            for entry in fs::read_dir(dir)
+                .with_context(|| format!("Failed to read locales from {}", dir.display()))?
+            {
+                let path = entry?.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+
+                let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+
+                let catalog: LocaleCatalog = serde_json::from_str(&fs::read_to_string(&path)?)
+                    .with_context(|| format!("Invalid locale catalog {}", path.display()))?;
+
+                catalogs
+                    .entry(locale.to_lowercase())
+                    .or_default()
+                    .extend(catalog);
+            }
+        }
+
+        Ok(Self { catalogs })
+    }
+
+    pub fn locales(&self) -> Vec<String> {
+        let mut locales: Vec<String> = self.catalogs.keys().cloned().collect();
+        locales.push("en".to_string());
+        locales.sort();
+        locales.dedup();
+        locales
+    }
+
+    /// Best catalog for a locale or an Accept-Language style list (`de-AT,de;q=0.9,en;q=0.8`).
+    /// Tries each tag in order of preference, then its primary language. None means English.
+    pub fn resolve(&self, requested: &str) -> Option<&LocaleCatalog> {
+        let mut tags: Vec<(&str, f32)> = requested
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.trim().split(';');
+                let tag = pieces.next()?.trim();
+                let quality = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty()).then_some((tag, quality))
+            })
+            .collect();
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (tag, _) in tags {
+            let tag = tag.to_lowercase().replace('_', "-");
+            if tag == "en" || tag.starts_with("en-") {
+                return None;
+            }
+
+            if let Some(catalog) = self.catalogs.get(&tag) {
+                return Some(catalog);
+            }
+
+            if let Some((language, _)) = tag.split_once('-')
+                && let Some(catalog) = self.catalogs.get(language)
+            {
+                return Some(catalog);
+            }
+        }
+
+        None
+    }
+}
@@ -1,5 +1,9 @@
+use std::borrow::Cow;
+
 use serde::Serialize;
 
+use crate::server::shared::types::locale::LocaleCatalog;
+
 #[derive(Serialize, Debug, Clone)]
 pub struct MetadataRegistry {
     pub service_definitions: Vec<TypeMetadata>,
@@ -9,19 +13,51 @@ pub struct MetadataRegistry {
     pub entities: Vec<EntityMetadata>,
     pub ports: Vec<TypeMetadata>,
     pub discovery_types: Vec<TypeMetadata>,
+    pub service_categories: Vec<TypeMetadata>,
+}
+
+impl MetadataRegistry {
+    /// Replace names and descriptions with translations from a catalog, keyed by
+    /// `<section>.<id>.name` / `<section>.<id>.description`. Untranslated strings stay English.
+    pub fn localize(&mut self, catalog: &LocaleCatalog) {
+        for (section, types) in [
+            ("service_definitions", &mut self.service_definitions),
+            ("subnet_types", &mut self.subnet_types),
+            ("edge_types", &mut self.edge_types),
+            ("group_types", &mut self.group_types),
+            ("ports", &mut self.ports),
+            ("discovery_types", &mut self.discovery_types),
+            ("service_categories", &mut self.service_categories),
+        ] {
+            for metadata in types.iter_mut() {
+                metadata.localize(section, catalog);
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
 pub struct TypeMetadata {
     pub id: &'static str,
-    pub name: Option<&'static str>,
-    pub description: Option<&'static str>,
+    pub name: Option<Cow<'static, str>>,
+    pub description: Option<Cow<'static, str>>,
     pub category: Option<&'static str>,
     pub icon: Option<&'static str>,
     pub color: Option<&'static str>,
     pub metadata: Option<serde_json::Value>,
 }
 
+impl TypeMetadata {
+    fn localize(&mut self, section: &str, catalog: &LocaleCatalog) {
+        if let Some(name) = catalog.get(&format!("{}.{}.name", section, self.id)) {
+            self.name = Some(Cow::Owned(name.to_string()));
+        }
+        if let Some(description) = catalog.get(&format!("{}.{}.description", section, self.id)) {
+            self.description = Some(Cow::Owned(description.to_string()));
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct EntityMetadata {
     pub id: &'static str,
@@ -83,8 +119,8 @@ where
 
         TypeMetadata {
             id,
-            name: (!name.is_empty()).then_some(name),
-            description: (!description.is_empty()).then_some(description),
+            name: (!name.is_empty()).then_some(Cow::Borrowed(name)),
+            description: (!description.is_empty()).then_some(Cow::Borrowed(description)),
             category: (!category.is_empty()).then_some(category),
             icon: (!icon.is_empty()).then_some(icon),
             color: (!color.is_empty()).then_some(color),
@@ -1,3 +1,5 @@
 pub mod api;
+pub mod attributes;
 pub mod entities;
+pub mod locale;
 pub mod metadata;
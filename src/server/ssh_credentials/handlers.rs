@@ -0,0 +1,88 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, delete_handler, get_all_handler, get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    ssh_credentials::r#impl::base::SshCredential,
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", post(create_credential))
+        .route("/", get(get_all_handler::<SshCredential>))
+        .route("/{id}", put(update_credential))
+        .route("/{id}", delete(delete_handler::<SshCredential>))
+        .route("/{id}", get(get_by_id_handler::<SshCredential>))
+}
+
+/// Validation the standard handlers do, plus that the host or subnet is in the credential's
+/// network
+async fn validate_credential(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    credential: &SshCredential,
+) -> ApiResult<()> {
+    credential
+        .validate()
+        .map_err(|e| ApiError::bad_request(&format!("SSH credential validation failed: {}", e)))?;
+    check_access(state, &user.0, credential).await?;
+
+    state
+        .services
+        .ssh_credential_service
+        .check_target(&credential.base)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))
+}
+
+async fn create_credential(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<SshCredential>,
+) -> ApiResult<Json<ApiResponse<SshCredential>>> {
+    validate_credential(&state, &user, &request).await?;
+
+    let created = state
+        .services
+        .ssh_credential_service
+        .create(request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+/// Secrets sent back redacted are kept as stored
+async fn update_credential(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<SshCredential>,
+) -> ApiResult<Json<ApiResponse<SshCredential>>> {
+    let service = &state.services.ssh_credential_service;
+    let existing = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("SSH credential '{}' not found", id)))?;
+    check_access(&state, &user.0, &existing).await?;
+
+    request.id = id;
+    request.base.keep_secrets(&existing.base);
+    validate_credential(&state, &user, &request).await?;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
@@ -0,0 +1,197 @@
+use std::{collections::HashMap, fmt::Display, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Stands in for secrets in API responses. Sent back unchanged in an update, it keeps the
+/// stored secret.
+pub const REDACTED: &str = "***REDACTED***";
+
+/// Login the daemon uses to read a Linux host's inventory over SSH, for a single host or
+/// every host of a subnet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshCredentialBase {
+    pub network_id: Uuid,
+    /// Exactly one of `host_id` and `subnet_id` is set
+    pub host_id: Option<Uuid>,
+    pub subnet_id: Option<Uuid>,
+    pub username: String,
+    #[serde(default, serialize_with = "serialize_secret")]
+    pub password: Option<String>,
+    /// OpenSSH or PEM private key, used instead of the password when both are set
+    #[serde(default, serialize_with = "serialize_secret")]
+    pub private_key: Option<String>,
+    #[serde(default, serialize_with = "serialize_secret")]
+    pub passphrase: Option<String>,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// SHA256 fingerprint the host key must have. When unset, each host's key is pinned at its
+    /// first login and must match at later ones.
+    #[serde(default)]
+    pub host_key_fingerprint: Option<String>,
+}
+
+fn default_port() -> u16 {
+    22
+}
+
+fn serialize_secret<S>(secret: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match secret {
+        Some(_) => serializer.serialize_str(REDACTED),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl SshCredentialBase {
+    /// Take the stored secrets for the ones an update left redacted
+    pub fn keep_secrets(&mut self, existing: &SshCredentialBase) {
+        let keep = |secret: &mut Option<String>, stored: &Option<String>| {
+            if secret.as_deref() == Some(REDACTED) {
+                *secret = stored.clone();
+            }
+        };
+
+        keep(&mut self.password, &existing.password);
+        keep(&mut self.private_key, &existing.private_key);
+        keep(&mut self.passphrase, &existing.passphrase);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshCredential {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: SshCredentialBase,
+}
+
+impl Display for SshCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SSH credential for {}: {}", self.base.username, self.id)
+    }
+}
+
+/// A credential as daemons receive it with a scan request: secrets in clear, and the
+/// addresses it applies to instead of the host or subnet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshLogin {
+    /// The host's addresses, or the subnet's range
+    pub cidrs: Vec<IpCidr>,
+    pub port: u16,
+    pub username: String,
+    pub password: Option<String>,
+    pub private_key: Option<String>,
+    pub passphrase: Option<String>,
+    pub host_key_fingerprint: Option<String>,
+    /// Fingerprints of the keys hosts in `cidrs` presented at their last login, by address
+    #[serde(default)]
+    pub known_host_keys: HashMap<IpAddr, String>,
+}
+
+impl SshLogin {
+    /// Login for an address: the one whose range is narrowest, so a host's own credential wins
+    /// over its subnet's
+    pub fn for_ip(logins: &[SshLogin], ip: IpAddr) -> Option<&SshLogin> {
+        logins
+            .iter()
+            .filter_map(|login| {
+                login
+                    .cidrs
+                    .iter()
+                    .filter(|cidr| cidr.contains(&ip))
+                    .map(|cidr| cidr.network_length())
+                    .max()
+                    .map(|length| (length, login))
+            })
+            .max_by_key(|(length, _)| *length)
+            .map(|(_, login)| login)
+    }
+
+    /// Fingerprint the host at the address must present: the credential's own, otherwise the
+    /// one it presented before. None only for hosts never logged into.
+    pub fn expected_host_key(&self, ip: IpAddr) -> Option<&str> {
+        self.host_key_fingerprint
+            .as_deref()
+            .or_else(|| self.known_host_keys.get(&ip).map(String::as_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn login(username: &str, cidrs: &[&str]) -> SshLogin {
+        SshLogin {
+            cidrs: cidrs.iter().map(|cidr| cidr.parse().unwrap()).collect(),
+            port: 22,
+            username: username.to_string(),
+            password: Some("secret".to_string()),
+            private_key: None,
+            passphrase: None,
+            host_key_fingerprint: None,
+            known_host_keys: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn host_login_wins_over_subnet_login() {
+        let logins = vec![
+            login("subnet", &["192.168.1.0/24"]),
+            login("host", &["192.168.1.10/32", "10.0.0.5/32"]),
+        ];
+
+        let for_ip = |ip: &str| SshLogin::for_ip(&logins, ip.parse().unwrap());
+        assert_eq!(for_ip("192.168.1.10").unwrap().username, "host");
+        assert_eq!(for_ip("10.0.0.5").unwrap().username, "host");
+        assert_eq!(for_ip("192.168.1.11").unwrap().username, "subnet");
+        assert!(for_ip("172.16.0.1").is_none());
+    }
+
+    #[test]
+    fn known_host_keys_are_pinned_unless_the_credential_sets_one() {
+        let known: IpAddr = "192.168.1.10".parse().unwrap();
+        let unknown: IpAddr = "192.168.1.11".parse().unwrap();
+
+        let mut subnet = login("subnet", &["192.168.1.0/24"]);
+        subnet
+            .known_host_keys
+            .insert(known, "SHA256:seen".to_string());
+        assert_eq!(subnet.expected_host_key(known), Some("SHA256:seen"));
+        assert_eq!(subnet.expected_host_key(unknown), None);
+
+        subnet.host_key_fingerprint = Some("SHA256:pinned".to_string());
+        assert_eq!(subnet.expected_host_key(known), Some("SHA256:pinned"));
+        assert_eq!(subnet.expected_host_key(unknown), Some("SHA256:pinned"));
+    }
+
+    #[test]
+    fn redacted_secrets_are_kept() {
+        let existing = SshCredentialBase {
+            network_id: Uuid::nil(),
+            host_id: Some(Uuid::nil()),
+            subnet_id: None,
+            username: "root".to_string(),
+            password: Some("old".to_string()),
+            private_key: Some("key".to_string()),
+            passphrase: None,
+            port: 22,
+            host_key_fingerprint: None,
+        };
+        let mut update = SshCredentialBase {
+            password: Some("new".to_string()),
+            private_key: Some(REDACTED.to_string()),
+            ..existing.clone()
+        };
+
+        update.keep_secrets(&existing);
+        assert_eq!(update.password.as_deref(), Some("new"));
+        assert_eq!(update.private_key.as_deref(), Some("key"));
+        assert_eq!(update.passphrase, None);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::server::shared::handlers::traits::CrudHandlers;
+use crate::server::ssh_credentials::r#impl::base::SshCredential;
+use crate::server::ssh_credentials::service::SshCredentialService;
+use uuid::Uuid;
+
+impl CrudHandlers for SshCredential {
+    type Service = SshCredentialService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.ssh_credential_service
+    }
+
+    fn entity_name() -> &'static str {
+        "SSH credential"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.host_id.is_some() == self.base.subnet_id.is_some() {
+            return Err("Set either a host or a subnet".to_string());
+        }
+        if self.base.username.trim().is_empty() {
+            return Err("Username is required".to_string());
+        }
+        if self.base.password.is_none() && self.base.private_key.is_none() {
+            return Err("Set a password or a private key".to_string());
+        }
+        if self.base.port == 0 {
+            return Err("Port can't be 0".to_string());
+        }
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
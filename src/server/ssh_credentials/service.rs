@@ -0,0 +1,149 @@
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use cidr::IpCidr;
+use std::{collections::HashMap, net::IpAddr, sync::Arc};
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::base::Host,
+    shared::{
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, generic::GenericPostgresStorage, traits::Storage},
+    },
+    ssh_credentials::r#impl::base::{SshCredential, SshCredentialBase, SshLogin},
+    subnets::r#impl::base::Subnet,
+};
+
+/// Credentials for reading hosts over SSH. Daemons receive them with each network scan and
+/// log into the hosts they cover that have SSH open.
+pub struct SshCredentialService {
+    storage: Arc<GenericPostgresStorage<SshCredential>>,
+    host_storage: Arc<GenericPostgresStorage<Host>>,
+    subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+}
+
+#[async_trait]
+impl CrudService<SshCredential> for SshCredentialService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<SshCredential>> {
+        &self.storage
+    }
+}
+
+impl SshCredentialService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<SshCredential>>,
+        host_storage: Arc<GenericPostgresStorage<Host>>,
+        subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+    ) -> Self {
+        Self {
+            storage,
+            host_storage,
+            subnet_storage,
+        }
+    }
+
+    /// Fails unless the credential's host or subnet is in its network
+    pub async fn check_target(&self, base: &SshCredentialBase) -> Result<()> {
+        let network_id = match (base.host_id, base.subnet_id) {
+            (Some(host_id), _) => {
+                self.host_storage
+                    .get_by_id(&host_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Host '{}' not found", host_id))?
+                    .base
+                    .network_id
+            }
+            (_, Some(subnet_id)) => {
+                self.subnet_storage
+                    .get_by_id(&subnet_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Subnet '{}' not found", subnet_id))?
+                    .base
+                    .network_id
+            }
+            (None, None) => bail!("Set either a host or a subnet"),
+        };
+
+        if network_id != base.network_id {
+            bail!("The host or subnet is in another network");
+        }
+        Ok(())
+    }
+
+    /// The network's credentials with the addresses each applies to, for a scan request. Each
+    /// carries the host keys seen at those addresses, so the daemon rejects a host presenting
+    /// a different one.
+    pub async fn daemon_logins(&self, network_id: &Uuid) -> Result<Vec<SshLogin>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let credentials = self.storage.get_all(filter.clone()).await?;
+        if credentials.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let hosts = self.host_storage.get_all(filter.clone()).await?;
+        let subnets = self.subnet_storage.get_all(filter).await?;
+
+        let host_keys: HashMap<IpAddr, &str> = hosts
+            .iter()
+            .filter_map(|host| {
+                let ssh = host.base.ssh.as_ref()?;
+                (!ssh.host_key_fingerprint.is_empty()).then_some((host, ssh))
+            })
+            .flat_map(|(host, ssh)| {
+                host.base
+                    .interfaces
+                    .iter()
+                    .map(|i| (i.base.ip_address, ssh.host_key_fingerprint.as_str()))
+            })
+            .collect();
+
+        Ok(credentials
+            .into_iter()
+            .filter_map(|credential| {
+                let cidrs: Vec<IpCidr> = match (credential.base.host_id, credential.base.subnet_id)
+                {
+                    (Some(host_id), _) => hosts
+                        .iter()
+                        .find(|h| h.id == host_id)?
+                        .base
+                        .interfaces
+                        .iter()
+                        .map(|i| IpCidr::new_host(i.base.ip_address))
+                        .collect(),
+                    (_, Some(subnet_id)) => {
+                        vec![subnets.iter().find(|s| s.id == subnet_id)?.base.cidr]
+                    }
+                    (None, None) => return None,
+                };
+
+                let SshCredentialBase {
+                    username,
+                    password,
+                    private_key,
+                    passphrase,
+                    port,
+                    host_key_fingerprint,
+                    ..
+                } = credential.base;
+
+                let known_host_keys = host_keys
+                    .iter()
+                    .filter(|(ip, _)| cidrs.iter().any(|cidr| cidr.contains(ip)))
+                    .map(|(ip, fingerprint)| (*ip, fingerprint.to_string()))
+                    .collect();
+
+                Some(SshLogin {
+                    cidrs,
+                    port,
+                    username,
+                    password,
+                    private_key,
+                    passphrase,
+                    host_key_fingerprint,
+                    known_host_keys,
+                })
+            })
+            .filter(|login| !login.cidrs.is_empty())
+            .collect())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::server::shared::handlers::traits::{
-    CrudHandlers, delete_handler, get_by_id_handler, update_handler,
+    CrudHandlers, ListQuery, check_entity_access, delete_handler, get_by_id_handler,
+    restore_handler, update_handler,
 };
 use crate::server::shared::types::api::ApiError;
 use crate::server::{
@@ -13,7 +14,11 @@ use crate::server::{
     subnets::r#impl::base::Subnet,
 };
 use axum::routing::{delete, get, post, put};
-use axum::{Router, extract::State, response::Json};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+};
 use std::sync::Arc;
 
 pub fn create_router() -> Router<Arc<AppState>> {
@@ -23,11 +28,12 @@ pub fn create_router() -> Router<Arc<AppState>> {
         .route("/{id}", put(update_handler::<Subnet>))
         .route("/{id}", delete(delete_handler::<Subnet>))
         .route("/{id}", get(get_by_id_handler::<Subnet>))
+        .route("/{id}/restore", post(restore_handler::<Subnet>))
 }
 
 pub async fn create_handler(
     State(state): State<Arc<AppState>>,
-    _entity: AuthenticatedEntity,
+    entity: AuthenticatedEntity,
     Json(request): Json<Subnet>,
 ) -> ApiResult<Json<ApiResponse<Subnet>>> {
     if let Err(err) = request.validate() {
@@ -36,6 +42,7 @@ pub async fn create_handler(
             err
         )));
     }
+    check_entity_access(&state, &entity, &request).await?;
 
     let service = Subnet::get_service(&state);
     let created = service
@@ -49,6 +56,7 @@ pub async fn create_handler(
 async fn get_all_subnets(
     State(state): State<Arc<AppState>>,
     entity: AuthenticatedEntity,
+    Query(query): Query<ListQuery>,
 ) -> ApiResult<Json<ApiResponse<Vec<Subnet>>>> {
     let service = &state.services.subnet_service;
 
@@ -57,7 +65,7 @@ async fn get_all_subnets(
             vec![network_id]
         }
         AuthenticatedEntity::User(user_id) => {
-            let filter = EntityFilter::unfiltered().user_id(&user_id);
+            let filter = EntityFilter::unfiltered().network_member(&user_id);
 
             state
                 .services
@@ -70,9 +78,9 @@ async fn get_all_subnets(
         }
     };
 
-    let filter = EntityFilter::unfiltered().network_ids(&network_ids);
+    let filter = query.apply::<Subnet>(EntityFilter::unfiltered().network_ids(&network_ids))?;
 
-    let subnets = service.get_all(filter).await?;
+    let subnets = service.get_page(filter).await?;
 
-    Ok(Json(ApiResponse::success(subnets)))
+    Ok(Json(ApiResponse::page(subnets)))
 }
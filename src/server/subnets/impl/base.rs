@@ -4,8 +4,9 @@ use std::net::Ipv4Addr;
 use crate::server::discovery::r#impl::types::DiscoveryType;
 use crate::server::shared::storage::traits::StorableEntity;
 use crate::server::shared::types::api::deserialize_empty_string_as_none;
+use crate::server::shared::types::attributes::EntityAttributes;
 use crate::server::shared::types::entities::{DiscoveryMetadata, EntitySource};
-use crate::server::subnets::r#impl::types::SubnetType;
+use crate::server::subnets::r#impl::types::{SubnetLayer2, SubnetType};
 use chrono::{DateTime, Utc};
 use cidr::{IpCidr, Ipv4Cidr};
 use pnet::ipnetwork::IpNetwork;
@@ -27,17 +28,26 @@ pub struct SubnetBase {
     pub description: Option<String>,
     pub subnet_type: SubnetType,
     pub source: EntitySource,
+    /// VLAN and bridge relationships, when known
+    #[serde(default)]
+    pub layer2: SubnetLayer2,
+    /// Tags and custom fields set by users
+    #[serde(default)]
+    #[validate(nested)]
+    pub attributes: EntityAttributes,
 }
 
 impl Default for SubnetBase {
     fn default() -> Self {
         Self {
-            cidr: IpCidr::V4(Ipv4Cidr::new(Ipv4Addr::new(127, 0, 0, 1), 24).unwrap()),
+            cidr: IpCidr::V4(Ipv4Cidr::new(Ipv4Addr::new(127, 0, 0, 0), 24).unwrap()),
             name: "New Subnet".to_string(),
             network_id: Uuid::new_v4(),
             description: None,
             subnet_type: SubnetType::Unknown,
             source: EntitySource::Manual,
+            layer2: SubnetLayer2::default(),
+            attributes: EntityAttributes::default(),
         }
     }
 }
@@ -47,6 +57,9 @@ pub struct Subnet {
     pub id: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Set while the subnet is in the trash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
     #[serde(flatten)]
     pub base: SubnetBase,
 }
@@ -93,6 +106,8 @@ impl Subnet {
                     source: EntitySource::Discovery {
                         metadata: vec![DiscoveryMetadata::new(discovery_type.clone(), daemon_id)],
                     },
+                    layer2: SubnetLayer2::default(),
+                    attributes: EntityAttributes::default(),
                 }))
             }
         }
@@ -2,6 +2,7 @@ use crate::server::{
     shared::handlers::traits::CrudHandlers,
     subnets::{r#impl::base::Subnet, service::SubnetService},
 };
+use uuid::Uuid;
 
 impl CrudHandlers for Subnet {
     type Service = SubnetService;
@@ -9,4 +10,8 @@ impl CrudHandlers for Subnet {
     fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
         &state.services.subnet_service
     }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
 }
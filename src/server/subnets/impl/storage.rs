@@ -8,11 +8,11 @@ use uuid::Uuid;
 use crate::server::{
     shared::{
         storage::traits::{SqlValue, StorableEntity},
-        types::entities::EntitySource,
+        types::{attributes::EntityAttributes, entities::EntitySource},
     },
     subnets::r#impl::{
         base::{Subnet, SubnetBase},
-        types::SubnetType,
+        types::{SubnetLayer2, SubnetType},
     },
 };
 
@@ -23,6 +23,22 @@ impl StorableEntity for Subnet {
         "subnets"
     }
 
+    fn has_attributes() -> bool {
+        true
+    }
+
+    fn sortable_columns() -> &'static [&'static str] {
+        &["name", "cidr"]
+    }
+
+    fn search_columns() -> &'static [&'static str] {
+        &["name", "description", "cidr"]
+    }
+
+    fn soft_delete() -> bool {
+        true
+    }
+
     fn get_base(&self) -> Self::BaseData {
         self.base.clone()
     }
@@ -34,6 +50,7 @@ impl StorableEntity for Subnet {
             id: Uuid::new_v4(),
             created_at: now,
             updated_at: now,
+            deleted_at: None,
             base,
         }
     }
@@ -59,6 +76,7 @@ impl StorableEntity for Subnet {
             id,
             created_at,
             updated_at,
+            deleted_at: _,
             base:
                 Self::BaseData {
                     name,
@@ -67,6 +85,8 @@ impl StorableEntity for Subnet {
                     cidr,
                     subnet_type,
                     description,
+                    layer2,
+                    attributes,
                 },
         } = self.clone();
 
@@ -79,6 +99,8 @@ impl StorableEntity for Subnet {
                 "source",
                 "subnet_type",
                 "network_id",
+                "layer2",
+                "attributes",
                 "created_at",
                 "updated_at",
             ],
@@ -90,6 +112,8 @@ impl StorableEntity for Subnet {
                 SqlValue::EntitySource(source),
                 SqlValue::SubnetType(subnet_type),
                 SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(layer2)?),
+                SqlValue::Json(serde_json::to_value(&attributes)?),
                 SqlValue::Timestamp(created_at),
                 SqlValue::Timestamp(updated_at),
             ],
@@ -105,11 +129,19 @@ impl StorableEntity for Subnet {
         let source: EntitySource =
             serde_json::from_value(row.get::<serde_json::Value, _>("source"))
                 .or(Err(Error::msg("Failed to deserialize source")))?;
+        let layer2: SubnetLayer2 =
+            serde_json::from_value(row.get::<serde_json::Value, _>("layer2"))
+                .or(Err(Error::msg("Failed to deserialize layer2")))?;
+
+        let attributes: EntityAttributes =
+            serde_json::from_value(row.get::<serde_json::Value, _>("attributes"))
+                .or(Err(Error::msg("Failed to deserialize attributes")))?;
 
         Ok(Subnet {
             id: row.get("id"),
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
+            deleted_at: row.get("deleted_at"),
             base: SubnetBase {
                 name: row.get("name"),
                 description: row.get("description"),
@@ -117,6 +149,8 @@ impl StorableEntity for Subnet {
                 source,
                 cidr,
                 subnet_type,
+                layer2,
+                attributes,
             },
         })
     }
@@ -43,6 +43,26 @@ pub enum SubnetType {
     None,
 }
 
+/// Layer 2 details of the interface a subnet was discovered on
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq, Hash)]
+#[serde(default)]
+pub struct SubnetLayer2 {
+    /// 802.1Q tag when the subnet is carried on a VLAN
+    pub vlan_id: Option<u16>,
+    /// Trunk interface the VLAN is tagged on, e.g. `eth0` for `eth0.20`
+    pub parent_interface: Option<String>,
+    /// Bridge the interface belongs to, or its own name if it is a bridge
+    pub bridge: Option<String>,
+    /// Ports enslaved to the bridge
+    pub bridge_members: Vec<String>,
+}
+
+impl SubnetLayer2 {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 impl SubnetType {
     pub fn from_interface_name(interface_name: &str) -> Self {
         // Docker containers
@@ -91,7 +91,14 @@ impl CrudService<Subnet> for SubnetService {
                     existing_subnet.base.name,
                     existing_subnet.id
                 );
-                existing_subnet.clone()
+
+                // Subnets discovered before VLAN/bridge support pick it up on the next scan
+                let mut existing_subnet = existing_subnet.clone();
+                if existing_subnet.base.layer2.is_empty() && !subnet.base.layer2.is_empty() {
+                    existing_subnet.base.layer2 = subnet.base.layer2.clone();
+                    self.storage.update(&mut existing_subnet).await?;
+                }
+                existing_subnet
             }
             // If there's no existing subnet, create a new one
             _ => {
@@ -0,0 +1,293 @@
+use std::{collections::HashMap, fmt::Write};
+
+use petgraph::Graph;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::server::topology::types::{
+    edges::{Edge, EdgeType},
+    nodes::{Node, NodeType},
+};
+
+/// Text formats the topology can be exported to for embedding in docs
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// GraphViz
+    #[default]
+    Dot,
+    Mermaid,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Dot => "text/vnd.graphviz; charset=utf-8",
+            ExportFormat::Mermaid => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+/// Converts a laid out topology into diagram text. Subnets become clusters containing their
+/// interface nodes; positions are left to the renderer.
+pub struct TopologyExporter<'a> {
+    graph: &'a Graph<Node, Edge>,
+    /// Display names by node id, falling back to the node header
+    labels: &'a HashMap<Uuid, String>,
+}
+
+impl<'a> TopologyExporter<'a> {
+    pub fn new(graph: &'a Graph<Node, Edge>, labels: &'a HashMap<Uuid, String>) -> Self {
+        Self { graph, labels }
+    }
+
+    pub fn export(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Dot => self.to_dot(),
+            ExportFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out =
+            String::from("digraph topology {\n    compound=true;\n    node [shape=box];\n");
+
+        for (subnet, children) in self.clusters() {
+            let _ = writeln!(out, "    subgraph \"cluster_{}\" {{", subnet.id.simple());
+            let _ = writeln!(
+                out,
+                "        label=\"{}\";",
+                dot_escape(&self.label(subnet))
+            );
+            for child in children {
+                let _ = writeln!(
+                    out,
+                    "        {} [label=\"{}\"];",
+                    node_id(child.id),
+                    dot_escape(&self.label(child))
+                );
+            }
+            out.push_str("    }\n");
+        }
+
+        for edge in self.graph.edge_weights() {
+            let mut attributes = vec![edge_style(&edge.edge_type).dot_attributes().to_string()];
+            if let Some(label) = &edge.label {
+                attributes.push(format!("label=\"{}\"", dot_escape(label)));
+            }
+            let _ = writeln!(
+                out,
+                "    {} -> {} [{}];",
+                node_id(edge.source),
+                node_id(edge.target),
+                attributes.join(", ")
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TB\n");
+
+        for (subnet, children) in self.clusters() {
+            let _ = writeln!(
+                out,
+                "    subgraph {}[\"{}\"]",
+                node_id(subnet.id),
+                mermaid_escape(&self.label(subnet))
+            );
+            for child in children {
+                let _ = writeln!(
+                    out,
+                    "        {}[\"{}\"]",
+                    node_id(child.id),
+                    mermaid_escape(&self.label(child))
+                );
+            }
+            out.push_str("    end\n");
+        }
+
+        for edge in self.graph.edge_weights() {
+            let arrow = edge_style(&edge.edge_type).mermaid_arrow();
+            let label = match &edge.label {
+                Some(label) => format!("|\"{}\"|", mermaid_escape(label)),
+                None => String::new(),
+            };
+            let _ = writeln!(
+                out,
+                "    {} {}{} {}",
+                node_id(edge.source),
+                arrow,
+                label,
+                node_id(edge.target)
+            );
+        }
+
+        out
+    }
+
+    /// Subnet nodes with their children, in graph order
+    fn clusters(&self) -> Vec<(&'a Node, Vec<&'a Node>)> {
+        let mut children: HashMap<Uuid, Vec<&'a Node>> = HashMap::new();
+        for node in self.graph.node_weights() {
+            if let NodeType::InterfaceNode { subnet_id, .. } = node.node_type {
+                children.entry(subnet_id).or_default().push(node);
+            }
+        }
+
+        self.graph
+            .node_weights()
+            .filter(|n| matches!(n.node_type, NodeType::SubnetNode { .. }))
+            .map(|subnet| (subnet, children.remove(&subnet.id).unwrap_or_default()))
+            .collect()
+    }
+
+    fn label(&self, node: &Node) -> String {
+        self.labels
+            .get(&node.id)
+            .cloned()
+            .or_else(|| node.header.clone())
+            .unwrap_or_else(|| node.id.to_string())
+    }
+}
+
+enum EdgeStyle {
+    /// A host's interfaces in different subnets
+    Link,
+    Virtualization,
+    /// Group edges, which point from requester to provider
+    Request,
+}
+
+impl EdgeStyle {
+    fn dot_attributes(&self) -> &'static str {
+        match self {
+            EdgeStyle::Link => "dir=none",
+            EdgeStyle::Virtualization => "dir=none, style=dashed",
+            EdgeStyle::Request => "style=bold",
+        }
+    }
+
+    fn mermaid_arrow(&self) -> &'static str {
+        match self {
+            EdgeStyle::Link => "---",
+            EdgeStyle::Virtualization => "-.-",
+            EdgeStyle::Request => "==>",
+        }
+    }
+}
+
+fn edge_style(edge_type: &EdgeType) -> EdgeStyle {
+    match edge_type {
+        EdgeType::Interface { .. } | EdgeType::Trunk { .. } => EdgeStyle::Link,
+        EdgeType::HostVirtualization { .. } | EdgeType::ServiceVirtualization { .. } => {
+            EdgeStyle::Virtualization
+        }
+        EdgeType::RequestPath { .. } | EdgeType::HubAndSpoke { .. } => EdgeStyle::Request,
+    }
+}
+
+/// Identifier that's valid unquoted in both DOT and Mermaid
+fn node_id(id: Uuid) -> String {
+    format!("n{}", id.simple())
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::topology::types::{
+        base::{Ixy, Uxy},
+        edges::EdgeHandle,
+    };
+
+    fn node(id: Uuid, node_type: NodeType) -> Node {
+        Node {
+            node_type,
+            id,
+            position: Ixy { x: 0, y: 0 },
+            size: Uxy { x: 0, y: 0 },
+            header: None,
+            pinned: false,
+            anchored: false,
+            aggregate: None,
+            badges: Vec::new(),
+            os: None,
+        }
+    }
+
+    fn graph() -> (Graph<Node, Edge>, HashMap<Uuid, String>) {
+        let subnet_id = Uuid::new_v4();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let host_id = Uuid::new_v4();
+
+        let mut graph = Graph::new();
+        graph.add_node(node(subnet_id, NodeType::SubnetNode { infra_width: 0 }));
+        let [na, nb] = [a, b].map(|id| {
+            graph.add_node(node(
+                id,
+                NodeType::InterfaceNode {
+                    subnet_id,
+                    host_id,
+                    interface_id: Some(id),
+                    is_infra: false,
+                },
+            ))
+        });
+        graph.add_edge(
+            na,
+            nb,
+            Edge {
+                source: a,
+                target: b,
+                edge_type: EdgeType::Interface { host_id },
+                label: None,
+                source_handle: EdgeHandle::Right,
+                target_handle: EdgeHandle::Left,
+                is_multi_hop: false,
+            },
+        );
+
+        let labels = HashMap::from([
+            (subnet_id, "Home \"LAN\"".to_string()),
+            (a, "router".to_string()),
+            (b, "nas".to_string()),
+        ]);
+
+        (graph, labels)
+    }
+
+    #[test]
+    fn dot_groups_children_into_subnet_clusters() {
+        let (graph, labels) = graph();
+        let dot = TopologyExporter::new(&graph, &labels).to_dot();
+
+        assert!(dot.starts_with("digraph topology {"));
+        assert!(dot.contains("subgraph \"cluster_"));
+        assert!(dot.contains("label=\"Home \\\"LAN\\\"\";"));
+        assert!(dot.contains("[label=\"router\"]"));
+        assert!(dot.contains("[dir=none]"));
+    }
+
+    #[test]
+    fn mermaid_groups_children_into_subgraphs() {
+        let (graph, labels) = graph();
+        let mermaid = TopologyExporter::new(&graph, &labels).to_mermaid();
+
+        assert!(mermaid.starts_with("flowchart TB\n"));
+        assert!(mermaid.contains("[\"Home #quot;LAN#quot;\"]"));
+        assert_eq!(mermaid.matches("    end\n").count(), 1);
+        assert!(mermaid.contains(" --- "));
+    }
+}
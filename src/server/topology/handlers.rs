@@ -1,25 +1,223 @@
 use crate::server::{
     auth::middleware::AuthenticatedUser,
     config::AppState,
-    shared::types::api::{ApiResponse, ApiResult},
-    topology::types::api::TopologyRequestOptions,
+    hosts::r#impl::base::Host,
+    network_members::handlers::network_role,
+    services::r#impl::base::Service,
+    shared::{
+        handlers::traits::get_accessible,
+        services::traits::CrudService,
+        storage::filter::EntityFilter,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    subnets::r#impl::base::Subnet,
+    topology::{
+        exporter::TopologyExporter,
+        service::optimizer::utils::LayoutReport,
+        types::{
+            analytics::{FailureImpact, TopologyAnalytics},
+            api::{
+                ImpactQuery, ServicePathQuery, TopologyDetailLevel, TopologyExportQuery,
+                TopologyQualityQuery, TopologyRequestOptions,
+            },
+            paths::ServicePath,
+        },
+    },
+    topology_snapshots::handlers::get_topology_diff,
 };
-use axum::{Router, extract::State, response::Json, routing::post};
-use std::sync::Arc;
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json},
+    routing::{get, post},
+};
+use petgraph::Graph;
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
 
 pub fn create_router() -> Router<Arc<AppState>> {
-    Router::new().route("/", post(get_topology))
+    Router::new()
+        .route("/", post(get_topology))
+        .route("/subnets/{subnet_id}", post(expand_subnet))
+        .route("/analytics", post(get_analytics))
+        .route("/impact", get(get_failure_impact))
+        .route("/path", get(trace_service_path))
+        .route("/export", get(export_topology))
+        .route("/quality", get(get_layout_quality))
+        .route("/diff", get(get_topology_diff))
 }
 
 async fn get_topology(
     State(state): State<Arc<AppState>>,
-    _user: AuthenticatedUser,
+    user: AuthenticatedUser,
     Json(request): Json<TopologyRequestOptions>,
 ) -> ApiResult<Json<ApiResponse<serde_json::Value>>> {
+    check_networks(&state, &user, &request).await?;
+    let service = &state.services.topology_service;
+
+    let json = match request.detail_level {
+        TopologyDetailLevel::Full => {
+            let (graph, report) = service.build_graph(request).await?;
+            let mut json = serde_json::to_value(graph)?;
+            // Alongside the serialized graph fields so existing clients are unaffected
+            if let Some(object) = json.as_object_mut() {
+                object.insert("quality".to_string(), serde_json::to_value(report)?);
+            }
+            json
+        }
+        TopologyDetailLevel::Subnets => {
+            serde_json::to_value(service.build_summary(request).await?)?
+        }
+    };
+
+    Ok(Json(ApiResponse::success(json)))
+}
+
+/// Lay out a single subnet's children on demand
+async fn expand_subnet(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(subnet_id): Path<Uuid>,
+    Json(request): Json<TopologyRequestOptions>,
+) -> ApiResult<Json<ApiResponse<serde_json::Value>>> {
+    get_accessible::<Subnet>(&state, &user.0, &subnet_id).await?;
+    check_networks(&state, &user, &request).await?;
+
     let service = &state.services.topology_service;
-    let graph = service.build_graph(request).await?;
+    let graph = service.build_subnet_graph(subnet_id, request).await?;
 
     let json = serde_json::to_value(&graph)?;
 
     Ok(Json(ApiResponse::success(json)))
 }
+
+async fn get_analytics(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<TopologyRequestOptions>,
+) -> ApiResult<Json<ApiResponse<TopologyAnalytics>>> {
+    check_networks(&state, &user, &request).await?;
+    let service = &state.services.topology_service;
+    let analytics = service.analyze(request).await?;
+
+    Ok(Json(ApiResponse::success(analytics)))
+}
+
+/// Which hosts and services are affected if the given host goes offline
+async fn get_failure_impact(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<ImpactQuery>,
+) -> ApiResult<Json<ApiResponse<FailureImpact>>> {
+    let host: Host = get_accessible(&state, &user.0, &query.host).await?;
+
+    let service = &state.services.topology_service;
+    let impact = service.failure_impact(&host).await?;
+
+    Ok(Json(ApiResponse::success(impact)))
+}
+
+/// Walk group edges and network links between two services
+async fn trace_service_path(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<ServicePathQuery>,
+) -> ApiResult<Json<ApiResponse<ServicePath>>> {
+    let source: Service = get_accessible(&state, &user.0, &query.source).await?;
+    let target: Service = get_accessible(&state, &user.0, &query.target).await?;
+
+    let service = &state.services.topology_service;
+    let path = service.trace_service_path(&source, &target).await?;
+
+    Ok(Json(ApiResponse::success(path)))
+}
+
+/// Diagram of the topology as GraphViz DOT or Mermaid, with subnets as clusters
+async fn export_topology(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<TopologyExportQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let network_ids = user_network_ids(&state, &user, query.network_id).await?;
+
+    // Nothing to look up for a user without networks
+    let text = if network_ids.is_empty() {
+        TopologyExporter::new(&Graph::new(), &HashMap::new()).export(query.format)
+    } else {
+        let options = TopologyRequestOptions {
+            network_ids,
+            ..Default::default()
+        };
+        state
+            .services
+            .topology_service
+            .export(options, query.format)
+            .await?
+    };
+
+    Ok(([(header::CONTENT_TYPE, query.format.content_type())], text))
+}
+
+/// Edge length, crossings and per-iteration convergence of the current layout
+async fn get_layout_quality(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<TopologyQualityQuery>,
+) -> ApiResult<Json<ApiResponse<LayoutReport>>> {
+    let network_ids = user_network_ids(&state, &user, query.network_id).await?;
+
+    // Nothing to look up for a user without networks
+    let report = if network_ids.is_empty() {
+        LayoutReport::default()
+    } else {
+        let options = TopologyRequestOptions {
+            network_ids,
+            ..Default::default()
+        };
+        state.services.topology_service.quality(options).await?
+    };
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Rejects topologies of networks the user has no access to, or of none at all
+async fn check_networks(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    request: &TopologyRequestOptions,
+) -> ApiResult<()> {
+    if request.network_ids.is_empty() {
+        return Err(ApiError::bad_request("At least one network is required"));
+    }
+    for network_id in &request.network_ids {
+        network_role(state, &user.0, network_id).await?;
+    }
+    Ok(())
+}
+
+/// The requested network if the user has access to it, otherwise all of the user's networks
+async fn user_network_ids(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    network_id: Option<Uuid>,
+) -> ApiResult<Vec<Uuid>> {
+    let user_filter = EntityFilter::unfiltered().network_member(&user.0);
+    let network_ids: Vec<Uuid> = state
+        .services
+        .network_service
+        .get_all(user_filter)
+        .await?
+        .iter()
+        .map(|n| n.id)
+        .collect();
+
+    match network_id {
+        Some(network_id) if network_ids.contains(&network_id) => Ok(vec![network_id]),
+        Some(network_id) => Err(ApiError::not_found(format!(
+            "Network '{}' not found",
+            network_id
+        ))),
+        None => Ok(network_ids),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod exporter;
 pub mod handlers;
 pub mod service;
 pub mod types;
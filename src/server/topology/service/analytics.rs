@@ -0,0 +1,470 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use petgraph::{algo::articulation_points::articulation_points, graph::NodeIndex};
+use uuid::Uuid;
+
+use crate::server::{
+    services::r#impl::definitions::ServiceDefinitionExt,
+    topology::{
+        service::{connectivity::ConnectivityGraph, context::TopologyContext},
+        types::analytics::{
+            ArticulationPoint, ConnectivityNode, FailureImpact, HostMetrics, ServiceImpact,
+            ServiceImpactReason, TopologyAnalytics,
+        },
+    },
+};
+
+/// Structural insights over the host connectivity graph
+pub struct TopologyAnalyzer<'a> {
+    context: &'a TopologyContext<'a>,
+    connectivity: ConnectivityGraph,
+}
+
+impl<'a> TopologyAnalyzer<'a> {
+    pub fn new(ctx: &'a TopologyContext<'a>) -> Self {
+        Self {
+            context: ctx,
+            connectivity: ConnectivityGraph::from_context(ctx),
+        }
+    }
+
+    pub fn analyze(&self) -> TopologyAnalytics {
+        TopologyAnalytics {
+            host_metrics: self.host_metrics(),
+            articulation_points: self.articulation_points(),
+            isolated_hosts: self.isolated_hosts(),
+        }
+    }
+
+    fn host_name(&self, host_id: Uuid) -> String {
+        self.context
+            .get_host_by_id(host_id)
+            .map(|h| h.base.name.clone())
+            .unwrap_or_default()
+    }
+
+    fn host_metrics(&self) -> Vec<HostMetrics> {
+        let centrality = self.connectivity.betweenness_centrality();
+
+        self.connectivity
+            .host_indices()
+            .map(|(host_id, idx)| HostMetrics {
+                host_id,
+                name: self.host_name(host_id),
+                degree: self.connectivity.degree(idx),
+                betweenness_centrality: centrality.get(&idx).copied().unwrap_or(0.0),
+            })
+            .sorted_by(|a, b| {
+                b.betweenness_centrality
+                    .total_cmp(&a.betweenness_centrality)
+                    .then(b.degree.cmp(&a.degree))
+                    .then(a.name.cmp(&b.name))
+            })
+            .collect()
+    }
+
+    /// Hosts whose removal leaves the remaining hosts of their island split up. Subnets are
+    /// excluded - every subnet is trivially a cut vertex for its single-homed hosts.
+    fn articulation_points(&self) -> Vec<ArticulationPoint> {
+        articulation_points(&self.connectivity.graph)
+            .into_iter()
+            .filter_map(|idx| {
+                let host_id = self.connectivity.host_id(idx)?;
+                let partitions = self.partitions_after_removal(&HashSet::from([idx]));
+
+                if partitions.len() < 2 {
+                    return None;
+                }
+
+                Some(ArticulationPoint {
+                    host_id,
+                    name: self.host_name(host_id),
+                    resulting_partitions: partitions.len(),
+                    stranded_host_ids: partitions.into_iter().skip(1).flatten().collect(),
+                })
+            })
+            .sorted_by_key(|point| std::cmp::Reverse(point.stranded_host_ids.len()))
+            .collect()
+    }
+
+    /// Host islands that neighbored the removed nodes, largest first
+    fn partitions_after_removal(&self, removed: &HashSet<NodeIndex>) -> Vec<Vec<Uuid>> {
+        let neighbors: HashSet<NodeIndex> = removed
+            .iter()
+            .flat_map(|idx| self.connectivity.graph.neighbors(*idx))
+            .filter(|idx| !removed.contains(idx))
+            .collect();
+
+        self.connectivity
+            .components_excluding(removed)
+            .into_iter()
+            .filter(|component| component.iter().any(|n| neighbors.contains(n)))
+            .map(|component| self.connectivity.hosts_in(&component))
+            .filter(|hosts| !hosts.is_empty())
+            .sorted_by_key(|hosts| std::cmp::Reverse(hosts.len()))
+            .collect()
+    }
+
+    /// Simulate the host going offline, along with any VMs it runs
+    pub fn failure_impact(&self, host_id: Uuid) -> FailureImpact {
+        let removed_host_ids = self.hosts_removed_with(host_id);
+        let removed_indices: HashSet<NodeIndex> = removed_host_ids
+            .iter()
+            .filter_map(|id| self.connectivity.node_index(ConnectivityNode::Host(*id)))
+            .collect();
+
+        let disconnected_host_ids: HashSet<Uuid> = self
+            .partitions_after_removal(&removed_indices)
+            .into_iter()
+            .skip(1)
+            .flatten()
+            .collect();
+
+        let subnets_without_gateway: Vec<Uuid> = self
+            .context
+            .subnets
+            .iter()
+            .filter(|subnet| {
+                let gateway_host_ids: HashSet<Uuid> = self
+                    .context
+                    .services
+                    .iter()
+                    .filter(|s| s.base.service_definition.is_gateway())
+                    .filter(|s| {
+                        self.context
+                            .get_host_by_id(s.base.host_id)
+                            .is_some_and(|host| subnet.has_interface_with_service(host, s))
+                    })
+                    .map(|s| s.base.host_id)
+                    .collect();
+
+                !gateway_host_ids.is_empty() && gateway_host_ids.is_subset(&removed_host_ids)
+            })
+            .map(|subnet| subnet.id)
+            .collect();
+
+        let hosts_without_gateway: Vec<Uuid> = self
+            .context
+            .hosts
+            .iter()
+            .filter(|h| !removed_host_ids.contains(&h.id))
+            .filter(|h| {
+                h.base
+                    .interfaces
+                    .iter()
+                    .any(|i| subnets_without_gateway.contains(&i.base.subnet_id))
+            })
+            .map(|h| h.id)
+            .collect();
+
+        let mut affected_services: Vec<ServiceImpact> = self
+            .context
+            .services
+            .iter()
+            .filter_map(|s| {
+                let reason = if removed_host_ids.contains(&s.base.host_id) {
+                    ServiceImpactReason::HostRemoved
+                } else if disconnected_host_ids.contains(&s.base.host_id) {
+                    ServiceImpactReason::HostDisconnected
+                } else {
+                    return None;
+                };
+
+                Some(ServiceImpact {
+                    service_id: s.id,
+                    host_id: s.base.host_id,
+                    reason,
+                })
+            })
+            .collect();
+
+        for group in self.context.groups {
+            for (source_binding_id, target_binding_id) in group.base.group_type.binding_pairs() {
+                let (Some(source), Some(target)) = (
+                    self.context.get_service_by_binding_id(source_binding_id),
+                    self.context.get_service_by_binding_id(target_binding_id),
+                ) else {
+                    continue;
+                };
+
+                for (lost, dependent) in [(source, target), (target, source)] {
+                    if !removed_host_ids.contains(&lost.base.host_id)
+                        || removed_host_ids.contains(&dependent.base.host_id)
+                    {
+                        continue;
+                    }
+
+                    let impact = ServiceImpact {
+                        service_id: dependent.id,
+                        host_id: dependent.base.host_id,
+                        reason: ServiceImpactReason::DependencyLost {
+                            group_id: group.id,
+                            service_id: lost.id,
+                        },
+                    };
+
+                    if !affected_services.contains(&impact) {
+                        affected_services.push(impact);
+                    }
+                }
+            }
+        }
+
+        FailureImpact {
+            host_id,
+            removed_host_ids: removed_host_ids.into_iter().collect(),
+            disconnected_host_ids: disconnected_host_ids.into_iter().collect(),
+            subnets_without_gateway,
+            hosts_without_gateway,
+            affected_services,
+        }
+    }
+
+    /// The host plus every VM transitively running on it
+    fn hosts_removed_with(&self, host_id: Uuid) -> HashSet<Uuid> {
+        let mut removed = HashSet::from([host_id]);
+
+        loop {
+            let newly_removed: Vec<Uuid> = self
+                .context
+                .hosts
+                .iter()
+                .filter(|h| !removed.contains(&h.id))
+                .filter(|h| {
+                    self.context
+                        .get_host_is_virtualized_by(&h.id)
+                        .is_some_and(|hypervisor| removed.contains(&hypervisor.base.host_id))
+                })
+                .map(|h| h.id)
+                .collect();
+
+            if newly_removed.is_empty() {
+                return removed;
+            }
+
+            removed.extend(newly_removed);
+        }
+    }
+
+    /// Hosts that share no subnet, hypervisor or dependency path with any other host
+    fn isolated_hosts(&self) -> Vec<Uuid> {
+        self.connectivity
+            .components_excluding(&HashSet::new())
+            .iter()
+            .map(|component| self.connectivity.hosts_in(component))
+            .filter(|hosts| hosts.len() == 1)
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+        },
+        services::r#impl::base::{Service, ServiceBase},
+        shared::storage::traits::StorableEntity,
+        subnets::r#impl::base::{Subnet, SubnetBase},
+        topology::types::api::TopologyRequestOptions,
+    };
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn subnets(count: usize) -> Vec<Subnet> {
+        (0..count)
+            .map(|_| Subnet::new(SubnetBase::default()))
+            .collect()
+    }
+
+    fn host(name: &str, subnets: &[&Subnet]) -> Host {
+        Host::new(HostBase {
+            name: name.to_string(),
+            interfaces: subnets
+                .iter()
+                .map(|subnet| {
+                    Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        mac_address: None,
+                        name: None,
+                    })
+                })
+                .collect(),
+            ..HostBase::default()
+        })
+    }
+
+    fn articulation_points(hosts: &[Host], subnets: &[Subnet]) -> Vec<ArticulationPoint> {
+        let options = TopologyRequestOptions::default();
+        let ctx = TopologyContext::new(hosts, subnets, &[], &[], &options);
+        TopologyAnalyzer::new(&ctx).articulation_points()
+    }
+
+    /// Two cliques of three hosts on their own subnet, joined by a subnet between a0 and b0
+    fn bridged_cliques() -> (Vec<Subnet>, [Host; 3], [Host; 3]) {
+        let s = subnets(3);
+        let a = [
+            host("a0", &[&s[0], &s[2]]),
+            host("a1", &[&s[0]]),
+            host("a2", &[&s[0]]),
+        ];
+        let b = [
+            host("b0", &[&s[1], &s[2]]),
+            host("b1", &[&s[1]]),
+            host("b2", &[&s[1]]),
+        ];
+        (s, a, b)
+    }
+
+    fn point<'p>(points: &'p [ArticulationPoint], host: &Host) -> &'p ArticulationPoint {
+        points.iter().find(|p| p.host_id == host.id).unwrap()
+    }
+
+    fn ids(hosts: &[&Host]) -> HashSet<Uuid> {
+        hosts.iter().map(|h| h.id).collect()
+    }
+
+    #[test]
+    fn inner_hosts_of_a_path_are_articulation_points() {
+        // h0 - s0 - h1 - s1 - h2 - s2 - h3
+        let s = subnets(3);
+        let hosts = [
+            host("h0", &[&s[0]]),
+            host("h1", &[&s[0], &s[1]]),
+            host("h2", &[&s[1], &s[2]]),
+            host("h3", &[&s[2]]),
+        ];
+
+        let points = articulation_points(&hosts, &s);
+
+        assert_eq!(
+            points.iter().map(|p| p.host_id).collect::<HashSet<_>>(),
+            ids(&[&hosts[1], &hosts[2]])
+        );
+        assert_eq!(point(&points, &hosts[1]).resulting_partitions, 2);
+        assert_eq!(
+            point(&points, &hosts[1]).stranded_host_ids,
+            vec![hosts[0].id]
+        );
+        assert_eq!(
+            point(&points, &hosts[2]).stranded_host_ids,
+            vec![hosts[3].id]
+        );
+    }
+
+    #[test]
+    fn star_center_is_the_only_articulation_point() {
+        let s = subnets(4);
+        let center = host("center", &s.iter().collect::<Vec<_>>());
+        let leaves: Vec<Host> = s.iter().map(|subnet| host("leaf", &[subnet])).collect();
+        let hosts: Vec<Host> = leaves.iter().cloned().chain([center.clone()]).collect();
+
+        let points = articulation_points(&hosts, &s);
+
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].host_id, center.id);
+        assert_eq!(points[0].resulting_partitions, 4);
+        // One leaf stays with the largest island, the rest are stranded
+        assert_eq!(points[0].stranded_host_ids.len(), 3);
+        assert!(
+            points[0]
+                .stranded_host_ids
+                .iter()
+                .all(|id| leaves.iter().any(|leaf| leaf.id == *id))
+        );
+    }
+
+    #[test]
+    fn hosts_on_a_bridge_between_cliques_are_articulation_points() {
+        let (s, a, b) = bridged_cliques();
+        let hosts: Vec<Host> = a.iter().chain(&b).cloned().collect();
+
+        let points = articulation_points(&hosts, &s);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(
+            point(&points, &a[0])
+                .stranded_host_ids
+                .iter()
+                .copied()
+                .collect::<HashSet<_>>(),
+            ids(&[&a[1], &a[2]])
+        );
+        assert_eq!(
+            point(&points, &b[0])
+                .stranded_host_ids
+                .iter()
+                .copied()
+                .collect::<HashSet<_>>(),
+            ids(&[&b[1], &b[2]])
+        );
+    }
+
+    fn failure_impact(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        services: &[Service],
+        host: &Host,
+    ) -> FailureImpact {
+        let options = TopologyRequestOptions::default();
+        let ctx = TopologyContext::new(hosts, subnets, services, &[], &options);
+        TopologyAnalyzer::new(&ctx).failure_impact(host.id)
+    }
+
+    fn service(host: &Host) -> Service {
+        Service::new(ServiceBase {
+            host_id: host.id,
+            ..ServiceBase::default()
+        })
+    }
+
+    #[test]
+    fn removing_a_bridge_host_disconnects_the_far_side() {
+        let (s, a, b) = bridged_cliques();
+        let hosts: Vec<Host> = a.iter().chain(&b).cloned().collect();
+        let services = [service(&a[0]), service(&a[1]), service(&b[1])];
+
+        let impact = failure_impact(&hosts, &s, &services, &a[0]);
+
+        assert_eq!(impact.removed_host_ids, vec![a[0].id]);
+        assert_eq!(
+            impact
+                .disconnected_host_ids
+                .iter()
+                .copied()
+                .collect::<HashSet<_>>(),
+            ids(&[&a[1], &a[2]])
+        );
+        assert_eq!(
+            impact.affected_services,
+            vec![
+                ServiceImpact {
+                    service_id: services[0].id,
+                    host_id: a[0].id,
+                    reason: ServiceImpactReason::HostRemoved,
+                },
+                ServiceImpact {
+                    service_id: services[1].id,
+                    host_id: a[1].id,
+                    reason: ServiceImpactReason::HostDisconnected,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn removing_a_leaf_host_disconnects_nothing() {
+        let (s, a, b) = bridged_cliques();
+        let hosts: Vec<Host> = a.iter().chain(&b).cloned().collect();
+
+        let impact = failure_impact(&hosts, &s, &[], &a[1]);
+
+        assert_eq!(impact.removed_host_ids, vec![a[1].id]);
+        assert!(impact.disconnected_host_ids.is_empty());
+        assert!(impact.affected_services.is_empty());
+    }
+}
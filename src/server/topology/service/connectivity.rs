@@ -0,0 +1,370 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use petgraph::{
+    graph::{NodeIndex, UnGraph},
+    visit::EdgeRef,
+};
+use uuid::Uuid;
+
+use crate::server::topology::{
+    service::context::TopologyContext,
+    types::analytics::{ConnectivityEdge, ConnectivityNode},
+};
+
+/// Undirected graph of hosts and subnets used for structural analysis.
+/// Unlike the rendered topology this has one node per host rather than per interface, so
+/// graph algorithms answer questions about hosts directly.
+pub struct ConnectivityGraph {
+    pub graph: UnGraph<ConnectivityNode, ConnectivityEdge>,
+    node_indices: HashMap<ConnectivityNode, NodeIndex>,
+}
+
+impl ConnectivityGraph {
+    pub fn from_context(ctx: &TopologyContext) -> Self {
+        let mut connectivity = Self {
+            graph: UnGraph::new_undirected(),
+            node_indices: HashMap::new(),
+        };
+
+        for subnet in ctx.subnets {
+            connectivity.add_node(ConnectivityNode::Subnet(subnet.id));
+        }
+
+        for host in ctx.hosts {
+            let host_idx = connectivity.add_node(ConnectivityNode::Host(host.id));
+
+            for interface in &host.base.interfaces {
+                if let Some(subnet_idx) =
+                    connectivity.node_index(ConnectivityNode::Subnet(interface.base.subnet_id))
+                {
+                    connectivity.add_edge(
+                        host_idx,
+                        subnet_idx,
+                        ConnectivityEdge::Interface {
+                            interface_id: interface.id,
+                        },
+                    );
+                }
+            }
+        }
+
+        // VMs are linked to the host running the hypervisor service
+        for host in ctx.hosts {
+            if let Some(virtualization) = &host.base.virtualization
+                && let Some(hypervisor) = ctx.get_service_by_id(virtualization.service_id())
+            {
+                connectivity.add_host_edge(
+                    host.id,
+                    hypervisor.base.host_id,
+                    ConnectivityEdge::Virtualization {
+                        service_id: hypervisor.id,
+                    },
+                );
+            }
+        }
+
+        for group in ctx.groups {
+            for (source_binding_id, target_binding_id) in group.base.group_type.binding_pairs() {
+                if let (Some(source), Some(target)) = (
+                    ctx.get_service_by_binding_id(source_binding_id),
+                    ctx.get_service_by_binding_id(target_binding_id),
+                ) {
+                    connectivity.add_host_edge(
+                        source.base.host_id,
+                        target.base.host_id,
+                        ConnectivityEdge::Dependency { group_id: group.id },
+                    );
+                }
+            }
+        }
+
+        connectivity
+    }
+
+    pub fn node_index(&self, node: ConnectivityNode) -> Option<NodeIndex> {
+        self.node_indices.get(&node).copied()
+    }
+
+    pub fn host_id(&self, idx: NodeIndex) -> Option<Uuid> {
+        match self.graph[idx] {
+            ConnectivityNode::Host(host_id) => Some(host_id),
+            ConnectivityNode::Subnet(_) => None,
+        }
+    }
+
+    pub fn host_indices(&self) -> impl Iterator<Item = (Uuid, NodeIndex)> + '_ {
+        self.graph
+            .node_indices()
+            .filter_map(|idx| self.host_id(idx).map(|host_id| (host_id, idx)))
+    }
+
+    pub fn degree(&self, idx: NodeIndex) -> usize {
+        self.graph.neighbors(idx).count()
+    }
+
+    /// Connected components once the excluded nodes are taken out of the graph
+    pub fn components_excluding(&self, excluded: &HashSet<NodeIndex>) -> Vec<Vec<NodeIndex>> {
+        let mut visited: HashSet<NodeIndex> = excluded.clone();
+        let mut components = Vec::new();
+
+        for start in self.graph.node_indices() {
+            if !visited.insert(start) {
+                continue;
+            }
+
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+
+            while let Some(node) = queue.pop_front() {
+                for neighbor in self.graph.neighbors(node) {
+                    if visited.insert(neighbor) {
+                        component.push(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Host ids within a component, ignoring subnet nodes
+    pub fn hosts_in(&self, component: &[NodeIndex]) -> Vec<Uuid> {
+        component
+            .iter()
+            .filter_map(|idx| self.host_id(*idx))
+            .collect()
+    }
+
+    /// Shortest route between two hosts over shared subnets, ignoring logical links such as
+    /// group dependencies
+    pub fn network_path(
+        &self,
+        from_host_id: Uuid,
+        to_host_id: Uuid,
+    ) -> Option<Vec<ConnectivityNode>> {
+        let start = self.node_index(ConnectivityNode::Host(from_host_id))?;
+        let goal = self.node_index(ConnectivityNode::Host(to_host_id))?;
+
+        let mut previous: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == goal {
+                let mut path = vec![self.graph[node]];
+                let mut current = node;
+                while let Some(prev) = previous.get(&current) {
+                    path.push(self.graph[*prev]);
+                    current = *prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for edge in self.graph.edges(node) {
+                if matches!(edge.weight(), ConnectivityEdge::Interface { .. })
+                    && visited.insert(edge.target())
+                {
+                    previous.insert(edge.target(), node);
+                    queue.push_back(edge.target());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Brandes' algorithm for unweighted graphs, normalized to 0.0..=1.0
+    pub fn betweenness_centrality(&self) -> HashMap<NodeIndex, f64> {
+        let mut centrality: HashMap<NodeIndex, f64> =
+            self.graph.node_indices().map(|idx| (idx, 0.0)).collect();
+
+        for source in self.graph.node_indices() {
+            let mut stack = Vec::new();
+            let mut predecessors: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+            let mut path_counts: HashMap<NodeIndex, f64> = HashMap::from([(source, 1.0)]);
+            let mut distances: HashMap<NodeIndex, usize> = HashMap::from([(source, 0)]);
+            let mut queue = VecDeque::from([source]);
+
+            while let Some(node) = queue.pop_front() {
+                stack.push(node);
+                let distance = distances[&node];
+                let node_paths = path_counts[&node];
+
+                for neighbor in self.graph.neighbors(node) {
+                    if !distances.contains_key(&neighbor) {
+                        distances.insert(neighbor, distance + 1);
+                        queue.push_back(neighbor);
+                    }
+                    if distances[&neighbor] == distance + 1 {
+                        *path_counts.entry(neighbor).or_default() += node_paths;
+                        predecessors.entry(neighbor).or_default().push(node);
+                    }
+                }
+            }
+
+            let mut dependencies: HashMap<NodeIndex, f64> = HashMap::new();
+            while let Some(node) = stack.pop() {
+                let node_dependency = dependencies.get(&node).copied().unwrap_or(0.0);
+
+                for predecessor in predecessors.get(&node).into_iter().flatten() {
+                    *dependencies.entry(*predecessor).or_default() +=
+                        path_counts[predecessor] / path_counts[&node] * (1.0 + node_dependency);
+                }
+
+                if node != source {
+                    *centrality.entry(node).or_default() += node_dependency;
+                }
+            }
+        }
+
+        // Each pair is counted from both ends in an undirected graph
+        let node_count = self.graph.node_count();
+        let scale = if node_count > 2 {
+            1.0 / ((node_count - 1) * (node_count - 2)) as f64
+        } else {
+            0.0
+        };
+
+        centrality.values_mut().for_each(|value| *value *= scale);
+        centrality
+    }
+
+    fn add_node(&mut self, node: ConnectivityNode) -> NodeIndex {
+        *self
+            .node_indices
+            .entry(node)
+            .or_insert_with(|| self.graph.add_node(node))
+    }
+
+    /// Keep the graph simple - parallel edges would skew path counting
+    fn add_edge(&mut self, a: NodeIndex, b: NodeIndex, edge: ConnectivityEdge) {
+        if a != b && self.graph.find_edge(a, b).is_none() {
+            self.graph.add_edge(a, b, edge);
+        }
+    }
+
+    fn add_host_edge(
+        &mut self,
+        source_host_id: Uuid,
+        target_host_id: Uuid,
+        edge: ConnectivityEdge,
+    ) {
+        if let (Some(a), Some(b)) = (
+            self.node_index(ConnectivityNode::Host(source_host_id)),
+            self.node_index(ConnectivityNode::Host(target_host_id)),
+        ) {
+            self.add_edge(a, b, edge);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+        },
+        shared::storage::traits::StorableEntity,
+        subnets::r#impl::base::{Subnet, SubnetBase},
+        topology::types::api::TopologyRequestOptions,
+    };
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn subnets(count: usize) -> Vec<Subnet> {
+        (0..count)
+            .map(|_| Subnet::new(SubnetBase::default()))
+            .collect()
+    }
+
+    /// A host with an interface in each of the subnets
+    fn host(subnets: &[&Subnet]) -> Host {
+        Host::new(HostBase {
+            interfaces: subnets
+                .iter()
+                .map(|subnet| {
+                    Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        mac_address: None,
+                        name: None,
+                    })
+                })
+                .collect(),
+            ..HostBase::default()
+        })
+    }
+
+    fn connectivity(hosts: &[Host], subnets: &[Subnet]) -> ConnectivityGraph {
+        let options = TopologyRequestOptions::default();
+        ConnectivityGraph::from_context(&TopologyContext::new(hosts, subnets, &[], &[], &options))
+    }
+
+    fn centrality(graph: &ConnectivityGraph, host: &Host) -> f64 {
+        let idx = graph.node_index(ConnectivityNode::Host(host.id)).unwrap();
+        graph.betweenness_centrality()[&idx]
+    }
+
+    #[test]
+    fn centrality_peaks_in_the_middle_of_a_path() {
+        // h0 - s0 - h1 - s1 - h2 - s2 - h3
+        let s = subnets(3);
+        let hosts = [
+            host(&[&s[0]]),
+            host(&[&s[0], &s[1]]),
+            host(&[&s[1], &s[2]]),
+            host(&[&s[2]]),
+        ];
+        let graph = connectivity(&hosts, &s);
+
+        assert_eq!(centrality(&graph, &hosts[0]), 0.0);
+        assert_eq!(centrality(&graph, &hosts[3]), 0.0);
+        // 2 nodes on one side and 4 on the other, out of 6 * 5 / 2 pairs that don't include it
+        assert!((centrality(&graph, &hosts[1]) - 8.0 / 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn star_center_carries_every_path_between_arms() {
+        // Each leaf shares its own subnet with the center
+        let s = subnets(4);
+        let center = host(&s.iter().collect::<Vec<_>>());
+        let leaves: Vec<Host> = s.iter().map(|subnet| host(&[subnet])).collect();
+        let hosts: Vec<Host> = leaves.iter().cloned().chain([center.clone()]).collect();
+        let graph = connectivity(&hosts, &s);
+
+        // 4 arms of 2 nodes: 6 * 2 * 2 of the 8 * 7 / 2 pairs cross the center
+        assert!((centrality(&graph, &center) - 6.0 / 7.0).abs() < 1e-9);
+        for leaf in &leaves {
+            assert_eq!(centrality(&graph, leaf), 0.0);
+        }
+    }
+
+    #[test]
+    fn bridge_hosts_are_the_most_central() {
+        // Two cliques of three hosts on their own subnet, joined by a subnet between a0 and b0
+        let s = subnets(3);
+        let a = [host(&[&s[0], &s[2]]), host(&[&s[0]]), host(&[&s[0]])];
+        let b = [host(&[&s[1], &s[2]]), host(&[&s[1]]), host(&[&s[1]])];
+        let hosts: Vec<Host> = a.iter().chain(&b).cloned().collect();
+        let graph = connectivity(&hosts, &s);
+
+        let bridge = centrality(&graph, &a[0]);
+        assert!(bridge > 0.0);
+        assert!((bridge - centrality(&graph, &b[0])).abs() < 1e-9);
+        for other in a[1..].iter().chain(&b[1..]) {
+            assert_eq!(centrality(&graph, other), 0.0);
+        }
+
+        // Without the bridge hosts the cliques and the bridge subnet fall apart
+        let removed = HashSet::from([
+            graph.node_index(ConnectivityNode::Host(a[0].id)).unwrap(),
+            graph.node_index(ConnectivityNode::Host(b[0].id)).unwrap(),
+        ]);
+        assert_eq!(graph.components_excluding(&removed).len(), 3);
+    }
+}
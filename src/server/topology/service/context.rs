@@ -2,7 +2,7 @@ use uuid::Uuid;
 
 use crate::server::{
     groups::r#impl::base::Group,
-    hosts::r#impl::{base::Host, interfaces::Interface, virtualization::HostVirtualization},
+    hosts::r#impl::{base::Host, interfaces::Interface},
     services::r#impl::{
         base::Service, definitions::ServiceDefinitionExt, virtualization::ServiceVirtualization,
     },
@@ -10,7 +10,7 @@ use crate::server::{
     topology::types::{
         api::TopologyRequestOptions,
         edges::Edge,
-        nodes::{Node, NodeType},
+        nodes::{Node, NodeOs, NodeType},
     },
 };
 
@@ -53,10 +53,37 @@ impl<'a> TopologyContext<'a> {
         self.hosts.iter().find(|h| h.id == host_id)
     }
 
+    pub fn host_badges(&self, host_id: Uuid) -> Vec<String> {
+        self.get_host_by_id(host_id)
+            .map(|host| host.base.attributes.badges())
+            .unwrap_or_default()
+    }
+
+    pub fn host_os(&self, host_id: Uuid) -> Option<NodeOs> {
+        let os = self.get_host_by_id(host_id)?.base.os.as_ref()?;
+        Some(NodeOs {
+            family: os.family,
+            name: os.name.clone(),
+            confidence: os.confidence,
+        })
+    }
+
+    pub fn subnet_badges(&self, subnet_id: Uuid) -> Vec<String> {
+        self.get_subnet_by_id(subnet_id)
+            .map(|subnet| subnet.base.attributes.badges())
+            .unwrap_or_default()
+    }
+
     pub fn get_service_by_id(&self, service_id: Uuid) -> Option<&'a Service> {
         self.services.iter().find(|s| s.id == service_id)
     }
 
+    pub fn get_service_by_binding_id(&self, binding_id: Uuid) -> Option<&'a Service> {
+        self.services
+            .iter()
+            .find(|s| s.get_binding(binding_id).is_some())
+    }
+
     pub fn get_interface_by_id(&self, interface_id: Option<Uuid>) -> Option<&'a Interface> {
         self.hosts
             .iter()
@@ -95,13 +122,12 @@ impl<'a> TopologyContext<'a> {
 
     pub fn get_host_is_virtualized_by(&self, host_id: &Uuid) -> Option<&Service> {
         if let Some(host) = self.get_host_by_id(*host_id)
-            && let Some(HostVirtualization::Proxmox(proxmox_virtualization)) =
-                &host.base.virtualization
+            && let Some(virtualization) = &host.base.virtualization
         {
             return self
                 .services
                 .iter()
-                .find(|s| s.id == proxmox_virtualization.service_id);
+                .find(|s| s.id == virtualization.service_id());
         }
         None
     }
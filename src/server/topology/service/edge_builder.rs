@@ -1,12 +1,12 @@
 use itertools::Itertools;
 use petgraph::{Graph, graph::NodeIndex};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use strum::IntoDiscriminant;
 use uuid::Uuid;
 
 use crate::server::{
     groups::r#impl::{base::Group, types::GroupType},
-    hosts::r#impl::virtualization::HostVirtualization,
+    hosts::r#impl::{base::Host, interfaces::Interface},
     services::r#impl::virtualization::ServiceVirtualization,
     subnets::r#impl::types::{SubnetType, SubnetTypeDiscriminants},
     topology::{
@@ -210,75 +210,71 @@ impl EdgeBuilder {
 
     // Create edges to connect a host that virtualizes other hosts as VMs
     pub fn create_vm_host_edges(ctx: &TopologyContext) -> Vec<Edge> {
-        // Proxmox service interface binding that is present for a given subnet.
+        // Hypervisor service interface binding that is present for a given subnet.
         // There could be multiple host interfaces with a given subnet, we arbitrarily choose the first one so there's
         // one clustering hub rather than multiple hubs
-        // (subnet_id, proxmox_service_id) : (interface_id)
-        let mut subnet_to_promxox_host_interface_id: HashMap<(Uuid, Uuid), Uuid> = HashMap::new();
+        // (subnet_id, hypervisor_service_id) : (interface_id)
+        let mut subnet_to_hypervisor_interface_id: HashMap<(Uuid, Uuid), Uuid> = HashMap::new();
 
-        // Hosts VMs managed by a given proxmox service
-        let mut vm_host_id_to_proxmox_service: HashMap<Uuid, Uuid> = HashMap::new();
+        // Hosts VMs managed by a given hypervisor service
+        let mut vm_host_id_to_hypervisor_service: HashMap<Uuid, Uuid> = HashMap::new();
 
         ctx.hosts.iter().for_each(|h| {
-            if let Some(HostVirtualization::Proxmox(proxmox_virtualization)) =
-                &h.base.virtualization
-            {
-                // Create mapping between subnet and proxmox interface(s) on that subnet
-                if let Some(promxox_service) =
-                    ctx.get_service_by_id(proxmox_virtualization.service_id)
+            if let Some(virtualization) = &h.base.virtualization {
+                // Create mapping between subnet and hypervisor interface(s) on that subnet
+                if let Some(hypervisor_service) = ctx.get_service_by_id(virtualization.service_id())
                 {
-                    promxox_service
+                    hypervisor_service
                         .base
                         .bindings
                         .iter()
                         .filter_map(|b| b.interface_id())
                         .for_each(|i| {
                             if let Some(subnet) = ctx.get_subnet_from_interface_id(i)
-                                && !subnet_to_promxox_host_interface_id
-                                    .contains_key(&(subnet.id, promxox_service.id))
+                                && !subnet_to_hypervisor_interface_id
+                                    .contains_key(&(subnet.id, hypervisor_service.id))
                             {
-                                subnet_to_promxox_host_interface_id
-                                    .entry((subnet.id, promxox_service.id))
+                                subnet_to_hypervisor_interface_id
+                                    .entry((subnet.id, hypervisor_service.id))
                                     .insert_entry(i);
                             }
                         });
                 }
 
-                vm_host_id_to_proxmox_service.insert(h.id, proxmox_virtualization.service_id);
+                vm_host_id_to_hypervisor_service.insert(h.id, virtualization.service_id());
             }
         });
 
-        // Creates edges between interface that proxmox service has on a given subnet with interfaces that the virtualized host has on the subnet
+        // Creates edges between interface that hypervisor service has on a given subnet with interfaces that the virtualized host has on the subnet
         ctx.hosts
             .iter()
             .flat_map(|h| {
-                if let Some(proxmox_service_id) = vm_host_id_to_proxmox_service.get(&h.id) {
+                if let Some(hypervisor_service_id) = vm_host_id_to_hypervisor_service.get(&h.id) {
                     return h
                         .base
                         .interfaces
                         .iter()
                         .filter_map(|i| {
-                            if let Some(proxmox_service_interface_id) =
-                                subnet_to_promxox_host_interface_id
-                                    .get(&(i.base.subnet_id, *proxmox_service_id))
-                                && ctx.interface_will_have_node(proxmox_service_interface_id)
+                            if let Some(hypervisor_interface_id) = subnet_to_hypervisor_interface_id
+                                .get(&(i.base.subnet_id, *hypervisor_service_id))
+                                && ctx.interface_will_have_node(hypervisor_interface_id)
                             {
                                 let is_multi_hop =
-                                    ctx.edge_is_multi_hop(proxmox_service_interface_id, &i.id);
+                                    ctx.edge_is_multi_hop(hypervisor_interface_id, &i.id);
 
                                 let (source_handle, target_handle) =
                                     EdgeBuilder::determine_interface_handles(
                                         ctx,
-                                        proxmox_service_interface_id,
+                                        hypervisor_interface_id,
                                         &i.id,
                                         is_multi_hop,
                                     )?;
 
                                 return Some(Edge {
-                                    source: *proxmox_service_interface_id,
+                                    source: *hypervisor_interface_id,
                                     target: i.id,
                                     edge_type: EdgeType::HostVirtualization {
-                                        vm_service_id: *proxmox_service_id,
+                                        vm_service_id: *hypervisor_service_id,
                                     },
                                     label: None,
                                     source_handle,
@@ -301,6 +297,9 @@ impl EdgeBuilder {
             .iter()
             .flat_map(|host| {
                 if let Some(origin_interface) = host.base.interfaces.first() {
+                    // Interfaces joined by a trunk get a trunk edge instead
+                    let trunk_links = EdgeBuilder::trunk_links(ctx, host);
+
                     host.base
                         .interfaces
                         .iter()
@@ -308,6 +307,10 @@ impl EdgeBuilder {
                             interface.id != origin_interface.id
                                 && ctx.interface_will_have_node(&interface.id)
                                 && ctx.interface_will_have_node(&origin_interface.id)
+                                && !trunk_links.iter().any(|(a, b, _)| {
+                                    (a.id == origin_interface.id && b.id == interface.id)
+                                        || (a.id == interface.id && b.id == origin_interface.id)
+                                })
                         })
                         .filter_map(|interface| {
                             let source_subnet =
@@ -357,6 +360,85 @@ impl EdgeBuilder {
             .collect()
     }
 
+    /// Create trunk edges between a host's VLAN interfaces and the interface they're tagged on.
+    /// When the trunk itself has no address, the VLANs sharing it are linked to the lowest tag.
+    pub fn create_trunk_edges(ctx: &TopologyContext) -> Vec<Edge> {
+        ctx.hosts
+            .iter()
+            .flat_map(|host| {
+                EdgeBuilder::trunk_links(ctx, host)
+                    .into_iter()
+                    .filter_map(|(source, target, vlan_id)| {
+                        let is_multi_hop = ctx.edge_is_multi_hop(&source.id, &target.id);
+
+                        let (source_handle, target_handle) =
+                            EdgeBuilder::determine_interface_handles(
+                                ctx,
+                                &source.id,
+                                &target.id,
+                                is_multi_hop,
+                            )?;
+
+                        Some(Edge {
+                            source: source.id,
+                            target: target.id,
+                            edge_type: EdgeType::Trunk {
+                                host_id: host.id,
+                                vlan_id,
+                            },
+                            label: vlan_id.map(|id| format!("VLAN {}", id)),
+                            source_handle,
+                            target_handle,
+                            is_multi_hop,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// A host's interfaces joined by a VLAN trunk, as (trunk, VLAN interface, VLAN tag)
+    fn trunk_links<'h>(
+        ctx: &TopologyContext,
+        host: &'h Host,
+    ) -> Vec<(&'h Interface, &'h Interface, Option<u16>)> {
+        let mut vlans_by_parent: BTreeMap<&str, Vec<(&'h Interface, Option<u16>)>> =
+            BTreeMap::new();
+        for interface in host
+            .base
+            .interfaces
+            .iter()
+            .filter(|i| ctx.interface_will_have_node(&i.id))
+        {
+            if let Some(subnet) = ctx.get_subnet_by_id(interface.base.subnet_id)
+                && let Some(parent) = subnet.base.layer2.parent_interface.as_deref()
+            {
+                vlans_by_parent
+                    .entry(parent)
+                    .or_default()
+                    .push((interface, subnet.base.layer2.vlan_id));
+            }
+        }
+
+        vlans_by_parent
+            .into_iter()
+            .flat_map(|(parent, mut vlans)| {
+                vlans.sort_by_key(|(interface, vlan_id)| (*vlan_id, interface.id));
+
+                let trunk = host.base.interfaces.iter().find(|i| {
+                    i.base.name.as_deref() == Some(parent) && ctx.interface_will_have_node(&i.id)
+                });
+                let hub = trunk.unwrap_or(vlans[0].0);
+
+                vlans
+                    .into_iter()
+                    .filter(|(interface, _)| interface.id != hub.id)
+                    .map(|(interface, vlan_id)| (hub, interface, vlan_id))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Figure out handles for two interfaces
     pub fn determine_interface_handles(
         ctx: &TopologyContext,
@@ -1,23 +1,45 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
 
-use anyhow::Error;
+use anyhow::{Error, anyhow};
 use petgraph::{Graph, graph::NodeIndex};
 use uuid::Uuid;
 
 use crate::server::{
-    groups::service::GroupService,
-    hosts::service::HostService,
+    groups::{
+        r#impl::{base::Group, types::GroupType},
+        service::GroupService,
+    },
+    hosts::{r#impl::base::Host, service::HostService},
+    node_pins::service::NodePinService,
     services::{r#impl::base::Service, service::ServiceService},
-    shared::{services::traits::CrudService, storage::filter::EntityFilter},
-    subnets::service::SubnetService,
+    shared::{
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, traits::StorableEntity},
+    },
+    subnets::{r#impl::base::Subnet, service::SubnetService},
     topology::{
+        exporter::{ExportFormat, TopologyExporter},
         service::{
-            context::TopologyContext, edge_builder::EdgeBuilder,
-            optimizer::main::TopologyOptimizer,
-            planner::subnet_layout_planner::SubnetLayoutPlanner,
+            analytics::TopologyAnalyzer,
+            context::TopologyContext,
+            edge_builder::EdgeBuilder,
+            optimizer::{main::TopologyOptimizer, utils::LayoutReport},
+            path_tracer::ServicePathTracer,
+            planner::{aggregation::SubnetAggregator, subnet_layout_planner::SubnetLayoutPlanner},
+        },
+        types::{
+            analytics::{FailureImpact, TopologyAnalytics},
+            api::{SubnetLink, SubnetSummary, TopologyRequestOptions, TopologySummary},
+            base::{SubnetLayout, Uxy},
+            edges::Edge,
+            nodes::Node,
+            paths::ServicePath,
         },
-        types::{api::TopologyRequestOptions, edges::Edge, nodes::Node},
     },
+    topology_snapshots::r#impl::data::TopologySnapshotData,
 };
 
 pub struct TopologyService {
@@ -25,6 +47,7 @@ pub struct TopologyService {
     subnet_service: Arc<SubnetService>,
     group_service: Arc<GroupService>,
     service_service: Arc<ServiceService>,
+    node_pin_service: Arc<NodePinService>,
 }
 
 impl TopologyService {
@@ -33,25 +56,279 @@ impl TopologyService {
         subnet_service: Arc<SubnetService>,
         group_service: Arc<GroupService>,
         service_service: Arc<ServiceService>,
+        node_pin_service: Arc<NodePinService>,
     ) -> Self {
         Self {
             host_service,
             subnet_service,
             group_service,
             service_service,
+            node_pin_service,
         }
     }
 
     pub async fn build_graph(
+        &self,
+        mut options: TopologyRequestOptions,
+    ) -> Result<(Graph<Node, Edge>, LayoutReport), Error> {
+        self.load_pins(&mut options).await?;
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+
+        Ok(Self::layout_graph_with_report(
+            &hosts, &subnets, &groups, &services, &options,
+        ))
+    }
+
+    /// Lay out the topology and report its quality without returning the graph
+    pub async fn quality(&self, options: TopologyRequestOptions) -> Result<LayoutReport, Error> {
+        let (_, report) = self.build_graph(options).await?;
+        Ok(report)
+    }
+
+    /// Build a subnet-only view with aggregate counts, skipping child layout entirely
+    pub async fn build_summary(
         &self,
         options: TopologyRequestOptions,
+    ) -> Result<TopologySummary, Error> {
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, &options);
+
+        // Map every possible node id (interfaces and subnets) to the subnet that contains it
+        let mut node_subnet: HashMap<Uuid, Uuid> = subnets.iter().map(|s| (s.id, s.id)).collect();
+        let mut host_ids_by_subnet: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+        let mut interface_counts: HashMap<Uuid, usize> = HashMap::new();
+
+        for host in &hosts {
+            for interface in &host.base.interfaces {
+                node_subnet.insert(interface.id, interface.base.subnet_id);
+                host_ids_by_subnet
+                    .entry(interface.base.subnet_id)
+                    .or_default()
+                    .insert(host.id);
+                *interface_counts
+                    .entry(interface.base.subnet_id)
+                    .or_default() += 1;
+            }
+        }
+
+        let mut service_counts: HashMap<Uuid, usize> = HashMap::new();
+        for service in &services {
+            let service_subnets: HashSet<Uuid> = service
+                .to_bound_interface_ids()
+                .iter()
+                .flat_map(|interface_id| match interface_id {
+                    Some(id) => node_subnet.get(id).copied().into_iter().collect(),
+                    // Bound to all interfaces on the host
+                    None => ctx
+                        .get_host_by_id(service.base.host_id)
+                        .map(|h| h.base.interfaces.iter().map(|i| i.base.subnet_id).collect())
+                        .unwrap_or_else(Vec::new),
+                })
+                .collect();
+
+            for subnet_id in service_subnets {
+                *service_counts.entry(subnet_id).or_default() += 1;
+            }
+        }
+
+        let mut all_edges = Vec::new();
+        all_edges.extend(EdgeBuilder::create_interface_edges(&ctx));
+        all_edges.extend(EdgeBuilder::create_trunk_edges(&ctx));
+        all_edges.extend(EdgeBuilder::create_group_edges(&ctx));
+        all_edges.extend(EdgeBuilder::create_vm_host_edges(&ctx));
+        let (container_edges, _) = EdgeBuilder::create_containerized_service_edges(&ctx, false);
+        all_edges.extend(container_edges);
+
+        let mut link_counts: BTreeMap<(Uuid, Uuid), usize> = BTreeMap::new();
+        for edge in &all_edges {
+            if let (Some(source), Some(target)) =
+                (node_subnet.get(&edge.source), node_subnet.get(&edge.target))
+                && source != target
+            {
+                *link_counts.entry((*source, *target)).or_default() += 1;
+            }
+        }
+
+        let layouts: HashMap<Uuid, SubnetLayout> = host_ids_by_subnet
+            .keys()
+            .map(|subnet_id| {
+                (
+                    *subnet_id,
+                    SubnetLayout {
+                        size: Uxy::subnet_summary_size(),
+                        infra_width: 0,
+                    },
+                )
+            })
+            .collect();
+
+        let subnet_summaries = SubnetLayoutPlanner::new()
+            .create_subnet_nodes(&ctx, &layouts)
+            .into_iter()
+            .map(|node| SubnetSummary {
+                host_count: host_ids_by_subnet.get(&node.id).map_or(0, |h| h.len()),
+                interface_count: interface_counts.get(&node.id).copied().unwrap_or(0),
+                service_count: service_counts.get(&node.id).copied().unwrap_or(0),
+                node,
+            })
+            .collect();
+
+        Ok(TopologySummary {
+            subnets: subnet_summaries,
+            links: link_counts
+                .into_iter()
+                .map(|((source, target), edge_count)| SubnetLink {
+                    source,
+                    target,
+                    edge_count,
+                })
+                .collect(),
+        })
+    }
+
+    /// Lay out a single subnet and its children, for expanding a subnet from the summary view.
+    /// Only hosts with an interface in the subnet are considered, so layout cost is bounded by
+    /// the size of that subnet rather than the whole network.
+    pub async fn build_subnet_graph(
+        &self,
+        subnet_id: Uuid,
+        mut options: TopologyRequestOptions,
     ) -> Result<Graph<Node, Edge>, Error> {
+        self.load_pins(&mut options).await?;
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+
+        let subnets: Vec<Subnet> = subnets.into_iter().filter(|s| s.id == subnet_id).collect();
+
+        if subnets.is_empty() {
+            return Err(anyhow!("Subnet {} not found", subnet_id));
+        }
+
+        let hosts: Vec<Host> = hosts
+            .into_iter()
+            .filter_map(|mut host| {
+                host.base
+                    .interfaces
+                    .retain(|i| i.base.subnet_id == subnet_id);
+                (!host.base.interfaces.is_empty()).then_some(host)
+            })
+            .collect();
+
+        let host_ids: HashSet<Uuid> = hosts.iter().map(|h| h.id).collect();
+        let services: Vec<Service> = services
+            .into_iter()
+            .filter(|s| host_ids.contains(&s.base.host_id))
+            .collect();
+
+        Ok(Self::layout_graph(
+            &hosts, &subnets, &groups, &services, &options,
+        ))
+    }
+
+    /// Lay out the topology and render it as DOT or Mermaid text
+    pub async fn export(
+        &self,
+        mut options: TopologyRequestOptions,
+        format: ExportFormat,
+    ) -> Result<String, Error> {
+        self.load_pins(&mut options).await?;
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let graph = Self::layout_graph(&hosts, &subnets, &groups, &services, &options);
+        let labels = Self::node_labels(&hosts, &subnets);
+
+        Ok(TopologyExporter::new(&graph, &labels).export(format))
+    }
+
+    /// Capture a network's hosts, services and graph edges for snapshot history
+    pub async fn snapshot(&self, network_id: Uuid) -> Result<TopologySnapshotData, Error> {
+        let options = TopologyRequestOptions {
+            network_ids: vec![network_id],
+            ..Default::default()
+        };
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let graph = Self::layout_graph(&hosts, &subnets, &groups, &services, &options);
+        let labels = Self::node_labels(&hosts, &subnets);
+
+        Ok(TopologySnapshotData::new(&hosts, &services, &graph, labels))
+    }
+
+    /// Readable names of subnet and interface nodes
+    fn node_labels(hosts: &[Host], subnets: &[Subnet]) -> HashMap<Uuid, String> {
+        let mut labels: HashMap<Uuid, String> = subnets
+            .iter()
+            .map(|s| (s.id, format!("{} ({})", s.base.name, s.base.cidr)))
+            .collect();
+        for host in hosts {
+            for interface in &host.base.interfaces {
+                labels.insert(
+                    interface.id,
+                    format!("{}\n{}", host.base.name, interface.base.ip_address),
+                );
+            }
+        }
+        labels
+    }
+
+    /// Compute centrality, single points of failure and isolated hosts
+    pub async fn analyze(
+        &self,
+        options: TopologyRequestOptions,
+    ) -> Result<TopologyAnalytics, Error> {
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, &options);
+
+        Ok(TopologyAnalyzer::new(&ctx).analyze())
+    }
+
+    /// Simulate the host failing within its network
+    pub async fn failure_impact(&self, host: &Host) -> Result<FailureImpact, Error> {
+        let options = TopologyRequestOptions {
+            network_ids: vec![host.base.network_id],
+            ..Default::default()
+        };
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, &options);
+
+        Ok(TopologyAnalyzer::new(&ctx).failure_impact(host.id))
+    }
+
+    /// Trace the group chain and network route from one service to another
+    pub async fn trace_service_path(
+        &self,
+        source: &Service,
+        target: &Service,
+    ) -> Result<ServicePath, Error> {
+        let options = TopologyRequestOptions {
+            network_ids: vec![source.base.network_id, target.base.network_id],
+            ..Default::default()
+        };
+        let (hosts, subnets, groups, services) = self.fetch_topology_data(&options).await?;
+        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, &options);
+
+        Ok(ServicePathTracer::new(&ctx).trace(source.id, target.id))
+    }
+
+    /// Add stored pins for the requested networks. Pins sent with the request win, so a client
+    /// can preview a position before saving it.
+    async fn load_pins(&self, options: &mut TopologyRequestOptions) -> Result<(), Error> {
+        let mut pins = self
+            .node_pin_service
+            .positions(&options.network_ids)
+            .await?;
+        pins.extend(options.pinned_nodes.drain());
+        options.pinned_nodes = pins;
+        Ok(())
+    }
+
+    async fn fetch_topology_data(
+        &self,
+        options: &TopologyRequestOptions,
+    ) -> Result<(Vec<Host>, Vec<Subnet>, Vec<Group>, Vec<Service>), Error> {
         let network_filter = EntityFilter::unfiltered().network_ids(&options.network_ids);
         // Fetch all data
-        let hosts = self.host_service.get_all(network_filter.clone()).await?;
-        let subnets = self.subnet_service.get_all(network_filter.clone()).await?;
-        let groups = self.group_service.get_all(network_filter.clone()).await?;
-        let services: Vec<Service> = self
+        let mut hosts = self.host_service.get_all(network_filter.clone()).await?;
+        let mut subnets = self.subnet_service.get_all(network_filter.clone()).await?;
+        let mut groups = self.group_service.get_all(network_filter.clone()).await?;
+        let mut services: Vec<Service> = self
             .service_service
             .get_all(network_filter.clone())
             .await?
@@ -63,14 +340,122 @@ impl TopologyService {
             })
             .collect();
 
+        Self::apply_scope(
+            options,
+            &mut hosts,
+            &mut subnets,
+            &mut groups,
+            &mut services,
+        );
+
+        Ok((hosts, subnets, groups, services))
+    }
+
+    /// Narrow fetched data down to the subnets, groups and service categories the request asks
+    /// for, so planning and optimization only work on that part of the network
+    fn apply_scope(
+        options: &TopologyRequestOptions,
+        hosts: &mut Vec<Host>,
+        subnets: &mut Vec<Subnet>,
+        groups: &mut Vec<Group>,
+        services: &mut Vec<Service>,
+    ) {
+        if !options.service_categories.is_empty() {
+            services.retain(|s| {
+                options
+                    .service_categories
+                    .contains(&s.base.service_definition.category())
+            });
+        }
+
+        // Only the services in the selected groups, and the hosts running them
+        if !options.group_ids.is_empty() {
+            groups.retain(|g| options.group_ids.contains(&g.id));
+
+            let binding_ids: HashSet<Uuid> = groups
+                .iter()
+                .flat_map(|group| {
+                    let (GroupType::RequestPath { service_bindings }
+                    | GroupType::HubAndSpoke { service_bindings }) = &group.base.group_type;
+                    service_bindings.iter().copied()
+                })
+                .collect();
+            services.retain(|s| {
+                s.base
+                    .bindings
+                    .iter()
+                    .any(|b| binding_ids.contains(&b.id()))
+            });
+
+            let host_ids: HashSet<Uuid> = services.iter().map(|s| s.base.host_id).collect();
+            hosts.retain(|h| host_ids.contains(&h.id));
+        }
+
+        // Hosts keep only their interfaces in the selected subnets
+        if !options.subnet_ids.is_empty() {
+            subnets.retain(|s| options.subnet_ids.contains(&s.id));
+            hosts.retain_mut(|h| {
+                h.base
+                    .interfaces
+                    .retain(|i| options.subnet_ids.contains(&i.base.subnet_id));
+                !h.base.interfaces.is_empty()
+            });
+        }
+
+        if !options.os_families.is_empty() {
+            hosts.retain(|h| {
+                h.base
+                    .os
+                    .as_ref()
+                    .is_some_and(|os| options.os_families.contains(&os.family))
+            });
+        }
+
+        if options.hide_hosts_without_services {
+            let host_ids: HashSet<Uuid> = services.iter().map(|s| s.base.host_id).collect();
+            hosts.retain(|h| host_ids.contains(&h.id));
+        }
+
+        // Services of hosts that were filtered out have nothing to attach to
+        let host_ids: HashSet<Uuid> = hosts.iter().map(|h| h.id).collect();
+        services.retain(|s| host_ids.contains(&s.base.host_id));
+    }
+
+    /// Lay out already fetched entities; public so the layout engine can be benchmarked
+    pub fn layout_graph(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        groups: &[Group],
+        services: &[Service],
+        options: &TopologyRequestOptions,
+    ) -> Graph<Node, Edge> {
+        Self::layout_graph_with_report(hosts, subnets, groups, services, options).0
+    }
+
+    pub fn layout_graph_with_report(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        groups: &[Group],
+        services: &[Service],
+        options: &TopologyRequestOptions,
+    ) -> (Graph<Node, Edge>, LayoutReport) {
+        // Laid out in the order storage returns them, so the same entities passed in any other
+        // order get the same layout
+        let hosts = in_storage_order(hosts);
+        let subnets = in_storage_order(subnets);
+        let groups = in_storage_order(groups);
+        let services = in_storage_order(services);
+
         // Create context to avoid parameter passing
-        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, &options);
+        let ctx = TopologyContext::new(&hosts, &subnets, &services, &groups, options);
 
         // Create all edges (needed for anchor analysis)
         let mut all_edges = Vec::new();
 
         all_edges.extend(EdgeBuilder::create_interface_edges(&ctx));
 
+        all_edges.extend(EdgeBuilder::create_trunk_edges(&ctx));
+
         all_edges.extend(EdgeBuilder::create_group_edges(&ctx));
         all_edges.extend(EdgeBuilder::create_vm_host_edges(&ctx));
         let (container_edges, docker_bridge_host_subnet_id_to_group_on) =
@@ -92,11 +477,15 @@ impl TopologyService {
 
         let subnet_nodes = layout_planner.create_subnet_nodes(&ctx, &subnet_layouts);
 
+        // Links into collapsed subnets now end at the subnet node
+        let all_edges = SubnetAggregator::new(&ctx).reroute_edges(all_edges);
+
         // Optimize node positions and handle edge adjustments
         let optimizer = TopologyOptimizer::new(&ctx);
         let mut all_nodes: Vec<Node> = subnet_nodes.into_iter().chain(child_nodes).collect();
 
-        let optimized_edges = optimizer.optimize_graph(&mut all_nodes, &all_edges);
+        let (optimized_edges, report) =
+            optimizer.optimize_graph_with_report(&mut all_nodes, &all_edges);
 
         // Build graph
         let mut graph: Graph<Node, Edge> = Graph::new();
@@ -112,6 +501,111 @@ impl TopologyService {
         // Add edges to graph
         EdgeBuilder::add_edges_to_graph(&mut graph, &node_indices, optimized_edges);
 
-        Ok(graph)
+        (graph, report)
+    }
+}
+
+fn in_storage_order<T: StorableEntity>(entities: &[T]) -> Vec<T> {
+    let mut sorted = entities.to_vec();
+    sorted.sort_by_key(|entity| (entity.created_at(), entity.id()));
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        hosts::r#impl::{
+            base::HostBase,
+            interfaces::{Interface, InterfaceBase},
+        },
+        services::r#impl::{base::ServiceBase, bindings::Binding},
+        subnets::r#impl::base::SubnetBase,
+        topology::types::base::Ixy,
+    };
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn subnet(index: usize) -> Subnet {
+        Subnet::new(SubnetBase {
+            name: format!("Subnet {}", index),
+            ..SubnetBase::default()
+        })
+    }
+
+    /// A host with an interface in each of the subnets and a service bound to each interface
+    fn host(index: u8, subnets: &[&Subnet]) -> (Host, Vec<Service>) {
+        let host = Host::new(HostBase {
+            name: format!("host-{}", index),
+            interfaces: subnets
+                .iter()
+                .map(|subnet| {
+                    Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: IpAddr::V4(Ipv4Addr::new(127, 0, 0, index)),
+                        mac_address: None,
+                        name: None,
+                    })
+                })
+                .collect(),
+            ..HostBase::default()
+        });
+        let services = host
+            .base
+            .interfaces
+            .iter()
+            .map(|interface| {
+                Service::new(ServiceBase {
+                    host_id: host.id,
+                    bindings: vec![Binding::new_interface(interface.id)],
+                    ..ServiceBase::default()
+                })
+            })
+            .collect();
+        (host, services)
+    }
+
+    fn reversed<T: Clone>(entities: &[T]) -> Vec<T> {
+        entities.iter().rev().cloned().collect()
+    }
+
+    fn positions(hosts: &[Host], subnets: &[Subnet], services: &[Service]) -> Vec<(Uuid, Ixy)> {
+        let graph = TopologyService::layout_graph(
+            hosts,
+            subnets,
+            &[],
+            services,
+            &TopologyRequestOptions::default(),
+        );
+        let mut positions: Vec<(Uuid, Ixy)> = graph
+            .node_weights()
+            .map(|node| (node.id, node.position))
+            .collect();
+        positions.sort_by_key(|(id, _)| *id);
+        positions
+    }
+
+    #[test]
+    fn identical_data_gets_an_identical_layout() {
+        let subnets = [subnet(0), subnet(1), subnet(2)];
+        // Every fourth host is also in the next subnet, so subnets are linked
+        let (hosts, services): (Vec<Host>, Vec<Vec<Service>>) = (0..12)
+            .map(|i| {
+                let index = i as u8 + 2;
+                if i % 4 == 0 {
+                    host(index, &[&subnets[i % 3], &subnets[(i + 1) % 3]])
+                } else {
+                    host(index, &[&subnets[i % 3]])
+                }
+            })
+            .unzip();
+        let services: Vec<Service> = services.into_iter().flatten().collect();
+
+        let first = positions(&hosts, &subnets, &services);
+        assert!(!first.is_empty());
+        assert_eq!(positions(&hosts, &subnets, &services), first);
+        assert_eq!(
+            positions(&reversed(&hosts), &reversed(&subnets), &reversed(&services)),
+            first
+        );
     }
 }
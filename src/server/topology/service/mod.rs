@@ -1,5 +1,8 @@
+pub mod analytics;
+pub mod connectivity;
 pub mod context;
 pub mod edge_builder;
 pub mod main;
 pub mod optimizer;
+pub mod path_tracer;
 pub mod planner;
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 use crate::server::topology::{
     service::{
-        context::TopologyContext, optimizer::utils::OptimizerUtils, planner::utils::NODE_PADDING,
+        context::TopologyContext,
+        optimizer::{spatial_index::SpatialGrid, utils::OptimizerUtils},
+        planner::utils::NODE_PADDING,
     },
     types::{
         base::{Ixy, Uxy},
@@ -32,6 +34,8 @@ struct OptimizeZoneParams<'a> {
     edges: &'a [&'a Edge],
     constraints: &'a HashMap<Uuid, NodeConstraints>,
     subnet_positions: &'a HashMap<Uuid, Ixy>,
+    /// Node index by id; indices are stable while positions change
+    indices: &'a HashMap<Uuid, usize>,
 }
 
 /// Optimizer for positioning child nodes (interface nodes) within subnets
@@ -68,9 +72,17 @@ impl<'a> ChildPositioner<'a> {
             .cloned()
             .collect();
 
-        // Group nodes by (subnet, infra)
-        let mut nodes_by_subnet_infra: HashMap<(Uuid, bool), Vec<Uuid>> = HashMap::new();
-        for node in nodes.iter() {
+        let indices: HashMap<Uuid, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, n)| (n.id, idx))
+            .collect();
+
+        // Group nodes by (subnet, infra). Zones are optimized in a fixed order since each one
+        // scores against positions the previous ones settled on.
+        let mut nodes_by_subnet_infra: BTreeMap<(Uuid, bool), Vec<Uuid>> = BTreeMap::new();
+        let mut children_by_subnet: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (idx, node) in nodes.iter().enumerate() {
             if let NodeType::InterfaceNode {
                 subnet_id,
                 is_infra,
@@ -81,11 +93,19 @@ impl<'a> ChildPositioner<'a> {
                     .entry((subnet_id, is_infra))
                     .or_default()
                     .push(node.id);
+                children_by_subnet.entry(subnet_id).or_default().push(idx);
             }
         }
 
+        // Children of a subnet share a coordinate space, so overlaps are only checked
+        // against siblings, whichever zone they're in
+        let mut grids: HashMap<Uuid, SpatialGrid> = children_by_subnet
+            .iter()
+            .map(|(subnet_id, children)| (*subnet_id, SpatialGrid::build(nodes, children)))
+            .collect();
+
         // For each subnet+infra zone, optimize
-        for ((_, _), node_ids) in nodes_by_subnet_infra.iter() {
+        for ((subnet_id, _), node_ids) in nodes_by_subnet_infra.iter() {
             if node_ids.len() < 2 {
                 continue;
             }
@@ -104,10 +124,15 @@ impl<'a> ChildPositioner<'a> {
                 edges: &all_edges,
                 constraints: &constraints,
                 subnet_positions: &subnet_positions,
+                indices: &indices,
+            };
+
+            let Some(grid) = grids.get_mut(subnet_id) else {
+                continue;
             };
 
             // Optimize using force-directed swapping
-            self.optimize_zone_with_swaps(nodes, node_ids, &params);
+            self.optimize_zone_with_swaps(nodes, node_ids, &params, grid);
         }
     }
 
@@ -170,6 +195,7 @@ impl<'a> ChildPositioner<'a> {
         nodes: &mut [Node],
         node_ids: &[Uuid],
         params: &OptimizeZoneParams,
+        grid: &mut SpatialGrid,
     ) {
         const MAX_ITERATIONS: usize = 100;
 
@@ -185,7 +211,7 @@ impl<'a> ChildPositioner<'a> {
             let tolerance_pct = 0.05 * (1.0 - (iteration as f64 / MAX_ITERATIONS as f64));
             let tolerance = best_score * tolerance_pct;
 
-            let swaps_made = self.try_all_swaps(nodes, node_ids, params, tolerance);
+            let swaps_made = self.try_all_swaps(nodes, node_ids, params, grid, tolerance);
 
             let new_score =
                 self.calculate_layout_score(nodes, params.edges, params.subnet_positions);
@@ -223,6 +249,7 @@ impl<'a> ChildPositioner<'a> {
         nodes: &mut [Node],
         node_ids: &[Uuid],
         params: &OptimizeZoneParams,
+        grid: &mut SpatialGrid,
         tolerance: f64,
     ) -> usize {
         let mut swaps_made = 0;
@@ -233,7 +260,7 @@ impl<'a> ChildPositioner<'a> {
                 let node_a = node_ids[i];
                 let node_b = node_ids[j];
 
-                if self.try_swap(nodes, node_a, node_b, params, tolerance) {
+                if self.try_swap(nodes, node_a, node_b, params, grid, tolerance) {
                     swaps_made += 1;
                 }
             }
@@ -249,6 +276,7 @@ impl<'a> ChildPositioner<'a> {
         node_a: Uuid,
         node_b: Uuid,
         params: &OptimizeZoneParams,
+        grid: &mut SpatialGrid,
         tolerance: f64,
     ) -> bool {
         // Get constraints and info for both nodes
@@ -260,23 +288,19 @@ impl<'a> ChildPositioner<'a> {
             return false;
         }
 
-        // Get current positions and sizes
-        let mut node_a_info: Option<(Ixy, Uxy)> = None;
-        let mut node_b_info: Option<(Ixy, Uxy)> = None;
-
-        for node in nodes.iter() {
-            if node.id == node_a {
-                node_a_info = Some((node.position, node.size));
-            } else if node.id == node_b {
-                node_b_info = Some((node.position, node.size));
-            }
-        }
-
-        let ((a_pos, a_size), (b_pos, b_size)) = match (node_a_info, node_b_info) {
-            (Some(a), Some(b)) => (a, b),
+        let (idx_a, idx_b) = match (params.indices.get(&node_a), params.indices.get(&node_b)) {
+            (Some(a), Some(b)) => (*a, *b),
             _ => return false,
         };
 
+        if nodes[idx_a].is_fixed() || nodes[idx_b].is_fixed() {
+            return false;
+        }
+
+        // Get current positions and sizes
+        let (a_pos, a_size) = (nodes[idx_a].position, nodes[idx_a].size);
+        let (b_pos, b_size) = (nodes[idx_b].position, nodes[idx_b].size);
+
         // Check if swap violates positional constraints
         let dx = b_pos.x - a_pos.x;
         let dy = b_pos.y - a_pos.y;
@@ -303,55 +327,23 @@ impl<'a> ChildPositioner<'a> {
             }
         }
 
-        // Check if swap would cause overlaps
-        for node in nodes.iter() {
-            if node.id == node_a || node.id == node_b {
-                continue;
-            }
-
-            // Check if node_a at node_b's position would overlap with this node
-            if self.utils.rectangles_overlap(
-                Ixy {
-                    x: b_pos.x,
-                    y: b_pos.y,
-                },
-                Uxy {
-                    x: a_size.x,
-                    y: a_size.y,
-                },
-                Ixy {
-                    x: node.position.x,
-                    y: node.position.y,
-                },
-                Uxy {
-                    x: node.size.x,
-                    y: node.size.y,
-                },
-            ) {
-                return false;
-            }
+        // Check if swap would cause overlaps with nearby siblings
+        let overlaps_sibling = |position: Ixy, size: Uxy| {
+            grid.query(position, size).into_iter().any(|idx| {
+                idx != idx_a
+                    && idx != idx_b
+                    && self.utils.rectangles_overlap(
+                        position,
+                        size,
+                        nodes[idx].position,
+                        nodes[idx].size,
+                    )
+            })
+        };
 
-            // Check if node_b at node_a's position would overlap with this node
-            if self.utils.rectangles_overlap(
-                Ixy {
-                    x: a_pos.x,
-                    y: a_pos.y,
-                },
-                Uxy {
-                    x: b_size.x,
-                    y: b_size.y,
-                },
-                Ixy {
-                    x: node.position.x,
-                    y: node.position.y,
-                },
-                Uxy {
-                    x: node.size.x,
-                    y: node.size.y,
-                },
-            ) {
-                return false;
-            }
+        // node_a at node_b's position, and node_b at node_a's
+        if overlaps_sibling(b_pos, a_size) || overlaps_sibling(a_pos, b_size) {
+            return false;
         }
 
         // Calculate current score
@@ -359,13 +351,8 @@ impl<'a> ChildPositioner<'a> {
             self.calculate_layout_score(nodes, params.edges, params.subnet_positions);
 
         // Perform swap
-        for node in nodes.iter_mut() {
-            if node.id == node_a {
-                node.position = b_pos;
-            } else if node.id == node_b {
-                node.position = a_pos;
-            }
-        }
+        nodes[idx_a].position = b_pos;
+        nodes[idx_b].position = a_pos;
 
         // Calculate new score
         let new_score = self.calculate_layout_score(nodes, params.edges, params.subnet_positions);
@@ -382,16 +369,17 @@ impl<'a> ChildPositioner<'a> {
                     tolerance
                 );
             }
+
+            // Keep the index in step with the new positions
+            grid.remove(idx_a, a_pos, a_size);
+            grid.remove(idx_b, b_pos, b_size);
+            grid.insert(idx_a, b_pos, a_size);
+            grid.insert(idx_b, a_pos, b_size);
             true
         } else {
             // Revert swap
-            for node in nodes.iter_mut() {
-                if node.id == node_a {
-                    node.position = a_pos;
-                } else if node.id == node_b {
-                    node.position = b_pos;
-                }
-            }
+            nodes[idx_a].position = a_pos;
+            nodes[idx_b].position = b_pos;
 
             // Log rejected swaps that were close
             if score_delta > 0.0 && score_delta < tolerance * 2.0 {
@@ -448,8 +436,11 @@ impl<'a> ChildPositioner<'a> {
         let mut nodes_by_subnet_and_x: HashMap<(Uuid, isize), Vec<usize>> = HashMap::new();
 
         // Map node indices by subnet and x position
+        // Fixed nodes keep their position and don't take part in compression
         nodes.iter().enumerate().for_each(|(idx, n)| {
-            if let NodeType::InterfaceNode { subnet_id, .. } = n.node_type {
+            if let NodeType::InterfaceNode { subnet_id, .. } = n.node_type
+                && !n.is_fixed()
+            {
                 nodes_by_subnet_and_x
                     .entry((subnet_id, n.position.x))
                     .or_default()
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::server::topology::{
+    service::{
+        context::TopologyContext,
+        planner::utils::{NODE_PADDING, SUBNET_PADDING},
+    },
+    types::nodes::{Node, NodeType},
+};
+
+/// Incremental layout: keeps the positions of nodes from the layout the client already shows
+/// and only places what's new, so adding or removing a host doesn't reshuffle the graph.
+///
+/// - Nodes in the previous layout are moved back there and anchored; the positioners treat
+///   them like pins
+/// - New children go in rows below their subnet's anchored children
+/// - New subnets go in a row below the anchored subnets, where the subnet positioner is free
+///   to move them sideways
+/// - Subnets that grew are pushed down past anything they now overlap
+pub struct IncrementalLayout<'a> {
+    context: &'a TopologyContext<'a>,
+}
+
+impl<'a> IncrementalLayout<'a> {
+    pub fn new(ctx: &'a TopologyContext<'a>) -> Self {
+        Self { context: ctx }
+    }
+
+    pub fn anchor(&self, nodes: &mut [Node]) {
+        let previous = &self.context.options.previous_layout;
+        if previous.is_empty() {
+            return;
+        }
+
+        for node in nodes.iter_mut() {
+            if let Some(position) = previous.get(&node.id) {
+                node.position = *position;
+                node.anchored = true;
+            }
+        }
+
+        Self::place_new_children(nodes);
+        Self::place_new_subnets(nodes);
+        Self::resolve_subnet_overlaps(nodes);
+    }
+
+    fn place_new_children(nodes: &mut [Node]) {
+        let mut children_by_subnet: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        for (idx, node) in nodes.iter().enumerate() {
+            if let NodeType::InterfaceNode { subnet_id, .. } = node.node_type {
+                children_by_subnet.entry(subnet_id).or_default().push(idx);
+            }
+        }
+
+        for (subnet_id, children) in children_by_subnet {
+            // A subnet with no anchored children keeps the planner's layout
+            let Some(anchored_bottom) = children
+                .iter()
+                .filter(|&&idx| nodes[idx].anchored)
+                .map(|&idx| nodes[idx].position.y + nodes[idx].size.y as isize)
+                .max()
+            else {
+                continue;
+            };
+            let Some(subnet_idx) = nodes.iter().position(|n| n.id == subnet_id) else {
+                continue;
+            };
+            let max_x = nodes[subnet_idx].size.x as isize - NODE_PADDING.x as isize;
+
+            let mut x = NODE_PADDING.x as isize;
+            let mut y = anchored_bottom + NODE_PADDING.y as isize;
+            let mut row_height = 0;
+
+            for &idx in children.iter().filter(|&&idx| !nodes[idx].anchored) {
+                let size = nodes[idx].size;
+                if x > NODE_PADDING.x as isize && x + size.x as isize > max_x {
+                    x = NODE_PADDING.x as isize;
+                    y += row_height + NODE_PADDING.y as isize;
+                    row_height = 0;
+                }
+                nodes[idx].position.x = x;
+                nodes[idx].position.y = y;
+                x += (size.x + NODE_PADDING.x) as isize;
+                row_height = row_height.max(size.y as isize);
+            }
+
+            // Fit the subnet to its children, which may have shrunk or grown it
+            let (width, height) = children.iter().fold((0, 0), |(w, h), &idx| {
+                let child = &nodes[idx];
+                (
+                    w.max(child.position.x + (child.size.x + NODE_PADDING.x) as isize),
+                    h.max(child.position.y + (child.size.y + NODE_PADDING.y) as isize),
+                )
+            });
+            nodes[subnet_idx].size.x = width.max(0) as usize;
+            nodes[subnet_idx].size.y = height.max(0) as usize;
+        }
+    }
+
+    fn place_new_subnets(nodes: &mut [Node]) {
+        let anchored = nodes
+            .iter()
+            .filter(|n| n.anchored && matches!(n.node_type, NodeType::SubnetNode { .. }));
+        let Some((left, bottom)) = anchored.fold(None, |acc: Option<(isize, isize)>, n| {
+            let bottom = n.position.y + n.size.y as isize;
+            Some(match acc {
+                Some((left, b)) => (left.min(n.position.x), b.max(bottom)),
+                None => (n.position.x, bottom),
+            })
+        }) else {
+            return;
+        };
+
+        let mut x = left;
+        let y = bottom + SUBNET_PADDING.y as isize;
+        for node in nodes.iter_mut() {
+            if !node.anchored && matches!(node.node_type, NodeType::SubnetNode { .. }) {
+                node.position.x = x;
+                node.position.y = y;
+                x += (node.size.x + SUBNET_PADDING.x) as isize;
+            }
+        }
+    }
+
+    /// Top to bottom, move each subnet below any subnet above it that it overlaps
+    fn resolve_subnet_overlaps(nodes: &mut [Node]) {
+        let mut order: Vec<usize> = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, n)| matches!(n.node_type, NodeType::SubnetNode { .. }))
+            .map(|(idx, _)| idx)
+            .collect();
+        order.sort_by_key(|&idx| (nodes[idx].position.y, nodes[idx].position.x));
+
+        for (k, &idx) in order.iter().enumerate() {
+            while let Some(&above) = order[..k]
+                .iter()
+                .find(|&&other| Self::overlaps(&nodes[idx], &nodes[other]))
+            {
+                nodes[idx].position.y =
+                    nodes[above].position.y + (nodes[above].size.y + SUBNET_PADDING.y) as isize;
+            }
+        }
+    }
+
+    fn overlaps(a: &Node, b: &Node) -> bool {
+        a.position.x < b.position.x + b.size.x as isize
+            && b.position.x < a.position.x + a.size.x as isize
+            && a.position.y < b.position.y + b.size.y as isize
+            && b.position.y < a.position.y + a.size.y as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::topology::types::base::{Ixy, Uxy};
+
+    fn subnet(x: isize, y: isize, width: usize, height: usize) -> Node {
+        Node {
+            node_type: NodeType::SubnetNode { infra_width: 0 },
+            id: Uuid::new_v4(),
+            position: Ixy { x, y },
+            size: Uxy {
+                x: width,
+                y: height,
+            },
+            header: None,
+            pinned: false,
+            anchored: true,
+            aggregate: None,
+            badges: Vec::new(),
+            os: None,
+        }
+    }
+
+    #[test]
+    fn grown_subnet_pushes_overlapping_subnets_down() {
+        let mut nodes = vec![subnet(0, 0, 400, 500), subnet(100, 300, 400, 200)];
+
+        IncrementalLayout::resolve_subnet_overlaps(&mut nodes);
+
+        assert_eq!(nodes[0].position, Ixy { x: 0, y: 0 });
+        assert_eq!(nodes[1].position.y, 500 + SUBNET_PADDING.y as isize);
+    }
+
+    #[test]
+    fn side_by_side_subnets_stay_put() {
+        let mut nodes = vec![subnet(0, 0, 400, 500), subnet(500, 100, 400, 200)];
+
+        IncrementalLayout::resolve_subnet_overlaps(&mut nodes);
+
+        assert_eq!(nodes[1].position, Ixy { x: 500, y: 100 });
+    }
+}
@@ -1,19 +1,31 @@
 // In optimizer/main.rs
 
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
 use crate::server::topology::{
     service::{
         context::TopologyContext,
         optimizer::{
-            child_positioner::ChildPositioner, subnet_positioner::SubnetPositioner,
-            utils::OptimizerUtils,
+            child_positioner::ChildPositioner,
+            incremental::IncrementalLayout,
+            subnet_positioner::SubnetPositioner,
+            utils::{LayoutReport, OptimizerUtils},
         },
+        planner::utils::NODE_PADDING,
+    },
+    types::{
+        base::Uxy,
+        edges::Edge,
+        nodes::{Node, NodeType},
     },
-    types::{edges::Edge, nodes::Node},
 };
 
 pub struct TopologyOptimizer<'a> {
     subnet_positioner: SubnetPositioner<'a>,
     child_positioner: ChildPositioner<'a>,
+    incremental_layout: IncrementalLayout<'a>,
     // anchor_optimizer: AnchorOptimizer<'a>,
     context: &'a TopologyContext<'a>,
     utils: OptimizerUtils,
@@ -24,20 +36,39 @@ impl<'a> TopologyOptimizer<'a> {
         Self {
             subnet_positioner: SubnetPositioner::new(ctx),
             child_positioner: ChildPositioner::new(ctx),
+            incremental_layout: IncrementalLayout::new(ctx),
             // anchor_optimizer: AnchorOptimizer::new(ctx),
             context: ctx,
             utils: OptimizerUtils::new(),
         }
     }
 
+    /// Nodes from the previous layout (in incremental mode) and pinned nodes are moved into place
+    /// first and treated as fixed from then on
     pub fn optimize_graph(&self, nodes: &mut [Node], edges: &[Edge]) -> Vec<Edge> {
+        self.optimize_graph_with_report(nodes, edges).0
+    }
+
+    /// Same as `optimize_graph`, also reporting layout quality along the way
+    pub fn optimize_graph_with_report(
+        &self,
+        nodes: &mut [Node],
+        edges: &[Edge],
+    ) -> (Vec<Edge>, LayoutReport) {
         const MAX_GLOBAL_ITERATIONS: usize = 20;
         const CONVERGENCE_THRESHOLD: f64 = 0.1;
 
+        self.incremental_layout.anchor(nodes);
+        self.apply_pins(nodes);
+
         let mut optimized_edges = edges.to_vec();
         let mut prev_quality =
             self.utils
                 .calculate_layout_quality(nodes, &optimized_edges, self.context);
+        let mut report = LayoutReport {
+            initial: prev_quality,
+            ..Default::default()
+        };
         let mut iterations = 0;
 
         loop {
@@ -52,6 +83,8 @@ impl<'a> TopologyOptimizer<'a> {
                 self.utils
                     .calculate_layout_quality(nodes, &optimized_edges, self.context);
 
+            report.iterations.push(current_quality);
+
             let improvement_pct = current_quality.improvement_percentage(&prev_quality);
 
             // Check convergence conditions
@@ -77,6 +110,49 @@ impl<'a> TopologyOptimizer<'a> {
             .child_positioner
             .fix_intra_subnet_handles(&optimized_edges, nodes);
 
-        optimized_edges
+        report.result = self
+            .utils
+            .calculate_layout_quality(nodes, &optimized_edges, self.context);
+
+        (optimized_edges, report)
+    }
+
+    /// Place pinned nodes where the user left them. Subnets grow to contain children pinned
+    /// past their bounds.
+    fn apply_pins(&self, nodes: &mut [Node]) {
+        let pins = &self.context.options.pinned_nodes;
+        if pins.is_empty() {
+            return;
+        }
+
+        let mut extents: HashMap<Uuid, Uxy> = HashMap::new();
+        for node in nodes.iter_mut() {
+            let Some(position) = pins.get(&node.id) else {
+                continue;
+            };
+            node.position = *position;
+            node.pinned = true;
+
+            if let NodeType::InterfaceNode { subnet_id, .. } = node.node_type {
+                // Children can't sit above or left of their subnet
+                node.position.x = node.position.x.max(0);
+                node.position.y = node.position.y.max(0);
+
+                let extent = extents.entry(subnet_id).or_default();
+                extent.x = extent
+                    .x
+                    .max(node.position.x as usize + node.size.x + NODE_PADDING.x);
+                extent.y = extent
+                    .y
+                    .max(node.position.y as usize + node.size.y + NODE_PADDING.y);
+            }
+        }
+
+        for subnet in nodes.iter_mut() {
+            if let Some(extent) = extents.get(&subnet.id) {
+                subnet.size.x = subnet.size.x.max(extent.x);
+                subnet.size.y = subnet.size.y.max(extent.y);
+            }
+        }
     }
 }
@@ -1,4 +1,6 @@
 pub mod child_positioner;
+pub mod incremental;
 pub mod main;
+pub mod spatial_index;
 pub mod subnet_positioner;
 pub mod utils;
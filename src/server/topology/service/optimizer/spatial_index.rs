@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::server::topology::types::{
+    base::{Ixy, Uxy},
+    nodes::Node,
+};
+
+/// Uniform grid over node rectangles for overlap queries
+///
+/// Child nodes are laid out on a regular grid of similarly sized nodes, so with cells about
+/// one node in size a query only returns the few nodes around the rectangle instead of every
+/// node in the subnet. Entries are node indices and have to be moved along with the nodes.
+pub struct SpatialGrid {
+    cell: Uxy,
+    cells: HashMap<(isize, isize), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell: Uxy) -> Self {
+        Self {
+            cell: Uxy {
+                x: cell.x.max(1),
+                y: cell.y.max(1),
+            },
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Index the given nodes, sizing cells to the largest of them
+    pub fn build(nodes: &[Node], indices: &[usize]) -> Self {
+        let cell = indices.iter().fold(Uxy::default(), |cell, &idx| Uxy {
+            x: cell.x.max(nodes[idx].size.x),
+            y: cell.y.max(nodes[idx].size.y),
+        });
+
+        let mut grid = Self::new(cell);
+        for &idx in indices {
+            grid.insert(idx, nodes[idx].position, nodes[idx].size);
+        }
+        grid
+    }
+
+    pub fn insert(&mut self, idx: usize, position: Ixy, size: Uxy) {
+        for key in self.covered_cells(position, size) {
+            self.cells.entry(key).or_default().push(idx);
+        }
+    }
+
+    pub fn remove(&mut self, idx: usize, position: Ixy, size: Uxy) {
+        for key in self.covered_cells(position, size) {
+            if let Some(entries) = self.cells.get_mut(&key) {
+                entries.retain(|&i| i != idx);
+            }
+        }
+    }
+
+    /// Indices of nodes that may overlap the rectangle; callers still test the exact bounds
+    pub fn query(&self, position: Ixy, size: Uxy) -> Vec<usize> {
+        let mut found: Vec<usize> = self
+            .covered_cells(position, size)
+            .filter_map(|key| self.cells.get(&key))
+            .flatten()
+            .copied()
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn covered_cells(&self, position: Ixy, size: Uxy) -> impl Iterator<Item = (isize, isize)> {
+        let (cell_x, cell_y) = (self.cell.x as isize, self.cell.y as isize);
+        let x_range =
+            position.x.div_euclid(cell_x)..=(position.x + size.x as isize).div_euclid(cell_x);
+        let y_range =
+            position.y.div_euclid(cell_y)..=(position.y + size.y as isize).div_euclid(cell_y);
+
+        x_range.flat_map(move |x| y_range.clone().map(move |y| (x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::topology::service::optimizer::utils::OptimizerUtils;
+
+    #[test]
+    fn query_returns_every_overlapping_node() {
+        let utils = OptimizerUtils::new();
+        let size = Uxy { x: 100, y: 50 };
+        let rects: Vec<Ixy> = (0..10)
+            .flat_map(|x| {
+                (0..10).map(move |y| Ixy {
+                    x: x * 150,
+                    y: y * 100,
+                })
+            })
+            .collect();
+
+        let mut grid = SpatialGrid::new(size);
+        for (idx, position) in rects.iter().enumerate() {
+            grid.insert(idx, *position, size);
+        }
+
+        for query in [
+            Ixy { x: 140, y: 90 },
+            Ixy { x: -60, y: -30 },
+            Ixy { x: 700, y: 480 },
+        ] {
+            let found = grid.query(query, size);
+            for (idx, position) in rects.iter().enumerate() {
+                if utils.rectangles_overlap(query, size, *position, size) {
+                    assert!(found.contains(&idx), "{:?} missing {}", query, idx);
+                }
+            }
+            assert!(found.len() < 10);
+        }
+
+        grid.remove(11, rects[11], size);
+        assert!(!grid.query(Ixy { x: 140, y: 90 }, size).contains(&11));
+    }
+}
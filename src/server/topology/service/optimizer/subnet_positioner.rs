@@ -34,6 +34,7 @@ const CONVERGENCE_THRESHOLD: f64 = 1.0; // Stop when improvement < 1.0 pixels
 /// - Edge weighting: vertical=100, mixed=1, horizontal/multi-hop=0
 /// - Grid snapping happens AFTER optimization to avoid local minima
 /// - Non-overlap constraints prevent subnet collisions in the same row
+/// - Pinned and anchored subnets are never moved; they only constrain their neighbors
 pub struct SubnetPositioner<'a> {
     max_iterations: usize,
     context: &'a TopologyContext<'a>,
@@ -116,7 +117,10 @@ impl<'a> SubnetPositioner<'a> {
 
         // NOW snap all positions to grid for visual alignment
         for subnet_id in &subnet_ids {
-            if let Some(subnet) = nodes.iter_mut().find(|n| n.id == *subnet_id) {
+            if let Some(subnet) = nodes
+                .iter_mut()
+                .find(|n| n.id == *subnet_id && !n.is_fixed())
+            {
                 let original_x = subnet.position.x;
                 let snapped_x = Self::snap_to_grid(original_x as f64);
                 subnet.position.x = snapped_x;
@@ -159,6 +163,12 @@ impl<'a> SubnetPositioner<'a> {
 
         // For each subnet in this layer, calculate optimal position based on neighbors
         for &subnet_id in current_layer {
+            // Fixed subnets stay put; the rest of the layer is arranged around them
+            if let Some(fixed) = nodes.iter().find(|n| n.id == subnet_id && n.is_fixed()) {
+                positioned_in_layer.insert(subnet_id, fixed.position.x);
+                continue;
+            }
+
             let optimal_x = self.calculate_barycenter_position(
                 nodes, edges, subnet_id, layers, layer_idx, down_sweep,
             );
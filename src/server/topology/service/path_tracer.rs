@@ -0,0 +1,314 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use uuid::Uuid;
+
+use crate::server::topology::{
+    service::{connectivity::ConnectivityGraph, context::TopologyContext},
+    types::paths::{ServicePath, ServicePathHop},
+};
+
+/// Follows RequestPath and HubAndSpoke group edges between services, then checks each hop
+/// against the network so users can see where a chain breaks
+pub struct ServicePathTracer<'a> {
+    context: &'a TopologyContext<'a>,
+    connectivity: ConnectivityGraph,
+}
+
+/// Group edge between two services, keyed by the service it leaves from
+#[derive(Clone, Copy)]
+struct GroupLink {
+    group_id: Uuid,
+    source_binding_id: Uuid,
+    target_service_id: Uuid,
+    target_binding_id: Uuid,
+}
+
+impl<'a> ServicePathTracer<'a> {
+    pub fn new(ctx: &'a TopologyContext<'a>) -> Self {
+        Self {
+            context: ctx,
+            connectivity: ConnectivityGraph::from_context(ctx),
+        }
+    }
+
+    pub fn trace(&self, source_service_id: Uuid, target_service_id: Uuid) -> ServicePath {
+        let hops = self.find_group_chain(source_service_id, target_service_id);
+
+        let direct_network_path = match (
+            self.context.get_service_by_id(source_service_id),
+            self.context.get_service_by_id(target_service_id),
+        ) {
+            (Some(source), Some(target)) => self
+                .connectivity
+                .network_path(source.base.host_id, target.base.host_id),
+            _ => None,
+        };
+
+        let is_reachable = !hops.is_empty() && hops.iter().all(|hop| hop.network_path.is_some());
+
+        ServicePath {
+            source_service_id,
+            target_service_id,
+            hops,
+            direct_network_path,
+            is_reachable,
+        }
+    }
+
+    /// Shortest chain of group edges from source to target (breadth-first)
+    fn find_group_chain(
+        &self,
+        source_service_id: Uuid,
+        target_service_id: Uuid,
+    ) -> Vec<ServicePathHop> {
+        let links = self.group_links();
+
+        let mut previous: HashMap<Uuid, (Uuid, GroupLink)> = HashMap::new();
+        let mut visited = HashSet::from([source_service_id]);
+        let mut queue = VecDeque::from([source_service_id]);
+
+        while let Some(service_id) = queue.pop_front() {
+            if service_id == target_service_id {
+                break;
+            }
+
+            for link in links.get(&service_id).into_iter().flatten() {
+                if visited.insert(link.target_service_id) {
+                    previous.insert(link.target_service_id, (service_id, *link));
+                    queue.push_back(link.target_service_id);
+                }
+            }
+        }
+
+        let mut hops = Vec::new();
+        let mut current = target_service_id;
+        while let Some((source_id, link)) = previous.get(&current) {
+            hops.push(self.hop(*source_id, link));
+            current = *source_id;
+        }
+        hops.reverse();
+        hops
+    }
+
+    fn group_links(&self) -> HashMap<Uuid, Vec<GroupLink>> {
+        let mut links: HashMap<Uuid, Vec<GroupLink>> = HashMap::new();
+
+        for group in self.context.groups {
+            for (source_binding_id, target_binding_id) in group.base.group_type.binding_pairs() {
+                if let (Some(source), Some(target)) = (
+                    self.context.get_service_by_binding_id(source_binding_id),
+                    self.context.get_service_by_binding_id(target_binding_id),
+                ) && source.id != target.id
+                {
+                    links.entry(source.id).or_default().push(GroupLink {
+                        group_id: group.id,
+                        source_binding_id,
+                        target_service_id: target.id,
+                        target_binding_id,
+                    });
+                }
+            }
+        }
+
+        links
+    }
+
+    fn hop(&self, source_service_id: Uuid, link: &GroupLink) -> ServicePathHop {
+        let network_path = match (
+            self.context.get_service_by_id(source_service_id),
+            self.context.get_service_by_id(link.target_service_id),
+        ) {
+            (Some(source), Some(target)) => self
+                .connectivity
+                .network_path(source.base.host_id, target.base.host_id),
+            _ => None,
+        };
+
+        ServicePathHop {
+            group_id: link.group_id,
+            source_service_id,
+            source_binding_id: link.source_binding_id,
+            target_service_id: link.target_service_id,
+            target_binding_id: link.target_binding_id,
+            network_path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{
+        groups::r#impl::{
+            base::{Group, GroupBase},
+            types::GroupType,
+        },
+        hosts::r#impl::{
+            base::{Host, HostBase},
+            interfaces::{Interface, InterfaceBase},
+        },
+        services::r#impl::{
+            base::{Service, ServiceBase},
+            bindings::Binding,
+        },
+        shared::{storage::traits::StorableEntity, types::entities::EntitySource},
+        subnets::r#impl::base::{Subnet, SubnetBase},
+        topology::types::{analytics::ConnectivityNode, api::TopologyRequestOptions},
+    };
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn subnet() -> Subnet {
+        Subnet::new(SubnetBase::default())
+    }
+
+    fn host(subnets: &[&Subnet]) -> Host {
+        Host::new(HostBase {
+            interfaces: subnets
+                .iter()
+                .map(|subnet| {
+                    Interface::new(InterfaceBase {
+                        subnet_id: subnet.id,
+                        ip_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        mac_address: None,
+                        name: None,
+                    })
+                })
+                .collect(),
+            ..HostBase::default()
+        })
+    }
+
+    /// A service bound to the host's first interface
+    fn service(host: &Host) -> Service {
+        Service::new(ServiceBase {
+            host_id: host.id,
+            bindings: vec![Binding::new_interface(host.base.interfaces[0].id)],
+            ..ServiceBase::default()
+        })
+    }
+
+    fn request_path(services: &[&Service]) -> Group {
+        Group::new(GroupBase {
+            name: "Request path".to_string(),
+            network_id: Uuid::nil(),
+            description: None,
+            group_type: GroupType::RequestPath {
+                service_bindings: services.iter().map(|s| s.base.bindings[0].id()).collect(),
+            },
+            source: EntitySource::Manual,
+            color: String::new(),
+        })
+    }
+
+    fn trace(
+        hosts: &[Host],
+        subnets: &[Subnet],
+        services: &[Service],
+        groups: &[Group],
+        source: &Service,
+        target: &Service,
+    ) -> ServicePath {
+        let options = TopologyRequestOptions::default();
+        let ctx = TopologyContext::new(hosts, subnets, services, groups, &options);
+        ServicePathTracer::new(&ctx).trace(source.id, target.id)
+    }
+
+    #[test]
+    fn follows_the_chain_and_the_network_between_its_hosts() {
+        // proxy - dmz - app - backend - db
+        let subnets = [subnet(), subnet()];
+        let hosts = [
+            host(&[&subnets[0]]),
+            host(&[&subnets[0], &subnets[1]]),
+            host(&[&subnets[1]]),
+        ];
+        let services = [service(&hosts[0]), service(&hosts[1]), service(&hosts[2])];
+        let groups = [request_path(&[&services[0], &services[1], &services[2]])];
+
+        let path = trace(
+            &hosts,
+            &subnets,
+            &services,
+            &groups,
+            &services[0],
+            &services[2],
+        );
+
+        assert!(path.is_reachable);
+        assert_eq!(
+            path.hops
+                .iter()
+                .map(|hop| (hop.source_service_id, hop.target_service_id))
+                .collect::<Vec<_>>(),
+            vec![
+                (services[0].id, services[1].id),
+                (services[1].id, services[2].id)
+            ]
+        );
+        assert_eq!(
+            path.hops[1].network_path,
+            Some(vec![
+                ConnectivityNode::Host(hosts[1].id),
+                ConnectivityNode::Subnet(subnets[1].id),
+                ConnectivityNode::Host(hosts[2].id),
+            ])
+        );
+        assert_eq!(path.direct_network_path.map(|p| p.len()), Some(5));
+    }
+
+    #[test]
+    fn hop_without_a_network_route_breaks_the_chain() {
+        // The database is on a subnet no other host is in
+        let subnets = [subnet(), subnet()];
+        let hosts = [
+            host(&[&subnets[0]]),
+            host(&[&subnets[0]]),
+            host(&[&subnets[1]]),
+        ];
+        let services = [service(&hosts[0]), service(&hosts[1]), service(&hosts[2])];
+        let groups = [request_path(&[&services[0], &services[1], &services[2]])];
+
+        let path = trace(
+            &hosts,
+            &subnets,
+            &services,
+            &groups,
+            &services[0],
+            &services[2],
+        );
+
+        assert!(!path.is_reachable);
+        assert_eq!(path.hops.len(), 2);
+        assert!(path.hops[0].network_path.is_some());
+        assert!(path.hops[1].network_path.is_none());
+        assert!(path.direct_network_path.is_none());
+
+        // Request paths only lead one way
+        let back = trace(
+            &hosts,
+            &subnets,
+            &services,
+            &groups,
+            &services[1],
+            &services[0],
+        );
+        assert!(!back.is_reachable);
+        assert!(back.hops.is_empty());
+    }
+
+    #[test]
+    fn same_source_and_target_has_no_hops() {
+        let subnets = [subnet()];
+        let hosts = [host(&[&subnets[0]])];
+        let services = [service(&hosts[0])];
+
+        let path = trace(&hosts, &subnets, &services, &[], &services[0], &services[0]);
+
+        assert!(path.hops.is_empty());
+        assert!(!path.is_reachable);
+        assert_eq!(
+            path.direct_network_path,
+            Some(vec![ConnectivityNode::Host(hosts[0].id)])
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::server::topology::{
+    service::context::TopologyContext,
+    types::{
+        edges::{Edge, EdgeTypeDiscriminants},
+        nodes::SubnetAggregate,
+    },
+};
+
+/// Collapses subnets with more hosts than `collapse_host_threshold` into a single summary node,
+/// unless the request lists them in `expanded_subnet_ids`. Collapsed subnets get no child
+/// nodes, so their hosts cost nothing to plan or optimize.
+pub struct SubnetAggregator<'a> {
+    context: &'a TopologyContext<'a>,
+    collapsed: HashSet<Uuid>,
+}
+
+impl<'a> SubnetAggregator<'a> {
+    pub fn new(ctx: &'a TopologyContext<'a>) -> Self {
+        let collapsed = match ctx.options.collapse_host_threshold {
+            Some(threshold) => {
+                let mut hosts_by_subnet: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+                for host in ctx.hosts {
+                    for interface in &host.base.interfaces {
+                        hosts_by_subnet
+                            .entry(interface.base.subnet_id)
+                            .or_default()
+                            .insert(host.id);
+                    }
+                }
+
+                hosts_by_subnet
+                    .into_iter()
+                    .filter(|(subnet_id, hosts)| {
+                        hosts.len() > threshold
+                            && !ctx.options.expanded_subnet_ids.contains(subnet_id)
+                    })
+                    .map(|(subnet_id, _)| subnet_id)
+                    .collect()
+            }
+            None => HashSet::new(),
+        };
+
+        Self {
+            context: ctx,
+            collapsed,
+        }
+    }
+
+    pub fn is_collapsed(&self, subnet_id: &Uuid) -> bool {
+        self.collapsed.contains(subnet_id)
+    }
+
+    pub fn collapsed_subnets(&self) -> &HashSet<Uuid> {
+        &self.collapsed
+    }
+
+    /// Host, interface and per-category service counts shown on a collapsed subnet
+    pub fn aggregate(&self, subnet_id: Uuid) -> SubnetAggregate {
+        let mut aggregate = SubnetAggregate {
+            host_count: 0,
+            interface_count: 0,
+            service_counts: BTreeMap::new(),
+        };
+
+        for host in self.context.hosts {
+            let interface_ids: Vec<Uuid> = host
+                .base
+                .interfaces
+                .iter()
+                .filter(|i| i.base.subnet_id == subnet_id)
+                .map(|i| i.id)
+                .collect();
+            if interface_ids.is_empty() {
+                continue;
+            }
+
+            aggregate.host_count += 1;
+            aggregate.interface_count += interface_ids.len();
+
+            for service in self
+                .context
+                .services
+                .iter()
+                .filter(|s| s.base.host_id == host.id)
+            {
+                // Bound to one of the subnet's interfaces, or to all of the host's
+                let in_subnet = service
+                    .to_bound_interface_ids()
+                    .iter()
+                    .any(|id| id.is_none_or(|id| interface_ids.contains(&id)));
+                if in_subnet {
+                    *aggregate
+                        .service_counts
+                        .entry(service.base.service_definition.category().to_string())
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        aggregate
+    }
+
+    /// Point edge ends inside collapsed subnets at the subnet node, so links between subnets
+    /// survive collapsing. Edges that end up inside a single collapsed subnet are dropped, and
+    /// parallel edges of the same type are merged.
+    pub fn reroute_edges(&self, edges: Vec<Edge>) -> Vec<Edge> {
+        if self.collapsed.is_empty() {
+            return edges;
+        }
+
+        let mut seen = HashSet::new();
+        edges
+            .into_iter()
+            .filter_map(|mut edge| {
+                edge.source = self.collapsed_subnet_of(edge.source).unwrap_or(edge.source);
+                edge.target = self.collapsed_subnet_of(edge.target).unwrap_or(edge.target);
+
+                (edge.source != edge.target
+                    && seen.insert((
+                        edge.source,
+                        edge.target,
+                        EdgeTypeDiscriminants::from(&edge.edge_type),
+                    )))
+                .then_some(edge)
+            })
+            .collect()
+    }
+
+    fn collapsed_subnet_of(&self, interface_id: Uuid) -> Option<Uuid> {
+        self.context
+            .get_subnet_from_interface_id(interface_id)
+            .map(|s| s.id)
+            .filter(|id| self.collapsed.contains(id))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod aggregation;
 pub mod anchor_planner;
 pub mod child_planner;
 pub mod subnet_layout_planner;
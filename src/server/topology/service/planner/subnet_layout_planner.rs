@@ -10,6 +10,7 @@ use crate::server::{
         service::{
             context::TopologyContext,
             planner::{
+                aggregation::SubnetAggregator,
                 anchor_planner::ChildAnchorPlanner,
                 child_planner::ChildNodePlanner,
                 utils::{NODE_PADDING, PlannerUtils, SUBNET_PADDING},
@@ -52,15 +53,17 @@ impl SubnetLayoutPlanner {
         group_docker_bridges_by_host: bool,
         docker_bridge_host_subnet_id_to_group_on: HashMap<Uuid, Uuid>,
     ) -> (HashMap<Uuid, SubnetLayout>, Vec<Node>) {
+        let aggregator = SubnetAggregator::new(ctx);
         let children_by_subnet = self.group_children_by_subnet(
             ctx,
             all_edges,
             group_docker_bridges_by_host,
             docker_bridge_host_subnet_id_to_group_on,
+            aggregator.collapsed_subnets(),
         );
         let mut child_nodes = Vec::new();
 
-        let subnet_sizes: HashMap<Uuid, SubnetLayout> = children_by_subnet
+        let mut subnet_sizes: HashMap<Uuid, SubnetLayout> = children_by_subnet
             .iter()
             .map(|(subnet_id, children)| {
                 let (size, infra_width) =
@@ -69,6 +72,17 @@ impl SubnetLayoutPlanner {
             })
             .collect();
 
+        // Collapsed subnets are a fixed size summary without children
+        for subnet_id in aggregator.collapsed_subnets() {
+            subnet_sizes.insert(
+                *subnet_id,
+                SubnetLayout {
+                    size: Uxy::subnet_summary_size(),
+                    infra_width: 0,
+                },
+            );
+        }
+
         (subnet_sizes, child_nodes)
     }
 
@@ -219,8 +233,10 @@ impl SubnetLayoutPlanner {
         all_edges: &mut [Edge],
         group_docker_bridges_by_host: bool,
         docker_bridge_host_subnet_id_to_group_on: HashMap<Uuid, Uuid>,
-    ) -> HashMap<Uuid, Vec<SubnetChild>> {
-        let mut children_by_subnet: HashMap<Uuid, Vec<SubnetChild>> = HashMap::new();
+        collapsed_subnets: &HashSet<Uuid>,
+    ) -> BTreeMap<Uuid, Vec<SubnetChild>> {
+        // Ordered so child nodes come out in the same order on every run
+        let mut children_by_subnet: BTreeMap<Uuid, Vec<SubnetChild>> = BTreeMap::new();
 
         // Track DockerBridge interfaces by host (only used if grouping is enabled)
         // Map: (host_id, primary_subnet_id) -> Vec<subnet_id>)
@@ -228,6 +244,10 @@ impl SubnetLayoutPlanner {
 
         for host in ctx.hosts {
             for interface in &host.base.interfaces {
+                if collapsed_subnets.contains(&interface.base.subnet_id) {
+                    continue;
+                }
+
                 let subnet = ctx.get_subnet_by_id(interface.base.subnet_id);
                 let subnet_type = subnet.map(|s| s.base.subnet_type).unwrap_or_default();
 
@@ -399,6 +419,11 @@ impl SubnetLayoutPlanner {
                     position: layout.position,
                     size: child.size,
                     header: child.header.clone(),
+                    pinned: false,
+                    anchored: false,
+                    aggregate: None,
+                    badges: ctx.host_badges(child.host_id),
+                    os: ctx.host_os(child.host_id),
                 });
             }
         }
@@ -427,6 +452,11 @@ impl SubnetLayoutPlanner {
                     position: node_position,
                     size: child.size,
                     header: child.header.clone(),
+                    pinned: false,
+                    anchored: false,
+                    aggregate: None,
+                    badges: ctx.host_badges(child.host_id),
+                    os: ctx.host_os(child.host_id),
                 });
             }
         }
@@ -449,10 +479,18 @@ impl SubnetLayoutPlanner {
         let (positions, _) =
             PlannerUtils::calculate_container_size(subnet_grid_positions, &SUBNET_PADDING);
 
+        let aggregator = SubnetAggregator::new(ctx);
+
+        // Sorted so subnet nodes come out in the same order on every run
         layouts
             .iter()
+            .sorted_by_key(|(subnet_id, _)| **subnet_id)
             .filter_map(|(subnet_id, layout)| {
                 if let Some(position) = positions.get(subnet_id) {
+                    let aggregate = aggregator
+                        .is_collapsed(subnet_id)
+                        .then(|| aggregator.aggregate(*subnet_id));
+
                     if let Some(consolidated_subnet_ids) =
                         self.consolidated_docker_subnets.get(subnet_id)
                     {
@@ -473,6 +511,11 @@ impl SubnetLayoutPlanner {
                             position: *position,
                             size: layout.size,
                             header: Some(header),
+                            pinned: false,
+                            anchored: false,
+                            aggregate,
+                            badges: ctx.subnet_badges(*subnet_id),
+                            os: None,
                         });
                     }
 
@@ -484,6 +527,11 @@ impl SubnetLayoutPlanner {
                         position: *position,
                         size: layout.size,
                         header: None,
+                        pinned: false,
+                        anchored: false,
+                        aggregate,
+                        badges: ctx.subnet_badges(*subnet_id),
+                        os: None,
                     });
                 }
                 None
@@ -505,6 +553,7 @@ impl SubnetLayoutPlanner {
                     s.base.subnet_type.vertical_order(),
                     s.base.subnet_type.horizontal_order(),
                     s.base.name.clone(),
+                    s.id,
                 )
             })
             .filter_map(|s| layouts.get(&s.id).map(|layout| (s, layout)))
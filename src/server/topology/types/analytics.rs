@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Vertex in the host-level connectivity graph
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(tag = "node_kind", content = "id")]
+pub enum ConnectivityNode {
+    Host(Uuid),
+    Subnet(Uuid),
+}
+
+/// Reason two connectivity nodes are linked
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd)]
+#[serde(tag = "edge_kind")]
+pub enum ConnectivityEdge {
+    /// Host has an interface in the subnet
+    Interface { interface_id: Uuid },
+    /// Host runs as a VM on the host of the virtualizing service
+    Virtualization { service_id: Uuid },
+    /// Services on both hosts are linked by a group
+    Dependency { group_id: Uuid },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologyAnalytics {
+    pub host_metrics: Vec<HostMetrics>,
+    pub articulation_points: Vec<ArticulationPoint>,
+    pub isolated_hosts: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HostMetrics {
+    pub host_id: Uuid,
+    pub name: String,
+    /// Number of subnets, VM hosts and dependency peers this host is linked to
+    pub degree: usize,
+    /// Normalized betweenness centrality in the range 0.0..=1.0
+    pub betweenness_centrality: f64,
+}
+
+/// Host whose removal splits the hosts it was connected to into separate islands
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ArticulationPoint {
+    pub host_id: Uuid,
+    pub name: String,
+    /// Number of host islands left behind once this host is removed
+    pub resulting_partitions: usize,
+    /// Hosts cut off from the largest remaining island
+    pub stranded_host_ids: Vec<Uuid>,
+}
+
+/// Simulated outcome of a host going offline
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FailureImpact {
+    pub host_id: Uuid,
+    /// The failed host plus any VMs it was running
+    pub removed_host_ids: Vec<Uuid>,
+    /// Hosts cut off from the rest of their island
+    pub disconnected_host_ids: Vec<Uuid>,
+    /// Subnets whose only gateways were removed
+    pub subnets_without_gateway: Vec<Uuid>,
+    /// Hosts left in a subnet without a gateway
+    pub hosts_without_gateway: Vec<Uuid>,
+    pub affected_services: Vec<ServiceImpact>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ServiceImpact {
+    pub service_id: Uuid,
+    pub host_id: Uuid,
+    pub reason: ServiceImpactReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "reason")]
+pub enum ServiceImpactReason {
+    /// Service was running on a removed host
+    HostRemoved,
+    /// Service's host can no longer reach the rest of the network
+    HostDisconnected,
+    /// A service this one is grouped with was running on a removed host
+    DependencyLost { group_id: Uuid, service_id: Uuid },
+}
@@ -1,7 +1,16 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::server::services::r#impl::categories::ServiceCategory;
+use crate::server::{
+    hosts::r#impl::os::OsFamily,
+    services::r#impl::categories::ServiceCategory,
+    topology::{
+        exporter::ExportFormat,
+        types::{base::Ixy, nodes::Node},
+    },
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TopologyRequestOptions {
@@ -12,4 +21,93 @@ pub struct TopologyRequestOptions {
     pub left_zone_service_categories: Vec<ServiceCategory>,
     pub hide_service_categories: Vec<ServiceCategory>,
     pub show_gateway_in_left_zone: bool,
+    #[serde(default)]
+    pub detail_level: TopologyDetailLevel,
+    /// Only lay out these subnets; empty means all
+    #[serde(default)]
+    pub subnet_ids: Vec<Uuid>,
+    /// Only lay out the services in these groups and the hosts running them; empty means all
+    #[serde(default)]
+    pub group_ids: Vec<Uuid>,
+    /// Only show services in these categories; empty means all
+    #[serde(default)]
+    pub service_categories: Vec<ServiceCategory>,
+    #[serde(default)]
+    pub hide_hosts_without_services: bool,
+    /// Only lay out hosts estimated to run these operating systems; empty means all
+    #[serde(default)]
+    pub os_families: Vec<OsFamily>,
+    /// Collapse subnets with more hosts than this into a single summary node
+    #[serde(default)]
+    pub collapse_host_threshold: Option<usize>,
+    /// Subnets to show in full even when they're over the collapse threshold
+    #[serde(default)]
+    pub expanded_subnet_ids: Vec<Uuid>,
+    /// Positions of nodes placed by hand, merged over the stored pins. Subnet positions are
+    /// absolute; interface positions are relative to their subnet.
+    #[serde(default)]
+    pub pinned_nodes: HashMap<Uuid, Ixy>,
+    /// Node positions from the layout the client is showing. When set, the layout is
+    /// incremental: nodes that still exist stay put and only new ones are placed.
+    #[serde(default)]
+    pub previous_layout: HashMap<Uuid, Ixy>,
+}
+
+/// How much of the topology to lay out and return
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TopologyDetailLevel {
+    /// Every subnet with all of its child nodes and edges
+    #[default]
+    Full,
+    /// Only subnet containers with aggregate counts; children are fetched per subnet
+    Subnets,
+}
+
+/// Subnet-only view of the topology used at low zoom levels
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopologySummary {
+    pub subnets: Vec<SubnetSummary>,
+    pub links: Vec<SubnetLink>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubnetSummary {
+    #[serde(flatten)]
+    pub node: Node,
+    pub host_count: usize,
+    pub interface_count: usize,
+    pub service_count: usize,
+}
+
+/// Aggregate of all edges running between two subnets
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubnetLink {
+    pub source: Uuid,
+    pub target: Uuid,
+    pub edge_count: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ImpactQuery {
+    pub host: Uuid,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ServicePathQuery {
+    pub source: Uuid,
+    pub target: Uuid,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TopologyQualityQuery {
+    /// Lay out a single network instead of all of the user's networks
+    pub network_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TopologyExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// Export a single network instead of all of the user's networks
+    pub network_id: Option<Uuid>,
 }
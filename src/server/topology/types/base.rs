@@ -16,6 +16,8 @@ const SUBNET_CHILD_HEADER_HEIGHT: usize = 25;
 const SUBNET_CHILD_FOOTER_HEIGHT: usize = 25;
 const HEIGHT_PER_SERVICE_IN_SUBNET_CHILD: usize = 50;
 const SUBNET_CHILD_WIDTH: usize = 250;
+const SUBNET_SUMMARY_WIDTH: usize = 300;
+const SUBNET_SUMMARY_HEIGHT: usize = 150;
 
 impl Uxy {
     pub fn default_subnet_child_size() -> Self {
@@ -25,6 +27,14 @@ impl Uxy {
         }
     }
 
+    /// Fixed container size for subnets rendered without their children
+    pub fn subnet_summary_size() -> Self {
+        Self {
+            x: SUBNET_SUMMARY_WIDTH,
+            y: SUBNET_SUMMARY_HEIGHT,
+        }
+    }
+
     pub fn subnet_child_size_from_service_count(
         services: &[&Service],
         interface_id: Uuid,
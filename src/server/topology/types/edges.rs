@@ -251,6 +251,11 @@ pub enum EdgeType {
         source_binding_id: Uuid,
         target_binding_id: Uuid,
     },
+    /// A VLAN interface and the trunk it's tagged on
+    Trunk {
+        host_id: Uuid,
+        vlan_id: Option<u16>,
+    },
 }
 
 impl HasId for EdgeType {
@@ -267,6 +272,7 @@ impl EntityMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => Entity::Host.color(),
             EdgeType::HostVirtualization { .. } => Entity::Virtualization.color(),
             EdgeType::ServiceVirtualization { .. } => Entity::Virtualization.color(),
+            EdgeType::Trunk { .. } => Entity::Subnet.color(),
         }
     }
 
@@ -277,6 +283,7 @@ impl EntityMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => Entity::Host.icon(),
             EdgeType::HostVirtualization { .. } => Entity::Virtualization.icon(),
             EdgeType::ServiceVirtualization { .. } => Entity::Virtualization.icon(),
+            EdgeType::Trunk { .. } => Entity::Subnet.icon(),
         }
     }
 }
@@ -289,6 +296,7 @@ impl TypeMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => "Host Interface",
             EdgeType::HostVirtualization { .. } => "Virtualized Host",
             EdgeType::ServiceVirtualization { .. } => "Virtualized Service",
+            EdgeType::Trunk { .. } => "VLAN Trunk",
         }
     }
 
@@ -299,6 +307,7 @@ impl TypeMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => EdgeStyle::SmoothStep.into(),
             EdgeType::HostVirtualization { .. } => EdgeStyle::Straight.into(),
             EdgeType::ServiceVirtualization { .. } => EdgeStyle::SmoothStep.into(),
+            EdgeType::Trunk { .. } => EdgeStyle::SmoothStep.into(),
         };
 
         let is_dashed = match &self {
@@ -307,6 +316,7 @@ impl TypeMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => true,
             EdgeType::HostVirtualization { .. } => true,
             EdgeType::ServiceVirtualization { .. } => true,
+            EdgeType::Trunk { .. } => false,
         };
 
         let has_start_marker = false;
@@ -317,6 +327,7 @@ impl TypeMetadataProvider for EdgeType {
             EdgeType::Interface { .. } => false,
             EdgeType::HostVirtualization { .. } => false,
             EdgeType::ServiceVirtualization { .. } => false,
+            EdgeType::Trunk { .. } => false,
         };
 
         serde_json::json!({
@@ -1,4 +1,6 @@
+pub mod analytics;
 pub mod api;
 pub mod base;
 pub mod edges;
 pub mod nodes;
+pub mod paths;
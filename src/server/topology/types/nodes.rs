@@ -1,7 +1,9 @@
+use crate::server::hosts::r#impl::os::OsFamily;
 use crate::server::subnets::r#impl::types::SubnetType;
 use crate::server::topology::types::base::{Ixy, Uxy};
 use crate::server::topology::types::edges::Edge;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use strum_macros::{Display, EnumDiscriminants, EnumIter, IntoStaticStr};
 use uuid::Uuid;
 
@@ -13,6 +15,45 @@ pub struct Node {
     pub position: Ixy,
     pub size: Uxy,
     pub header: Option<String>,
+    /// Placed by the user; the optimizer leaves it where it is
+    #[serde(default)]
+    pub pinned: bool,
+    /// Kept where it was in the previous layout during an incremental layout
+    #[serde(skip)]
+    pub anchored: bool,
+    /// Set on subnets collapsed into a summary instead of showing their children
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregate: Option<SubnetAggregate>,
+    /// Tags and custom fields of the node's host or subnet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub badges: Vec<String>,
+    /// Estimated operating system of an interface node's host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<NodeOs>,
+}
+
+/// What a collapsed subnet contains
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SubnetAggregate {
+    pub host_count: usize,
+    pub interface_count: usize,
+    /// Service count by category
+    pub service_counts: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeOs {
+    pub family: OsFamily,
+    pub name: Option<String>,
+    /// 0-100
+    pub confidence: u8,
+}
+
+impl Node {
+    /// Whether the optimizer has to leave the node where it is
+    pub fn is_fixed(&self) -> bool {
+        self.pinned || self.anchored
+    }
 }
 
 #[derive(
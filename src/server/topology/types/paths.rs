@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology::types::analytics::ConnectivityNode;
+
+/// Result of tracing a request from one service to another
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServicePath {
+    pub source_service_id: Uuid,
+    pub target_service_id: Uuid,
+    /// Group hops from source to target, empty if no group chain connects them
+    pub hops: Vec<ServicePathHop>,
+    /// Network route between the source and target hosts, regardless of group chains
+    pub direct_network_path: Option<Vec<ConnectivityNode>>,
+    /// True when a group chain exists and every hop is reachable over the network
+    pub is_reachable: bool,
+}
+
+/// One group edge in a service chain, e.g. reverse proxy -> app
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServicePathHop {
+    pub group_id: Uuid,
+    pub source_service_id: Uuid,
+    pub source_binding_id: Uuid,
+    pub target_service_id: Uuid,
+    pub target_binding_id: Uuid,
+    /// Hosts and subnets traversed for this hop, None if the hosts share no network route
+    pub network_path: Option<Vec<ConnectivityNode>>,
+}
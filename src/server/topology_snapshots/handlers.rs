@@ -0,0 +1,106 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    network_members::handlers::network_role,
+    shared::{
+        handlers::traits::{delete_handler, get_by_id_handler},
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    topology_snapshots::r#impl::{
+        api::{SnapshotHistoryQuery, TopologyDiffQuery, TopologySnapshotSummary},
+        base::TopologySnapshot,
+        diff::TopologyDiff,
+    },
+};
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json,
+    routing::{delete, get, post},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_history))
+        .route("/", post(capture_snapshot))
+        .route("/{id}", get(get_by_id_handler::<TopologySnapshot>))
+        .route("/{id}", delete(delete_handler::<TopologySnapshot>))
+}
+
+/// Snapshot versions of a network, newest first
+async fn get_history(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<SnapshotHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<Vec<TopologySnapshotSummary>>>> {
+    network_role(&state, &user.0, &query.network_id).await?;
+
+    let history = state
+        .services
+        .topology_snapshot_service
+        .history(&query.network_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(
+        history
+            .iter()
+            .rev()
+            .map(TopologySnapshotSummary::from)
+            .collect(),
+    )))
+}
+
+/// Take a snapshot now instead of waiting for the next discovery run
+async fn capture_snapshot(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<SnapshotHistoryQuery>,
+) -> ApiResult<Json<ApiResponse<TopologySnapshotSummary>>> {
+    network_role(&state, &user.0, &query.network_id).await?;
+
+    let snapshot = state
+        .services
+        .topology_snapshot_service
+        .capture(query.network_id, None)
+        .await?;
+
+    Ok(Json(ApiResponse::success(TopologySnapshotSummary::from(
+        &snapshot,
+    ))))
+}
+
+/// Hosts, services and edges added, removed or changed between two snapshots
+pub async fn get_topology_diff(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Query(query): Query<TopologyDiffQuery>,
+) -> ApiResult<Json<ApiResponse<TopologyDiff>>> {
+    let service = &state.services.topology_snapshot_service;
+
+    let mut snapshots = Vec::new();
+    for id in [query.from, query.to] {
+        snapshots.push(
+            service.get_by_id(&id).await?.ok_or_else(|| {
+                ApiError::not_found(format!("Topology snapshot '{}' not found", id))
+            })?,
+        );
+    }
+    let (from, to) = (&snapshots[0], &snapshots[1]);
+
+    if from.base.network_id != to.base.network_id {
+        return Err(ApiError::bad_request(
+            "Snapshots belong to different networks",
+        ));
+    }
+    network_role(&state, &user.0, &from.base.network_id).await?;
+
+    Ok(Json(ApiResponse::success(TopologyDiff::new(
+        from.id,
+        &from.base.data,
+        to.id,
+        &to.base.data,
+    ))))
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology_snapshots::r#impl::base::TopologySnapshot;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SnapshotHistoryQuery {
+    pub network_id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopologyDiffQuery {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+/// Snapshot without its data, for listing history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshotSummary {
+    pub id: Uuid,
+    pub network_id: Uuid,
+    pub version: i32,
+    pub discovery_session_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub host_count: usize,
+    pub service_count: usize,
+    pub edge_count: usize,
+}
+
+impl From<&TopologySnapshot> for TopologySnapshotSummary {
+    fn from(snapshot: &TopologySnapshot) -> Self {
+        Self {
+            id: snapshot.id,
+            network_id: snapshot.base.network_id,
+            version: snapshot.base.version,
+            discovery_session_id: snapshot.base.discovery_session_id,
+            created_at: snapshot.created_at,
+            host_count: snapshot.base.data.hosts.len(),
+            service_count: snapshot.base.data.services.len(),
+            edge_count: snapshot.base.data.edges.len(),
+        }
+    }
+}
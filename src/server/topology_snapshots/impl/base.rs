@@ -0,0 +1,36 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology_snapshots::r#impl::data::TopologySnapshotData;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshotBase {
+    pub network_id: Uuid,
+    /// Increments per network with every stored snapshot
+    pub version: i32,
+    /// Discovery run the snapshot was taken after, if any
+    pub discovery_session_id: Option<Uuid>,
+    pub data: TopologySnapshotData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: TopologySnapshotBase,
+}
+
+impl Display for TopologySnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Topology snapshot v{} for network {}: {}",
+            self.base.version, self.base.network_id, self.id
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use petgraph::Graph;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::base::Host,
+    services::r#impl::base::Service,
+    topology::types::{edges::Edge, nodes::Node},
+};
+
+/// What a network's topology looked like at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct TopologySnapshotData {
+    pub hosts: BTreeMap<Uuid, HostState>,
+    pub services: BTreeMap<Uuid, ServiceState>,
+    /// Display names of graph nodes, so edges can still be described once their hosts are gone
+    pub nodes: BTreeMap<Uuid, String>,
+    pub edges: BTreeSet<EdgeState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct HostState {
+    pub name: String,
+    pub hostname: Option<String>,
+    pub ip_addresses: BTreeSet<String>,
+    pub mac_addresses: BTreeSet<String>,
+    pub ports: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServiceState {
+    pub host_id: Uuid,
+    pub name: String,
+    /// Name of the matched service definition
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EdgeState {
+    pub source: Uuid,
+    pub target: Uuid,
+    /// e.g. `Interface` or `RequestPath`
+    pub edge_type: String,
+}
+
+impl TopologySnapshotData {
+    pub fn new(
+        hosts: &[Host],
+        services: &[Service],
+        graph: &Graph<Node, Edge>,
+        labels: HashMap<Uuid, String>,
+    ) -> Self {
+        let hosts = hosts
+            .iter()
+            .map(|host| {
+                let state = HostState {
+                    name: host.base.name.clone(),
+                    hostname: host.base.hostname.clone(),
+                    ip_addresses: host
+                        .base
+                        .interfaces
+                        .iter()
+                        .map(|i| i.base.ip_address.to_string())
+                        .collect(),
+                    mac_addresses: host
+                        .base
+                        .interfaces
+                        .iter()
+                        .filter_map(|i| i.base.mac_address.as_ref().map(|mac| mac.to_string()))
+                        .collect(),
+                    ports: host.base.ports.iter().map(|p| p.base.to_string()).collect(),
+                };
+                (host.id, state)
+            })
+            .collect();
+
+        let services = services
+            .iter()
+            .map(|service| {
+                let state = ServiceState {
+                    host_id: service.base.host_id,
+                    name: service.base.name.clone(),
+                    definition: service.base.service_definition.name().to_string(),
+                };
+                (service.id, state)
+            })
+            .collect();
+
+        let nodes = graph
+            .node_weights()
+            .map(|node| {
+                let label = labels
+                    .get(&node.id)
+                    .cloned()
+                    .or_else(|| node.header.clone())
+                    .unwrap_or_else(|| node.id.to_string());
+                (node.id, label)
+            })
+            .collect();
+
+        let edges = graph
+            .edge_weights()
+            .map(|edge| EdgeState {
+                source: edge.source,
+                target: edge.target,
+                edge_type: <&'static str>::from(&edge.edge_type).to_string(),
+            })
+            .collect();
+
+        Self {
+            hosts,
+            services,
+            nodes,
+            edges,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::topology_snapshots::r#impl::data::{
+    EdgeState, HostState, ServiceState, TopologySnapshotData,
+};
+
+/// Changes between two snapshots of a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyDiff {
+    pub from: Uuid,
+    pub to: Uuid,
+    pub hosts: EntityChanges<HostState>,
+    pub services: EntityChanges<ServiceState>,
+    pub edges: EdgeChanges,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityChanges<T> {
+    pub added: Vec<SnapshotEntry<T>>,
+    pub removed: Vec<SnapshotEntry<T>>,
+    pub changed: Vec<ChangedEntry<T>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotEntry<T> {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub state: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedEntry<T> {
+    pub id: Uuid,
+    pub before: T,
+    pub after: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EdgeChanges {
+    pub added: Vec<EdgeEntry>,
+    pub removed: Vec<EdgeEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeEntry {
+    #[serde(flatten)]
+    pub edge: EdgeState,
+    pub source_name: Option<String>,
+    pub target_name: Option<String>,
+}
+
+impl TopologyDiff {
+    pub fn new(
+        from_id: Uuid,
+        from: &TopologySnapshotData,
+        to_id: Uuid,
+        to: &TopologySnapshotData,
+    ) -> Self {
+        let edge_entry = |edge: &EdgeState, data: &TopologySnapshotData| EdgeEntry {
+            edge: edge.clone(),
+            source_name: data.nodes.get(&edge.source).cloned(),
+            target_name: data.nodes.get(&edge.target).cloned(),
+        };
+
+        Self {
+            from: from_id,
+            to: to_id,
+            hosts: EntityChanges::between(&from.hosts, &to.hosts),
+            services: EntityChanges::between(&from.services, &to.services),
+            edges: EdgeChanges {
+                added: to
+                    .edges
+                    .difference(&from.edges)
+                    .map(|e| edge_entry(e, to))
+                    .collect(),
+                removed: from
+                    .edges
+                    .difference(&to.edges)
+                    .map(|e| edge_entry(e, from))
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+            && self.services.is_empty()
+            && self.edges.added.is_empty()
+            && self.edges.removed.is_empty()
+    }
+}
+
+impl<T: Clone + PartialEq> EntityChanges<T> {
+    fn between(from: &BTreeMap<Uuid, T>, to: &BTreeMap<Uuid, T>) -> Self {
+        let entry = |(id, state): (&Uuid, &T)| SnapshotEntry {
+            id: *id,
+            state: state.clone(),
+        };
+
+        Self {
+            added: to
+                .iter()
+                .filter(|(id, _)| !from.contains_key(id))
+                .map(entry)
+                .collect(),
+            removed: from
+                .iter()
+                .filter(|(id, _)| !to.contains_key(id))
+                .map(entry)
+                .collect(),
+            changed: to
+                .iter()
+                .filter_map(|(id, after)| match from.get(id) {
+                    Some(before) if before != after => Some(ChangedEntry {
+                        id: *id,
+                        before: before.clone(),
+                        after: after.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(name: &str, ip: &str) -> HostState {
+        HostState {
+            name: name.to_string(),
+            ip_addresses: [ip.to_string()].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let (kept, gone, rogue) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let from = TopologySnapshotData {
+            hosts: [
+                (kept, host("nas", "10.0.0.2")),
+                (gone, host("printer", "10.0.0.3")),
+            ]
+            .into(),
+            nodes: [(kept, "nas".to_string()), (gone, "printer".to_string())].into(),
+            edges: [EdgeState {
+                source: kept,
+                target: gone,
+                edge_type: "Interface".to_string(),
+            }]
+            .into(),
+            ..Default::default()
+        };
+        let to = TopologySnapshotData {
+            hosts: [
+                (kept, host("nas", "10.0.0.20")),
+                (rogue, host("unknown", "10.0.0.99")),
+            ]
+            .into(),
+            ..Default::default()
+        };
+
+        let diff = TopologyDiff::new(Uuid::new_v4(), &from, Uuid::new_v4(), &to);
+
+        assert_eq!(diff.hosts.added.len(), 1);
+        assert_eq!(diff.hosts.added[0].id, rogue);
+        assert_eq!(diff.hosts.removed[0].id, gone);
+        assert_eq!(diff.hosts.changed[0].id, kept);
+        assert_eq!(diff.edges.removed.len(), 1);
+        assert_eq!(
+            diff.edges.removed[0].target_name.as_deref(),
+            Some("printer")
+        );
+        assert!(diff.edges.added.is_empty());
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let data = TopologySnapshotData {
+            hosts: [(Uuid::new_v4(), host("nas", "10.0.0.2"))].into(),
+            ..Default::default()
+        };
+
+        assert!(TopologyDiff::new(Uuid::new_v4(), &data, Uuid::new_v4(), &data).is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::server::shared::handlers::traits::CrudHandlers;
+use crate::server::topology_snapshots::r#impl::base::TopologySnapshot;
+use crate::server::topology_snapshots::service::TopologySnapshotService;
+use uuid::Uuid;
+
+impl CrudHandlers for TopologySnapshot {
+    type Service = TopologySnapshotService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.topology_snapshot_service
+    }
+
+    fn entity_name() -> &'static str {
+        "Topology snapshot"
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
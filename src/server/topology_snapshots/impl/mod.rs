@@ -0,0 +1,6 @@
+pub mod api;
+pub mod base;
+pub mod data;
+pub mod diff;
+pub mod handlers;
+pub mod storage;
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    topology::service::main::TopologyService,
+    topology_snapshots::r#impl::base::{TopologySnapshot, TopologySnapshotBase},
+};
+
+/// Older snapshots of a network are deleted once it has this many
+const MAX_SNAPSHOTS_PER_NETWORK: usize = 100;
+
+pub struct TopologySnapshotService {
+    storage: Arc<GenericPostgresStorage<TopologySnapshot>>,
+    topology_service: Arc<TopologyService>,
+}
+
+#[async_trait]
+impl CrudService<TopologySnapshot> for TopologySnapshotService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<TopologySnapshot>> {
+        &self.storage
+    }
+}
+
+impl TopologySnapshotService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<TopologySnapshot>>,
+        topology_service: Arc<TopologyService>,
+    ) -> Self {
+        Self {
+            storage,
+            topology_service,
+        }
+    }
+
+    /// Snapshots of a network, oldest first
+    pub async fn history(&self, network_id: &Uuid) -> Result<Vec<TopologySnapshot>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let mut snapshots = self.storage.get_all(filter).await?;
+        snapshots.sort_by_key(|s| s.base.version);
+
+        Ok(snapshots)
+    }
+
+    /// Store the network's current topology as a new version. Nothing is stored when it's
+    /// unchanged since the last snapshot, which is returned instead.
+    pub async fn capture(
+        &self,
+        network_id: Uuid,
+        discovery_session_id: Option<Uuid>,
+    ) -> Result<TopologySnapshot> {
+        let history = self.history(&network_id).await?;
+        let data = self.topology_service.snapshot(network_id).await?;
+
+        if let Some(latest) = history.last()
+            && latest.base.data == data
+        {
+            return Ok(latest.clone());
+        }
+
+        let snapshot = TopologySnapshot::new(TopologySnapshotBase {
+            network_id,
+            version: history.last().map(|s| s.base.version + 1).unwrap_or(1),
+            discovery_session_id,
+            data,
+        });
+        let snapshot = self.storage.create(&snapshot).await?;
+
+        let excess = (history.len() + 1).saturating_sub(MAX_SNAPSHOTS_PER_NETWORK);
+        for old in history.iter().take(excess) {
+            self.storage.delete(&old.id).await?;
+        }
+
+        Ok(snapshot)
+    }
+}
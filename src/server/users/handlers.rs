@@ -1,15 +1,72 @@
 use crate::server::shared::handlers::traits::{
     create_handler, delete_handler, get_by_id_handler, update_handler,
 };
-use crate::server::{config::AppState, users::r#impl::base::User};
+use crate::server::shared::types::api::{ApiError, ApiResponse, ApiResult};
+use crate::server::{
+    auth::middleware::{AuthenticatedOperator, AuthenticatedUser, is_operator},
+    config::AppState,
+    users::r#impl::base::User,
+};
 use axum::Router;
+use axum::extract::{Path, State};
+use axum::response::Json;
 use axum::routing::{delete, get, post, put};
 use std::sync::Arc;
+use uuid::Uuid;
 
 pub fn create_router() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/", post(create_handler::<User>))
-        .route("/{id}", put(update_handler::<User>))
-        .route("/{id}", delete(delete_handler::<User>))
-        .route("/{id}", get(get_by_id_handler::<User>))
+        .route("/", post(create_user))
+        .route("/{id}", put(update_user))
+        .route("/{id}", delete(delete_user))
+        .route("/{id}", get(get_user))
+}
+
+/// Users can only see and change their own account; server operators can manage any
+async fn ensure_self_or_operator(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    id: &Uuid,
+) -> ApiResult<()> {
+    if user.0 == *id || is_operator(state, &user.0).await? {
+        Ok(())
+    } else {
+        Err(ApiError::not_found(format!("User '{}' not found", id)))
+    }
+}
+
+async fn create_user(
+    state: State<Arc<AppState>>,
+    operator: AuthenticatedOperator,
+    request: Json<User>,
+) -> ApiResult<Json<ApiResponse<User>>> {
+    create_handler::<User>(state, AuthenticatedUser(operator.0), request).await
+}
+
+async fn get_user(
+    state: State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<User>>> {
+    ensure_self_or_operator(&state, &user, &id).await?;
+    get_by_id_handler::<User>(state, user, Path(id)).await
+}
+
+async fn update_user(
+    state: State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    request: Json<User>,
+) -> ApiResult<Json<ApiResponse<User>>> {
+    ensure_self_or_operator(&state, &user, &id).await?;
+    update_handler::<User>(state, user, Path(id), request).await
+}
+
+async fn delete_user(
+    state: State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<()>>> {
+    ensure_self_or_operator(&state, &user, &id).await?;
+    delete_handler::<User>(state, user, Path(id)).await
 }
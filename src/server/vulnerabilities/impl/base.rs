@@ -0,0 +1,98 @@
+use std::{fmt::Display, hash::Hash};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display as StrumDisplay;
+use uuid::Uuid;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+    StrumDisplay,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilitySeverity {
+    /// The feed has no CVSS score for it yet
+    #[default]
+    Unknown,
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl VulnerabilitySeverity {
+    /// From a CVSS `baseSeverity`, e.g. "HIGH"
+    pub fn from_cvss(severity: &str) -> Self {
+        match severity.to_ascii_uppercase().as_str() {
+            "NONE" => Self::None,
+            "LOW" => Self::Low,
+            "MEDIUM" => Self::Medium,
+            "HIGH" => Self::High,
+            "CRITICAL" => Self::Critical,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Known vulnerability of a version a service announced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceVulnerability {
+    /// e.g. "CVE-2021-23017"
+    pub id: String,
+    /// Product of the detected version it affects, e.g. "nginx"
+    pub product: String,
+    pub severity: VulnerabilitySeverity,
+    /// CVSS base score, preferring v3.1 over v3.0 over v2
+    pub score: Option<f32>,
+    pub summary: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+// Scores are parsed from JSON numbers and never NaN
+impl Eq for ServiceVulnerability {}
+
+impl Hash for ServiceVulnerability {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.product.hash(state);
+    }
+}
+
+/// CVEs the feed lists for one CPE, cached so each version is only looked up once a day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VulnerabilityFeedEntryBase {
+    /// e.g. "cpe:2.3:a:f5:nginx:1.18.0"
+    pub cpe: String,
+    pub vulnerabilities: Vec<ServiceVulnerability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VulnerabilityFeedEntry {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// When the feed was last queried for the CPE
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: VulnerabilityFeedEntryBase,
+}
+
+impl Display for VulnerabilityFeedEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Vulnerability feed entry for {}: {}",
+            self.base.cpe, self.id
+        )
+    }
+}
@@ -0,0 +1,3 @@
+pub mod base;
+pub mod nvd;
+pub mod storage;
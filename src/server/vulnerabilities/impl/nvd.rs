@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::server::vulnerabilities::r#impl::base::{ServiceVulnerability, VulnerabilitySeverity};
+
+const CVE_API_URL: &str = "https://services.nvd.nist.gov/rest/json/cves/2.0";
+const PAGE_SIZE: usize = 2000;
+
+/// Client for the NVD CVE API
+pub struct NvdClient {
+    client: reqwest::Client,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CveResponse {
+    total_results: usize,
+    #[serde(default)]
+    vulnerabilities: Vec<CveItem>,
+}
+
+#[derive(Deserialize)]
+struct CveItem {
+    cve: Cve,
+}
+
+#[derive(Deserialize)]
+struct Cve {
+    id: String,
+    published: Option<String>,
+    #[serde(default)]
+    descriptions: Vec<CveDescription>,
+    #[serde(default)]
+    metrics: CveMetrics,
+}
+
+#[derive(Deserialize)]
+struct CveDescription {
+    lang: String,
+    value: String,
+}
+
+#[derive(Deserialize, Default)]
+struct CveMetrics {
+    #[serde(default, rename = "cvssMetricV31")]
+    v31: Vec<CvssMetric>,
+    #[serde(default, rename = "cvssMetricV30")]
+    v30: Vec<CvssMetric>,
+    #[serde(default, rename = "cvssMetricV2")]
+    v2: Vec<CvssMetric>,
+}
+
+/// v2 metrics carry the severity next to the CVSS data rather than in it
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssMetric {
+    cvss_data: CvssData,
+    base_severity: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CvssData {
+    base_score: f32,
+    base_severity: Option<String>,
+}
+
+impl NvdClient {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            api_key,
+        }
+    }
+
+    /// Pause between requests that keeps within the API's rate limit, which is ten times
+    /// higher with a key
+    pub fn request_interval(&self) -> Duration {
+        match self.api_key {
+            Some(_) => Duration::from_millis(700),
+            None => Duration::from_secs(6),
+        }
+    }
+
+    /// CVEs affecting a CPE, including ones listed for a range of versions containing it
+    pub async fn vulnerabilities(
+        &self,
+        cpe: &str,
+        product: &str,
+    ) -> Result<Vec<ServiceVulnerability>> {
+        let mut vulnerabilities = Vec::new();
+        let mut start_index = 0;
+
+        loop {
+            let mut request = self.client.get(CVE_API_URL).query(&[
+                ("virtualMatchString", cpe.to_string()),
+                ("resultsPerPage", PAGE_SIZE.to_string()),
+                ("startIndex", start_index.to_string()),
+            ]);
+            if let Some(api_key) = &self.api_key {
+                request = request.header("apiKey", api_key);
+            }
+
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                bail!("NVD returned HTTP {} for {}", status, cpe);
+            }
+
+            let (total, page) = parse(&response.text().await?, product)?;
+            vulnerabilities.extend(page);
+            start_index += PAGE_SIZE;
+            if start_index >= total {
+                return Ok(vulnerabilities);
+            }
+
+            tokio::time::sleep(self.request_interval()).await;
+        }
+    }
+}
+
+/// Total results and the page's CVEs
+fn parse(body: &str, product: &str) -> Result<(usize, Vec<ServiceVulnerability>)> {
+    let response: CveResponse = serde_json::from_str(body)?;
+
+    let vulnerabilities = response
+        .vulnerabilities
+        .into_iter()
+        .map(|item| {
+            let Cve {
+                id,
+                published,
+                descriptions,
+                metrics,
+            } = item.cve;
+
+            let metric = metrics
+                .v31
+                .into_iter()
+                .chain(metrics.v30)
+                .chain(metrics.v2)
+                .next();
+            let (score, severity) = match metric {
+                Some(metric) => (
+                    Some(metric.cvss_data.base_score),
+                    metric
+                        .cvss_data
+                        .base_severity
+                        .or(metric.base_severity)
+                        .map(|s| VulnerabilitySeverity::from_cvss(&s))
+                        .unwrap_or_default(),
+                ),
+                None => (None, VulnerabilitySeverity::Unknown),
+            };
+
+            ServiceVulnerability {
+                id,
+                product: product.to_string(),
+                severity,
+                score,
+                summary: descriptions
+                    .into_iter()
+                    .find(|d| d.lang == "en")
+                    .map(|d| d.value),
+                // NVD timestamps are UTC without an offset
+                published_at: published
+                    .and_then(|p| NaiveDateTime::parse_from_str(&p, "%Y-%m-%dT%H:%M:%S%.f").ok())
+                    .map(|p| p.and_utc()),
+            }
+        })
+        .collect();
+
+    Ok((response.total_results, vulnerabilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cves_with_preferred_metric() {
+        let body = r#"{
+            "resultsPerPage": 2,
+            "startIndex": 0,
+            "totalResults": 2,
+            "vulnerabilities": [
+                {
+                    "cve": {
+                        "id": "CVE-2021-23017",
+                        "published": "2021-06-01T13:15:07.853",
+                        "descriptions": [
+                            {"lang": "es", "value": "Una vulnerabilidad"},
+                            {"lang": "en", "value": "A security issue in nginx resolver"}
+                        ],
+                        "metrics": {
+                            "cvssMetricV31": [
+                                {"cvssData": {"baseScore": 7.7, "baseSeverity": "HIGH"}}
+                            ],
+                            "cvssMetricV2": [
+                                {"cvssData": {"baseScore": 6.8}, "baseSeverity": "MEDIUM"}
+                            ]
+                        }
+                    }
+                },
+                {
+                    "cve": {
+                        "id": "CVE-2009-3896",
+                        "metrics": {
+                            "cvssMetricV2": [
+                                {"cvssData": {"baseScore": 5.0}, "baseSeverity": "MEDIUM"}
+                            ]
+                        }
+                    }
+                }
+            ]
+        }"#;
+
+        let (total, vulnerabilities) = parse(body, "nginx").unwrap();
+
+        assert_eq!(total, 2);
+        assert_eq!(vulnerabilities[0].id, "CVE-2021-23017");
+        assert_eq!(vulnerabilities[0].severity, VulnerabilitySeverity::High);
+        assert_eq!(vulnerabilities[0].score, Some(7.7));
+        assert_eq!(
+            vulnerabilities[0].summary.as_deref(),
+            Some("A security issue in nginx resolver")
+        );
+        assert!(vulnerabilities[0].published_at.is_some());
+        assert_eq!(vulnerabilities[1].severity, VulnerabilitySeverity::Medium);
+        assert_eq!(vulnerabilities[1].product, "nginx");
+    }
+}
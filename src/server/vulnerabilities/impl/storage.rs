@@ -0,0 +1,89 @@
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    shared::storage::traits::{SqlValue, StorableEntity},
+    vulnerabilities::r#impl::base::{VulnerabilityFeedEntry, VulnerabilityFeedEntryBase},
+};
+
+impl StorableEntity for VulnerabilityFeedEntry {
+    type BaseData = VulnerabilityFeedEntryBase;
+
+    fn table_name() -> &'static str {
+        "vulnerability_feed"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    cpe,
+                    vulnerabilities,
+                },
+        } = self.clone();
+
+        Ok((
+            vec!["id", "created_at", "updated_at", "cpe", "vulnerabilities"],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::String(cpe),
+                SqlValue::Json(serde_json::to_value(&vulnerabilities)?),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let vulnerabilities =
+            serde_json::from_value(row.get::<serde_json::Value, _>("vulnerabilities"))
+                .or(Err(Error::msg("Failed to deserialize vulnerabilities")))?;
+
+        Ok(VulnerabilityFeedEntry {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: VulnerabilityFeedEntryBase {
+                cpe: row.get("cpe"),
+                vulnerabilities,
+            },
+        })
+    }
+}
@@ -0,0 +1,2 @@
+pub mod r#impl;
+pub mod service;
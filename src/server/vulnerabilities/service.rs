@@ -0,0 +1,123 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+
+use crate::server::{
+    services::r#impl::base::Service,
+    shared::storage::{
+        filter::EntityFilter,
+        generic::GenericPostgresStorage,
+        traits::{StorableEntity, Storage},
+    },
+    vulnerabilities::r#impl::{
+        base::{ServiceVulnerability, VulnerabilityFeedEntry, VulnerabilityFeedEntryBase},
+        nvd::NvdClient,
+    },
+};
+
+/// How long a CPE's CVEs are cached before the feed is queried again
+const FEED_MAX_AGE_HOURS: i64 = 24;
+
+/// Annotates services with the known vulnerabilities of the versions they announced
+pub struct VulnerabilityService {
+    feed_storage: Arc<GenericPostgresStorage<VulnerabilityFeedEntry>>,
+    service_storage: Arc<GenericPostgresStorage<Service>>,
+}
+
+impl VulnerabilityService {
+    pub fn new(
+        feed_storage: Arc<GenericPostgresStorage<VulnerabilityFeedEntry>>,
+        service_storage: Arc<GenericPostgresStorage<Service>>,
+    ) -> Self {
+        Self {
+            feed_storage,
+            service_storage,
+        }
+    }
+
+    /// Refresh the cached CVEs of every detected version older than a day from NVD, then
+    /// update each service's vulnerabilities. Returns how many services changed.
+    pub async fn sync(&self, nvd_api_key: Option<String>) -> Result<usize> {
+        let services = self
+            .service_storage
+            .get_all(EntityFilter::unfiltered())
+            .await?;
+        let mut feed: HashMap<String, VulnerabilityFeedEntry> = self
+            .feed_storage
+            .get_all(EntityFilter::unfiltered())
+            .await?
+            .into_iter()
+            .map(|entry| (entry.base.cpe.clone(), entry))
+            .collect();
+
+        let detected: BTreeMap<String, String> = services
+            .iter()
+            .flat_map(|s| &s.base.versions)
+            .map(|v| (v.cpe.clone(), v.product.clone()))
+            .collect();
+
+        let nvd = NvdClient::new(nvd_api_key);
+        let fresh_after = Utc::now() - Duration::hours(FEED_MAX_AGE_HOURS);
+        let mut first_request = true;
+
+        for (cpe, product) in detected {
+            if feed.get(&cpe).is_some_and(|e| e.updated_at > fresh_after) {
+                continue;
+            }
+            if !first_request {
+                tokio::time::sleep(nvd.request_interval()).await;
+            }
+            first_request = false;
+
+            let vulnerabilities = match nvd.vulnerabilities(&cpe, &product).await {
+                Ok(vulnerabilities) => vulnerabilities,
+                Err(e) => {
+                    tracing::warn!("Vulnerability feed lookup for {} failed: {}", cpe, e);
+                    continue;
+                }
+            };
+
+            let entry = match feed.remove(&cpe) {
+                Some(mut entry) => {
+                    entry.base.vulnerabilities = vulnerabilities;
+                    self.feed_storage.update(&mut entry).await?;
+                    entry
+                }
+                None => {
+                    let entry = VulnerabilityFeedEntry::new(VulnerabilityFeedEntryBase {
+                        cpe: cpe.clone(),
+                        vulnerabilities,
+                    });
+                    self.feed_storage.create(&entry).await?;
+                    entry
+                }
+            };
+            feed.insert(cpe, entry);
+        }
+
+        let mut changed = 0;
+        for mut service in services {
+            let mut vulnerabilities: Vec<ServiceVulnerability> = service
+                .base
+                .versions
+                .iter()
+                .filter_map(|v| feed.get(&v.cpe))
+                .flat_map(|entry| entry.base.vulnerabilities.iter().cloned())
+                .collect();
+            // Most severe first
+            vulnerabilities.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.id.cmp(&b.id)));
+
+            if vulnerabilities != service.base.vulnerabilities {
+                service.base.vulnerabilities = vulnerabilities;
+                self.service_storage.update(&mut service).await?;
+                changed += 1;
+            }
+        }
+
+        Ok(changed)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, delete_handler, get_accessible, get_all_handler,
+            get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    webhooks::r#impl::{
+        api::{
+            WebhookDeliveryResult, WebhookEvent, WebhookResponse, WebhookTemplatePreviewRequest,
+        },
+        base::{Webhook, WebhookDelivery},
+        template,
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing::{delete, get, post, put},
+};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_all_handler::<Webhook>))
+        .route("/", post(create_handler))
+        .route("/{id}/rotate", post(rotate_secret_handler))
+        .route("/{id}/test", post(test_handler))
+        .route("/{id}/preview", post(preview_handler))
+        .route("/{id}/deliveries", get(get_deliveries_handler))
+        .route("/{id}", put(update_handler))
+        .route("/{id}", delete(delete_handler::<Webhook>))
+        .route("/{id}", get(get_by_id_handler::<Webhook>))
+}
+
+pub async fn create_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(webhook): Json<Webhook>,
+) -> ApiResult<Json<ApiResponse<WebhookResponse>>> {
+    if let Err(err) = webhook.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+    check_access(&state, &user.0, &webhook).await?;
+
+    let service = Webhook::get_service(&state);
+    let webhook = service.create(webhook).await?;
+
+    Ok(Json(ApiResponse::success(WebhookResponse {
+        secret: webhook.base.secret.clone(),
+        webhook,
+    })))
+}
+
+pub async fn rotate_secret_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(webhook_id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<String>>> {
+    get_accessible::<Webhook>(&state, &user.0, &webhook_id).await?;
+
+    let service = Webhook::get_service(&state);
+    let secret = service.rotate_secret(webhook_id).await?;
+
+    Ok(Json(ApiResponse::success(secret)))
+}
+
+pub async fn update_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<Webhook>,
+) -> ApiResult<Json<ApiResponse<Webhook>>> {
+    if let Err(err) = request.validate() {
+        return Err(ApiError::bad_request(&err));
+    }
+
+    let existing: Webhook = get_accessible(&state, &user.0, &id).await?;
+    check_access(&state, &user.0, &request).await?;
+
+    request.base.secret = existing.base.secret;
+
+    let service = Webhook::get_service(&state);
+    let updated = service
+        .update(&mut request)
+        .await
+        .map_err(|e| ApiError::internal_error(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
+
+/// Send a signed test event to the webhook and return the receiver's response
+pub async fn test_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<WebhookDeliveryResult>>> {
+    let webhook: Webhook = get_accessible(&state, &user.0, &id).await?;
+
+    let event = test_event(&webhook);
+    let result = Webhook::get_service(&state)
+        .deliver(&webhook, &event)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// Render a template against a test event without sending it
+pub async fn preview_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(request): Json<WebhookTemplatePreviewRequest>,
+) -> ApiResult<Json<ApiResponse<String>>> {
+    let webhook: Webhook = get_accessible(&state, &user.0, &id).await?;
+
+    let rendered = template::render(&request.template, &test_event(&webhook))
+        .map_err(|e| ApiError::bad_request(&e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(rendered)))
+}
+
+/// Delivery log of a webhook, newest first
+pub async fn get_deliveries_handler(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<Vec<WebhookDelivery>>>> {
+    get_accessible::<Webhook>(&state, &user.0, &id).await?;
+
+    let mut deliveries = Webhook::get_service(&state).deliveries(&id).await?;
+    deliveries.reverse();
+
+    Ok(Json(ApiResponse::success(deliveries)))
+}
+
+fn test_event(webhook: &Webhook) -> WebhookEvent {
+    WebhookEvent::new(
+        "webhook.test",
+        webhook.base.network_id,
+        json!({ "message": format!("Test event from NetVisor webhook '{}'", webhook.base.name) }),
+    )
+}
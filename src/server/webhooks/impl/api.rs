@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::{
+    daemons::r#impl::api::DiscoveryUpdatePayload, shared::events::EntityEvent,
+    webhooks::r#impl::base::Webhook,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookResponse {
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+/// Event delivered to webhooks; also the context templates are rendered with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    pub event: String,
+    pub network_id: Uuid,
+    pub timestamp: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+impl WebhookEvent {
+    pub fn new(event: &str, network_id: Uuid, data: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event: event.to_string(),
+            network_id,
+            timestamp: Utc::now(),
+            data,
+        }
+    }
+
+    /// e.g. `host.updated`. Changes to entities outside a network aren't sent.
+    pub fn from_entity_event(event: &EntityEvent) -> Option<Self> {
+        Some(Self {
+            id: Uuid::new_v4(),
+            event: event.name(),
+            network_id: event.network_id?,
+            timestamp: event.timestamp,
+            data: event.data.clone(),
+        })
+    }
+
+    /// e.g. `discovery.scanning`. Sent for every update, so callers only dispatch it when the
+    /// session's phase changed.
+    pub fn from_discovery_update(update: &DiscoveryUpdatePayload) -> Self {
+        Self::new(
+            &format!("discovery.{:?}", update.phase).to_lowercase(),
+            update.network_id,
+            serde_json::to_value(update).unwrap_or_default(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryResult {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookTemplatePreviewRequest {
+    pub template: String,
+}
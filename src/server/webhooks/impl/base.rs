@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use strum_macros::EnumString;
+use uuid::Uuid;
+
+use crate::server::webhooks::r#impl::api::WebhookEvent;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookBase {
+    pub name: String,
+    pub network_id: Uuid,
+    pub url: String,
+    /// HMAC-SHA256 signing secret, generated by the server
+    #[serde(default, serialize_with = "serialize_webhook_secret")]
+    pub secret: String,
+    /// Tera template rendered with the event as context; the event is sent as JSON when unset
+    pub template: Option<String>,
+    /// Content-Type of rendered templates, defaults to application/json
+    pub content_type: Option<String>,
+    pub is_enabled: bool,
+    /// Events to send, e.g. `host.created` or `host.*`; every event when empty
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub last_delivery: Option<DateTime<Utc>>,
+}
+
+fn serialize_webhook_secret<S>(_secret: &String, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str("***REDACTED***")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: WebhookBase,
+}
+
+impl Webhook {
+    pub fn accepts(&self, event: &str) -> bool {
+        self.base.events.is_empty()
+            || self
+                .base
+                .events
+                .iter()
+                .any(|filter| match filter.strip_suffix('*') {
+                    Some(prefix) => event.starts_with(prefix),
+                    None => filter == event,
+                })
+    }
+}
+
+impl Display for Webhook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.base.name, self.id)
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, strum_macros::Display, EnumString,
+)]
+pub enum WebhookDeliveryStatus {
+    /// Not delivered yet; retried at `next_attempt_at`
+    Pending,
+    Delivered,
+    /// Gave up after the last retry
+    Failed,
+}
+
+/// An event sent, or being sent, to a webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDeliveryBase {
+    pub webhook_id: Uuid,
+    pub network_id: Uuid,
+    pub event: WebhookEvent,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    /// HTTP status of the last attempt, if the receiver responded
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: WebhookDeliveryBase,
+}
+
+impl Display for WebhookDelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Delivery of {} to webhook {}: {}",
+            self.base.event.event, self.base.webhook_id, self.id
+        )
+    }
+}
@@ -0,0 +1,34 @@
+use crate::server::shared::handlers::traits::CrudHandlers;
+use crate::server::webhooks::r#impl::base::Webhook;
+use crate::server::webhooks::service::WebhookService;
+use uuid::Uuid;
+
+impl CrudHandlers for Webhook {
+    type Service = WebhookService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.webhook_service
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match url::Url::parse(&self.base.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => return Err(format!("Invalid webhook URL '{}'", self.base.url)),
+        }
+
+        // Wildcards are only supported as a suffix
+        match self
+            .base
+            .events
+            .iter()
+            .find(|e| e.is_empty() || e.trim_end_matches('*').contains('*'))
+        {
+            Some(event) => Err(format!("Invalid event filter '{}'", event)),
+            None => Ok(()),
+        }
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
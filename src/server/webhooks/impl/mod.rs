@@ -0,0 +1,6 @@
+pub mod api;
+pub mod base;
+pub mod handlers;
+pub mod signing;
+pub mod storage;
+pub mod template;
@@ -0,0 +1,17 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const TIMESTAMP_HEADER: &str = "X-NetVisor-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-NetVisor-Signature";
+
+/// `sha256=<hex>` HMAC over `<timestamp>.<body>`. Signing the timestamp with the body means a
+/// captured delivery can't be replayed later with a fresh timestamp; receivers should also
+/// reject deliveries whose timestamp is more than a few minutes from their clock.
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
@@ -0,0 +1,228 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    shared::storage::traits::{SqlValue, StorableEntity},
+    webhooks::r#impl::base::{Webhook, WebhookBase, WebhookDelivery, WebhookDeliveryBase},
+};
+
+impl StorableEntity for Webhook {
+    type BaseData = WebhookBase;
+
+    fn table_name() -> &'static str {
+        "webhooks"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    name,
+                    network_id,
+                    url,
+                    secret,
+                    template,
+                    content_type,
+                    is_enabled,
+                    events,
+                    last_delivery,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "network_id",
+                "name",
+                "url",
+                "secret",
+                "template",
+                "content_type",
+                "is_enabled",
+                "events",
+                "last_delivery",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::String(name),
+                SqlValue::String(url),
+                SqlValue::String(secret),
+                SqlValue::OptionalString(template),
+                SqlValue::OptionalString(content_type),
+                SqlValue::Bool(is_enabled),
+                SqlValue::Json(serde_json::to_value(&events)?),
+                SqlValue::OptionTimestamp(last_delivery),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let events: serde_json::Value = row.get("events");
+
+        Ok(Webhook {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: WebhookBase {
+                name: row.get("name"),
+                network_id: row.get("network_id"),
+                url: row.get("url"),
+                secret: row.get("secret"),
+                template: row.get("template"),
+                content_type: row.get("content_type"),
+                is_enabled: row.get("is_enabled"),
+                events: serde_json::from_value(events)?,
+                last_delivery: row.get("last_delivery"),
+            },
+        })
+    }
+}
+
+impl StorableEntity for WebhookDelivery {
+    type BaseData = WebhookDeliveryBase;
+
+    fn table_name() -> &'static str {
+        "webhook_deliveries"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    webhook_id,
+                    network_id,
+                    event,
+                    status,
+                    attempts,
+                    response_status,
+                    error,
+                    next_attempt_at,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "webhook_id",
+                "network_id",
+                "event",
+                "status",
+                "attempts",
+                "response_status",
+                "error",
+                "next_attempt_at",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(webhook_id),
+                SqlValue::Uuid(network_id),
+                SqlValue::Json(serde_json::to_value(&event)?),
+                SqlValue::String(status.to_string()),
+                SqlValue::I32(attempts),
+                SqlValue::OptionalI32(response_status),
+                SqlValue::OptionalString(error),
+                SqlValue::OptionTimestamp(next_attempt_at),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        let event: serde_json::Value = row.get("event");
+        let status: String = row.get("status");
+
+        Ok(WebhookDelivery {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: WebhookDeliveryBase {
+                webhook_id: row.get("webhook_id"),
+                network_id: row.get("network_id"),
+                event: serde_json::from_value(event)?,
+                status: status.parse()?,
+                attempts: row.get("attempts"),
+                response_status: row.get("response_status"),
+                error: row.get("error"),
+                next_attempt_at: row.get("next_attempt_at"),
+            },
+        })
+    }
+}
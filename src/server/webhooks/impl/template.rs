@@ -0,0 +1,27 @@
+use anyhow::{Result, anyhow};
+use tera::{Context, Tera};
+
+use crate::server::webhooks::r#impl::api::WebhookEvent;
+
+/// Render a webhook payload template with the event fields (`event`, `network_id`,
+/// `timestamp`, `data`) in scope. Autoescaping is off since payloads are usually JSON; use the
+/// `json_encode` filter to embed strings safely.
+pub fn render(template: &str, event: &WebhookEvent) -> Result<String> {
+    let context = Context::from_serialize(event)?;
+
+    Tera::one_off(template, &context, false)
+        .map_err(|e| anyhow!("Failed to render webhook template: {}", format_error(&e)))
+}
+
+/// Tera nests the useful message in the error's source chain
+fn format_error(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+
+    while let Some(e) = source {
+        message = format!("{}: {}", message, e);
+        source = e.source();
+    }
+
+    message
+}
@@ -0,0 +1,263 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::server::{
+    shared::{
+        services::traits::CrudService,
+        storage::{
+            filter::EntityFilter,
+            generic::GenericPostgresStorage,
+            traits::{StorableEntity, Storage},
+        },
+    },
+    webhooks::r#impl::{
+        api::{WebhookDeliveryResult, WebhookEvent},
+        base::{Webhook, WebhookBase, WebhookDelivery, WebhookDeliveryBase, WebhookDeliveryStatus},
+        signing::{SIGNATURE_HEADER, TIMESTAMP_HEADER, sign},
+        template,
+    },
+};
+
+/// Attempts before a delivery is marked failed
+const MAX_ATTEMPTS: i32 = 6;
+const DELIVERY_RETENTION_DAYS: i64 = 7;
+
+pub struct WebhookService {
+    storage: Arc<GenericPostgresStorage<Webhook>>,
+    delivery_storage: Arc<GenericPostgresStorage<WebhookDelivery>>,
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl CrudService<Webhook> for WebhookService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<Webhook>> {
+        &self.storage
+    }
+}
+
+impl WebhookService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<Webhook>>,
+        delivery_storage: Arc<GenericPostgresStorage<WebhookDelivery>>,
+    ) -> Self {
+        Self {
+            storage,
+            delivery_storage,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn generate_secret(&self) -> String {
+        hex::encode(rand::rng().random::<[u8; 32]>())
+    }
+
+    pub async fn create(&self, webhook: Webhook) -> Result<Webhook> {
+        let webhook = Webhook::new(WebhookBase {
+            secret: self.generate_secret(),
+            last_delivery: None,
+            ..webhook.base
+        });
+
+        self.storage.create(&webhook).await
+    }
+
+    pub async fn rotate_secret(&self, webhook_id: Uuid) -> Result<String> {
+        let mut webhook = self
+            .get_by_id(&webhook_id)
+            .await?
+            .ok_or_else(|| anyhow!("Could not find webhook {}", webhook_id))?;
+
+        let secret = self.generate_secret();
+        webhook.base.secret = secret.clone();
+        self.update(&mut webhook).await?;
+
+        Ok(secret)
+    }
+
+    /// Render the payload for a webhook: its template if set, otherwise the event as JSON
+    pub fn render_payload(&self, webhook: &Webhook, event: &WebhookEvent) -> Result<String> {
+        match &webhook.base.template {
+            Some(template) => template::render(template, event),
+            None => Ok(serde_json::to_string(event)?),
+        }
+    }
+
+    /// Send an event to a single webhook, signed with its secret
+    pub async fn deliver(
+        &self,
+        webhook: &Webhook,
+        event: &WebhookEvent,
+    ) -> Result<WebhookDeliveryResult> {
+        let body = self.render_payload(webhook, event)?;
+        let timestamp = Utc::now().timestamp();
+        let content_type = webhook
+            .base
+            .content_type
+            .as_deref()
+            .unwrap_or("application/json");
+
+        let response = self
+            .client
+            .post(&webhook.base.url)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                sign(&webhook.base.secret, timestamp, body.as_bytes()),
+            )
+            .body(body)
+            .send()
+            .await?;
+
+        let result = WebhookDeliveryResult {
+            status: response.status().as_u16(),
+            body: response.text().await.unwrap_or_default(),
+        };
+
+        let mut webhook = webhook.clone();
+        webhook.base.last_delivery = Some(Utc::now());
+        self.update(&mut webhook).await?;
+
+        Ok(result)
+    }
+
+    /// Send an event to every enabled webhook in its network whose filter accepts it. Each send
+    /// is logged as a delivery, and failed ones are retried by `retry_due`.
+    pub async fn dispatch(&self, event: &WebhookEvent) -> Result<()> {
+        let filter = EntityFilter::unfiltered().network_ids(&[event.network_id]);
+        let webhooks = self.get_all(filter).await?;
+
+        for webhook in webhooks
+            .iter()
+            .filter(|w| w.base.is_enabled && w.accepts(&event.event))
+        {
+            let delivery = WebhookDelivery::new(WebhookDeliveryBase {
+                webhook_id: webhook.id,
+                network_id: event.network_id,
+                event: event.clone(),
+                status: WebhookDeliveryStatus::Pending,
+                attempts: 0,
+                response_status: None,
+                error: None,
+                next_attempt_at: None,
+            });
+            let mut delivery = self.delivery_storage.create(&delivery).await?;
+
+            self.attempt(webhook, &mut delivery).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send a pending delivery once and record the outcome
+    async fn attempt(&self, webhook: &Webhook, delivery: &mut WebhookDelivery) -> Result<()> {
+        delivery.base.attempts += 1;
+
+        let error = match self.deliver(webhook, &delivery.base.event).await {
+            Ok(result) => {
+                delivery.base.response_status = Some(result.status as i32);
+                if (200..300).contains(&result.status) {
+                    None
+                } else {
+                    Some(format!("HTTP {}", result.status))
+                }
+            }
+            Err(e) => {
+                delivery.base.response_status = None;
+                Some(e.to_string())
+            }
+        };
+
+        match error {
+            None => {
+                delivery.base.status = WebhookDeliveryStatus::Delivered;
+                delivery.base.error = None;
+                delivery.base.next_attempt_at = None;
+            }
+            Some(error) => {
+                tracing::warn!(
+                    "Webhook {} delivery attempt {} failed: {}",
+                    webhook,
+                    delivery.base.attempts,
+                    error
+                );
+                delivery.base.error = Some(error);
+                if delivery.base.attempts >= MAX_ATTEMPTS {
+                    delivery.base.status = WebhookDeliveryStatus::Failed;
+                    delivery.base.next_attempt_at = None;
+                } else {
+                    delivery.base.next_attempt_at =
+                        Some(Utc::now() + retry_delay(delivery.base.attempts));
+                }
+            }
+        }
+
+        self.delivery_storage.update(delivery).await?;
+        Ok(())
+    }
+
+    /// Retry pending deliveries whose backoff has elapsed. Deliveries to webhooks that were
+    /// disabled in the meantime are given up on. Returns how many were retried.
+    pub async fn retry_due(&self) -> Result<usize> {
+        let filter = EntityFilter::unfiltered().due_webhook_deliveries(Utc::now());
+        let deliveries = self.delivery_storage.get_all(filter).await?;
+        let count = deliveries.len();
+
+        for mut delivery in deliveries {
+            match self.get_by_id(&delivery.base.webhook_id).await? {
+                Some(webhook) if webhook.base.is_enabled => {
+                    self.attempt(&webhook, &mut delivery).await?
+                }
+                _ => {
+                    delivery.base.status = WebhookDeliveryStatus::Failed;
+                    delivery.base.next_attempt_at = None;
+                    delivery.base.error = Some("Webhook was disabled or deleted".to_string());
+                    self.delivery_storage.update(&mut delivery).await?;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Delivery log of a webhook
+    pub async fn deliveries(&self, webhook_id: &Uuid) -> Result<Vec<WebhookDelivery>> {
+        let filter = EntityFilter::unfiltered().webhook_id(webhook_id);
+        self.delivery_storage.get_all(filter).await
+    }
+
+    /// Delete finished deliveries past the retention period
+    pub async fn prune_deliveries(&self) -> Result<u64> {
+        let cutoff: DateTime<Utc> = Utc::now() - chrono::Duration::days(DELIVERY_RETENTION_DAYS);
+        let filter = EntityFilter::unfiltered()
+            .created_before(cutoff)
+            .delivery_finished();
+        self.delivery_storage.delete_all(filter).await
+    }
+}
+
+/// Backoff before the next attempt: 30s doubling per failed attempt, capped at an hour
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let seconds = 30i64 << (attempts - 1).clamp(0, 7);
+    chrono::Duration::seconds(seconds.min(60 * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_exponentially() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(2), chrono::Duration::seconds(60));
+        assert_eq!(retry_delay(5), chrono::Duration::seconds(480));
+        assert_eq!(retry_delay(20), chrono::Duration::hours(1));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::server::{
+    auth::middleware::AuthenticatedUser,
+    config::AppState,
+    shared::{
+        handlers::traits::{
+            CrudHandlers, check_access, delete_handler, get_all_handler, get_by_id_handler,
+        },
+        services::traits::CrudService,
+        types::api::{ApiError, ApiResponse, ApiResult},
+    },
+    winrm_credentials::r#impl::base::{REDACTED, WinrmCredential},
+};
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub fn create_router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", post(create_credential))
+        .route("/", get(get_all_handler::<WinrmCredential>))
+        .route("/{id}", put(update_credential))
+        .route("/{id}", delete(delete_handler::<WinrmCredential>))
+        .route("/{id}", get(get_by_id_handler::<WinrmCredential>))
+}
+
+/// Validation the standard handlers do, plus that the host or subnet is in the credential's
+/// network
+async fn validate_credential(
+    state: &AppState,
+    user: &AuthenticatedUser,
+    credential: &WinrmCredential,
+) -> ApiResult<()> {
+    credential.validate().map_err(|e| {
+        ApiError::bad_request(&format!("WinRM credential validation failed: {}", e))
+    })?;
+    check_access(state, &user.0, credential).await?;
+
+    state
+        .services
+        .winrm_credential_service
+        .check_target(&credential.base)
+        .await
+        .map_err(|e| ApiError::bad_request(&e.to_string()))
+}
+
+async fn create_credential(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Json(request): Json<WinrmCredential>,
+) -> ApiResult<Json<ApiResponse<WinrmCredential>>> {
+    validate_credential(&state, &user, &request).await?;
+
+    let created = state
+        .services
+        .winrm_credential_service
+        .create(request)
+        .await?;
+
+    Ok(Json(ApiResponse::success(created)))
+}
+
+/// A password sent back redacted is kept as stored
+async fn update_credential(
+    State(state): State<Arc<AppState>>,
+    user: AuthenticatedUser,
+    Path(id): Path<Uuid>,
+    Json(mut request): Json<WinrmCredential>,
+) -> ApiResult<Json<ApiResponse<WinrmCredential>>> {
+    let service = &state.services.winrm_credential_service;
+    let existing = service
+        .get_by_id(&id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("WinRM credential '{}' not found", id)))?;
+    check_access(&state, &user.0, &existing).await?;
+
+    request.id = id;
+    if request.base.password == REDACTED {
+        request.base.password = existing.base.password;
+    }
+    validate_credential(&state, &user, &request).await?;
+
+    let updated = service.update(&mut request).await?;
+
+    Ok(Json(ApiResponse::success(updated)))
+}
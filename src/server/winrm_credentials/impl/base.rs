@@ -0,0 +1,111 @@
+use std::{fmt::Display, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+use cidr::IpCidr;
+use serde::{Deserialize, Serialize, Serializer};
+use uuid::Uuid;
+
+/// Stands in for the password in API responses. Sent back unchanged in an update, it keeps
+/// the stored password.
+pub const REDACTED: &str = "***REDACTED***";
+
+/// Account the daemon uses to query a Windows host over WinRM, for a single host or every
+/// host of a subnet. WinRM must accept Basic authentication, which Windows only allows for
+/// local accounts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinrmCredentialBase {
+    pub network_id: Uuid,
+    /// Exactly one of `host_id` and `subnet_id` is set
+    pub host_id: Option<Uuid>,
+    pub subnet_id: Option<Uuid>,
+    pub username: String,
+    #[serde(serialize_with = "serialize_password")]
+    pub password: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Windows refuses Basic authentication over plain HTTP unless AllowUnencrypted is set
+    #[serde(default = "default_https")]
+    pub https: bool,
+    /// Only host credentials can turn this off, e.g. for a listener with a self-signed
+    /// certificate; a subnet credential would send its password to any listener on the subnet
+    #[serde(default = "default_verify_tls")]
+    pub verify_tls: bool,
+}
+
+fn default_port() -> u16 {
+    5986
+}
+
+fn default_https() -> bool {
+    true
+}
+
+fn default_verify_tls() -> bool {
+    true
+}
+
+fn serialize_password<S>(_password: &String, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(REDACTED)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinrmCredential {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub base: WinrmCredentialBase,
+}
+
+impl WinrmCredentialBase {
+    /// The password only goes over HTTPS to listeners with a trusted certificate
+    pub fn is_verified(&self) -> bool {
+        self.https && self.verify_tls
+    }
+}
+
+impl Display for WinrmCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "WinRM credential for {}: {}",
+            self.base.username, self.id
+        )
+    }
+}
+
+/// A credential as daemons receive it with a scan request: the password in clear, and the
+/// addresses it applies to instead of the host or subnet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WinrmLogin {
+    /// The host's addresses, or the subnet's range
+    pub cidrs: Vec<IpCidr>,
+    pub port: u16,
+    pub https: bool,
+    pub verify_tls: bool,
+    pub username: String,
+    pub password: String,
+}
+
+impl WinrmLogin {
+    /// Login for an address: the one whose range is narrowest, so a host's own credential wins
+    /// over its subnet's
+    pub fn for_ip(logins: &[WinrmLogin], ip: IpAddr) -> Option<&WinrmLogin> {
+        logins
+            .iter()
+            .filter_map(|login| {
+                login
+                    .cidrs
+                    .iter()
+                    .filter(|cidr| cidr.contains(&ip))
+                    .map(|cidr| cidr.network_length())
+                    .max()
+                    .map(|length| (length, login))
+            })
+            .max_by_key(|(length, _)| *length)
+            .map(|(_, login)| login)
+    }
+}
@@ -0,0 +1,38 @@
+use crate::server::shared::handlers::traits::CrudHandlers;
+use crate::server::winrm_credentials::r#impl::base::WinrmCredential;
+use crate::server::winrm_credentials::service::WinrmCredentialService;
+use uuid::Uuid;
+
+impl CrudHandlers for WinrmCredential {
+    type Service = WinrmCredentialService;
+
+    fn get_service(state: &crate::server::config::AppState) -> &Self::Service {
+        &state.services.winrm_credential_service
+    }
+
+    fn entity_name() -> &'static str {
+        "WinRM credential"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.base.host_id.is_some() == self.base.subnet_id.is_some() {
+            return Err("Set either a host or a subnet".to_string());
+        }
+        if self.base.username.trim().is_empty() || self.base.password.is_empty() {
+            return Err("Username and password are required".to_string());
+        }
+        if self.base.port == 0 {
+            return Err("Port can't be 0".to_string());
+        }
+        if self.base.subnet_id.is_some() && !self.base.is_verified() {
+            return Err(
+                "Subnet credentials must use HTTPS with certificate verification".to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    fn network_id(&self) -> Option<Uuid> {
+        Some(self.base.network_id)
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::server::{
+    shared::storage::traits::{SqlValue, StorableEntity},
+    winrm_credentials::r#impl::base::{WinrmCredential, WinrmCredentialBase},
+};
+
+impl StorableEntity for WinrmCredential {
+    type BaseData = WinrmCredentialBase;
+
+    fn table_name() -> &'static str {
+        "winrm_credentials"
+    }
+
+    fn get_base(&self) -> Self::BaseData {
+        self.base.clone()
+    }
+
+    fn new(base: Self::BaseData) -> Self {
+        let now = chrono::Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            created_at: now,
+            updated_at: now,
+            base,
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn set_updated_at(&mut self, time: DateTime<Utc>) {
+        self.updated_at = time;
+    }
+
+    fn to_params(&self) -> Result<(Vec<&'static str>, Vec<SqlValue>), anyhow::Error> {
+        let Self {
+            id,
+            created_at,
+            updated_at,
+            base:
+                Self::BaseData {
+                    network_id,
+                    host_id,
+                    subnet_id,
+                    username,
+                    password,
+                    port,
+                    https,
+                    verify_tls,
+                },
+        } = self.clone();
+
+        Ok((
+            vec![
+                "id",
+                "created_at",
+                "updated_at",
+                "network_id",
+                "host_id",
+                "subnet_id",
+                "username",
+                "password",
+                "port",
+                "https",
+                "verify_tls",
+            ],
+            vec![
+                SqlValue::Uuid(id),
+                SqlValue::Timestamp(created_at),
+                SqlValue::Timestamp(updated_at),
+                SqlValue::Uuid(network_id),
+                SqlValue::OptionalUuid(host_id),
+                SqlValue::OptionalUuid(subnet_id),
+                SqlValue::String(username),
+                SqlValue::String(password),
+                SqlValue::U16(port),
+                SqlValue::Bool(https),
+                SqlValue::Bool(verify_tls),
+            ],
+        ))
+    }
+
+    fn from_row(row: &PgRow) -> Result<Self, anyhow::Error> {
+        Ok(WinrmCredential {
+            id: row.get("id"),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
+            base: WinrmCredentialBase {
+                network_id: row.get("network_id"),
+                host_id: row.get("host_id"),
+                subnet_id: row.get("subnet_id"),
+                username: row.get("username"),
+                password: row.get("password"),
+                port: row.get::<i32, _>("port").try_into()?,
+                https: row.get("https"),
+                verify_tls: row.get("verify_tls"),
+            },
+        })
+    }
+}
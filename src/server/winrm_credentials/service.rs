@@ -0,0 +1,128 @@
+use anyhow::{Result, anyhow, bail};
+use async_trait::async_trait;
+use cidr::IpCidr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::server::{
+    hosts::r#impl::base::Host,
+    shared::{
+        services::traits::CrudService,
+        storage::{filter::EntityFilter, generic::GenericPostgresStorage, traits::Storage},
+    },
+    subnets::r#impl::base::Subnet,
+    winrm_credentials::r#impl::base::{WinrmCredential, WinrmCredentialBase, WinrmLogin},
+};
+
+/// Credentials for querying Windows hosts over WinRM, sent to daemons with each network scan
+pub struct WinrmCredentialService {
+    storage: Arc<GenericPostgresStorage<WinrmCredential>>,
+    host_storage: Arc<GenericPostgresStorage<Host>>,
+    subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+}
+
+#[async_trait]
+impl CrudService<WinrmCredential> for WinrmCredentialService {
+    fn storage(&self) -> &Arc<GenericPostgresStorage<WinrmCredential>> {
+        &self.storage
+    }
+}
+
+impl WinrmCredentialService {
+    pub fn new(
+        storage: Arc<GenericPostgresStorage<WinrmCredential>>,
+        host_storage: Arc<GenericPostgresStorage<Host>>,
+        subnet_storage: Arc<GenericPostgresStorage<Subnet>>,
+    ) -> Self {
+        Self {
+            storage,
+            host_storage,
+            subnet_storage,
+        }
+    }
+
+    /// Fails unless the credential's host or subnet is in its network
+    pub async fn check_target(&self, base: &WinrmCredentialBase) -> Result<()> {
+        let network_id = match (base.host_id, base.subnet_id) {
+            (Some(host_id), _) => {
+                self.host_storage
+                    .get_by_id(&host_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Host '{}' not found", host_id))?
+                    .base
+                    .network_id
+            }
+            (_, Some(subnet_id)) => {
+                self.subnet_storage
+                    .get_by_id(&subnet_id)
+                    .await?
+                    .ok_or_else(|| anyhow!("Subnet '{}' not found", subnet_id))?
+                    .base
+                    .network_id
+            }
+            (None, None) => bail!("Set either a host or a subnet"),
+        };
+
+        if network_id != base.network_id {
+            bail!("The host or subnet is in another network");
+        }
+        Ok(())
+    }
+
+    /// The network's credentials with the addresses each applies to, for a scan request
+    pub async fn daemon_logins(&self, network_id: &Uuid) -> Result<Vec<WinrmLogin>> {
+        let filter = EntityFilter::unfiltered().network_ids(&[*network_id]);
+        let credentials = self.storage.get_all(filter.clone()).await?;
+        if credentials.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let hosts = self.host_storage.get_all(filter.clone()).await?;
+        let subnets = self.subnet_storage.get_all(filter).await?;
+
+        Ok(credentials
+            .into_iter()
+            .filter_map(|credential| {
+                let cidrs: Vec<IpCidr> = match (credential.base.host_id, credential.base.subnet_id)
+                {
+                    (Some(host_id), _) => hosts
+                        .iter()
+                        .find(|h| h.id == host_id)?
+                        .base
+                        .interfaces
+                        .iter()
+                        .map(|i| IpCidr::new_host(i.base.ip_address))
+                        .collect(),
+                    // Saved before subnet credentials had to be verified
+                    (_, Some(_)) if !credential.base.is_verified() => {
+                        tracing::warn!("Skipping unverified subnet credential {}", credential);
+                        return None;
+                    }
+                    (_, Some(subnet_id)) => {
+                        vec![subnets.iter().find(|s| s.id == subnet_id)?.base.cidr]
+                    }
+                    (None, None) => return None,
+                };
+
+                let WinrmCredentialBase {
+                    username,
+                    password,
+                    port,
+                    https,
+                    verify_tls,
+                    ..
+                } = credential.base;
+
+                Some(WinrmLogin {
+                    cidrs,
+                    port,
+                    https,
+                    verify_tls,
+                    username,
+                    password,
+                })
+            })
+            .filter(|login| !login.cidrs.is_empty())
+            .collect())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{
+    client::api::ApiClient,
+    server::{
+        daemons::r#impl::{api::DiscoveryUpdatePayload, base::Daemon},
+        hosts::r#impl::base::Host,
+    },
+};
+use chrono::{DateTime, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::widgets::TableState;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumIter)]
+pub enum Tab {
+    Discovery,
+    Daemons,
+    Hosts,
+}
+
+pub struct App {
+    pub tab: Tab,
+    pub hosts: Vec<Host>,
+    pub daemons: Vec<Daemon>,
+    pub sessions: Vec<DiscoveryUpdatePayload>,
+    pub host_table: TableState,
+    pub host_filter: String,
+    pub editing_filter: bool,
+    pub last_refresh: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub should_quit: bool,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn new() -> Self {
+        Self {
+            tab: Tab::Discovery,
+            hosts: Vec::new(),
+            daemons: Vec::new(),
+            sessions: Vec::new(),
+            host_table: TableState::default(),
+            host_filter: String::new(),
+            editing_filter: false,
+            last_refresh: None,
+            last_error: None,
+            should_quit: false,
+        }
+    }
+
+    pub async fn refresh(&mut self, client: &ApiClient) {
+        let result = async {
+            let sessions = client.get_active_sessions().await?;
+            let daemons = client.get_daemons().await?;
+            let hosts = client.get_hosts().await?;
+            anyhow::Ok((sessions, daemons, hosts))
+        }
+        .await;
+
+        match result {
+            Ok((sessions, daemons, mut hosts)) => {
+                hosts.sort_by(|a, b| a.base.name.cmp(&b.base.name));
+                self.sessions = sessions;
+                self.daemons = daemons;
+                self.hosts = hosts;
+                self.last_error = None;
+                self.clamp_selection();
+            }
+            Err(e) => self.last_error = Some(e.to_string()),
+        }
+
+        self.last_refresh = Some(Utc::now());
+    }
+
+    pub fn filtered_hosts(&self) -> Vec<&Host> {
+        let filter = self.host_filter.to_lowercase();
+
+        self.hosts
+            .iter()
+            .filter(|h| {
+                filter.is_empty()
+                    || h.base.name.to_lowercase().contains(&filter)
+                    || h.base
+                        .interfaces
+                        .iter()
+                        .any(|i| i.base.ip_address.to_string().contains(&filter))
+            })
+            .collect()
+    }
+
+    pub fn selected_host(&self) -> Option<&Host> {
+        self.host_table
+            .selected()
+            .and_then(|i| self.filtered_hosts().get(i).copied())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => self.editing_filter = false,
+                KeyCode::Backspace => {
+                    self.host_filter.pop();
+                    self.clamp_selection();
+                }
+                KeyCode::Char(c) => {
+                    self.host_filter.push(c);
+                    self.clamp_selection();
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Tab | KeyCode::Right => self.tab = self.next_tab(1),
+            KeyCode::BackTab | KeyCode::Left => self.tab = self.next_tab(Tab::iter().count() - 1),
+            KeyCode::Char('1') => self.tab = Tab::Discovery,
+            KeyCode::Char('2') => self.tab = Tab::Daemons,
+            KeyCode::Char('3') => self.tab = Tab::Hosts,
+            KeyCode::Char('/') if self.tab == Tab::Hosts => self.editing_filter = true,
+            KeyCode::Down | KeyCode::Char('j') if self.tab == Tab::Hosts => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') if self.tab == Tab::Hosts => self.move_selection(-1),
+            _ => {}
+        }
+    }
+
+    fn next_tab(&self, offset: usize) -> Tab {
+        let tabs: Vec<Tab> = Tab::iter().collect();
+        let current = tabs.iter().position(|t| *t == self.tab).unwrap_or(0);
+        tabs[(current + offset) % tabs.len()]
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.filtered_hosts().len();
+        if len == 0 {
+            self.host_table.select(None);
+            return;
+        }
+
+        let current = self.host_table.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, len as isize - 1) as usize;
+        self.host_table.select(Some(next));
+    }
+
+    fn clamp_selection(&mut self) {
+        let len = self.filtered_hosts().len();
+        match (len, self.host_table.selected()) {
+            (0, _) => self.host_table.select(None),
+            (_, None) => self.host_table.select(Some(0)),
+            (len, Some(i)) if i >= len => self.host_table.select(Some(len - 1)),
+            _ => {}
+        }
+    }
+}
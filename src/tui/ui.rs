@@ -0,0 +1,280 @@
+use crate::tui::app::{App, Tab};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Table, Tabs},
+};
+use strum::IntoEnumIterator;
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [tabs_area, body_area, status_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+    let titles: Vec<String> = Tab::iter()
+        .enumerate()
+        .map(|(i, t)| format!("{} {}", i + 1, t))
+        .collect();
+    let selected = Tab::iter().position(|t| t == app.tab).unwrap_or(0);
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title("NetVisor"))
+        .select(selected)
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .fg(Color::Cyan),
+        );
+    frame.render_widget(tabs, tabs_area);
+
+    match app.tab {
+        Tab::Discovery => draw_discovery(frame, app, body_area),
+        Tab::Daemons => draw_daemons(frame, app, body_area),
+        Tab::Hosts => draw_hosts(frame, app, body_area),
+    }
+
+    draw_status(frame, app, status_area);
+}
+
+fn draw_discovery(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Active discovery sessions");
+
+    if app.sessions.is_empty() {
+        frame.render_widget(Paragraph::new("No discovery running").block(block), area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(app.sessions.iter().map(|_| Constraint::Length(3)))
+        .split(inner);
+
+    for (session, row) in app.sessions.iter().zip(rows.iter()) {
+        let ratio = if session.total_to_process == 0 {
+            0.0
+        } else {
+            (session.processed as f64 / session.total_to_process as f64).clamp(0.0, 1.0)
+        };
+
+        let daemon_name = app
+            .daemons
+            .iter()
+            .find(|d| d.id == session.daemon_id)
+            .and_then(|d| app.hosts.iter().find(|h| h.id == d.base.host_id))
+            .map(|h| h.base.name.clone())
+            .unwrap_or_else(|| session.daemon_id.to_string());
+
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "{} on {} — {:?}",
+                session.discovery_type, daemon_name, session.phase
+            )))
+            .gauge_style(Style::default().fg(Color::Green))
+            .label(format!(
+                "{}/{}",
+                session.processed, session.total_to_process
+            ))
+            .ratio(ratio);
+
+        frame.render_widget(gauge, *row);
+    }
+}
+
+fn draw_daemons(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.daemons.iter().map(|daemon| {
+        let name = app
+            .hosts
+            .iter()
+            .find(|h| h.id == daemon.base.host_id)
+            .map(|h| h.base.name.clone())
+            .unwrap_or_else(|| daemon.id.to_string());
+
+        let status = if daemon.is_online() {
+            Cell::from("online").style(Style::default().fg(Color::Green))
+        } else {
+            Cell::from("offline").style(Style::default().fg(Color::Red))
+        };
+
+        Row::new(vec![
+            Cell::from(name),
+            status,
+            Cell::from(format!("{}:{}", daemon.base.ip, daemon.base.port)),
+            Cell::from(
+                daemon
+                    .base
+                    .last_seen
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            ),
+            Cell::from(if daemon.base.capabilities.has_docker_socket {
+                "yes"
+            } else {
+                "no"
+            }),
+            Cell::from(
+                daemon
+                    .base
+                    .capabilities
+                    .interfaced_subnet_ids
+                    .len()
+                    .to_string(),
+            ),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(25),
+            Constraint::Length(8),
+            Constraint::Percentage(25),
+            Constraint::Length(20),
+            Constraint::Length(7),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Name",
+            "Status",
+            "Address",
+            "Last seen",
+            "Docker",
+            "Subnets",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title("Daemons"));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_hosts(frame: &mut Frame, app: &mut App, area: Rect) {
+    let [list_area, detail_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .areas(area);
+
+    let hosts = app.filtered_hosts();
+    let rows: Vec<Row> = hosts
+        .iter()
+        .map(|host| {
+            let ips = host
+                .base
+                .interfaces
+                .iter()
+                .map(|i| i.base.ip_address.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            Row::new(vec![
+                Cell::from(host.base.name.clone()),
+                Cell::from(ips),
+                Cell::from(host.base.services.len().to_string()),
+            ])
+        })
+        .collect();
+
+    let title = if app.editing_filter || !app.host_filter.is_empty() {
+        format!("Hosts ({}) filter: {}", hosts.len(), app.host_filter)
+    } else {
+        format!("Hosts ({})", hosts.len())
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Percentage(40),
+            Constraint::Percentage(45),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec!["Name", "Addresses", "Services"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    .block(Block::default().borders(Borders::ALL).title(title));
+
+    frame.render_stateful_widget(table, list_area, &mut app.host_table);
+
+    let detail = match app.selected_host() {
+        Some(host) => {
+            let mut lines = vec![
+                Line::from(Span::styled(
+                    host.base.name.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(format!("id: {}", host.id)),
+            ];
+
+            if let Some(hostname) = &host.base.hostname {
+                lines.push(Line::from(format!("hostname: {}", hostname)));
+            }
+            if let Some(description) = &host.base.description {
+                lines.push(Line::from(format!("description: {}", description)));
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from("Interfaces:"));
+            for interface in &host.base.interfaces {
+                let mac = interface
+                    .base
+                    .mac_address
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                lines.push(Line::from(format!(
+                    "  {} {} ({})",
+                    interface.base.name.as_deref().unwrap_or("-"),
+                    interface.base.ip_address,
+                    mac
+                )));
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from("Ports:"));
+            for port in &host.base.ports {
+                lines.push(Line::from(format!("  {}", port)));
+            }
+
+            Paragraph::new(lines)
+        }
+        None => Paragraph::new("No host selected"),
+    };
+
+    frame.render_widget(
+        detail.block(Block::default().borders(Borders::ALL).title("Details")),
+        detail_area,
+    );
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let refreshed = app
+        .last_refresh
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "never".to_string());
+
+    let line = match &app.last_error {
+        Some(error) => Line::from(Span::styled(
+            format!("Refresh failed at {}: {}", refreshed, error),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(format!(
+            "Refreshed {} | q quit | tab/1-3 switch | ↑↓ select | / filter hosts",
+            refreshed
+        )),
+    };
+
+    frame.render_widget(Paragraph::new(line), area);
+}